@@ -209,6 +209,11 @@ impl<T: Default> Default for Sc<T> {
   fn default() -> Sc<T> { Sc::new(Default::default()) }
 }
 
+impl<T> From<T> for Sc<T> {
+  #[inline]
+  fn from(value: T) -> Self { Sc::new(value) }
+}
+
 impl<T: ?Sized + PartialEq> PartialEq for Sc<T> {
   #[inline]
   fn eq(&self, other: &Sc<T>) -> bool { **self == **other }
@@ -1,4 +1,10 @@
-use crate::{prelude::*, ticker::FrameMsg, window::WindowId};
+use std::{cell::RefCell, rc::Rc};
+
+use crate::{prelude::*, ticker::FrameMsg, timer::Timer, window::WindowId};
+
+type ProgressFn = dyn FnMut(f32);
+type CompleteFn = dyn FnMut();
+
 #[simple_declare]
 pub struct Animate<S>
 where
@@ -9,6 +15,14 @@ where
   #[declare(strict)]
   pub state: S,
   pub from: S::Value,
+  /// Called every time the animation finishes. It's not called for a run
+  /// stopped before it finishes, and a retargeted run calls it when it
+  /// finishes to the new target.
+  #[declare(custom, default)]
+  on_complete: Option<Rc<RefCell<CompleteFn>>>,
+  /// Called in every frame of the animation with the rate of its progress.
+  #[declare(custom, default)]
+  on_progress: Option<Rc<RefCell<ProgressFn>>>,
   #[declare(skip)]
   running_info: Option<AnimateInfo<S::Value>>,
   #[declare(skip, default = ctx!().window().id())]
@@ -20,6 +34,8 @@ pub(crate) struct AnimateInfo<V> {
   to: V,
   start_at: Instant,
   last_progress: AnimateProgress,
+  /// The transition to continue the animation after it's retargeted.
+  retargeted: Option<Box<dyn Transition>>,
   // Determines if lerp value in current frame.
   already_lerp: bool,
   _tick_msg_guard: Option<Box<dyn Any>>,
@@ -35,13 +51,26 @@ where
     let mut animate_ref = self.write();
     let this = &mut *animate_ref;
     let wnd_id = this.window_id;
-    let new_to = this.state.get();
+    let new_to = this.state.get_value();
 
-    if let Some(AnimateInfo { from, to, last_progress, .. }) = &mut this.running_info {
-      *from = this
+    if let Some(info) = &mut this.running_info {
+      let AnimateInfo { from, to, last_progress, start_at, retargeted, .. } = info;
+      let current = this
         .state
         .calc_lerp_value(from, to, last_progress.value());
+      let velocity_scale = this
+        .state
+        .distance_ratio(from, to, &current, &new_to)
+        .unwrap_or(1.);
+      *from = current;
       *to = new_to;
+      let now = Timer::now();
+      let transition = retargeted.as_ref().unwrap_or(&this.transition);
+      if let Some(t) = transition.retarget(now - *start_at, velocity_scale) {
+        *retargeted = Some(t);
+        *start_at = now;
+        *last_progress = AnimateProgress::Dismissed;
+      }
     } else if let Some(wnd) = AppCtx::get_window(wnd_id) {
       drop(animate_ref);
 
@@ -68,7 +97,16 @@ where
                   .frame_spawn(async move { animate.stop() })
                   .unwrap();
               } else {
-                animate.shallow().advance_to(time);
+                let p = animate.shallow().advance_to(time);
+                let on_progress = animate.read().on_progress.clone();
+                if let Some(on_progress) = on_progress {
+                  let rate = match p {
+                    AnimateProgress::Dismissed => 0.,
+                    AnimateProgress::Between(rate) => rate,
+                    AnimateProgress::Finish => 1.,
+                  };
+                  (*on_progress.borrow_mut())(rate);
+                }
               }
             }
             FrameMsg::LayoutReady(_) => {}
@@ -92,7 +130,7 @@ where
         .animate_state_modifies()
         .subscribe(move |_| {
           let mut animate = animate.write();
-          let v = animate.state.get();
+          let v = animate.state.get_value();
           // if the animate state modified, we need to update the restore value.
           if let Some(info) = animate.running_info.as_mut() {
             info.to = v;
@@ -104,8 +142,9 @@ where
       this.running_info = Some(AnimateInfo {
         from: this.from.clone(),
         to: new_to,
-        start_at: Instant::now(),
+        start_at: Timer::now(),
         last_progress: AnimateProgress::Dismissed,
+        retargeted: None,
         _tick_msg_guard: Some(Box::new((tick_handle, state_handle))),
         already_lerp: false,
       });
@@ -120,7 +159,14 @@ where
     if this.is_running() {
       if let Some(wnd) = AppCtx::get_window(this.window_id) {
         wnd.dec_running_animate();
-        this.running_info.take();
+        let info = this.running_info.take().unwrap();
+        let on_complete = this.on_complete.clone();
+        drop(this);
+        if matches!(info.last_progress, AnimateProgress::Finish) {
+          if let Some(on_complete) = on_complete {
+            (*on_complete.borrow_mut())();
+          }
+        }
       }
     }
   }
@@ -128,6 +174,26 @@ where
   fn box_clone(&self) -> Box<dyn Animation> { Box::new(self.clone_writer()) }
 }
 
+impl<S> AnimateDeclarer<S>
+where
+  S: AnimateState + 'static,
+{
+  /// Set the callback that is called every time the animation finishes.
+  pub fn on_complete(mut self, f: impl FnMut() + 'static) -> Self {
+    let f: Rc<RefCell<CompleteFn>> = Rc::new(RefCell::new(f));
+    self.on_complete = Some(Some(f));
+    self
+  }
+
+  /// Set the callback that is called in every frame of the animation with the
+  /// rate of its progress.
+  pub fn on_progress(mut self, f: impl FnMut(f32) + 'static) -> Self {
+    let f: Rc<RefCell<dyn FnMut(f32)>> = Rc::new(RefCell::new(f));
+    self.on_progress = Some(Some(f));
+    self
+  }
+}
+
 impl<S> Animate<S>
 where
   S: AnimateState + 'static,
@@ -141,7 +207,7 @@ where
   ///
   /// Panics if the animation is not running.
   fn advance_to(&mut self, at: Instant) -> AnimateProgress {
-    let AnimateInfo { from, to, start_at, last_progress, already_lerp, .. } = self
+    let AnimateInfo { from, to, start_at, last_progress, already_lerp, retargeted, .. } = self
       .running_info
       .as_mut()
       .expect("This animation is not running.");
@@ -151,13 +217,16 @@ where
     }
 
     let elapsed = at - *start_at;
-    let progress = self.transition.rate_of_change(elapsed);
+    let progress = retargeted
+      .as_ref()
+      .unwrap_or(&self.transition)
+      .rate_of_change(elapsed);
 
     match progress {
       AnimateProgress::Between(rate) => {
         let value = self.state.calc_lerp_value(from, to, rate);
         // the state may change during animate.
-        *to = self.state.get();
+        *to = self.state.get_value();
         self.state.set(value);
       }
       AnimateProgress::Dismissed => self.state.set(from.clone()),
@@ -186,6 +255,8 @@ where
 
 #[cfg(test)]
 mod tests {
+  use std::cell::Cell;
+
   use super::*;
   use crate::{reset_test_env, test_helper::TestWindow};
 
@@ -234,4 +305,183 @@ mod tests {
     wnd.draw_frame();
     assert_eq!(*c_state.read(), 1);
   }
+
+  struct CallbackCounter {
+    completed: Rc<Cell<usize>>,
+    progressed: Rc<Cell<usize>>,
+  }
+
+  fn callback_animate(
+    transition: Box<dyn Transition>,
+  ) -> (TestWindow, Writer<Animate<Stateful<f32>>>, CallbackCounter) {
+    let completed = Rc::new(Cell::new(0));
+    let progressed = Rc::new(Cell::new(0));
+    let counter = CallbackCounter { completed: completed.clone(), progressed: progressed.clone() };
+    let handle = Rc::new(RefCell::new(None));
+    let c_handle = handle.clone();
+    let w = fn_widget! {
+      let c_completed = completed.clone();
+      let c_progressed = progressed.clone();
+      let animate = Animate::declarer()
+        .transition(transition)
+        .state(Stateful::new(1.))
+        .from(0.)
+        .on_complete(move || c_completed.set(c_completed.get() + 1))
+        .on_progress(move |_| c_progressed.set(c_progressed.get() + 1))
+        .finish(ctx!());
+      *c_handle.borrow_mut() = Some(animate.clone_writer());
+      @Void {}
+    };
+    let mut wnd = TestWindow::new(w);
+    wnd.draw_frame();
+    let animate = handle.borrow_mut().take().unwrap();
+    (wnd, animate, counter)
+  }
+
+  fn drive_to_end(wnd: &mut TestWindow, animate: &impl Animation) {
+    for _ in 0..100 {
+      if !animate.is_running() {
+        return;
+      }
+      std::thread::sleep(Duration::from_millis(1));
+      wnd.draw_frame();
+    }
+    panic!("The animation is not finished.");
+  }
+
+  #[test]
+  fn complete_callback_once() {
+    reset_test_env!();
+
+    let transition = EasingTransition { easing: easing::LINEAR, duration: Duration::ZERO };
+    let (mut wnd, animate, counter) = callback_animate(transition.box_it());
+    animate.run();
+    drive_to_end(&mut wnd, &animate);
+
+    assert_eq!(counter.completed.get(), 1);
+    assert!(counter.progressed.get() > 0);
+  }
+
+  #[test]
+  fn complete_after_last_repeat() {
+    reset_test_env!();
+
+    let transition =
+      EasingTransition { easing: easing::LINEAR, duration: Duration::from_millis(2) }.repeat(3.);
+    let (mut wnd, animate, counter) = callback_animate(transition.box_it());
+    animate.run();
+    drive_to_end(&mut wnd, &animate);
+
+    assert_eq!(counter.completed.get(), 1);
+  }
+
+  #[test]
+  fn infinite_repeat_not_complete() {
+    reset_test_env!();
+
+    let transition =
+      EasingTransition { easing: easing::LINEAR, duration: Duration::from_millis(1) }
+        .repeat(f32::INFINITY);
+    let (mut wnd, animate, counter) = callback_animate(transition.box_it());
+    animate.run();
+    for _ in 0..5 {
+      std::thread::sleep(Duration::from_millis(1));
+      wnd.draw_frame();
+    }
+    assert!(animate.is_running());
+    assert!(counter.progressed.get() >= 5);
+
+    animate.stop();
+    assert_eq!(counter.completed.get(), 0);
+  }
+
+  #[test]
+  fn cancelled_run_not_complete() {
+    reset_test_env!();
+
+    let transition =
+      EasingTransition { easing: easing::LINEAR, duration: Duration::from_millis(20) };
+    let (mut wnd, animate, counter) = callback_animate(transition.box_it());
+    animate.run();
+    wnd.draw_frame();
+    animate.stop();
+    wnd.draw_frame();
+    assert_eq!(counter.completed.get(), 0);
+
+    // Only the cancelled run is skipped.
+    animate.run();
+    drive_to_end(&mut wnd, &animate);
+    assert_eq!(counter.completed.get(), 1);
+  }
+
+  #[test]
+  fn retargeted_run_complete() {
+    reset_test_env!();
+
+    let transition =
+      EasingTransition { easing: easing::LINEAR, duration: Duration::from_millis(20) };
+    let (mut wnd, animate, counter) = callback_animate(transition.box_it());
+    animate.run();
+    wnd.draw_frame();
+    // Retarget the animation before it finishes.
+    animate.run();
+    assert!(animate.is_running());
+    drive_to_end(&mut wnd, &animate);
+    assert_eq!(counter.completed.get(), 1);
+
+    animate.run();
+    drive_to_end(&mut wnd, &animate);
+    assert_eq!(counter.completed.get(), 2);
+  }
+
+  /// A transition always at its half, to sample the midpoint of an animation.
+  struct HalfTransition;
+
+  impl Transition for HalfTransition {
+    fn rate_of_change(&self, _: Duration) -> AnimateProgress { AnimateProgress::Between(0.5) }
+
+    fn duration(&self) -> Duration { Duration::from_secs(1) }
+  }
+
+  fn sample_half<V: Lerp + Clone + 'static>(from: V, to: V) -> V {
+    let sampled = Rc::new(RefCell::new(None));
+    let handle = Rc::new(RefCell::new(None));
+    let (c_sampled, c_handle) = (sampled.clone(), handle.clone());
+    let w = fn_widget! {
+      let state = Stateful::new(to);
+      let reader = state.clone_reader();
+      let animate = Animate::declarer()
+        .transition(HalfTransition.box_it())
+        .state(state)
+        .from(from)
+        .on_progress(move |_| *c_sampled.borrow_mut() = Some(reader.read().clone()))
+        .finish(ctx!());
+      *c_handle.borrow_mut() = Some(animate);
+      @Void {}
+    };
+    let mut wnd = TestWindow::new(w);
+    wnd.draw_frame();
+    let animate = handle.borrow_mut().take().unwrap();
+    animate.run();
+    wnd.draw_frame();
+
+    let v = sampled.borrow_mut().take();
+    v.expect("The animation is not sampled.")
+  }
+
+  #[test]
+  fn color_animate_midpoint() {
+    reset_test_env!();
+
+    let half = sample_half(Color::RED, Color::BLUE);
+    assert_eq!(half, Color::new(128, 0, 128, 255));
+  }
+
+  #[test]
+  fn integer_animate_midpoint() {
+    reset_test_env!();
+
+    assert_eq!(sample_half(0, 10), 5);
+    assert_eq!(sample_half(0usize, 5), 3);
+  }
 }
@@ -10,7 +10,7 @@ pub trait AnimateStateSetter {
   type C: AnimateStateSetter<Value = Self::Value>;
   type Value: Clone;
 
-  fn get(&self) -> Self::Value;
+  fn get_value(&self) -> Self::Value;
   fn set(&self, v: Self::Value);
   fn animate_state_modifies(&self) -> BoxOp<'static, ModifyScope, Infallible>;
   fn clone_setter(&self) -> Self::C;
@@ -19,6 +19,14 @@ pub trait AnimateStateSetter {
 /// Trait to help animate calc the lerp value.
 pub trait AnimateState: AnimateStateSetter {
   fn calc_lerp_value(&mut self, from: &Self::Value, to: &Self::Value, rate: f32) -> Self::Value;
+
+  /// Return the ratio of the distance from `from` to `to` to the distance
+  /// from `new_from` to `new_to`, see [`Lerp::distance_ratio`].
+  fn distance_ratio(
+    &self, _from: &Self::Value, _to: &Self::Value, _new_from: &Self::Value, _new_to: &Self::Value,
+  ) -> Option<f32> {
+    None
+  }
 }
 
 /// A state with a lerp function as an animation state that use the `lerp_fn`
@@ -41,7 +49,7 @@ where
   type Value = S::Value;
 
   #[inline]
-  fn get(&self) -> Self::Value { self.read().clone() }
+  fn get_value(&self) -> Self::Value { self.read().clone() }
 
   #[inline]
   fn set(&self, v: Self::Value) { *self.shallow() = v; }
@@ -63,6 +71,12 @@ where
   fn calc_lerp_value(&mut self, from: &Self::Value, to: &Self::Value, rate: f32) -> Self::Value {
     from.lerp(to, rate)
   }
+
+  fn distance_ratio(
+    &self, from: &Self::Value, to: &Self::Value, new_from: &Self::Value, new_to: &Self::Value,
+  ) -> Option<f32> {
+    from.distance_ratio(to, new_from, new_to)
+  }
 }
 
 impl<S, F> AnimateStateSetter for LerpFnState<S, F>
@@ -73,7 +87,7 @@ where
   type Value = S::Value;
 
   #[inline]
-  fn get(&self) -> Self::Value { self.state.get() }
+  fn get_value(&self) -> Self::Value { self.state.get_value() }
 
   #[inline]
   fn set(&self, v: Self::Value) { self.state.set(v) }
@@ -120,8 +134,8 @@ macro_rules! impl_animate_state_for_tuple {
         type C = ($([<S $tuple>]::C), *);
         type Value = ($([<S $tuple>]::Value), *);
 
-        fn get(&self) -> Self::Value {
-          ($(self.$tuple.get()),*)
+        fn get_value(&self) -> Self::Value {
+          ($(self.$tuple.get_value()),*)
         }
 
 
@@ -175,7 +189,7 @@ mod tests {
   fn group_two() {
     reset_test_env!();
     let mut group = (State::value(1.), State::value(2.));
-    let half = group.calc_lerp_value(&(0., 0.), &group.get(), 0.5);
+    let half = group.calc_lerp_value(&(0., 0.), &group.get_value(), 0.5);
     assert_eq!(half, (0.5, 1.));
   }
 }
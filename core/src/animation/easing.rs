@@ -3,6 +3,13 @@ use lyon_geom::{CubicBezierSegment, QuadraticBezierSegment};
 /// Specify the rate of change of the rate of over time.
 pub trait Easing {
   fn easing(&self, time_rate: f32) -> f32;
+
+  fn box_it(self) -> Box<dyn Easing>
+  where
+    Self: Sized + 'static,
+  {
+    Box::new(self)
+  }
 }
 
 /// Animate at a Cubic Bézier curve. Limit x value between [0., 1.], so x-axis
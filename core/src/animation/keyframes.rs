@@ -0,0 +1,202 @@
+use std::convert::Infallible;
+
+use rxrust::ops::box_it::BoxOp;
+
+use super::*;
+use crate::state::ModifyScope;
+
+/// An animation state that animates the state through multiple keyframes,
+/// use it as the state of an `Animate` to play the keyframes by its
+/// transition.
+///
+/// Every keyframe is an `(offset, value, easing)`, the `offset` is the
+/// progress of the animation in `[0, 1]` where the value should be reached,
+/// and the `easing` is used from this keyframe to the next one.
+///
+/// # Example
+///
+/// ```
+/// use ribir_core::prelude::*;
+///
+/// let opacity = Stateful::new(1.);
+/// let keyframes = Keyframes::new(
+///   opacity,
+///   vec![
+///     (0., 0., easing::LINEAR.box_it()),
+///     (0.4, 1., easing::EASE_IN.box_it()),
+///     (0.7, 0.5, easing::LINEAR.box_it()),
+///     (1., 1., easing::LINEAR.box_it()),
+///   ],
+/// );
+/// assert!(keyframes.is_ok());
+/// ```
+pub struct Keyframes<S: AnimateStateSetter> {
+  state: S,
+  frames: Vec<(f32, S::Value, Box<dyn Easing>)>,
+}
+
+/// The error of the invalid keyframes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum KeyframesError {
+  /// There is no keyframe.
+  Empty,
+  /// The offset is not in `[0, 1]`.
+  OutOfRange(f32),
+  /// The offset is the same as the previous one.
+  Duplicate(f32),
+  /// The offset is less than the previous one.
+  NotIncreasing(f32),
+}
+
+impl std::fmt::Display for KeyframesError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      KeyframesError::Empty => write!(f, "The keyframes is empty."),
+      KeyframesError::OutOfRange(o) => write!(f, "The keyframe offset `{o}` is not in [0, 1]."),
+      KeyframesError::Duplicate(o) => write!(f, "The keyframe offset `{o}` is duplicated."),
+      KeyframesError::NotIncreasing(o) => {
+        write!(f, "The keyframe offset `{o}` is less than the previous one.")
+      }
+    }
+  }
+}
+
+impl std::error::Error for KeyframesError {}
+
+impl<S> Keyframes<S>
+where
+  S: AnimateStateSetter,
+  S::Value: Lerp,
+{
+  /// Create keyframes of the `state`, the offsets of the `frames` must be in
+  /// `[0, 1]` and strictly increasing.
+  pub fn new(
+    state: S, frames: Vec<(f32, S::Value, Box<dyn Easing>)>,
+  ) -> Result<Self, KeyframesError> {
+    if frames.is_empty() {
+      return Err(KeyframesError::Empty);
+    }
+    let mut last = None;
+    for (offset, ..) in frames.iter() {
+      let offset = *offset;
+      if !(0. ..=1.).contains(&offset) {
+        return Err(KeyframesError::OutOfRange(offset));
+      }
+      match last {
+        Some(last) if last == offset => return Err(KeyframesError::Duplicate(offset)),
+        Some(last) if last > offset => return Err(KeyframesError::NotIncreasing(offset)),
+        _ => last = Some(offset),
+      }
+    }
+    Ok(Self { state, frames })
+  }
+
+  /// Return the value of the keyframes at the `rate` of the progress.
+  pub fn value_at(&self, rate: f32) -> S::Value {
+    let frames = &self.frames;
+    let idx = frames.partition_point(|(offset, ..)| *offset <= rate);
+    if idx == 0 {
+      return frames[0].1.clone();
+    } else if idx == frames.len() {
+      return frames[idx - 1].1.clone();
+    }
+
+    let (start, from, easing) = &frames[idx - 1];
+    let (end, to, _) = &frames[idx];
+    let t = easing.easing((rate - start) / (end - start));
+    from.lerp(to, t)
+  }
+}
+
+impl<S: AnimateStateSetter> AnimateStateSetter for Keyframes<S> {
+  type C = S::C;
+  type Value = S::Value;
+
+  #[inline]
+  fn get_value(&self) -> Self::Value { self.state.get_value() }
+
+  #[inline]
+  fn set(&self, v: Self::Value) { self.state.set(v) }
+
+  #[inline]
+  fn clone_setter(&self) -> Self::C { self.state.clone_setter() }
+
+  #[inline]
+  fn animate_state_modifies(&self) -> BoxOp<'static, ModifyScope, Infallible> {
+    self.state.animate_state_modifies()
+  }
+}
+
+impl<S> AnimateState for Keyframes<S>
+where
+  S: AnimateStateSetter,
+  S::Value: Lerp,
+{
+  #[inline]
+  fn calc_lerp_value(&mut self, _: &Self::Value, _: &Self::Value, rate: f32) -> Self::Value {
+    self.value_at(rate)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{prelude::*, reset_test_env};
+
+  fn opacity_frames() -> Vec<(f32, f32, Box<dyn Easing>)> {
+    vec![
+      (0., 0., easing::LINEAR.box_it()),
+      (0.4, 1., easing::LINEAR.box_it()),
+      (0.7, 0.5, easing::LINEAR.box_it()),
+      (1., 1., easing::LINEAR.box_it()),
+    ]
+  }
+
+  #[test]
+  fn sample_keyframes() {
+    reset_test_env!();
+
+    let keyframes = Keyframes::new(Stateful::new(1.), opacity_frames())
+      .ok()
+      .unwrap();
+
+    let samples = [0., 0.2, 0.4, 0.55, 0.7, 0.85, 1.];
+    let values = samples.map(|rate| keyframes.value_at(rate));
+    let expected = [0., 0.5, 1., 0.75, 0.5, 0.75, 1.];
+    for (v, e) in values.iter().zip(expected) {
+      assert!((v - e).abs() < 1e-5);
+    }
+
+    // Clamp to the first and the last keyframe.
+    assert_eq!(keyframes.value_at(-0.5), 0.);
+    assert_eq!(keyframes.value_at(1.5), 1.);
+
+    let reverse = EasingTransition { duration: Duration::from_secs(1), easing: easing::LINEAR }
+      .reverse()
+      .rate_of_change(Duration::from_millis(600))
+      .value();
+    assert!((keyframes.value_at(reverse) - 1.).abs() < 1e-5);
+  }
+
+  #[test]
+  fn invalid_keyframes() {
+    reset_test_env!();
+
+    let keyframes =
+      |frames: Vec<(f32, f32, Box<dyn Easing>)>| Keyframes::new(Stateful::new(1.), frames).err();
+
+    assert_eq!(keyframes(vec![]), Some(KeyframesError::Empty));
+    assert_eq!(
+      keyframes(vec![(1.5, 0., easing::LINEAR.box_it())]),
+      Some(KeyframesError::OutOfRange(1.5))
+    );
+    assert_eq!(
+      keyframes(vec![(0.5, 0., easing::LINEAR.box_it()), (0.5, 1., easing::LINEAR.box_it())]),
+      Some(KeyframesError::Duplicate(0.5))
+    );
+    assert_eq!(
+      keyframes(vec![(0.5, 0., easing::LINEAR.box_it()), (0.2, 1., easing::LINEAR.box_it())]),
+      Some(KeyframesError::NotIncreasing(0.2))
+    );
+  }
+}
@@ -10,6 +10,21 @@ use crate::prelude::{
 /// * `factor`: the percent of the distance between two value to advance.s
 pub trait Lerp {
   fn lerp(&self, to: &Self, factor: f32) -> Self;
+
+  /// Return the ratio of the distance from `self` to `to` to the distance from
+  /// `from` to `target`, it's negative if they are in the opposite directions.
+  /// Return `None` if the distance of the value can't be measured.
+  ///
+  /// The animation uses it to keep the velocity when it's retargeted.
+  fn distance_ratio(&self, _to: &Self, _from: &Self, _target: &Self) -> Option<f32> { None }
+}
+
+fn scalar_ratio(a: f32, b: f32) -> Option<f32> { (b.abs() > f32::EPSILON).then(|| a / b) }
+
+/// The ratio of the vector `a` projected on the vector `b` to `b`.
+fn projection_ratio(a: Vector, b: Vector) -> Option<f32> {
+  let len = b.square_length();
+  (len > f32::EPSILON).then(|| a.dot(b) / len)
 }
 
 macro_rules! impl_lerp_for_integer {
@@ -18,7 +33,12 @@ macro_rules! impl_lerp_for_integer {
       impl Lerp for $ty {
         #[inline]
         fn lerp(&self, to: &Self, factor: f32) -> Self{
-        (*self as f32 * (1. - factor)   +  *to as f32 * factor) as $ty
+          // Round to the nearest, so the value reaches every integer between.
+          (*self as f32 * (1. - factor)   +  *to as f32 * factor).round() as $ty
+        }
+
+        fn distance_ratio(&self, to: &Self, from: &Self, target: &Self) -> Option<f32> {
+          scalar_ratio(*to as f32 - *self as f32, *target as f32 - *from as f32)
         }
       }
     )*
@@ -29,10 +49,19 @@ impl_lerp_for_integer! { i8, i16, i32, i64, isize, u8, u16, u32, u64, usize }
 
 impl Lerp for f32 {
   fn lerp(&self, to: &Self, factor: f32) -> Self { factor.mul_add(to - self, *self) }
+
+  fn distance_ratio(&self, to: &Self, from: &Self, target: &Self) -> Option<f32> {
+    scalar_ratio(to - self, target - from)
+  }
 }
 
 impl Lerp for f64 {
   fn lerp(&self, to: &Self, factor: f32) -> Self { (factor as f64).mul_add(to - self, *self) }
+
+  fn distance_ratio(&self, to: &Self, from: &Self, target: &Self) -> Option<f32> {
+    let d = target - from;
+    (d.abs() > f64::EPSILON).then(|| ((to - self) / d) as f32)
+  }
 }
 
 impl Lerp for bool {
@@ -80,19 +109,6 @@ impl_lerp_for_tuple! {T0, 0, T1, 1, T2, 2, T3, 3, T4, 4, T5, 5, T6, 6, T7, 7,T8,
   T30, 30, T31, 31
 }
 
-macro_rules! impl_lerp_for_copy_geom {
-  ($($ty: ident), *) => {
-    $(
-      impl Lerp for $ty {
-        #[inline]
-        fn lerp(&self, to: &Self, factor: f32) -> Self{
-          $ty::lerp(*self, *to, factor)
-        }
-      }
-    )*
-  }
-}
-
 macro_rules! impl_lerp_for_geom {
   ($($ty: ident), *) => {
     $(
@@ -119,7 +135,33 @@ macro_rules! impl_lerp_for_device_geom {
   }
 }
 
-impl_lerp_for_copy_geom! { Point, Size, Vector }
+impl Lerp for Point {
+  #[inline]
+  fn lerp(&self, to: &Self, factor: f32) -> Self { Point::lerp(*self, *to, factor) }
+
+  fn distance_ratio(&self, to: &Self, from: &Self, target: &Self) -> Option<f32> {
+    projection_ratio(*to - *self, *target - *from)
+  }
+}
+
+impl Lerp for Vector {
+  #[inline]
+  fn lerp(&self, to: &Self, factor: f32) -> Self { Vector::lerp(*self, *to, factor) }
+
+  fn distance_ratio(&self, to: &Self, from: &Self, target: &Self) -> Option<f32> {
+    projection_ratio(*to - *self, *target - *from)
+  }
+}
+
+impl Lerp for Size {
+  #[inline]
+  fn lerp(&self, to: &Self, factor: f32) -> Self { Size::lerp(*self, *to, factor) }
+
+  fn distance_ratio(&self, to: &Self, from: &Self, target: &Self) -> Option<f32> {
+    projection_ratio((*to - *self).to_vector(), (*target - *from).to_vector())
+  }
+}
+
 impl_lerp_for_geom! { Rect, Angle, Box2D }
 impl_lerp_for_device_geom! { DeviceRect, DevicePoint, DeviceSize, DeviceVector }
 
@@ -136,12 +178,24 @@ impl Lerp for Radius {
 }
 
 impl Lerp for Color {
+  /// Interpolate the color components premultiplied by the alpha, so the
+  /// color of a transparent end doesn't bleed into the midpoint.
   fn lerp(&self, to: &Self, factor: f32) -> Self {
+    let (from_a, to_a) = (self.alpha as f32, to.alpha as f32);
+    let alpha = from_a.lerp(&to_a, factor).clamp(0., 255.);
+    let component = |from: u8, to: u8| {
+      let v = if alpha > 0. {
+        (from as f32 * from_a).lerp(&(to as f32 * to_a), factor) / alpha
+      } else {
+        (from as f32).lerp(&(to as f32), factor)
+      };
+      v.round().clamp(0., 255.) as u8
+    };
     Self::new(
-      self.red.lerp(&to.red, factor),
-      self.green.lerp(&to.green, factor),
-      self.blue.lerp(&to.blue, factor),
-      self.alpha.lerp(&to.alpha, factor),
+      component(self.red, to.red),
+      component(self.green, to.green),
+      component(self.blue, to.blue),
+      alpha.round() as u8,
     )
   }
 }
@@ -237,4 +291,24 @@ mod tests {
   fn fix_avoid_calc_overflow() {
     assert_eq!(255u8.lerp(&0u8, 0.), 255);
   }
+
+  #[test]
+  fn lerp_integer_round() {
+    assert_eq!(0i32.lerp(&10, 0.46), 5);
+    assert_eq!(0usize.lerp(&10, 0.44), 4);
+    assert_eq!(10i32.lerp(&-10, 0.5), 0);
+  }
+
+  #[test]
+  fn lerp_color_premultiplied() {
+    let (red, blue) = (Color::RED, Color::BLUE);
+    assert_eq!(red.lerp(&blue, 0.5), Color::new(128, 0, 128, 255));
+    assert_eq!(red.lerp(&blue, 0.), red);
+    assert_eq!(red.lerp(&blue, 1.), blue);
+
+    // Fading to a transparent black keeps the red hue, not darkens it.
+    let transparent = Color::new(0, 0, 0, 0);
+    assert_eq!(red.lerp(&transparent, 0.5), Color::new(255, 0, 0, 128));
+    assert_eq!(transparent.lerp(&transparent, 0.5), transparent);
+  }
 }
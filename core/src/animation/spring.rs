@@ -0,0 +1,155 @@
+use crate::prelude::*;
+
+/// The displacement and velocity below which the spring is considered at rest.
+const REST_DELTA: f32 = 0.001;
+/// The max duration of a spring, to avoid a spring never stop.
+const MAX_DURATION: Duration = Duration::from_secs(60);
+
+/// A transition driven by the physics of a damped spring, which pulls the
+/// value from the start to the target. Unlike the `EasingTransition`, it has no
+/// fixed duration, it's finished when the spring is at rest.
+///
+/// When the target changed before the spring at rest, the spring continues
+/// from the current value with its current velocity, so there is no jump.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Spring {
+  /// The stiffness of the spring, a stiffer spring moves faster.
+  pub stiffness: f32,
+  /// The damping of the spring, a spring with less damping oscillates more.
+  pub damping: f32,
+  /// The mass of the object attached to the spring.
+  pub mass: f32,
+  /// The initial velocity of the value, in the unit of the distance from the
+  /// start to the target per second.
+  pub initial_velocity: f32,
+}
+
+impl Default for Spring {
+  fn default() -> Self { Self { stiffness: 100., damping: 10., mass: 1., initial_velocity: 0. } }
+}
+
+impl Spring {
+  /// Return the displacement from the target at the time `t` seconds, the
+  /// displacement is `-1` at the start.
+  fn displacement(&self, t: f32) -> f32 {
+    let Self { stiffness, damping, mass, initial_velocity: v0 } = *self;
+    let x0 = -1.;
+    let omega = (stiffness / mass).sqrt();
+    let zeta = damping / (2. * (stiffness * mass).sqrt());
+
+    if zeta < 1. {
+      let omega_d = omega * (1. - zeta * zeta).sqrt();
+      let decay = (-zeta * omega * t).exp();
+      let b = (v0 + zeta * omega * x0) / omega_d;
+      decay * (x0 * (omega_d * t).cos() + b * (omega_d * t).sin())
+    } else if zeta == 1. {
+      (-omega * t).exp() * (x0 + (v0 + omega * x0) * t)
+    } else {
+      let s = (zeta * zeta - 1.).sqrt();
+      let r1 = -omega * (zeta - s);
+      let r2 = -omega * (zeta + s);
+      let c2 = (v0 - r1 * x0) / (r2 - r1);
+      let c1 = x0 - c2;
+      c1 * (r1 * t).exp() + c2 * (r2 * t).exp()
+    }
+  }
+
+  /// Return the velocity of the value at the time `t` seconds.
+  fn velocity(&self, t: f32) -> f32 {
+    let h = 0.001;
+    (self.displacement(t + h) - self.displacement(t)) / h
+  }
+
+  fn is_at_rest(&self, t: f32) -> bool {
+    self.displacement(t).abs() < REST_DELTA && self.velocity(t).abs() < REST_DELTA
+  }
+}
+
+impl Transition for Spring {
+  fn rate_of_change(&self, dur: Duration) -> AnimateProgress {
+    let t = dur.as_secs_f32();
+    if dur >= MAX_DURATION || (t > 0. && self.is_at_rest(t)) {
+      AnimateProgress::Finish
+    } else {
+      AnimateProgress::Between(1. + self.displacement(t))
+    }
+  }
+
+  fn duration(&self) -> Duration {
+    // Find the time the spring at rest frame by frame.
+    let step = Duration::from_secs_f32(1. / 60.);
+    let mut dur = step;
+    while dur < MAX_DURATION && !self.is_at_rest(dur.as_secs_f32()) {
+      dur += step;
+    }
+    dur
+  }
+
+  fn retarget(&self, elapsed: Duration, velocity_scale: f32) -> Option<Box<dyn Transition>> {
+    let initial_velocity = self.velocity(elapsed.as_secs_f32()) * velocity_scale;
+    Some(Spring { initial_velocity, ..*self }.box_it())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn spring_rest() {
+    let spring = Spring::default();
+    assert_eq!(spring.rate_of_change(Duration::ZERO).value(), 0.);
+    assert!(
+      spring
+        .rate_of_change(spring.duration())
+        .is_finish()
+    );
+    assert!(spring.duration() < MAX_DURATION);
+
+    let overdamped = Spring { damping: 40., ..Spring::default() };
+    let p = overdamped
+      .rate_of_change(Duration::from_millis(100))
+      .value();
+    assert!(0. < p && p < 1.);
+  }
+
+  /// The value and the velocity of the value animated from `from` to `to` by
+  /// the `transition` at `t`.
+  fn motion(transition: &dyn Transition, from: f32, to: f32, t: Duration) -> (f32, f32) {
+    let h = Duration::from_millis(1);
+    let value = |t| from.lerp(&to, transition.rate_of_change(t).value());
+    let v = value(t);
+    (v, (value(t + h) - v) / h.as_secs_f32())
+  }
+
+  fn assert_retarget_continuity(to: f32) {
+    let spring = Spring::default();
+    let elapsed = Duration::from_millis(100);
+    let (from, old_to) = (0., 100.);
+    let (current, velocity) = motion(&spring, from, old_to, elapsed);
+
+    // Retarget to a new value, continue from the current value.
+    let scale = from.distance_ratio(&old_to, &current, &to).unwrap();
+    let retargeted = spring.retarget(elapsed, scale).unwrap();
+    let (value, new_velocity) = motion(&*retargeted, current, to, Duration::ZERO);
+    assert_eq!(value, current);
+    // The velocity is kept, the tolerance is for the numeric differentiation.
+    assert!(
+      (new_velocity - velocity).abs() < velocity.abs() * 0.05,
+      "velocity {velocity} jumps to {new_velocity}"
+    );
+  }
+
+  #[test]
+  fn retarget_continuity() {
+    assert_retarget_continuity(200.);
+    assert_retarget_continuity(60.);
+  }
+
+  #[test]
+  fn retarget_opposite_direction() {
+    // The new target is behind the current value, it's still moving forward
+    // at first.
+    assert_retarget_continuity(-100.);
+  }
+}
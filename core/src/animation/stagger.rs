@@ -50,7 +50,7 @@ use ribir_algo::Sc;
 use ribir_macros::rdl;
 
 use super::*;
-use crate::prelude::*;
+use crate::{prelude::*, timer::Timer};
 
 /// The controller of a stagger animation. It's allow you to transition states
 /// and run animation in a stagger way.
@@ -144,7 +144,7 @@ impl<T: Transition + 'static> Animation for Stateful<Stagger<T>> {
     }
     {
       let mut this = self.write();
-      this.next_to_run = Some(AnimationCursor { prev_at: Instant::now(), index: 0 });
+      this.next_to_run = Some(AnimationCursor { prev_at: Timer::now(), index: 0 });
       this.run_times += 1;
     }
 
@@ -1,3 +1,5 @@
+use std::rc::Rc;
+
 use ribir_algo::Sc;
 
 use crate::prelude::*;
@@ -16,6 +18,12 @@ pub struct DelayTransition<T> {
   pub transition: T,
 }
 
+/// Transition plays the transition backward.
+#[derive(Clone)]
+pub struct ReverseTransition<T> {
+  pub transition: T,
+}
+
 /// Transition will apply with repeat times.
 #[derive(Clone)]
 pub struct RepeatTransition<T> {
@@ -33,15 +41,15 @@ pub trait TransitionState: Sized + 'static {
     let state = self.clone_setter();
     let animate = Animate::declarer()
       .transition(transition)
-      .from(self.get())
+      .from(self.get_value())
       .state(self)
       .finish(ctx);
 
     let c_animate = animate.clone_writer();
-    let init_value = observable::of(state.get());
+    let init_value = observable::of(state.get_value());
     state
       .animate_state_modifies()
-      .map(move |_| state.get())
+      .map(move |_| state.get_value())
       .merge(init_value)
       .pairwise()
       .subscribe(move |(old, _)| {
@@ -77,6 +85,19 @@ pub trait Transition {
   /// Return the duration of the animation from start to finish.
   fn duration(&self) -> Duration;
 
+  /// Called when the target of the animation changed at `elapsed` before it's
+  /// finished. Return a new transition to restart the animation from the
+  /// current value, so it can continue the motion of this one, e.g. the
+  /// velocity of a spring. Return `None` to keep running this transition.
+  ///
+  /// The `velocity_scale` is the ratio of the distance of this transition to
+  /// the distance of the new one, the velocity in the rate of this transition
+  /// multiplied by it is the same velocity in the rate of the new one. It's
+  /// negative if the new target is in the opposite direction.
+  fn retarget(&self, _elapsed: Duration, _velocity_scale: f32) -> Option<Box<dyn Transition>> {
+    None
+  }
+
   /// Transition will apply with repeat times
   fn repeat(self, repeat: f32) -> RepeatTransition<Self>
   where
@@ -93,6 +114,14 @@ pub trait Transition {
     DelayTransition { delay, transition: self }
   }
 
+  /// Transition will apply backward, from the end to the start.
+  fn reverse(self) -> ReverseTransition<Self>
+  where
+    Self: Sized,
+  {
+    ReverseTransition { transition: self }
+  }
+
   fn box_it(self) -> Box<dyn Transition>
   where
     Self: Sized + 'static,
@@ -118,6 +147,28 @@ impl<T: Transition> Transition for DelayTransition<T> {
   }
 
   fn duration(&self) -> Duration { self.delay + self.transition.duration() }
+
+  fn retarget(&self, elapsed: Duration, velocity_scale: f32) -> Option<Box<dyn Transition>> {
+    if elapsed < self.delay {
+      None
+    } else {
+      self
+        .transition
+        .retarget(elapsed - self.delay, velocity_scale)
+    }
+  }
+}
+
+impl<T: Transition> Transition for ReverseTransition<T> {
+  fn rate_of_change(&self, dur: Duration) -> AnimateProgress {
+    match self.transition.rate_of_change(dur) {
+      AnimateProgress::Dismissed => AnimateProgress::Between(1.),
+      AnimateProgress::Between(p) => AnimateProgress::Between(1. - p),
+      AnimateProgress::Finish => AnimateProgress::Finish,
+    }
+  }
+
+  fn duration(&self) -> Duration { self.transition.duration() }
 }
 
 impl<T: Transition> Transition for RepeatTransition<T> {
@@ -150,12 +201,30 @@ impl Transition for Box<dyn Transition> {
   fn rate_of_change(&self, dur: Duration) -> AnimateProgress { (**self).rate_of_change(dur) }
 
   fn duration(&self) -> Duration { (**self).duration() }
+
+  fn retarget(&self, elapsed: Duration, velocity_scale: f32) -> Option<Box<dyn Transition>> {
+    (**self).retarget(elapsed, velocity_scale)
+  }
 }
 
 impl<T: Transition> Transition for Sc<T> {
   fn rate_of_change(&self, dur: Duration) -> AnimateProgress { (**self).rate_of_change(dur) }
 
   fn duration(&self) -> Duration { (**self).duration() }
+
+  fn retarget(&self, elapsed: Duration, velocity_scale: f32) -> Option<Box<dyn Transition>> {
+    (**self).retarget(elapsed, velocity_scale)
+  }
+}
+
+impl<T: Transition> Transition for Rc<T> {
+  fn rate_of_change(&self, dur: Duration) -> AnimateProgress { (**self).rate_of_change(dur) }
+
+  fn duration(&self) -> Duration { (**self).duration() }
+
+  fn retarget(&self, elapsed: Duration, velocity_scale: f32) -> Option<Box<dyn Transition>> {
+    (**self).retarget(elapsed, velocity_scale)
+  }
 }
 
 impl<E: Easing> Transition for EasingTransition<E> {
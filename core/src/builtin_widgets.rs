@@ -13,6 +13,8 @@ pub use keep_alive::*;
 mod theme;
 use ribir_algo::Sc;
 pub use theme::*;
+pub(crate) mod provider;
+pub use provider::Provider;
 mod cursor;
 pub use cursor::*;
 pub use winit::window::CursorIcon;
@@ -36,6 +38,8 @@ mod unconstrained_box;
 pub use unconstrained_box::*;
 mod opacity;
 pub use opacity::*;
+mod hit_test;
+pub use hit_test::*;
 mod anchor;
 pub use anchor::*;
 mod layout_box;
@@ -64,6 +68,10 @@ mod mix_builtin;
 pub use mix_builtin::*;
 pub mod container;
 pub use container::*;
+mod repaint_boundary;
+pub use repaint_boundary::*;
+mod accessibility;
+pub use accessibility::*;
 
 use crate::prelude::*;
 
@@ -101,6 +109,7 @@ pub struct FatObj<T> {
   host: T,
   host_id: LazyWidgetId,
   id: LazyWidgetId,
+  accessibility: Option<State<Accessibility>>,
   mix_builtin: Option<State<MixBuiltin>>,
   request_focus: Option<State<RequestFocus>>,
   has_focus: Option<State<HasFocus>>,
@@ -120,6 +129,7 @@ pub struct FatObj<T> {
   global_anchor: Option<State<GlobalAnchor>>,
   visibility: Option<State<Visibility>>,
   opacity: Option<State<Opacity>>,
+  hit_test: Option<State<HitTestWidget>>,
   keep_alive: Option<State<KeepAlive>>,
   keep_alive_unsubscribe_handle: Option<Box<dyn Any>>,
 }
@@ -145,6 +155,7 @@ impl<T> FatObj<T> {
       host,
       host_id: LazyWidgetId::default(),
       id: LazyWidgetId::default(),
+      accessibility: None,
       mix_builtin: None,
       request_focus: None,
       has_focus: None,
@@ -164,6 +175,7 @@ impl<T> FatObj<T> {
       global_anchor: None,
       visibility: None,
       opacity: None,
+      hit_test: None,
       keep_alive: None,
       keep_alive_unsubscribe_handle: None,
     }
@@ -177,6 +189,7 @@ impl<T> FatObj<T> {
       host: f(self.host),
       host_id: self.host_id,
       id: self.id,
+      accessibility: self.accessibility,
       mix_builtin: self.mix_builtin,
       request_focus: self.request_focus,
       has_focus: self.has_focus,
@@ -196,6 +209,7 @@ impl<T> FatObj<T> {
       global_anchor: self.global_anchor,
       visibility: self.visibility,
       opacity: self.opacity,
+      hit_test: self.hit_test,
       keep_alive: self.keep_alive,
       keep_alive_unsubscribe_handle: self.keep_alive_unsubscribe_handle,
     }
@@ -205,6 +219,7 @@ impl<T> FatObj<T> {
   pub fn is_empty(&self) -> bool {
     self.host_id.ref_count() == 1
       && self.id.ref_count() == 1
+      && self.accessibility.is_none()
       && self.mix_builtin.is_none()
       && self.request_focus.is_none()
       && self.has_focus.is_none()
@@ -224,6 +239,7 @@ impl<T> FatObj<T> {
       && self.global_anchor.is_none()
       && self.visibility.is_none()
       && self.opacity.is_none()
+      && self.hit_test.is_none()
       && self.keep_alive.is_none()
   }
 
@@ -398,6 +414,14 @@ impl<T> FatObj<T> {
       .get_or_insert_with(|| State::value(<_>::default()))
   }
 
+  /// Returns the `State<HitTestWidget>` widget from the FatObj. If it doesn't
+  /// exist, a new one is created.
+  pub fn get_hit_test_widget(&mut self) -> &mut State<HitTestWidget> {
+    self
+      .hit_test
+      .get_or_insert_with(|| State::value(<_>::default()))
+  }
+
   /// Returns the `State<KeepAlive>` widget from the FatObj. If it doesn't
   /// exist, a new one is created.
   pub fn get_keep_alive_widget(&mut self) -> &mut State<KeepAlive> {
@@ -405,6 +429,14 @@ impl<T> FatObj<T> {
       .keep_alive
       .get_or_insert_with(|| State::value(<_>::default()))
   }
+
+  /// Returns the `State<Accessibility>` widget from the FatObj. If it doesn't
+  /// exist, a new one is created.
+  pub fn get_accessibility_widget(&mut self) -> &mut State<Accessibility> {
+    self
+      .accessibility
+      .get_or_insert_with(|| State::value(<_>::default()))
+  }
 }
 
 macro_rules! on_mixin {
@@ -523,6 +555,19 @@ impl<T> FatObj<T> {
     on_mixin!(self, on_double_tap, f)
   }
 
+  /// Attaches a handler to the widget that is triggered when a double tap
+  /// occurs, with the config of the max interval and distance between the
+  /// two taps.
+  pub fn on_double_tap_with(
+    mut self, (config, f): (DoubleTapConfig, impl FnMut(&mut PointerEvent) + 'static),
+  ) -> Self {
+    self
+      .get_mix_builtin_widget()
+      .read()
+      .on_double_tap_with((config, f));
+    self
+  }
+
   /// Attaches a handler to the widget that is triggered during the capture
   /// phase of a double tap event. This is similar to `on_double_tap`, but it's
   /// triggered earlier in the event flow. For more information on event
@@ -571,6 +616,44 @@ impl<T> FatObj<T> {
     self
   }
 
+  /// Attaches a handler to the widget that is triggered by the pan gesture,
+  /// with the default `PanConfig`.
+  pub fn on_pan(mut self, f: impl FnMut(&Pan, &mut PointerEvent) + 'static) -> Self {
+    on_mixin!(self, on_pan, f)
+  }
+
+  /// Attaches a handler to the widget that is triggered by the pan gesture,
+  /// the pan starts after the pointer pressed and moved past the threshold of
+  /// the `PanConfig`, and optionally locks to the dominant axis.
+  pub fn on_pan_with(
+    mut self, (config, f): (PanConfig, impl FnMut(&Pan, &mut PointerEvent) + 'static),
+  ) -> Self {
+    self
+      .get_mix_builtin_widget()
+      .read()
+      .on_pan_with((config, f));
+    self
+  }
+
+  /// Attaches a handler to the widget that is triggered by the long press
+  /// gesture, with the default `LongPressConfig`.
+  pub fn on_long_press(mut self, f: impl FnMut(&mut PointerEvent) + 'static) -> Self {
+    on_mixin!(self, on_long_press, f)
+  }
+
+  /// Attaches a handler to the widget that is triggered by the long press
+  /// gesture, the pointer must stay pressed past the duration of the
+  /// `LongPressConfig` and not move farther than its slop.
+  pub fn on_long_press_with(
+    mut self, (config, f): (LongPressConfig, impl FnMut(&mut PointerEvent) + 'static),
+  ) -> Self {
+    self
+      .get_mix_builtin_widget()
+      .read()
+      .on_long_press_with((config, f));
+    self
+  }
+
   /// Attaches a handler to the widget that is triggered when the user rotates a
   /// wheel button on a pointing device (typically a mouse).
   pub fn on_wheel(mut self, f: impl FnMut(&mut WheelEvent) + 'static) -> Self {
@@ -888,6 +971,17 @@ impl<T> FatObj<T> {
     })
   }
 
+  /// Initializes whether the hidden widget still takes its space in the
+  /// layout.
+  pub fn maintain_space<V, M>(self, v: V) -> Self
+  where
+    DeclareInit<bool>: DeclareFrom<V, M>,
+  {
+    self.declare_builtin_init(DeclareFrom::declare_from(v), Self::get_visibility_widget, |m, v| {
+      m.maintain_space = v
+    })
+  }
+
   /// Initializes the opacity of the widget.
   pub fn opacity<V, M>(self, v: V) -> Self
   where
@@ -898,6 +992,13 @@ impl<T> FatObj<T> {
     })
   }
 
+  /// Initializes the hit test function of the widget, the pointer passes
+  /// through the widget where the function returns `false`.
+  pub fn hit_test(mut self, f: impl Fn(Point) -> bool + 'static) -> Self {
+    self.get_hit_test_widget().write().hit_test = Some(Box::new(f));
+    self
+  }
+
   /// Initializes the `keep_alive` value of the `KeepAlive` widget.
   pub fn keep_alive<V, M>(mut self, v: V) -> Self
   where
@@ -923,6 +1024,67 @@ impl<T> FatObj<T> {
     self
   }
 
+  /// Initializes the role of the widget in the accessibility tree.
+  pub fn a11y_role<V, M>(self, v: V) -> Self
+  where
+    DeclareInit<AccessRole>: DeclareFrom<V, M>,
+  {
+    self.declare_builtin_init(
+      DeclareFrom::declare_from(v),
+      Self::get_accessibility_widget,
+      |m, v| m.a11y_role = Some(v),
+    )
+  }
+
+  /// Initializes the label of the widget in the accessibility tree.
+  pub fn a11y_label<V, M>(self, v: V) -> Self
+  where
+    DeclareInit<CowArc<str>>: DeclareFrom<V, M>,
+  {
+    self.declare_builtin_init(
+      DeclareFrom::declare_from(v),
+      Self::get_accessibility_widget,
+      |m, v| m.a11y_label = Some(v),
+    )
+  }
+
+  /// Initializes the value of the widget in the accessibility tree.
+  pub fn a11y_value<V, M>(self, v: V) -> Self
+  where
+    DeclareInit<CowArc<str>>: DeclareFrom<V, M>,
+  {
+    self.declare_builtin_init(
+      DeclareFrom::declare_from(v),
+      Self::get_accessibility_widget,
+      |m, v| m.a11y_value = Some(v),
+    )
+  }
+
+  /// Initializes the hint of the widget in the accessibility tree.
+  pub fn a11y_hint<V, M>(self, v: V) -> Self
+  where
+    DeclareInit<CowArc<str>>: DeclareFrom<V, M>,
+  {
+    self.declare_builtin_init(
+      DeclareFrom::declare_from(v),
+      Self::get_accessibility_widget,
+      |m, v| m.a11y_hint = Some(v),
+    )
+  }
+
+  /// Initializes whether the widget and its descendants are excluded from the
+  /// accessibility tree.
+  pub fn a11y_hidden<V, M>(self, v: V) -> Self
+  where
+    DeclareInit<bool>: DeclareFrom<V, M>,
+  {
+    self.declare_builtin_init(
+      DeclareFrom::declare_from(v),
+      Self::get_accessibility_widget,
+      |m, v| m.a11y_hidden = v,
+    )
+  }
+
   fn declare_builtin_init<V: 'static, B: 'static>(
     mut self, init: DeclareInit<V>, get_builtin: impl FnOnce(&mut Self) -> &mut State<B>,
     set_value: fn(&mut B, V),
@@ -966,6 +1128,9 @@ impl WidgetBuilder for FatObj<Widget> {
   fn build(self, ctx: &BuildCtx) -> Widget {
     let mut host = self.host;
     self.host_id.set(host.id());
+    if let Some(accessibility) = self.accessibility {
+      host = accessibility.with_child(host, ctx).build(ctx);
+    }
     if let Some(mix_builtin) = self.mix_builtin {
       host = mix_builtin.with_child(host, ctx).build(ctx)
     }
@@ -1023,6 +1188,9 @@ impl WidgetBuilder for FatObj<Widget> {
     if let Some(opacity) = self.opacity {
       host = opacity.with_child(host, ctx).build(ctx);
     }
+    if let Some(hit_test) = self.hit_test {
+      host = hit_test.with_child(host, ctx).build(ctx);
+    }
     if let Some(keep_alive) = self.keep_alive {
       host = keep_alive.with_child(host, ctx).build(ctx);
     }
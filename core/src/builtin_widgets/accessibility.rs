@@ -0,0 +1,225 @@
+use crate::{data_widget::Queryable, prelude::*, widget_tree::WidgetTree};
+
+/// The role of a widget in the accessibility tree, it tells the assistive
+/// technologies what the widget is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccessRole {
+  /// The root of the accessibility tree of a window.
+  Window,
+  /// A node that only groups its children. It's the role of a widget that
+  /// declares the accessibility information but no role.
+  Group,
+  StaticText,
+  Button,
+  Link,
+  Image,
+  CheckBox,
+  TextInput,
+  Slider,
+  List,
+  ListItem,
+}
+
+/// `Accessibility` is an attribute to describe a widget for the assistive
+/// technologies, like screen readers.
+///
+/// A widget has a node in the accessibility tree exported by
+/// [`Window::accessibility_tree`] if it declares any accessibility information
+/// or its render object provides a default one, like a `Text` is a
+/// `StaticText` node labeled by its text. The declared information overrides
+/// the default one.
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct Accessibility {
+  /// The role of the widget, `AccessRole::Group` if not set.
+  pub a11y_role: Option<AccessRole>,
+  /// The name of the widget to announce.
+  pub a11y_label: Option<CowArc<str>>,
+  /// The current value of the widget, like the text of an input or the url
+  /// of a link.
+  pub a11y_value: Option<CowArc<str>>,
+  /// The extra description of what the widget does.
+  pub a11y_hint: Option<CowArc<str>>,
+  /// Whether the widget and its descendants are excluded from the
+  /// accessibility tree.
+  pub a11y_hidden: bool,
+}
+
+/// A node of the accessibility tree, see [`Window::accessibility_tree`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccessNode {
+  pub id: WidgetId,
+  pub role: AccessRole,
+  pub label: Option<CowArc<str>>,
+  pub value: Option<CowArc<str>>,
+  pub hint: Option<CowArc<str>>,
+  /// The rect of the widget in the window coordinate.
+  pub bounds: Rect,
+  pub children: Vec<AccessNode>,
+}
+
+impl Declare for Accessibility {
+  type Builder = FatObj<()>;
+  #[inline]
+  fn declarer() -> Self::Builder { FatObj::new(()) }
+}
+
+impl ComposeChild for Accessibility {
+  type Child = Widget;
+  fn compose_child(this: impl StateWriter<Value = Self>, child: Self::Child) -> impl WidgetBuilder {
+    fn_widget! {
+      let data: Box<dyn AnyAccessibility> = Box::new(this);
+      child.attach_data(Queryable(data), ctx!()).build(ctx!())
+    }
+  }
+}
+
+impl Accessibility {
+  /// Override the information of `self` by the set ones of `other`.
+  pub(crate) fn merge(&mut self, other: &Accessibility) {
+    if other.a11y_role.is_some() {
+      self.a11y_role = other.a11y_role;
+    }
+    if other.a11y_label.is_some() {
+      self.a11y_label = other.a11y_label.clone();
+    }
+    if other.a11y_value.is_some() {
+      self.a11y_value = other.a11y_value.clone();
+    }
+    if other.a11y_hint.is_some() {
+      self.a11y_hint = other.a11y_hint.clone();
+    }
+    self.a11y_hidden |= other.a11y_hidden;
+  }
+}
+
+/// A trait to query the `Accessibility` attached to a widget.
+pub(crate) trait AnyAccessibility: Any {
+  fn accessibility(&self) -> Accessibility;
+}
+
+impl<T> AnyAccessibility for T
+where
+  T: StateWriter<Value = Accessibility>,
+{
+  fn accessibility(&self) -> Accessibility { self.read().clone() }
+}
+
+impl WidgetTree {
+  pub(crate) fn accessibility_tree(&self) -> AccessNode {
+    let root = self.root();
+    let mut children = vec![];
+    self.collect_access_nodes(root, &mut children);
+    AccessNode {
+      id: root,
+      role: AccessRole::Window,
+      label: None,
+      value: None,
+      hint: None,
+      bounds: self.access_bounds(root),
+      children,
+    }
+  }
+
+  /// Collect the accessibility nodes of the subtree of `id` to `out`. A widget
+  /// without accessibility information doesn't have a node, its descendants
+  /// are collected to its nearest ancestor node.
+  fn collect_access_nodes(&self, id: WidgetId, out: &mut Vec<AccessNode>) {
+    let render = id.assert_get(&self.arena);
+    let mut info = render.accessibility();
+    // The inner attached information is overridden by the outer one.
+    for attached in render.query_all_iter::<Box<dyn AnyAccessibility>>() {
+      info
+        .get_or_insert_with(Accessibility::default)
+        .merge(&attached.accessibility());
+    }
+
+    match info {
+      Some(info) if info.a11y_hidden => {}
+      Some(info) => {
+        let mut children = vec![];
+        id.children(&self.arena)
+          .for_each(|c| self.collect_access_nodes(c, &mut children));
+        out.push(AccessNode {
+          id,
+          role: info.a11y_role.unwrap_or(AccessRole::Group),
+          label: info.a11y_label,
+          value: info.a11y_value,
+          hint: info.a11y_hint,
+          bounds: self.access_bounds(id),
+          children,
+        });
+      }
+      None => id
+        .children(&self.arena)
+        .for_each(|c| self.collect_access_nodes(c, out)),
+    }
+  }
+
+  fn access_bounds(&self, id: WidgetId) -> Rect {
+    self
+      .store
+      .global_rect(id, &self.arena)
+      .unwrap_or_default()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{reset_test_env, test_helper::*};
+
+  #[test]
+  fn export_accessibility_tree() {
+    reset_test_env!();
+
+    let w = fn_widget! {
+      @MockMulti {
+        a11y_role: AccessRole::List,
+        a11y_label: "actions",
+        @MockBox {
+          size: Size::new(20., 10.),
+          a11y_role: AccessRole::Button,
+          a11y_label: "Ok",
+          a11y_hint: "Submit the form",
+        }
+        @MockBox {
+          size: Size::new(30., 10.),
+          visible: false,
+          a11y_role: AccessRole::Button,
+          a11y_label: "Hidden",
+        }
+        @MockBox {
+          size: Size::new(40., 10.),
+          margin: EdgeInsets::only_left(5.),
+          a11y_label: "More",
+        }
+        @MockBox { size: Size::new(10., 10.) }
+      }
+    };
+
+    let mut wnd = TestWindow::new(w);
+    wnd.draw_frame();
+
+    let tree = wnd.accessibility_tree();
+    assert_eq!(tree.role, AccessRole::Window);
+    assert_eq!(tree.children.len(), 1);
+
+    let list = &tree.children[0];
+    assert_eq!(list.role, AccessRole::List);
+    assert_eq!(list.label.as_deref(), Some("actions"));
+    assert_eq!(list.bounds, Rect::new(Point::zero(), Size::new(75., 10.)));
+    assert_eq!(list.children.len(), 2);
+
+    let ok = &list.children[0];
+    assert_eq!(ok.role, AccessRole::Button);
+    assert_eq!(ok.label.as_deref(), Some("Ok"));
+    assert_eq!(ok.hint.as_deref(), Some("Submit the form"));
+    assert_eq!(ok.bounds, Rect::new(Point::zero(), Size::new(20., 10.)));
+
+    // The hidden widget is excluded, and a widget without role is a group.
+    let more = &list.children[1];
+    assert_eq!(more.role, AccessRole::Group);
+    assert_eq!(more.label.as_deref(), Some("More"));
+    assert_eq!(more.bounds, Rect::new(Point::new(25., 0.), Size::new(40., 10.)));
+  }
+}
@@ -118,4 +118,69 @@ mod tests {
     wnd.run_frame_tasks();
     assert_eq!(wnd.get_cursor(), CursorIcon::Help);
   }
+
+  fn move_to(wnd: &TestWindow, x: f64, y: f64) {
+    let device_id = unsafe { DeviceId::dummy() };
+    wnd
+      .dispatcher
+      .borrow_mut()
+      .dispatch(WindowEvent::CursorMoved { device_id, position: (x, y).into() }, 1.);
+    wnd.run_frame_tasks();
+  }
+
+  #[test]
+  fn topmost_overlap() {
+    reset_test_env!();
+
+    let w = fn_widget! {
+      @MockStack {
+        child_pos: vec![Point::zero(), Point::zero()],
+        @MockBox {
+          size: Size::new(100., 100.),
+          cursor: CursorIcon::Pointer,
+        }
+        @MockBox {
+          size: Size::new(50., 50.),
+          cursor: CursorIcon::Text,
+        }
+      }
+    };
+    let mut wnd = TestWindow::new(w);
+    wnd.draw_frame();
+
+    move_to(&wnd, 10., 10.);
+    assert_eq!(wnd.get_cursor(), CursorIcon::Text);
+    move_to(&wnd, 70., 10.);
+    assert_eq!(wnd.get_cursor(), CursorIcon::Pointer);
+    move_to(&wnd, 200., 10.);
+    assert_eq!(wnd.get_cursor(), CursorIcon::Default);
+  }
+
+  #[test]
+  fn reset_when_removed() {
+    reset_test_env!();
+
+    let show = Stateful::new(true);
+    let c_show = show.clone_writer();
+    let w = fn_widget! {
+      @MockMulti {
+        @ {
+          pipe!(*$show).map(move |show| show.then(|| @MockBox {
+            size: Size::new(100., 100.),
+            cursor: CursorIcon::Text,
+          }))
+        }
+      }
+    };
+    let mut wnd = TestWindow::new(w);
+    wnd.draw_frame();
+
+    move_to(&wnd, 10., 10.);
+    assert_eq!(wnd.get_cursor(), CursorIcon::Text);
+
+    *c_show.write() = false;
+    wnd.draw_frame();
+    wnd.run_frame_tasks();
+    assert_eq!(wnd.get_cursor(), CursorIcon::Default);
+  }
 }
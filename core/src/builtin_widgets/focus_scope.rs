@@ -12,6 +12,13 @@ pub struct FocusScope {
   /// skip the whole subtree.
   #[declare(default)]
   pub can_focus: bool,
+
+  /// If true, the Tab and Shift-Tab wrap within the scope instead of moving
+  /// out of it, this is useful for the modal overlays. The focus moves into
+  /// the scope when it's mounted, and is restored to the widget focused before
+  /// when it's disposed.
+  #[declare(default)]
+  pub trap: bool,
 }
 
 impl ComposeChild for FocusScope {
@@ -19,8 +26,18 @@ impl ComposeChild for FocusScope {
   fn compose_child(this: impl StateWriter<Value = Self>, child: Self::Child) -> impl WidgetBuilder {
     fn_widget! {
       @ $child {
-        on_mounted: move |e| e.window().add_focus_node(e.id, false, FocusType::Scope),
-        on_disposed: move|e| e.window().remove_focus_node(e.id, FocusType::Scope),
+        on_mounted: move |e| {
+          let wnd = e.window();
+          wnd.add_focus_node(e.id, false, FocusType::Scope);
+          if $this.trap {
+            wnd.focus_mgr.borrow_mut().open_trap_scope(e.id);
+          }
+        },
+        on_disposed: move |e| {
+          let wnd = e.window();
+          wnd.remove_focus_node(e.id, FocusType::Scope);
+          wnd.focus_mgr.borrow_mut().close_trap_scope(e.id);
+        },
       }
       .build(ctx!())
       .try_unwrap_state_and_attach(this, ctx!())
@@ -30,13 +47,15 @@ impl ComposeChild for FocusScope {
 
 #[cfg(test)]
 mod tests {
+  use std::{cell::Cell, rc::Rc};
+
   use winit::{
     dpi::LogicalPosition,
     event::{DeviceId, ElementState, MouseButton, WindowEvent},
   };
 
   use super::*;
-  use crate::test_helper::*;
+  use crate::{reset_test_env, test_helper::*};
 
   #[test]
   fn tab_scope() {
@@ -203,4 +222,142 @@ mod tests {
     wnd.draw_frame();
     assert_eq!(*result.read(), 2);
   }
+
+  fn focus_box(focused: &Rc<Cell<&'static str>>, name: &'static str) -> impl WidgetBuilder {
+    let focused = focused.clone();
+    fn_widget! {
+      @MockBox {
+        size: Size::zero(),
+        tab_index: 0i16,
+        on_focus: move |_| focused.set(name),
+      }
+    }
+  }
+
+  #[test]
+  fn trap_and_restore() {
+    reset_test_env!();
+
+    let focused = Rc::new(Cell::new(""));
+    let open = Stateful::new(false);
+    let c_open = open.clone_writer();
+    let (c_focused1, c_focused2) = (focused.clone(), focused.clone());
+    let w = fn_widget! {
+      let dialog = pipe!(*$open).map(move |open| {
+        if open {
+          @FocusScope {
+            trap: true,
+            @MockMulti {
+              @ { focus_box(&c_focused1, "first") }
+              @ { focus_box(&c_focused1, "last") }
+            }
+          }
+          .build(ctx!())
+        } else {
+          Void.build(ctx!())
+        }
+      });
+      @MockMulti {
+        @ { focus_box(&c_focused2, "outer") }
+        @ { dialog }
+        @ { focus_box(&c_focused2, "after") }
+      }
+    };
+
+    let mut wnd = TestWindow::new(w);
+    wnd.draw_frame();
+    wnd.request_next_focus();
+    wnd.run_frame_tasks();
+    assert_eq!(focused.get(), "outer");
+
+    *c_open.write() = true;
+    wnd.draw_frame();
+    assert_eq!(focused.get(), "first");
+
+    wnd.request_next_focus();
+    wnd.run_frame_tasks();
+    assert_eq!(focused.get(), "last");
+    // Tab past the last wraps to the first rather than escaping.
+    wnd.request_next_focus();
+    wnd.run_frame_tasks();
+    assert_eq!(focused.get(), "first");
+    wnd.request_prev_focus();
+    wnd.run_frame_tasks();
+    assert_eq!(focused.get(), "last");
+
+    *c_open.write() = false;
+    wnd.draw_frame();
+    assert_eq!(focused.get(), "outer");
+  }
+
+  #[test]
+  fn nested_trap_scopes() {
+    reset_test_env!();
+
+    let focused = Rc::new(Cell::new(""));
+    let outer = Stateful::new(false);
+    let inner = Stateful::new(false);
+    let (c_outer, c_inner) = (outer.clone_writer(), inner.clone_writer());
+    let (c_focused1, c_focused2, c_focused3) =
+      (focused.clone(), focused.clone(), focused.clone());
+    let w = fn_widget! {
+      let outer_dialog = pipe!(*$outer).map(move |open| {
+        if open {
+          @FocusScope {
+            trap: true,
+            @MockMulti {
+              @ { focus_box(&c_focused1, "dialog1") }
+              @ { focus_box(&c_focused1, "dialog2") }
+            }
+          }
+          .build(ctx!())
+        } else {
+          Void.build(ctx!())
+        }
+      });
+      let inner_dialog = pipe!(*$inner).map(move |open| {
+        if open {
+          @FocusScope {
+            trap: true,
+            @MockMulti { @ { focus_box(&c_focused2, "inner") } }
+          }
+          .build(ctx!())
+        } else {
+          Void.build(ctx!())
+        }
+      });
+      @MockMulti {
+        @ { focus_box(&c_focused3, "page") }
+        @ { outer_dialog }
+        @ { inner_dialog }
+      }
+    };
+
+    let mut wnd = TestWindow::new(w);
+    wnd.draw_frame();
+    wnd.request_next_focus();
+    wnd.run_frame_tasks();
+    assert_eq!(focused.get(), "page");
+
+    *c_outer.write() = true;
+    wnd.draw_frame();
+    wnd.request_next_focus();
+    wnd.run_frame_tasks();
+    assert_eq!(focused.get(), "dialog2");
+
+    *c_inner.write() = true;
+    wnd.draw_frame();
+    assert_eq!(focused.get(), "inner");
+    wnd.request_next_focus();
+    wnd.run_frame_tasks();
+    assert_eq!(focused.get(), "inner");
+
+    // Close the scopes from the innermost one.
+    *c_inner.write() = false;
+    wnd.draw_frame();
+    assert_eq!(focused.get(), "dialog2");
+    *c_outer.write() = false;
+    wnd.draw_frame();
+    assert_eq!(focused.get(), "page");
+  }
 }
@@ -0,0 +1,94 @@
+use crate::prelude::*;
+
+/// A widget that let you customize the hit test of its child.
+///
+/// The `hit_test` function receives the pointer position relative to the
+/// widget. If it returns `false`, the pointer passes through the widget and
+/// the widgets below it have a chance to be hit, this is useful for widgets
+/// that have transparent regions.
+#[derive(SingleChild, Default)]
+pub struct HitTestWidget {
+  pub hit_test: Option<Box<dyn Fn(Point) -> bool>>,
+}
+
+impl Declare for HitTestWidget {
+  type Builder = FatObj<()>;
+  #[inline]
+  fn declarer() -> Self::Builder { FatObj::new(()) }
+}
+
+impl Render for HitTestWidget {
+  #[inline]
+  fn perform_layout(&self, clamp: BoxClamp, ctx: &mut LayoutCtx) -> Size {
+    ctx.assert_perform_single_child_layout(clamp)
+  }
+
+  #[inline]
+  fn paint(&self, _: &mut PaintingCtx) {}
+
+  fn hit_test(&self, ctx: &HitTestCtx, pos: Point) -> HitTest {
+    let can_hit_child = ctx.box_rect().map_or(false, |rect| {
+      rect.contains(pos)
+        && self
+          .hit_test
+          .as_ref()
+          .map_or(true, |f| f(pos - rect.origin.to_vector()))
+    });
+    HitTest { hit: false, can_hit_child }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use winit::{
+    dpi::LogicalPosition,
+    event::{DeviceId, ElementState, MouseButton, WindowEvent},
+  };
+
+  use super::*;
+  use crate::{reset_test_env, test_helper::*};
+
+  #[test]
+  fn pass_through_rejected_region() {
+    reset_test_env!();
+
+    let (top, w_top) = split_value(0);
+    let (bottom, w_bottom) = split_value(0);
+    let w = fn_widget! {
+      @MockStack {
+        child_pos: vec![Point::zero(), Point::zero()],
+        @MockBox {
+          size: Size::new(100., 100.),
+          on_pointer_down: move |_| *$w_bottom.write() += 1,
+        }
+        @MockBox {
+          size: Size::new(100., 100.),
+          hit_test: |p: Point| p.x >= 50.,
+          on_pointer_down: move |_| *$w_top.write() += 1,
+        }
+      }
+    };
+
+    let mut wnd = TestWindow::new_with_size(w, Size::new(500., 500.));
+    wnd.draw_frame();
+
+    let device_id = unsafe { DeviceId::dummy() };
+    let press_at = |x: f32, y: f32| {
+      #[allow(deprecated)]
+      wnd.processes_native_event(WindowEvent::CursorMoved {
+        device_id,
+        position: LogicalPosition::new(x, y).to_physical(1.),
+      });
+      wnd.process_mouse_input(device_id, ElementState::Pressed, MouseButton::Left);
+      wnd.process_mouse_input(device_id, ElementState::Released, MouseButton::Left);
+      wnd.run_frame_tasks();
+    };
+
+    // The left half of the top box rejects the hit, the bottom box receives it.
+    press_at(20., 20.);
+    assert_eq!((*top.read(), *bottom.read()), (0, 1));
+
+    press_at(80., 20.);
+    assert_eq!((*top.read(), *bottom.read()), (1, 1));
+  }
+}
@@ -14,4 +14,8 @@ impl Render for Resource<PixelImage> {
       painter.draw_img(self.clone(), &rc, &Some(rc));
     }
   }
+
+  fn accessibility(&self) -> Option<Accessibility> {
+    Some(Accessibility { a11y_role: Some(AccessRole::Image), ..Default::default() })
+  }
 }
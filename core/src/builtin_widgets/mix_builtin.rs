@@ -1,4 +1,8 @@
-use std::{cell::Cell, convert::Infallible};
+use std::{
+  cell::{Cell, RefCell},
+  convert::Infallible,
+  rc::Rc,
+};
 
 use rxrust::prelude::*;
 
@@ -45,6 +49,10 @@ pub type EventSubject = MutRefItemSubject<'static, Event, Infallible>;
 pub struct MixBuiltin {
   flags: Cell<BuiltinFlags>,
   subject: EventSubject,
+  double_taps: RefCell<Vec<Rc<DoubleTapRecognizer>>>,
+  /// If the tap in dispatching is the second tap of a double tap, it's not
+  /// delivered to the `on_tap` handlers.
+  double_tapped: Rc<Cell<bool>>,
 }
 
 impl Declare for MixBuiltin {
@@ -78,12 +86,25 @@ impl MixBuiltin {
   #[inline]
   pub fn contain_flag(&self, t: BuiltinFlags) -> bool { self.flags.get().contains(t) }
 
+  pub(crate) fn flags(&self) -> BuiltinFlags { self.flags.get() }
+
   pub fn flag_mark(&self, t: BuiltinFlags) {
     let t = self.flags.get() | t;
     self.flags.set(t)
   }
 
-  pub fn dispatch(&self, event: &mut Event) { self.subject.clone().next(event) }
+  pub fn dispatch(&self, event: &mut Event) {
+    if let Event::Tap(e) = event {
+      // Every recognizer must see the tap, so don't short circuit.
+      let double = self
+        .double_taps
+        .borrow()
+        .iter()
+        .fold(false, |double, r| r.recognize(e) | double);
+      self.double_tapped.set(double);
+    }
+    self.subject.clone().next(event)
+  }
 
   pub fn subject(&self) -> EventSubject { self.subject.clone() }
 
@@ -156,7 +177,15 @@ impl MixBuiltin {
     impl_event_callback!(self, Pointer, PointerLeave, PointerEvent, handler)
   }
 
-  pub fn on_tap(&self, handler: impl FnMut(&mut PointerEvent) + 'static) -> &Self {
+  /// Listen to the tap event, the second tap of a double tap recognized by
+  /// `on_double_tap` is not delivered to it.
+  pub fn on_tap(&self, mut handler: impl FnMut(&mut PointerEvent) + 'static) -> &Self {
+    let double_tapped = self.double_tapped.clone();
+    let handler = move |e: &mut PointerEvent| {
+      if !double_tapped.get() {
+        handler(e)
+      }
+    };
     impl_event_callback!(self, Pointer, Tap, PointerEvent, handler)
   }
 
@@ -164,8 +193,29 @@ impl MixBuiltin {
     impl_event_callback!(self, Pointer, TapCapture, PointerEvent, handler)
   }
 
+  /// Listen to the double tap gesture with the default config, see
+  /// `on_double_tap_with`.
   pub fn on_double_tap(&self, handler: impl FnMut(&mut PointerEvent) + 'static) -> &Self {
-    self.on_x_times_tap((2, handler))
+    self.on_double_tap_with((DoubleTapConfig::default(), handler))
+  }
+
+  /// Listen to the double tap gesture, it fires with the second of two taps
+  /// within the interval and the slop of the `config`. The `on_tap` handlers
+  /// only receive the first tap.
+  pub fn on_double_tap_with(
+    &self, (config, mut handler): (DoubleTapConfig, impl FnMut(&mut PointerEvent) + 'static),
+  ) -> &Self {
+    let recognizer = Rc::new(DoubleTapRecognizer::new(config));
+    self
+      .double_taps
+      .borrow_mut()
+      .push(recognizer.clone());
+    let handler = move |e: &mut PointerEvent| {
+      if recognizer.recognized() {
+        handler(e)
+      }
+    };
+    impl_event_callback!(self, Pointer, Tap, PointerEvent, handler)
   }
 
   pub fn on_double_tap_capture(&self, handler: impl FnMut(&mut PointerEvent) + 'static) -> &Self {
@@ -212,6 +262,43 @@ impl MixBuiltin {
     self
   }
 
+  /// Listen to the pan gesture with the default config, see `on_pan_with`.
+  pub fn on_pan(&self, handler: impl FnMut(&Pan, &mut PointerEvent) + 'static) -> &Self {
+    self.on_pan_with((PanConfig::default(), handler))
+  }
+
+  /// Listen to the pan gesture, the pan starts after the pointer pressed and
+  /// moved past the threshold of the `config`. The pointer is grabbed during
+  /// the pan, so it continues even if the pointer is out of the widget.
+  pub fn on_pan_with(
+    &self, (config, handler): (PanConfig, impl FnMut(&Pan, &mut PointerEvent) + 'static),
+  ) -> &Self {
+    self.flag_mark(BuiltinFlags::Pointer);
+    let _ = self
+      .subject()
+      .subscribe(pan_recognizer(config, handler));
+    self
+  }
+
+  /// Listen to the long press gesture with the default config, see
+  /// `on_long_press_with`.
+  pub fn on_long_press(&self, handler: impl FnMut(&mut PointerEvent) + 'static) -> &Self {
+    self.on_long_press_with((LongPressConfig::default(), handler))
+  }
+
+  /// Listen to the long press gesture, it fires when the pointer stays pressed
+  /// past the duration of the `config`. It's canceled if the pointer is
+  /// released early or moves farther than the slop.
+  pub fn on_long_press_with(
+    &self, (config, handler): (LongPressConfig, impl FnMut(&mut PointerEvent) + 'static),
+  ) -> &Self {
+    self.flag_mark(BuiltinFlags::Pointer);
+    let _ = self
+      .subject()
+      .subscribe(long_press_recognizer(config, handler));
+    self
+  }
+
   pub fn on_ime_pre_edit(&self, f: impl FnMut(&mut ImePreEditEvent) + 'static) -> &Self {
     impl_event_callback!(self, KeyBoard, ImePreEdit, ImePreEditEvent, f)
   }
@@ -1,5 +1,13 @@
 use crate::prelude::*;
 
+/// Widget keep whether the pointer is over its child. As a builtin widget, user
+/// can call `mouse_hover` method to get the hover state of a widget, and bind
+/// it to update the style, e.g. `background: pipe!(if $w.mouse_hover() { .. }
+/// else { .. })`.
+///
+/// The hover state follows the hit test, when the children overlap, only the
+/// topmost hit widget and its ancestors are hovered. The state is cleared if
+/// the widget is disposed while hovered.
 #[derive(PartialEq, Clone, Default)]
 pub struct MouseHover {
   hover: bool,
@@ -22,7 +30,73 @@ impl ComposeChild for MouseHover {
       @ $child {
         on_pointer_enter: move |_| $this.write().hover = true,
         on_pointer_leave: move |_| $this.write().hover = false,
+        // The disposed widget never receives the leave event.
+        on_disposed: move |_| if $this.hover {
+          $this.write().hover = false;
+        },
       }
     }
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use winit::event::{DeviceId, WindowEvent};
+
+  use super::*;
+  use crate::{reset_test_env, test_helper::*};
+
+  fn move_to(wnd: &TestWindow, x: f64, y: f64) {
+    let device_id = unsafe { DeviceId::dummy() };
+    wnd
+      .dispatcher
+      .borrow_mut()
+      .dispatch(WindowEvent::CursorMoved { device_id, position: (x, y).into() }, 1.);
+    wnd.run_frame_tasks();
+  }
+
+  #[test]
+  fn hover_state() {
+    reset_test_env!();
+
+    let hovers = Stateful::new([false; 3]);
+    let c_hovers = hovers.clone_writer();
+    let show = Stateful::new(true);
+    let c_show = show.clone_watcher();
+    let w = fn_widget! {
+      let mut stack = @MockStack { child_pos: vec![Point::zero(), Point::zero()] };
+      let mut bottom = @MockBox { size: Size::new(100., 100.) };
+      let mut top = @MockBox { size: Size::new(50., 50.) };
+      watch!([$stack.mouse_hover(), $bottom.mouse_hover(), $top.mouse_hover()])
+        .subscribe(move |v| *c_hovers.write() = v);
+
+      let mut top = Some(top);
+      @$stack {
+        @ { bottom }
+        @ { pipe!(*$c_show).map(move |show| if show { top.take() } else { None }) }
+      }
+    };
+    let mut wnd = TestWindow::new(w);
+    wnd.draw_frame();
+
+    move_to(&wnd, 80., 80.);
+    assert_eq!(*hovers.read(), [true, true, false]);
+
+    // Only the topmost hit widget and its ancestors are hovered.
+    move_to(&wnd, 10., 10.);
+    assert_eq!(*hovers.read(), [true, false, true]);
+
+    move_to(&wnd, 200., 200.);
+    assert_eq!(*hovers.read(), [false, false, false]);
+
+    move_to(&wnd, 10., 10.);
+    assert_eq!(*hovers.read(), [true, false, true]);
+
+    // When the hovered widget is removed, the widget now under the cursor is
+    // hovered.
+    *show.write() = false;
+    wnd.draw_frame();
+    wnd.run_frame_tasks();
+    assert_eq!(*hovers.read(), [true, true, false]);
+  }
+}
@@ -0,0 +1,62 @@
+use std::rc::Rc;
+
+use crate::{data_widget::Queryable, prelude::*};
+
+/// A widget that provides a value to its descendants, the descendants can read
+/// it by [`BuildCtx::find_provider`] or [`BuildCtx::provide_or_default`].
+///
+/// The nearest provider of a type shadows the outer ones of the same type.
+///
+/// # Example
+///
+/// ```
+/// use ribir_core::prelude::*;
+///
+/// let _w = fn_widget! {
+///   let provider = Provider::new(1i32);
+///   @ $provider {
+///     @ {
+///       Box::new(fn_widget! {
+///         assert_eq!(ctx!().find_provider::<i32>(), Some(&1));
+///         Void
+///       })
+///     }
+///   }
+/// };
+/// ```
+pub struct Provider {
+  value: Rc<dyn Any>,
+}
+
+/// The value provided by a `Provider`, attached to the widget of the provider.
+pub(crate) struct ProvidedValue(pub(crate) Rc<dyn Any>);
+
+impl Provider {
+  #[inline]
+  pub fn new<T: Any>(value: T) -> Self { Self { value: Rc::new(value) } }
+}
+
+impl ComposeChild for Provider {
+  type Child = GenWidget;
+
+  fn compose_child(
+    this: impl StateWriter<Value = Self>, mut child: Self::Child,
+  ) -> impl WidgetBuilder {
+    move |ctx: &BuildCtx| {
+      let value = this.read().value.clone();
+      let mut providers = vec![value.clone()];
+      providers.extend(ctx.providers().iter().cloned());
+
+      // The child is built with a context from the provider widget, so it can
+      // find the provided value before it's mounted in the tree.
+      let p = Void
+        .build(ctx)
+        .attach_data(Queryable(ProvidedValue(value)), ctx);
+      let ctx = BuildCtx::new_with_data(Some(p.id()), ctx.tree, ctx.themes().clone(), providers);
+      let child = child.gen_widget(&ctx);
+      ctx.append_child(p.id(), child);
+
+      p
+    }
+  }
+}
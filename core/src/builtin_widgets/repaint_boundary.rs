@@ -0,0 +1,133 @@
+use std::cell::RefCell;
+
+use crate::prelude::*;
+
+/// A widget caches the painted output of its subtree, and only repaints the
+/// subtree when any widget of it is marked dirty. Otherwise, the cache is
+/// composited directly, so a change of the transform or opacity of its
+/// ancestors will not repaint the subtree.
+///
+/// It's useful to wrap a mostly static subtree that is expensive to paint, such
+/// as a row of a large scrolling list.
+///
+/// The output is cached in the box of the boundary, the part of the subtree
+/// painted out of the box is clipped.
+#[derive(SingleChild, Declare, Default)]
+pub struct RepaintBoundary {
+  #[declare(skip)]
+  cache: RefCell<Option<Resource<Box<[PaintCommand]>>>>,
+}
+
+impl Render for RepaintBoundary {
+  fn perform_layout(&self, clamp: BoxClamp, ctx: &mut LayoutCtx) -> Size {
+    // The subtree may be laid out differently, so the cache is outdated.
+    self.cache.borrow_mut().take();
+    ctx.assert_perform_single_child_layout(clamp)
+  }
+
+  fn paint(&self, ctx: &mut PaintingCtx) {
+    let Some(size) = ctx.box_size().filter(|s| !s.is_empty()) else {
+      return;
+    };
+    let wnd = ctx.window();
+    let mut cache = self.cache.borrow_mut();
+    if wnd
+      .widget_tree
+      .borrow()
+      .is_subtree_dirty(ctx.id())
+    {
+      cache.take();
+    }
+
+    let cmds = cache.get_or_insert_with(|| {
+      let mut painter = Painter::new(Rect::from_size(size));
+      if let Some(child) = ctx.single_child() {
+        let mut ctx = PaintingCtx::new(child, wnd.id(), &mut painter);
+        child.paint_subtree(&mut ctx);
+      }
+      let cmds = painter.finish().to_owned().into_boxed_slice();
+      Resource::new(cmds)
+    });
+    if !cmds.is_empty() {
+      ctx
+        .painter()
+        .draw_bundle_commands(Rect::from_size(size), cmds.clone());
+    }
+  }
+
+  #[inline]
+  fn paints_descendants(&self) -> bool { true }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{cell::Cell, rc::Rc};
+
+  use super::*;
+  use crate::{reset_test_env, test_helper::*};
+
+  struct PaintCounter {
+    size: Size,
+    cnt: Rc<Cell<usize>>,
+  }
+
+  impl Render for PaintCounter {
+    fn perform_layout(&self, clamp: BoxClamp, _: &mut LayoutCtx) -> Size { clamp.clamp(self.size) }
+
+    fn paint(&self, ctx: &mut PaintingCtx) {
+      self.cnt.set(self.cnt.get() + 1);
+      ctx
+        .painter()
+        .rect(&Rect::from_size(self.size))
+        .fill();
+    }
+  }
+
+  fn bundle_transform(wnd: &mut TestWindow) -> Option<Transform> {
+    let Frame { commands, .. } = wnd.take_last_frame()?;
+    commands.iter().find_map(|cmd| match cmd {
+      PaintCommand::Bundle { transform, .. } => Some(*transform),
+      _ => None,
+    })
+  }
+
+  #[test]
+  fn keep_cache_when_ancestor_transform_changed() {
+    reset_test_env!();
+
+    let cnt = Rc::new(Cell::new(0));
+    let counter = Stateful::new(PaintCounter { size: Size::new(50., 50.), cnt: cnt.clone() });
+    let counter_writer = counter.clone_writer();
+    let (ts, ts_writer) = split_value(Transform::identity());
+    let w = fn_widget! {
+      @MockBox {
+        size: Size::new(100., 100.),
+        transform: pipe!(*$ts),
+        @RepaintBoundary { @ { counter } }
+      }
+    };
+    let mut wnd = TestWindow::new(w);
+    wnd.draw_frame();
+    assert_eq!(cnt.get(), 1);
+    assert_eq!(bundle_transform(&mut wnd), Some(Transform::identity()));
+
+    // Only the transform of the ancestor changed, the cache is composited again.
+    *ts_writer.write() = Transform::translation(10., 20.);
+    assert!(wnd.draw_frame());
+    assert_eq!(cnt.get(), 1);
+    assert_eq!(bundle_transform(&mut wnd), Some(Transform::translation(10., 20.)));
+
+    *ts_writer.write() = Transform::scale(2., 2.);
+    assert!(wnd.draw_frame());
+    assert_eq!(cnt.get(), 1);
+
+    // The subtree changed, repaint it.
+    counter_writer.write().size = Size::new(60., 60.);
+    wnd.draw_frame();
+    assert_eq!(cnt.get(), 2);
+
+    // Nothing changed, not draw.
+    wnd.draw_frame();
+    assert_eq!(cnt.get(), 2);
+  }
+}
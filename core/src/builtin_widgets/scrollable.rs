@@ -15,12 +15,41 @@ pub enum Scrollable {
 }
 
 /// Helper struct for builtin scrollable field.
-#[derive(Default)]
+///
+/// The `scroll_pos` is the position of the content relative to the view, so
+/// it's always in the range of `[view - content, 0]`. When the content is
+/// smaller than the view, it's pinned to zero.
+///
+/// Besides the wheel, the content can be dragged by the primary button of the
+/// pointer if `drag_to_scroll` is enabled, unless a descendant prevents the
+/// default action of the pointer down event, like the text selection of
+/// `Input`.
 pub struct ScrollableWidget {
   pub scrollable: Scrollable,
   pub scroll_pos: Point,
+  /// The distance in pixels to scroll for a line of the mouse wheel.
+  pub line_height: f32,
+  /// Whether the content can be dragged by the primary button to scroll, it's
+  /// disabled by default.
+  pub drag_to_scroll: bool,
   page: Size,
   content_size: Size,
+  /// The last position of the pointer when dragging the content.
+  drag_from: Option<Point>,
+}
+
+impl Default for ScrollableWidget {
+  fn default() -> Self {
+    Self {
+      scrollable: <_>::default(),
+      scroll_pos: Point::zero(),
+      line_height: PIXELS_PER_EM,
+      drag_to_scroll: false,
+      page: Size::zero(),
+      content_size: Size::zero(),
+      drag_from: None,
+    }
+  }
 }
 
 impl Declare for ScrollableWidget {
@@ -55,7 +84,29 @@ impl ComposeChild for ScrollableWidget {
 
       @Clip {
         @ $view {
-          on_wheel: move |e| $this.write().validate_scroll(Point::new(e.delta_x, e.delta_y)),
+          on_wheel: move |e| {
+            let delta = e.delta.to_pixels($this.line_height);
+            ScrollableWidget::scroll_by(&mut $this.write(), delta);
+          },
+          on_pointer_down: move |e| {
+            if $this.drag_to_scroll
+              && !e.is_prevent_default()
+              && e.mouse_buttons() == MouseButtons::PRIMARY
+            {
+              $this.silent().drag_from = Some(e.global_pos());
+            }
+          },
+          on_pointer_move: move |e| {
+            let Some(from) = $this.drag_from else { return };
+            if e.mouse_buttons().contains(MouseButtons::PRIMARY) {
+              let pos = e.global_pos();
+              $this.silent().drag_from = Some(pos);
+              ScrollableWidget::scroll_by(&mut $this.write(), pos - from);
+            } else {
+              $this.silent().drag_from = None;
+            }
+          },
+          on_pointer_up: move |_| $this.silent().drag_from = None,
           @ { child }
         }
       }
@@ -87,7 +138,8 @@ impl ScrollableWidget {
     }
   }
 
-  fn validate_scroll(&mut self, delta: Point) {
+  /// Move the content by `delta` in the scrollable direction.
+  pub fn scroll_by(&mut self, delta: Vector) {
     let mut new = self.scroll_pos;
     if self.scrollable != Scrollable::X {
       new.y += delta.y;
@@ -98,6 +150,29 @@ impl ScrollableWidget {
     self.jump_to(new);
   }
 
+  /// Scroll the least distance to bring the `rect` into the view, the `rect`
+  /// is relative to the content. If the `rect` is larger than the view, its
+  /// left-top is aligned to the view.
+  pub fn ensure_visible(&mut self, rect: Rect) {
+    let fit = |pos: f32, min: f32, max: f32, page: f32| {
+      if -pos > min || max - min > page {
+        -min
+      } else if page - pos < max {
+        page - max
+      } else {
+        pos
+      }
+    };
+    let Point { mut x, mut y, .. } = self.scroll_pos;
+    if self.scrollable != Scrollable::Y {
+      x = fit(x, rect.min_x(), rect.max_x(), self.page.width);
+    }
+    if self.scrollable != Scrollable::X {
+      y = fit(y, rect.min_y(), rect.max_y(), self.page.height);
+    }
+    self.jump_to(Point::new(x, y));
+  }
+
   pub fn set_content_size(&mut self, content_size: Size) {
     self.content_size = content_size;
     self.sync_pos()
@@ -117,10 +192,15 @@ impl ScrollableWidget {
 
 #[cfg(test)]
 mod tests {
-  use winit::event::{DeviceId, MouseScrollDelta, TouchPhase, WindowEvent};
+  use winit::event::{
+    DeviceId, ElementState, MouseButton, MouseScrollDelta, TouchPhase, WindowEvent,
+  };
 
   use super::*;
-  use crate::test_helper::{MockBox, TestWindow};
+  use crate::{
+    reset_test_env,
+    test_helper::{MockBox, TestWindow},
+  };
 
   fn test_assert(scrollable: Scrollable, delta_x: f32, delta_y: f32, expect_x: f32, expect_y: f32) {
     let w = fn_widget! {
@@ -176,6 +256,164 @@ mod tests {
     test_assert(Scrollable::Both, 100., 100., 0., 0.);
   }
 
+  #[test]
+  fn line_delta_by_line_height() {
+    reset_test_env!();
+
+    let scrolling = Stateful::new(ScrollableWidget {
+      scrollable: Scrollable::Y,
+      line_height: 10.,
+      ..<_>::default()
+    });
+    let c_scrolling = scrolling.clone_writer();
+    let w = fn_widget! {
+      @ $c_scrolling { @MockBox { size: Size::new(100., 1000.) } }
+    };
+    let mut wnd = TestWindow::new_with_size(w, Size::new(100., 100.));
+    wnd.draw_frame();
+
+    let device_id = unsafe { DeviceId::dummy() };
+    #[allow(deprecated)]
+    wnd.processes_native_event(WindowEvent::MouseWheel {
+      device_id,
+      delta: MouseScrollDelta::LineDelta(0., -2.),
+      phase: TouchPhase::Moved,
+    });
+    wnd.draw_frame();
+    assert_eq!(scrolling.read().scroll_pos, Point::new(0., -20.));
+
+    #[allow(deprecated)]
+    wnd.processes_native_event(WindowEvent::MouseWheel {
+      device_id,
+      delta: MouseScrollDelta::PixelDelta((0., -5.).into()),
+      phase: TouchPhase::Moved,
+    });
+    wnd.draw_frame();
+    assert_eq!(scrolling.read().scroll_pos, Point::new(0., -25.));
+  }
+
+  fn scrollable_widget(scrollable: Scrollable, page: Size, content: Size) -> ScrollableWidget {
+    let mut w = ScrollableWidget { scrollable, ..Default::default() };
+    w.set_page(page);
+    w.set_content_size(content);
+    w
+  }
+
+  #[test]
+  fn scroll_clamp() {
+    let mut w = scrollable_widget(Scrollable::Both, Size::new(100., 100.), Size::new(300., 200.));
+    w.scroll_by(Vector::new(-50., -50.));
+    assert_eq!(w.scroll_pos, Point::new(-50., -50.));
+    w.scroll_by(Vector::new(-1000., -1000.));
+    assert_eq!(w.scroll_pos, Point::new(-200., -100.));
+    w.scroll_by(Vector::new(1000., 1000.));
+    assert_eq!(w.scroll_pos, Point::zero());
+
+    // Only scroll in the scrollable direction.
+    w.scrollable = Scrollable::Y;
+    w.scroll_by(Vector::new(-10., -10.));
+    assert_eq!(w.scroll_pos, Point::new(0., -10.));
+
+    // The content shrinks, the position follows.
+    w.set_content_size(Size::new(100., 105.));
+    assert_eq!(w.scroll_pos, Point::new(0., -5.));
+  }
+
+  #[test]
+  fn small_content_pinned() {
+    let mut w = scrollable_widget(Scrollable::Both, Size::new(100., 100.), Size::new(50., 50.));
+    assert!(!w.can_scroll());
+    w.scroll_by(Vector::new(-10., -10.));
+    assert_eq!(w.scroll_pos, Point::zero());
+    w.jump_to(Point::new(20., -20.));
+    assert_eq!(w.scroll_pos, Point::zero());
+    w.ensure_visible(Rect::new(Point::new(40., 40.), Size::new(10., 10.)));
+    assert_eq!(w.scroll_pos, Point::zero());
+  }
+
+  #[test]
+  fn ensure_visible() {
+    let mut w = scrollable_widget(Scrollable::Both, Size::new(100., 100.), Size::new(1000., 1000.));
+
+    // Already visible, nothing changed.
+    w.ensure_visible(Rect::new(Point::new(10., 10.), Size::new(20., 20.)));
+    assert_eq!(w.scroll_pos, Point::zero());
+
+    // Below and right of the view, align its right-bottom to the view.
+    w.ensure_visible(Rect::new(Point::new(200., 150.), Size::new(20., 30.)));
+    assert_eq!(w.scroll_pos, Point::new(-120., -80.));
+
+    // Above and left of the view, align its left-top to the view.
+    w.ensure_visible(Rect::new(Point::new(50., 60.), Size::new(20., 30.)));
+    assert_eq!(w.scroll_pos, Point::new(-50., -60.));
+
+    // Partially visible, scroll the least distance.
+    w.ensure_visible(Rect::new(Point::new(140., 60.), Size::new(20., 20.)));
+    assert_eq!(w.scroll_pos, Point::new(-60., -60.));
+
+    // Larger than the view, align its left-top.
+    w.ensure_visible(Rect::new(Point::new(300., 400.), Size::new(200., 200.)));
+    assert_eq!(w.scroll_pos, Point::new(-300., -400.));
+
+    // Out of the content, clamped.
+    w.ensure_visible(Rect::new(Point::new(990., 990.), Size::new(20., 20.)));
+    assert_eq!(w.scroll_pos, Point::new(-900., -900.));
+
+    // Only scroll in the scrollable direction.
+    w.scrollable = Scrollable::X;
+    w.ensure_visible(Rect::new(Point::new(0., 0.), Size::new(10., 10.)));
+    assert_eq!(w.scroll_pos, Point::new(0., -900.));
+  }
+
+  fn drag_content(drag_to_scroll: bool) -> Point {
+    let w = fn_widget! {
+      let mut content = @MockBox {
+        size: Size::new(1000., 1000.),
+        scrollable: Scrollable::Both,
+      };
+      content
+        .get_scrollable_widget()
+        .write()
+        .drag_to_scroll = drag_to_scroll;
+      content
+    };
+    let mut wnd = TestWindow::new_with_size(w, Size::new(100., 100.));
+    wnd.draw_frame();
+
+    let device_id = unsafe { DeviceId::dummy() };
+    #[allow(deprecated)]
+    let move_to = |wnd: &TestWindow, x: f32, y: f32| {
+      wnd.processes_native_event(WindowEvent::CursorMoved { device_id, position: (x, y).into() });
+      wnd.run_frame_tasks();
+    };
+    let press = |wnd: &TestWindow, state| {
+      wnd.process_mouse_input(device_id, state, MouseButton::Left);
+      wnd.run_frame_tasks();
+    };
+    move_to(&wnd, 50., 50.);
+    press(&wnd, ElementState::Pressed);
+    move_to(&wnd, 30., 40.);
+    move_to(&wnd, 20., 20.);
+    press(&wnd, ElementState::Released);
+    // Not dragging after the release.
+    move_to(&wnd, 10., 10.);
+    wnd.draw_frame();
+
+    wnd
+      .layout_info_by_path(&[0, 0, 0, 0])
+      .unwrap()
+      .pos
+  }
+
+  #[test]
+  fn drag_to_scroll() {
+    reset_test_env!();
+
+    assert_eq!(drag_content(true), Point::new(-30., -30.));
+    // Dragging is disabled by default.
+    assert_eq!(drag_content(false), Point::zero());
+  }
+
   #[derive(SingleChild, Declare, Clone)]
   pub struct FixedBox {
     pub size: Size,
@@ -40,6 +40,8 @@ pub struct FullTheme {
   pub transitions_theme: TransitionTheme,
   pub compose_decorators: ComposeDecorators,
   pub custom_styles: CustomStyles,
+  /// The direction to place the children of the horizontal layouts.
+  pub text_direction: TextDirection,
   pub font_bytes: Option<Vec<Vec<u8>>>,
   pub font_files: Option<Vec<String>>,
 }
@@ -57,6 +59,7 @@ pub struct InheritTheme {
   pub transitions_theme: Option<TransitionTheme>,
   pub compose_decorators: Option<ComposeDecorators>,
   pub custom_styles: Option<CustomStyles>,
+  pub text_direction: Option<TextDirection>,
   pub font_bytes: Option<Vec<Vec<u8>>>,
   pub font_files: Option<Vec<String>>,
 }
@@ -66,6 +69,12 @@ pub enum Theme {
   Inherit(InheritTheme),
 }
 
+/// A widget that provides the `theme` to its descendants.
+///
+/// If the `theme` has a palette, it's provided as a state, see
+/// [`Palette::state_of`], write it to switch the palette at runtime, e.g.
+/// toggle the brightness, the styles watching it follow without rebuilding
+/// the descendants.
 #[derive(Declare)]
 pub struct ThemeWidget {
   pub theme: Sc<Theme>,
@@ -84,6 +93,11 @@ impl ComposeChild for ThemeWidget {
 
       let mut themes = ctx!().themes().clone();
       themes.push(theme.clone());
+      let palette = match &*theme {
+        Theme::Full(f) => Some(&f.palette),
+        Theme::Inherit(i) => i.palette.as_ref(),
+      }
+      .map(|palette| Stateful::new((**palette).clone()));
 
       // Keep the empty node, because the subtree may be hold its id.
       //
@@ -91,9 +105,13 @@ impl ComposeChild for ThemeWidget {
       // node, because the subtree may be hold its id.
       //
       // A `Void` is cheap for a theme.
-      let p = Void.build(ctx!()).attach_data(Queryable(theme), ctx!());
+      let mut p = Void.build(ctx!()).attach_data(Queryable(theme), ctx!());
+      if let Some(palette) = palette {
+        p = p.attach_data(Queryable(palette), ctx!());
+      }
       // shadow the context with the theme.
-      let ctx = BuildCtx::new_with_data(Some(p.id()), ctx!().tree, themes);
+      let providers = ctx!().providers().clone();
+      let ctx = BuildCtx::new_with_data(Some(p.id()), ctx!().tree, themes, providers);
       let child = child.gen_widget(&ctx);
       ctx.append_child(p.id(), child);
 
@@ -163,6 +181,7 @@ impl Default for FullTheme {
       transitions_theme: Default::default(),
       compose_decorators: Default::default(),
       custom_styles: Default::default(),
+      text_direction: TextDirection::LeftToRight,
       font_bytes: None,
       font_files: None,
     }
@@ -186,6 +205,7 @@ fn typography_theme(
         font_size: FontSize::Pixel(57.0.into()),
         letter_space: Some(0.0.into()),
         font_face: regular_face.clone(),
+        fallback_fonts: vec![],
       }),
       decoration: decoration.clone(),
     },
@@ -195,6 +215,7 @@ fn typography_theme(
         font_size: FontSize::Pixel(45.0.into()),
         letter_space: Some(0.0.into()),
         font_face: regular_face.clone(),
+        fallback_fonts: vec![],
       }),
       decoration: decoration.clone(),
     },
@@ -204,6 +225,7 @@ fn typography_theme(
         font_size: FontSize::Pixel(36.0.into()),
         letter_space: Some(0.0.into()),
         font_face: regular_face.clone(),
+        fallback_fonts: vec![],
       }),
       decoration: decoration.clone(),
     },
@@ -213,6 +235,7 @@ fn typography_theme(
         font_size: FontSize::Pixel(32.0.into()),
         letter_space: Some(0.0.into()),
         font_face: regular_face.clone(),
+        fallback_fonts: vec![],
       }),
       decoration: decoration.clone(),
     },
@@ -222,6 +245,7 @@ fn typography_theme(
         font_size: FontSize::Pixel(28.0.into()),
         letter_space: Some(0.0.into()),
         font_face: regular_face.clone(),
+        fallback_fonts: vec![],
       }),
       decoration: decoration.clone(),
     },
@@ -231,6 +255,7 @@ fn typography_theme(
         font_size: FontSize::Pixel(24.0.into()),
         letter_space: Some(0.0.into()),
         font_face: regular_face.clone(),
+        fallback_fonts: vec![],
       }),
       decoration: decoration.clone(),
     },
@@ -240,6 +265,7 @@ fn typography_theme(
         font_size: FontSize::Pixel(22.0.into()),
         letter_space: Some(0.0.into()),
         font_face: medium_face.clone(),
+        fallback_fonts: vec![],
       }),
       decoration: decoration.clone(),
     },
@@ -249,6 +275,7 @@ fn typography_theme(
         font_size: FontSize::Pixel(16.0.into()),
         letter_space: Some(0.15.into()),
         font_face: medium_face.clone(),
+        fallback_fonts: vec![],
       }),
       decoration: decoration.clone(),
     },
@@ -258,6 +285,7 @@ fn typography_theme(
         font_size: FontSize::Pixel(14.0.into()),
         letter_space: Some(0.1.into()),
         font_face: medium_face.clone(),
+        fallback_fonts: vec![],
       }),
       decoration: decoration.clone(),
     },
@@ -267,6 +295,7 @@ fn typography_theme(
         font_size: FontSize::Pixel(14.0.into()),
         letter_space: Some(0.1.into()),
         font_face: medium_face.clone(),
+        fallback_fonts: vec![],
       }),
       decoration: decoration.clone(),
     },
@@ -276,6 +305,7 @@ fn typography_theme(
         font_size: FontSize::Pixel(12.0.into()),
         letter_space: Some(0.5.into()),
         font_face: medium_face.clone(),
+        fallback_fonts: vec![],
       }),
       decoration: decoration.clone(),
     },
@@ -285,6 +315,7 @@ fn typography_theme(
         font_size: FontSize::Pixel(11.0.into()),
         letter_space: Some(0.5.into()),
         font_face: medium_face,
+        fallback_fonts: vec![],
       }),
       decoration: decoration.clone(),
     },
@@ -294,6 +325,7 @@ fn typography_theme(
         font_size: FontSize::Pixel(16.0.into()),
         letter_space: Some(0.5.into()),
         font_face: regular_face.clone(),
+        fallback_fonts: vec![],
       }),
       decoration: decoration.clone(),
     },
@@ -303,6 +335,7 @@ fn typography_theme(
         font_size: FontSize::Pixel(14.0.into()),
         letter_space: Some(0.25.into()),
         font_face: regular_face.clone(),
+        fallback_fonts: vec![],
       }),
       decoration: decoration.clone(),
     },
@@ -312,6 +345,7 @@ fn typography_theme(
         font_size: FontSize::Pixel(12.0.into()),
         letter_space: Some(0.4.into()),
         font_face: regular_face,
+        fallback_fonts: vec![],
       }),
       decoration,
     },
@@ -327,3 +361,65 @@ impl From<InheritTheme> for Theme {
   #[inline]
   fn from(value: InheritTheme) -> Self { Theme::Inherit(value) }
 }
+
+#[cfg(test)]
+mod tests {
+  use std::cell::RefCell;
+
+  use super::*;
+  use crate::{reset_test_env, test_helper::*};
+
+  #[test]
+  fn switch_brightness() {
+    reset_test_env!();
+
+    let palette = Palette { primary: Color::RED, ..Default::default() };
+    let theme = Sc::new(Theme::Inherit(InheritTheme {
+      palette: Some(Rc::new(palette)),
+      ..<_>::default()
+    }));
+    let state = Rc::new(RefCell::new(None));
+    let c_state = state.clone();
+    let colors = Stateful::new(vec![]);
+    let c_colors = colors.clone_writer();
+    let w = fn_widget! {
+      @ThemeWidget {
+        theme: theme.clone(),
+        @ {
+          Box::new(fn_widget! {
+            let palette = Palette::state_of(ctx!());
+            *c_state.borrow_mut() = Some((palette.clone_writer(), ctx!().handle()));
+            $c_colors.write().push(($palette.on_surface(), $palette.primary()));
+            let u = watch!(($palette.on_surface(), $palette.primary()))
+              .subscribe(move |colors| $c_colors.write().push(colors));
+            @Void { on_disposed: move |_| u.unsubscribe() }
+          })
+        }
+      }
+    };
+
+    let mut wnd = TestWindow::new(w);
+    wnd.draw_frame();
+
+    let light = Palette { primary: Color::RED, ..Default::default() };
+    let dark = Palette { brightness: Brightness::Dark, ..light.clone() };
+    assert_eq!(*colors.read(), [(light.on_surface(), light.primary())]);
+
+    // Only toggle the brightness, the overridden colors are kept.
+    let (palette, handle) = state.borrow_mut().take().unwrap();
+    palette.write().brightness = Brightness::Dark;
+    wnd.draw_frame();
+
+    assert_ne!(light.on_surface(), dark.on_surface());
+    // The watcher follows the switch, and the subtree is not rebuilt.
+    assert_eq!(
+      *colors.read(),
+      [(light.on_surface(), light.primary()), (dark.on_surface(), dark.primary())]
+    );
+    assert!(state.borrow().is_none());
+
+    // The widgets built later read the switched palette.
+    let brightness = handle.with_ctx(|ctx| Palette::of(ctx).brightness);
+    assert_eq!(brightness, Some(Brightness::Dark));
+  }
+}
@@ -1,10 +1,18 @@
 use std::{
   any::{Any, TypeId},
+  cell::RefCell,
   collections::HashMap,
+  rc::Rc,
 };
 
-use super::Theme;
-use crate::context::BuildCtx;
+use rxrust::ops::box_it::BoxIt;
+
+use super::{Palette, Theme};
+use crate::{
+  context::BuildCtx,
+  pipe::{MapPipe, ModifiesPipe},
+  prelude::{ModifyScope, ObservableExt, StateWatcher},
+};
 
 /// A map can store any type of config, useful for widget which the common
 /// information of theme mod not enough and need have itself theme.
@@ -13,6 +21,8 @@ pub struct CustomStyles {
   themes: HashMap<TypeId, Box<dyn Any>, ahash::RandomState>,
 }
 
+type PaletteStyle<T> = Box<dyn Fn(&Palette) -> T>;
+
 /// macro use to batch set custom theme.
 #[macro_export]
 macro_rules! fill_custom_style {
@@ -35,9 +45,37 @@ pub trait CustomStyle: Sized + Clone + 'static {
         .and_then(|c| c.themes.get(&tid)),
     });
 
-    c.and_then(|c| c.downcast_ref::<Self>())
-      .cloned()
-      .unwrap_or_else(|| Self::default_style(ctx))
+    c.and_then(|c| {
+      c.downcast_ref::<Self>().cloned().or_else(|| {
+        c.downcast_ref::<PaletteStyle<Self>>()
+          .map(|f| f(Palette::of(ctx)))
+      })
+    })
+    .unwrap_or_else(|| Self::default_style(ctx))
+  }
+
+  /// Return a pipe of the part of the style picked by `f`, it follows the
+  /// switch of the palette, see [`Palette::state_of`]. So the widget can
+  /// update its colors without rebuilding.
+  fn pipe_of<U: PartialEq + Clone + 'static>(
+    ctx: &BuildCtx, f: impl Fn(&Self) -> U + 'static,
+  ) -> MapPipe<U, ModifiesPipe, Box<dyn FnMut(ModifyScope) -> U>> {
+    let last = Rc::new(RefCell::new(f(&Self::of(ctx))));
+    let c_last = last.clone();
+    let handle = ctx.handle();
+    let modifies = Palette::state_of(ctx)
+      .framework_modifies()
+      .filter(move |_| {
+        let Some(v) = handle.with_ctx(|ctx| f(&Self::of(ctx))) else { return false };
+        let mut last = c_last.borrow_mut();
+        let changed = *last != v;
+        if changed {
+          *last = v;
+        }
+        changed
+      })
+      .box_it();
+    MapPipe::new(ModifiesPipe::new(modifies), Box::new(move |_| last.borrow().clone()))
   }
 }
 
@@ -46,4 +84,13 @@ impl CustomStyles {
   pub fn set_custom_style<T: CustomStyle + 'static>(&mut self, v: T) {
     self.themes.insert(v.type_id(), Box::new(v));
   }
+
+  /// Set a style derived from the palette, it's derived from the current
+  /// palette every time it's read, so it follows the switch of the palette.
+  pub fn set_palette_style<T: CustomStyle + 'static>(
+    &mut self, f: impl Fn(&Palette) -> T + 'static,
+  ) {
+    let f: PaletteStyle<T> = Box::new(f);
+    self.themes.insert(TypeId::of::<T>(), Box::new(f));
+  }
 }
@@ -1,7 +1,7 @@
 use ribir_painter::{Color, LightnessTone};
 
-use super::{Brightness, Theme};
-use crate::prelude::BuildCtx;
+use super::Brightness;
+use crate::prelude::{BuildCtx, Writer};
 
 /// The palette enables you to modify the color of your application to suit
 /// your brand. `Palette` provide colors base on the 8 key colors with different
@@ -100,14 +100,15 @@ pub struct LightnessCfg {
 
 impl Palette {
   #[inline]
-  pub fn of<'a>(ctx: &'a BuildCtx) -> &'a Self {
-    ctx
-      .find_cfg(|t| match t {
-        Theme::Full(f) => Some(&f.palette),
-        Theme::Inherit(i) => i.palette.as_ref(),
-      })
-      .unwrap()
-  }
+  pub fn of<'a>(ctx: &'a BuildCtx) -> &'a Self { ctx.current_palette() }
+
+  /// Return the palette state of the nearest `ThemeWidget` that has a palette,
+  /// or the palette state of the application theme, see
+  /// [`AppCtx::app_palette`](crate::prelude::AppCtx::app_palette).
+  ///
+  /// Write the state to switch the palette at runtime, e.g. toggle the
+  /// brightness, and watch it to follow the switch without rebuilding.
+  pub fn state_of(ctx: &BuildCtx) -> Writer<Palette> { ctx.palette_state() }
 
   #[inline]
   pub fn primary(&self) -> Color { self.base_of(&self.primary) }
@@ -1,8 +1,17 @@
 use crate::prelude::*;
 
-#[derive(Default)]
+/// A widget to show or hide its child. A hidden child is not painted, can't
+/// be hit and can't be focused.
 pub struct Visibility {
   pub visible: bool,
+  /// Whether the hidden child still takes its space in the layout, like the
+  /// `visibility: hidden` of CSS. Otherwise, the hidden child is laid out as
+  /// a zero size.
+  pub maintain_space: bool,
+}
+
+impl Default for Visibility {
+  fn default() -> Self { Self::new(true) }
 }
 
 impl Declare for Visibility {
@@ -20,6 +29,7 @@ impl ComposeChild for Visibility {
         can_focus: pipe!($this.get_visible()),
         @VisibilityRender {
           display: pipe!($this.get_visible()),
+          keep_space: pipe!($this.get_maintain_space()),
           @ { child }
         }
       }
@@ -30,12 +40,17 @@ impl ComposeChild for Visibility {
 #[derive(SingleChild, Declare, Clone)]
 struct VisibilityRender {
   display: bool,
+  keep_space: bool,
 }
 
 impl Render for VisibilityRender {
   #[inline]
   fn perform_layout(&self, clamp: BoxClamp, ctx: &mut LayoutCtx) -> Size {
-    if self.display { ctx.assert_perform_single_child_layout(clamp) } else { ZERO_SIZE }
+    if self.display || self.keep_space {
+      ctx.assert_perform_single_child_layout(clamp)
+    } else {
+      ZERO_SIZE
+    }
   }
 
   #[inline]
@@ -48,12 +63,107 @@ impl Render for VisibilityRender {
   fn hit_test(&self, _: &HitTestCtx, _: Point) -> HitTest {
     HitTest { hit: false, can_hit_child: self.display }
   }
+
+  fn accessibility(&self) -> Option<Accessibility> {
+    (!self.display).then(|| Accessibility { a11y_hidden: true, ..Default::default() })
+  }
 }
 
 impl Visibility {
   #[inline]
-  pub fn new(visible: bool) -> Self { Self { visible } }
+  pub fn new(visible: bool) -> Self { Self { visible, maintain_space: false } }
 
   #[inline]
   fn get_visible(&self) -> bool { self.visible }
+
+  #[inline]
+  fn get_maintain_space(&self) -> bool { self.maintain_space }
+}
+
+#[cfg(test)]
+mod tests {
+  use ribir_dev_helper::*;
+
+  use super::*;
+  use crate::{reset_test_env, test_helper::*};
+
+  fn hidden_with_space() -> impl WidgetBuilder {
+    fn_widget! {
+      @MockMulti {
+        @MockBox {
+          size: Size::new(100., 100.),
+          visible: false,
+          maintain_space: true,
+        }
+        @MockBox { size: Size::new(50., 50.) }
+      }
+    }
+  }
+  widget_layout_test!(
+    hidden_with_space,
+    { path = [0], width == 150., height == 100., }
+    { path = [0, 0], width == 100., height == 100., }
+  );
+
+  fn hidden_without_space() -> impl WidgetBuilder {
+    fn_widget! {
+      @MockMulti {
+        @MockBox {
+          size: Size::new(100., 100.),
+          visible: false,
+        }
+        @MockBox { size: Size::new(50., 50.) }
+      }
+    }
+  }
+  widget_layout_test!(
+    hidden_without_space,
+    { path = [0], width == 50., height == 50., }
+    { path = [0, 0], width == 0., height == 0., }
+  );
+
+  #[test]
+  fn hidden_with_space_not_focusable() {
+    reset_test_env!();
+
+    let visible = Stateful::new(true);
+    let c_visible = visible.clone_writer();
+    let w = fn_widget! {
+      @MockBox {
+        size: Size::new(100., 100.),
+        tab_index: 0i16,
+        visible: pipe!(*$visible),
+        maintain_space: true,
+      }
+    };
+    let mut wnd = TestWindow::new(w);
+    wnd.draw_frame();
+    wnd.request_next_focus();
+    wnd.run_frame_tasks();
+    assert!(wnd.focusing().is_some());
+
+    *c_visible.write() = false;
+    wnd.draw_frame();
+    wnd.request_next_focus();
+    wnd.run_frame_tasks();
+    assert_eq!(wnd.focusing(), None);
+  }
+
+  #[test]
+  fn only_maintain_space_visible() {
+    reset_test_env!();
+
+    let w = fn_widget! {
+      @MockBox {
+        size: Size::new(100., 100.),
+        tab_index: 0i16,
+        maintain_space: true,
+      }
+    };
+    let mut wnd = TestWindow::new(w);
+    wnd.draw_frame();
+    wnd.request_next_focus();
+    wnd.run_frame_tasks();
+    assert!(wnd.focusing().is_some());
+  }
 }
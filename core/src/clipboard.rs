@@ -3,6 +3,11 @@ use std::{borrow::Cow, io::Error};
 use log::warn;
 use ribir_painter::PixelImage;
 
+/// The format of the plain text in the clipboard.
+pub const TEXT_FORMAT: &str = "text/plain";
+/// The format of the image in the clipboard, read it by `read_img`.
+pub const IMAGE_FORMAT: &str = "image/png";
+
 pub trait Clipboard {
   // read the string from the clipboard
   fn read_text(&mut self) -> Result<String, Error>;
@@ -10,18 +15,35 @@ pub trait Clipboard {
   // write the string to the clipboard
   fn write_text(&mut self, text: &str) -> Result<(), Error>;
 
-  // read the img_data from the clipboard
+  // read the img_data from the clipboard, an `Unsupported` error is returned if
+  // the platform not support image clipboard.
   fn read_img(&mut self) -> Result<PixelImage, Error>;
 
-  // write the img_data to the clipboard
+  // write the img_data to the clipboard, an `Unsupported` error is returned if
+  // the platform not support image clipboard.
   fn write_img(&mut self, img: &PixelImage) -> Result<(), Error>;
 
-  // read the custom format from the clipboard
+  // read the custom format, like `application/x-myapp+json`, from the
+  // clipboard.
   fn read(&mut self, format: &str) -> Result<Cow<[u8]>, Error>;
 
-  // write the custom format from the clipboard
+  // write the custom format to the clipboard. Unlike `write_text` and
+  // `write_img` that replace the whole content, the data is added beside the
+  // text or the image already in the clipboard, so write the text first to
+  // give the other applications a readable representation.
+  //
+  // The platform clipboard may not accept the custom formats, then the data is
+  // kept in the process, only this process can read it back, the other
+  // processes get the text or the image instead.
   fn write(&mut self, format: &str, data: &[u8]) -> Result<(), Error>;
 
+  // the formats of the content in the clipboard, `TEXT_FORMAT`, `IMAGE_FORMAT`
+  // and the custom formats, an `Unsupported` error is returned if the
+  // clipboard can't tell them.
+  fn available_formats(&mut self) -> Result<Vec<String>, Error> {
+    Err(Error::new(std::io::ErrorKind::Unsupported, "clipboard available_formats"))
+  }
+
   // clear all content in the clipboard
   fn clear(&mut self) -> Result<(), Error>;
 }
@@ -1,5 +1,6 @@
 use std::{
-  cell::RefCell,
+  any::{Any, TypeId},
+  cell::{OnceCell, RefCell},
   rc::Rc,
   sync::{Mutex, MutexGuard, Once},
   task::{Context, RawWaker, RawWakerVTable, Waker},
@@ -13,9 +14,12 @@ use ribir_text::{font_db::FontDB, shaper::TextShaper, TextReorder, TypographySto
 use rxrust::scheduler::NEW_TIMER_FN;
 
 use crate::{
-  builtin_widgets::{FullTheme, InheritTheme, Theme},
+  builtin_widgets::{FullTheme, InheritTheme, Palette, Theme},
   clipboard::{Clipboard, MockClipboard},
-  prelude::FuturesLocalScheduler,
+  prelude::{
+    FuturesLocalScheduler, SharedWriter, StateChangeTrace, StateWriter, Stateful, Writer,
+  },
+  state::StateTracer,
   timer::Timer,
   widget::WidgetBuilder,
   window::{ShellWindow, Window, WindowId},
@@ -36,6 +40,7 @@ pub trait RuntimeWaker {
 /// already hold by others.
 pub struct AppCtx {
   app_theme: Theme,
+  app_palette: OnceCell<Stateful<Palette>>,
   windows: RefCell<ahash::HashMap<WindowId, Rc<Window>>>,
   font_db: Rc<RefCell<FontDB>>,
   shaper: TextShaper,
@@ -45,6 +50,8 @@ pub struct AppCtx {
   runtime_waker: Box<dyn RuntimeWaker + Send>,
   scheduler: FuturesLocalScheduler,
   executor: RefCell<LocalPool>,
+  shared_states: RefCell<ahash::HashMap<TypeId, Box<dyn Any>>>,
+  state_tracer: RefCell<StateTracer>,
 
   #[cfg(feature = "tokio-async")]
   tokio_runtime: tokio::runtime::Runtime,
@@ -67,6 +74,22 @@ impl AppCtx {
   #[track_caller]
   pub fn app_theme() -> &'static Theme { &Self::shared().app_theme }
 
+  /// Get the palette state of the application theme, write it to switch the
+  /// palette of the application at runtime, e.g. toggle the brightness. The
+  /// widgets under a `ThemeWidget` with a palette follow its palette instead.
+  #[track_caller]
+  pub fn app_palette() -> Writer<Palette> {
+    Self::shared()
+      .app_palette
+      .get_or_init(|| {
+        let Theme::Full(theme) = Self::app_theme() else {
+          unreachable!("The application theme is always a full theme.");
+        };
+        Stateful::new((*theme.palette).clone())
+      })
+      .clone_writer()
+  }
+
   pub fn new_window(shell_wnd: Box<dyn ShellWindow>, content: impl WidgetBuilder) -> Rc<Window> {
     let wnd = Window::new(shell_wnd);
     let id = wnd.id();
@@ -135,6 +158,78 @@ impl AppCtx {
   #[track_caller]
   pub fn font_db() -> &'static Rc<RefCell<FontDB>> { &Self::shared().font_db }
 
+  /// Get the writer of the shared state of type `T`, which is created by
+  /// [`Stateful::shared`].
+  #[track_caller]
+  pub fn shared_state<T: 'static>() -> Option<SharedWriter<T>> {
+    Self::shared()
+      .shared_states
+      .borrow()
+      .get(&TypeId::of::<T>())
+      .and_then(|s| s.downcast_ref::<Stateful<T>>())
+      .map(|s| s.clone_writer())
+  }
+
+  #[track_caller]
+  pub(crate) fn insert_shared_state<T: 'static>(data: T) -> Result<SharedWriter<T>, T> {
+    let mut states = Self::shared().shared_states.borrow_mut();
+    match states.entry(TypeId::of::<T>()) {
+      std::collections::hash_map::Entry::Occupied(_) => Err(data),
+      std::collections::hash_map::Entry::Vacant(e) => {
+        let state = Stateful::new(data);
+        let writer = state.clone_writer();
+        e.insert(Box::new(state));
+        Ok(writer)
+      }
+    }
+  }
+
+  /// Enable or disable the trace of the state changes, it's disabled by
+  /// default.
+  ///
+  /// When enabled, every modification of a state is recorded as a
+  /// [`StateChangeTrace`] with the type of the modified value, the
+  /// `ModifyScope` it notifies, and the source location that modified it. The
+  /// records are logged by `log::debug!` with the target `ribir::state`, unless
+  /// a sink is set by [`AppCtx::set_state_trace_sink`].
+  ///
+  /// This helps to find out which writer fired and from where when debugging
+  /// an unexpected rebuild.
+  #[track_caller]
+  pub fn trace_state_changes(enable: bool) {
+    Self::shared().state_tracer.borrow_mut().enabled = enable;
+  }
+
+  /// Limit the traced state changes to at most `max_per_sec` records within a
+  /// second, so the high-frequency changes will not flood the log. The records
+  /// beyond the limit are dropped, and their count is logged when the next
+  /// second begins. `None` to remove the limit.
+  #[track_caller]
+  pub fn set_state_trace_limit(max_per_sec: Option<usize>) {
+    Self::shared().state_tracer.borrow_mut().limit = max_per_sec;
+  }
+
+  /// Set the sink to receive the state change records instead of the log.
+  #[track_caller]
+  pub fn set_state_trace_sink(sink: impl FnMut(&StateChangeTrace) + 'static) {
+    Self::shared().state_tracer.borrow_mut().sink = Some(Box::new(sink));
+  }
+
+  pub(crate) fn is_tracing_state_changes() -> bool {
+    Self::shared()
+      .state_tracer
+      .try_borrow()
+      .is_ok_and(|t| t.enabled)
+  }
+
+  pub(crate) fn trace_state_change(record: impl FnOnce() -> StateChangeTrace) {
+    // The modifications in the sink are not traced.
+    let Ok(mut tracer) = Self::shared().state_tracer.try_borrow_mut() else { return };
+    if tracer.enabled {
+      tracer.trace(record());
+    }
+  }
+
   /// Runs all tasks in the local(usually means on the main thread) pool and
   /// returns if no more progress can be made on any task.
   #[track_caller]
@@ -179,7 +274,9 @@ impl AppCtx {
   /// undefined if you call it in a running application.
   #[track_caller]
   pub unsafe fn set_app_theme(theme: FullTheme) {
-    Self::shared_mut().app_theme = Theme::Full(theme);
+    let ctx = Self::shared_mut();
+    ctx.app_theme = Theme::Full(theme);
+    ctx.app_palette = OnceCell::new();
     load_font_from_theme(Self::app_theme(), &mut Self::font_db().borrow_mut());
   }
 
@@ -264,6 +361,7 @@ impl AppCtx {
       let ctx = AppCtx {
         font_db,
         app_theme,
+        app_palette: OnceCell::new(),
         shaper,
         reorder,
         typography_store,
@@ -272,6 +370,8 @@ impl AppCtx {
         scheduler,
         runtime_waker: Box::new(MockWaker),
         windows: RefCell::new(ahash::HashMap::default()),
+        shared_states: RefCell::new(ahash::HashMap::default()),
+        state_tracer: <_>::default(),
 
         #[cfg(feature = "tokio-async")]
         tokio_runtime: tokio::runtime::Builder::new_multi_thread()
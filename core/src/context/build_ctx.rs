@@ -7,6 +7,7 @@ use ribir_algo::Sc;
 use widget_id::RenderQueryable;
 
 use crate::{
+  builtin_widgets::provider::ProvidedValue,
   prelude::*,
   widget::widget_id::new_node,
   window::{DelayEvent, WindowId},
@@ -15,6 +16,10 @@ use crate::{
 /// A context provide during build the widget tree.
 pub struct BuildCtx<'a> {
   pub(crate) themes: OnceCell<Vec<Sc<Theme>>>,
+  /// The values provided by the ancestors, the nearest first.
+  providers: OnceCell<Vec<Rc<dyn Any>>>,
+  /// The current value of the nearest palette state.
+  palette: OnceCell<Palette>,
   /// The widget which this `BuildCtx` is created from. It's not means this
   /// is the parent of the widget which is builded by this `BuildCtx`.
   ctx_from: Option<WidgetId>,
@@ -46,23 +51,64 @@ impl<'a> BuildCtx<'a> {
     BuildCtxHandle { wnd_id: self.window().id(), ctx_from: self.ctx_from }
   }
 
+  /// Return the text direction provided by the nearest theme, the horizontal
+  /// layouts place their children in this direction.
+  pub fn text_direction(&self) -> TextDirection {
+    self
+      .find_cfg(|t| match t {
+        Theme::Full(f) => Some(&f.text_direction),
+        Theme::Inherit(i) => i.text_direction.as_ref(),
+      })
+      .copied()
+      .unwrap_or_default()
+  }
+
+  /// Return the value of type `T` provided by the nearest [`Provider`] of this
+  /// context, or `None` if no ancestor provides it.
+  pub fn find_provider<T: Any>(&self) -> Option<&T> {
+    self
+      .providers()
+      .iter()
+      .find_map(|v| v.downcast_ref::<T>())
+  }
+
+  /// Return the value of type `T` provided by the nearest [`Provider`] of this
+  /// context, or the default value of `T` if no ancestor provides it.
+  pub fn provide_or_default<T: Default + Clone + Any>(&self) -> T {
+    self
+      .find_provider::<T>()
+      .cloned()
+      .unwrap_or_default()
+  }
+
   #[inline]
   pub(crate) fn new(from: Option<WidgetId>, tree: &'a RefCell<WidgetTree>) -> Self {
-    Self { themes: OnceCell::new(), ctx_from: from, tree }
+    Self {
+      themes: OnceCell::new(),
+      providers: OnceCell::new(),
+      palette: OnceCell::new(),
+      ctx_from: from,
+      tree,
+    }
   }
 
   pub(crate) fn new_with_data(
     from: Option<WidgetId>, tree: &'a RefCell<WidgetTree>, data: Vec<Sc<Theme>>,
+    providers: Vec<Rc<dyn Any>>,
   ) -> Self {
     let themes: OnceCell<Vec<Sc<Theme>>> = OnceCell::new();
+    let c_providers: OnceCell<Vec<Rc<dyn Any>>> = OnceCell::new();
     // Safety: we just create the `OnceCell` and it's empty.
-    unsafe { themes.set(data).unwrap_unchecked() };
+    unsafe {
+      themes.set(data).unwrap_unchecked();
+      c_providers.set(providers).unwrap_unchecked();
+    };
 
-    Self { themes, ctx_from: from, tree }
+    Self { themes, providers: c_providers, palette: OnceCell::new(), ctx_from: from, tree }
   }
 
   pub(crate) fn find_cfg<T>(&self, f: impl Fn(&Theme) -> Option<&T>) -> Option<&T> {
-    for t in self.themes().iter() {
+    for t in self.themes().iter().rev() {
       let v = f(t);
       if v.is_some() {
         return v;
@@ -132,9 +178,53 @@ impl<'a> BuildCtx<'a> {
 
         matches!(themes.last().map(Sc::deref), Some(Theme::Full(_)))
       });
+      // Keep the same order as `ThemeWidget` pushes, the nearest is the last.
+      themes.reverse();
       themes
     })
   }
+
+  /// The palette state attached by the nearest `ThemeWidget` with a palette,
+  /// or the palette state of the application theme.
+  pub(crate) fn palette_state(&self) -> Writer<Palette> {
+    let tree = self.tree.borrow();
+    let arena = &tree.arena;
+    let state = self.ctx_from.and_then(|p| {
+      p.ancestors(arena).find_map(|p| {
+        p.assert_get(arena)
+          .query_all_iter::<Stateful<Palette>>()
+          .next()
+          .map(|s| s.clone_writer())
+      })
+    });
+    state.unwrap_or_else(AppCtx::app_palette)
+  }
+
+  /// The current value of the palette state, it's read once and kept by this
+  /// context.
+  pub(crate) fn current_palette(&self) -> &Palette {
+    self
+      .palette
+      .get_or_init(|| self.palette_state().read().clone())
+  }
+
+  pub(crate) fn providers(&self) -> &Vec<Rc<dyn Any>> {
+    self.providers.get_or_init(|| {
+      let Some(p) = self.ctx_from else {
+        return vec![];
+      };
+
+      let arena = &self.tree.borrow().arena;
+      p.ancestors(arena)
+        .flat_map(|p| {
+          p.assert_get(arena)
+            .query_all_iter::<ProvidedValue>()
+            .map(|v| v.0.clone())
+            .collect::<Vec<_>>()
+        })
+        .collect()
+    })
+  }
 }
 
 impl BuildCtxHandle {
@@ -217,4 +307,104 @@ mod tests {
     assert_eq!(iter.next(), Some(Brightness::Light));
     assert_eq!(iter.next(), Some(Brightness::Dark));
   }
+
+  #[test]
+  fn inner_theme_shadows_outer() {
+    reset_test_env!();
+
+    let brightness = Stateful::new(vec![]);
+    let c_brightness = brightness.clone_writer();
+    let trigger = Stateful::new(0);
+    let c_trigger = trigger.clone_writer();
+    let palette_theme = |brightness| {
+      let palette = Palette { brightness, ..Default::default() };
+      Sc::new(Theme::Inherit(InheritTheme { palette: Some(Rc::new(palette)), ..<_>::default() }))
+    };
+
+    let w = fn_widget! {
+      let c_brightness = c_brightness.clone_writer();
+      let c_trigger = c_trigger.clone_watcher();
+      @ThemeWidget {
+        theme: palette_theme(Brightness::Light),
+        @ {
+          Box::new(fn_widget! {
+            let c_brightness = c_brightness.clone_writer();
+            let c_trigger = c_trigger.clone_watcher();
+            @ThemeWidget {
+              theme: palette_theme(Brightness::Dark),
+              @ {
+                Box::new(fn_widget! {
+                  let c_brightness = c_brightness.clone_writer();
+                  // The pipe rebuilds its widget by a context found from the tree.
+                  @MockBox {
+                    size: ZERO_SIZE,
+                    @ {
+                      pipe!(*$c_trigger).map(move |_| {
+                        let c_brightness = c_brightness.clone_writer();
+                        move |ctx: &BuildCtx| {
+                          c_brightness.write().push(Palette::of(ctx).brightness);
+                          Void.build(ctx)
+                        }
+                      })
+                    }
+                  }
+                })
+              }
+            }
+          })
+        }
+      }
+    };
+
+    let mut wnd = TestWindow::new(w);
+    wnd.draw_frame();
+    *trigger.write() += 1;
+    wnd.draw_frame();
+    assert_eq!(&*brightness.read(), &[Brightness::Dark, Brightness::Dark]);
+  }
+
+  #[test]
+  fn nearest_provider() {
+    reset_test_env!();
+
+    let found = Stateful::new(Vec::<(Option<i32>, Option<&'static str>, u8)>::new());
+    let c_found = found.clone_writer();
+
+    let w = fn_widget! {
+      let outer = Provider::new(1i32);
+      @ $outer {
+        @ {
+          Box::new(fn_widget! {
+            let c_found = c_found.clone_writer();
+            let name = Provider::new("outer");
+            @ $name {
+              @ {
+                Box::new(fn_widget! {
+                  let c_found = c_found.clone_writer();
+                  let inner = Provider::new(2i32);
+                  @ $inner {
+                    @ {
+                      Box::new(fn_widget! {
+                        let ctx = ctx!();
+                        $c_found.write().push((
+                          ctx.find_provider::<i32>().copied(),
+                          ctx.find_provider::<&'static str>().copied(),
+                          ctx.provide_or_default::<u8>(),
+                        ));
+                        Void
+                      })
+                    }
+                  }
+                })
+              }
+            }
+          })
+        }
+      }
+    };
+
+    let wnd = TestWindow::new(w);
+    wnd.layout();
+    assert_eq!(*found.read(), [(Some(2), Some("outer"), 0)]);
+  }
 }
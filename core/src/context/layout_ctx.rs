@@ -74,6 +74,28 @@ impl<'a> LayoutCtx<'a> {
     self.new_layouter(wid)
   }
 
+  /// Return the max intrinsic width of the children if their height is
+  /// `height`, see [`Layouter::intrinsic_width`].
+  pub fn children_max_intrinsic_width(&mut self, height: f32) -> f32 {
+    self.children_max_intrinsic(|l| l.intrinsic_width(height))
+  }
+
+  /// Return the max intrinsic height of the children if their width is
+  /// `width`, see [`Layouter::intrinsic_height`].
+  pub fn children_max_intrinsic_height(&mut self, width: f32) -> f32 {
+    self.children_max_intrinsic(|l| l.intrinsic_height(width))
+  }
+
+  fn children_max_intrinsic(&mut self, f: impl Fn(&mut Layouter) -> f32) -> f32 {
+    let mut max = 0f32;
+    let mut child = self.first_child();
+    while let Some(id) = child {
+      max = max.max(f(&mut self.new_layouter(id)));
+      child = id.next_sibling(&self.tree.arena);
+    }
+    max
+  }
+
   /// Clear the child layout information, so the `child` will be force layout
   /// when call `[LayoutCtx::perform_child_layout]!` even if it has layout cache
   /// information with same input.
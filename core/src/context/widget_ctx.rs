@@ -32,6 +32,10 @@ pub trait WidgetCtx {
   fn single_child_box(&self) -> Option<Rect>;
   /// Return the widget box rect.
   fn box_rect(&self) -> Option<Rect>;
+  /// Return the widget box rect in the window coordinate, it's the bounding
+  /// box if the widget or its ancestors are transformed. Return `None` if the
+  /// widget has not been laid out yet.
+  fn global_box_rect(&self) -> Option<Rect>;
   /// Return the widget box size.
   fn box_size(&self) -> Option<Size>;
   /// Return the widget box lef-top position .
@@ -102,6 +106,10 @@ impl<T: WidgetCtxImpl> WidgetCtx for T {
   #[inline]
   fn box_rect(&self) -> Option<Rect> { self.widget_box_rect(self.id()) }
 
+  fn global_box_rect(&self) -> Option<Rect> {
+    self.with_tree(|tree| tree.store.global_rect(self.id(), &tree.arena))
+  }
+
   #[inline]
   fn box_pos(&self) -> Option<Point> {
     self.with_tree(|tree| {
@@ -293,6 +301,33 @@ mod tests {
     assert_eq!(w_ctx.map_to(to_pos, child), Point::new(40., 40.));
   }
 
+  #[test]
+  fn global_rect_with_transform() {
+    let _guard = unsafe { AppCtx::new_lock_scope() };
+
+    let w = fn_widget! {
+      @MockBox {
+        size: Size::new(100., 100.),
+        @MockBox {
+          transform: Transform::scale(0.5, 0.5),
+          anchor: Anchor::left_top(30., 30.),
+          size: Size::new(40., 40.)
+        }
+      }
+    };
+
+    let mut wnd = TestWindow::new_with_size(w, Size::new(100., 100.));
+    let root = wnd.widget_tree.borrow().root();
+    let child = get_single_child_by_depth(root, &wnd.widget_tree.borrow().arena, 3);
+    assert_eq!(wnd.widget_rect(child), None);
+
+    wnd.draw_frame();
+    let expected = Rect::new(Point::new(30., 30.), Size::new(20., 20.));
+    assert_eq!(wnd.widget_rect(child), Some(expected));
+    let w_ctx = TestCtx { id: child, wnd_id: wnd.id() };
+    assert_eq!(w_ctx.global_box_rect(), Some(expected));
+  }
+
   fn get_single_child_by_depth(id: WidgetId, tree: &TreeArena, mut depth: u32) -> WidgetId {
     let mut child = id;
     while depth > 0 {
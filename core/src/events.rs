@@ -23,6 +23,12 @@ mod ime_pre_edit;
 pub use ime_pre_edit::*;
 mod lifecycle;
 pub use lifecycle::*;
+mod pan;
+pub use pan::*;
+mod long_press;
+pub use long_press::*;
+mod double_tap;
+pub use double_tap::*;
 
 pub(crate) mod focus_mgr;
 mod listener_impl_helper;
@@ -1,6 +1,8 @@
 use std::rc::{Rc, Weak};
 
-use winit::event::{DeviceId, ElementState, MouseButton, MouseScrollDelta, WindowEvent};
+use winit::event::{
+  DeviceId, ElementState, MouseButton, MouseScrollDelta, TouchPhase, WindowEvent,
+};
 
 use crate::{prelude::*, window::DelayEvent};
 
@@ -9,11 +11,20 @@ pub(crate) struct Dispatcher {
   pub(crate) info: DispatchInfo,
   pub(crate) entered_widgets: Vec<WidgetId>,
   pub(crate) pointer_down_uid: Option<WidgetId>,
+  /// The widgets captured the pointers, every one receives the move and up
+  /// events of its pointer even if the pointer is out of its bounds.
+  pub(crate) pointer_captures: Vec<(PointerId, WidgetId)>,
 }
 
 impl Dispatcher {
   pub fn new() -> Self {
-    Self { wnd: Weak::new(), info: <_>::default(), entered_widgets: vec![], pointer_down_uid: None }
+    Self {
+      wnd: Weak::new(),
+      info: <_>::default(),
+      entered_widgets: vec![],
+      pointer_down_uid: None,
+      pointer_captures: vec![],
+    }
   }
 
   pub fn init(&mut self, wnd: Weak<Window>) { self.wnd = wnd; }
@@ -45,7 +56,7 @@ impl Dispatcher {
         self.cursor_move_to(Point::new(pos.x, pos.y))
       }
       WindowEvent::CursorLeft { .. } => self.on_cursor_left(),
-      WindowEvent::MouseWheel { delta, .. } => self.dispatch_wheel(delta, wnd_factor),
+      WindowEvent::MouseWheel { delta, phase, .. } => self.dispatch_wheel(delta, phase, wnd_factor),
       _ => log::info!("not processed event {:?}", event),
     }
   }
@@ -85,16 +96,23 @@ impl Dispatcher {
   pub fn cursor_move_to(&mut self, position: Point) {
     self.info.cursor_pos = position;
     self.pointer_enter_leave_dispatch();
-    if let Some(hit) = self.hit_widget() {
+    if let Some(target) = self.pointer_target() {
       self
         .window()
-        .add_delay_event(DelayEvent::PointerMove(hit));
+        .add_delay_event(DelayEvent::PointerMove(target));
     }
   }
 
   pub fn on_cursor_left(&mut self) {
     self.info.cursor_pos = Point::new(-1., -1.);
     self.pointer_enter_leave_dispatch();
+    // The pointer is lost, cancel the capture.
+    if let Some(capture) = self.captured_by(PointerId::MOUSE) {
+      self.release_capture(PointerId::MOUSE);
+      self
+        .window()
+        .add_delay_event(DelayEvent::PointerCancel(capture));
+    }
   }
 
   pub fn dispatch_mouse_input(
@@ -117,8 +135,11 @@ impl Dispatcher {
             self.info.mouse_button.0 = None;
             let wnd = self.window();
             let mut dispatch = |tree: &WidgetTree| {
+              if let Some(target) = self.pointer_target() {
+                wnd.add_delay_event(DelayEvent::PointerUp(target));
+              }
+              self.release_capture(PointerId::MOUSE);
               let hit = self.hit_widget()?;
-              wnd.add_delay_event(DelayEvent::PointerUp(hit));
 
               let tap_on = self
                 .pointer_down_uid
@@ -135,19 +156,19 @@ impl Dispatcher {
     }
   }
 
-  pub fn dispatch_wheel(&mut self, delta: MouseScrollDelta, wnd_factor: f64) {
+  pub fn dispatch_wheel(&mut self, delta: MouseScrollDelta, phase: TouchPhase, wnd_factor: f64) {
     if let Some(wid) = self.hit_widget() {
-      let (delta_x, delta_y) = match delta {
-        MouseScrollDelta::LineDelta(x, y) => (x * PIXELS_PER_EM, y * PIXELS_PER_EM),
+      let delta = match delta {
+        MouseScrollDelta::LineDelta(x, y) => WheelDelta::Lines(Vector::new(x, y)),
         MouseScrollDelta::PixelDelta(delta) => {
           let winit::dpi::LogicalPosition { x, y } = delta.to_logical(wnd_factor);
-          (x, y)
+          WheelDelta::Pixels(Vector::new(x, y))
         }
       };
 
       self
         .window()
-        .add_delay_event(DelayEvent::Wheel { id: wid, delta_x, delta_y });
+        .add_delay_event(DelayEvent::Wheel { id: wid, delta, phase: phase.into() });
     }
   }
 
@@ -180,6 +201,18 @@ impl Dispatcher {
     }
   }
 
+  /// Hit test the cursor again after the tree changed, so the hover state
+  /// follows the widgets under a cursor that has not moved.
+  pub(crate) fn refresh_pointer_hover(&mut self) {
+    // Nothing hovered means the cursor may be out of the window.
+    if self.entered_widgets.is_empty() {
+      return;
+    }
+    if self.hit_widget() != self.entered_widgets.first().copied() {
+      self.pointer_enter_leave_dispatch();
+    }
+  }
+
   fn pointer_enter_leave_dispatch(&mut self) {
     let new_hit = self.hit_widget();
     let wnd = self.window();
@@ -205,6 +238,37 @@ impl Dispatcher {
       new_hit.map_or(vec![], |wid| wid.ancestors(&tree.arena).collect::<Vec<_>>());
   }
 
+  /// The widget the mouse events should be dispatched to, the widget
+  /// captured the mouse if any, otherwise the widget hit by the mouse.
+  fn pointer_target(&self) -> Option<WidgetId> {
+    self
+      .captured_by(PointerId::MOUSE)
+      .or_else(|| self.hit_widget())
+  }
+
+  pub(crate) fn capture(&mut self, pointer: PointerId, wid: WidgetId) {
+    self.release_capture(pointer);
+    self.pointer_captures.push((pointer, wid));
+  }
+
+  pub(crate) fn release_capture(&mut self, pointer: PointerId) {
+    self
+      .pointer_captures
+      .retain(|(p, _)| *p != pointer);
+  }
+
+  /// The widget captured the `pointer`, the capture of a disposed widget is
+  /// treated as released.
+  pub(crate) fn captured_by(&self, pointer: PointerId) -> Option<WidgetId> {
+    let &(_, wid) = self
+      .pointer_captures
+      .iter()
+      .find(|(p, _)| *p == pointer)?;
+    let wnd = self.window();
+    let tree = wnd.widget_tree.borrow();
+    (!wid.is_dropped(&tree.arena)).then_some(wid)
+  }
+
   fn hit_widget(&self) -> Option<WidgetId> {
     let mut hit_target = None;
     let wnd = self.window();
@@ -369,6 +433,58 @@ mod tests {
     assert_eq!(records[2].btns, MouseButtons::default());
   }
 
+  #[test]
+  fn capture_pointer() {
+    reset_test_env!();
+
+    let moves = Rc::new(RefCell::new(0));
+    let c_moves = moves.clone();
+    let w = fn_widget! {
+      @MockMulti {
+        @MockBox {
+          size: Size::new(50., 50.),
+          on_pointer_down: move |e| e.capture_pointer(),
+          on_pointer_move: move |_| *c_moves.borrow_mut() += 1,
+        }
+        @MockBox { size: Size::new(50., 50.) }
+      }
+    };
+    let mut wnd = TestWindow::new_with_size(w, Size::new(200., 200.));
+    wnd.draw_frame();
+
+    #[allow(deprecated)]
+    fn move_to(wnd: &Window, x: f32, y: f32) {
+      let device_id = unsafe { DeviceId::dummy() };
+      wnd.processes_native_event(WindowEvent::CursorMoved { device_id, position: (x, y).into() });
+      wnd.run_frame_tasks();
+    }
+
+    // An other pointer is captured separately.
+    let touch = PointerId(1);
+    let root = wnd.widget_tree.borrow().content_root();
+    wnd.capture_pointer(touch, root);
+
+    let device_id = unsafe { DeviceId::dummy() };
+    move_to(&wnd, 10., 10.);
+    wnd.process_mouse_input(device_id, ElementState::Pressed, MouseButton::Left);
+    wnd.run_frame_tasks();
+    assert_eq!(*moves.borrow(), 1);
+    assert!(wnd.pointer_capture(PointerId::MOUSE).is_some());
+
+    // Out of the widget, over its sibling and out of both.
+    move_to(&wnd, 70., 10.);
+    move_to(&wnd, 150., 150.);
+    assert_eq!(*moves.borrow(), 3);
+
+    wnd.process_mouse_input(device_id, ElementState::Released, MouseButton::Left);
+    wnd.run_frame_tasks();
+    assert!(wnd.pointer_capture(PointerId::MOUSE).is_none());
+    assert_eq!(wnd.pointer_capture(touch), Some(root));
+
+    move_to(&wnd, 70., 10.);
+    assert_eq!(*moves.borrow(), 3);
+  }
+
   // Can not mock two different device id for macos.
   #[cfg(not(target_os = "macos"))]
   #[test]
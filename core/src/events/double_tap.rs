@@ -0,0 +1,161 @@
+use std::cell::Cell;
+
+use crate::{prelude::*, timer::Timer};
+
+/// The config of the double tap gesture, see [`MixBuiltin::on_double_tap`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DoubleTapConfig {
+  /// The max duration between the two taps.
+  pub interval: Duration,
+  /// The max distance between the two taps.
+  pub slop: f32,
+}
+
+impl Default for DoubleTapConfig {
+  fn default() -> Self { Self { interval: Duration::from_millis(250), slop: 8. } }
+}
+
+/// Recognizes the double tap gesture from the tap events.
+///
+/// The second tap of a double tap is consumed by it, so it never starts
+/// another double tap, three taps in a row fire only once.
+pub(crate) struct DoubleTapRecognizer {
+  config: DoubleTapConfig,
+  last_tap: Cell<Option<(PointerId, Instant, Point)>>,
+  recognized: Cell<bool>,
+}
+
+impl DoubleTapRecognizer {
+  pub(crate) fn new(config: DoubleTapConfig) -> Self {
+    Self { config, last_tap: Cell::new(None), recognized: Cell::new(false) }
+  }
+
+  /// Feed a tap to the recognizer, return if it's the second tap of a double
+  /// tap.
+  pub(crate) fn recognize(&self, e: &PointerEvent) -> bool {
+    let DoubleTapConfig { interval, slop } = self.config;
+    let now = Timer::now();
+    let pos = e.global_pos();
+    let is_double = self
+      .last_tap
+      .take()
+      .map_or(false, |(id, stamp, last_pos)| {
+        id == e.id && now.duration_since(stamp) <= interval && (pos - last_pos).length() <= slop
+      });
+    if !is_double {
+      self.last_tap.set(Some((e.id, now, pos)));
+    }
+    self.recognized.set(is_double);
+    is_double
+  }
+
+  /// If the last tap fed to the recognizer is the second tap of a double tap.
+  pub(crate) fn recognized(&self) -> bool { self.recognized.get() }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{cell::Cell, rc::Rc};
+
+  use winit::event::{DeviceId, ElementState, MouseButton, WindowEvent};
+
+  use super::*;
+  use crate::{reset_test_env, test_helper::*};
+
+  fn double_tap_widget(taps: Rc<Cell<usize>>) -> impl WidgetBuilder {
+    fn_widget! {
+      @MockBox {
+        size: Size::new(100., 100.),
+        on_double_tap: move |_| taps.set(taps.get() + 1),
+      }
+    }
+  }
+
+  #[allow(deprecated)]
+  fn tap_at(wnd: &TestWindow, x: f32, y: f32) {
+    let device_id = unsafe { DeviceId::dummy() };
+    wnd.processes_native_event(WindowEvent::CursorMoved { device_id, position: (x, y).into() });
+    wnd.process_mouse_input(device_id, ElementState::Pressed, MouseButton::Left);
+    wnd.process_mouse_input(device_id, ElementState::Released, MouseButton::Left);
+    wnd.run_frame_tasks();
+  }
+
+  #[test]
+  fn double_tap_in_window() {
+    reset_test_env!();
+
+    let taps = Rc::new(Cell::new(0));
+    let mut wnd = TestWindow::new(double_tap_widget(taps.clone()));
+    wnd.draw_frame();
+
+    tap_at(&wnd, 10., 10.);
+    assert_eq!(taps.get(), 0);
+    Timer::advance(Duration::from_millis(100));
+    tap_at(&wnd, 14., 10.);
+    assert_eq!(taps.get(), 1);
+
+    // The third tap starts a new double tap.
+    tap_at(&wnd, 14., 10.);
+    assert_eq!(taps.get(), 1);
+    tap_at(&wnd, 14., 10.);
+    assert_eq!(taps.get(), 2);
+  }
+
+  #[test]
+  fn second_tap_not_delivered_to_tap() {
+    reset_test_env!();
+
+    let taps = Rc::new(Cell::new(0));
+    let double_taps = Rc::new(Cell::new(0));
+    let (c_taps, c_double_taps) = (taps.clone(), double_taps.clone());
+    let w = fn_widget! {
+      @MockBox {
+        size: Size::new(100., 100.),
+        on_tap: move |_| c_taps.set(c_taps.get() + 1),
+        on_double_tap: move |_| c_double_taps.set(c_double_taps.get() + 1),
+      }
+    };
+    let mut wnd = TestWindow::new(w);
+    wnd.draw_frame();
+
+    tap_at(&wnd, 10., 10.);
+    assert_eq!((taps.get(), double_taps.get()), (1, 0));
+    tap_at(&wnd, 10., 10.);
+    assert_eq!((taps.get(), double_taps.get()), (1, 1));
+
+    // The third tap is a single tap again.
+    tap_at(&wnd, 10., 10.);
+    assert_eq!((taps.get(), double_taps.get()), (2, 1));
+  }
+
+  #[test]
+  fn taps_too_slow() {
+    reset_test_env!();
+
+    let taps = Rc::new(Cell::new(0));
+    let mut wnd = TestWindow::new(double_tap_widget(taps.clone()));
+    wnd.draw_frame();
+
+    tap_at(&wnd, 10., 10.);
+    Timer::advance(Duration::from_millis(300));
+    tap_at(&wnd, 10., 10.);
+    assert_eq!(taps.get(), 0);
+
+    // The late tap is the first tap of the next double tap.
+    tap_at(&wnd, 10., 10.);
+    assert_eq!(taps.get(), 1);
+  }
+
+  #[test]
+  fn taps_too_far() {
+    reset_test_env!();
+
+    let taps = Rc::new(Cell::new(0));
+    let mut wnd = TestWindow::new(double_tap_widget(taps.clone()));
+    wnd.draw_frame();
+
+    tap_at(&wnd, 10., 10.);
+    tap_at(&wnd, 50., 10.);
+    assert_eq!(taps.get(), 0);
+  }
+}
@@ -18,6 +18,9 @@ pub(crate) struct FocusManager {
   frame_auto_focus: Vec<WidgetId>,
   focus_widgets: Vec<WidgetId>,
   node_ids: ahash::HashMap<WidgetId, NodeId>,
+  /// The opened trapping scopes and the focus before it opened, the last one
+  /// is the innermost.
+  trap_scopes: Vec<(WidgetId, Option<WidgetId>)>,
   arena: Arena<FocusNodeInfo>,
   root: NodeId,
   wnd: Weak<Window>,
@@ -95,6 +98,7 @@ impl FocusManager {
       request_focusing: None,
       focusing: None,
       node_ids: ahash::HashMap::default(),
+      trap_scopes: vec![],
       arena,
       root,
     }
@@ -133,6 +137,31 @@ impl FocusManager {
     }
   }
 
+  /// Record the current focus to restore when the trapping `scope` closes,
+  /// and move the focus into the scope.
+  pub(crate) fn open_trap_scope(&mut self, scope: WidgetId) {
+    self.trap_scopes.push((scope, self.focusing));
+    self.request_focus_to(Some(scope));
+  }
+
+  /// Restore the focus recorded when the trapping `scope` opened. If an inner
+  /// scope is still opened, it restores to the focus of the `scope` instead.
+  pub(crate) fn close_trap_scope(&mut self, scope: WidgetId) {
+    let Some(idx) = self
+      .trap_scopes
+      .iter()
+      .position(|(id, _)| *id == scope)
+    else {
+      return;
+    };
+    let (_, restore) = self.trap_scopes.remove(idx);
+    if let Some((_, inner_restore)) = self.trap_scopes.get_mut(idx) {
+      *inner_restore = restore;
+    } else {
+      self.request_focus_to(restore);
+    }
+  }
+
   pub(crate) fn focus_handle(&self, wid: WidgetId) -> FocusHandle {
     FocusHandle { wid, wnd_id: self.window().id() }
   }
@@ -198,7 +227,7 @@ impl FocusManager {
     self.request_focus_to(wid);
   }
 
-  fn focus_step(&mut self, focusing: Option<WidgetId>, backward: bool) -> Option<WidgetId> {
+  fn focus_step(&self, focusing: Option<WidgetId>, backward: bool) -> Option<WidgetId> {
     let mut node_id = focusing
       .and_then(|id| self.node_ids.get(&id))
       .copied();
@@ -207,7 +236,17 @@ impl FocusManager {
       .or(Some(self.root));
     loop {
       scope_id?;
-      let next = self.focus_step_in_scope(scope_id.unwrap(), node_id, backward);
+      let next = self
+        .focus_step_in_scope(scope_id.unwrap(), node_id, backward)
+        .or_else(|| {
+          // The focus wraps within a trapping scope instead of escaping it.
+          let scope = self.assert_get(scope_id.unwrap()).wid;
+          if self.scope_property(scope).trap {
+            self.focus_step_in_scope(scope_id.unwrap(), None, backward)
+          } else {
+            None
+          }
+        });
       if let Some(id) = next {
         return self.get(id).and_then(|n| n.wid);
       } else {
@@ -490,12 +529,51 @@ impl FocusManager {
     self.refresh_focus(arena);
   }
 
+  /// Focus the first focusable widget in the `scope`, the focus is kept if
+  /// there isn't any.
+  pub(crate) fn focus_into_scope(&mut self, scope: WidgetId, arena: &TreeArena) {
+    let first = self
+      .node_ids
+      .get(&scope)
+      .and_then(|id| self.focus_step_in_scope(*id, None, false))
+      .and_then(|id| self.assert_get(id).wid);
+    if let Some(wid) = first {
+      self.focus(wid, arena);
+    }
+  }
+
   pub fn blur(&mut self, arena: &TreeArena) {
     self.request_focus_to(None);
     self.refresh_focus(arena);
   }
 
-  pub(crate) fn blur_on_dispose(&mut self) { self.change_focusing_to(None); }
+  /// The focusing widget is disposed with the subtree of `disposed`, move the
+  /// focus to the nearest focusable widget outside of the subtree, the next one
+  /// in the tab order first, then the previous one.
+  pub(crate) fn focus_on_dispose(&mut self, disposed: WidgetId) {
+    let in_disposed = |wid: WidgetId| {
+      let wnd = self.window();
+      let tree = wnd.widget_tree.borrow();
+      let in_subtree = wid
+        .ancestors(&tree.arena)
+        .any(|id| id == disposed);
+      in_subtree
+    };
+    let nearest = |backward: bool| {
+      let mut focusing = self.focusing;
+      let mut visited = ahash::HashSet::default();
+      loop {
+        match self.focus_step(focusing, backward) {
+          // A trapping scope in the disposed subtree never steps out of it.
+          Some(wid) if !visited.insert(wid) => break None,
+          Some(wid) if in_disposed(wid) => focusing = Some(wid),
+          next => break next,
+        }
+      }
+    };
+    let focus_to = nearest(false).or_else(|| nearest(true));
+    self.change_focusing_to(focus_to);
+  }
 
   /// return the focusing widget.
   pub fn focusing(&self) -> Option<WidgetId> { self.focusing }
@@ -865,6 +943,58 @@ mod tests {
     assert_eq!(*input.read(), "hello ribir");
   }
 
+  #[test]
+  fn focus_nearest_after_removed() {
+    reset_test_env!();
+
+    let size = Size::new(10., 10.);
+    let (second, second_writer) = split_value(true);
+    let (third, third_writer) = split_value(true);
+    let w = fn_widget! {
+      @MockMulti {
+        @MockBox { size, tab_index: 2i16 }
+        @ { pipe! {
+          if *$second {
+            @MockBox { size, tab_index: 1i16, auto_focus: true }.build(ctx!())
+          } else {
+            Void.build(ctx!())
+          }
+        }}
+        @ { pipe! {
+          if *$third {
+            @MockBox { size, tab_index: 3i16 }.build(ctx!())
+          } else {
+            Void.build(ctx!())
+          }
+        }}
+      }
+    };
+    let mut wnd = TestWindow::new(w);
+    wnd.draw_frame();
+    let first = {
+      let tree = wnd.widget_tree.borrow();
+      tree.content_root().first_child(&tree.arena)
+    };
+    assert!(wnd.focusing().is_some());
+    assert_ne!(wnd.focusing(), first);
+
+    // Fall to the next one in the tab order.
+    *second_writer.write() = false;
+    wnd.draw_frame();
+    assert_eq!(wnd.focusing(), first);
+
+    wnd
+      .focus_mgr
+      .borrow_mut()
+      .focus_next_widget(&wnd.widget_tree.borrow().arena);
+    assert_ne!(wnd.focusing(), first);
+
+    // The last one in the tab order, fall to the previous one.
+    *third_writer.write() = false;
+    wnd.draw_frame();
+    assert_eq!(wnd.focusing(), first);
+  }
+
   #[test]
   fn multi_focused_update() {
     reset_test_env!();
@@ -39,14 +39,75 @@ impl KeyboardEvent {
   }
 }
 
+/// A combination of a key and modifiers, used to register a shortcut of the
+/// window by [`Window::add_shortcut`].
+///
+/// Use [`KeyCombo::command`] to require the main modifier of the current
+/// platform, so the same combination works across platforms.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyCombo {
+  key: VirtualKey,
+  modifiers: ModifiersState,
+}
+
+impl KeyCombo {
+  pub fn new(key: VirtualKey) -> Self { Self { key, modifiers: ModifiersState::empty() } }
+
+  /// Create a combination of a character key, the character is case
+  /// insensitive.
+  pub fn char(c: &str) -> Self { Self::new(VirtualKey::Character(c.to_lowercase().into())) }
+
+  /// Require the main modifier key of the current platform. Specifically:
+  /// - the `logo` or command key (⌘) on macOS
+  /// - the `control` key on other platforms
+  pub fn command(self) -> Self {
+    #[cfg(target_os = "macos")]
+    return self.logo();
+
+    #[cfg(not(target_os = "macos"))]
+    return self.ctrl();
+  }
+
+  pub fn ctrl(mut self) -> Self {
+    self.modifiers |= ModifiersState::CONTROL;
+    self
+  }
+
+  pub fn shift(mut self) -> Self {
+    self.modifiers |= ModifiersState::SHIFT;
+    self
+  }
+
+  pub fn alt(mut self) -> Self {
+    self.modifiers |= ModifiersState::ALT;
+    self
+  }
+
+  pub fn logo(mut self) -> Self {
+    self.modifiers |= ModifiersState::SUPER;
+    self
+  }
+
+  /// Whether the key and modifiers match this combination.
+  pub fn is_match(&self, key: &VirtualKey, modifiers: ModifiersState) -> bool {
+    if self.modifiers != modifiers {
+      return false;
+    }
+    match (&self.key, key) {
+      (VirtualKey::Character(a), VirtualKey::Character(b)) => a.to_lowercase() == b.to_lowercase(),
+      (a, b) => a == b,
+    }
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use std::{cell::RefCell, rc::Rc};
 
-  use winit::event::ElementState;
+  use winit::event::{ElementState, WindowEvent};
 
   use super::*;
-  use crate::test_helper::*;
+  use crate::{reset_test_env, test_helper::*};
 
   #[test]
   fn smoke() {
@@ -136,4 +197,61 @@ mod tests {
       ]
     );
   }
+
+  #[test]
+  fn shortcut() {
+    reset_test_env!();
+
+    let keys = Rc::new(RefCell::new(vec![]));
+    let c_keys = keys.clone();
+    let w = fn_widget! {
+      @MockBox {
+        size: Size::zero(),
+        auto_focus: true,
+        on_key_down: move |e| c_keys.borrow_mut().push(format!("key down {:?}", e.key)),
+      }
+    };
+    let mut wnd = TestWindow::new(w);
+    wnd.draw_frame();
+
+    let c_keys = keys.clone();
+    wnd.add_shortcut(KeyCombo::char("s").command(), move |_| {
+      c_keys.borrow_mut().push("save".to_string());
+      true
+    });
+    let c_keys = keys.clone();
+    wnd.add_shortcut(KeyCombo::char("p").command(), move |_| {
+      c_keys.borrow_mut().push("print".to_string());
+      false
+    });
+
+    let command = KeyCombo::char("s").command().modifiers;
+    let key_down = |key: &str, modifiers: ModifiersState| {
+      #[allow(deprecated)]
+      wnd.processes_native_event(WindowEvent::ModifiersChanged(modifiers.into()));
+      wnd.processes_keyboard_event(
+        PhysicalKey::Code(KeyCode::KeyS),
+        VirtualKey::Character(key.into()),
+        false,
+        KeyLocation::Standard,
+        ElementState::Pressed,
+      );
+    };
+
+    key_down("s", command);
+    key_down("P", command);
+    key_down("s", ModifiersState::empty());
+    wnd.run_frame_tasks();
+
+    assert_eq!(
+      &*keys.borrow(),
+      &["save", "print", "key down Character(\"P\")", "key down Character(\"s\")"]
+    );
+
+    wnd.remove_shortcut(&KeyCombo::char("s").command());
+    key_down("s", command);
+    wnd.run_frame_tasks();
+    assert_eq!(keys.borrow().last().unwrap(), "key down Character(\"s\")");
+    assert_eq!(keys.borrow().len(), 5);
+  }
 }
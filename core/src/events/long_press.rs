@@ -0,0 +1,164 @@
+use std::{
+  cell::{Cell, RefCell},
+  rc::Rc,
+};
+
+use crate::{prelude::*, timer::Timer};
+
+/// The config of the long press gesture, see [`MixBuiltin::on_long_press`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LongPressConfig {
+  /// How long the pointer must stay pressed before the long press fires.
+  pub duration: Duration,
+  /// The distance the pointer can move while pressed, the long press is
+  /// canceled if the pointer moves farther.
+  pub slop: f32,
+}
+
+impl Default for LongPressConfig {
+  fn default() -> Self { Self { duration: Duration::from_millis(500), slop: 8. } }
+}
+
+/// Return an event handler that recognizes the long press gesture from the
+/// pointer events, and calls `handler` when the pointer stays pressed past the
+/// duration of the `config`.
+///
+/// The handler receives a new pointer event targeting the widget, because the
+/// long press fires on time instead of on a pointer event.
+pub(crate) fn long_press_recognizer(
+  config: LongPressConfig, handler: impl FnMut(&mut PointerEvent) + 'static,
+) -> impl FnMut(&mut Event) + 'static {
+  let handler = Rc::new(RefCell::new(handler));
+  // Every press has its own generation, a timer only fires if its press is
+  // still the pending one.
+  let pending: Rc<Cell<Option<usize>>> = Rc::new(Cell::new(None));
+  let mut generation = 0;
+  let mut start = Point::zero();
+
+  move |e: &mut Event| match e {
+    Event::PointerDown(e) => {
+      generation += 1;
+      pending.set(Some(generation));
+      start = e.global_pos();
+
+      let target = e.current_target();
+      let wnd_id = e.window().id();
+      let (handler, pending, this_press) = (handler.clone(), pending.clone(), generation);
+      let timer = Timer::new(Timer::now() + config.duration);
+      let _ = AppCtx::spawn_local(async move {
+        timer.await;
+        if pending.get() != Some(this_press) {
+          return;
+        }
+        pending.set(None);
+        if let Some(wnd) = AppCtx::get_window(wnd_id) {
+          let mut e = PointerEvent::from_mouse(target, &wnd);
+          (handler.borrow_mut())(&mut e);
+        }
+      });
+    }
+    Event::PointerMove(e) => {
+      if pending.get().is_some() && (e.global_pos() - start).length() > config.slop {
+        pending.set(None);
+      }
+    }
+    Event::PointerUp(_) | Event::PointerCancel(_) | Event::PointerLeave(_) => pending.set(None),
+    _ => {}
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use winit::event::{DeviceId, ElementState, MouseButton, WindowEvent};
+
+  use super::*;
+  use crate::{reset_test_env, test_helper::*};
+
+  fn long_press_widget(presses: Rc<Cell<usize>>) -> impl WidgetBuilder {
+    fn_widget! {
+      @MockBox {
+        size: Size::new(100., 100.),
+        on_long_press: move |_| presses.set(presses.get() + 1),
+      }
+    }
+  }
+
+  #[allow(deprecated)]
+  fn move_to(wnd: &TestWindow, x: f32, y: f32) {
+    let device_id = unsafe { DeviceId::dummy() };
+    wnd.processes_native_event(WindowEvent::CursorMoved { device_id, position: (x, y).into() });
+    wnd.run_frame_tasks();
+  }
+
+  fn press(wnd: &TestWindow, state: ElementState) {
+    let device_id = unsafe { DeviceId::dummy() };
+    wnd.process_mouse_input(device_id, state, MouseButton::Left);
+    wnd.run_frame_tasks();
+  }
+
+  fn wait(wnd: &TestWindow, dur: Duration) {
+    Timer::advance(dur);
+    wnd.run_frame_tasks();
+  }
+
+  #[test]
+  fn fire_after_duration() {
+    reset_test_env!();
+
+    let presses = Rc::new(Cell::new(0));
+    let mut wnd = TestWindow::new(long_press_widget(presses.clone()));
+    wnd.draw_frame();
+
+    move_to(&wnd, 10., 10.);
+    press(&wnd, ElementState::Pressed);
+    wait(&wnd, Duration::from_millis(300));
+    assert_eq!(presses.get(), 0);
+
+    // A move in the slop keeps the press.
+    move_to(&wnd, 14., 12.);
+    wait(&wnd, Duration::from_millis(300));
+    assert_eq!(presses.get(), 1);
+
+    press(&wnd, ElementState::Released);
+    wait(&wnd, Duration::from_secs(1));
+    assert_eq!(presses.get(), 1);
+  }
+
+  #[test]
+  fn cancel_by_release() {
+    reset_test_env!();
+
+    let presses = Rc::new(Cell::new(0));
+    let mut wnd = TestWindow::new(long_press_widget(presses.clone()));
+    wnd.draw_frame();
+
+    move_to(&wnd, 10., 10.);
+    press(&wnd, ElementState::Pressed);
+    wait(&wnd, Duration::from_millis(300));
+    press(&wnd, ElementState::Released);
+    wait(&wnd, Duration::from_millis(300));
+    assert_eq!(presses.get(), 0);
+
+    // The timer of the released press not fires for the next press.
+    press(&wnd, ElementState::Pressed);
+    wait(&wnd, Duration::from_millis(300));
+    assert_eq!(presses.get(), 0);
+    wait(&wnd, Duration::from_millis(200));
+    assert_eq!(presses.get(), 1);
+  }
+
+  #[test]
+  fn cancel_by_move() {
+    reset_test_env!();
+
+    let presses = Rc::new(Cell::new(0));
+    let mut wnd = TestWindow::new(long_press_widget(presses.clone()));
+    wnd.draw_frame();
+
+    move_to(&wnd, 10., 10.);
+    press(&wnd, ElementState::Pressed);
+    move_to(&wnd, 30., 10.);
+    wait(&wnd, Duration::from_secs(1));
+    assert_eq!(presses.get(), 0);
+  }
+}
@@ -0,0 +1,242 @@
+use crate::prelude::*;
+
+/// The max duration between the last move and the release, that the pan still
+/// has a velocity. If the pointer stays longer, it's released at rest.
+const VELOCITY_KEEP_DURATION: Duration = Duration::from_millis(100);
+
+/// The phase of a pan gesture, see [`MixBuiltin::on_pan`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Pan {
+  /// The pointer pressed and moved past the threshold, the pan started.
+  Start,
+  /// The pointer moved, `delta` is the offset from the last update and `total`
+  /// is the offset from where the pointer pressed.
+  Update { delta: Vector, total: Vector },
+  /// The pointer released, `velocity` is the pixels per second of the pointer
+  /// when it's released.
+  End { velocity: Vector },
+  /// The pointer is lost before it's released.
+  Cancel,
+}
+
+/// The config of the pan gesture.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PanConfig {
+  /// The distance the pointer must move before the pan started.
+  pub threshold: f32,
+  /// Lock the pan to the dominant axis of the move when the pan started.
+  pub lock_axis: bool,
+}
+
+impl Default for PanConfig {
+  fn default() -> Self { Self { threshold: 4., lock_axis: false } }
+}
+
+#[derive(Clone, Copy)]
+enum PanAxis {
+  Horizontal,
+  Vertical,
+}
+
+struct PanTrack {
+  start: Point,
+  last: Point,
+  started: bool,
+  axis: Option<PanAxis>,
+  last_stamp: Instant,
+  velocity: Vector,
+}
+
+impl PanAxis {
+  fn lock(axis: Option<Self>, v: Vector) -> Vector {
+    match axis {
+      Some(PanAxis::Horizontal) => Vector::new(v.x, 0.),
+      Some(PanAxis::Vertical) => Vector::new(0., v.y),
+      None => v,
+    }
+  }
+}
+
+/// Return an event handler that recognizes the pan gesture from the pointer
+/// events, and calls `handler` with the phases of the pan.
+pub(crate) fn pan_recognizer(
+  config: PanConfig, mut handler: impl FnMut(&Pan, &mut PointerEvent) + 'static,
+) -> impl FnMut(&mut Event) + 'static {
+  let mut track: Option<PanTrack> = None;
+  move |e: &mut Event| match e {
+    Event::PointerDown(e) => {
+      let pos = e.global_pos();
+      let now = Instant::now();
+      track = Some(PanTrack {
+        start: pos,
+        last: pos,
+        started: false,
+        axis: None,
+        last_stamp: now,
+        velocity: Vector::zero(),
+      });
+    }
+    Event::PointerMove(e) => {
+      let Some(t) = track.as_mut() else { return };
+      let pos = e.global_pos();
+      let total = pos - t.start;
+      if !t.started {
+        // The pointer is released out of the widget before the pan started.
+        if e.mouse_buttons().is_empty() {
+          track = None;
+          return;
+        }
+        if total.length() < config.threshold {
+          return;
+        }
+        t.started = true;
+        // Keep receiving the pointer events even if the pointer is out of the
+        // widget. Not grab it before the pan started, so a press without move
+        // still taps the other widgets.
+        e.capture_pointer();
+        if config.lock_axis {
+          let axis =
+            if total.x.abs() >= total.y.abs() { PanAxis::Horizontal } else { PanAxis::Vertical };
+          t.axis = Some(axis);
+        }
+        handler(&Pan::Start, e);
+      }
+
+      let delta = PanAxis::lock(t.axis, pos - t.last);
+      let now = Instant::now();
+      let dur = now.duration_since(t.last_stamp).as_secs_f32();
+      if dur > 0. {
+        t.velocity = delta / dur;
+      }
+      t.last = pos;
+      t.last_stamp = now;
+      handler(&Pan::Update { delta, total: PanAxis::lock(t.axis, total) }, e);
+    }
+    Event::PointerUp(e) => {
+      if let Some(t) = track.take().filter(|t| t.started) {
+        let velocity = if t.last_stamp.elapsed() <= VELOCITY_KEEP_DURATION {
+          t.velocity
+        } else {
+          Vector::zero()
+        };
+        handler(&Pan::End { velocity }, e);
+      }
+    }
+    Event::PointerCancel(e) => {
+      if track.take().map_or(false, |t| t.started) {
+        handler(&Pan::Cancel, e);
+      }
+    }
+    _ => {}
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{cell::RefCell, rc::Rc};
+
+  use winit::event::{DeviceId, ElementState, MouseButton, WindowEvent};
+
+  use super::*;
+  use crate::{reset_test_env, test_helper::*};
+
+  fn pan_widget(config: PanConfig, pans: Rc<RefCell<Vec<Pan>>>) -> impl WidgetBuilder {
+    fn_widget! {
+      @MockBox {
+        size: Size::new(100., 100.),
+        on_pan_with: (config, move |pan: &Pan, _: &mut PointerEvent| pans.borrow_mut().push(*pan)),
+      }
+    }
+  }
+
+  #[allow(deprecated)]
+  fn move_to(wnd: &TestWindow, x: f32, y: f32) {
+    let device_id = unsafe { DeviceId::dummy() };
+    wnd.processes_native_event(WindowEvent::CursorMoved { device_id, position: (x, y).into() });
+    wnd.run_frame_tasks();
+  }
+
+  fn press(wnd: &TestWindow, state: ElementState) {
+    let device_id = unsafe { DeviceId::dummy() };
+    wnd.process_mouse_input(device_id, state, MouseButton::Left);
+    wnd.run_frame_tasks();
+  }
+
+  #[test]
+  fn pan_threshold() {
+    reset_test_env!();
+
+    let pans = Rc::new(RefCell::new(vec![]));
+    let config = PanConfig { threshold: 10., lock_axis: false };
+    let mut wnd = TestWindow::new(pan_widget(config, pans.clone()));
+    wnd.draw_frame();
+
+    move_to(&wnd, 10., 10.);
+    press(&wnd, ElementState::Pressed);
+    move_to(&wnd, 15., 10.);
+    assert!(pans.borrow().is_empty());
+    assert!(wnd.pointer_capture(PointerId::MOUSE).is_none());
+
+    move_to(&wnd, 25., 10.);
+    assert!(wnd.pointer_capture(PointerId::MOUSE).is_some());
+    move_to(&wnd, 30., 10.);
+    // Continue out of the widget bounds.
+    move_to(&wnd, 150., 10.);
+    {
+      let pans = pans.borrow();
+      assert_eq!(pans.iter().filter(|p| **p == Pan::Start).count(), 1);
+      assert_eq!(pans[0], Pan::Start);
+      assert_eq!(pans[1], Pan::Update { delta: Vector::new(15., 0.), total: Vector::new(15., 0.) });
+      assert_eq!(pans[2], Pan::Update { delta: Vector::new(5., 0.), total: Vector::new(20., 0.) });
+      assert_eq!(
+        pans[3],
+        Pan::Update { delta: Vector::new(120., 0.), total: Vector::new(140., 0.) }
+      );
+    }
+
+    press(&wnd, ElementState::Released);
+    assert!(matches!(pans.borrow().last(), Some(Pan::End { .. })));
+  }
+
+  #[test]
+  fn pan_lock_axis() {
+    reset_test_env!();
+
+    let pans = Rc::new(RefCell::new(vec![]));
+    let config = PanConfig { threshold: 5., lock_axis: true };
+    let mut wnd = TestWindow::new(pan_widget(config, pans.clone()));
+    wnd.draw_frame();
+
+    move_to(&wnd, 10., 10.);
+    press(&wnd, ElementState::Pressed);
+    move_to(&wnd, 12., 20.);
+    move_to(&wnd, 20., 30.);
+
+    let pans = pans.borrow();
+    assert_eq!(pans[1], Pan::Update { delta: Vector::new(0., 10.), total: Vector::new(0., 10.) });
+    assert_eq!(pans[2], Pan::Update { delta: Vector::new(0., 10.), total: Vector::new(0., 20.) });
+  }
+
+  #[test]
+  fn pan_cancel() {
+    reset_test_env!();
+
+    let pans = Rc::new(RefCell::new(vec![]));
+    let mut wnd = TestWindow::new(pan_widget(PanConfig::default(), pans.clone()));
+    wnd.draw_frame();
+
+    move_to(&wnd, 10., 10.);
+    press(&wnd, ElementState::Pressed);
+    move_to(&wnd, 50., 10.);
+
+    let device_id = unsafe { DeviceId::dummy() };
+    #[allow(deprecated)]
+    wnd.processes_native_event(WindowEvent::CursorLeft { device_id });
+    wnd.run_frame_tasks();
+    assert_eq!(pans.borrow().last(), Some(&Pan::Cancel));
+
+    // The pan is over, the release does nothing.
+    press(&wnd, ElementState::Released);
+    assert_eq!(pans.borrow().last(), Some(&Pan::Cancel));
+  }
+}
@@ -1,8 +1,13 @@
 use super::CommonEvent;
-use crate::impl_common_event_deref;
+use crate::{context::WidgetCtx, impl_common_event_deref};
 mod from_mouse;
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub struct PointerId(usize);
+pub struct PointerId(pub(crate) usize);
+
+impl PointerId {
+  /// The id of the mouse pointer.
+  pub const MOUSE: PointerId = PointerId(0);
+}
 
 /// The pointer is a hardware-agnostic device that can target a specific set of
 /// screen coordinates. Having a single event model for pointers can simplify
@@ -74,6 +79,20 @@ pub enum PointerType {
   Touch,
 }
 
+impl PointerEvent {
+  /// Capture this pointer to the current target, see
+  /// [`Window::capture_pointer`](crate::window::Window::capture_pointer).
+  pub fn capture_pointer(&self) {
+    self
+      .window()
+      .capture_pointer(self.id, self.current_target())
+  }
+
+  /// Release the capture of this pointer, see
+  /// [`Window::release_pointer`](crate::window::Window::release_pointer).
+  pub fn release_pointer(&self) { self.window().release_pointer(self.id) }
+}
+
 impl_common_event_deref!(PointerEvent);
 #[cfg(test)]
 mod tests {
@@ -13,7 +13,7 @@ impl PointerEvent {
       .is_empty();
     PointerEvent {
       // todo: we need to trace the pressed pointer, how to generate pointer id, by device + button?
-      id: PointerId(0),
+      id: PointerId::MOUSE,
       width: 1.0,
       height: 1.0,
       pressure: if no_button { 0. } else { 0.5 },
@@ -1,18 +1,66 @@
+use winit::event::TouchPhase;
+
 use crate::{impl_common_event_deref, prelude::*, window::WindowId};
 
 #[derive(Debug)]
 pub struct WheelEvent {
-  pub delta_x: f32,
-  pub delta_y: f32,
+  pub delta: WheelDelta,
+  pub phase: ScrollPhase,
   pub common: CommonEvent,
 }
 
+/// The scroll distance of a wheel event, in the unit reported by the device.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WheelDelta {
+  /// The distance in logical pixels, reported by the precise devices like the
+  /// touchpad.
+  Pixels(Vector),
+  /// The distance in lines, reported by the mouse wheel.
+  Lines(Vector),
+}
+
+/// The phase of a scroll gesture.
+///
+/// A mouse wheel reports every notch as a separate event, while a touchpad
+/// reports a gesture from `Started` to `Ended`. On the platforms with inertial
+/// scroll, like macOS, the momentum is reported by the platform itself as a
+/// gesture after the fingers lift, otherwise a widget can implement its own
+/// fling when the gesture `Ended`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScrollPhase {
+  Started,
+  Moved,
+  Ended,
+  Cancelled,
+}
+
 impl_common_event_deref!(WheelEvent);
 
 impl WheelEvent {
   #[inline]
-  pub fn new(delta_x: f32, delta_y: f32, id: WidgetId, wnd_id: WindowId) -> Self {
-    Self { delta_x, delta_y, common: CommonEvent::new(id, wnd_id) }
+  pub fn new(delta: WheelDelta, phase: ScrollPhase, id: WidgetId, wnd_id: WindowId) -> Self {
+    Self { delta, phase, common: CommonEvent::new(id, wnd_id) }
+  }
+}
+
+impl WheelDelta {
+  /// The distance in logical pixels, a line is `line_height` pixels.
+  pub fn to_pixels(self, line_height: f32) -> Vector {
+    match self {
+      WheelDelta::Pixels(v) => v,
+      WheelDelta::Lines(v) => v * line_height,
+    }
+  }
+}
+
+impl From<TouchPhase> for ScrollPhase {
+  fn from(phase: TouchPhase) -> Self {
+    match phase {
+      TouchPhase::Started => ScrollPhase::Started,
+      TouchPhase::Moved => ScrollPhase::Moved,
+      TouchPhase::Ended => ScrollPhase::Ended,
+      TouchPhase::Cancelled => ScrollPhase::Cancelled,
+    }
   }
 }
 
@@ -29,9 +77,9 @@ mod tests {
   fn smoke() {
     let _guard = unsafe { AppCtx::new_lock_scope() };
 
-    let source_receive_for_bubble = Rc::new(RefCell::new((0., 0.)));
+    let source_receive_for_bubble = Rc::new(RefCell::new(None));
     let bubble_receive = source_receive_for_bubble.clone();
-    let source_receive_for_capture = Rc::new(RefCell::new((0., 0.)));
+    let source_receive_for_capture = Rc::new(RefCell::new(None));
     let capture_receive = source_receive_for_capture.clone();
     let event_order = Rc::new(RefCell::new(Vec::new()));
     let bubble_event_order = event_order.clone();
@@ -41,14 +89,14 @@ mod tests {
       @MockBox {
         size: Size::new(200., 200.),
         on_wheel_capture: move |wheel| {
-          *capture_receive.borrow_mut() = (wheel.delta_x,  wheel.delta_y);
+          *capture_receive.borrow_mut() = Some(wheel.delta);
           (*capture_event_order.borrow_mut()).push("capture");
         },
         @MockBox {
           size: Size::new(100., 100.),
           auto_focus: true,
           on_wheel: move |wheel| {
-            *bubble_receive.borrow_mut() = (wheel.delta_x, wheel.delta_y);
+            *bubble_receive.borrow_mut() = Some(wheel.delta);
             (*bubble_event_order.borrow_mut()).push("bubble");
           }
         }
@@ -67,8 +115,9 @@ mod tests {
     });
     wnd.run_frame_tasks();
 
-    assert_eq!(*source_receive_for_bubble.borrow(), (1., 1.));
-    assert_eq!(*source_receive_for_capture.borrow(), (1., 1.));
+    let delta = Some(WheelDelta::Pixels(Vector::new(1., 1.)));
+    assert_eq!(*source_receive_for_bubble.borrow(), delta);
+    assert_eq!(*source_receive_for_capture.borrow(), delta);
     assert_eq!(*event_order.borrow(), ["capture", "bubble"]);
   }
 }
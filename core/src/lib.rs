@@ -46,9 +46,9 @@ pub mod prelude {
   #[doc(no_inline)]
   pub use crate::events::*;
   #[doc(no_inline)]
-  pub use crate::overlay::{Overlay, OverlayCloseHandle};
+  pub use crate::overlay::{Overlay, OverlayCloseHandle, Placement};
   #[doc(no_inline)]
-  pub use crate::pipe::{BoxPipe, FinalChain, MapPipe, ModifiesPipe, Pipe};
+  pub use crate::pipe::{BoxPipe, FinalChain, KeyedList, MapPipe, ModifiesPipe, Pipe};
   #[doc(no_inline)]
   pub use crate::state::*;
   #[doc(no_inline)]
@@ -60,7 +60,7 @@ pub mod prelude {
   #[doc(no_inline)]
   pub use crate::widget_tree::{BoxClamp, LayoutInfo, Layouter, WidgetId};
   #[doc(no_inline)]
-  pub use crate::window::Window;
+  pub use crate::window::{DelayedTask, FrameStats, Window};
   pub use crate::{
     animation::*,
     query::*,
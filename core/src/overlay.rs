@@ -1,11 +1,31 @@
-use std::{cell::RefCell, mem::replace, rc::Rc};
-
-use crate::prelude::*;
-
+use std::{
+  cell::{Cell, RefCell},
+  mem::replace,
+  rc::Rc,
+};
+
+use crate::{data_widget::Queryable, prelude::*, ticker::FrameMsg};
+
+/// The style of an overlay.
+///
+/// Overlays are stacked in the order they are shown, and the outside tap and
+/// the `Esc` key are only handled by the top one. So a submenu shown from a
+/// menu is always closed before its parent menu, and a tap outside of both
+/// only closes the submenu. Exclude the parent menu from the submenu to let
+/// the taps on the parent menu pass through without closing the submenu.
+///
+/// The outside of the overlay always absorbs the pointer events, so the
+/// widgets below it are not reachable whether the overlay has a backdrop or
+/// not.
 #[derive(Clone)]
 pub struct OverlayStyle {
   pub close_policy: ClosePolicy,
-  pub mask_brush: Option<Brush>,
+  /// The scrim painted over the whole window beneath the overlay.
+  pub backdrop: Option<BackdropStyle>,
+  /// The widgets not treated as the outside of the overlay, the taps in them
+  /// pass through the mask and the focus moved into them not close the
+  /// overlay.
+  pub exclude_regions: Vec<WidgetId>,
 }
 
 bitflags! {
@@ -14,6 +34,14 @@ bitflags! {
     const NONE = 0b0000;
     const ESC = 0b0001;
     const TAP_OUTSIDE = 0b0010;
+    /// Close the overlay when the focus moved out of it.
+    ///
+    /// The focus is moved into the overlay when it's shown, to the first
+    /// focusable widget in it if none of its widgets is auto focused, after
+    /// its first layout. The focus changes before that don't close it. If the
+    /// overlay has nothing to focus, the focus stays outside and this policy
+    /// never closes it.
+    const FOCUS_OUT = 0b0100;
   }
 }
 
@@ -21,11 +49,54 @@ impl CustomStyle for OverlayStyle {
   fn default_style(_: &BuildCtx) -> Self {
     Self {
       close_policy: ClosePolicy::ESC | ClosePolicy::TAP_OUTSIDE,
-      mask_brush: Some(Color::from_f32_rgba(0.3, 0.3, 0.3, 0.3).into()),
+      backdrop: Some(BackdropStyle {
+        color: Color::from_f32_rgba(0.3, 0.3, 0.3, 0.3).into(),
+        blur: 0.,
+      }),
+      exclude_regions: vec![],
     }
   }
 }
 
+/// The scrim dims the content behind a modal overlay.
+///
+/// When the overlays with backdrop are stacked, only the top one paints its
+/// backdrop, so the content behind them is dimmed once and the lower overlays
+/// are dimmed by the top backdrop like the other content.
+#[derive(Clone)]
+pub struct BackdropStyle {
+  pub color: Brush,
+  /// The radius to blur the content behind the overlay before it's dimmed,
+  /// zero for no blur.
+  pub blur: f32,
+}
+
+impl OverlayStyle {
+  /// Exclude the `regions` from the outside of the overlay, see
+  /// [`OverlayStyle::exclude_regions`].
+  pub fn exclude(mut self, regions: Vec<WidgetId>) -> Self {
+    self.exclude_regions = regions;
+    self
+  }
+}
+
+/// The placement of an overlay relative to its anchor widget.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Placement {
+  /// Above the anchor, aligned to its left edge.
+  TopStart,
+  /// Above the anchor, aligned to its horizontal center.
+  TopCenter,
+  /// Above the anchor, aligned to its right edge.
+  TopEnd,
+  /// Below the anchor, aligned to its left edge.
+  BottomStart,
+  /// Below the anchor, aligned to its horizontal center.
+  BottomCenter,
+  /// Below the anchor, aligned to its right edge.
+  BottomEnd,
+}
+
 /// A handle to close the overlay
 #[derive(Clone)]
 pub struct OverlayCloseHandle(OverlayState);
@@ -198,6 +269,51 @@ impl Overlay {
     );
   }
 
+  /// Show the overlay anchored to the widget `anchor` with the `placement`.
+  /// The `offset.x` is added to the horizontal position, and the `offset.y` is
+  /// the gap between the overlay and the anchor.
+  ///
+  /// The overlay follows the anchor when it's relaid out, and is placed on the
+  /// opposite side if it would overflow the window. The overlay will be
+  /// closed if the anchor is disposed. If the overlay is showing, nothing
+  /// will happen.
+  pub fn show_anchored(
+    &self, anchor: WidgetId, placement: Placement, offset: Vector, wnd: Rc<Window>,
+  ) {
+    if self.is_show() {
+      return;
+    }
+    self.show_map(
+      move |w, close_handle| {
+        fn_widget! {
+          let wnd = ctx!().window();
+          let placer = @AnchoredPlacer { target: anchor, placement, offset };
+          // The overlay is placed in its layout, but if only the anchor is relaid
+          // out, the overlay needs to be relaid out to follow it.
+          let u = wnd
+            .frame_tick_stream()
+            .filter(|msg| matches!(msg, FrameMsg::LayoutReady(_)))
+            .subscribe(move |_| {
+              let tree = wnd.widget_tree.borrow();
+              if anchor.is_dropped(&tree.arena) {
+                drop(tree);
+                close_handle.close();
+              } else if $placer.anchor_rect.get() != anchor_rect(anchor, &tree) {
+                drop(tree);
+                // Modify the placer to lay it out again.
+                *$placer.write().anchor_rect.get_mut() = None;
+              }
+            });
+          @$placer {
+            on_disposed: move |_| u.unsubscribe(),
+            @ { w }
+          }
+        }
+      },
+      wnd,
+    );
+  }
+
   /// return whether the overlay is show.
   pub fn is_show(&self) -> bool { self.0.state.is_show() }
 
@@ -205,6 +321,32 @@ impl Overlay {
   pub fn close(&self) { self.0.state.close() }
 }
 
+impl Placement {
+  fn position(self, anchor: Rect, size: Size, offset: Vector, wnd_size: Size) -> Point {
+    let x = match self {
+      Placement::TopStart | Placement::BottomStart => anchor.min_x(),
+      Placement::TopCenter | Placement::BottomCenter => anchor.center().x - size.width / 2.,
+      Placement::TopEnd | Placement::BottomEnd => anchor.max_x() - size.width,
+    };
+    let above = anchor.min_y() - offset.y - size.height;
+    let below = anchor.max_y() + offset.y;
+    let below_overflow = below + size.height > wnd_size.height;
+    let y = match self {
+      Placement::TopStart | Placement::TopCenter | Placement::TopEnd => {
+        if above < 0. && !below_overflow { below } else { above }
+      }
+      _ => {
+        if below_overflow && above >= 0. {
+          above
+        } else {
+          below
+        }
+      }
+    };
+    Point::new(x + offset.x, y)
+  }
+}
+
 enum OverlayInnerState {
   ToShow(Instant, Rc<Window>),
   Showing(WidgetId, Rc<Window>),
@@ -245,7 +387,11 @@ impl OverlayState {
       };
       let build_ctx = BuildCtx::new(None, &wnd.widget_tree);
       let style = style.unwrap_or_else(|| OverlayStyle::of(&build_ctx));
-      let w = this.wrap_style(w, style).build(&build_ctx);
+      let has_backdrop = style.backdrop.is_some();
+      let mut w = this.wrap_style(w, style).build(&build_ctx);
+      if has_backdrop {
+        w = w.attach_data(Queryable(HasBackdrop), &build_ctx);
+      }
       let wid = w.id();
       *this.0.borrow_mut() = OverlayInnerState::Showing(wid, wnd.clone());
       let root = wnd.widget_tree.borrow().root();
@@ -258,11 +404,18 @@ impl OverlayState {
   fn wrap_style(&self, w: impl WidgetBuilder, style: OverlayStyle) -> impl WidgetBuilder {
     let this = self.clone();
     fn_widget! {
-      let OverlayStyle { close_policy, mask_brush } = style;
+      let OverlayStyle { close_policy, backdrop, exclude_regions } = style;
       let this2 = this.clone();
-      @Container {
-        size: Size::new(f32::INFINITY, f32::INFINITY),
-        background: mask_brush.unwrap_or_else(|| Color::from_u32(0).into()),
+      let this3 = this.clone();
+      let excludes = exclude_regions.clone();
+      let focus_out = close_policy.contains(ClosePolicy::FOCUS_OUT);
+      // The focus out closes the overlay only after the overlay pulled the focus
+      // into it, so a focus change before its first layout doesn't close it.
+      let focus_pulled = Rc::new(Cell::new(false));
+      let c_focus_pulled = focus_pulled.clone();
+      let mut mask = @OverlayMask {
+        backdrop,
+        exclude_regions,
         on_tap: move |e| {
           if close_policy.contains(ClosePolicy::TAP_OUTSIDE)
             && e.target() == e.current_target() {
@@ -275,14 +428,183 @@ impl OverlayState {
             this2.close();
           }
         },
-        @$w {}
+        on_focus_out: move |e| {
+          if !focus_out || !focus_pulled.get() {
+            return;
+          }
+          let wnd = e.window();
+          let into_excluded = wnd.focusing().map_or(false, |focus| {
+            let tree = wnd.widget_tree.borrow();
+            excludes
+              .iter()
+              .any(|id| !id.is_dropped(&tree.arena) && id.ancestor_of(focus, &tree.arena))
+          });
+          if !into_excluded {
+            this3.close();
+          }
+        },
+      };
+      if focus_out {
+        let u = focus_into_after_layout(mask.lazy_id(), ctx!().window(), c_focus_pulled);
+        mask = mask.on_disposed(move |_| u.unsubscribe());
       }
+      @FocusScope { @$mask { @$w {} } }
     }
   }
 
   fn close_handle(&self) -> OverlayCloseHandle { OverlayCloseHandle(self.clone()) }
 }
 
+/// Move the focus into the overlay `id` after it's laid out, when the auto
+/// focus in it has been applied, and mark it in `pulled`.
+fn focus_into_after_layout(
+  id: LazyWidgetId, wnd: Rc<Window>, pulled: Rc<Cell<bool>>,
+) -> impl Subscription {
+  wnd
+    .frame_tick_stream()
+    .filter(|msg| matches!(msg, FrameMsg::LayoutReady(_)))
+    .take(1)
+    .subscribe(move |_| {
+      let tree = wnd.widget_tree.borrow();
+      let Some(id) = id.id().filter(|id| !id.is_dropped(&tree.arena)) else { return };
+      let mut focus_mgr = wnd.focus_mgr.borrow_mut();
+      let focused_in = focus_mgr
+        .focusing()
+        .map_or(false, |focus| id.ancestor_of(focus, &tree.arena));
+      if !focused_in {
+        focus_mgr.focus_into_scope(id, &tree.arena);
+      }
+      pulled.set(true);
+    })
+}
+
+/// A mark of the overlay that has a backdrop.
+struct HasBackdrop;
+
+/// The mask fills the whole window behind the overlay, it's the outside of the
+/// overlay except the excluded regions.
+#[derive(SingleChild, Declare)]
+struct OverlayMask {
+  backdrop: Option<BackdropStyle>,
+  exclude_regions: Vec<WidgetId>,
+}
+
+impl Render for OverlayMask {
+  fn perform_layout(&self, clamp: BoxClamp, ctx: &mut LayoutCtx) -> Size {
+    if let Some(mut l) = ctx.single_child_layouter() {
+      l.perform_widget_layout(clamp);
+    }
+    clamp.max
+  }
+
+  fn paint(&self, ctx: &mut PaintingCtx) {
+    let Some(backdrop) = self.backdrop.as_ref() else { return };
+    if self.covered_by_backdrop(ctx) {
+      return;
+    }
+    let rect = Rect::from_size(ctx.box_size().unwrap());
+    let painter = ctx.painter();
+    painter.blur_backdrop(Path::rect(&rect), backdrop.blur);
+    painter.set_brush(backdrop.color.clone());
+    painter.rect(&rect);
+    painter.fill();
+  }
+
+  fn only_sized_by_parent(&self) -> bool { true }
+
+  fn hit_test(&self, ctx: &HitTestCtx, pos: Point) -> HitTest {
+    // The hit test of the excluded regions falls through to the widgets below,
+    // but the content of the overlay is still hit first.
+    let hit = hit_test_impl(ctx, pos) && !self.in_exclude_regions(ctx, pos);
+    HitTest { hit, can_hit_child: true }
+  }
+}
+
+impl OverlayMask {
+  /// Whether an overlay above this one has a backdrop.
+  fn covered_by_backdrop(&self, ctx: &PaintingCtx) -> bool {
+    let id = ctx.id();
+    ctx.with_tree(|tree| {
+      let arena = &tree.arena;
+      std::iter::successors(id.next_sibling(arena), |s| s.next_sibling(arena))
+        .any(|s| s.assert_get(arena).contain_type::<HasBackdrop>())
+    })
+  }
+
+  fn in_exclude_regions(&self, ctx: &HitTestCtx, pos: Point) -> bool {
+    if self.exclude_regions.is_empty() {
+      return false;
+    }
+    let global = ctx.map_to_global(ctx.map_from_parent(pos));
+    ctx.with_tree(|tree| {
+      let WidgetTree { arena, store, .. } = tree;
+      self.exclude_regions.iter().any(|id| {
+        if id.is_dropped(arena) {
+          return false;
+        }
+        let pos = store.map_from_global(global, *id, arena);
+        store
+          .layout_box_size(*id)
+          .map_or(false, |size| Rect::from_size(size).contains(pos))
+      })
+    })
+  }
+}
+
+/// Places its child by the `placement` to the `target` widget. It fills the
+/// overlay mask, so its coordinate is the window coordinate.
+#[derive(SingleChild, Declare)]
+struct AnchoredPlacer {
+  target: WidgetId,
+  placement: Placement,
+  offset: Vector,
+  /// The rect of the target used in the last layout.
+  #[declare(skip)]
+  anchor_rect: Cell<Option<Rect>>,
+}
+
+impl Render for AnchoredPlacer {
+  fn perform_layout(&self, clamp: BoxClamp, ctx: &mut LayoutCtx) -> Size {
+    // The target is laid out before the overlays, so its layout result of this
+    // frame is ready.
+    let rect = ctx.with_tree(|tree| anchor_rect(self.target, tree));
+    self.anchor_rect.set(rect);
+    if let Some(mut l) = ctx.single_child_layouter() {
+      let size = l.perform_widget_layout(clamp.loose());
+      if let Some(rect) = rect {
+        l.update_position(
+          self
+            .placement
+            .position(rect, size, self.offset, clamp.max),
+        );
+      }
+    }
+    clamp.max
+  }
+
+  fn only_sized_by_parent(&self) -> bool { true }
+
+  fn paint(&self, _: &mut PaintingCtx) {}
+
+  fn hit_test(&self, _: &HitTestCtx, _: Point) -> HitTest {
+    // It fills the mask, so only its child is hit, the rest is the mask.
+    HitTest { hit: false, can_hit_child: true }
+  }
+}
+
+/// The rect of the `anchor` in the window coordinate, `None` if it's dropped or
+/// not laid out.
+fn anchor_rect(anchor: WidgetId, tree: &WidgetTree) -> Option<Rect> {
+  if anchor.is_dropped(&tree.arena) {
+    return None;
+  }
+  let size = tree.store.layout_box_size(anchor)?;
+  let pos = tree
+    .store
+    .map_to_global(Point::zero(), anchor, &tree.arena);
+  Some(Rect::new(pos, size))
+}
+
 pub(crate) struct OverlayRoot {}
 
 impl Render for OverlayRoot {
@@ -302,10 +624,15 @@ impl Render for OverlayRoot {
 
 #[cfg(test)]
 mod tests {
-  use std::{cell::RefCell, rc::Rc};
+  use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+  };
 
   use ribir_dev_helper::assert_layout_result_by_path;
+  use winit::event::{DeviceId, ElementState, MouseButton, WindowEvent};
 
+  use super::{BackdropStyle, ClosePolicy, OverlayStyle};
   use crate::{prelude::*, reset_test_env, test_helper::*};
 
   #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
@@ -343,13 +670,331 @@ mod tests {
     overlay.show_at(Point::new(50., 30.), wnd.0.clone());
     wnd.draw_frame();
     assert_eq!(*r_log.borrow(), &["mounted"]);
-    // the path [1, 0, 0, 0] is from root to anchor,
-    // OverlayRoot -> BoxDecoration-> Container -> Anchor
-    assert_layout_result_by_path!(wnd, {path = [1, 0, 0, 0], x == 50., y == 30.,});
+    // the path [1, 0, 0] is from root to anchor,
+    // OverlayRoot -> OverlayMask -> Anchor
+    assert_layout_result_by_path!(wnd, {path = [1, 0, 0], x == 50., y == 30.,});
 
     overlay.close();
     wnd.draw_frame();
     assert_eq!(*r_log.borrow(), &["mounted", "disposed"]);
     assert_eq!(wnd.widget_tree.borrow().count(root), 3);
   }
+
+  #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+  #[test]
+  fn anchored_overlay() {
+    reset_test_env!();
+
+    let show = Stateful::new(true);
+    let c_show = show.clone_writer();
+    let anchor_pos = Stateful::new(Point::new(10., 10.));
+    let c_anchor_pos = anchor_pos.clone_writer();
+    let anchor = Rc::new(RefCell::new(None));
+    let c_anchor = anchor.clone();
+    let widget = fn_widget! {
+      @MockBox {
+        size: Size::new(100., 100.),
+        @ {
+          pipe!(*$show).map(move |v| {
+            let c_anchor = c_anchor.clone();
+            v.then(move || @MockBox {
+              anchor: pipe!(Anchor::from_point(*$anchor_pos)),
+              size: Size::new(20., 10.),
+              on_mounted: move |e| *c_anchor.borrow_mut() = Some(e.current_target()),
+            })
+          })
+        }
+      }
+    };
+
+    let mut wnd = TestWindow::new_with_size(widget, Size::new(100., 100.));
+    wnd.draw_frame();
+    let overlay = Overlay::new(fn_widget! { @MockBox { size: Size::new(30., 30.) } });
+    let anchor = anchor.borrow().unwrap();
+    overlay.show_anchored(anchor, Placement::BottomStart, Vector::new(0., 5.), wnd.0.clone());
+    wnd.draw_frame();
+    // OverlayMask -> AnchoredPlacer -> MockBox
+    assert_layout_result_by_path!(wnd, {path = [1, 0, 0], x == 10., y == 25.,});
+
+    // follow the anchor in the same frame, and flip to the top if overflow the
+    // window.
+    *c_anchor_pos.write() = Point::new(20., 80.);
+    wnd.draw_frame();
+    assert_layout_result_by_path!(wnd, {path = [1, 0, 0], x == 20., y == 45.,});
+
+    // close when the anchor is disposed.
+    *c_show.write() = false;
+    wnd.draw_frame();
+    wnd.draw_frame();
+    assert!(!overlay.is_show());
+  }
+
+  #[allow(deprecated)]
+  fn tap_at(wnd: &mut TestWindow, x: f32, y: f32) {
+    let device_id = unsafe { DeviceId::dummy() };
+    wnd.processes_native_event(WindowEvent::CursorMoved { device_id, position: (x, y).into() });
+    wnd.process_mouse_input(device_id, ElementState::Pressed, MouseButton::Left);
+    wnd.process_mouse_input(device_id, ElementState::Released, MouseButton::Left);
+    wnd.draw_frame();
+  }
+
+  #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+  #[test]
+  fn submenu_exclude_parent() {
+    reset_test_env!();
+
+    let size = Size::new(500., 500.);
+    let mut wnd = TestWindow::new_with_size(fn_widget! { @MockBox { size } }, size);
+    wnd.draw_frame();
+
+    let menu_id = Rc::new(Cell::new(None));
+    let menu_taps = Rc::new(Cell::new(0));
+    let c_menu_id = menu_id.clone();
+    let c_menu_taps = menu_taps.clone();
+    let menu = Overlay::new(fn_widget! {
+      @MockBox {
+        size: Size::new(100., 100.),
+        on_mounted: move |e| c_menu_id.set(Some(e.current_target())),
+        on_tap: move |_| c_menu_taps.set(c_menu_taps.get() + 1),
+      }
+    });
+    menu.show_at(Point::zero(), wnd.0.clone());
+    wnd.draw_frame();
+
+    let submenu = Overlay::new(fn_widget! { @MockBox { size: Size::new(100., 100.) } });
+    let style = OverlayStyle {
+      close_policy: ClosePolicy::ESC | ClosePolicy::TAP_OUTSIDE,
+      backdrop: None,
+      exclude_regions: vec![],
+    };
+    submenu.with_style(style.exclude(vec![menu_id.get().unwrap()]));
+    submenu.show_at(Point::new(200., 0.), wnd.0.clone());
+    wnd.draw_frame();
+
+    // The tap on the parent menu passes through the mask of the submenu.
+    tap_at(&mut wnd, 50., 50.);
+    assert_eq!(menu_taps.get(), 1);
+    assert!(submenu.is_show());
+    assert!(menu.is_show());
+
+    // The submenu is on the top, so it's closed before the parent menu.
+    tap_at(&mut wnd, 400., 400.);
+    assert!(!submenu.is_show());
+    assert!(menu.is_show());
+
+    tap_at(&mut wnd, 400., 400.);
+    assert!(!menu.is_show());
+    assert_eq!(menu_taps.get(), 1);
+  }
+
+  #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+  #[test]
+  fn focus_out_close() {
+    reset_test_env!();
+
+    let size = Size::new(500., 500.);
+    let outside = Rc::new(Cell::new(None));
+    let c_outside = outside.clone();
+    let w = fn_widget! {
+      @MockBox {
+        size,
+        tab_index: 0i16,
+        on_mounted: move |e| c_outside.set(Some(e.current_target())),
+      }
+    };
+    let mut wnd = TestWindow::new_with_size(w, size);
+    wnd.draw_frame();
+
+    let inside = Rc::new(Cell::new(None));
+    let c_inside = inside.clone();
+    let overlay = Overlay::new(fn_widget! {
+      @MockBox {
+        size: Size::new(100., 100.),
+        tab_index: 0i16,
+        on_mounted: move |e| c_inside.set(Some(e.current_target())),
+      }
+    });
+    overlay.with_style(OverlayStyle {
+      close_policy: ClosePolicy::FOCUS_OUT,
+      backdrop: None,
+      exclude_regions: vec![],
+    });
+    overlay.show_at(Point::zero(), wnd.0.clone());
+    wnd.draw_frame();
+    assert_eq!(wnd.focus_mgr.borrow().focusing(), inside.get());
+
+    wnd
+      .focus_mgr
+      .borrow_mut()
+      .focus(outside.get().unwrap(), &wnd.widget_tree.borrow().arena);
+    wnd.draw_frame();
+    assert!(!overlay.is_show());
+  }
+
+  #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+  #[test]
+  fn focus_out_before_first_frame() {
+    reset_test_env!();
+
+    let size = Size::new(500., 500.);
+    let outside = Rc::new(Cell::new(None));
+    let c_outside = outside.clone();
+    let w = fn_widget! {
+      @MockBox {
+        size,
+        tab_index: 0i16,
+        on_mounted: move |e| c_outside.set(Some(e.current_target())),
+      }
+    };
+    let mut wnd = TestWindow::new_with_size(w, size);
+    wnd.draw_frame();
+
+    let inside = Rc::new(Cell::new(None));
+    let c_inside = inside.clone();
+    let overlay = Overlay::new(fn_widget! {
+      @MockBox {
+        size: Size::new(100., 100.),
+        auto_focus: true,
+        on_mounted: move |e| c_inside.set(Some(e.current_target())),
+      }
+    });
+    overlay.with_style(OverlayStyle {
+      close_policy: ClosePolicy::FOCUS_OUT,
+      backdrop: None,
+      exclude_regions: vec![],
+    });
+    overlay.show_at(Point::zero(), wnd.0.clone());
+    // Build the overlay, then move the focus into it by its auto focus and out
+    // of it before its first frame.
+    AppCtx::run_until_stalled();
+    wnd.run_frame_tasks();
+    {
+      let tree = wnd.widget_tree.borrow();
+      let mut focus_mgr = wnd.focus_mgr.borrow_mut();
+      focus_mgr.refresh_focus(&tree.arena);
+      assert_eq!(focus_mgr.focusing(), inside.get());
+      focus_mgr.focus(outside.get().unwrap(), &tree.arena);
+    }
+
+    wnd.draw_frame();
+    assert!(overlay.is_show());
+    assert_eq!(wnd.focus_mgr.borrow().focusing(), inside.get());
+  }
+
+  fn modal(color: Color, close_policy: ClosePolicy) -> Overlay {
+    let overlay = Overlay::new(fn_widget! { @MockBox { size: Size::new(100., 100.) } });
+    overlay.with_style(OverlayStyle {
+      close_policy,
+      backdrop: Some(BackdropStyle { color: color.into(), blur: 0. }),
+      exclude_regions: vec![],
+    });
+    overlay
+  }
+
+  #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+  #[test]
+  fn backdrop_absorb_taps() {
+    reset_test_env!();
+
+    let size = Size::new(500., 500.);
+    let taps = Rc::new(Cell::new(0));
+    let c_taps = taps.clone();
+    let w = fn_widget! {
+      @MockBox { size, on_tap: move |_| c_taps.set(c_taps.get() + 1) }
+    };
+    let mut wnd = TestWindow::new_with_size(w, size);
+    wnd.draw_frame();
+
+    let sticky = modal(Color::BLACK, ClosePolicy::NONE);
+    sticky.show_at(Point::zero(), wnd.0.clone());
+    wnd.draw_frame();
+    tap_at(&mut wnd, 400., 400.);
+    assert_eq!(taps.get(), 0);
+    assert!(sticky.is_show());
+
+    sticky.close();
+    wnd.draw_frame();
+
+    let dismissible = modal(Color::BLACK, ClosePolicy::TAP_OUTSIDE);
+    dismissible.show_at(Point::zero(), wnd.0.clone());
+    wnd.draw_frame();
+    tap_at(&mut wnd, 400., 400.);
+    assert_eq!(taps.get(), 0);
+    assert!(!dismissible.is_show());
+
+    tap_at(&mut wnd, 400., 400.);
+    assert_eq!(taps.get(), 1);
+  }
+
+  #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+  #[test]
+  fn stacked_backdrops_dim_once() {
+    reset_test_env!();
+
+    let size = Size::new(500., 500.);
+    let mut wnd = TestWindow::new_with_size(fn_widget! { @MockBox { size } }, size);
+    wnd.draw_frame();
+
+    let bottom = modal(Color::RED, ClosePolicy::NONE);
+    bottom.show_at(Point::zero(), wnd.0.clone());
+    wnd.draw_frame();
+    let top = modal(Color::BLUE, ClosePolicy::NONE);
+    top.show_at(Point::new(200., 200.), wnd.0.clone());
+    wnd.draw_frame();
+
+    let frame = wnd.take_last_frame().unwrap();
+    let backdrops = frame
+      .commands
+      .iter()
+      .filter_map(|cmd| match cmd {
+        PaintCommand::Path(PathCommand { action: PaintPathAction::Color(c), .. }) => Some(*c),
+        _ => None,
+      })
+      .collect::<Vec<_>>();
+    assert_eq!(backdrops, [Color::BLUE]);
+
+    top.close();
+    wnd.draw_frame();
+    let frame = wnd.take_last_frame().unwrap();
+    let red = frame.commands.iter().any(|cmd| {
+      matches!(cmd, PaintCommand::Path(PathCommand {
+        action: PaintPathAction::Color(c), ..
+      }) if *c == Color::RED)
+    });
+    assert!(red);
+  }
+
+  #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+  #[test]
+  fn backdrop_blur() {
+    reset_test_env!();
+
+    let size = Size::new(500., 500.);
+    let mut wnd = TestWindow::new_with_size(fn_widget! { @MockBox { size } }, size);
+    wnd.draw_frame();
+
+    let overlay = Overlay::new(fn_widget! { @MockBox { size: Size::new(100., 100.) } });
+    overlay.with_style(OverlayStyle {
+      close_policy: ClosePolicy::NONE,
+      backdrop: Some(BackdropStyle { color: Color::BLACK.with_alpha(0.3).into(), blur: 6. }),
+      exclude_regions: vec![],
+    });
+    overlay.show_at(Point::zero(), wnd.0.clone());
+    wnd.draw_frame();
+
+    let frame = wnd.take_last_frame().unwrap();
+    let actions = frame
+      .commands
+      .iter()
+      .filter_map(|cmd| match cmd {
+        PaintCommand::Path(PathCommand { action, .. }) => Some(action),
+        _ => None,
+      })
+      .collect::<Vec<_>>();
+    // The content is blurred first, then dimmed.
+    assert!(matches!(
+      actions[..],
+      [PaintPathAction::Backdrop(r), PaintPathAction::Color(_)] if *r == 6.
+    ));
+  }
 }
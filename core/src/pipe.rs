@@ -15,6 +15,9 @@ use crate::{
   ticker::FrameMsg,
 };
 
+mod keyed_list;
+pub use keyed_list::*;
+
 type ValueStream<V> = BoxOp<'static, (ModifyScope, V), Infallible>;
 
 /// A trait for a value that can be subscribed its continuous modifies.
@@ -176,21 +179,10 @@ pub(crate) trait InnerPipe: Pipe {
           .map(Widget::consume)
           .collect::<Vec<_>>();
 
-        set_pos_of_multi(&new, ctx);
-        query_info_outside_until(old[0], &info, ctx, |info| info.multi_replace(&old, &new));
-        pipe_node.primary_transplant(old[0], new[0], ctx);
-
+        // The new widgets are always mounted, the keyed ones take over the state
+        // of the old widgets with the same key.
         update_key_state_multi(old.iter().copied(), new.iter().copied(), ctx);
-
-        new
-          .iter()
-          .rev()
-          .for_each(|w| ctx.insert_after(old[0], *w));
-        old.iter().for_each(|id| ctx.dispose_subtree(*id));
-        new.iter().for_each(|w| {
-          ctx.on_subtree_mounted(*w);
-          ctx.mark_dirty(*w)
-        });
+        update_multi_children(&old, &new, &new, &info, &pipe_node, ctx);
       });
     });
     c_pipe_node.own_subscription(u, ctx);
@@ -738,6 +730,54 @@ fn update_key_state_multi(
   }
 }
 
+/// Replace the `old` children of a multi pipe with the `new` children. The
+/// children in both `old` and `new` are moved to their new position, the other
+/// `old` children are disposed, and the `fresh` children are mounted.
+fn update_multi_children(
+  old: &[WidgetId], new: &[WidgetId], fresh: &[WidgetId], info: &Sc<RefCell<MultiPipeInfo>>,
+  pipe_node: &PipeNode, ctx: &BuildCtx,
+) {
+  set_pos_of_multi(new, ctx);
+  query_info_outside_until(old[0], info, ctx, |info| info.multi_replace(old, new));
+  if old[0] != new[0] {
+    pipe_node.primary_transplant(old[0], new[0], ctx);
+  }
+
+  let (prev, parent) = {
+    let arena = &ctx.tree.borrow().arena;
+    (old[0].prev_sibling(arena), old[0].parent(arena))
+  };
+  let new_set = new.iter().collect::<ahash::HashSet<_>>();
+  old.iter().for_each(|id| {
+    if new_set.contains(id) {
+      ctx.tree.borrow_mut().detach(*id);
+    } else {
+      ctx.dispose_subtree(*id);
+    }
+  });
+
+  {
+    let arena = &mut ctx.tree.borrow_mut().arena;
+    if let Some(prev) = prev {
+      new.iter().fold(prev, |prev, id| {
+        prev.insert_after(*id, arena);
+        *id
+      });
+    } else {
+      let parent = parent.expect("The children of a list must have a parent.");
+      new
+        .iter()
+        .rev()
+        .for_each(|id| parent.prepend(*id, arena));
+    }
+  }
+
+  fresh
+    .iter()
+    .for_each(|id| ctx.on_subtree_mounted(*id));
+  new.iter().for_each(|id| ctx.mark_dirty(*id));
+}
+
 fn update_key_states(
   old_key: &dyn AnyKey, old: WidgetId, new_key: &dyn AnyKey, new: WidgetId, ctx: &BuildCtx,
 ) {
@@ -1059,7 +1099,7 @@ impl RenderProxy for PipeNode {
 #[cfg(test)]
 mod tests {
   use std::{
-    cell::{Cell, Ref},
+    cell::{Cell, Ref, RefCell},
     rc::Rc,
   };
 
@@ -1397,6 +1437,216 @@ mod tests {
     update_list.write().clear();
   }
 
+  #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+  #[test]
+  fn pipe_widgets_mount_regenerated_keyed_children() {
+    reset_test_env!();
+
+    // The item with key `0` has no key widget.
+    let v = Stateful::new(vec![(0, 'x'), (1, 'a'), (2, 'b'), (3, 'c')]);
+    let generation = Stateful::new(1.);
+    let mounted: Stateful<Vec<char>> = Stateful::new(vec![]);
+    let disposed: Stateful<Vec<char>> = Stateful::new(vec![]);
+    let changed: Stateful<Vec<char>> = Stateful::new(vec![]);
+
+    let c_v = v.clone_writer();
+    let c_generation = generation.clone_writer();
+    let c_mounted = mounted.clone_writer();
+    let c_disposed = disposed.clone_writer();
+    let c_changed = changed.clone_writer();
+    let w = fn_widget! {
+      @MockMulti {
+        @ {
+          pipe!(($v.clone(), *$c_generation)).map(move |(v, generation)| {
+            v.into_iter().map(move |(i, c)| {
+              let key = @KeyWidget { key: i, value: c };
+              let w = @MockBox {
+                size: Size::new(generation, i as f32),
+                on_mounted: move |_| {
+                  $c_mounted.write().push(c);
+                  if $key.is_changed() {
+                    $c_changed.write().push(c);
+                  }
+                },
+                on_disposed: move |_| $c_disposed.write().push(c),
+              };
+              if i == 0 {
+                w.build(ctx!())
+              } else {
+                @$key { @ { w } }.build(ctx!())
+              }
+            })
+          })
+        }
+      }
+    };
+
+    let mut wnd = TestWindow::new(w);
+    wnd.draw_frame();
+    assert_eq!(*mounted.read(), ['x', 'a', 'b', 'c']);
+    mounted.write().clear();
+
+    // Every new widget is mounted, and only the key with a new value is changed.
+    c_v.write()[2].1 = 'B';
+    wnd.draw_frame();
+    assert_eq!(*mounted.read(), ['x', 'a', 'B', 'c']);
+    assert_eq!(*disposed.read(), ['x', 'a', 'b', 'c']);
+    assert_eq!(*changed.read(), ['B']);
+    mounted.write().clear();
+    disposed.write().clear();
+    changed.write().clear();
+
+    // The data captured out of the key value is not lost, and the new widgets
+    // follow the new order.
+    *generation.write() = 2.;
+    c_v.write().rotate_right(1);
+    wnd.draw_frame();
+    assert_eq!(*mounted.read(), ['c', 'x', 'a', 'B']);
+    assert_eq!(*disposed.read(), ['x', 'a', 'B', 'c']);
+    assert!(changed.read().is_empty());
+    assert_layout_result_by_path!(
+      wnd,
+      { path = [0, 0], width == 2., height == 3., }
+      { path = [0, 1], width == 2., height == 0., }
+      { path = [0, 2], width == 2., height == 1., }
+      { path = [0, 3], width == 2., height == 2., }
+    );
+  }
+
+  #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+  #[test]
+  fn keyed_list_keep_state() {
+    reset_test_env!();
+
+    let items = Stateful::new(vec![1, 2, 3]);
+    let c_items = items.clone_writer();
+    let counters = Rc::new(RefCell::new(Vec::<(i32, Writer<i32>)>::new()));
+    let c_counters = counters.clone();
+    let w = fn_widget! {
+      let counters = c_counters.clone();
+      @MockMulti {
+        @ {
+          KeyedList::new(pipe!($items.clone()), |i: &i32| *i, move |item: Watcher<Reader<i32>>| {
+            let counter = Stateful::new(0);
+            counters.borrow_mut().push((*item.read(), counter.clone_writer()));
+            fn_widget! {
+              @MockBox { size: pipe!(Size::new(*$counter as f32, 1.)) }
+            }
+          })
+        }
+      }
+    };
+
+    let mut wnd = TestWindow::new(w);
+    wnd.draw_frame();
+    for (i, counter) in counters.borrow().iter() {
+      *counter.write() = *i * 10;
+    }
+    wnd.draw_frame();
+    assert_layout_result_by_path!(
+      wnd,
+      { path = [0, 0], width == 10., }
+      { path = [0, 1], width == 20., }
+      { path = [0, 2], width == 30., }
+    );
+
+    // The state follows the key.
+    *c_items.write() = vec![3, 1, 2];
+    wnd.draw_frame();
+    assert_eq!(counters.borrow().len(), 3);
+    assert_layout_result_by_path!(
+      wnd,
+      { path = [0, 0], width == 30., }
+      { path = [0, 1], width == 10., }
+      { path = [0, 2], width == 20., }
+    );
+
+    // Only the new key is built.
+    *c_items.write() = vec![2, 4];
+    wnd.draw_frame();
+    assert_eq!(counters.borrow().len(), 4);
+    assert_layout_result_by_path!(
+      wnd,
+      { path = [0, 0], width == 20., }
+      { path = [0, 1], width == 0., }
+    );
+
+    // Duplicate keys fall back to match by position.
+    *c_items.write() = vec![4, 4, 4];
+    wnd.draw_frame();
+    assert_eq!(counters.borrow().len(), 5);
+    assert_layout_result_by_path!(
+      wnd,
+      { path = [0, 0], width == 20., }
+      { path = [0, 1], width == 0., }
+      { path = [0, 2], width == 0., }
+    );
+  }
+
+  #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+  #[test]
+  fn keyed_list_duplicate_keys() {
+    reset_test_env!();
+
+    // Capture the warnings of this thread.
+    struct WarnLogger;
+    thread_local! {
+      static WARNINGS: RefCell<Vec<String>> = const { RefCell::new(vec![]) };
+    }
+    impl log::Log for WarnLogger {
+      fn enabled(&self, metadata: &log::Metadata) -> bool { metadata.level() <= log::Level::Warn }
+      fn log(&self, record: &log::Record) {
+        if self.enabled(record.metadata()) {
+          WARNINGS.with(|w| w.borrow_mut().push(record.args().to_string()));
+        }
+      }
+      fn flush(&self) {}
+    }
+    let _ = log::set_logger(&WarnLogger);
+    log::set_max_level(log::LevelFilter::Warn);
+
+    let items = Stateful::new(vec![1, 2]);
+    let c_items = items.clone_writer();
+    let children = Rc::new(RefCell::new(Vec::<Watcher<Reader<i32>>>::new()));
+    let c_children = children.clone();
+    let w = fn_widget! {
+      let children = c_children.clone();
+      @MockMulti {
+        @ {
+          KeyedList::new(pipe!($items.clone()), |i: &i32| *i, move |item: Watcher<Reader<i32>>| {
+            // The size is of the item that the child built with.
+            let size = Size::new(*item.read() as f32 * 10., 1.);
+            children.borrow_mut().push(item);
+            fn_widget! { @MockBox { size } }
+          })
+        }
+      }
+    };
+
+    let mut wnd = TestWindow::new(w);
+    wnd.draw_frame();
+    assert!(WARNINGS.with(|w| w.borrow().is_empty()));
+
+    // The children are matched by their position, the first two are reused
+    // with the new items, and only the third is built.
+    *c_items.write() = vec![2, 2, 1];
+    wnd.draw_frame();
+    assert!(WARNINGS.with(|w| w.borrow().iter().any(|w| w.contains("Duplicate keys"))));
+    let children = children.borrow();
+    assert_eq!(children.len(), 3);
+    let items = children
+      .iter()
+      .map(|item| *item.read())
+      .collect::<Vec<_>>();
+    assert_eq!(items, [2, 2, 1]);
+    assert_layout_result_by_path!(
+      wnd,
+      { path = [0, 0], width == 10., }
+      { path = [0, 1], width == 20., }
+      { path = [0, 2], width == 10., }
+    );
+  }
+
   #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
   #[test]
   fn delay_drop_widgets() {
@@ -1873,4 +2123,54 @@ mod tests {
     wnd.draw_frame();
     assert_eq!(&*w2.read(), &[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10]);
   }
+
+  #[test]
+  fn pipe_map_distinct() {
+    reset_test_env!();
+
+    let state = Stateful::new(0);
+    let host = Stateful::new(true);
+    let built = Rc::new(Cell::new(0));
+    let (c_state, c_host, c_built) = (state.clone_writer(), host.clone_writer(), built.clone());
+    let widget = fn_widget! {
+      @MockMulti {
+        @ {
+          pipe!(*$host).map(move |host| {
+            if host {
+              let c_built = c_built.clone();
+              state
+                .pipe_map(|v| *v / 10)
+                .map(move |tens| {
+                  c_built.set(c_built.get() + 1);
+                  @MockBox { size: Size::new(tens as f32, 1.) }.build(ctx!())
+                })
+                .build(ctx!())
+            } else {
+              Void.build(ctx!())
+            }
+          })
+        }
+      }
+    };
+
+    let mut wnd = TestWindow::new(widget);
+    wnd.draw_frame();
+    assert_eq!(built.get(), 1);
+
+    // The projected value not changed.
+    *c_state.write() = 5;
+    wnd.draw_frame();
+    assert_eq!(built.get(), 1);
+
+    *c_state.write() = 15;
+    wnd.draw_frame();
+    assert_eq!(built.get(), 2);
+
+    // The pipe is unsubscribed with its host.
+    *c_host.write() = false;
+    wnd.draw_frame();
+    *c_state.write() = 25;
+    wnd.draw_frame();
+    assert_eq!(built.get(), 2);
+  }
 }
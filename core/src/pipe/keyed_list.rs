@@ -0,0 +1,164 @@
+use ahash::{HashMap, HashSet};
+
+use super::*;
+
+/// A pipe list that matches its children by the key of their items when
+/// rebuilding.
+///
+/// A `Pipe` of widgets rebuilds all its children when its value changes, so
+/// the state of the children is lost, like the scroll position, the focus or an
+/// animation. `KeyedList` gives every item a `Key`, and when the items change,
+/// the child of a key that exists in both the old and the new items is reused
+/// and moved to its new position, only the children of the new keys are built
+/// and the children of the removed keys are disposed.
+///
+/// The builder receives a watcher of its item instead of the item, so the
+/// reused child can follow the new value of its item.
+///
+/// If the items have duplicate keys, a warning is logged and the children are
+/// matched by their position.
+///
+/// # Example
+///
+/// ```rust
+/// use ribir_core::{prelude::*, test_helper::*};
+///
+/// let items = Stateful::new(vec![(1, 10.), (2, 20.)]);
+/// let _w = fn_widget! {
+///   @MockMulti {
+///     @ {
+///       KeyedList::new(
+///         pipe!($items.clone()),
+///         |(id, _): &(i32, f32)| *id,
+///         |item: Watcher<Reader<(i32, f32)>>| fn_widget! {
+///           @MockBox { size: pipe!(Size::new($item.1, 10.)) }
+///         },
+///       )
+///     }
+///   }
+/// };
+/// ```
+pub struct KeyedList<P, K, B> {
+  items: P,
+  key: K,
+  builder: B,
+}
+
+impl<P, K, B> KeyedList<P, K, B> {
+  pub fn new<V, Item, R, W>(items: P, key: K, builder: B) -> Self
+  where
+    P: Pipe<Value = V>,
+    V: IntoIterator<Item = Item>,
+    K: Fn(&Item) -> R,
+    R: Into<Key>,
+    B: Fn(Watcher<Reader<Item>>) -> W,
+    W: WidgetBuilder,
+  {
+    Self { items, key, builder }
+  }
+
+  pub(crate) fn build_keyed<V, Item, R, W>(self, vec: &mut Vec<Widget>, ctx: &BuildCtx)
+  where
+    P: InnerPipe<Value = V>,
+    V: IntoIterator<Item = Item> + 'static,
+    Item: PartialEq + 'static,
+    K: Fn(&Item) -> R + 'static,
+    R: Into<Key>,
+    B: Fn(Watcher<Reader<Item>>) -> W + 'static,
+    W: WidgetBuilder,
+  {
+    let Self { items, key, builder } = self;
+    let build_item = move |item: Item, ctx: &BuildCtx| {
+      let state = Stateful::new(item);
+      let w = builder(state.clone_watcher()).build(ctx);
+      (state, w)
+    };
+
+    let info = Sc::new(RefCell::new(MultiPipeInfo { widgets: vec![], multi_pos: 0 }));
+    let info2 = info.clone();
+    let handle = ctx.handle();
+    let (v, modifies) = items.tick_unzip(move || pipe_priority_value(&info2, handle), ctx);
+
+    // The keys and the items of the children, in the same order as the widgets
+    // in `info`. It's empty if the list is empty and a `Void` takes its place.
+    let mut entries: Vec<(Key, Stateful<Item>)> = vec![];
+    let mut widgets = vec![];
+    for item in v {
+      let k = key(&item).into();
+      let (state, w) = build_item(item, ctx);
+      entries.push((k, state));
+      widgets.push(w);
+    }
+    if widgets.is_empty() {
+      widgets.push(Void.build(ctx));
+    }
+
+    let pipe_node = PipeNode::share_capture(widgets[0].id(), Box::new(info.clone()), ctx);
+    let ids = widgets.iter().map(|w| w.id()).collect::<Vec<_>>();
+    set_pos_of_multi(&ids, ctx);
+    info.borrow_mut().widgets = ids;
+    vec.extend(widgets);
+
+    let c_pipe_node = pipe_node.clone();
+    let u = modifies.subscribe(move |(_, v)| {
+      handle.with_ctx(|ctx| {
+        let old = info.borrow().widgets.clone();
+        let items = v
+          .into_iter()
+          .map(|item| (key(&item).into(), item))
+          .collect::<Vec<(Key, Item)>>();
+
+        let mut keys = HashSet::default();
+        let unique = items.iter().all(|(k, _)| keys.insert(k));
+        if !unique {
+          log::warn!("Duplicate keys in the `KeyedList`, its children are matched by position.");
+        }
+        let mut old_entries = std::mem::take(&mut entries)
+          .into_iter()
+          .map(Some)
+          .collect::<Vec<_>>();
+        let mut old_keys = HashMap::default();
+        if unique {
+          old_entries
+            .iter()
+            .enumerate()
+            .for_each(|(idx, e)| {
+              if let Some((k, _)) = e {
+                old_keys.entry(k.clone()).or_insert(idx);
+              }
+            });
+        }
+
+        let mut new = Vec::with_capacity(items.len());
+        let mut fresh = vec![];
+        for (pos, (k, item)) in items.into_iter().enumerate() {
+          let matched = if unique { old_keys.get(&k).copied() } else { Some(pos) };
+          match matched.and_then(|idx| Some((idx, old_entries.get_mut(idx)?.take()?))) {
+            Some((idx, (_, state))) => {
+              if *state.read() != item {
+                *state.write() = item;
+              }
+              new.push(old[idx]);
+              entries.push((k, state));
+            }
+            None => {
+              let (state, w) = build_item(item, ctx);
+              let id = w.consume();
+              fresh.push(id);
+              new.push(id);
+              entries.push((k, state));
+            }
+          }
+        }
+        if new.is_empty() {
+          let id = Void.build(ctx).consume();
+          fresh.push(id);
+          new.push(id);
+        }
+
+        update_multi_children(&old, &new, &fresh, &info, &pipe_node, ctx);
+      });
+    });
+    c_pipe_node.own_subscription(u, ctx);
+  }
+}
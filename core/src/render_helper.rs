@@ -43,11 +43,30 @@ impl<T: RenderProxy + 'static> Render for T {
   #[inline]
   fn only_sized_by_parent(&self) -> bool { self.proxy().only_sized_by_parent() }
 
+  #[inline]
+  fn intrinsic_width(&self, height: f32, ctx: &mut LayoutCtx) -> f32 {
+    self.proxy().intrinsic_width(height, ctx)
+  }
+
+  #[inline]
+  fn intrinsic_height(&self, width: f32, ctx: &mut LayoutCtx) -> f32 {
+    self.proxy().intrinsic_height(width, ctx)
+  }
+
   #[inline]
   fn hit_test(&self, ctx: &HitTestCtx, pos: Point) -> HitTest { self.proxy().hit_test(ctx, pos) }
 
   #[inline]
   fn get_transform(&self) -> Option<Transform> { self.proxy().get_transform() }
+
+  #[inline]
+  fn paints_descendants(&self) -> bool { self.proxy().paints_descendants() }
+
+  #[inline]
+  fn type_name(&self) -> &'static str { self.proxy().type_name() }
+
+  #[inline]
+  fn accessibility(&self) -> Option<Accessibility> { self.proxy().accessibility() }
 }
 
 impl<R: Render> RenderProxy for RefCell<R> {
@@ -1,23 +1,40 @@
+mod deferred_writer;
+mod for_each_writer;
+mod history;
 mod map_state;
+#[cfg(feature = "serde")]
+mod persisted;
 mod prior_op;
 mod splitted_state;
 mod stateful;
+mod timing_op;
+mod trace;
 mod watcher;
 use std::{
-  cell::{Cell, UnsafeCell},
+  any::type_name,
+  cell::{Cell, RefCell, UnsafeCell},
   convert::Infallible,
   mem::MaybeUninit,
   ops::DerefMut,
+  panic::Location,
+  rc::Rc,
 };
 pub mod state_cell;
 
+pub use deferred_writer::*;
+pub use for_each_writer::*;
+pub use history::*;
 pub use map_state::*;
+#[cfg(feature = "serde")]
+pub use persisted::*;
 pub use prior_op::*;
 use rxrust::ops::box_it::{BoxOp, CloneableBoxOp};
 pub use splitted_state::*;
 pub use state_cell::{PartData, ReadRef};
 use state_cell::{StateCell, ValueMutRef};
 pub use stateful::*;
+pub use timing_op::*;
+pub use trace::*;
 pub use watcher::*;
 
 use crate::prelude::*;
@@ -33,6 +50,36 @@ pub trait StateReader: 'static {
 
   /// Return a reference of this state.
   fn read(&self) -> ReadRef<Self::Value>;
+  /// Return a clone of the value of this state, the read borrow is released
+  /// before it returns, so it will not conflict with a write in the same
+  /// scope.
+  #[inline]
+  fn read_owned(&self) -> Self::Value
+  where
+    Self::Value: Clone,
+  {
+    self.read().clone()
+  }
+  /// Return a copy of the value of this state, like `read_owned` but for the
+  /// `Copy` value.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// use ribir_core::prelude::*;
+  ///
+  /// let counter = Stateful::new(1);
+  /// let n = counter.get();
+  /// *counter.write() = n + 1;
+  /// assert_eq!(counter.get(), 2);
+  /// ```
+  #[inline]
+  fn get(&self) -> Self::Value
+  where
+    Self::Value: Copy,
+  {
+    *self.read()
+  }
   /// get a clone of this state that only can read.
   fn clone_reader(&self) -> Self::Reader;
   /// Maps an reader to another by applying a function to a contained
@@ -72,6 +119,150 @@ pub trait StateWatcher: StateReader {
       .box_it()
   }
 
+  /// Return a modifies `Rx` stream of the state like `modifies`, but only emit
+  /// when the value is really different from the last emitted one. The value
+  /// when this method called is the first value to compare.
+  fn modifies_distinct(&self) -> BoxOp<'static, ModifyScope, Infallible>
+  where
+    Self::Value: PartialEq + Clone + 'static,
+  {
+    let reader = self.clone_reader();
+    let last = RefCell::new(self.read().clone());
+    self
+      .modifies()
+      .filter(move |_| {
+        let v = reader.read();
+        let mut last = last.borrow_mut();
+        if *v != *last {
+          *last = v.clone();
+          true
+        } else {
+          false
+        }
+      })
+      .box_it()
+  }
+
+  /// Return a modifies `Rx` stream like `modifies`, but only emit the last
+  /// modify after the state hasn't been modified for `dur`. So a burst of
+  /// writes, like the typing in an input, is handled only once after it ends.
+  ///
+  /// The quiet period is timed by the clock of `Timer`, so it's driven by the
+  /// tasks of `AppCtx` and can be advanced by `Timer::advance` in tests.
+  fn modifies_debounced(&self, dur: Duration) -> BoxOp<'static, ModifyScope, Infallible> {
+    self.modifies().debounce_timer(dur).box_it()
+  }
+
+  /// Return a modifies `Rx` stream like `modifies`, but emit at most once
+  /// within every `dur`. The first modify is emitted immediately, and the
+  /// last modify within the `dur` is emitted when the `dur` elapsed, even if
+  /// no more modifies arrive.
+  ///
+  /// Timed by the clock of `Timer` like `modifies_debounced`.
+  fn modifies_throttled(&self, dur: Duration) -> BoxOp<'static, ModifyScope, Infallible> {
+    self.modifies().throttle_timer(dur).box_it()
+  }
+
+  /// Return a `Rx` stream of the value projected from the state by `f`. The
+  /// projected value of the current state is emitted first when subscribed,
+  /// then a new projected value is emitted only if it's different from the
+  /// last one.
+  ///
+  /// This is useful to derive a value from a part of the state, for example:
+  ///
+  /// ```
+  /// use ribir_core::prelude::*;
+  ///
+  /// let name = State::value(String::new());
+  /// name
+  ///   .watch_map(|v| !v.is_empty())
+  ///   .subscribe(|valid| println!("The name is valid: {valid}"));
+  /// ```
+  fn watch_map<U>(&self, f: impl Fn(&Self::Value) -> U + 'static) -> BoxOp<'static, U, Infallible>
+  where
+    U: PartialEq + Clone + 'static,
+  {
+    let f = Rc::new(f);
+    let c_f = f.clone();
+    let reader = self.clone_reader();
+    // Project the value when subscribed, not when the stream is created.
+    let init = observable::defer(move || observable::of(c_f(&reader.read())));
+    let reader = self.clone_reader();
+    self
+      .modifies()
+      .map(move |_| f(&reader.read()))
+      .merge(init)
+      .distinct_until_changed()
+      .box_it()
+  }
+
+  /// Return a pipe of the value projected from the state by `f`. The pipe
+  /// starts with the projected value of the current state, and then only
+  /// emits when the projected value is different from the last one, so the
+  /// widget built from it is only regenerated when the projection changes.
+  ///
+  /// It's like a `pipe!` that only depends on a part of the state, for
+  /// example:
+  ///
+  /// ```
+  /// use ribir_core::prelude::*;
+  ///
+  /// let count = Stateful::new(0);
+  /// let _w = fn_widget! {
+  ///   let many = count.pipe_map(|v| *v > 10);
+  ///   @Container {
+  ///     size: many.map(|many| if many { Size::new(100., 100.) } else { Size::zero() })
+  ///   }
+  /// };
+  /// ```
+  fn pipe_map<U>(
+    &self, f: impl Fn(&Self::Value) -> U + 'static,
+  ) -> MapPipe<U, ModifiesPipe, Box<dyn FnMut(ModifyScope) -> U>>
+  where
+    U: PartialEq + Clone + 'static,
+  {
+    let reader = self.clone_reader();
+    let last = Rc::new(RefCell::new(f(&self.read())));
+    let c_last = last.clone();
+    // Only the modifies that notify the framework can regenerate the widget,
+    // so compare with the last value the pipe emitted.
+    let modifies = self
+      .raw_modifies_matching(ModifyScope::FRAMEWORK)
+      .filter(move |_| {
+        let v = f(&reader.read());
+        let mut last = c_last.borrow_mut();
+        let changed = *last != v;
+        if changed {
+          *last = v;
+        }
+        changed
+      })
+      .box_it();
+    MapPipe::new(ModifiesPipe::new(modifies), Box::new(move |_| last.borrow().clone()))
+  }
+
+  /// Return a modifies `Rx` stream of the state that only emit when the
+  /// modifies effect the data, the same as `modifies`.
+  fn data_modifies(&self) -> BoxOp<'static, ModifyScope, Infallible> {
+    self.raw_modifies_matching(ModifyScope::DATA)
+  }
+
+  /// Return a modifies `Rx` stream of the state that only emit when the
+  /// modifies effect the framework, include the modifies by the `shallow`
+  /// write reference.
+  fn framework_modifies(&self) -> BoxOp<'static, ModifyScope, Infallible> {
+    self.raw_modifies_matching(ModifyScope::FRAMEWORK)
+  }
+
+  /// Return a modifies `Rx` stream of the state that only emit when the
+  /// modifies scope intersects with `scope`.
+  fn raw_modifies_matching(&self, scope: ModifyScope) -> BoxOp<'static, ModifyScope, Infallible> {
+    self
+      .raw_modifies()
+      .filter(move |s| s.intersects(scope))
+      .box_it()
+  }
+
   /// Return a modifies `Rx` stream of the state, including all modifies. Use
   /// `modifies` instead if you only want to response the data changes.
   fn raw_modifies(&self) -> CloneableBoxOp<'static, ModifyScope, Infallible>;
@@ -101,6 +292,14 @@ pub trait StateWriter: StateWatcher {
   fn clone_writer(&self) -> Self::Writer;
   /// Return the origin writer that this state map or split from.
   fn origin_writer(&self) -> &Self::OriginWriter;
+  /// Convert this writer into a reader if there is no other clone of this
+  /// writer, otherwise return an error with self.
+  ///
+  /// For a map or split writer, only its own clones are counted, so the
+  /// return reader still reflect the modifies from the origin writer.
+  fn into_reader(self) -> Result<Self::Reader, Self>
+  where
+    Self: Sized;
   /// Return a new writer that be part of the origin writer by applying a
   /// function to the contained value.
   ///
@@ -144,7 +343,117 @@ pub trait StateWriter: StateWatcher {
     M: Fn(&mut Self::Value) -> PartData<V> + Clone,
   {
     let origin = self.clone_writer();
-    MapWriter { origin, part_map }
+    MapWriter::new(origin, part_map)
+  }
+
+  /// Return a new writer by applying a function to the contained value, the
+  /// function returns `None` if the part not exists, e.g. the payload of an
+  /// enum variant. The `try_read` and `try_write` of the return writer return
+  /// `None` if the part not exists, and its `read` and `write` panic.
+  ///
+  /// The return writer is a shortcut to access part of the origin writer like
+  /// `map_writer(...)`, and the notice of `map_writer` also applies to it.
+  #[inline]
+  fn filter_map_writer<V, M>(&self, part_map: M) -> FilterMapWriter<Self::Writer, M>
+  where
+    M: Fn(&mut Self::Value) -> Option<PartData<V>> + Clone,
+  {
+    FilterMapWriter::new(self.clone_writer(), part_map)
+  }
+
+  /// Return a splitted writer of the element in a list state that be
+  /// identified by `key`, the `key_of` function use to get the key of an
+  /// element.
+  ///
+  /// Unlike a writer split by a position index, the return writer always
+  /// point to the element with the same key, so insert or remove the elements
+  /// before it will not misroute the modifies.
+  ///
+  /// ##Panics
+  ///
+  /// Read or write the return writer will panic if the element with `key` was
+  /// removed from the list.
+  fn index_writer<Item, K, F>(
+    &self, key: K, key_of: F,
+  ) -> SplittedWriter<
+    Self::Writer,
+    impl Fn(&mut Self::Value) -> PartData<Item> + Clone + 'static,
+  >
+  where
+    Self::Value: AsMut<[Item]>,
+    Item: 'static,
+    K: PartialEq + std::fmt::Debug + Clone + 'static,
+    F: Fn(&Item) -> K + Clone + 'static,
+  {
+    self.split_writer(index_part(key, key_of))
+  }
+
+  /// Return a writer of the list state that notifies the item writers split
+  /// from it by `ForEachWriter::item_writer` when the whole list is written.
+  /// See [`ForEachWriter`] for more details.
+  #[inline]
+  fn for_each_writer(&self) -> ForEachWriter<Self::Writer> {
+    ForEachWriter::new(self.clone_writer())
+  }
+
+  /// Batch all the modifies in `f`, and notify only once immediately after `f`
+  /// returned.
+  ///
+  /// The modifies in `f` can be made by any writer, including the writers
+  /// split or mapped from this state. Every modified writer is notified once
+  /// with all its modifies, and the modifies waiting to be notified of these
+  /// writers are merged into the notification too.
+  fn batch<R>(&self, f: impl FnOnce() -> R) -> R {
+    let outermost = BATCHED_WRITERS.with(|writers| {
+      let mut writers = writers.borrow_mut();
+      let outermost = writers.is_none();
+      writers.get_or_insert_with(Vec::new);
+      outermost
+    });
+    // The guard ends the batch even if `f` panics, otherwise the later writes
+    // of this thread are batched and never notified.
+    let _guard = outermost.then_some(BatchGuard);
+    f()
+  }
+
+  /// Write `value` to the state only if it's not equal to the current value,
+  /// return if the state is changed. The state is not notified if the value is
+  /// equal.
+  ///
+  /// The equality is decided by `PartialEq`, so it relies on a meaningful
+  /// `PartialEq` of the value. A value with interior mutability, or compares
+  /// only a part of it, may be considered equal but semantically changed, write
+  /// it by `write` instead.
+  fn set_if_changed(&self, value: Self::Value) -> bool
+  where
+    Self::Value: PartialEq + Sized,
+  {
+    if *self.read() == value {
+      false
+    } else {
+      *self.write() = value;
+      true
+    }
+  }
+}
+
+/// The part map of `index_writer`, find the element with `key` in the list.
+pub(crate) fn index_part<V, Item, K, F>(
+  key: K, key_of: F,
+) -> impl Fn(&mut V) -> PartData<Item> + Clone + 'static
+where
+  V: AsMut<[Item]> + 'static,
+  Item: 'static,
+  K: PartialEq + std::fmt::Debug + Clone + 'static,
+  F: Fn(&Item) -> K + Clone + 'static,
+{
+  move |list: &mut V| {
+    let item = list
+      .as_mut()
+      .iter_mut()
+      .find(|item| key_of(item) == key)
+      .unwrap_or_else(|| panic!("The element with key `{key:?}` does not exist in the list."));
+    PartData::from_ref_mut(item)
   }
 }
 
@@ -153,6 +462,8 @@ pub struct WriteRef<'a, V> {
   control: &'a dyn WriterControl,
   modify_scope: ModifyScope,
   modified: bool,
+  /// Where the value was last modified through this reference.
+  modified_at: Option<&'static Location<'static>>,
 }
 
 /// Enum to store both stateless and stateful object.
@@ -163,6 +474,33 @@ pub(crate) enum InnerState<W> {
   Stateful(Stateful<W>),
 }
 
+thread_local! {
+  /// The writers modified in the running `StateWriter::batch`, `None` if no
+  /// batch is running.
+  static BATCHED_WRITERS: RefCell<Option<Vec<Box<dyn WriterControl>>>> =
+    const { RefCell::new(None) };
+}
+
+/// Ends the outermost `StateWriter::batch` when dropped, and notifies the
+/// writers modified in it.
+struct BatchGuard;
+
+impl Drop for BatchGuard {
+  fn drop(&mut self) {
+    let writers = BATCHED_WRITERS
+      .with(|writers| writers.borrow_mut().take())
+      .unwrap_or_default();
+    for control in writers {
+      let scope = control
+        .batched_modifies()
+        .replace(ModifyScope::empty());
+      if !scope.is_empty() {
+        control.notifier().next(scope);
+      }
+    }
+  }
+}
+
 trait WriterControl {
   fn batched_modifies(&self) -> &Cell<ModifyScope>;
   fn notifier(&self) -> &Notifier;
@@ -220,6 +558,13 @@ impl<T: 'static> StateWriter for State<T> {
 
   #[inline]
   fn origin_writer(&self) -> &Self::OriginWriter { self }
+
+  fn into_reader(self) -> Result<Self::Reader, Self> {
+    match self.0.into_inner() {
+      InnerState::Data(w) => Ok(Stateful::new(w.into_inner()).clone_reader()),
+      InnerState::Stateful(w) => w.into_reader().map_err(State::stateful),
+    }
+  }
 }
 
 impl<W> State<W> {
@@ -271,7 +616,32 @@ impl<'a, V> WriteRef<'a, V> {
     let borrow = orig.value.borrow.clone();
     let value = ValueMutRef { inner, borrow };
 
-    WriteRef { value, modified: false, modify_scope: orig.modify_scope, control: orig.control }
+    WriteRef {
+      value,
+      modified: false,
+      modify_scope: orig.modify_scope,
+      control: orig.control,
+      modified_at: None,
+    }
+  }
+
+  /// Makes a new `WriteRef` for an optional part of the borrowed data, the
+  /// origin reference is returned as the error if `part_map` returns `None`.
+  pub fn filter_map<U, M>(
+    mut orig: WriteRef<'a, V>, part_map: M,
+  ) -> Result<WriteRef<'a, U>, WriteRef<'a, V>>
+  where
+    M: Fn(&mut V) -> Option<PartData<U>>,
+  {
+    match part_map(&mut orig.value) {
+      Some(inner) => {
+        let borrow = orig.value.borrow.clone();
+        let value = ValueMutRef { inner, borrow };
+        let (modify_scope, control) = (orig.modify_scope, orig.control);
+        Ok(WriteRef { value, modified: false, modify_scope, control, modified_at: None })
+      }
+      None => Err(orig),
+    }
   }
 
   pub fn map_split<U1, U2, F>(
@@ -280,14 +650,14 @@ impl<'a, V> WriteRef<'a, V> {
   where
     F: FnOnce(&mut V) -> (PartData<U1>, PartData<U2>),
   {
-    let WriteRef { control, modify_scope, modified, .. } = orig;
+    let WriteRef { control, modify_scope, modified, modified_at, .. } = orig;
     let (a, b) = f(&mut *orig.value);
     let borrow = orig.value.borrow.clone();
     let a = ValueMutRef { inner: a, borrow: borrow.clone() };
     let b = ValueMutRef { inner: b, borrow };
     (
-      WriteRef { value: a, modified, modify_scope, control },
-      WriteRef { value: b, modified, modify_scope, control },
+      WriteRef { value: a, modified, modify_scope, control, modified_at },
+      WriteRef { value: b, modified, modify_scope, control, modified_at },
     )
   }
 
@@ -296,6 +666,16 @@ impl<'a, V> WriteRef<'a, V> {
   /// is any modifies on this reference.
   #[inline]
   pub fn forget_modifies(&mut self) -> bool { std::mem::replace(&mut self.modified, false) }
+
+  fn trace_modified(&self) {
+    if let (true, Some(location)) = (self.modified, self.modified_at) {
+      AppCtx::trace_state_change(|| StateChangeTrace {
+        value_type: type_name::<V>(),
+        scope: self.modify_scope,
+        location,
+      });
+    }
+  }
 }
 
 impl<'a, W> Deref for WriteRef<'a, W> {
@@ -310,19 +690,35 @@ impl<'a, W> DerefMut for WriteRef<'a, W> {
   #[inline]
   fn deref_mut(&mut self) -> &mut Self::Target {
     self.modified = true;
+    if AppCtx::is_tracing_state_changes() {
+      self.modified_at = Some(Location::caller());
+    }
     self.value.deref_mut()
   }
 }
 
 impl<'a, W> Drop for WriteRef<'a, W> {
   fn drop(&mut self) {
+    self.trace_modified();
     let Self { control, modify_scope, modified, .. } = self;
     if !*modified {
       return;
     }
 
     let batched_modifies = control.batched_modifies();
-    if batched_modifies.get().is_empty() && !modify_scope.is_empty() {
+    // In a batch, the writer is notified when the batch finished.
+    let in_batch = BATCHED_WRITERS.with(|writers| {
+      let mut writers = writers.borrow_mut();
+      let Some(writers) = writers.as_mut() else { return false };
+      if !writers
+        .iter()
+        .any(|c| std::ptr::eq(c.batched_modifies(), batched_modifies))
+      {
+        writers.push(control.dyn_clone());
+      }
+      true
+    });
+    if batched_modifies.get().is_empty() && !modify_scope.is_empty() && !in_batch {
       batched_modifies.set(*modify_scope);
 
       let control = control.dyn_clone();
@@ -330,7 +726,10 @@ impl<'a, W> Drop for WriteRef<'a, W> {
         let scope = control
           .batched_modifies()
           .replace(ModifyScope::empty());
-        control.notifier().next(scope);
+        // the modifies may already be flushed by a batch write.
+        if !scope.is_empty() {
+          control.notifier().next(scope);
+        }
       });
     } else {
       batched_modifies.set(*modify_scope | batched_modifies.get());
@@ -480,6 +879,25 @@ mod tests {
     assert_eq!(track_map.get(), 2);
   }
 
+  #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+  #[test]
+  fn map_writer_to_nested_data() {
+    reset_test_env!();
+
+    let origin = State::value((vec![0], Box::new(Origin { a: 0, b: 0 })));
+    let elem = origin.map_writer(|v| PartData::from_ref_mut(&mut v.0[0]));
+    let boxed = origin.split_writer(|v| PartData::from_ref_mut(&mut *v.1));
+    let field = boxed.map_writer(|v| PartData::from_ref_mut(&mut v.a));
+
+    *elem.write() = 1;
+    *field.write() = 2;
+    boxed.write().b = 3;
+
+    let origin = origin.read();
+    assert_eq!(origin.0[0], 1);
+    assert_eq!((origin.1.a, origin.1.b), (2, 3));
+  }
+
   #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
   #[test]
   fn split_not_notify_origin() {
@@ -516,6 +934,575 @@ mod tests {
     assert_eq!(track_split.get(), ModifyScope::BOTH.bits());
   }
 
+  #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+  #[test]
+  fn modifies_distinct() {
+    reset_test_env!();
+
+    let origin = State::value(Origin { a: 0, b: 0 });
+    let split = origin.split_writer(|v| PartData::from_ref_mut(&mut v.b));
+
+    let track_origin = Sc::new(Cell::new(0));
+    let c_origin = track_origin.clone();
+    origin
+      .map_writer(|v| PartData::from_ref_mut(&mut v.a))
+      .modifies_distinct()
+      .subscribe(move |_| c_origin.set(c_origin.get() + 1));
+    let track_split = Sc::new(Cell::new(0));
+    let c_split = track_split.clone();
+    split
+      .modifies_distinct()
+      .subscribe(move |_| c_split.set(c_split.get() + 1));
+
+    origin.write().a = 0;
+    *split.write() = 0;
+    Timer::wake_timeout_futures();
+    AppCtx::run_until_stalled();
+    assert_eq!(track_origin.get(), 0);
+    assert_eq!(track_split.get(), 0);
+
+    origin.write().a = 1;
+    *split.write() = 1;
+    Timer::wake_timeout_futures();
+    AppCtx::run_until_stalled();
+    assert_eq!(track_origin.get(), 1);
+    assert_eq!(track_split.get(), 1);
+
+    *split.write() = 1;
+    Timer::wake_timeout_futures();
+    AppCtx::run_until_stalled();
+    assert_eq!(track_split.get(), 1);
+  }
+
+  #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+  #[test]
+  fn modifies_debounced() {
+    reset_test_env!();
+
+    let state = State::value(0);
+    let track = Sc::new(Cell::new(0));
+    let c_track = track.clone();
+    state
+      .modifies_debounced(Duration::from_secs(1))
+      .subscribe(move |_| c_track.set(c_track.get() + 1));
+
+    for i in 1..=3 {
+      *state.write() = i;
+      AppCtx::run_until_stalled();
+      Timer::advance(Duration::from_millis(500));
+      AppCtx::run_until_stalled();
+    }
+    assert_eq!(track.get(), 0);
+
+    // The trailing modify is emitted after the quiet period without new writes.
+    Timer::advance(Duration::from_secs(1));
+    AppCtx::run_until_stalled();
+    assert_eq!(track.get(), 1);
+
+    Timer::advance(Duration::from_secs(1));
+    AppCtx::run_until_stalled();
+    assert_eq!(track.get(), 1);
+  }
+
+  #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+  #[test]
+  fn modifies_throttled() {
+    reset_test_env!();
+
+    let state = State::value(0);
+    let track = Sc::new(Cell::new(0));
+    let c_track = track.clone();
+    state
+      .modifies_throttled(Duration::from_secs(1))
+      .subscribe(move |_| c_track.set(c_track.get() + 1));
+
+    // The first modify is emitted immediately.
+    *state.write() = 1;
+    AppCtx::run_until_stalled();
+    assert_eq!(track.get(), 1);
+
+    for i in 2..=3 {
+      Timer::advance(Duration::from_millis(300));
+      *state.write() = i;
+      AppCtx::run_until_stalled();
+    }
+    assert_eq!(track.get(), 1);
+
+    // The last modify within the duration is emitted when it elapsed.
+    Timer::advance(Duration::from_millis(500));
+    AppCtx::run_until_stalled();
+    assert_eq!(track.get(), 2);
+
+    // No more modifies, the window ends quietly.
+    Timer::advance(Duration::from_millis(1500));
+    AppCtx::run_until_stalled();
+    assert_eq!(track.get(), 2);
+
+    *state.write() = 4;
+    AppCtx::run_until_stalled();
+    assert_eq!(track.get(), 3);
+  }
+
+  #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+  #[test]
+  fn modifies_by_scope() {
+    reset_test_env!();
+
+    let state = State::value(0);
+    let track_data = Sc::new(Cell::new(0));
+    let track_framework = Sc::new(Cell::new(0));
+
+    let c_data = track_data.clone();
+    state
+      .data_modifies()
+      .subscribe(move |_| c_data.set(c_data.get() + 1));
+    let c_framework = track_framework.clone();
+    state
+      .framework_modifies()
+      .subscribe(move |_| c_framework.set(c_framework.get() + 1));
+
+    *state.shallow() = 1;
+    Timer::wake_timeout_futures();
+    AppCtx::run_until_stalled();
+    assert_eq!(track_data.get(), 0);
+    assert_eq!(track_framework.get(), 1);
+
+    *state.silent() = 2;
+    Timer::wake_timeout_futures();
+    AppCtx::run_until_stalled();
+    assert_eq!(track_data.get(), 1);
+    assert_eq!(track_framework.get(), 1);
+
+    *state.write() = 3;
+    Timer::wake_timeout_futures();
+    AppCtx::run_until_stalled();
+    assert_eq!(track_data.get(), 2);
+    assert_eq!(track_framework.get(), 2);
+  }
+
+  #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+  #[test]
+  fn watch_map() {
+    reset_test_env!();
+
+    let origin = State::value(Origin { a: 0, b: 0 });
+    let emitted = Sc::new(RefCell::new(vec![]));
+    let c_emitted = emitted.clone();
+    origin
+      .watch_map(|v| v.a > 0)
+      .subscribe(move |v| c_emitted.borrow_mut().push(v));
+    assert_eq!(&*emitted.borrow(), &[false]);
+
+    origin.write().b = 1;
+    origin.write().b = 2;
+    Timer::wake_timeout_futures();
+    AppCtx::run_until_stalled();
+    assert_eq!(&*emitted.borrow(), &[false]);
+
+    origin.write().a = 1;
+    Timer::wake_timeout_futures();
+    AppCtx::run_until_stalled();
+    origin.write().a = 2;
+    Timer::wake_timeout_futures();
+    AppCtx::run_until_stalled();
+    assert_eq!(&*emitted.borrow(), &[false, true]);
+
+    origin.write().a = 0;
+    Timer::wake_timeout_futures();
+    AppCtx::run_until_stalled();
+    assert_eq!(&*emitted.borrow(), &[false, true, false]);
+  }
+
+  #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+  #[test]
+  fn watch_map_subscribe_later() {
+    reset_test_env!();
+
+    let origin = State::value(0);
+    let projected = origin.watch_map(|v| *v * 2);
+    *origin.write() = 1;
+    Timer::wake_timeout_futures();
+    AppCtx::run_until_stalled();
+
+    let emitted = Sc::new(RefCell::new(vec![]));
+    let c_emitted = emitted.clone();
+    projected.subscribe(move |v| c_emitted.borrow_mut().push(v));
+    // Start with the value when subscribed, not when the stream was created.
+    assert_eq!(&*emitted.borrow(), &[2]);
+  }
+
+  #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+  #[test]
+  fn batch_notify_once() {
+    reset_test_env!();
+
+    let state = Stateful::new((0, 0, 0));
+    let track = Sc::new(Cell::new(0));
+    let c_track = track.clone();
+    state
+      .modifies()
+      .subscribe(move |_| c_track.set(c_track.get() + 1));
+
+    state.write().0 = 1;
+    state.batch(|| {
+      state.write().0 = 2;
+      state.write().1 = 2;
+      state.write().2 = 2;
+    });
+    // notified immediately, and merge the pending modifies.
+    assert_eq!(track.get(), 1);
+
+    Timer::wake_timeout_futures();
+    AppCtx::run_until_stalled();
+    assert_eq!(track.get(), 1);
+    assert_eq!(*state.read(), (2, 2, 2));
+  }
+
+  #[test]
+  fn batch_ends_after_panic() {
+    reset_test_env!();
+
+    let state = Stateful::new(0);
+    let track = Sc::new(Cell::new(0));
+    let c_track = track.clone();
+    state
+      .modifies()
+      .subscribe(move |_| c_track.set(c_track.get() + 1));
+
+    let panicked = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+      state.batch(|| {
+        *state.write() = 1;
+        panic!("panic in the batch");
+      })
+    }));
+    assert!(panicked.is_err());
+    // The modifies before the panic are notified.
+    assert_eq!(track.get(), 1);
+
+    // The later writes are not batched.
+    *state.write() = 2;
+    Timer::wake_timeout_futures();
+    AppCtx::run_until_stalled();
+    assert_eq!(track.get(), 2);
+  }
+
+  #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+  #[test]
+  fn batch_with_part_writers() {
+    reset_test_env!();
+
+    let origin = Stateful::new(Origin { a: 0, b: 0 });
+    let split = origin.split_writer(|v| PartData::from_ref_mut(&mut v.a));
+    let map = origin.map_writer(|v| PartData::from_ref_mut(&mut v.b));
+    let (origin_track, split_track) = (Sc::new(Cell::new(0)), Sc::new(Cell::new(0)));
+    let c_origin_track = origin_track.clone();
+    origin
+      .modifies()
+      .subscribe(move |_| c_origin_track.set(c_origin_track.get() + 1));
+    let c_split_track = split_track.clone();
+    split
+      .modifies()
+      .subscribe(move |_| c_split_track.set(c_split_track.get() + 1));
+
+    origin.batch(|| {
+      *split.write() = 1;
+      *map.write() = 1;
+      *split.write() = 2;
+      origin.write().b = 2;
+      // Not notified until the batch finished.
+      assert_eq!(origin_track.get(), 0);
+      assert_eq!(split_track.get(), 0);
+    });
+    assert_eq!(origin_track.get(), 1);
+    assert_eq!(split_track.get(), 1);
+
+    Timer::wake_timeout_futures();
+    AppCtx::run_until_stalled();
+    assert_eq!(origin_track.get(), 1);
+    assert_eq!(split_track.get(), 1);
+    assert_eq!(origin.read().a, 2);
+    assert_eq!(origin.read().b, 2);
+  }
+
+  #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+  #[test]
+  fn map_writer_into_reader() {
+    reset_test_env!();
+
+    let origin = Stateful::new(Origin { a: 0, b: 0 });
+    let map = origin.map_writer(|v| PartData::from_ref_mut(&mut v.b));
+    let map2 = map.clone_writer();
+
+    let map = map.into_reader().err().unwrap();
+    drop(map2);
+    // The origin is still alive, only the clones of the map writer are counted.
+    let reader = map.into_reader().ok().unwrap();
+
+    origin.write().b = 1;
+    assert_eq!(*reader.read(), 1);
+  }
+
+  #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+  #[test]
+  fn split_writer_into_reader() {
+    reset_test_env!();
+
+    let origin = Stateful::new(Origin { a: 0, b: 0 });
+    let split = origin.split_writer(|v| PartData::from_ref_mut(&mut v.b));
+    let split2 = split.clone_writer();
+
+    let split = split.into_reader().err().unwrap();
+    drop(split2);
+    let reader = split.into_reader().ok().unwrap();
+
+    origin.write().b = 1;
+    assert_eq!(*reader.read(), 1);
+  }
+
+  #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+  #[test]
+  fn split_writer_try_into_value() {
+    reset_test_env!();
+
+    let origin = Stateful::new(Origin { a: 0, b: 0 });
+    let split = origin.split_writer(PartData::from_ref_mut);
+    let split2 = split.split_writer(PartData::from_ref_mut);
+    let watcher = split2.clone_watcher();
+
+    // The origin, the split and the watcher are alive.
+    let split2 = split2.try_into_value().err().unwrap();
+    drop(origin);
+    drop(split);
+    let split2 = split2.try_into_value().err().unwrap();
+    drop(watcher);
+    let Origin { a, b } = split2.try_into_value().ok().unwrap();
+    assert_eq!((a, b), (0, 0));
+
+    // Reclaim the value by a watcher of the split chain.
+    let origin = Stateful::new(Origin { a: 1, b: 1 });
+    let watcher = origin
+      .split_writer(PartData::from_ref_mut)
+      .clone_watcher();
+    let watcher = watcher.try_into_value().err().unwrap();
+    drop(origin);
+    AppCtx::run_until_stalled();
+    let Origin { a, b } = watcher.try_into_value().ok().unwrap();
+    assert_eq!((a, b), (1, 1));
+
+    // A part of the origin value can't be taken out.
+    let origin = Stateful::new(Origin { a: 0, b: 0 });
+    let split_b = origin.split_writer(|v| PartData::from_ref_mut(&mut v.b));
+    drop(origin);
+    let split_b = split_b.try_into_value().err().unwrap();
+    assert!(split_b.try_into_origin().is_ok());
+
+    // A field of another type is not the whole origin, even if it has the same
+    // size and address.
+    struct Name(String);
+    let origin = Stateful::new(Name("Ribir".to_string()));
+    let split = origin.split_writer(|v| PartData::from_ref_mut(&mut v.0));
+    drop(origin);
+    let split = split.try_into_value().err().unwrap();
+    let origin = split.try_into_origin().ok().unwrap();
+    let Name(name) = origin.try_into_value().ok().unwrap();
+    assert_eq!(name, "Ribir");
+  }
+
+  #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+  #[test]
+  fn split_writer_reclaim_origin_value() {
+    reset_test_env!();
+
+    let origin = Stateful::new(Origin { a: 0, b: 0 });
+    let split = origin.split_writer(|v| PartData::from_ref_mut(&mut v.b));
+    let split_b = split.split_writer(PartData::from_ref_mut);
+    *split_b.write() = 1;
+    // Wait the batched modifies to be notified, they hold the writers.
+    AppCtx::run_until_stalled();
+    let split_b2 = split_b.clone_writer();
+    drop(origin);
+
+    // Another writer of the split is alive.
+    let split_b = split_b.try_into_origin().err().unwrap();
+    drop(split_b2);
+    let split2 = split_b.try_into_origin().ok().unwrap();
+
+    // Unwind the chain to the root.
+    let split2 = split2.try_into_origin().err().unwrap();
+    drop(split);
+    let origin = split2.try_into_origin().ok().unwrap();
+    let Origin { a, b } = origin.try_into_value().ok().unwrap();
+    assert_eq!((a, b), (0, 1));
+  }
+
+  #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+  #[test]
+  fn filter_map_writer_of_enum_variant() {
+    reset_test_env!();
+
+    enum Shape {
+      Circle(f32),
+      Square(f32),
+    }
+
+    let shape = Stateful::new(Shape::Circle(1.));
+    let radius = shape.filter_map_writer(|s| match s {
+      Shape::Circle(r) => Some(PartData::from_ref_mut(r)),
+      Shape::Square(_) => None,
+    });
+    let notified = Sc::new(Cell::new(0));
+    let c_notified = notified.clone();
+    radius
+      .modifies()
+      .subscribe(move |_| c_notified.set(c_notified.get() + 1));
+
+    *radius.try_write().unwrap() = 2.;
+    AppCtx::run_until_stalled();
+    assert_eq!(notified.get(), 1);
+    assert!(matches!(*shape.read(), Shape::Circle(r) if r == 2.));
+
+    // The variant changed, the part is unavailable rather than panic.
+    *shape.write() = Shape::Square(3.);
+    assert!(!radius.is_available());
+    assert!(radius.try_read().is_none());
+    assert!(radius.try_write().is_none());
+    assert!(matches!(*shape.read(), Shape::Square(s) if s == 3.));
+
+    *shape.write() = Shape::Circle(4.);
+    assert_eq!(radius.clone_writer().try_read().map(|r| *r), Some(4.));
+
+    // It's a `StateWriter` while the part exists.
+    *radius.write() = 5.;
+    AppCtx::run_until_stalled();
+    assert_eq!(notified.get(), 2);
+    assert_eq!(*radius.read(), 5.);
+    let reader = radius.clone_reader();
+    *shape.write() = Shape::Square(6.);
+    assert!(reader.try_read().is_none());
+  }
+
+  #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+  #[test]
+  fn part_writer_clone_reader() {
+    reset_test_env!();
+
+    let origin = Stateful::new(Origin { a: 0, b: 0 });
+    let split = origin.split_writer(|v| PartData::from_ref_mut(&mut v.b));
+    let map = origin.map_writer(|v| PartData::from_ref_mut(&mut v.a));
+
+    // The readers of the part writers only hold the reader of the origin, they
+    // can't be written.
+    let split_reader: MapWriterAsReader<Reader<Origin>, _> = split.clone_reader();
+    let map_reader: MapWriterAsReader<Reader<Origin>, _> = map.clone_reader();
+
+    origin.write().a = 1;
+    origin.write().b = 2;
+    assert_eq!(*map_reader.read(), 1);
+    assert_eq!(*split_reader.read(), 2);
+
+    drop(split);
+    drop(map);
+    assert_eq!(*split_reader.clone_reader().read(), 2);
+  }
+
+  struct Todo {
+    id: usize,
+    done: bool,
+  }
+
+  fn todos() -> Stateful<Vec<Todo>> {
+    Stateful::new((0..3).map(|id| Todo { id, done: false }).collect())
+  }
+
+  #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+  #[test]
+  fn index_writer_track_key() {
+    reset_test_env!();
+
+    let todos = todos();
+    let first = todos.index_writer(1, |t: &Todo| t.id);
+    let second = todos.index_writer(2, |t: &Todo| t.id);
+
+    let track_first = Sc::new(Cell::new(0));
+    let track_second = Sc::new(Cell::new(0));
+    let c_first = track_first.clone();
+    first
+      .modifies()
+      .subscribe(move |_| c_first.set(c_first.get() + 1));
+    let c_second = track_second.clone();
+    second
+      .modifies()
+      .subscribe(move |_| c_second.set(c_second.get() + 1));
+
+    todos.write().remove(0);
+    second.write().done = true;
+    Timer::wake_timeout_futures();
+    AppCtx::run_until_stalled();
+
+    assert_eq!(track_first.get(), 0);
+    assert_eq!(track_second.get(), 1);
+    assert!(!todos.read()[0].done);
+    assert!(todos.read()[1].done);
+    assert_eq!(second.read().id, 2);
+  }
+
+  #[test]
+  #[should_panic = "The element with key `1` does not exist in the list."]
+  fn index_writer_removed() {
+    reset_test_env!();
+
+    let todos = todos();
+    let first = todos.index_writer(1, |t: &Todo| t.id);
+    todos.write().retain(|t| t.id != 1);
+    let _ = first.read();
+  }
+
+  #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+  #[test]
+  fn for_each_writer_notify_items() {
+    reset_test_env!();
+
+    let todos = todos().for_each_writer();
+    let first = todos.item_writer(0, |t: &Todo| t.id);
+    let second = todos.item_writer(1, |t: &Todo| t.id);
+
+    let track_first = Sc::new(Cell::new(0));
+    let track_second = Sc::new(Cell::new(0));
+    let c_first = track_first.clone();
+    first
+      .modifies()
+      .subscribe(move |_| c_first.set(c_first.get() + 1));
+    let c_second = track_second.clone();
+    second
+      .modifies()
+      .subscribe(move |_| c_second.set(c_second.get() + 1));
+
+    todos
+      .write()
+      .iter_mut()
+      .for_each(|t| t.done = true);
+    Timer::wake_timeout_futures();
+    AppCtx::run_until_stalled();
+    assert_eq!(track_first.get(), 1);
+    assert_eq!(track_second.get(), 1);
+
+    // Write an item not notify the other items.
+    second.write().done = false;
+    Timer::wake_timeout_futures();
+    AppCtx::run_until_stalled();
+    assert_eq!(track_first.get(), 1);
+    assert_eq!(track_second.get(), 2);
+
+    // The removed item is not notified anymore.
+    todos.write().remove(0);
+    todos.write().push(Todo { id: 3, done: false });
+    Timer::wake_timeout_futures();
+    AppCtx::run_until_stalled();
+    assert_eq!(track_first.get(), 1);
+    assert_eq!(track_second.get(), 3);
+    assert_eq!(second.read().id, 1);
+  }
+
   struct C;
 
   impl Compose for C {
@@ -645,4 +1632,25 @@ mod tests {
         .split_writer(|v| PartData::from_ref_mut(&mut v.0))
     };
   }
+
+  #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+  #[test]
+  fn set_if_changed() {
+    reset_test_env!();
+
+    let state = State::value(0);
+    let track = Sc::new(Cell::new(0));
+    let c_track = track.clone();
+    state
+      .modifies()
+      .subscribe(move |_| c_track.set(c_track.get() + 1));
+
+    assert!(state.set_if_changed(1));
+    AppCtx::run_until_stalled();
+    assert!(!state.set_if_changed(1));
+    AppCtx::run_until_stalled();
+
+    assert_eq!(*state.read(), 1);
+    assert_eq!(track.get(), 1);
+  }
 }
@@ -0,0 +1,117 @@
+use std::{cell::Cell, rc::Rc};
+
+use super::{ReadRef, StateWriter, WriteRef};
+use crate::{context::AppCtx, ticker::Duration, timer::Timer};
+
+/// A wrapper of a `StateWriter` that applies the writes to the value
+/// immediately, but defers the notification to the framework, so a burst of
+/// writes, like dragging a control, regenerates the view at most once within
+/// every `interval`.
+///
+/// The writes notify the data subscribers as a `silent` write does. The
+/// framework is notified `interval` after the first write that is not
+/// notified yet, so the last write of a burst is always notified.
+///
+/// The interval is timed by the clock of `Timer`, so it's driven by the tasks
+/// of `AppCtx` and can be advanced by `Timer::advance` in tests.
+pub struct DeferredWriter<W: StateWriter> {
+  writer: W,
+  interval: Duration,
+  pending: Rc<Cell<bool>>,
+}
+
+impl<W: StateWriter> DeferredWriter<W> {
+  /// Create a deferred writer of the `writer`, notify the framework at most
+  /// once within every `interval`.
+  pub fn new(writer: W, interval: Duration) -> Self {
+    Self { writer, interval, pending: Rc::new(Cell::new(false)) }
+  }
+
+  /// Return a write reference of the state, the framework is notified later.
+  pub fn write(&self) -> WriteRef<W::Value> {
+    if !self.pending.replace(true) {
+      let writer = self.writer.clone_writer();
+      let pending = self.pending.clone();
+      let timer = Timer::new(Timer::now() + self.interval);
+      let _ = AppCtx::spawn_local(async move {
+        timer.await;
+        if pending.replace(false) {
+          notify_framework(&writer);
+        }
+      });
+    }
+    self.writer.silent()
+  }
+
+  /// Return a reference of the state, it always reads the latest value even
+  /// if the framework is not notified yet.
+  pub fn read(&self) -> ReadRef<W::Value> { self.writer.read() }
+
+  /// Notify the framework of the pending writes now, return `false` if there
+  /// are none.
+  pub fn flush(&self) -> bool {
+    let pending = self.pending.replace(false);
+    if pending {
+      notify_framework(&self.writer);
+    }
+    pending
+  }
+
+  /// Whether there are writes that the framework is not notified of.
+  pub fn is_pending(&self) -> bool { self.pending.get() }
+
+  /// The writer this deferred writer wraps.
+  pub fn writer(&self) -> &W { &self.writer }
+}
+
+fn notify_framework<W: StateWriter>(writer: &W) {
+  // A shallow modify notifies the framework only.
+  let mut w = writer.shallow();
+  let _ = &mut *w;
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  #[cfg(target_arch = "wasm32")]
+  use crate::test_helper::wasm_bindgen_test;
+  use crate::{prelude::*, reset_test_env};
+
+  #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+  #[test]
+  fn coalesce_notifications() {
+    reset_test_env!();
+
+    let state = Stateful::new(0);
+    let notified = Rc::new(Cell::new(0));
+    let c_notified = notified.clone();
+    state
+      .framework_modifies()
+      .subscribe(move |_| c_notified.set(c_notified.get() + 1));
+
+    let deferred = DeferredWriter::new(state.clone_writer(), Duration::from_secs(1));
+    for i in 1..=3 {
+      *deferred.write() = i;
+      AppCtx::run_until_stalled();
+      Timer::advance(Duration::from_millis(200));
+      AppCtx::run_until_stalled();
+    }
+    // The reads see the latest value before the notification.
+    assert_eq!(*deferred.read(), 3);
+    assert_eq!(notified.get(), 0);
+
+    Timer::advance(Duration::from_millis(400));
+    AppCtx::run_until_stalled();
+    assert_eq!(notified.get(), 1);
+    assert!(!deferred.is_pending());
+
+    // The trailing write of a burst is notified without more activity.
+    *deferred.write() = 4;
+    AppCtx::run_until_stalled();
+    assert_eq!(notified.get(), 1);
+    Timer::advance(Duration::from_secs(1));
+    AppCtx::run_until_stalled();
+    assert_eq!(notified.get(), 2);
+    assert_eq!(*state.read(), 4);
+  }
+}
@@ -0,0 +1,202 @@
+use std::cell::{Cell, RefCell};
+
+use ribir_algo::Sc;
+use rxrust::{ops::box_it::CloneableBoxOp, prelude::*};
+
+use super::{
+  index_part, state_cell::PartData, ModifyScope, Notifier, ReadRef, SplittedWriter, StateReader,
+  StateWatcher, StateWriter, WriteRef, WriterControl,
+};
+use crate::{prelude::AppCtx, state::state_cell::ValueMutRef};
+
+/// A writer of a list state that fans out its writes to the writers of the
+/// items split from it by [`ForEachWriter::item_writer`].
+///
+/// A writer split from a list state has its own notifier, so writing the whole
+/// list through the origin writer not notify it. Write the list through this
+/// writer instead, all its item writers are notified with the same modify
+/// scope, the origin state is also notified as a normal write.
+///
+/// The items are tracked by their keys, so the rows added or removed between
+/// the writes are fine. An item writer is only notified if its item still
+/// exists in the list when the write is notified.
+pub struct ForEachWriter<O> {
+  origin: O,
+  notifier: Notifier,
+  batched_modify: Sc<Cell<ModifyScope>>,
+  items: Sc<RefCell<Vec<ItemNotifier>>>,
+  ref_count: Sc<Cell<usize>>,
+}
+
+struct ItemNotifier {
+  notifier: Notifier,
+  exists: Box<dyn Fn() -> bool>,
+}
+
+impl<O> Drop for ForEachWriter<O> {
+  fn drop(&mut self) {
+    let count = self.ref_count.get() - 1;
+    self.ref_count.set(count);
+    if count == 0 {
+      let mut notifier = self.notifier.clone();
+      // we use an async task to unsubscribe to wait the batched modifies to be
+      // notified.
+      let _ = AppCtx::spawn_local(async move {
+        notifier.unsubscribe();
+      });
+    }
+  }
+}
+
+impl<O: StateWriter> StateReader for ForEachWriter<O> {
+  type Value = O::Value;
+  type OriginReader = O;
+  type Reader = O::Reader;
+
+  #[inline]
+  fn read(&self) -> ReadRef<Self::Value> { self.origin.read() }
+
+  #[inline]
+  fn clone_reader(&self) -> Self::Reader { self.origin.clone_reader() }
+
+  #[inline]
+  fn origin_reader(&self) -> &Self::OriginReader { &self.origin }
+
+  #[inline]
+  fn try_into_value(self) -> Result<Self::Value, Self>
+  where
+    Self::Value: Sized,
+  {
+    Err(self)
+  }
+}
+
+impl<O: StateWriter> StateWatcher for ForEachWriter<O> {
+  #[inline]
+  fn raw_modifies(&self) -> CloneableBoxOp<'static, ModifyScope, std::convert::Infallible> {
+    self.origin.raw_modifies()
+  }
+}
+
+impl<O: StateWriter> StateWriter for ForEachWriter<O> {
+  type Writer = ForEachWriter<O::Writer>;
+  type OriginWriter = O;
+
+  #[track_caller]
+  #[inline]
+  fn write(&self) -> WriteRef<Self::Value> { self.fan_out_ref(self.origin.write()) }
+
+  #[track_caller]
+  #[inline]
+  fn silent(&self) -> WriteRef<Self::Value> { self.fan_out_ref(self.origin.silent()) }
+
+  #[track_caller]
+  #[inline]
+  fn shallow(&self) -> WriteRef<Self::Value> { self.fan_out_ref(self.origin.shallow()) }
+
+  fn clone_writer(&self) -> Self::Writer {
+    self.ref_count.set(self.ref_count.get() + 1);
+    ForEachWriter {
+      origin: self.origin.clone_writer(),
+      notifier: self.notifier.clone(),
+      batched_modify: self.batched_modify.clone(),
+      items: self.items.clone(),
+      ref_count: self.ref_count.clone(),
+    }
+  }
+
+  #[inline]
+  fn origin_writer(&self) -> &Self::OriginWriter { &self.origin }
+
+  fn into_reader(self) -> Result<Self::Reader, Self> {
+    if self.ref_count.get() == 1 { Ok(self.clone_reader()) } else { Err(self) }
+  }
+}
+
+impl<O: StateWriter> WriterControl for ForEachWriter<O> {
+  #[inline]
+  fn batched_modifies(&self) -> &Cell<ModifyScope> { &self.batched_modify }
+
+  #[inline]
+  fn notifier(&self) -> &Notifier { &self.notifier }
+
+  #[inline]
+  fn dyn_clone(&self) -> Box<dyn WriterControl> { Box::new(self.clone_writer()) }
+}
+
+impl<O: StateWriter> ForEachWriter<O> {
+  pub(super) fn new(origin: O) -> Self {
+    let notifier = Notifier::default();
+    let items: Sc<RefCell<Vec<ItemNotifier>>> = <_>::default();
+    let c_items = items.clone();
+    notifier.raw_modifies().subscribe(move |scope| {
+      let notifiers: Vec<_> = {
+        let mut items = c_items.borrow_mut();
+        // The item writers were all dropped.
+        items.retain(|item| !item.notifier.is_closed());
+        items
+          .iter()
+          .filter(|item| (item.exists)())
+          .map(|item| item.notifier.clone())
+          .collect()
+      };
+      // Release the borrow before notify, the subscribers may split new items.
+      notifiers.into_iter().for_each(|n| n.next(scope));
+    });
+
+    Self {
+      origin,
+      notifier,
+      batched_modify: <_>::default(),
+      items,
+      ref_count: Sc::new(Cell::new(1)),
+    }
+  }
+
+  /// Return a writer of the element in the list that be identified by `key`,
+  /// like `index_writer`. But the return writer is also notified when the list
+  /// is written by this writer.
+  ///
+  /// ##Panics
+  ///
+  /// Read or write the return writer will panic if the element with `key` was
+  /// removed from the list.
+  pub fn item_writer<Item, K>(
+    &self, key: K, key_of: impl Fn(&Item) -> K + Clone + 'static,
+  ) -> SplittedWriter<O::Writer, impl Fn(&mut O::Value) -> PartData<Item> + Clone + 'static>
+  where
+    O::Value: AsRef<[Item]> + AsMut<[Item]>,
+    Item: 'static,
+    K: PartialEq + std::fmt::Debug + Clone + 'static,
+  {
+    let writer = self
+      .origin
+      .split_writer(index_part(key.clone(), key_of.clone()));
+    let reader = self.origin.clone_reader();
+    let exists = move || {
+      let list = reader.read();
+      AsRef::<[Item]>::as_ref(&*list)
+        .iter()
+        .any(|item| key_of(item) == key)
+    };
+    self.items.borrow_mut().push(ItemNotifier {
+      notifier: WriterControl::notifier(&writer).clone(),
+      exists: Box::new(exists),
+    });
+    writer
+  }
+
+  #[track_caller]
+  fn fan_out_ref<'a>(&'a self, mut orig: WriteRef<'a, O::Value>) -> WriteRef<'a, O::Value> {
+    let modify_scope = orig.modify_scope;
+
+    // The origin is notified as a normal write, and the item writers are notified
+    // by the notifier of this writer.
+    assert!(!orig.modified);
+    orig.modified = true;
+    let inner = PartData::from_ref_mut(&mut *orig.value);
+    let value = ValueMutRef { inner, borrow: orig.value.borrow.clone() };
+
+    WriteRef { value, modified: false, modify_scope, control: self, modified_at: None }
+  }
+}
@@ -0,0 +1,204 @@
+use std::{cell::RefCell, collections::VecDeque};
+
+use super::{StateWriter, WriteRef};
+use crate::{
+  ticker::{Duration, Instant},
+  timer::Timer,
+};
+
+/// A wrapper of a `StateWriter` that records the history of the state value,
+/// so the modifies can be undone and redone.
+///
+/// Only the modifies across `History::write` are recorded. The value before
+/// the write is kept as a snapshot, and the writes within the `debounce`
+/// duration of the last one are coalesced into one step, so a continuous
+/// modify like dragging a slider can be undone in one step.
+pub struct History<W: StateWriter>
+where
+  W::Value: Clone,
+{
+  writer: W,
+  capacity: usize,
+  debounce: Duration,
+  record: RefCell<HistoryRecord<W::Value>>,
+}
+
+struct HistoryRecord<V> {
+  undo: VecDeque<V>,
+  redo: Vec<V>,
+  last_write: Option<Instant>,
+}
+
+impl<W: StateWriter> History<W>
+where
+  W::Value: Clone,
+{
+  /// Create a history of the `writer`, keep at most `capacity` undo steps.
+  pub fn new(writer: W, capacity: usize, debounce: Duration) -> Self {
+    let record = HistoryRecord { undo: VecDeque::new(), redo: vec![], last_write: None };
+    Self { writer, capacity, debounce, record: RefCell::new(record) }
+  }
+
+  /// Return a write reference of the state, the value before this write is
+  /// recorded as an undo step if it's not coalesced with the last write.
+  ///
+  /// A new write always clears the redo steps.
+  pub fn write(&self) -> WriteRef<W::Value> {
+    let mut record = self.record.borrow_mut();
+    let now = Timer::now();
+    let coalesced = record
+      .last_write
+      .map_or(false, |last| now.duration_since(last) < self.debounce);
+    if !coalesced {
+      let snapshot = self.writer.read().clone();
+      record.push_undo(snapshot, self.capacity);
+    }
+    record.last_write = Some(now);
+    record.redo.clear();
+
+    self.writer.write()
+  }
+
+  /// Restore the state to the value before the last step, return `false` if
+  /// there is nothing to undo.
+  pub fn undo(&self) -> bool {
+    let mut record = self.record.borrow_mut();
+    let Some(value) = record.undo.pop_back() else { return false };
+    let current = std::mem::replace(&mut *self.writer.write(), value);
+    record.redo.push(current);
+    record.last_write = None;
+    true
+  }
+
+  /// Restore the state to the value before the last undo, return `false` if
+  /// there is nothing to redo.
+  pub fn redo(&self) -> bool {
+    let mut record = self.record.borrow_mut();
+    let Some(value) = record.redo.pop() else { return false };
+    let current = std::mem::replace(&mut *self.writer.write(), value);
+    record.push_undo(current, self.capacity);
+    record.last_write = None;
+    true
+  }
+
+  pub fn can_undo(&self) -> bool { !self.record.borrow().undo.is_empty() }
+
+  pub fn can_redo(&self) -> bool { !self.record.borrow().redo.is_empty() }
+
+  /// The max count of the undo steps, the oldest step will be dropped if the
+  /// steps exceed it.
+  pub fn capacity(&self) -> usize { self.capacity }
+
+  /// The writer this history wraps.
+  pub fn writer(&self) -> &W { &self.writer }
+}
+
+impl<V> HistoryRecord<V> {
+  fn push_undo(&mut self, value: V, capacity: usize) {
+    self.undo.push_back(value);
+    while self.undo.len() > capacity {
+      self.undo.pop_front();
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  #[cfg(target_arch = "wasm32")]
+  use crate::test_helper::wasm_bindgen_test;
+  use crate::{prelude::*, reset_test_env};
+
+  #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+  #[test]
+  fn undo_redo() {
+    reset_test_env!();
+
+    let history = History::new(State::value(0), 10, Duration::ZERO);
+    assert!(!history.can_undo());
+
+    *history.write() = 1;
+    *history.write() = 2;
+    assert!(history.undo());
+    assert_eq!(*history.writer().read(), 1);
+    assert!(history.undo());
+    assert_eq!(*history.writer().read(), 0);
+    assert!(!history.undo());
+
+    assert!(history.redo());
+    assert!(history.redo());
+    assert_eq!(*history.writer().read(), 2);
+    assert!(!history.redo());
+  }
+
+  #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+  #[test]
+  fn capacity_eviction() {
+    reset_test_env!();
+
+    let history = History::new(State::value(0), 2, Duration::ZERO);
+    for i in 1..=5 {
+      *history.write() = i;
+    }
+
+    assert!(history.undo());
+    assert!(history.undo());
+    assert!(!history.can_undo());
+    assert_eq!(*history.writer().read(), 3);
+  }
+
+  #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+  #[test]
+  fn new_edit_invalidate_redo() {
+    reset_test_env!();
+
+    let history = History::new(State::value(0), 10, Duration::ZERO);
+    *history.write() = 1;
+    *history.write() = 2;
+    history.undo();
+    assert!(history.can_redo());
+
+    *history.write() = 3;
+    assert!(!history.can_redo());
+    history.undo();
+    assert_eq!(*history.writer().read(), 1);
+  }
+
+  #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+  #[test]
+  fn coalesce_rapid_writes() {
+    reset_test_env!();
+
+    let history = History::new(State::value(0), 10, Duration::from_secs(60));
+    for i in 1..=100 {
+      *history.write() = i;
+    }
+    assert!(history.undo());
+    assert_eq!(*history.writer().read(), 0);
+    assert!(!history.can_undo());
+
+    // An undo ends the coalescing, the next write is a new step.
+    history.redo();
+    *history.write() = 101;
+    history.undo();
+    assert_eq!(*history.writer().read(), 100);
+  }
+  #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+  #[test]
+  fn debounce_by_timer() {
+    reset_test_env!();
+
+    let history = History::new(State::value(0), 10, Duration::from_secs(1));
+    *history.write() = 1;
+    Timer::advance(Duration::from_millis(500));
+    *history.write() = 2;
+    Timer::advance(Duration::from_secs(1));
+    *history.write() = 3;
+
+    assert!(history.undo());
+    assert_eq!(*history.writer().read(), 2);
+    assert!(history.undo());
+    assert_eq!(*history.writer().read(), 0);
+    assert!(!history.can_undo());
+  }
+}
@@ -1,9 +1,11 @@
-use std::convert::Infallible;
+use std::{cell::Cell, convert::Infallible};
 
+use ribir_algo::Sc;
 use rxrust::ops::box_it::CloneableBoxOp;
 
 use super::{
-  state_cell::PartData, ModifyScope, ReadRef, StateReader, StateWatcher, StateWriter, WriteRef,
+  state_cell::{is_whole_part, take_whole_part, PartData},
+  ModifyScope, ReadRef, StateReader, StateWatcher, StateWriter, WriteRef,
 };
 use crate::{
   context::BuildCtx,
@@ -21,6 +23,8 @@ pub struct MapReader<S, F> {
 pub struct MapWriter<W, WM> {
   pub(super) origin: W,
   pub(super) part_map: WM,
+  /// The count of the clones of this map writer.
+  ref_count: Sc<Cell<usize>>,
 }
 
 pub struct MapWriterAsReader<W, M> {
@@ -28,6 +32,30 @@ pub struct MapWriterAsReader<W, M> {
   pub(super) part_map: M,
 }
 
+/// A writer that map a writer to a part may not exist of the origin writer,
+/// e.g. the payload of an enum variant. Like `MapWriter`, this writer shares
+/// the notifier with the origin writer.
+///
+/// Use `try_read`, `try_write` and `try_silent` to access the part, they return
+/// `None` if the part not exists now, so the writer not panic when the variant
+/// changed.
+///
+/// ##Panics
+///
+/// The methods of `StateReader` and `StateWriter`, like `read` and `write`,
+/// panic if the part not exists.
+pub struct FilterMapWriter<W, M> {
+  pub(super) origin: W,
+  pub(super) part_map: M,
+  /// The count of the clones of this filter map writer.
+  ref_count: Sc<Cell<usize>>,
+}
+
+pub struct FilterMapWriterAsReader<W, M> {
+  pub(super) origin: W,
+  pub(super) part_map: M,
+}
+
 impl<S, V, M> StateReader for MapReader<S, M>
 where
   Self: 'static,
@@ -58,7 +86,7 @@ where
   }
 }
 
-impl<S, V, M> StateReader for MapWriterAsReader<S, M>
+impl<S, V: 'static, M> StateReader for MapWriterAsReader<S, M>
 where
   Self: 'static,
   S: StateReader,
@@ -81,16 +109,24 @@ where
   #[inline]
   fn origin_reader(&self) -> &Self::OriginReader { &self.origin }
 
-  #[inline]
+  /// The part can be taken out only if it's the whole value of the origin.
   fn try_into_value(self) -> Result<Self::Value, Self>
   where
     Self::Value: Sized,
   {
-    Err(self)
+    if !is_whole_part(&*self.origin.read(), &*self.read()) {
+      return Err(self);
+    }
+    let Self { origin, part_map } = self;
+    match origin.try_into_value() {
+      // Safety: the part is checked to be the whole origin.
+      Ok(value) => Ok(unsafe { take_whole_part(value) }),
+      Err(origin) => Err(Self { origin, part_map }),
+    }
   }
 }
 
-impl<V, S, M> StateReader for MapWriter<S, M>
+impl<V: 'static, S, M> StateReader for MapWriter<S, M>
 where
   Self: 'static,
   S: StateWriter,
@@ -122,7 +158,7 @@ where
   }
 }
 
-impl<V, W, M> StateWatcher for MapWriter<W, M>
+impl<V: 'static, W, M> StateWatcher for MapWriter<W, M>
 where
   Self: 'static,
   W: StateWriter,
@@ -134,7 +170,7 @@ where
   }
 }
 
-impl<V, W, M> StateWriter for MapWriter<W, M>
+impl<V: 'static, W, M> StateWriter for MapWriter<W, M>
 where
   Self: 'static,
   W: StateWriter,
@@ -156,11 +192,205 @@ where
 
   #[inline]
   fn clone_writer(&self) -> Self::Writer {
-    MapWriter { origin: self.origin.clone_writer(), part_map: self.part_map.clone() }
+    self.ref_count.set(self.ref_count.get() + 1);
+    MapWriter {
+      origin: self.origin.clone_writer(),
+      part_map: self.part_map.clone(),
+      ref_count: self.ref_count.clone(),
+    }
+  }
+
+  #[inline]
+  fn origin_writer(&self) -> &Self::OriginWriter { &self.origin }
+
+  fn into_reader(self) -> Result<Self::Reader, Self> {
+    if self.ref_count.get() == 1 { Ok(self.clone_reader()) } else { Err(self) }
+  }
+}
+
+impl<W, M> MapWriter<W, M> {
+  pub(super) fn new(origin: W, part_map: M) -> Self {
+    Self { origin, part_map, ref_count: Sc::new(Cell::new(1)) }
+  }
+}
+
+impl<W, M> Drop for MapWriter<W, M> {
+  fn drop(&mut self) { self.ref_count.set(self.ref_count.get() - 1); }
+}
+
+impl<V: 'static, S, M> StateReader for FilterMapWriterAsReader<S, M>
+where
+  Self: 'static,
+  S: StateReader,
+  M: Fn(&mut S::Value) -> Option<PartData<V>> + Clone,
+{
+  type Value = V;
+  type OriginReader = S;
+  type Reader = FilterMapWriterAsReader<S::Reader, M>;
+
+  #[track_caller]
+  #[inline]
+  fn read(&self) -> ReadRef<Self::Value> { self.try_read().unwrap_or_else(|| part_not_exist()) }
+
+  #[inline]
+  fn clone_reader(&self) -> Self::Reader {
+    FilterMapWriterAsReader { origin: self.origin.clone_reader(), part_map: self.part_map.clone() }
+  }
+
+  #[inline]
+  fn origin_reader(&self) -> &Self::OriginReader { &self.origin }
+
+  #[inline]
+  fn try_into_value(self) -> Result<Self::Value, Self>
+  where
+    Self::Value: Sized,
+  {
+    Err(self)
+  }
+}
+
+impl<V: 'static, W, M> StateReader for FilterMapWriter<W, M>
+where
+  Self: 'static,
+  W: StateWriter,
+  M: Fn(&mut W::Value) -> Option<PartData<V>> + Clone,
+{
+  type Value = V;
+  type OriginReader = W;
+  type Reader = FilterMapWriterAsReader<W::Reader, M>;
+
+  #[track_caller]
+  #[inline]
+  fn read(&self) -> ReadRef<Self::Value> { self.try_read().unwrap_or_else(|| part_not_exist()) }
+
+  #[inline]
+  fn clone_reader(&self) -> Self::Reader {
+    FilterMapWriterAsReader { origin: self.origin.clone_reader(), part_map: self.part_map.clone() }
+  }
+
+  #[inline]
+  fn origin_reader(&self) -> &Self::OriginReader { &self.origin }
+
+  #[inline]
+  fn try_into_value(self) -> Result<Self::Value, Self>
+  where
+    Self::Value: Sized,
+  {
+    Err(self)
+  }
+}
+
+impl<V: 'static, W, M> StateWatcher for FilterMapWriter<W, M>
+where
+  Self: 'static,
+  W: StateWriter,
+  M: Fn(&mut W::Value) -> Option<PartData<V>> + Clone,
+{
+  #[inline]
+  fn raw_modifies(&self) -> CloneableBoxOp<'static, ModifyScope, Infallible> {
+    self.origin.raw_modifies()
+  }
+}
+
+impl<V: 'static, W, M> StateWriter for FilterMapWriter<W, M>
+where
+  Self: 'static,
+  W: StateWriter,
+  M: Fn(&mut W::Value) -> Option<PartData<V>> + Clone,
+{
+  type Writer = FilterMapWriter<W::Writer, M>;
+  type OriginWriter = W;
+
+  #[track_caller]
+  #[inline]
+  fn write(&self) -> WriteRef<Self::Value> { self.try_write().unwrap_or_else(|| part_not_exist()) }
+
+  #[track_caller]
+  #[inline]
+  fn silent(&self) -> WriteRef<Self::Value> {
+    self.try_silent().unwrap_or_else(|| part_not_exist())
+  }
+
+  #[track_caller]
+  #[inline]
+  fn shallow(&self) -> WriteRef<Self::Value> {
+    WriteRef::filter_map(self.origin.shallow(), &self.part_map)
+      .unwrap_or_else(|_| part_not_exist())
+  }
+
+  #[inline]
+  fn clone_writer(&self) -> Self::Writer {
+    self.ref_count.set(self.ref_count.get() + 1);
+    FilterMapWriter {
+      origin: self.origin.clone_writer(),
+      part_map: self.part_map.clone(),
+      ref_count: self.ref_count.clone(),
+    }
   }
 
   #[inline]
   fn origin_writer(&self) -> &Self::OriginWriter { &self.origin }
+
+  fn into_reader(self) -> Result<Self::Reader, Self> {
+    if self.ref_count.get() == 1 { Ok(self.clone_reader()) } else { Err(self) }
+  }
+}
+
+impl<W, M> FilterMapWriter<W, M> {
+  pub(super) fn new(origin: W, part_map: M) -> Self {
+    Self { origin, part_map, ref_count: Sc::new(Cell::new(1)) }
+  }
+}
+
+impl<V, W, M> FilterMapWriter<W, M>
+where
+  W: StateWriter,
+  M: Fn(&mut W::Value) -> Option<PartData<V>> + Clone,
+{
+  /// Return a reference of the part, or `None` if the part not exists now.
+  #[inline]
+  pub fn try_read(&self) -> Option<ReadRef<V>> {
+    ReadRef::mut_as_ref_filter_map(self.origin.read(), &self.part_map)
+  }
+
+  /// Return a write reference of the part, or `None` if the part not exists
+  /// now.
+  #[inline]
+  pub fn try_write(&self) -> Option<WriteRef<V>> {
+    WriteRef::filter_map(self.origin.write(), &self.part_map).ok()
+  }
+
+  /// Return a silent write reference of the part, or `None` if the part not
+  /// exists now.
+  #[inline]
+  pub fn try_silent(&self) -> Option<WriteRef<V>> {
+    WriteRef::filter_map(self.origin.silent(), &self.part_map).ok()
+  }
+
+  /// Whether the part exists now.
+  #[inline]
+  pub fn is_available(&self) -> bool { self.try_read().is_some() }
+}
+
+impl<W, M> Drop for FilterMapWriter<W, M> {
+  fn drop(&mut self) { self.ref_count.set(self.ref_count.get() - 1); }
+}
+
+impl<V, S, M> FilterMapWriterAsReader<S, M>
+where
+  S: StateReader,
+  M: Fn(&mut S::Value) -> Option<PartData<V>> + Clone,
+{
+  /// Return a reference of the part, or `None` if the part not exists now.
+  #[inline]
+  pub fn try_read(&self) -> Option<ReadRef<V>> {
+    ReadRef::mut_as_ref_filter_map(self.origin.read(), &self.part_map)
+  }
+}
+
+#[track_caller]
+fn part_not_exist() -> ! {
+  panic!("The part of the state not exists now, use the `try_` methods to access it.")
 }
 
 impl<V, S, F> RenderProxy for MapReader<S, F>
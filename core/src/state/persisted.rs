@@ -0,0 +1,153 @@
+use std::rc::Rc;
+
+use serde::{de::DeserializeOwned, Serialize};
+
+use super::{StateReader, StateWriter};
+use crate::prelude::*;
+
+/// A storage to persist the states, every state is stored as bytes by a key.
+///
+/// Implement it to persist the states to a file, a database or the local
+/// storage of the browser.
+pub trait StateStore {
+  /// Load the bytes stored by the `key`, return `None` if there is nothing.
+  fn load(&self, key: &str) -> Option<Vec<u8>>;
+
+  /// Save the `bytes` by the `key`, replace the previous one if it exists.
+  fn save(&self, key: &str, bytes: Vec<u8>);
+}
+
+/// A wrapper of a `StateWriter` that persists the state value to a
+/// `StateStore`.
+///
+/// The value is loaded from the store when the `Persisted` is created, and
+/// saved to the store after the state modified. The modifies within the
+/// `debounce` duration are saved only once.
+///
+/// If the stored data can't be deserialized, the current value of the writer
+/// is kept as the default value.
+///
+/// The saving stops when the `Persisted` is dropped, call `save` before it if
+/// you don't want to lose the last modifies.
+pub struct Persisted<W: StateWriter>
+where
+  W::Value: Serialize + DeserializeOwned,
+{
+  writer: W,
+  key: Rc<str>,
+  store: Rc<dyn StateStore>,
+  _saving: SubscriptionGuard<BoxSubscription<'static>>,
+}
+
+impl<W: StateWriter> Persisted<W>
+where
+  W::Value: Serialize + DeserializeOwned,
+{
+  pub fn new(
+    writer: W, key: impl Into<Rc<str>>, store: Rc<dyn StateStore>, debounce: Duration,
+  ) -> Self {
+    let key: Rc<str> = key.into();
+    if let Some(bytes) = store.load(&key) {
+      match serde_json::from_slice(&bytes) {
+        Ok(value) => *writer.write() = value,
+        Err(err) => log::warn!("Load the persisted state `{key}` failed, use the default: {err}"),
+      }
+    }
+
+    let reader = writer.clone_reader();
+    let (c_key, c_store) = (key.clone(), store.clone());
+    let saving = writer
+      .modifies()
+      .debounce(debounce, AppCtx::scheduler())
+      .subscribe(move |_| save_to(&*reader.read(), &c_key, &*c_store));
+
+    Self { writer, key, store, _saving: BoxSubscription::new(saving).unsubscribe_when_dropped() }
+  }
+
+  /// Save the current value to the store immediately.
+  pub fn save(&self) { save_to(&*self.writer.read(), &self.key, &*self.store) }
+
+  /// The key of the state in the store.
+  pub fn key(&self) -> &str { &self.key }
+
+  /// The writer this persisted state wraps.
+  pub fn writer(&self) -> &W { &self.writer }
+}
+
+fn save_to<V: Serialize>(value: &V, key: &str, store: &dyn StateStore) {
+  match serde_json::to_vec(value) {
+    Ok(bytes) => store.save(key, bytes),
+    Err(err) => log::warn!("Save the persisted state `{key}` failed: {err}"),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{cell::RefCell, collections::HashMap};
+
+  use super::*;
+  #[cfg(target_arch = "wasm32")]
+  use crate::test_helper::wasm_bindgen_test;
+  use crate::{reset_test_env, timer::Timer};
+
+  #[derive(Default)]
+  struct MemoryStore(RefCell<HashMap<String, Vec<u8>>>);
+
+  impl StateStore for MemoryStore {
+    fn load(&self, key: &str) -> Option<Vec<u8>> { self.0.borrow().get(key).cloned() }
+
+    fn save(&self, key: &str, bytes: Vec<u8>) { self.0.borrow_mut().insert(key.into(), bytes); }
+  }
+
+  fn run_until_saved() {
+    AppCtx::run_until_stalled();
+    Timer::wake_timeout_futures();
+    AppCtx::run_until_stalled();
+  }
+
+  #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+  #[test]
+  fn save_and_load() {
+    reset_test_env!();
+
+    let store = Rc::new(MemoryStore::default());
+    let persisted = Persisted::new(State::value(vec![1, 2]), "list", store.clone(), Duration::ZERO);
+    assert!(store.load("list").is_none());
+
+    persisted.writer().write().push(3);
+    run_until_saved();
+    assert_eq!(store.load("list"), Some(b"[1,2,3]".to_vec()));
+
+    let loaded = Persisted::new(State::value(Vec::<i32>::new()), "list", store, Duration::ZERO);
+    assert_eq!(*loaded.writer().read(), vec![1, 2, 3]);
+  }
+
+  #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+  #[test]
+  fn corrupt_fallback() {
+    reset_test_env!();
+
+    let store = Rc::new(MemoryStore::default());
+    store.save("count", b"not a number".to_vec());
+    let persisted = Persisted::new(State::value(1), "count", store.clone(), Duration::ZERO);
+    assert_eq!(*persisted.writer().read(), 1);
+
+    persisted.save();
+    assert_eq!(store.load("count"), Some(b"1".to_vec()));
+  }
+
+  #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+  #[test]
+  fn stop_saving_after_drop() {
+    reset_test_env!();
+
+    let store = Rc::new(MemoryStore::default());
+    let state = State::value(0);
+    let persisted = Persisted::new(state.clone_writer(), "count", store.clone(), Duration::ZERO);
+    drop(persisted);
+
+    *state.write() = 1;
+    run_until_saved();
+    assert!(store.load("count").is_none());
+  }
+}
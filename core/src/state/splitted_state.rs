@@ -1,4 +1,4 @@
-use std::cell::Cell;
+use std::{cell::Cell, mem::ManuallyDrop, ptr};
 
 use ribir_algo::Sc;
 use rxrust::{ops::box_it::CloneableBoxOp, prelude::BoxIt};
@@ -10,7 +10,7 @@ use super::{
 use crate::{
   context::BuildCtx,
   prelude::AppCtx,
-  state::state_cell::ValueMutRef,
+  state::state_cell::{is_whole_part, take_whole_part, ValueMutRef},
   widget::{Render, RenderBuilder, Widget},
 };
 
@@ -25,7 +25,9 @@ pub struct SplittedWriter<O, W> {
 
 impl<O, W> Drop for SplittedWriter<O, W> {
   fn drop(&mut self) {
-    if self.ref_count.get() == 1 {
+    let count = self.ref_count.get() - 1;
+    self.ref_count.set(count);
+    if count == 0 {
       let mut notifier = self.notifier.clone();
       // we use an async task to unsubscribe to wait the batched modifies to be
       // notified.
@@ -36,7 +38,7 @@ impl<O, W> Drop for SplittedWriter<O, W> {
   }
 }
 
-impl<V, O, W> StateReader for SplittedWriter<O, W>
+impl<V: 'static, O, W> StateReader for SplittedWriter<O, W>
 where
   Self: 'static,
   O: StateWriter,
@@ -59,16 +61,43 @@ where
   #[inline]
   fn origin_reader(&self) -> &Self::OriginReader { &self.origin }
 
-  #[inline]
+  /// The part can be taken out only if it's the whole value of the origin,
+  /// and there is no other writer of the split chain or reader of the root.
+  /// Otherwise, the split chain is returned in `Err`, use `try_into_origin` to
+  /// reclaim the root value.
   fn try_into_value(self) -> Result<Self::Value, Self>
   where
     Self::Value: Sized,
   {
-    Err(self)
+    let whole = is_whole_part(&*self.origin.read(), &*self.read());
+    if !whole || self.ref_count.get() != 1 {
+      return Err(self);
+    }
+
+    let this = ManuallyDrop::new(self);
+    // Safety: `this` is forgotten, every field is read out only once.
+    let (origin, splitter, notifier, batched_modify, ref_count) = unsafe {
+      (
+        ptr::read(&this.origin),
+        ptr::read(&this.splitter),
+        ptr::read(&this.notifier),
+        ptr::read(&this.batched_modify),
+        ptr::read(&this.ref_count),
+      )
+    };
+    match origin.try_into_value() {
+      Ok(value) => {
+        let mut notifier = notifier;
+        notifier.unsubscribe();
+        // Safety: the part is checked to be the whole origin.
+        Ok(unsafe { take_whole_part(value) })
+      }
+      Err(origin) => Err(Self { origin, splitter, notifier, batched_modify, ref_count }),
+    }
   }
 }
 
-impl<V, O, W> StateWatcher for SplittedWriter<O, W>
+impl<V: 'static, O, W> StateWatcher for SplittedWriter<O, W>
 where
   Self: 'static,
   O: StateWriter,
@@ -79,7 +108,7 @@ where
   }
 }
 
-impl<V, O, W> StateWriter for SplittedWriter<O, W>
+impl<V: 'static, O, W> StateWriter for SplittedWriter<O, W>
 where
   Self: 'static,
   O: StateWriter,
@@ -98,6 +127,7 @@ where
   fn shallow(&self) -> WriteRef<Self::Value> { self.split_ref(self.origin.shallow()) }
 
   fn clone_writer(&self) -> Self::Writer {
+    self.ref_count.set(self.ref_count.get() + 1);
     SplittedWriter {
       origin: self.origin.clone_writer(),
       splitter: self.splitter.clone(),
@@ -109,9 +139,13 @@ where
 
   #[inline]
   fn origin_writer(&self) -> &Self::OriginWriter { &self.origin }
+
+  fn into_reader(self) -> Result<Self::Reader, Self> {
+    if self.ref_count.get() == 1 { Ok(self.clone_reader()) } else { Err(self) }
+  }
 }
 
-impl<V, O, W> WriterControl for SplittedWriter<O, W>
+impl<V: 'static, O, W> WriterControl for SplittedWriter<O, W>
 where
   Self: 'static,
   O: StateWriter,
@@ -127,12 +161,7 @@ where
   fn dyn_clone(&self) -> Box<dyn WriterControl> { Box::new(self.clone_writer()) }
 }
 
-impl<V, O, W> SplittedWriter<O, W>
-where
-  Self: 'static,
-  O: StateWriter,
-  W: Fn(&mut O::Value) -> PartData<V> + Clone,
-{
+impl<O, W> SplittedWriter<O, W> {
   pub(super) fn new(origin: O, mut_map: W) -> Self {
     Self {
       origin,
@@ -142,6 +171,23 @@ where
       ref_count: Sc::new(Cell::new(1)),
     }
   }
+}
+
+impl<V: 'static, O, W> SplittedWriter<O, W>
+where
+  Self: 'static,
+  O: StateWriter,
+  W: Fn(&mut O::Value) -> PartData<V> + Clone,
+{
+  /// Return the writer of the origin state if this is the last writer of the
+  /// split, otherwise return itself in `Err`.
+  ///
+  /// A split that is only a part of its origin value can't be taken out by
+  /// `try_into_value`. Instead, unwind a chain of splits by this method to its
+  /// root `Stateful`, then reclaim the value by `try_into_value` of the root.
+  pub fn try_into_origin(self) -> Result<O::Writer, Self> {
+    if self.ref_count.get() == 1 { Ok(self.origin.clone_writer()) } else { Err(self) }
+  }
 
   #[track_caller]
   fn split_ref<'a>(&'a self, mut orig: WriteRef<'a, O::Value>) -> WriteRef<'a, V> {
@@ -155,7 +201,7 @@ where
     let value =
       ValueMutRef { inner: (self.splitter)(&mut orig.value), borrow: orig.value.borrow.clone() };
 
-    WriteRef { value, modified: false, modify_scope, control: self }
+    WriteRef { value, modified: false, modify_scope, control: self, modified_at: None }
   }
 }
 
@@ -1,6 +1,7 @@
 //! This implementation is a fork from `std::cell::RefCell`, allowing us to
 //! manage the borrow flag.
 use std::{
+  any::TypeId,
   cell::{Cell, UnsafeCell},
   ops::{Deref, DerefMut},
   ptr::NonNull,
@@ -106,6 +107,9 @@ impl<T> PartData<T> {
   pub fn from_ref(v: &T) -> Self { PartData::PartRef(NonNull::from(v)) }
 
   /// Create a `PartData` from a mutable reference.
+  ///
+  /// The reference should point to the data of the state, not to a copy of it
+  /// like `&mut v.clone()`, or the modifies on it will not be notified.
   pub fn from_ref_mut(v: &mut T) -> Self { PartData::PartRef(NonNull::from(v)) }
 
   /// Create a `PartData` from a type that should be point to the part data not
@@ -113,7 +117,30 @@ impl<T> PartData<T> {
   ///
   /// Caller should ensure that the data is not a copy.
   pub fn from_data(ptr_data: T) -> Self { PartData::PartData(ptr_data) }
+
+}
+
+/// Return if the `part` is the whole `origin`, so the value of `origin` can be
+/// taken as the part by `take_whole_part`.
+///
+/// A value can't contain a field of its own type, so a part of the same type
+/// at the address of the origin is the origin itself.
+pub(crate) fn is_whole_part<O: 'static, V: 'static>(origin: &O, part: &V) -> bool {
+  TypeId::of::<O>() == TypeId::of::<V>()
+    && std::ptr::eq(origin as *const O as *const u8, part as *const V as *const u8)
 }
+
+/// Take the value of `origin` as its part.
+///
+/// # Safety
+///
+/// The part of `origin` must be checked by `is_whole_part`, so `O` and `V` are
+/// the same type.
+pub(crate) unsafe fn take_whole_part<O, V>(origin: O) -> V {
+  let origin = std::mem::ManuallyDrop::new(origin);
+  std::ptr::read(&*origin as *const O as *const V)
+}
+
 pub struct ReadRef<'a, T> {
   pub(crate) inner: PartData<T>,
   pub(crate) borrow: BorrowRef<'a>,
@@ -234,6 +261,21 @@ impl<'a, V> ReadRef<'a, V> {
 
     ReadRef { inner: value, borrow }
   }
+
+  pub(crate) fn mut_as_ref_filter_map<U>(
+    orig: ReadRef<'a, V>, f: impl FnOnce(&mut V) -> Option<PartData<U>>,
+  ) -> Option<ReadRef<'a, U>> {
+    let ReadRef { inner: value, borrow } = orig;
+    let value = match value {
+      PartData::PartRef(mut ptr) => unsafe {
+        // Safety: the same as `mut_as_ref_map`.
+        f(ptr.as_mut())
+      },
+      PartData::PartData(mut data) => f(&mut data),
+    }?;
+
+    Some(ReadRef { inner: value, borrow })
+  }
 }
 
 impl<'a, T> Deref for ReadRef<'a, T> {
@@ -16,6 +16,10 @@ pub struct Reader<W>(Sc<StateCell<W>>);
 
 pub struct Writer<W>(Stateful<W>);
 
+/// The writer of a state that lives as long as the application, see
+/// [`Stateful::shared`].
+pub type SharedWriter<W> = Writer<W>;
+
 /// The notifier is a `RxRust` stream that emit notification when the state
 /// changed.
 #[derive(Default, Clone)]
@@ -97,6 +101,10 @@ impl<W: 'static> StateWriter for Stateful<W> {
 
   #[inline]
   fn origin_writer(&self) -> &Self::OriginWriter { self }
+
+  fn into_reader(self) -> Result<Self::Reader, Self> {
+    if self.writer_count() == 1 { Ok(self.clone_reader()) } else { Err(self) }
+  }
 }
 
 impl<W: 'static> StateReader for Reader<W> {
@@ -167,6 +175,9 @@ impl<V: 'static> StateWriter for Writer<V> {
 
   #[inline]
   fn origin_writer(&self) -> &Self::OriginWriter { self }
+
+  #[inline]
+  fn into_reader(self) -> Result<Self::Reader, Self> { self.0.into_reader().map_err(Writer) }
 }
 
 impl WriterControl for Sc<StatefulInfo> {
@@ -234,14 +245,53 @@ impl<R: Render> RenderBuilder for Writer<R> {
   fn build(self, ctx: &BuildCtx) -> Widget { self.0.build(ctx) }
 }
 
+/// Create a state of `value`, and return a watcher and a writer of it.
+///
+/// It's a shortcut to create a `Stateful` and call its `clone_watcher` and
+/// `clone_writer`, the `Stateful` itself is dropped. The watcher can only read
+/// and watch the state, so pass it to the code that should not modify the
+/// state, and keep the writer to modify it.
+///
+/// # Example
+///
+/// ```
+/// use ribir_core::prelude::*;
+///
+/// let (watcher, writer) = state_pair(1);
+/// let reader = watcher.clone_reader();
+/// watcher
+///   .modifies()
+///   .subscribe(move |_| println!("The value is {}", *reader.read()));
+///
+/// *writer.write() += 1;
+/// AppCtx::run_until_stalled();
+/// assert_eq!(*watcher.read(), 2);
+/// ```
+pub fn state_pair<T: 'static>(value: T) -> (Watcher<Reader<T>>, Writer<T>) {
+  let state = Stateful::new(value);
+  (state.clone_watcher(), state.clone_writer())
+}
+
 impl<W> Stateful<W> {
   pub fn new(data: W) -> Self {
     Self { data: Sc::new(StateCell::new(data)), info: Sc::new(StatefulInfo::new()) }
   }
 
+  /// Create a state that lives as long as the application, it's held by the
+  /// `AppCtx` and can be fetched anywhere by [`AppCtx::shared_state`].
+  ///
+  /// Only one shared state is allowed for a type, return the `data` back as
+  /// an error if there is already a shared state of the same type.
+  pub fn shared(data: W) -> Result<SharedWriter<W>, W>
+  where
+    W: 'static,
+  {
+    AppCtx::insert_shared_state(data)
+  }
+
   fn write_ref(&self, scope: ModifyScope) -> WriteRef<'_, W> {
     let value = self.data.write();
-    WriteRef { value, modified: false, modify_scope: scope, control: &self.info }
+    WriteRef { value, modified: false, modify_scope: scope, control: &self.info, modified_at: None }
   }
 
   fn writer_count(&self) -> usize { self.info.writer_count.get() }
@@ -301,6 +351,8 @@ impl Notifier {
   }
 
   pub(crate) fn next(&self, scope: ModifyScope) { self.0.clone().next(scope) }
+
+  pub(crate) fn is_closed(&self) -> bool { self.0.is_closed() }
 }
 
 impl<W: std::fmt::Debug> std::fmt::Debug for Stateful<W> {
@@ -422,6 +474,62 @@ mod tests {
     assert_eq!(&*changed_size.borrow(), &Size::new(1., 1.));
   }
 
+  #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+  #[test]
+  fn write_scope_only_notify_its_side() {
+    crate::reset_test_env!();
+
+    let data_cnt = Sc::new(Cell::new(0));
+    let c_data_cnt = data_cnt.clone();
+    let sized_box = Stateful::new(MockBox { size: Size::new(100., 100.) });
+    sized_box
+      .modifies()
+      .subscribe(move |_| c_data_cnt.set(c_data_cnt.get() + 1));
+
+    let split_cnt = Sc::new(Cell::new(0));
+    let c_split_cnt = split_cnt.clone();
+    let split = sized_box.split_writer(|v| PartData::from_ref_mut(&mut v.size));
+    split
+      .modifies()
+      .subscribe(move |_| c_split_cnt.set(c_split_cnt.get() + 1));
+
+    let state = sized_box.clone_writer();
+    let mut wnd = TestWindow::new(fn_widget! {sized_box});
+    wnd.draw_frame();
+    assert!(!wnd.widget_tree.borrow().is_dirty());
+
+    // A shallow write only marks the widget dirty.
+    state.shallow().size = Size::new(1., 1.);
+    Timer::wake_timeout_futures();
+    AppCtx::run_until_stalled();
+    assert_eq!(data_cnt.get(), 0);
+    assert!(wnd.widget_tree.borrow().is_dirty());
+    wnd.draw_frame();
+
+    // A silent write only notifies the data subscribers.
+    state.silent().size = Size::new(2., 2.);
+    Timer::wake_timeout_futures();
+    AppCtx::run_until_stalled();
+    assert_eq!(data_cnt.get(), 1);
+    assert!(!wnd.widget_tree.borrow().is_dirty());
+
+    // A shallow write of the split writer reaches neither the data subscribers
+    // of the split writer nor the origin.
+    *split.shallow() = Size::new(3., 3.);
+    Timer::wake_timeout_futures();
+    AppCtx::run_until_stalled();
+    assert_eq!((data_cnt.get(), split_cnt.get()), (1, 0));
+    assert!(!wnd.widget_tree.borrow().is_dirty());
+
+    // A silent write of the split writer notifies the data subscribers of both,
+    // but not the framework.
+    *split.silent() = Size::new(4., 4.);
+    Timer::wake_timeout_futures();
+    AppCtx::run_until_stalled();
+    assert_eq!((data_cnt.get(), split_cnt.get()), (2, 1));
+    assert!(!wnd.widget_tree.borrow().is_dirty());
+  }
+
   #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
   #[test]
   fn fix_pin_widget_node() {
@@ -553,4 +661,32 @@ mod tests {
     assert!(info.notifier.0.is_closed());
     assert_eq!(info.ref_count(), 1);
   }
+
+  #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+  #[test]
+  fn shared_state() {
+    crate::reset_test_env!();
+
+    struct AppStore(i32);
+
+    let store = Stateful::shared(AppStore(1)).ok().unwrap();
+    assert!(matches!(Stateful::shared(AppStore(2)), Err(AppStore(2))));
+    // The `AppCtx` holds the state, so the value can't be taken out.
+    let store = store.try_into_value().err().unwrap();
+
+    let read = Rc::new(RefCell::new(vec![]));
+    let c_read = read.clone();
+    let w = fn_widget! {
+      let store = AppCtx::shared_state::<AppStore>().unwrap();
+      c_read.borrow_mut().push(store.read().0);
+      @MockBox { size: Size::zero() }
+    };
+    let mut wnd = TestWindow::new(w);
+    wnd.draw_frame();
+    assert_eq!(&*read.borrow(), &[1]);
+
+    store.write().0 = 2;
+    let store = AppCtx::shared_state::<AppStore>().unwrap();
+    assert_eq!(store.read().0, 2);
+  }
 }
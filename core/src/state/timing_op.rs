@@ -0,0 +1,189 @@
+use std::cell::RefCell;
+
+use ribir_algo::Sc;
+use rxrust::prelude::*;
+
+use crate::{context::AppCtx, ticker::Duration, timer::Timer};
+
+/// The way `TimingOp` limits the rate of the values.
+#[derive(Clone, Copy)]
+enum Timing {
+  /// Emit the last value after the source is quiet for the duration.
+  Debounce,
+  /// Emit the first value immediately, and at most one value, the last one,
+  /// at the end of every duration after it.
+  Throttle,
+}
+
+/// An operator limits the rate of the values by the clock of `Timer`, so it
+/// works with the tasks of `AppCtx` and can be driven by `Timer::advance` in
+/// tests.
+pub struct TimingOp<S> {
+  source: S,
+  dur: Duration,
+  timing: Timing,
+}
+
+pub struct TimingObserver<O, Item> {
+  observer: Sc<RefCell<Option<O>>>,
+  state: Sc<RefCell<TimingState<Item>>>,
+  dur: Duration,
+  timing: Timing,
+}
+
+struct TimingState<Item> {
+  /// The value waits to be emitted when the timer fires.
+  pending: Option<Item>,
+  /// Increased when a timer is scheduled, the timers before the last one are
+  /// stale.
+  generation: usize,
+  /// Whether a throttle window is running.
+  in_window: bool,
+}
+
+/// A trait for Observable that can limit the rate of its values by the clock
+/// of `Timer`.
+pub trait TimingObservable<Item, Err>: ObservableExt<Item, Err> {
+  /// Emit the last value only after the source hasn't emitted for `dur`. The
+  /// last value is also emitted if the source completes before the quiet
+  /// period ends.
+  fn debounce_timer(self, dur: Duration) -> TimingOp<Self>
+  where
+    Self: Sized,
+  {
+    TimingOp { source: self, dur, timing: Timing::Debounce }
+  }
+
+  /// Emit the first value immediately, then ignore the values within `dur`
+  /// but emit the last ignored one when `dur` elapsed.
+  fn throttle_timer(self, dur: Duration) -> TimingOp<Self>
+  where
+    Self: Sized,
+  {
+    TimingOp { source: self, dur, timing: Timing::Throttle }
+  }
+}
+
+impl<Item, Err, T> TimingObservable<Item, Err> for T where T: ObservableExt<Item, Err> {}
+
+impl<Item: 'static, Err: 'static, O, S> Observable<Item, Err, O> for TimingOp<S>
+where
+  O: Observer<Item, Err> + 'static,
+  S: Observable<Item, Err, TimingObserver<O, Item>>,
+{
+  type Unsub = ZipSubscription<S::Unsub, TimingSubscription<O>>;
+
+  fn actual_subscribe(self, observer: O) -> Self::Unsub {
+    let Self { source, dur, timing } = self;
+    let observer = Sc::new(RefCell::new(Some(observer)));
+    let state = TimingState { pending: None, generation: 0, in_window: false };
+    let state = Sc::new(RefCell::new(state));
+    let o2 = observer.clone();
+    let u = source.actual_subscribe(TimingObserver { observer, state, dur, timing });
+    ZipSubscription::new(u, TimingSubscription(o2))
+  }
+}
+
+impl<Item, Err, S> ObservableExt<Item, Err> for TimingOp<S> where S: ObservableExt<Item, Err> {}
+
+impl<O, Item: 'static, Err: 'static> Observer<Item, Err> for TimingObserver<O, Item>
+where
+  O: Observer<Item, Err> + 'static,
+{
+  fn next(&mut self, value: Item) {
+    match self.timing {
+      Timing::Debounce => {
+        self.state.borrow_mut().pending = Some(value);
+        self.schedule::<Err>();
+      }
+      Timing::Throttle => {
+        let mut state = self.state.borrow_mut();
+        if state.in_window {
+          state.pending = Some(value);
+        } else {
+          state.in_window = true;
+          drop(state);
+          if let Some(o) = self.observer.borrow_mut().as_mut() {
+            o.next(value);
+          }
+          self.schedule::<Err>();
+        }
+      }
+    }
+  }
+
+  fn error(self, err: Err) {
+    if let Some(o) = self.observer.borrow_mut().take() {
+      o.error(err)
+    }
+  }
+
+  fn complete(self) {
+    let pending = self.state.borrow_mut().pending.take();
+    if let Some(mut o) = self.observer.borrow_mut().take() {
+      if let Some(value) = pending {
+        o.next(value);
+      }
+      o.complete();
+    }
+  }
+
+  fn is_finished(&self) -> bool { self.observer.borrow().is_none() }
+}
+
+impl<O, Item: 'static> TimingObserver<O, Item> {
+  fn schedule<Err: 'static>(&self)
+  where
+    O: Observer<Item, Err> + 'static,
+  {
+    let generation = {
+      let mut state = self.state.borrow_mut();
+      state.generation += 1;
+      state.generation
+    };
+    let timer = Timer::new(Timer::now() + self.dur);
+    let this = TimingObserver {
+      observer: self.observer.clone(),
+      state: self.state.clone(),
+      dur: self.dur,
+      timing: self.timing,
+    };
+    let _ = AppCtx::spawn_local(async move {
+      timer.await;
+      this.fire::<Err>(generation);
+    });
+  }
+
+  fn fire<Err: 'static>(&self, generation: usize)
+  where
+    O: Observer<Item, Err> + 'static,
+  {
+    let mut state = self.state.borrow_mut();
+    if state.generation != generation {
+      return;
+    }
+    let pending = state.pending.take();
+    if matches!(self.timing, Timing::Throttle) {
+      // Another window starts with the trailing value.
+      state.in_window = pending.is_some();
+    }
+    drop(state);
+
+    if let Some(value) = pending {
+      if let Some(o) = self.observer.borrow_mut().as_mut() {
+        o.next(value);
+      }
+      if matches!(self.timing, Timing::Throttle) {
+        self.schedule::<Err>();
+      }
+    }
+  }
+}
+
+pub struct TimingSubscription<O>(Sc<RefCell<Option<O>>>);
+
+impl<O> Subscription for TimingSubscription<O> {
+  fn unsubscribe(self) { self.0.borrow_mut().take(); }
+
+  fn is_closed(&self) -> bool { self.0.borrow().is_none() }
+}
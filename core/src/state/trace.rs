@@ -0,0 +1,139 @@
+use std::{fmt, panic::Location};
+
+use crate::{prelude::*, timer::Timer};
+
+/// A record of a modification of a state, it's emitted when the trace is
+/// enabled by [`AppCtx::trace_state_changes`].
+#[derive(Debug, Clone, Copy)]
+pub struct StateChangeTrace {
+  /// The type name of the modified value. For a writer of a part of a state,
+  /// it's the type of the part.
+  pub value_type: &'static str,
+  /// The scope the modification will notify.
+  pub scope: ModifyScope,
+  /// Where the value was modified.
+  pub location: &'static Location<'static>,
+}
+
+impl fmt::Display for StateChangeTrace {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "`{}` modified with {:?} at {}", self.value_type, self.scope, self.location)
+  }
+}
+
+type TraceSink = dyn FnMut(&StateChangeTrace);
+
+pub(crate) const STATE_TRACE_TARGET: &str = "ribir::state";
+
+#[derive(Default)]
+pub(crate) struct StateTracer {
+  pub(crate) enabled: bool,
+  /// The max count of the records emitted within a second, `None` means no
+  /// limit.
+  pub(crate) limit: Option<usize>,
+  pub(crate) sink: Option<Box<TraceSink>>,
+  window_start: Option<Instant>,
+  emitted: usize,
+  dropped: usize,
+}
+
+impl StateTracer {
+  pub(crate) fn trace(&mut self, record: StateChangeTrace) {
+    if let Some(limit) = self.limit {
+      let now = Timer::now();
+      let start = *self.window_start.get_or_insert(now);
+      if now.duration_since(start) >= Duration::from_secs(1) {
+        if self.dropped > 0 {
+          log::debug!(
+            target: STATE_TRACE_TARGET,
+            "{} state changes were not traced by the limit.",
+            self.dropped
+          );
+        }
+        self.window_start = Some(now);
+        self.emitted = 0;
+        self.dropped = 0;
+      }
+      if self.emitted >= limit {
+        self.dropped += 1;
+        return;
+      }
+      self.emitted += 1;
+    }
+
+    match self.sink.as_mut() {
+      Some(sink) => sink(&record),
+      None => log::debug!(target: STATE_TRACE_TARGET, "{record}"),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{cell::RefCell, rc::Rc};
+
+  use super::*;
+  use crate::reset_test_env;
+
+  fn collect_traces() -> Rc<RefCell<Vec<StateChangeTrace>>> {
+    let traces = Rc::new(RefCell::new(vec![]));
+    let c_traces = traces.clone();
+    AppCtx::set_state_trace_sink(move |t| c_traces.borrow_mut().push(*t));
+    AppCtx::trace_state_changes(true);
+    traces
+  }
+
+  #[test]
+  fn trace_write() {
+    reset_test_env!();
+
+    let traces = collect_traces();
+    let state = Stateful::new((0, String::new()));
+    state.write().0 = 1;
+    let line = line!() - 1;
+    let part = state.map_writer(|v| PartData::from_ref_mut(&mut v.1));
+    part.silent().push('a');
+
+    let traces = traces.borrow();
+    assert_eq!(traces.len(), 2);
+    assert_eq!(traces[0].value_type, "(i32, alloc::string::String)");
+    assert_eq!(traces[0].scope, ModifyScope::BOTH);
+    assert_eq!(traces[0].location.file(), file!());
+    assert_eq!(traces[0].location.line(), line);
+    assert_eq!(traces[1].value_type, "alloc::string::String");
+    assert_eq!(traces[1].scope, ModifyScope::DATA);
+  }
+
+  #[test]
+  fn no_trace_if_disabled() {
+    reset_test_env!();
+
+    let traces = collect_traces();
+    AppCtx::trace_state_changes(false);
+    let state = Stateful::new(0);
+    *state.write() = 1;
+    // Only read, not modified.
+    let _ = *state.write();
+    AppCtx::trace_state_changes(true);
+    let _ = *state.write();
+
+    assert!(traces.borrow().is_empty());
+  }
+
+  #[test]
+  fn trace_limit() {
+    reset_test_env!();
+
+    let traces = collect_traces();
+    AppCtx::set_state_trace_limit(Some(2));
+    let state = Stateful::new(0);
+    for i in 0..5 {
+      *state.write() = i;
+    }
+    assert_eq!(traces.borrow().len(), 2);
+
+    Timer::advance(Duration::from_secs(1));
+    *state.write() = 5;
+    assert_eq!(traces.borrow().len(), 3);
+  }
+}
@@ -21,11 +21,12 @@ pub struct Frame {
   pub commands: Vec<PaintCommand>,
   pub viewport: Rect,
   pub surface: Color,
+  /// The transform from the logic pixels to the device pixels.
+  pub transform: Transform,
 }
 
 pub fn split_value<T: 'static>(v: T) -> (Watcher<Reader<T>>, impl StateWriter<Value = T>) {
-  let src = Stateful::new(v);
-  (src.clone_watcher(), src.clone_writer())
+  state_pair(v)
 }
 
 #[derive(Clone)]
@@ -36,6 +37,7 @@ macro_rules! reset_test_env {
   () => {
     let _ = $crate::prelude::NEW_TIMER_FN.set($crate::timer::Timer::new_timer_future);
     let _guard = unsafe { $crate::prelude::AppCtx::new_lock_scope() };
+    $crate::timer::Timer::reset_clock();
   };
 }
 
@@ -83,6 +85,13 @@ impl TestWindow {
       .take()
   }
 
+  /// Return the count of the children of the root, the first one is the
+  /// content, the others are the overlays.
+  pub fn root_children_count(&self) -> usize {
+    let tree = self.0.widget_tree.borrow();
+    tree.root().children(&tree.arena).count()
+  }
+
   pub fn content_count(&self) -> usize {
     let widget_tree = self.0.widget_tree.borrow();
     let root = widget_tree.root();
@@ -90,12 +99,36 @@ impl TestWindow {
     widget_tree.count(content)
   }
 
+  /// Simulate the window moved to a display with the device pixel `ratio`.
+  pub fn set_device_pixel_ratio(&self, ratio: f32) {
+    self
+      .shell_wnd()
+      .borrow_mut()
+      .as_any_mut()
+      .downcast_mut::<TestShellWindow>()
+      .unwrap()
+      .device_pixel_ratio = ratio;
+    self.processes_scale_factor_changed(ratio);
+  }
+
+  /// Set the function that renders a captured frame to an image. Without it,
+  /// `capture_frame` and `capture_widget` of the test window return `None`.
+  pub fn set_rasterizer(&self, rasterizer: impl FnMut(&Frame) -> PixelImage + 'static) {
+    self
+      .shell_wnd()
+      .borrow_mut()
+      .as_any_mut()
+      .downcast_mut::<TestShellWindow>()
+      .unwrap()
+      .rasterizer = Some(Box::new(rasterizer));
+  }
+
   #[track_caller]
-  pub fn draw_frame(&mut self) {
+  pub fn draw_frame(&mut self) -> bool {
     // Test window not have a eventloop, manually wake-up every frame.
     Timer::wake_timeout_futures();
     self.run_frame_tasks();
-    self.0.draw_frame();
+    self.0.draw_frame()
   }
 }
 
@@ -105,12 +138,19 @@ impl std::ops::Deref for TestWindow {
   fn deref(&self) -> &Self::Target { &self.0 }
 }
 
+type Rasterizer = dyn FnMut(&Frame) -> PixelImage;
+
 pub struct TestShellWindow {
   pub size: Size,
   pub cursor: CursorIcon,
   pub id: WindowId,
   pub surface_color: Color,
   pub last_frame: Option<Frame>,
+  pub device_pixel_ratio: f32,
+  /// The count of the redraw requested by the window.
+  pub redraw_requests: usize,
+  /// Render the captured frames to images.
+  pub rasterizer: Option<Box<Rasterizer>>,
 }
 
 impl ShellWindow for TestShellWindow {
@@ -155,22 +195,41 @@ impl ShellWindow for TestShellWindow {
 
   fn set_ime_allowed(&mut self, _: bool) {}
 
+  fn request_redraw(&mut self) { self.redraw_requests += 1; }
+
   fn as_any(&self) -> &dyn Any { self }
 
   fn as_any_mut(&mut self) -> &mut dyn Any { self }
 
   fn begin_frame(&mut self, surface: Color) { self.surface_color = surface; }
 
-  fn draw_commands(&mut self, viewport: Rect, commands: &[PaintCommand]) {
-    self.last_frame =
-      Some(Frame { commands: commands.to_owned(), viewport, surface: self.surface_color });
+  fn draw_commands(&mut self, viewport: Rect, transform: &Transform, commands: &[PaintCommand]) {
+    self.last_frame = Some(Frame {
+      commands: commands.to_owned(),
+      viewport,
+      surface: self.surface_color,
+      transform: *transform,
+    });
   }
 
   fn end_frame(&mut self) {}
 
+  fn capture_commands(
+    &mut self, viewport: Rect, surface: Color, commands: &[PaintCommand],
+  ) -> Option<PixelImage> {
+    let ratio = self.device_pixel_ratio;
+    let frame = Frame {
+      commands: commands.to_owned(),
+      viewport,
+      surface,
+      transform: Transform::scale(ratio, ratio),
+    };
+    self.rasterizer.as_mut().map(|r| r(&frame))
+  }
+
   fn id(&self) -> WindowId { self.id }
 
-  fn device_pixel_ratio(&self) -> f32 { 1. }
+  fn device_pixel_ratio(&self) -> f32 { self.device_pixel_ratio }
 }
 
 impl TestShellWindow {
@@ -183,6 +242,9 @@ impl TestShellWindow {
       id: ID.fetch_add(1, Ordering::Relaxed).into(),
       last_frame: None,
       surface_color: Color::WHITE,
+      device_pixel_ratio: 1.,
+      redraw_requests: 0,
+      rasterizer: None,
     }
   }
 }
@@ -1,5 +1,7 @@
 use std::{
+  cell::RefCell,
   collections::BTreeMap,
+  convert::Infallible,
   future::Future,
   mem::swap,
   sync::{
@@ -10,9 +12,13 @@ use std::{
 };
 
 use once_cell::sync::Lazy;
-use rxrust::scheduler::BoxFuture;
+use ribir_algo::Sc;
+use rxrust::{prelude::*, scheduler::BoxFuture};
 
-use crate::ticker::{Duration, Instant};
+use crate::{
+  context::AppCtx,
+  ticker::{Duration, Instant},
+};
 
 #[derive(Default)]
 pub(crate) struct TimeReactor {
@@ -46,6 +52,11 @@ impl TimeReactor {
 pub(crate) static TIME_REACTOR: Lazy<Mutex<TimeReactor>> =
   Lazy::new(|| Mutex::new(TimeReactor::default()));
 
+/// The offset added to the clock of the timers, only advanced by
+/// `Timer::advance` to fire the timers without waiting in tests.
+#[cfg(any(test, feature = "test-utils"))]
+static CLOCK_OFFSET: Mutex<Duration> = Mutex::new(Duration::ZERO);
+
 pub struct Timer {
   id: Option<usize>,
   when: Instant,
@@ -66,15 +77,50 @@ impl Timer {
 
   pub fn recently_timeout() -> Option<Instant> { TIME_REACTOR.lock().unwrap().recently_timeout() }
 
+  /// The current time of the clock of the timers.
+  pub fn now() -> Instant {
+    #[cfg(any(test, feature = "test-utils"))]
+    return Instant::now() + *CLOCK_OFFSET.lock().unwrap();
+    #[cfg(not(any(test, feature = "test-utils")))]
+    Instant::now()
+  }
+
+  /// Advance the clock of the timers by `dur` and wake the timeout futures, so
+  /// the test can fire the timers deterministically without waiting.
+  ///
+  /// Only available in the tests or with the `test-utils` feature, the clock
+  /// is reset by `reset_test_env!`.
+  #[cfg(any(test, feature = "test-utils"))]
+  pub fn advance(dur: Duration) {
+    *CLOCK_OFFSET.lock().unwrap() += dur;
+    Self::wake_timeout_futures();
+  }
+
+  #[doc(hidden)]
+  #[cfg(any(test, feature = "test-utils"))]
+  pub fn reset_clock() { *CLOCK_OFFSET.lock().unwrap() = Duration::ZERO; }
+
   pub fn new_timer_future(dur: Duration) -> BoxFuture<'static, ()> {
-    Box::pin(Timer::new(Instant::now() + dur))
+    Box::pin(Timer::new(Timer::now() + dur))
   }
 
+  /// Return a stream that emits every `dur`, timed by the clock of the timers
+  /// and driven by the tasks of `AppCtx`, so it can be advanced by
+  /// `Timer::advance` in tests.
+  ///
+  /// A tick is only emitted when the timers are woken, which the event loop
+  /// does once for every turn. If the timers are woken late, or the `dur` is
+  /// shorter than a turn, the missed ticks are coalesced into one.
+  ///
+  /// The timer stops when the subscription is unsubscribed, use
+  /// `unsubscribe_when_dropped` to stop it with its owner, e.g. a widget.
+  pub fn interval(dur: Duration) -> TimerInterval { TimerInterval { dur } }
+
   pub fn wake_timeout_futures() {
     let notifies = TIME_REACTOR
       .lock()
       .unwrap()
-      .timeout_wakers(Instant::now());
+      .timeout_wakers(Timer::now());
     notifies.for_each(|waker| waker.wake());
   }
 }
@@ -84,7 +130,7 @@ impl Future for Timer {
   fn poll(
     mut self: std::pin::Pin<&mut Self>, cx: &mut std::task::Context<'_>,
   ) -> std::task::Poll<Self::Output> {
-    let now = Instant::now();
+    let now = Timer::now();
     let when = self.as_ref().when;
     if let Some(id) = self.as_mut().id.take() {
       TIME_REACTOR
@@ -105,3 +151,143 @@ impl Future for Timer {
     Poll::Pending
   }
 }
+
+/// A timer future that is never ready in its first poll, so it's only woken
+/// by `Timer::wake_timeout_futures` even if its time has passed.
+struct Tick {
+  timer: Timer,
+  polled: bool,
+}
+
+impl Future for Tick {
+  type Output = ();
+  fn poll(
+    mut self: std::pin::Pin<&mut Self>, cx: &mut std::task::Context<'_>,
+  ) -> std::task::Poll<Self::Output> {
+    if self.polled {
+      std::pin::Pin::new(&mut self.timer).poll(cx)
+    } else {
+      self.polled = true;
+      let when = self.timer.when;
+      let id = TIME_REACTOR
+        .lock()
+        .unwrap()
+        .insert_timer(when, cx.waker().clone());
+      self.timer.id = Some(id);
+      Poll::Pending
+    }
+  }
+}
+
+/// The stream returned by `Timer::interval`.
+pub struct TimerInterval {
+  dur: Duration,
+}
+
+impl<O> Observable<(), Infallible, O> for TimerInterval
+where
+  O: Observer<(), Infallible> + 'static,
+{
+  type Unsub = TimerIntervalSubscription<O>;
+
+  fn actual_subscribe(self, observer: O) -> Self::Unsub {
+    let dur = self.dur;
+    let observer = Sc::new(RefCell::new(Some(observer)));
+    let c_observer = observer.clone();
+    let _ = AppCtx::spawn_local(async move {
+      let mut deadline = Timer::now() + dur;
+      loop {
+        Tick { timer: Timer::new(deadline), polled: false }.await;
+        if let Some(o) = c_observer.borrow_mut().as_mut() {
+          o.next(());
+        } else {
+          break;
+        }
+
+        // Skip the missed ticks, they are coalesced into the emitted one.
+        let now = Timer::now();
+        if dur.is_zero() {
+          deadline = now;
+        } else if now >= deadline {
+          let missed = (now - deadline).as_nanos() / dur.as_nanos() + 1;
+          let skip = dur.saturating_mul(u32::try_from(missed).unwrap_or(u32::MAX));
+          // If too many ticks are missed to skip, restart from now.
+          deadline = deadline
+            .checked_add(skip)
+            .filter(|d| *d > now)
+            .unwrap_or(now + dur);
+        }
+      }
+    });
+    TimerIntervalSubscription(observer)
+  }
+}
+
+impl ObservableExt<(), Infallible> for TimerInterval {}
+
+pub struct TimerIntervalSubscription<O>(Sc<RefCell<Option<O>>>);
+
+impl<O> Subscription for TimerIntervalSubscription<O> {
+  fn unsubscribe(self) { self.0.borrow_mut().take(); }
+
+  fn is_closed(&self) -> bool { self.0.borrow().is_none() }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::cell::Cell;
+
+  use super::*;
+  use crate::reset_test_env;
+
+  #[test]
+  fn interval() {
+    reset_test_env!();
+
+    let count = Sc::new(Cell::new(0));
+    let c_count = count.clone();
+    let u =
+      Timer::interval(Duration::from_secs(1)).subscribe(move |_| c_count.set(c_count.get() + 1));
+    AppCtx::run_until_stalled();
+    assert_eq!(count.get(), 0);
+
+    Timer::advance(Duration::from_secs(1));
+    AppCtx::run_until_stalled();
+    assert_eq!(count.get(), 1);
+
+    Timer::advance(Duration::from_millis(500));
+    AppCtx::run_until_stalled();
+    assert_eq!(count.get(), 1);
+    Timer::advance(Duration::from_millis(500));
+    AppCtx::run_until_stalled();
+    assert_eq!(count.get(), 2);
+
+    // A late wake up emits once for all the missed ticks.
+    Timer::advance(Duration::from_secs(5));
+    AppCtx::run_until_stalled();
+    assert_eq!(count.get(), 3);
+
+    u.unsubscribe();
+    Timer::advance(Duration::from_secs(1));
+    AppCtx::run_until_stalled();
+    assert_eq!(count.get(), 3);
+  }
+
+  #[test]
+  fn coalesce_short_interval() {
+    reset_test_env!();
+
+    let count = Sc::new(Cell::new(0));
+    let c_count = count.clone();
+    let _guard = Timer::interval(Duration::from_nanos(1))
+      .subscribe(move |_| c_count.set(c_count.get() + 1))
+      .unsubscribe_when_dropped();
+    AppCtx::run_until_stalled();
+
+    for i in 1..=3 {
+      Timer::advance(Duration::from_millis(100));
+      AppCtx::run_until_stalled();
+      assert_eq!(count.get(), i);
+    }
+  }
+}
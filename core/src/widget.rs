@@ -43,6 +43,28 @@ pub trait Render: 'static {
   /// widget size, and child nodes' size not affect its size.
   fn only_sized_by_parent(&self) -> bool { false }
 
+  /// Return the width the widget needs to lay out its content without
+  /// constraint when its height is `height`.
+  ///
+  /// The default implementation performs a probing layout with an unbounded
+  /// width. Don't call it directly, query it across
+  /// [`Layouter::intrinsic_width`] that caches the result in a layout pass.
+  fn intrinsic_width(&self, height: f32, ctx: &mut LayoutCtx) -> f32 {
+    let clamp = BoxClamp { min: Size::zero(), max: Size::new(f32::INFINITY, height) };
+    self.perform_layout(clamp, ctx).width
+  }
+
+  /// Return the height the widget needs to lay out its content without
+  /// constraint when its width is `width`.
+  ///
+  /// The default implementation performs a probing layout with an unbounded
+  /// height. Don't call it directly, query it across
+  /// [`Layouter::intrinsic_height`] that caches the result in a layout pass.
+  fn intrinsic_height(&self, width: f32, ctx: &mut LayoutCtx) -> f32 {
+    let clamp = BoxClamp { min: Size::zero(), max: Size::new(width, f32::INFINITY) };
+    self.perform_layout(clamp, ctx).height
+  }
+
   /// Determines the set of render widgets located at the given position.
   fn hit_test(&self, ctx: &HitTestCtx, pos: Point) -> HitTest {
     let is_hit = hit_test_impl(ctx, pos);
@@ -50,6 +72,18 @@ pub trait Render: 'static {
   }
 
   fn get_transform(&self) -> Option<Transform> { None }
+
+  /// Whether the widget paints its descendants by itself in `paint`. If true,
+  /// the framework will not paint them.
+  fn paints_descendants(&self) -> bool { false }
+
+  /// The type name of the widget, used to describe the widget in the debug
+  /// output like [`Window::debug_tree`](crate::window::Window::debug_tree).
+  fn type_name(&self) -> &'static str { std::any::type_name::<Self>() }
+
+  /// The default accessibility information of the widget, it's overridden by
+  /// the declared [`Accessibility`] of the widget.
+  fn accessibility(&self) -> Option<Accessibility> { None }
 }
 
 /// The common type of all widget can convert to.
@@ -1,5 +1,5 @@
 use super::*;
-use crate::pipe::InnerPipe;
+use crate::pipe::{InnerPipe, KeyedList};
 
 /// Trait specify what child a multi child widget can have, and the target type
 /// after widget compose its child.
@@ -48,6 +48,19 @@ crate::widget::multi_build_replace_impl_include_self! {
   }
 }
 
+impl<P, K, B, V, Item, R, W> FillVec<KeyedList<P, K, B>> for KeyedList<P, K, B>
+where
+  P: InnerPipe<Value = V>,
+  V: IntoIterator<Item = Item> + 'static,
+  Item: PartialEq + 'static,
+  K: Fn(&Item) -> R + 'static,
+  R: Into<Key>,
+  B: Fn(Watcher<Reader<Item>>) -> W + 'static,
+  W: WidgetBuilder,
+{
+  fn fill_vec(self, vec: &mut Vec<Widget>, ctx: &BuildCtx) { self.build_keyed(vec, ctx) }
+}
+
 impl<M: ?Sized, P, C> MultiWithChild<C, M> for P
 where
   P: MultiParent,
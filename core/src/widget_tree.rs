@@ -14,7 +14,9 @@ mod layout_info;
 pub use layout_info::*;
 
 use self::widget::widget_id::new_node;
-use crate::{overlay::OverlayRoot, prelude::*, render_helper::PureRender};
+use crate::{
+  builtin_widgets::key::AnyKey, overlay::OverlayRoot, prelude::*, render_helper::PureRender,
+};
 
 pub(crate) type DirtySet = Rc<RefCell<HashSet<WidgetId, ahash::RandomState>>>;
 
@@ -24,6 +26,11 @@ pub(crate) struct WidgetTree {
   pub(crate) arena: TreeArena,
   pub(crate) store: LayoutStore,
   pub(crate) dirty_set: DirtySet,
+  /// The widgets painting their descendants by themselves, which have any
+  /// descendant marked dirty since the last paint.
+  dirty_boundaries: RefCell<HashSet<WidgetId, ahash::RandomState>>,
+  /// The statistics of the current frame, only recorded if it's `Some`.
+  pub(crate) frame_stats: Option<FrameStats>,
 }
 
 impl WidgetTree {
@@ -50,11 +57,13 @@ impl WidgetTree {
     let mut painter = wnd.painter.borrow_mut();
     let mut ctx = PaintingCtx::new(self.root(), wnd.id(), &mut painter);
     self.root().paint_subtree(&mut ctx);
+    self.dirty_boundaries.borrow_mut().clear();
   }
 
   /// Do the work of computing the layout for all node which need, Return if any
   /// node has really computing the layout.
   pub(crate) fn layout(&mut self, win_size: Size) {
+    self.store.clear_intrinsic();
     loop {
       let Some(mut needs_layout) = self.layout_list() else {
         break;
@@ -64,6 +73,10 @@ impl WidgetTree {
           continue;
         }
 
+        if let Some(stats) = self.frame_stats.as_mut() {
+          stats.layout_roots.push(wid);
+        }
+
         let clamp = self
           .store
           .layout_info(wid)
@@ -80,6 +93,12 @@ impl WidgetTree {
 
   pub(crate) fn is_dirty(&self) -> bool { !self.dirty_set.borrow().is_empty() }
 
+  /// Return if any descendant of `id` is marked dirty since the last paint,
+  /// only tracked for the widgets that paint their descendants by themselves.
+  pub(crate) fn is_subtree_dirty(&self, id: WidgetId) -> bool {
+    self.dirty_boundaries.borrow().contains(&id)
+  }
+
   pub(crate) fn count(&self, wid: WidgetId) -> usize { wid.descendants(&self.arena).count() }
 
   pub(crate) fn window(&self) -> Rc<Window> {
@@ -113,6 +132,79 @@ impl WidgetTree {
     display
   }
 
+  /// Describe the tree in an indented text, every line is the type name of a
+  /// widget, its rect relative to its parent, then its key and builtin fields.
+  /// If `compact` is true, a widget that only wraps one child with the same
+  /// rect is merged into the line of its child.
+  pub(crate) fn debug_tree(&self, compact: bool) -> String {
+    let mut display = String::new();
+    let mut stack = vec![(self.root, 0)];
+    while let Some((mut id, depth)) = stack.pop() {
+      let info = self.store.layout_info(id);
+      let mut names = vec![short_type_name(id.assert_get(&self.arena).type_name())];
+      let mut fields = self.debug_fields(id);
+      if compact {
+        while let Some(child) = self.pure_wrapped_child(id) {
+          id = child;
+          names.push(short_type_name(id.assert_get(&self.arena).type_name()));
+          fields.extend(self.debug_fields(id));
+        }
+      }
+
+      display.push_str(&"  ".repeat(depth));
+      display.push_str(&names.join(" > "));
+      match info {
+        Some(LayoutInfo { size: Some(size), pos, .. }) => {
+          display.push_str(&format!(" [{}, {}, {}x{}]", pos.x, pos.y, size.width, size.height))
+        }
+        _ => display.push_str(" [not laid out]"),
+      }
+      for f in fields {
+        display.push(' ');
+        display.push_str(&f);
+      }
+      display.push('\n');
+
+      let children = id.children(&self.arena).collect::<Vec<_>>();
+      stack.extend(children.into_iter().rev().map(|c| (c, depth + 1)));
+    }
+    display
+  }
+
+  /// The key and the builtin fields of the widget for the `debug_tree`, like
+  /// `key=Kusize(1)`, `builtin=Pointer|Focus` and `tab_index=1`.
+  fn debug_fields(&self, id: WidgetId) -> Vec<String> {
+    let node = id.assert_get(&self.arena);
+    let mut fields = vec![];
+    if let Some(key) = node.query_ref::<Box<dyn AnyKey>>() {
+      fields.push(format!("key={:?}", key.key()));
+    }
+    if let Some(mix) = node.query_ref::<MixBuiltin>() {
+      let flags = mix.flags().iter_names().map(|(name, _)| name);
+      let flags = flags.collect::<Vec<_>>();
+      if !flags.is_empty() {
+        fields.push(format!("builtin={}", flags.join("|")));
+      }
+      let tab_index = mix.get_tab_index();
+      if tab_index != 0 {
+        fields.push(format!("tab_index={tab_index}"));
+      }
+    }
+    fields
+  }
+
+  /// Return the only child of `id` if it's placed at the origin of `id` with
+  /// the same size.
+  fn pure_wrapped_child(&self, id: WidgetId) -> Option<WidgetId> {
+    let child = id.first_child(&self.arena)?;
+    if id.last_child(&self.arena) != Some(child) {
+      return None;
+    }
+    let size = self.store.layout_box_size(id)?;
+    let info = self.store.layout_info(child)?;
+    (info.pos == Point::zero() && info.size == Some(size)).then_some(child)
+  }
+
   pub(crate) fn layout_list(&mut self) -> Option<Vec<WidgetId>> {
     if self.dirty_set.borrow().is_empty() {
       return None;
@@ -132,6 +224,14 @@ impl WidgetTree {
         continue;
       }
 
+      let boundaries = self.dirty_boundaries.get_mut();
+      for p in id.ancestors(&self.arena).skip(1) {
+        // The ancestors of a recorded boundary are already recorded.
+        if p.assert_get(&self.arena).paints_descendants() && !boundaries.insert(p) {
+          break;
+        }
+      }
+
       let mut relayout_root = *id;
       if let Some(info) = self.store.get_mut(id) {
         info.size.take();
@@ -214,8 +314,28 @@ impl Default for WidgetTree {
       arena,
       store: LayoutStore::default(),
       dirty_set: Rc::new(RefCell::new(HashSet::default())),
+      dirty_boundaries: RefCell::new(HashSet::default()),
+      frame_stats: None,
+    }
+  }
+}
+
+/// Strip the module paths of a type name, include the paths in its generic
+/// arguments, e.g. `a::B<c::D>` to `B<D>`.
+fn short_type_name(name: &str) -> String {
+  let mut short = String::with_capacity(name.len());
+  let mut segment_start = 0;
+  for (idx, c) in name.char_indices() {
+    if !(c.is_alphanumeric() || c == '_' || c == ':') {
+      let segment = &name[segment_start..idx];
+      short.push_str(segment.rsplit("::").next().unwrap_or(segment));
+      short.push(c);
+      segment_start = idx + c.len_utf8();
     }
   }
+  let segment = &name[segment_start..];
+  short.push_str(segment.rsplit("::").next().unwrap_or(segment));
+  short
 }
 
 #[cfg(test)]
@@ -5,7 +5,7 @@ use ribir_geom::ZERO_SIZE;
 use super::{WidgetId, WidgetTree};
 use crate::{
   context::{AppCtx, LayoutCtx, WidgetCtx, WidgetCtxImpl},
-  prelude::{Point, Size, INFINITY_SIZE},
+  prelude::{Point, Rect, Size, INFINITY_SIZE},
   widget::TreeArena,
   window::{DelayEvent, Window, WindowId},
 };
@@ -89,6 +89,16 @@ pub struct LayoutInfo {
 #[derive(Default)]
 pub(crate) struct LayoutStore {
   data: HashMap<WidgetId, LayoutInfo, ahash::RandomState>,
+  /// The intrinsic size queried in the current layout pass.
+  intrinsic: HashMap<(WidgetId, IntrinsicKey), f32, ahash::RandomState>,
+}
+
+/// The intrinsic dimension of a widget, with the bits of the given extent of
+/// the other axis.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum IntrinsicKey {
+  Width(u32),
+  Height(u32),
 }
 
 pub struct Layouter<'a> {
@@ -112,6 +122,20 @@ impl LayoutStore {
 
   pub(crate) fn remove(&mut self, id: WidgetId) -> Option<LayoutInfo> { self.data.remove(&id) }
 
+  /// Drop the sizes of all the widgets, so the whole tree will be laid out
+  /// again. The positions and the clamps are kept until then.
+  pub(crate) fn reset_sizes(&mut self) {
+    self
+      .data
+      .values_mut()
+      .for_each(|info| info.size = None);
+    self.intrinsic.clear();
+  }
+
+  /// Drop the intrinsic size cache, the intrinsic size only keeps in a layout
+  /// pass.
+  pub(crate) fn clear_intrinsic(&mut self) { self.intrinsic.clear(); }
+
   pub(crate) fn layout_box_size(&self, id: WidgetId) -> Option<Size> {
     self.layout_info(id).and_then(|info| info.size)
   }
@@ -160,6 +184,20 @@ impl LayoutStore {
       .fold(pos, |pos, p| self.map_to_parent(p, pos, arena))
   }
 
+  /// Return the rect of the widget in the window coordinate, it's the bounding
+  /// box of the widget if it or its ancestors are transformed.
+  pub(crate) fn global_rect(&self, widget: WidgetId, arena: &TreeArena) -> Option<Rect> {
+    let size = self.layout_box_size(widget)?;
+    let corners = [
+      Point::zero(),
+      Point::new(size.width, 0.),
+      Point::new(0., size.height),
+      Point::new(size.width, size.height),
+    ];
+    let corners = corners.map(|pos| self.map_to_global(pos, widget, arena));
+    Some(Rect::from_points(corners))
+  }
+
   pub(crate) fn map_from_global(&self, pos: Point, widget: WidgetId, arena: &TreeArena) -> Point {
     let stack = widget.ancestors(arena).collect::<Vec<_>>();
     stack
@@ -216,6 +254,10 @@ impl<'a> Layouter<'a> {
           self.id = ctx.id;
         }
 
+        if let Some(stats) = tree2.frame_stats.as_mut() {
+          stats.laid_out_count += 1;
+        }
+
         let info = tree2.store.layout_info_or_default(id);
         let size = clamp.clamp(size);
         info.clamp = clamp;
@@ -231,6 +273,39 @@ impl<'a> Layouter<'a> {
     size
   }
 
+  /// Return the width the widget needs to lay out its content when its height
+  /// is `height`. The result is cached in the current layout pass, so the
+  /// query is cheap even if the parent queries it many times.
+  pub fn intrinsic_width(&mut self, height: f32) -> f32 {
+    self.intrinsic(IntrinsicKey::Width(height.to_bits()))
+  }
+
+  /// Return the height the widget needs to lay out its content when its width
+  /// is `width`. The result is cached in the current layout pass, so the query
+  /// is cheap even if the parent queries it many times.
+  pub fn intrinsic_height(&mut self, width: f32) -> f32 {
+    self.intrinsic(IntrinsicKey::Height(width.to_bits()))
+  }
+
+  fn intrinsic(&mut self, key: IntrinsicKey) -> f32 {
+    if let Some(v) = self.tree.store.intrinsic.get(&(self.id, key)) {
+      return *v;
+    }
+
+    // Safety: the same as `perform_widget_layout`, the `tree` just use to get the
+    // widget of `id`.
+    let tree2 = unsafe { &mut *(self.tree as *mut WidgetTree) };
+    let Self { id, wnd_id, ref tree, .. } = *self;
+    let mut ctx = LayoutCtx { id, wnd_id, tree: tree2 };
+    let r = id.assert_get(&tree.arena);
+    let v = match key {
+      IntrinsicKey::Width(h) => r.intrinsic_width(f32::from_bits(h), &mut ctx),
+      IntrinsicKey::Height(w) => r.intrinsic_height(f32::from_bits(w), &mut ctx),
+    };
+    self.tree.store.intrinsic.insert((id, key), v);
+    v
+  }
+
   /// Get layouter of the next sibling of this layouter, panic if self is not
   /// performed layout.
   pub fn into_next_sibling(mut self) -> Option<Self> {
@@ -245,6 +320,16 @@ impl<'a> Layouter<'a> {
     })
   }
 
+  /// Get layouter of the next sibling of this layouter even if self is not
+  /// performed layout, use it to skip the child that will be laid out later.
+  pub fn skip_to_next_sibling(mut self) -> Option<Self> {
+    let next = self.id.next_sibling(&self.tree.arena);
+    next.map(move |sibling| {
+      self.id = sibling;
+      self
+    })
+  }
+
   /// Return layouter of the first child of this widget.
   #[inline]
   pub fn into_first_child_layouter(mut self) -> Option<Self> {
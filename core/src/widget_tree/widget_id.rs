@@ -129,6 +129,10 @@ impl WidgetId {
     self.0.append(child.0, tree);
   }
 
+  pub(crate) fn prepend(self, child: WidgetId, tree: &mut TreeArena) {
+    self.0.prepend(child.0, tree);
+  }
+
   /// Return the single child of `widget`, panic if have more than once child.
   pub(crate) fn single_child(&self, tree: &TreeArena) -> Option<WidgetId> {
     assert_eq!(self.first_child(tree), self.last_child(tree), "Have more than one child.");
@@ -185,7 +189,7 @@ impl WidgetId {
             .painter
             .translate(layout_box.min_x(), layout_box.min_y());
           render.paint(ctx);
-          need_paint = true;
+          need_paint = !render.paints_descendants();
         }
       }
 
@@ -197,10 +201,11 @@ impl WidgetId {
           while let Some(p) = node {
             // self node sub-tree paint finished, goto sibling
             ctx.painter.restore();
-            node = match p == self {
-              true => None,
-              false => p.next_sibling(arena),
-            };
+            if p == self {
+              node = None;
+              break;
+            }
+            node = p.next_sibling(arena);
             if node.is_some() {
               break;
             } else {
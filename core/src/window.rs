@@ -16,6 +16,7 @@ use crate::{
   },
   prelude::*,
   ticker::{FrameMsg, FrameTicker},
+  timer::Timer,
 };
 
 /// Window is the root to represent.
@@ -51,11 +52,57 @@ pub struct Window {
   ///
   /// This widgets it's detached from its parent, but still need to paint.
   delay_drop_widgets: RefCell<Vec<(Option<WidgetId>, WidgetId)>>,
+  /// The shortcuts of the window, matched before the key down event is
+  /// dispatched to the focused widget.
+  shortcuts: RefCell<Vec<(KeyCombo, ShortcutHandler)>>,
+  /// The handlers called when the scale factor of the window changed.
+  scale_factor_handlers: RefCell<Vec<ScaleFactorHandler>>,
+  /// The callbacks to run before the paint of the next frame.
+  frame_callbacks: RefCell<Vec<FrameCallback>>,
+  /// Whether a redraw is requested and the frame not drawn yet.
+  redraw_requested: Cell<bool>,
+  /// Whether the widgets are laid out by a capture, but the changes are not
+  /// drawn to the shell window yet.
+  capture_laid_out: Cell<bool>,
+  /// The statistics of the last drawn frame, see `enable_frame_stats`.
+  last_frame_stats: RefCell<Option<FrameStats>>,
+}
+
+/// The statistics of a frame, see [`Window::enable_frame_stats`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FrameStats {
+  /// The time spent on laying out the widgets.
+  pub layout_duration: Duration,
+  /// The time spent on painting the widgets and submitting the paint commands
+  /// to the shell window.
+  pub paint_duration: Duration,
+  /// The count of the widgets that performed layout, the widgets that reused
+  /// their last layout result are not counted.
+  pub laid_out_count: usize,
+  /// The roots of the subtrees that were laid out again.
+  pub layout_roots: Vec<WidgetId>,
+  /// The count of the paint commands submitted to the shell window.
+  pub draw_calls: usize,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Hash)]
 pub struct WindowId(u64);
 
+type ShortcutHandler = Rc<RefCell<dyn FnMut(&Window) -> bool>>;
+type ScaleFactorHandler = Rc<RefCell<dyn FnMut(f32)>>;
+type FrameCallback = Box<dyn FnOnce(&Window)>;
+
+/// The handle of a task scheduled by [`Window::run_after`].
+pub struct DelayedTask(Rc<RefCell<Option<FrameCallback>>>);
+
+impl DelayedTask {
+  /// Cancel the task if it has not run yet, the task is dropped immediately.
+  pub fn cancel(self) { self.0.borrow_mut().take(); }
+
+  /// Return if the task is still waiting to run.
+  pub fn is_pending(&self) -> bool { self.0.borrow().is_some() }
+}
+
 pub trait ShellWindow {
   fn id(&self) -> WindowId;
   fn inner_size(&self) -> Size;
@@ -78,6 +125,9 @@ pub trait ShellWindow {
   fn set_minimized(&mut self, minimized: bool);
   fn focus_window(&mut self);
   fn set_decorations(&mut self, decorations: bool);
+  /// Request the platform to draw a new frame of the window. Do nothing by
+  /// default, for the shell that draws continuously.
+  fn request_redraw(&mut self) {}
   fn as_any(&self) -> &dyn Any;
   fn as_any_mut(&mut self) -> &mut dyn Any;
   /// The device pixel ratio of Window interface returns the ratio of the
@@ -85,8 +135,18 @@ pub trait ShellWindow {
   /// device.
   fn device_pixel_ratio(&self) -> f32;
   fn begin_frame(&mut self, surface_color: Color);
-  fn draw_commands(&mut self, viewport: Rect, commands: &[PaintCommand]);
+  /// Draw the `commands` in the `viewport` of logic pixels, the `transform`
+  /// maps the logic pixels to the device pixels.
+  fn draw_commands(&mut self, viewport: Rect, transform: &Transform, commands: &[PaintCommand]);
   fn end_frame(&mut self);
+  /// Render the `commands` in the `viewport` to an offscreen image in device
+  /// pixels, without presenting them. Return `None` if the shell can't read
+  /// back the pixels, which is the default.
+  fn capture_commands(
+    &mut self, _viewport: Rect, _surface_color: Color, _commands: &[PaintCommand],
+  ) -> Option<PixelImage> {
+    None
+  }
 }
 
 impl Window {
@@ -105,12 +165,159 @@ impl Window {
     &self, physical_key: PhysicalKey, key: VirtualKey, is_repeat: bool, location: KeyLocation,
     state: ElementState,
   ) {
+    if state == ElementState::Pressed && self.trigger_shortcut(&key) {
+      return;
+    }
     self
       .dispatcher
       .borrow_mut()
       .dispatch_keyboard_input(physical_key, key, is_repeat, location, state);
   }
 
+  /// Register a shortcut of the window, the `handler` is called when the
+  /// `combo` is pressed, whatever which widget is focused. Return `true` in
+  /// the `handler` to consume the key event, so it will not be dispatched to
+  /// the focused widget.
+  ///
+  /// Only one handler is kept for a combination, the handler registered
+  /// before will be replaced.
+  pub fn add_shortcut(&self, combo: KeyCombo, handler: impl FnMut(&Window) -> bool + 'static) {
+    let handler: ShortcutHandler = Rc::new(RefCell::new(handler));
+    let mut shortcuts = self.shortcuts.borrow_mut();
+    shortcuts.retain(|(c, _)| c != &combo);
+    shortcuts.push((combo, handler));
+  }
+
+  /// Remove the shortcut of the `combo`.
+  pub fn remove_shortcut(&self, combo: &KeyCombo) {
+    self
+      .shortcuts
+      .borrow_mut()
+      .retain(|(c, _)| c != combo);
+  }
+
+  /// Capture the `pointer` to the widget `wid`, the move and up events of the
+  /// pointer are dispatched to it even if the pointer is out of its bounds.
+  /// Every pointer is captured separately, a new capture of a pointer replaces
+  /// the old one.
+  ///
+  /// The capture is released when the pointer is up, the pointer left the
+  /// window or the widget is disposed. When the pointer left the window, the
+  /// widget receives a `PointerCancel` event.
+  pub fn capture_pointer(&self, pointer: PointerId, wid: WidgetId) {
+    self.dispatcher.borrow_mut().capture(pointer, wid);
+  }
+
+  /// Release the capture of the `pointer` by `capture_pointer`.
+  pub fn release_pointer(&self, pointer: PointerId) {
+    self
+      .dispatcher
+      .borrow_mut()
+      .release_capture(pointer);
+  }
+
+  /// Return the widget captured the `pointer`.
+  pub fn pointer_capture(&self, pointer: PointerId) -> Option<WidgetId> {
+    self.dispatcher.borrow().captured_by(pointer)
+  }
+
+  fn trigger_shortcut(&self, key: &VirtualKey) -> bool {
+    let modifiers = self.dispatcher.borrow().info.modifiers();
+    let handler = self
+      .shortcuts
+      .borrow()
+      .iter()
+      .find(|(combo, _)| combo.is_match(key, modifiers))
+      .map(|(_, handler)| handler.clone());
+    // Release the borrow of the shortcuts before calling, so the handler can
+    // register or remove shortcuts.
+    handler.map_or(false, |handler| (*handler.borrow_mut())(self))
+  }
+
+  /// Register a handler called with the new device pixel ratio when the
+  /// scale factor of the window changed, e.g. the window moved to a display
+  /// with a different scale factor.
+  pub fn on_scale_factor_changed(&self, handler: impl FnMut(f32) + 'static) {
+    let handler: ScaleFactorHandler = Rc::new(RefCell::new(handler));
+    self
+      .scale_factor_handlers
+      .borrow_mut()
+      .push(handler);
+  }
+
+  /// Register a callback that runs once before the paint of the next frame,
+  /// after the layout, and request a redraw of the window.
+  ///
+  /// The callbacks registered during a frame, including by another frame
+  /// callback, run in the following frame.
+  pub fn on_next_frame(&self, callback: impl FnOnce(&Window) + 'static) {
+    self
+      .frame_callbacks
+      .borrow_mut()
+      .push(Box::new(callback));
+    self.request_redraw();
+  }
+
+  /// Schedule a task that runs once with the window after `delay`.
+  ///
+  /// The delay is timed by the clock of the timers, so it can be advanced by
+  /// `Timer::advance` in tests. The task is cancelled by the returned handle,
+  /// and is never run if the window is removed before the delay elapses.
+  pub fn run_after(&self, delay: Duration, task: impl FnOnce(&Window) + 'static) -> DelayedTask {
+    let task: FrameCallback = Box::new(task);
+    let task = Rc::new(RefCell::new(Some(task)));
+    let c_task = task.clone();
+    let wnd_id = self.id();
+    let timer = Timer::new(Timer::now() + delay);
+    let _ = AppCtx::spawn_local(async move {
+      timer.await;
+      // Release the borrow before running, so the task can schedule again.
+      let task = c_task.borrow_mut().take();
+      if let (Some(task), Some(wnd)) = (task, AppCtx::get_window(wnd_id)) {
+        task(&wnd);
+      }
+    });
+    DelayedTask(task)
+  }
+
+  /// Request to draw a new frame of the window. The requests before the frame
+  /// drawn are coalesced into one.
+  pub fn request_redraw(&self) {
+    if !self.redraw_requested.replace(true) {
+      self.shell_wnd.borrow_mut().request_redraw();
+    }
+  }
+
+  /// Tell the window the requested redraw is delivered. Call it even if the
+  /// frame is skipped, otherwise the following requests are coalesced into the
+  /// dropped one and never reach the shell window.
+  pub fn redraw_delivered(&self) { self.redraw_requested.set(false); }
+
+  /// Processes the scale factor of the window changed to `scale`.
+  ///
+  /// The whole tree of this window will be laid out again, and its painter is
+  /// reset, so nothing computed at the old scale is reused in the next frame.
+  /// The shape and typography caches of the text are shared by all the windows
+  /// and are in logic pixels, so they are still valid at the new scale. The
+  /// glyph rasters cached by the backend are keyed by the scale they are
+  /// rendered at, so a raster of a smaller scale is never reused.
+  pub fn processes_scale_factor_changed(&self, scale: f32) {
+    {
+      let mut tree = self.widget_tree.borrow_mut();
+      tree.store.reset_sizes();
+      let root = tree.root();
+      tree.mark_dirty(root);
+    }
+    self.painter.borrow_mut().reset();
+
+    let handlers = self.scale_factor_handlers.borrow().clone();
+    // Release the borrow of the handlers before calling, so the handler can
+    // register another handler.
+    for handler in handlers {
+      (*handler.borrow_mut())(scale);
+    }
+  }
+
   pub fn processes_receive_chars(&self, chars: String) {
     self
       .dispatcher
@@ -180,42 +387,150 @@ impl Window {
   /// Draw an image what current render tree represent.
   #[track_caller]
   pub fn draw_frame(&self) -> bool {
+    self.redraw_delivered();
+    if let Some(stats) = self.widget_tree.borrow_mut().frame_stats.as_mut() {
+      *stats = FrameStats::default();
+    }
+    // The callbacks registered after here wait for the next frame.
+    let frame_callbacks = std::mem::take(&mut *self.frame_callbacks.borrow_mut());
     AppCtx::run_until_stalled();
     self
       .frame_ticker
-      .emit(FrameMsg::NewFrame(Instant::now()));
+      .emit(FrameMsg::NewFrame(Timer::now()));
     self.run_frame_tasks();
 
     self.update_painter_viewport();
     let draw = self.need_draw() && !self.size().is_empty();
     if draw {
-      let surface = match AppCtx::app_theme() {
-        Theme::Full(theme) => theme.palette.surface(),
-        Theme::Inherit(_) => unreachable!(),
-      };
+      let surface = Self::surface_color();
       self.shell_wnd.borrow_mut().begin_frame(surface);
 
-      self.layout();
+      self.frame_layout();
+    }
+
+    if !frame_callbacks.is_empty() {
+      for callback in frame_callbacks {
+        callback(self);
+      }
+      self.run_frame_tasks();
+      if draw && self.widget_tree.borrow().is_dirty() {
+        self.frame_layout();
+      }
+    }
 
+    if draw {
+      self.capture_laid_out.set(false);
+      let paint_start = self.frame_stats_enabled().then(Instant::now);
       self.widget_tree.borrow().draw();
       self.draw_delay_drop_widgets();
 
       let mut shell = self.shell_wnd.borrow_mut();
       let inner_size = shell.inner_size();
+      let ratio = shell.device_pixel_ratio();
       let mut painter = self.painter.borrow_mut();
-      shell.draw_commands(Rect::from_size(inner_size), &painter.finish());
+      let commands = painter.finish();
+      shell.draw_commands(Rect::from_size(inner_size), &Transform::scale(ratio, ratio), &commands);
 
       shell.end_frame();
+
+      if let Some(start) = paint_start {
+        if let Some(stats) = self.widget_tree.borrow_mut().frame_stats.as_mut() {
+          stats.paint_duration = start.elapsed();
+          stats.draw_calls = commands.len();
+          *self.last_frame_stats.borrow_mut() = Some(std::mem::take(stats));
+        }
+      }
     }
 
     AppCtx::end_frame();
     self
       .frame_ticker
-      .emit(FrameMsg::Finish(Instant::now()));
+      .emit(FrameMsg::Finish(Timer::now()));
 
     draw
   }
 
+  /// Enable or disable recording the statistics of every drawn frame, like the
+  /// time spent on layout and paint. Read the statistics of the last drawn
+  /// frame by [`Window::last_frame_stats`].
+  ///
+  /// It's disabled by default, nothing is recorded when disabled.
+  pub fn enable_frame_stats(&self, enable: bool) {
+    self.widget_tree.borrow_mut().frame_stats = enable.then(FrameStats::default);
+    if !enable {
+      self.last_frame_stats.borrow_mut().take();
+    }
+  }
+
+  /// The statistics of the last drawn frame. Return `None` if the frame stats
+  /// is disabled or no frame drawn since enabled.
+  pub fn last_frame_stats(&self) -> Option<FrameStats> { self.last_frame_stats.borrow().clone() }
+
+  fn frame_stats_enabled(&self) -> bool { self.widget_tree.borrow().frame_stats.is_some() }
+
+  fn frame_layout(&self) {
+    let start = self.frame_stats_enabled().then(Instant::now);
+    self.layout();
+    if let Some(start) = start {
+      if let Some(stats) = self.widget_tree.borrow_mut().frame_stats.as_mut() {
+        stats.layout_duration += start.elapsed();
+      }
+    }
+  }
+
+  /// Render the current UI of the window to an image in device pixels, at the
+  /// current size and scale of the window. It lays out the widgets first if
+  /// they are not ready, so it works even before the first frame.
+  ///
+  /// Return `None` if the shell window can't read back the pixels.
+  pub fn capture_frame(&self) -> Option<PixelImage> {
+    self.prepare_capture();
+    let size = self.shell_wnd.borrow().inner_size();
+    self.capture_rect(Rect::from_size(size))
+  }
+
+  /// Render the bounds of the widget `id` to an image in device pixels, the
+  /// widgets overlapping it are captured too.
+  ///
+  /// Return `None` if the widget is not laid out or the shell window can't
+  /// read back the pixels.
+  pub fn capture_widget(&self, id: WidgetId) -> Option<PixelImage> {
+    self.prepare_capture();
+    let rect = self.widget_rect(id)?;
+    self.capture_rect(rect)
+  }
+
+  fn prepare_capture(&self) {
+    self.update_painter_viewport();
+    if self.widget_tree.borrow().is_dirty() {
+      self.layout();
+      // The layout consumes the dirty widgets, keep the next frame drawing the
+      // changes to the shell window.
+      self.capture_laid_out.set(true);
+      self.request_redraw();
+    }
+  }
+
+  fn capture_rect(&self, rect: Rect) -> Option<PixelImage> {
+    if rect.is_empty() {
+      return None;
+    }
+    self.widget_tree.borrow().draw();
+    let mut painter = self.painter.borrow_mut();
+    let commands = painter.finish();
+    self
+      .shell_wnd
+      .borrow_mut()
+      .capture_commands(rect, Self::surface_color(), &commands)
+  }
+
+  fn surface_color() -> Color {
+    match AppCtx::app_theme() {
+      Theme::Full(theme) => theme.palette.surface(),
+      Theme::Inherit(_) => unreachable!(),
+    }
+  }
+
   pub fn layout(&self) {
     loop {
       self.run_frame_tasks();
@@ -231,11 +546,15 @@ impl Window {
           .focus_mgr
           .borrow_mut()
           .refresh_focus(&self.widget_tree.borrow().arena);
+        self
+          .dispatcher
+          .borrow_mut()
+          .refresh_pointer_hover();
         self.run_frame_tasks();
       }
 
       if !self.widget_tree.borrow().is_dirty() {
-        let ready = FrameMsg::LayoutReady(Instant::now());
+        let ready = FrameMsg::LayoutReady(Timer::now());
         self.frame_ticker.emit(ready);
         self.run_frame_tasks();
       }
@@ -260,7 +579,9 @@ impl Window {
   }
 
   pub fn need_draw(&self) -> bool {
-    self.widget_tree.borrow().is_dirty() || self.running_animates.get() > 0
+    self.widget_tree.borrow().is_dirty()
+      || self.running_animates.get() > 0
+      || self.capture_laid_out.get()
   }
 
   pub fn new(shell_wnd: Box<dyn ShellWindow>) -> Rc<Self> {
@@ -281,6 +602,12 @@ impl Window {
       priority_task_queue: PriorityTaskQueue::default(),
       shell_wnd: RefCell::new(shell_wnd),
       delay_drop_widgets: <_>::default(),
+      shortcuts: <_>::default(),
+      scale_factor_handlers: <_>::default(),
+      frame_callbacks: <_>::default(),
+      redraw_requested: Cell::new(false),
+      capture_laid_out: Cell::new(false),
+      last_frame_stats: <_>::default(),
     };
     let window = Rc::new(window);
     window
@@ -397,12 +724,12 @@ impl Window {
             .collect::<Vec<_>>()
             .into_iter()
             .rev()
-            .for_each(|id| {
-              if Some(id) == self.focusing() {
-                self.focus_mgr.borrow_mut().blur_on_dispose();
+            .for_each(|wid| {
+              if Some(wid) == self.focusing() {
+                self.focus_mgr.borrow_mut().focus_on_dispose(id);
               }
-              let mut e = Event::Disposed(LifecycleEvent::new(id, self.id()));
-              self.emit(id, &mut e);
+              let mut e = Event::Disposed(LifecycleEvent::new(wid, self.id()));
+              self.emit(wid, &mut e);
             });
 
           let keep_alive = id
@@ -485,10 +812,10 @@ impl Window {
           let mut e = Event::Chars(e);
           self.bottom_up_emit(&mut e, id, None);
         }
-        DelayEvent::Wheel { id, delta_x, delta_y } => {
-          let mut e = Event::WheelCapture(WheelEvent::new(delta_x, delta_y, id, self.id()));
+        DelayEvent::Wheel { id, delta, phase } => {
+          let mut e = Event::WheelCapture(WheelEvent::new(delta, phase, id, self.id()));
           self.top_down_emit(&mut e, id, None);
-          let mut e = Event::Wheel(WheelEvent::new(delta_x, delta_y, id, self.id()));
+          let mut e = Event::Wheel(WheelEvent::new(delta, phase, id, self.id()));
           self.bottom_up_emit(&mut e, id, None);
         }
         DelayEvent::PointerDown(id) => {
@@ -513,7 +840,7 @@ impl Window {
           let mut e = Event::PointerUp(PointerEvent::from_mouse(id, self));
           self.bottom_up_emit(&mut e, id, None);
         }
-        DelayEvent::_PointerCancel(id) => {
+        DelayEvent::PointerCancel(id) => {
           let mut e = Event::PointerCancel(PointerEvent::from_mouse(id, self));
           self.bottom_up_emit(&mut e, id, None);
         }
@@ -624,6 +951,14 @@ impl Window {
       .map_to_global(point, id, &self.widget_tree.borrow().arena)
   }
 
+  /// Return the rect of the widget in the window coordinate. If the widget or
+  /// its ancestors are transformed, return the bounding box of it. Return
+  /// `None` if the widget has not been laid out yet.
+  pub fn widget_rect(&self, id: WidgetId) -> Option<Rect> {
+    let tree = self.widget_tree.borrow();
+    tree.store.global_rect(id, &tree.arena)
+  }
+
   pub fn layout_size(&self, id: WidgetId) -> Option<Size> {
     self
       .widget_tree
@@ -631,6 +966,24 @@ impl Window {
       .store
       .layout_box_size(id)
   }
+
+  /// Dump the widget tree of the window to a stable indented text for
+  /// snapshot testing. Every line is the type name of a widget and its rect
+  /// `[x, y, width x height]` relative to its parent, followed by its key and
+  /// its builtin fields, like `key=Kstring("a") builtin=Pointer tab_index=1`.
+  ///
+  /// If `compact` is true, a widget that only wraps one child with the same
+  /// rect, like most of the builtin widgets, is merged into the line of its
+  /// child as `Wrapper > Child`.
+  pub fn debug_tree(&self, compact: bool) -> String {
+    self.widget_tree.borrow().debug_tree(compact)
+  }
+
+  /// Export the accessibility tree of the window, the root node is the window
+  /// and every node carries its role, labels and the rect in the window
+  /// coordinate. The hidden widgets are excluded. See [`Accessibility`] for
+  /// which widgets have a node.
+  pub fn accessibility_tree(&self) -> AccessNode { self.widget_tree.borrow().accessibility_tree() }
 }
 
 /// Window attributes configuration.
@@ -713,11 +1066,11 @@ pub(crate) enum DelayEvent {
   KeyUp(KeyboardEvent),
   TabFocusMove,
   Chars { id: WidgetId, chars: String },
-  Wheel { id: WidgetId, delta_x: f32, delta_y: f32 },
+  Wheel { id: WidgetId, delta: WheelDelta, phase: ScrollPhase },
   PointerDown(WidgetId),
   PointerMove(WidgetId),
   PointerUp(WidgetId),
-  _PointerCancel(WidgetId),
+  PointerCancel(WidgetId),
   PointerEnter { bottom: WidgetId, up: Option<WidgetId> },
   PointerLeave { bottom: WidgetId, up: Option<WidgetId> },
   Tap(WidgetId),
@@ -756,4 +1109,343 @@ mod tests {
     wnd.draw_frame();
     assert_layout_result_by_path!(wnd, { path = [0], size == new_size, });
   }
+
+  #[test]
+  fn dump_debug_tree() {
+    reset_test_env!();
+
+    let w = fn_widget! {
+      @MockMulti {
+        @MockBox { size: Size::new(10., 20.) }
+        @MockBox {
+          size: Size::new(30., 20.),
+          margin: EdgeInsets::all(5.),
+          @MockBox { size: Size::new(30., 20.) }
+        }
+      }
+    };
+    let mut wnd = TestWindow::new_with_size(w, Size::new(100., 100.));
+    wnd.draw_frame();
+
+    assert_eq!(
+      wnd.debug_tree(false),
+      "\
+OverlayRoot [0, 0, 50x30]
+  MockMulti [0, 0, 50x30]
+    MockBox [0, 0, 10x20]
+    Margin [10, 0, 40x30]
+      MockBox [5, 5, 30x20]
+        MockBox [0, 0, 30x20]
+"
+    );
+    assert_eq!(
+      wnd.debug_tree(true),
+      "\
+OverlayRoot > MockMulti [0, 0, 50x30]
+  MockBox [0, 0, 10x20]
+  Margin [10, 0, 40x30]
+    MockBox > MockBox [5, 5, 30x20]
+"
+    );
+  }
+
+  #[test]
+  fn debug_tree_with_fields() {
+    reset_test_env!();
+
+    let w = fn_widget! {
+      @MockMulti {
+        @KeyWidget {
+          key: "a",
+          value: (),
+          @MockBox { size: Size::new(10., 20.) }
+        }
+        @MockBox {
+          size: Size::new(30., 20.),
+          tab_index: 2i16,
+          on_tap: |_| {},
+        }
+      }
+    };
+    let mut wnd = TestWindow::new_with_size(w, Size::new(100., 100.));
+    wnd.draw_frame();
+
+    assert_eq!(
+      wnd.debug_tree(true),
+      "\
+OverlayRoot > MockMulti [0, 0, 40x20]
+  MockBox [0, 0, 10x20] key=Kstring(\"a\")
+  MockBox [10, 0, 30x20] builtin=Lifecycle|Pointer|Focus tab_index=2
+"
+    );
+  }
+
+  #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+  #[test]
+  fn relayout_after_scale_factor_changed() {
+    reset_test_env!();
+
+    let layout_cnt = Rc::new(Cell::new(0));
+    let c_layout_cnt = layout_cnt.clone();
+    let mut wnd = TestWindow::new(fn_widget! {
+      @MockBox {
+        size: Size::new(100., 100.),
+        on_performed_layout: move |_| c_layout_cnt.set(c_layout_cnt.get() + 1),
+      }
+    });
+    let scales = Rc::new(RefCell::new(vec![]));
+    let c_scales = scales.clone();
+    wnd.on_scale_factor_changed(move |scale| c_scales.borrow_mut().push(scale));
+
+    wnd.draw_frame();
+    assert_eq!(layout_cnt.get(), 1);
+    let frame = wnd.take_last_frame().unwrap();
+    assert_eq!(frame.transform, Transform::scale(1., 1.));
+
+    wnd.set_device_pixel_ratio(2.);
+    assert_eq!(&*scales.borrow(), &[2.]);
+    assert!(wnd.need_draw());
+    // Only the sizes are dropped, the layout info is kept until the next layout.
+    assert!(wnd.layout_info_by_path(&[0]).is_some());
+
+    wnd.draw_frame();
+    assert_eq!(layout_cnt.get(), 2);
+    let frame = wnd.take_last_frame().unwrap();
+    assert_eq!(frame.transform, Transform::scale(2., 2.));
+  }
+
+  #[test]
+  fn next_frame_callback() {
+    reset_test_env!();
+
+    let mut wnd = TestWindow::new(fn_widget! { MockBox { size: Size::new(100., 100.) } });
+    wnd.draw_frame();
+
+    let calls = Rc::new(RefCell::new(vec![]));
+    let c_calls = calls.clone();
+    wnd.on_next_frame(move |wnd| {
+      c_calls.borrow_mut().push(1);
+      // Registered during the frame, run in the following frame.
+      let c_calls = c_calls.clone();
+      wnd.on_next_frame(move |_| c_calls.borrow_mut().push(2));
+    });
+    assert!(calls.borrow().is_empty());
+
+    wnd.draw_frame();
+    assert_eq!(&*calls.borrow(), &[1]);
+    wnd.draw_frame();
+    assert_eq!(&*calls.borrow(), &[1, 2]);
+    wnd.draw_frame();
+    assert_eq!(&*calls.borrow(), &[1, 2]);
+  }
+
+  #[test]
+  fn run_task_after_delay() {
+    reset_test_env!();
+
+    let wnd = TestWindow::new(fn_widget! { MockBox { size: Size::new(100., 100.) } });
+    let runs = Rc::new(Cell::new(0));
+    let c_runs = runs.clone();
+    let task = wnd.run_after(Duration::from_secs(3), move |_| c_runs.set(c_runs.get() + 1));
+
+    Timer::advance(Duration::from_secs(2));
+    AppCtx::run_until_stalled();
+    assert_eq!(runs.get(), 0);
+    assert!(task.is_pending());
+
+    Timer::advance(Duration::from_secs(1));
+    AppCtx::run_until_stalled();
+    assert_eq!(runs.get(), 1);
+    assert!(!task.is_pending());
+
+    Timer::advance(Duration::from_secs(3));
+    AppCtx::run_until_stalled();
+    assert_eq!(runs.get(), 1);
+  }
+
+  #[test]
+  fn cancel_delayed_task() {
+    reset_test_env!();
+
+    let wnd = TestWindow::new(fn_widget! { MockBox { size: Size::new(100., 100.) } });
+    let runs = Rc::new(Cell::new(0));
+    let c_runs = runs.clone();
+    let task = wnd.run_after(Duration::from_secs(1), move |_| c_runs.set(c_runs.get() + 1));
+    task.cancel();
+
+    // The window is removed before the delay elapses.
+    let c_runs = runs.clone();
+    wnd.run_after(Duration::from_secs(1), move |_| c_runs.set(c_runs.get() + 1));
+    AppCtx::remove_wnd(wnd.id());
+    drop(wnd);
+
+    Timer::advance(Duration::from_secs(1));
+    AppCtx::run_until_stalled();
+    assert_eq!(runs.get(), 0);
+  }
+
+  #[test]
+  fn coalesce_redraw_requests() {
+    reset_test_env!();
+
+    let mut wnd = TestWindow::new(fn_widget! { MockBox { size: Size::new(100., 100.) } });
+    let redraw_requests = |wnd: &TestWindow| {
+      wnd
+        .shell_wnd()
+        .borrow()
+        .as_any()
+        .downcast_ref::<TestShellWindow>()
+        .unwrap()
+        .redraw_requests
+    };
+    wnd.draw_frame();
+
+    wnd.request_redraw();
+    wnd.request_redraw();
+    wnd.on_next_frame(|_| {});
+    assert_eq!(redraw_requests(&wnd), 1);
+
+    wnd.draw_frame();
+    wnd.request_redraw();
+    assert_eq!(redraw_requests(&wnd), 2);
+
+    // The frame of the request is skipped, the next request is not lost.
+    wnd.redraw_delivered();
+    wnd.request_redraw();
+    assert_eq!(redraw_requests(&wnd), 3);
+  }
+
+  #[test]
+  fn record_frame_stats() {
+    reset_test_env!();
+
+    let size = Stateful::new(Size::new(10., 10.));
+    let c_size = size.clone_watcher();
+    let mut wnd = TestWindow::new(fn_widget! {
+      @MockMulti {
+        @MockBox { size: pipe!(*$c_size) }
+        @MockBox { size: Size::new(10., 10.), background: Color::RED }
+      }
+    });
+    wnd.draw_frame();
+    assert_eq!(wnd.last_frame_stats(), None);
+
+    wnd.enable_frame_stats(true);
+    *size.write() = Size::new(20., 20.);
+    wnd.draw_frame();
+    let stats = wnd.last_frame_stats().unwrap();
+    assert!(stats.laid_out_count > 0);
+    assert!(!stats.layout_roots.is_empty());
+    assert!(stats.draw_calls > 0);
+
+    wnd.enable_frame_stats(false);
+    *size.write() = Size::new(30., 30.);
+    wnd.draw_frame();
+    assert_eq!(wnd.last_frame_stats(), None);
+  }
+
+  #[cfg(not(target_arch = "wasm32"))]
+  fn rasterize(frame: &Frame) -> PixelImage {
+    let Frame { commands, viewport, surface, transform } = frame;
+    let viewport = transform
+      .outer_transformed_rect(viewport)
+      .round_out()
+      .to_i32()
+      .cast_unit();
+    ribir_dev_helper::wgpu_render_viewport(commands, viewport, transform, *surface)
+  }
+
+  /// Assert the pixel is the `color`, the rasterizer may dither the color a
+  /// little.
+  #[cfg(not(target_arch = "wasm32"))]
+  fn assert_pixel(img: &PixelImage, x: u32, y: u32, color: [u8; 4]) {
+    let start = ((y * img.width() + x) * 4) as usize;
+    let pixel = &img.pixel_bytes()[start..start + 4];
+    let near = pixel
+      .iter()
+      .zip(color)
+      .all(|(p, c)| p.abs_diff(c) <= 4);
+    assert!(near, "pixel {pixel:?} is not {color:?}");
+  }
+
+  #[cfg(not(target_arch = "wasm32"))]
+  #[test]
+  fn capture_before_first_frame() {
+    reset_test_env!();
+
+    let w = fn_widget! {
+      @MockBox { size: Size::new(50., 50.), background: Color::RED }
+    };
+    let wnd = TestWindow::new_with_size(w, Size::new(100., 100.));
+    wnd.set_rasterizer(rasterize);
+
+    let img = wnd.capture_frame().unwrap();
+    assert_eq!((img.width(), img.height()), (100, 100));
+    assert_pixel(&img, 25, 25, Color::RED.into_components());
+    let surface = Window::surface_color().into_components();
+    assert_pixel(&img, 75, 75, surface);
+  }
+
+  #[cfg(not(target_arch = "wasm32"))]
+  #[test]
+  fn capture_widget_bounds() {
+    reset_test_env!();
+
+    let w = fn_widget! {
+      @MockMulti {
+        @MockBox { size: Size::new(50., 50.), background: Color::RED }
+        @MockBox { size: Size::new(40., 20.), background: Color::BLUE }
+      }
+    };
+    let wnd = TestWindow::new_with_size(w, Size::new(100., 100.));
+    wnd.set_device_pixel_ratio(2.);
+    wnd.set_rasterizer(rasterize);
+
+    let blue = {
+      let tree = wnd.widget_tree.borrow();
+      let multi = tree.root().first_child(&tree.arena).unwrap();
+      multi.last_child(&tree.arena).unwrap()
+    };
+    let img = wnd.capture_widget(blue).unwrap();
+    assert_eq!((img.width(), img.height()), (80, 40));
+    assert_pixel(&img, 40, 20, Color::BLUE.into_components());
+  }
+
+  #[cfg(not(target_arch = "wasm32"))]
+  #[test]
+  fn capture_keep_changes_to_draw() {
+    reset_test_env!();
+
+    let color = Stateful::new(Color::RED);
+    let c_color = color.clone_watcher();
+    let w = fn_widget! {
+      @MockBox { size: Size::new(50., 50.), background: pipe!(*$c_color) }
+    };
+    let mut wnd = TestWindow::new_with_size(w, Size::new(100., 100.));
+    wnd.set_rasterizer(rasterize);
+    wnd.draw_frame();
+
+    *color.write() = Color::BLUE;
+    wnd.run_frame_tasks();
+    let img = wnd.capture_frame().unwrap();
+    assert_pixel(&img, 25, 25, Color::BLUE.into_components());
+
+    // The capture doesn't draw to the window, the next frame still does.
+    assert!(wnd.draw_frame());
+    let shell = wnd.shell_wnd().borrow();
+    let shell = shell
+      .as_any()
+      .downcast_ref::<TestShellWindow>()
+      .unwrap();
+    let img = rasterize(shell.last_frame.as_ref().unwrap());
+    assert_pixel(&img, 25, 25, Color::BLUE.into_components());
+  }
+
+  #[test]
+  fn capture_without_rasterizer() {
+    reset_test_env!();
+
+    let wnd = TestWindow::new(fn_widget! { MockBox { size: Size::new(100., 100.) } });
+    assert!(wnd.capture_frame().is_none());
+  }
 }
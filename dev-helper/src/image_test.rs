@@ -195,3 +195,30 @@ pub fn wgpu_render_commands(
   backend.end_frame();
   block_on(img).unwrap()
 }
+
+/// Render the commands in the device `viewport` by wgpu backend, and return
+/// the image of the `viewport`, which is moved to the origin of the image.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn wgpu_render_viewport(
+  commands: &[ribir_painter::PaintCommand], viewport: ribir_geom::DeviceRect,
+  transform: &Transform, surface: ribir_painter::Color,
+) -> PixelImage {
+  use futures::executor::block_on;
+  use ribir_geom::DeviceRect;
+  use ribir_gpu::{GPUBackend, GPUBackendImpl, Texture};
+  use ribir_painter::PainterBackend;
+
+  let mut gpu_impl = block_on(ribir_gpu::WgpuImpl::headless());
+
+  let rect = DeviceRect::from_size(viewport.size);
+  let offset = viewport.origin.to_f32().to_vector().cast_unit();
+  let matrix = transform.then_translate(-offset);
+  let mut texture = gpu_impl.new_texture(rect.size, ColorFormat::Rgba8);
+  let mut backend = GPUBackend::new(gpu_impl);
+
+  backend.begin_frame(surface);
+  backend.draw_commands(rect, commands, &matrix, &mut texture);
+  let img = texture.copy_as_image(&rect, backend.get_impl_mut());
+  backend.end_frame();
+  block_on(img).unwrap()
+}
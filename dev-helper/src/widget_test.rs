@@ -222,7 +222,7 @@ macro_rules! widget_image_test {
         let _scope = unsafe { AppCtx::new_lock_scope() };
         let mut wnd = TestWindow::new_with_size($widget_fn(), $size);
         wnd.draw_frame();
-        let Frame { commands, viewport, surface} = wnd.take_last_frame().unwrap();
+        let Frame { commands, viewport, surface, .. } = wnd.take_last_frame().unwrap();
         let viewport = viewport.to_i32().cast_unit();
         let img = wgpu_render_commands(&commands, viewport, surface);
         let name = format!("{}_with_default_by_wgpu", std::stringify!($widget_fn));
@@ -239,7 +239,7 @@ macro_rules! widget_image_test {
 
         let mut wnd = TestWindow::new_with_size($widget_fn(), $size);
         wnd.draw_frame();
-        let Frame { commands, viewport, surface} = wnd.take_last_frame().unwrap();
+        let Frame { commands, viewport, surface, .. } = wnd.take_last_frame().unwrap();
         let viewport = viewport.to_i32().cast_unit();
         let img = wgpu_render_commands(&commands, viewport, surface);
         let name = format!("{}_with_material_by_wgpu", std::stringify!($widget_fn));
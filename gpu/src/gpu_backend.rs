@@ -2,17 +2,17 @@ use std::error::Error;
 
 use guillotiere::euclid::Vector2D;
 use ribir_geom::{
-  rect_corners, transform_to_device_rect, DeviceRect, DeviceSize, Point, Transform,
+  rect_corners, transform_to_device_rect, DeviceRect, DeviceSize, Point, Rect, Transform,
 };
 use ribir_painter::{
-  image::ColorFormat, Color, PaintCommand, PaintPath, PaintPathAction, PainterBackend, PathCommand,
-  PixelImage, Vertex, VertexBuffers,
+  image::ColorFormat, BlendMode, Color, PaintCommand, PaintPath, PaintPathAction, PainterBackend,
+  Path, PathCommand, PathSegment, PixelImage, ShadowStyle, StrokeOptions, Vertex, VertexBuffers,
 };
 
 use crate::{
-  ColorAttr, GPUBackendImpl, GradientStopPrimitive, ImagePrimIndex, ImgPrimitive,
-  LinearGradientPrimIndex, LinearGradientPrimitive, MaskLayer, RadialGradientPrimIndex,
-  RadialGradientPrimitive,
+  ColorAttr, ConicGradientPrimIndex, ConicGradientPrimitive, GPUBackendImpl, GradientStopPrimitive,
+  ImagePrimIndex, ImgPrimitive, LinearGradientPrimIndex, LinearGradientPrimitive, MaskLayer,
+  RadialGradientPrimIndex, RadialGradientPrimitive,
 };
 
 mod atlas;
@@ -32,6 +32,9 @@ pub struct GPUBackend<Impl: GPUBackendImpl> {
   linear_gradient_prims: Vec<LinearGradientPrimitive>,
   linear_gradient_stops: Vec<GradientStopPrimitive>,
   linear_gradient_vertices_buffer: VertexBuffers<LinearGradientPrimIndex>,
+  conic_gradient_prims: Vec<ConicGradientPrimitive>,
+  conic_gradient_stops: Vec<GradientStopPrimitive>,
+  conic_gradient_vertices_buffer: VertexBuffers<ConicGradientPrimIndex>,
   current_phase: CurrentPhase,
   tex_ids_map: TextureIdxMap,
   viewport: DeviceRect,
@@ -39,6 +42,8 @@ pub struct GPUBackend<Impl: GPUBackendImpl> {
   clip_layer_stack: Vec<ClipLayer>,
   skip_clip_cnt: usize,
   surface_color: Option<Color>,
+  /// The blend mode of the current draw phase.
+  blend_mode: BlendMode,
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
@@ -48,6 +53,7 @@ enum CurrentPhase {
   Img,
   RadialGradient,
   LinearGradient,
+  ConicGradient,
 }
 
 struct ClipLayer {
@@ -140,10 +146,14 @@ where
       linear_gradient_vertices_buffer: VertexBuffers::with_capacity(256, 512),
       linear_gradient_stops: vec![],
       linear_gradient_prims: vec![],
+      conic_gradient_vertices_buffer: VertexBuffers::with_capacity(256, 512),
+      conic_gradient_stops: vec![],
+      conic_gradient_prims: vec![],
       img_prims: vec![],
       current_phase: CurrentPhase::None,
       viewport: DeviceRect::zero(),
       surface_color: Some(Color::WHITE),
+      blend_mode: BlendMode::SrcOver,
     }
   }
 
@@ -158,7 +168,9 @@ where
     output: &mut Impl::Texture,
   ) {
     match cmd {
-      PaintCommand::Path(cmd @ PathCommand { path, paint_bounds, transform, action }) => {
+      PaintCommand::Path(
+        cmd @ PathCommand { path, paint_bounds, transform, action, blend_mode },
+      ) => {
         if self.skip_clip_cnt > 0 {
           if matches!(action, PaintPathAction::Clip) {
             self.skip_clip_cnt += 1;
@@ -179,12 +191,20 @@ where
         if !self.can_batch_path_command(cmd) {
           self.new_draw_phase(output);
         }
+        if !matches!(action, PaintPathAction::Clip) {
+          self.blend_mode = *blend_mode;
+        }
 
         let matrix = transform.then(global_matrix);
-        let (rect, mask_head) = self.new_mask_layer(&viewport, &matrix, path);
+        let (rect, mask_head) = match action {
+          PaintPathAction::Shadow(shadow) => {
+            self.new_shadow_mask_layer(&viewport, &matrix, path, shadow)
+          }
+          _ => self.new_mask_layer(&viewport, &matrix, path),
+        };
 
         match &action {
-          PaintPathAction::Color(color) => {
+          PaintPathAction::Color(color) | PaintPathAction::Shadow(ShadowStyle { color, .. }) => {
             let color = color.into_components();
             let color_attr = ColorAttr { color, mask_head };
             let buffer = &mut self.color_vertices_buffer;
@@ -241,6 +261,28 @@ where
             add_rect_vertices(rect, output_tex_size, LinearGradientPrimIndex(prim_idx), buffer);
             self.current_phase = CurrentPhase::LinearGradient;
           }
+          PaintPathAction::Conic(conic) => {
+            let stop = (self.conic_gradient_stops.len() << 16 | conic.stops.len()) as u32;
+            let prim = ConicGradientPrimitive {
+              transform: matrix.inverse().unwrap().to_array(),
+              center: conic.center.to_array(),
+              start_angle: conic.start_angle,
+              stop,
+              mask_head,
+              winding: conic.winding as u32,
+            };
+            let stops = conic.stops.iter().map(GradientStopPrimitive::new);
+            self.conic_gradient_stops.extend(stops);
+            let prim_idx = self.conic_gradient_prims.len() as u32;
+            self.conic_gradient_prims.push(prim);
+            let buffer = &mut self.conic_gradient_vertices_buffer;
+            add_rect_vertices(rect, output_tex_size, ConicGradientPrimIndex(prim_idx), buffer);
+            self.current_phase = CurrentPhase::ConicGradient;
+          }
+          PaintPathAction::Backdrop(radius) => {
+            let (slice, ts) = self.blur_backdrop(&viewport, &matrix, *radius, output);
+            self.draw_img_slice(slice, &ts, mask_head, 1., output_tex_size, rect);
+          }
           PaintPathAction::Clip => self
             .clip_layer_stack
             .push(ClipLayer { viewport, mask_head }),
@@ -253,7 +295,7 @@ where
           self.clip_layer_stack.pop();
         }
       }
-      PaintCommand::Bundle { transform, opacity, bounds, cmds } => {
+      PaintCommand::Bundle { transform, opacity, bounds, cmds, blend_mode } => {
         let matrix = transform.then(global_matrix);
         let scale = self.tex_mgr.cache_scale(&bounds.size, &matrix);
         let cache_size = bounds.size * scale;
@@ -308,9 +350,10 @@ where
           // scale to the cache size.
           .then_scale(cache_scale, cache_scale);
 
-        if !self.can_batch_img_path() {
+        if !self.can_batch_img_path(*blend_mode) {
           self.new_draw_phase(output);
         }
+        self.blend_mode = *blend_mode;
         let mask_head = self
           .clip_layer_stack
           .last()
@@ -320,10 +363,12 @@ where
     }
   }
 
-  fn can_batch_img_path(&self) -> bool {
+  fn can_batch_img_path(&self, blend_mode: BlendMode) -> bool {
     let limits = self.gpu_impl.limits();
     self.current_phase == CurrentPhase::None
       || (self.current_phase == CurrentPhase::Img
+        && self.blend_mode == blend_mode
+        && blend_mode != BlendMode::Overlay
         && self.tex_ids_map.len() < limits.max_tex_load - 1
         && self.img_prims.len() < limits.max_image_primitives)
   }
@@ -394,6 +439,16 @@ where
       .indices
       .clear();
     self.linear_gradient_stops.clear();
+    self.conic_gradient_prims.clear();
+    self
+      .conic_gradient_vertices_buffer
+      .indices
+      .clear();
+    self
+      .conic_gradient_vertices_buffer
+      .vertices
+      .clear();
+    self.conic_gradient_stops.clear();
   }
 
   fn draw_img_slice(
@@ -420,11 +475,23 @@ where
   fn can_batch_path_command(&self, cmd: &PathCommand) -> bool {
     let limits = self.gpu_impl.limits();
     let tex_used = self.tex_ids_map.len();
+    let other_blend = cmd.blend_mode != self.blend_mode;
     match (self.current_phase, &cmd.action) {
+      // The backdrop must be drawn to the output before it's blurred.
+      (_, PaintPathAction::Backdrop(_)) => false,
       (CurrentPhase::None, _) => true,
+      // Every path composited in the shader reads the backdrop before the phase,
+      // so it can't overlap the other paths in the same phase.
+      (_, action) if (other_blend || cmd.blend_mode == BlendMode::Overlay)
+        && !matches!(action, PaintPathAction::Clip) =>
+      {
+        false
+      }
       (_, PaintPathAction::Clip) | (CurrentPhase::Color, PaintPathAction::Color(_)) => {
         tex_used < limits.max_tex_load
       }
+      // An inset shadow may use two masks.
+      (CurrentPhase::Color, PaintPathAction::Shadow(_)) => tex_used < limits.max_tex_load - 1,
       (CurrentPhase::Img, PaintPathAction::Image { .. }) => {
         tex_used < limits.max_tex_load - 1 && self.img_prims.len() < limits.max_image_primitives
       }
@@ -438,6 +505,11 @@ where
           && self.linear_gradient_prims.len() < limits.max_linear_gradient_primitives
           && self.linear_gradient_stops.len() < limits.max_gradient_stop_primitives
       }
+      (CurrentPhase::ConicGradient, PaintPathAction::Conic(_)) => {
+        tex_used < limits.max_tex_load
+          && self.conic_gradient_prims.len() < limits.max_conic_gradient_primitives
+          && self.conic_gradient_stops.len() < limits.max_gradient_stop_primitives
+      }
       _ => false,
     }
   }
@@ -482,6 +554,83 @@ where
     (points, index as i32)
   }
 
+  fn new_shadow_mask_layer(
+    &mut self, view: &DeviceRect, matrix: &Transform, path: &PaintPath, shadow: &ShadowStyle,
+  ) -> ([Point; 4], i32) {
+    // An inset shadow only paints inside the path.
+    let prev_mask_idx = if shadow.inset {
+      self.new_mask_layer(view, matrix, path).1
+    } else {
+      self.current_clip_mask_index()
+    };
+
+    let Transform { m11, m12, m21, m22, .. } = matrix;
+    let scale = (m11.abs() + m12.abs()).max(m21.abs() + m22.abs());
+    // Clamp the radius in device pixels to limit the kernel size of the blur.
+    let radius = (shadow.blur * scale).min(ShadowStyle::MAX_BLUR);
+    let silhouette_bounds = shadow
+      .silhouette_bounds(path.bounds())
+      .expect("An empty silhouette should not be painted.");
+
+    // The blur reads the pixels around the painted area, so the mask covers
+    // them too.
+    let pad = radius.ceil() as i32;
+    let painted = if shadow.inset { path.bounds() } else { &silhouette_bounds };
+    let painted = transform_to_device_rect(painted, matrix).inflate(pad, pad);
+    let area = view
+      .inflate(pad, pad)
+      .intersection(&painted)
+      .unwrap_or(*view);
+
+    let silhouette = Transform::translation(shadow.offset.x, shadow.offset.y).then(matrix);
+    let cover = silhouette
+      .inverse()
+      .unwrap()
+      .outer_transformed_rect(&area.to_f32().cast_unit())
+      .inflate(1., 1.);
+    let (paths, invert) = silhouette_paths(path, shadow, &silhouette, &cover);
+    let (mask, mask_to_view) = self.tex_mgr.store_shadow_path(
+      paths,
+      &silhouette,
+      &area,
+      radius,
+      invert,
+      &mut self.gpu_impl,
+    );
+
+    let index = self.mask_layers.len();
+    let min_max = mask.rect.to_box2d().to_f32();
+    self.mask_layers.push(MaskLayer {
+      transform: mask_to_view.inverse().unwrap().to_array(),
+      min: min_max.min.to_array(),
+      max: min_max.max.to_array(),
+      mask_tex_idx: self.tex_ids_map.tex_idx(mask.tex_id),
+      prev_mask_idx,
+    });
+    (rect_corners(&view.to_f32().cast_unit()), index as i32)
+  }
+
+  /// Blur the `view` of the output, return the blurred slice and the transform
+  /// from the view to the slice.
+  fn blur_backdrop(
+    &mut self, view: &DeviceRect, matrix: &Transform, radius: f32, output: &Impl::Texture,
+  ) -> (TextureSlice, Transform) {
+    let Transform { m11, m12, m21, m22, .. } = matrix;
+    let scale = (m11.abs() + m12.abs()).max(m21.abs() + m22.abs());
+    let radius = (radius * scale).min(ShadowStyle::MAX_BLUR);
+    // The blur reads the pixels around the view.
+    let pad = radius.ceil() as i32;
+    let area = view
+      .inflate(pad, pad)
+      .intersection(&DeviceRect::from_size(output.size()))
+      .unwrap_or(*view);
+    let slice = self
+      .tex_mgr
+      .store_backdrop(output, &area, radius, &mut self.gpu_impl);
+
+    (slice, Transform::translation(-area.origin.x as f32, -area.origin.y as f32))
+  }
+
   fn draw_triangles(&mut self, output: &mut Impl::Texture) {
     let mut color = self.surface_color.take();
     let gpu_impl = &mut self.gpu_impl;
@@ -491,6 +640,14 @@ where
       gpu_impl.load_mask_layers(&self.mask_layers);
     }
 
+    gpu_impl.set_blend_mode(self.blend_mode);
+    if self.blend_mode == BlendMode::Overlay && self.current_phase != CurrentPhase::None {
+      let output_rect = DeviceRect::from_size(output.size());
+      if let Some(rect) = self.viewport.intersection(&output_rect) {
+        gpu_impl.copy_backdrop(output, &rect, color.take());
+      }
+    }
+
     let textures = self.tex_ids_map.all_textures();
     let max_textures = gpu_impl.limits().max_tex_load;
     let mut tex_buffer = Vec::with_capacity(max_textures);
@@ -544,6 +701,18 @@ where
         let rg = 0..self.linear_gradient_vertices_buffer.indices.len() as u32;
         gpu_impl.draw_linear_gradient_triangles(output, rg, color.take())
       }
+      CurrentPhase::ConicGradient
+        if !self
+          .conic_gradient_vertices_buffer
+          .indices
+          .is_empty() =>
+      {
+        gpu_impl.load_conic_gradient_primitives(&self.conic_gradient_prims);
+        gpu_impl.load_conic_gradient_stops(&self.conic_gradient_stops);
+        gpu_impl.load_conic_gradient_vertices(&self.conic_gradient_vertices_buffer);
+        let rg = 0..self.conic_gradient_vertices_buffer.indices.len() as u32;
+        gpu_impl.draw_conic_gradient_triangles(output, rg, color.take())
+      }
       _ => {}
     }
   }
@@ -572,6 +741,95 @@ pub fn vertices_coord(pos: Point, tex_size: DeviceSize) -> [f32; 2] {
   [pos.x / tex_size.width as f32, pos.y / tex_size.height as f32]
 }
 
+/// Return the paths that fill the silhouette of the `shadow` in the mask, and
+/// whether the mask should be inverted.
+///
+/// The spread offsets the outline of the path by a stroke of twice its width.
+/// A growing silhouette is the union of the path and the stroke, and a
+/// shrinking one is the complement of the union of the stroke and the
+/// complement of the path in the `cover` rect.
+fn silhouette_paths(
+  path: &PaintPath, shadow: &ShadowStyle, matrix: &Transform, cover: &Rect,
+) -> (Vec<PaintPath>, bool) {
+  let spread = if shadow.inset { -shadow.spread } else { shadow.spread };
+  let mut invert = shadow.inset;
+  let mut paths = vec![];
+  if spread < 0. {
+    paths.push(complement_path(path, cover).into());
+    invert = !invert;
+  } else {
+    paths.push(path.clone());
+  }
+  if spread != 0. {
+    // The miter join keeps the corners of a rect sharp, like the spread of the
+    // CSS box shadow.
+    let options = StrokeOptions { width: 2. * spread.abs(), ..<_>::default() };
+    paths.extend(path.stroke(&options, Some(matrix)).map(PaintPath::from));
+  }
+  (paths, invert)
+}
+
+/// Return the area in the `cover` rect but not in the `path`, filled by the
+/// non-zero rule.
+fn complement_path(path: &Path, cover: &Rect) -> Path {
+  let mut corners = rect_corners(cover);
+  // Wind the cover rect opposite to the path to cut the path out of it.
+  if (signed_area(path) > 0.) == (polygon_area(&corners) > 0.) {
+    corners.reverse();
+  }
+  let mut builder = Path::builder();
+  builder
+    .begin_path(corners[0])
+    .line_to(corners[1])
+    .line_to(corners[2])
+    .line_to(corners[3])
+    .end_path(true);
+
+  let mut open = false;
+  for seg in path.segments() {
+    match seg {
+      PathSegment::MoveTo(at) => {
+        builder.begin_path(at);
+        open = true;
+      }
+      PathSegment::LineTo(to) => {
+        builder.line_to(to);
+      }
+      PathSegment::QuadTo { ctrl, to } => builder.quadratic_curve_to(ctrl, to),
+      PathSegment::CubicTo { ctrl1, ctrl2, to } => builder.bezier_curve_to(ctrl1, ctrl2, to),
+      PathSegment::Close(close) if open => {
+        builder.end_path(close);
+        open = false;
+      }
+      PathSegment::Close(_) => {}
+    }
+  }
+  builder.build()
+}
+
+/// The signed area of the path, its sign tells the winding direction of the
+/// path. The curves are measured by their control points, that's enough for
+/// the sign.
+fn signed_area(path: &Path) -> f32 {
+  let mut area = 0.;
+  let mut points = vec![];
+  for seg in path.segments() {
+    match seg {
+      PathSegment::MoveTo(at) => points = vec![at],
+      PathSegment::LineTo(to) => points.push(to),
+      PathSegment::QuadTo { ctrl, to } => points.extend([ctrl, to]),
+      PathSegment::CubicTo { ctrl1, ctrl2, to } => points.extend([ctrl1, ctrl2, to]),
+      PathSegment::Close(_) => area += polygon_area(&points),
+    }
+  }
+  area
+}
+
+fn polygon_area(points: &[Point]) -> f32 {
+  let edges = points.iter().zip(points.iter().cycle().skip(1));
+  edges.map(|(a, b)| a.x * b.y - b.x * a.y).sum::<f32>() / 2.
+}
+
 pub fn add_rect_vertices<Attr: Copy>(
   [lt, rt, rb, lb]: [Point; 4], tex_size: DeviceSize, attr: Attr, buffer: &mut VertexBuffers<Attr>,
 ) {
@@ -597,12 +855,24 @@ mod tests {
   use ribir_algo::Resource;
   use ribir_dev_helper::*;
   use ribir_geom::*;
-  use ribir_painter::{Brush, Painter, Path, Svg};
+  use ribir_painter::{Brush, GradientStop, Painter, Path, RRect, Radius, Svg};
 
   use super::*;
 
   fn painter(bounds: Size) -> Painter { Painter::new(Rect::from_size(bounds)) }
 
+  /// Assert the pixel of the image is near the expected color, the software
+  /// renderers not output the exact color.
+  fn assert_pixel(img: &PixelImage, x: usize, y: usize, expect: [u8; 4]) {
+    let start = (y * img.width() as usize + x) * 4;
+    let pixel = &img.pixel_bytes()[start..start + 4];
+    let near = pixel
+      .iter()
+      .zip(expect)
+      .all(|(a, b)| a.abs_diff(b) <= 8);
+    assert!(near, "pixel at ({x}, {y}) is {pixel:?}, expect {expect:?}");
+  }
+
   painter_backend_eq_image_test!(smoke, comparison = 0.001);
   fn smoke() -> Painter {
     fn draw_arrow_path(painter: &mut Painter) {
@@ -784,6 +1054,7 @@ mod tests {
           path: circle.clone().into(),
           transform: Transform::translation(i as f32 * 8., i as f32 * 8.),
           action: PaintPathAction::Color(color),
+          blend_mode: BlendMode::SrcOver,
         })
       })
       .collect();
@@ -793,4 +1064,339 @@ mod tests {
     painter
   }
   painter_backend_eq_image_test!(draw_bundle_svg, comparison = 0.001);
+
+  fn draw_shadows() -> Painter {
+    let mut painter = painter(Size::new(320., 120.));
+    let shadow = ShadowStyle {
+      color: Color::BLACK.with_alpha(0.6),
+      blur: 0.,
+      offset: Vector::zero(),
+      spread: 10.,
+      inset: false,
+    };
+    let card = Path::rect(&rect(20., 30., 60., 60.));
+    painter
+      .draw_shadow(card.clone(), shadow)
+      .set_brush(Color::from_rgb(200, 220, 255))
+      .fill_path(card);
+
+    let circle = Path::circle(Point::new(160., 60.), 40.);
+    let shrunk = ShadowStyle { blur: 4., offset: Vector::new(0., 16.), spread: -12., ..shadow };
+    painter
+      .draw_shadow(circle.clone(), shrunk)
+      .set_brush(Color::from_rgb(200, 220, 255))
+      .fill_path(circle);
+
+    let rrect = Path::rect_round(&rect(220., 20., 80., 80.), &Radius::all(16.));
+    let offset = Vector::new(6., 6.);
+    let inset = ShadowStyle { blur: 2., offset, spread: 4., inset: true, ..shadow };
+    painter
+      .set_brush(Color::from_rgb(200, 220, 255))
+      .fill_path(rrect.clone())
+      .draw_shadow(rrect, inset);
+    painter
+  }
+  painter_backend_eq_image_test!(draw_shadows, comparison = 0.001);
+
+  #[test]
+  fn multiply_blend() {
+    let mut painter = painter(Size::new(32., 32.));
+    painter
+      .set_brush(Color::from_rgb(128, 128, 128))
+      .rect(&rect(0., 0., 32., 32.))
+      .fill();
+    {
+      let mut painter = painter.with_blend_mode(BlendMode::Multiply);
+      painter
+        .set_brush(Color::RED)
+        .rect(&rect(8., 8., 16., 16.))
+        .fill();
+    }
+
+    let viewport = painter.viewport().to_i32().cast_unit();
+    let img = wgpu_render_commands(&painter.finish(), viewport, Color::TRANSPARENT);
+    assert_pixel(&img, 16, 16, [128, 0, 0, 255]);
+    assert_pixel(&img, 2, 2, [128, 128, 128, 255]);
+  }
+
+  #[test]
+  fn multiply_transparent_destination() {
+    let mut painter = painter(Size::new(32., 32.));
+    painter
+      .set_brush(Color::from_rgb(128, 128, 128))
+      .rect(&rect(0., 0., 16., 32.))
+      .fill();
+    {
+      let mut painter = painter.with_blend_mode(BlendMode::Multiply);
+      painter
+        .set_brush(Color::RED)
+        .rect(&rect(0., 0., 32., 32.))
+        .fill();
+    }
+
+    let viewport = painter.viewport().to_i32().cast_unit();
+    let img = wgpu_render_commands(&painter.finish(), viewport, Color::TRANSPARENT);
+    assert_pixel(&img, 8, 16, [128, 0, 0, 255]);
+    // Nothing to multiply with, the source is painted as it is.
+    assert_pixel(&img, 24, 16, [255, 0, 0, 255]);
+  }
+
+  #[test]
+  fn multiply_bundle() {
+    let mut bundle = painter(Size::new(16., 16.));
+    bundle
+      .set_brush(Color::RED)
+      .rect(&rect(0., 0., 16., 16.))
+      .fill();
+    let cmds = bundle.finish().to_vec().into_boxed_slice();
+
+    let mut painter = painter(Size::new(32., 32.));
+    painter
+      .set_brush(Color::from_rgb(128, 128, 128))
+      .rect(&rect(0., 0., 32., 32.))
+      .fill();
+    painter
+      .with_blend_mode(BlendMode::Multiply)
+      .translate(8., 8.)
+      .draw_bundle_commands(rect(0., 0., 16., 16.), Resource::new(cmds));
+
+    let viewport = painter.viewport().to_i32().cast_unit();
+    let img = wgpu_render_commands(&painter.finish(), viewport, Color::TRANSPARENT);
+    assert_pixel(&img, 16, 16, [128, 0, 0, 255]);
+    assert_pixel(&img, 2, 2, [128, 128, 128, 255]);
+  }
+
+  #[test]
+  fn overlay_blend() {
+    let mut painter = painter(Size::new(32., 32.));
+    painter
+      .set_brush(Color::from_rgb(64, 64, 64))
+      .rect(&rect(0., 0., 16., 32.))
+      .fill()
+      .set_brush(Color::from_rgb(192, 192, 192))
+      .rect(&rect(16., 0., 16., 32.))
+      .fill();
+    {
+      let mut painter = painter.with_blend_mode(BlendMode::Overlay);
+      painter
+        .set_brush(Color::RED)
+        .rect(&rect(0., 0., 32., 16.))
+        .fill()
+        .set_brush(Color::BLUE)
+        .rect(&rect(0., 8., 32., 24.))
+        .fill();
+    }
+
+    let viewport = painter.viewport().to_i32().cast_unit();
+    let img = wgpu_render_commands(&painter.finish(), viewport, Color::TRANSPARENT);
+    // Multiply the dark destination and screen the light destination.
+    assert_pixel(&img, 8, 4, [128, 0, 0, 255]);
+    assert_pixel(&img, 24, 4, [255, 130, 130, 255]);
+    // The blue is overlaid on the red overlay result, not the original gray.
+    assert_pixel(&img, 8, 12, [1, 0, 0, 255]);
+    assert_pixel(&img, 8, 24, [0, 0, 128, 255]);
+  }
+
+  fn draw_overlay() -> Painter {
+    let mut painter = painter(Size::new(128., 64.));
+    for (i, gray) in [0, 85, 170, 255].into_iter().enumerate() {
+      painter
+        .set_brush(Color::from_rgb(gray, gray, gray))
+        .rect(&rect(i as f32 * 32., 0., 32., 64.))
+        .fill();
+    }
+
+    painter
+      .with_blend_mode(BlendMode::Overlay)
+      .set_brush(Color::RED.with_alpha(0.8))
+      .circle(Point::new(40., 32.), 28.)
+      .fill()
+      .set_brush(Color::from_rgb(64, 160, 255))
+      .circle(Point::new(88., 32.), 28.)
+      .fill();
+    painter
+  }
+  painter_backend_eq_image_test!(draw_overlay, comparison = 0.001);
+
+  #[test]
+  fn backdrop_blur_only_inside_path() {
+    let mut painter = painter(Size::new(64., 32.));
+    painter
+      .blur_backdrop(Path::rect(&rect(0., 0., 64., 32.)), 4.)
+      .set_brush(Color::BLACK)
+      .rect(&rect(0., 0., 32., 32.))
+      .fill()
+      .blur_backdrop(Path::rect(&rect(16., 0., 16., 32.)), 8.);
+
+    let viewport = painter.viewport().to_i32().cast_unit();
+    let img = wgpu_render_commands(&painter.finish(), viewport, Color::WHITE);
+    // The blur reads the white on the right side of the edge.
+    assert_pixel(&img, 31, 16, [117, 117, 117, 255]);
+    // Outside the path is not blurred.
+    assert_pixel(&img, 32, 16, [255, 255, 255, 255]);
+    assert_pixel(&img, 15, 16, [0, 0, 0, 255]);
+  }
+
+  fn draw_backdrop_blur() -> Painter {
+    let mut painter = painter(Size::new(128., 64.));
+    for (i, color) in [Color::RED, Color::GREEN, Color::BLUE, Color::YELLOW]
+      .into_iter()
+      .enumerate()
+    {
+      painter
+        .set_brush(color)
+        .rect(&rect(i as f32 * 32., 0., 32., 64.))
+        .fill();
+    }
+
+    let card = Path::rect_round(&rect(16., 12., 96., 40.), &Radius::all(12.));
+    painter
+      .blur_backdrop(card.clone(), 8.)
+      .set_brush(Color::WHITE.with_alpha(0.3))
+      .fill_path(card);
+    painter
+  }
+  painter_backend_eq_image_test!(draw_backdrop_blur, comparison = 0.001);
+
+  #[test]
+  fn conic_gradient() {
+    let mut painter = painter(Size::new(100., 100.));
+    let stops = vec![GradientStop::new(Color::RED, 0.), GradientStop::new(Color::BLUE, 1.)];
+    painter
+      .set_brush(Brush::conic_gradient(Point::new(50., 50.), Angle::zero(), stops))
+      .rect(&rect(0., 0., 100., 100.))
+      .fill();
+
+    let viewport = painter.viewport().to_i32().cast_unit();
+    let img = wgpu_render_commands(&painter.finish(), viewport, Color::TRANSPARENT);
+    let assert_pixel = |x: usize, y: usize, expect: [u8; 4]| {
+      let start = (y * img.width() as usize + x) * 4;
+      let pixel = &img.pixel_bytes()[start..start + 4];
+      let near = pixel
+        .iter()
+        .zip(expect)
+        .all(|(a, b)| a.abs_diff(b) <= 2);
+      assert!(near, "pixel at ({x}, {y}) is {pixel:?}, expect {expect:?}");
+    };
+    // The right, the bottom and the left of the center.
+    assert_pixel(90, 50, [255, 0, 0, 255]);
+    assert_pixel(50, 90, [191, 0, 64, 255]);
+    assert_pixel(10, 50, [128, 0, 127, 255]);
+  }
+
+  #[test]
+  fn rrect_corners() {
+    let mut painter = painter(Size::new(100., 100.));
+    let card = RRect::new(rect(0., 0., 100., 40.), Radius::new(20., 0., 20., 20.));
+    painter.set_brush(Color::RED).fill_rrect(&card);
+    // The radii are clamped to a pill.
+    let pill = RRect::new(rect(0., 50., 100., 40.), Radius::all(100.));
+    painter
+      .save()
+      .clip_rrect(&pill)
+      .set_brush(Color::BLUE)
+      .rect(&rect(0., 50., 100., 40.))
+      .fill();
+    painter.restore();
+
+    let viewport = painter.viewport().to_i32().cast_unit();
+    let img = wgpu_render_commands(&painter.finish(), viewport, Color::TRANSPARENT);
+    let pixel = |x: usize, y: usize| {
+      let start = (y * img.width() as usize + x) * 4;
+      &img.pixel_bytes()[start..start + 4]
+    };
+
+    // The pixels out of the rounded corners are transparent.
+    assert_eq!(pixel(1, 1)[3], 0);
+    assert_eq!(pixel(1, 38)[3], 0);
+    assert_eq!(pixel(98, 38)[3], 0);
+    assert_eq!(pixel(1, 51)[3], 0);
+    assert_eq!(pixel(98, 88)[3], 0);
+    // The zero radius corner is sharp.
+    assert_pixel(&img, 98, 1, [255, 0, 0, 255]);
+    assert_pixel(&img, 50, 20, [255, 0, 0, 255]);
+    assert_pixel(&img, 50, 51, [0, 0, 255, 255]);
+    assert_pixel(&img, 2, 70, [0, 0, 255, 255]);
+  }
+
+  #[test]
+  fn opacity_layer() {
+    let mut painter = painter(Size::new(64., 32.));
+    painter
+      .set_brush(Color::WHITE)
+      .rect(&rect(0., 0., 64., 32.))
+      .fill();
+    painter
+      .push_opacity_layer(0.5)
+      .set_brush(Color::RED)
+      .rect(&rect(0., 0., 20., 20.))
+      .fill()
+      .rect(&rect(10., 0., 20., 20.))
+      .fill()
+      .pop_layer();
+    // The same overlapping rects without a layer.
+    painter
+      .save()
+      .apply_alpha(0.5)
+      .set_brush(Color::RED)
+      .rect(&rect(34., 0., 20., 20.))
+      .fill()
+      .rect(&rect(44., 0., 20., 20.))
+      .fill();
+    painter.restore();
+
+    let viewport = painter.viewport().to_i32().cast_unit();
+    let img = wgpu_render_commands(&painter.finish(), viewport, Color::TRANSPARENT);
+
+    // The layer is composited once, so the overlap is same as the single rect.
+    assert_pixel(&img, 15, 10, [255, 128, 128, 255]);
+    assert_pixel(&img, 5, 10, [255, 128, 128, 255]);
+    // The overlap without a layer is blended twice.
+    assert_pixel(&img, 49, 10, [255, 64, 64, 255]);
+    assert_pixel(&img, 39, 10, [255, 128, 128, 255]);
+  }
+
+  #[test]
+  fn nine_patch_corners_unscaled() {
+    // A 6x6 image with 2 pixels red corners, green edges and a blue center, the
+    // outermost pixels of the corners are yellow.
+    let border = |v: usize| !(2..4).contains(&v);
+    let mut data = vec![];
+    for y in 0..6 {
+      for x in 0..6 {
+        let corner = border(x) && border(y);
+        let edge = border(x) || border(y);
+        let color = if corner && (x == 0 || x == 5) && (y == 0 || y == 5) {
+          [255, 255, 0, 255]
+        } else if corner {
+          [255, 0, 0, 255]
+        } else if edge {
+          [0, 255, 0, 255]
+        } else {
+          [0, 0, 255, 255]
+        };
+        data.extend_from_slice(&color);
+      }
+    }
+    let img = Resource::new(PixelImage::new(data.into(), 6, 6, ColorFormat::Rgba8));
+
+    let mut painter = painter(Size::new(30., 30.));
+    let insets = SideOffsets::new(2., 2., 2., 2.);
+    painter.draw_image_nine_patch(img, &insets, &rect(0., 0., 30., 30.));
+
+    let viewport = painter.viewport().to_i32().cast_unit();
+    let img = wgpu_render_commands(&painter.finish(), viewport, Color::TRANSPARENT);
+
+    // The corners are unscaled.
+    assert_pixel(&img, 0, 0, [255, 255, 0, 255]);
+    assert_pixel(&img, 1, 1, [255, 0, 0, 255]);
+    assert_pixel(&img, 29, 0, [255, 255, 0, 255]);
+    assert_pixel(&img, 28, 1, [255, 0, 0, 255]);
+    assert_pixel(&img, 29, 29, [255, 255, 0, 255]);
+    assert_pixel(&img, 28, 28, [255, 0, 0, 255]);
+    // The edges and the center are stretched.
+    assert_pixel(&img, 15, 0, [0, 255, 0, 255]);
+    assert_pixel(&img, 0, 15, [0, 255, 0, 255]);
+    assert_pixel(&img, 15, 15, [0, 0, 255, 255]);
+  }
 }
@@ -33,6 +33,7 @@ pub(super) struct TexturesMgr<T: Texture> {
   target_atlas: Atlas<T>,
   fill_task: Vec<FillTask>,
   fill_task_buffers: VertexBuffers<()>,
+  blur_task: Vec<BlurTask>,
   need_clear_areas: Vec<DeviceRect>,
 }
 
@@ -44,6 +45,12 @@ struct FillTask {
   clip_rect: Option<DeviceRect>,
 }
 
+struct BlurTask {
+  slice: TextureSlice,
+  radius: f32,
+  invert: bool,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub(super) struct TextureSlice {
   pub(super) tex_id: TextureID,
@@ -96,6 +103,7 @@ where
       ),
       fill_task: <_>::default(),
       fill_task_buffers: <_>::default(),
+      blur_task: vec![],
       need_clear_areas: vec![],
     }
   }
@@ -165,6 +173,48 @@ where
     }
   }
 
+  /// Store the silhouette filled by the `paths` and blurred by `radius` pixels
+  /// in texture, and return the texture and a transform that can transform the
+  /// mask to viewport. The mask covers the `area` of the viewport, and its
+  /// alpha is inverted if `invert` is true.
+  pub(super) fn store_shadow_path(
+    &mut self, paths: Vec<PaintPath>, matrix: &Transform, area: &DeviceRect, radius: f32,
+    invert: bool, gpu: &mut T::Host,
+  ) -> (TextureSlice, Transform) {
+    let (_, slice) = self.alpha_allocate(area.size, gpu);
+    let offset = (slice.rect.origin - area.origin)
+      .to_f32()
+      .cast_unit();
+    let ts = matrix.then_translate(offset);
+    // The paths are filled additively, so the silhouette is their union.
+    for path in paths {
+      let task = FillTask { slice, transform: ts, path, clip_rect: Some(slice.rect) };
+      self.fill_task.push(task);
+    }
+    if radius > 0. || invert {
+      self
+        .blur_task
+        .push(BlurTask { slice, radius, invert });
+    }
+
+    let offset = (area.origin - slice.rect.origin).to_f32();
+    (slice, Transform::translation(offset.x, offset.y))
+  }
+
+  /// Copy the `area` of the `output` to a slice that only lives in this frame,
+  /// and blur the slice with `radius`.
+  pub(super) fn store_backdrop(
+    &mut self, output: &T, area: &DeviceRect, radius: f32, gpu: &mut T::Host,
+  ) -> TextureSlice {
+    let dist = self.rgba_atlas.allocate(area.size, gpu);
+    let rect = dist.tex_rect(&self.rgba_atlas);
+    let texture = self.rgba_atlas.get_texture_mut(dist.tex_id());
+    gpu.copy_texture_from_texture(texture, rect.origin, output, area);
+    gpu.blur_rgba_texture(texture, &rect, radius);
+
+    TextureSlice { tex_id: TextureID::Rgba(dist.tex_id()), rect }
+  }
+
   pub(super) fn store_image(
     &mut self, img: &Resource<PixelImage>, gpu: &mut T::Host,
   ) -> TextureSlice {
@@ -353,6 +403,12 @@ where
       gpu_impl.draw_alpha_triangles(&indices, texture);
     }
 
+    // The blur must run after the silhouettes are filled.
+    for BlurTask { slice, radius, invert } in self.blur_task.drain(..) {
+      let texture = id_to_texture_mut!(self, slice.tex_id);
+      gpu_impl.blur_alpha_texture(texture, &slice.rect, radius, invert);
+    }
+
     self.fill_task.clear();
     self.fill_task_buffers.vertices.clear();
     self.fill_task_buffers.indices.clear();
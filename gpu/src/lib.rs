@@ -3,7 +3,7 @@ use std::ops::Range;
 
 pub use gpu_backend::Texture;
 use ribir_geom::{DevicePoint, DeviceRect, DeviceSize};
-use ribir_painter::{image::ColorFormat, Color, GradientStop, VertexBuffers};
+use ribir_painter::{image::ColorFormat, BlendMode, Color, GradientStop, VertexBuffers};
 mod gpu_backend;
 use zerocopy::AsBytes;
 
@@ -34,6 +34,10 @@ pub use wgpu_impl::*;
 ///   |    ^                                      v      |
 ///   |    +----<-----------<---------------------+      |
 ///   |                                                  |
+///   | -> + blur_alpha_texture()-----------------+      |
+///   |    ^                                      v      |
+///   |    +----<-----------<---------------------+      |
+///   |                                                  |
 ///   | -> load_textures()                               |
 ///   | -> load_mask_layers()                            |
 ///   |                                                  |    
@@ -58,8 +62,15 @@ pub use wgpu_impl::*;
 ///   |     |  +------------------------------------+    |
 ///   |     |  | load_linear_gradient_primitives()  |    |
 ///   |     +->| load_linear_gradient_stops()       |    |
-///   |        | load_linear_gradient_vertices()    |    |
-///   |        | draw_linear_gradient_triangles()   |    |
+///   |     |  | load_linear_gradient_vertices()    |    |
+///   |     |  | draw_linear_gradient_triangles()   |    |
+///   |     |  +------------------------------------+    |
+///   |     |                                            |
+///   |     |  +------------------------------------+    |
+///   |     |  | load_conic_gradient_primitives()   |    |
+///   |     +->| load_conic_gradient_stops()        |    |
+///   |        | load_conic_gradient_vertices()     |    |
+///   |        | draw_conic_gradient_triangles()    |    |
 ///   |        +------------------------------------+    |
 ///   +---<----------------------------------------------+
 ///
@@ -85,6 +96,10 @@ pub trait GPUBackendImpl {
   /// Returns the limits of the GPU backend.
   fn limits(&self) -> &DrawPhaseLimits;
 
+  /// Set the blend mode that the following draw triangles methods use to
+  /// composite the triangles into the texture, except the alpha triangles.
+  fn set_blend_mode(&mut self, mode: BlendMode);
+
   /// Create a texture.
   fn new_texture(&mut self, size: DeviceSize, format: ColorFormat) -> Self::Texture;
   /// Load the vertices and indices buffer that `draw_alpha_triangles` &
@@ -100,6 +115,23 @@ pub trait GPUBackendImpl {
   fn draw_alpha_triangles_with_scissor(
     &mut self, indices: &Range<u32>, texture: &mut Self::Texture, scissor: DeviceRect,
   );
+  /// Blur the `rect` area of an alpha texture with a separable Gaussian blur of
+  /// `radius` pixels, and invert the blurred alpha if `invert` is true. Only
+  /// the pixels in the `rect` are read and written.
+  fn blur_alpha_texture(
+    &mut self, texture: &mut Self::Texture, rect: &DeviceRect, radius: f32, invert: bool,
+  );
+  /// Blur the `rect` area of a rgba texture with a separable Gaussian blur of
+  /// `radius` pixels. Only the pixels in the `rect` are read and written.
+  fn blur_rgba_texture(&mut self, texture: &mut Self::Texture, rect: &DeviceRect, radius: f32);
+
+  /// Copy the `rect` area of the `texture` as the backdrop, which the blend
+  /// modes that composite in the shader read, like `BlendMode::Overlay`. And
+  /// use the clear color clear the texture first if it's a Some-Value. Called
+  /// before `load_textures` of the draw phase.
+  fn copy_backdrop(
+    &mut self, texture: &mut Self::Texture, rect: &DeviceRect, clear: Option<Color>,
+  );
 
   /// load textures that will be use in this draw phase
   fn load_textures(&mut self, textures: &[&Self::Texture]);
@@ -131,6 +163,15 @@ pub trait GPUBackendImpl {
   /// Load the vertices and indices buffer that `draw_linear_gradient_triangles`
   /// will use.
   fn load_linear_gradient_vertices(&mut self, buffers: &VertexBuffers<LinearGradientPrimIndex>);
+
+  /// Load the primitives that `draw_conic_gradient_triangles` will use.
+  fn load_conic_gradient_primitives(&mut self, primitives: &[ConicGradientPrimitive]);
+  /// Load the gradient color stops that `draw_conic_gradient_triangles` will
+  /// use.
+  fn load_conic_gradient_stops(&mut self, stops: &[GradientStopPrimitive]);
+  /// Load the vertices and indices buffer that `draw_conic_gradient_triangles`
+  /// will use.
+  fn load_conic_gradient_vertices(&mut self, buffers: &VertexBuffers<ConicGradientPrimIndex>);
   /// Draw pure color triangles in the texture. And use the clear color clear
   /// the texture first if it's a Some-Value
   fn draw_color_triangles(
@@ -153,6 +194,12 @@ pub trait GPUBackendImpl {
     &mut self, texture: &mut Self::Texture, indices: Range<u32>, clear: Option<Color>,
   );
 
+  /// Draw triangles fill with color conic gradient. And use the clear color
+  /// clear the texture first if it's a Some-Value
+  fn draw_conic_gradient_triangles(
+    &mut self, texture: &mut Self::Texture, indices: Range<u32>, clear: Option<Color>,
+  );
+
   fn copy_texture_from_texture(
     &mut self, dist_tex: &mut Self::Texture, copy_to: DevicePoint, from_tex: &Self::Texture,
     from_rect: &DeviceRect,
@@ -176,6 +223,9 @@ pub struct DrawPhaseLimits {
   /// The maximum number of linear gradient primitives that the backend can load
   /// in a single draw
   pub max_linear_gradient_primitives: usize,
+  /// The maximum number of conic gradient primitives that the backend can load
+  /// in a single draw
+  pub max_conic_gradient_primitives: usize,
   /// The maximum number of gradient stops that the backend can load in a single
   /// draw phase
   pub max_gradient_stop_primitives: usize,
@@ -204,6 +254,10 @@ pub struct RadialGradientPrimIndex(u32);
 #[derive(AsBytes, PartialEq, Clone, Copy, Debug)]
 pub struct LinearGradientPrimIndex(u32);
 
+#[repr(packed)]
+#[derive(AsBytes, PartialEq, Clone, Copy, Debug)]
+pub struct ConicGradientPrimIndex(u32);
+
 #[repr(packed)]
 #[derive(AsBytes, PartialEq, Clone, Copy, Debug)]
 pub struct GradientStopPrimitive {
@@ -263,6 +317,26 @@ pub struct LinearGradientPrimitive {
   pub mask_head_and_spread: i32,
 }
 
+#[repr(packed)]
+#[derive(AsBytes, PartialEq, Clone, Copy, Debug)]
+pub struct ConicGradientPrimitive {
+  /// A 2x3 column-major matrix, transform a vertex position to the texture
+  /// position
+  pub transform: [f32; 6],
+  /// The center of the gradient.
+  pub center: [f32; 2],
+  /// The angle of the first stop in radians.
+  pub start_angle: f32,
+  /// The color stop information, there are two parts:
+  /// - The high 16-bit index represents the start index of the color stop.
+  /// - The low 16-bit index represents the size of the color stop.
+  pub stop: u32,
+  /// The index of the head mask layer.
+  pub mask_head: i32,
+  /// The winding of the gradient, 0 for clockwise and 1 for counter-clockwise.
+  pub winding: u32,
+}
+
 #[repr(packed)]
 #[derive(AsBytes, PartialEq, Clone, Copy)]
 pub struct ImgPrimitive {
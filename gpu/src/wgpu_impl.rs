@@ -6,21 +6,23 @@ use std::{
 
 use futures::channel::oneshot;
 use ribir_geom::{DevicePoint, DeviceRect, DeviceSize};
-use ribir_painter::{image::ColorFormat, Color, PixelImage, VertexBuffers};
+use ribir_painter::{image::ColorFormat, BlendMode, Color, PixelImage, VertexBuffers};
 
 use self::{
   draw_alpha_triangles_pass::DrawAlphaTrianglesPass,
   draw_color_triangles_pass::DrawColorTrianglesPass,
+  draw_conic_gradient_pass::DrawConicGradientTrianglesPass,
   draw_img_triangles_pass::DrawImgTrianglesPass,
   draw_linear_gradient_pass::DrawLinearGradientTrianglesPass,
   draw_radial_gradient_pass::DrawRadialGradientTrianglesPass,
-  texture_pass::{ClearTexturePass, CopyTexturePass},
+  texture_pass::{BlurTexturePass, ClearTexturePass, CopyTexturePass},
   uniform::Uniform,
 };
 use crate::{
-  gpu_backend::Texture, ColorAttr, DrawPhaseLimits, GPUBackendImpl, GradientStopPrimitive,
-  ImagePrimIndex, ImgPrimitive, LinearGradientPrimIndex, LinearGradientPrimitive, MaskLayer,
-  RadialGradientPrimIndex, RadialGradientPrimitive,
+  gpu_backend::Texture, ColorAttr, ConicGradientPrimIndex, ConicGradientPrimitive,
+  DrawPhaseLimits, GPUBackendImpl, GradientStopPrimitive, ImagePrimIndex, ImgPrimitive,
+  LinearGradientPrimIndex, LinearGradientPrimitive, MaskLayer, RadialGradientPrimIndex,
+  RadialGradientPrimitive,
 };
 mod shaders;
 mod uniform;
@@ -28,6 +30,7 @@ mod vertex_buffer;
 
 mod draw_alpha_triangles_pass;
 mod draw_color_triangles_pass;
+mod draw_conic_gradient_pass;
 mod draw_img_triangles_pass;
 mod draw_linear_gradient_pass;
 mod draw_radial_gradient_pass;
@@ -46,14 +49,21 @@ pub struct WgpuImpl {
   clear_tex_pass: ClearTexturePass,
   alpha_triangles_pass: DrawAlphaTrianglesPass,
   copy_tex_pass: Option<CopyTexturePass>,
+  blur_tex_pass: Option<BlurTexturePass>,
+  blur_rgba_pass: Option<BlurTexturePass>,
   color_triangles_pass: Option<DrawColorTrianglesPass>,
   img_triangles_pass: Option<DrawImgTrianglesPass>,
   radial_gradient_pass: Option<DrawRadialGradientTrianglesPass>,
   linear_gradient_pass: Option<DrawLinearGradientTrianglesPass>,
+  conic_gradient_pass: Option<DrawConicGradientTrianglesPass>,
   texs_layout: wgpu::BindGroupLayout,
   textures_bind: Option<wgpu::BindGroup>,
   mask_layers_uniform: Uniform<MaskLayer>,
   limits: DrawPhaseLimits,
+  blend_mode: BlendMode,
+  /// The destination colors copied for the blend modes that composite in the
+  /// shader.
+  backdrop: Option<WgpuTexture>,
 }
 
 macro_rules! command_encoder {
@@ -125,6 +135,21 @@ macro_rules! linear_gradient_pass {
   };
 }
 
+macro_rules! conic_gradient_pass {
+  ($backend:ident) => {
+    $backend
+      .conic_gradient_pass
+      .get_or_insert_with(|| {
+        DrawConicGradientTrianglesPass::new(
+          &$backend.device,
+          $backend.mask_layers_uniform.layout(),
+          &$backend.texs_layout,
+          &$backend.limits,
+        )
+      })
+  };
+}
+
 pub(crate) use command_encoder;
 
 pub struct Surface<'a> {
@@ -138,6 +163,8 @@ impl GPUBackendImpl for WgpuImpl {
 
   fn limits(&self) -> &DrawPhaseLimits { &self.limits }
 
+  fn set_blend_mode(&mut self, mode: BlendMode) { self.blend_mode = mode; }
+
   fn begin_frame(&mut self) {
     if self.command_encoder.is_none() {
       #[cfg(debug_assertions)]
@@ -170,8 +197,25 @@ impl GPUBackendImpl for WgpuImpl {
   }
 
   fn load_textures(&mut self, textures: &[&Self::Texture]) {
+    let backdrop = self.backdrop.as_ref();
     self.textures_bind =
-      Some(textures_bind(&self.device, &self.sampler, &self.texs_layout, textures));
+      Some(textures_bind(&self.device, &self.sampler, &self.texs_layout, textures, backdrop));
+  }
+
+  fn copy_backdrop(
+    &mut self, texture: &mut Self::Texture, rect: &DeviceRect, clear: Option<Color>,
+  ) {
+    if clear.is_some() {
+      let encoder = command_encoder!(self);
+      encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+        label: Some("Clear before backdrop pass"),
+        color_attachments: &[Some(texture.color_attachments(clear))],
+        depth_stencil_attachment: None,
+        timestamp_writes: None,
+        occlusion_query_set: None,
+      });
+    }
+    self.copy_to_backdrop(texture, rect);
   }
 
   fn load_alpha_vertices(&mut self, buffers: &VertexBuffers<()>) {
@@ -216,6 +260,18 @@ impl GPUBackendImpl for WgpuImpl {
     linear_gradient_pass!(self).load_triangles_vertices(buffers, &self.device, &self.queue);
   }
 
+  fn load_conic_gradient_primitives(&mut self, primitives: &[ConicGradientPrimitive]) {
+    conic_gradient_pass!(self).load_conic_gradient_primitives(&self.queue, primitives);
+  }
+
+  fn load_conic_gradient_stops(&mut self, stops: &[GradientStopPrimitive]) {
+    conic_gradient_pass!(self).load_gradient_stops(&self.queue, stops);
+  }
+
+  fn load_conic_gradient_vertices(&mut self, buffers: &VertexBuffers<ConicGradientPrimIndex>) {
+    conic_gradient_pass!(self).load_triangles_vertices(buffers, &self.device, &self.queue);
+  }
+
   fn load_mask_layers(&mut self, layers: &[crate::MaskLayer]) {
     self
       .mask_layers_uniform
@@ -238,6 +294,7 @@ impl GPUBackendImpl for WgpuImpl {
       texture,
       indices,
       clear,
+      self.blend_mode,
       &self.device,
       encoder,
       self.textures_bind.as_ref().unwrap(),
@@ -256,6 +313,26 @@ impl GPUBackendImpl for WgpuImpl {
       texture,
       indices,
       clear,
+      self.blend_mode,
+      &self.device,
+      encoder,
+      self.textures_bind.as_ref().unwrap(),
+      &self.mask_layers_uniform,
+    );
+
+    self.submit()
+  }
+
+  fn draw_conic_gradient_triangles(
+    &mut self, texture: &mut Self::Texture, indices: Range<u32>, clear: Option<Color>,
+  ) {
+    let encoder = command_encoder!(self);
+
+    conic_gradient_pass!(self).draw_triangles(
+      texture,
+      indices,
+      clear,
+      self.blend_mode,
       &self.device,
       encoder,
       self.textures_bind.as_ref().unwrap(),
@@ -278,6 +355,16 @@ impl GPUBackendImpl for WgpuImpl {
     );
   }
 
+  fn blur_alpha_texture(
+    &mut self, texture: &mut Self::Texture, rect: &DeviceRect, radius: f32, invert: bool,
+  ) {
+    self.blur_tex(texture, rect, radius, invert);
+  }
+
+  fn blur_rgba_texture(&mut self, texture: &mut Self::Texture, rect: &DeviceRect, radius: f32) {
+    self.blur_tex(texture, rect, radius, false);
+  }
+
   fn draw_color_triangles(
     &mut self, texture: &mut Self::Texture, indices: Range<u32>, clear: Option<Color>,
   ) {
@@ -286,6 +373,7 @@ impl GPUBackendImpl for WgpuImpl {
       texture,
       indices,
       clear,
+      self.blend_mode,
       &self.device,
       encoder,
       self.textures_bind.as_ref().unwrap(),
@@ -302,6 +390,7 @@ impl GPUBackendImpl for WgpuImpl {
       texture,
       indices,
       clear,
+      self.blend_mode,
       &self.device,
       encoder,
       self.textures_bind.as_ref().unwrap(),
@@ -321,8 +410,14 @@ impl GPUBackendImpl for WgpuImpl {
         from_tex.inner_tex.texture(),
         from_rect,
       );
-    } else {
+    } else if from_tex.usage().contains(wgpu::TextureUsages::TEXTURE_BINDING) {
       self.draw_texture_to_texture(dist_tex, dist_pos, from_tex, from_rect)
+    } else {
+      // The surface texture can't be sampled, so draw it from the backdrop.
+      self.copy_to_backdrop(from_tex, from_rect);
+      let backdrop = self.backdrop.take().unwrap();
+      self.draw_texture_to_texture(dist_tex, dist_pos, &backdrop, from_rect);
+      self.backdrop = Some(backdrop);
     }
   }
 
@@ -418,6 +513,8 @@ impl WgpuTexture {
 
   fn format(&self) -> wgpu::TextureFormat { self.inner_tex.texture().format() }
 
+  fn usage(&self) -> wgpu::TextureUsages { self.inner_tex.texture().usage() }
+
   fn view(&self) -> &wgpu::TextureView { &self.view }
 }
 
@@ -617,6 +714,7 @@ impl WgpuImpl {
       max_image_primitives: uniform_bytes / size_of::<ImgPrimitive>(),
       max_radial_gradient_primitives: uniform_bytes / size_of::<RadialGradientPrimitive>(),
       max_linear_gradient_primitives: uniform_bytes / size_of::<LinearGradientPrimitive>(),
+      max_conic_gradient_primitives: uniform_bytes / size_of::<ConicGradientPrimitive>(),
       max_gradient_stop_primitives: uniform_bytes / size_of::<GradientStopPrimitive>(),
       max_mask_layers: uniform_bytes / size_of::<MaskLayer>(),
     };
@@ -634,27 +732,34 @@ impl WgpuImpl {
       alpha_triangles_pass,
       clear_tex_pass,
       copy_tex_pass: None,
+      blur_tex_pass: None,
+      blur_rgba_pass: None,
       color_triangles_pass: None,
       img_triangles_pass: None,
       radial_gradient_pass: None,
       linear_gradient_pass: None,
+      conic_gradient_pass: None,
       texs_layout,
       textures_bind: None,
       mask_layers_uniform,
       limits,
+      blend_mode: BlendMode::SrcOver,
+      backdrop: None,
     };
 
     let surface = surface.map(|surface| {
       use wgpu::TextureFormat::*;
-      let format = surface
-        .get_capabilities(&adapter)
+      let capabilities = surface.get_capabilities(&adapter);
+      let format = capabilities
         .formats
         .into_iter()
         .find(|&f| f == Rgba8Unorm || f == Bgra8Unorm)
         .expect("No suitable format found for the surface!");
 
+      // The `Overlay` blend copies the surface colors as its backdrop.
+      let copy_src = capabilities.usages & wgpu::TextureUsages::COPY_SRC;
       let config = wgpu::SurfaceConfiguration {
-        usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT | copy_src,
         format,
         width: 0,
         height: 0,
@@ -715,6 +820,56 @@ impl WgpuImpl {
     );
   }
 
+  /// Copy the `rect` area of the `texture` to the same area of the backdrop
+  /// texture, the backdrop texture is created if it can't hold the `texture`.
+  fn copy_to_backdrop(&mut self, texture: &WgpuTexture, rect: &DeviceRect) {
+    let size = texture.size();
+    let format = texture.format();
+    let reuse = self.backdrop.as_ref().is_some_and(|b| {
+      b.format() == format && b.width() >= texture.width() && b.height() >= texture.height()
+    });
+    if !reuse {
+      let tex = self.device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("Backdrop texture"),
+        size: wgpu::Extent3d {
+          width: size.width as u32,
+          height: size.height as u32,
+          depth_or_array_layers: 1,
+        },
+        dimension: wgpu::TextureDimension::D2,
+        format,
+        usage: wgpu::TextureUsages::COPY_DST | wgpu::TextureUsages::TEXTURE_BINDING,
+        mip_level_count: 1,
+        sample_count: 1,
+        view_formats: &[],
+      });
+      self.backdrop = Some(WgpuTexture::from_tex(tex));
+    }
+
+    let backdrop = self.backdrop.as_ref().unwrap();
+    let encoder = command_encoder!(self);
+    let origin = wgpu::Origin3d { x: rect.min_x() as u32, y: rect.min_y() as u32, z: 0 };
+    encoder.copy_texture_to_texture(
+      wgpu::ImageCopyTexture {
+        texture: texture.inner_tex.texture(),
+        mip_level: 0,
+        origin,
+        aspect: wgpu::TextureAspect::All,
+      },
+      wgpu::ImageCopyTexture {
+        texture: backdrop.inner_tex.texture(),
+        mip_level: 0,
+        origin,
+        aspect: wgpu::TextureAspect::All,
+      },
+      wgpu::Extent3d {
+        width: rect.width() as u32,
+        height: rect.height() as u32,
+        depth_or_array_layers: 1,
+      },
+    );
+  }
+
   pub(crate) fn finish_command(&mut self) {
     if let Some(encoder) = self.command_encoder.take() {
       self.command_buffers.push(encoder.finish());
@@ -729,6 +884,50 @@ fn into_wgpu_format(format: ColorFormat) -> wgpu::TextureFormat {
   }
 }
 
+/// Return the blend states of the pipelines that composite the blend mode one
+/// after another, and the `color_output` of the shader that each blend state
+/// requires.
+///
+/// The blend modes are composited by the fixed-function blending, except
+/// `Overlay`, which composites in the shader with the backdrop and replaces the
+/// destination. `Multiply` draws twice, the first draw multiplies the
+/// destination color and keeps its alpha, the second draw adds the source color
+/// where the destination is transparent. `Darken` and `Lighten` are exact only
+/// for the opaque source.
+fn blend_states(mode: BlendMode) -> Vec<(wgpu::BlendState, f64)> {
+  use wgpu::{BlendComponent, BlendFactor, BlendOperation};
+
+  let component =
+    |src_factor, dst_factor, operation| BlendComponent { src_factor, dst_factor, operation };
+  let blend = |color, alpha| wgpu::BlendState { color, alpha };
+  let add = BlendOperation::Add;
+  match mode {
+    BlendMode::SrcOver => vec![(wgpu::BlendState::ALPHA_BLENDING, 0.)],
+    BlendMode::Multiply => {
+      let multiply = component(BlendFactor::Dst, BlendFactor::OneMinusSrcAlpha, add);
+      let keep_alpha = component(BlendFactor::Zero, BlendFactor::One, add);
+      let uncovered = component(BlendFactor::OneMinusDstAlpha, BlendFactor::One, add);
+      vec![
+        (blend(multiply, keep_alpha), 1.),
+        (blend(uncovered, BlendComponent::OVER), 1.),
+      ]
+    }
+    BlendMode::Screen => {
+      let screen = component(BlendFactor::One, BlendFactor::OneMinusSrc, add);
+      vec![(blend(screen, BlendComponent::OVER), 1.)]
+    }
+    BlendMode::Overlay => vec![(wgpu::BlendState::REPLACE, 3.)],
+    BlendMode::Darken => {
+      let darken = component(BlendFactor::One, BlendFactor::One, BlendOperation::Min);
+      vec![(blend(darken, BlendComponent::OVER), 2.)]
+    }
+    BlendMode::Lighten => {
+      let lighten = component(BlendFactor::One, BlendFactor::One, BlendOperation::Max);
+      vec![(blend(lighten, BlendComponent::OVER), 1.)]
+    }
+  }
+}
+
 fn align(width: u32, align: u32) -> u32 {
   match width % align {
     0 => width,
@@ -737,7 +936,8 @@ fn align(width: u32, align: u32) -> u32 {
 }
 
 fn textures_layout(device: &wgpu::Device) -> wgpu::BindGroupLayout {
-  let mut entries: [MaybeUninit<wgpu::BindGroupLayoutEntry>; 1 + TEX_PER_DRAW] =
+  // The sampler, the textures and the backdrop.
+  let mut entries: [MaybeUninit<wgpu::BindGroupLayoutEntry>; 2 + TEX_PER_DRAW] =
     unsafe { MaybeUninit::uninit().assume_init() };
   entries[0].write(wgpu::BindGroupLayoutEntry {
     binding: 0,
@@ -758,7 +958,7 @@ fn textures_layout(device: &wgpu::Device) -> wgpu::BindGroupLayout {
       count: None,
     });
   }
-  let entries: [wgpu::BindGroupLayoutEntry; 1 + TEX_PER_DRAW] =
+  let entries: [wgpu::BindGroupLayoutEntry; 2 + TEX_PER_DRAW] =
     unsafe { std::mem::transmute(entries) };
 
   device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
@@ -769,24 +969,28 @@ fn textures_layout(device: &wgpu::Device) -> wgpu::BindGroupLayout {
 
 fn textures_bind(
   device: &wgpu::Device, sampler: &wgpu::Sampler, layout: &wgpu::BindGroupLayout,
-  textures: &[&WgpuTexture],
+  textures: &[&WgpuTexture], backdrop: Option<&WgpuTexture>,
 ) -> wgpu::BindGroup {
   assert!(!textures.is_empty());
   assert!(textures.len() <= TEX_PER_DRAW);
 
-  let mut entries: [MaybeUninit<wgpu::BindGroupEntry>; 1 + TEX_PER_DRAW] =
+  let mut entries: [MaybeUninit<wgpu::BindGroupEntry>; 2 + TEX_PER_DRAW] =
     unsafe { MaybeUninit::uninit().assume_init() };
   entries[0]
     .write(wgpu::BindGroupEntry { binding: 0, resource: wgpu::BindingResource::Sampler(sampler) });
   for (i, entry) in entries.iter_mut().enumerate().skip(1) {
-    // if the texture is not enough, use the first texture to fill the gap
-    let view = textures.get(i - 1).unwrap_or(&textures[0]).view();
+    let view = if i == 1 + TEX_PER_DRAW {
+      backdrop.unwrap_or(textures[0]).view()
+    } else {
+      // if the texture is not enough, use the first texture to fill the gap
+      textures.get(i - 1).unwrap_or(&textures[0]).view()
+    };
     entry.write(wgpu::BindGroupEntry {
       binding: i as u32,
       resource: wgpu::BindingResource::TextureView(view),
     });
   }
-  let entries: [wgpu::BindGroupEntry; 1 + TEX_PER_DRAW] = unsafe { std::mem::transmute(entries) };
+  let entries: [wgpu::BindGroupEntry; 2 + TEX_PER_DRAW] = unsafe { std::mem::transmute(entries) };
 
   device.create_bind_group(&wgpu::BindGroupDescriptor {
     layout,
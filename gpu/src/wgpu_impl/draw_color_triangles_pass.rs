@@ -1,15 +1,17 @@
-use std::{mem::size_of, ops::Range};
+use std::{collections::HashMap, mem::size_of, ops::Range};
 
-use ribir_painter::{Color, Vertex, VertexBuffers};
+use ribir_painter::{BlendMode, Color, Vertex, VertexBuffers};
 
-use super::{shaders::color_triangles_shader, uniform::Uniform, vertex_buffer::VerticesBuffer};
+use super::{
+  blend_states, shaders::color_triangles_shader, uniform::Uniform, vertex_buffer::VerticesBuffer,
+};
 use crate::{ColorAttr, MaskLayer, WgpuTexture};
 
 pub struct DrawColorTrianglesPass {
   vertices_buffer: VerticesBuffer<ColorAttr>,
   layout: wgpu::PipelineLayout,
-  pipeline: Option<wgpu::RenderPipeline>,
-  shader: wgpu::ShaderModule,
+  pipelines: ahash::HashMap<BlendMode, Vec<wgpu::RenderPipeline>>,
+  shader: String,
   format: Option<wgpu::TextureFormat>,
 }
 
@@ -20,17 +22,14 @@ impl DrawColorTrianglesPass {
   ) -> Self {
     let vertices_buffer = VerticesBuffer::new(512, 1024, device);
 
-    let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
-      label: Some("Color triangles shader"),
-      source: wgpu::ShaderSource::Wgsl(color_triangles_shader(max_mask_layers).into()),
-    });
+    let shader = color_triangles_shader(max_mask_layers);
     let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
       label: Some("Color triangles pipeline layout"),
       bind_group_layouts: &[mask_layout, texs_layout],
       push_constant_ranges: &[],
     });
 
-    Self { layout, vertices_buffer, pipeline: None, shader, format: None }
+    Self { layout, vertices_buffer, pipelines: <_>::default(), shader, format: None }
   }
 
   pub fn load_triangles_vertices(
@@ -44,11 +43,11 @@ impl DrawColorTrianglesPass {
   #[allow(clippy::too_many_arguments)]
   pub fn draw_triangles(
     &mut self, texture: &WgpuTexture, indices: Range<u32>, clear: Option<Color>,
-    device: &wgpu::Device, encoder: &mut wgpu::CommandEncoder, textures_bind: &wgpu::BindGroup,
-    mask_layer_storage: &Uniform<MaskLayer>,
+    blend_mode: BlendMode, device: &wgpu::Device, encoder: &mut wgpu::CommandEncoder,
+    textures_bind: &wgpu::BindGroup, mask_layer_storage: &Uniform<MaskLayer>,
   ) {
-    self.update(texture.format(), device);
-    let pipeline = self.pipeline.as_ref().unwrap();
+    self.update(texture.format(), blend_mode, device);
+    let pipelines = &self.pipelines[&blend_mode];
 
     let color_attachments = texture.color_attachments(clear);
     let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
@@ -64,79 +63,97 @@ impl DrawColorTrianglesPass {
     rpass.set_bind_group(0, mask_layer_storage.bind_group(), &[]);
     rpass.set_bind_group(1, textures_bind, &[]);
 
-    rpass.set_pipeline(pipeline);
-    rpass.draw_indexed(indices, 0, 0..1);
+    for pipeline in pipelines {
+      rpass.set_pipeline(pipeline);
+      rpass.draw_indexed(indices.clone(), 0, 0..1);
+    }
   }
 
-  fn update(&mut self, format: wgpu::TextureFormat, device: &wgpu::Device) {
+  fn update(&mut self, format: wgpu::TextureFormat, blend_mode: BlendMode, device: &wgpu::Device) {
     if self.format != Some(format) {
-      self.pipeline.take();
+      self.pipelines.clear();
       self.format = Some(format);
     }
 
-    if self.pipeline.is_none() {
-      let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-        label: Some("Color triangles pipeline"),
-        layout: Some(&self.layout),
-        vertex: wgpu::VertexState {
-          module: &self.shader,
-          entry_point: "vs_main",
-          buffers: &[wgpu::VertexBufferLayout {
-            array_stride: size_of::<Vertex<ColorAttr>>() as wgpu::BufferAddress,
-            step_mode: wgpu::VertexStepMode::Vertex,
-            attributes: &[
-              // position
-              wgpu::VertexAttribute {
-                offset: 0,
-                shader_location: 0,
-                format: wgpu::VertexFormat::Float32x2,
-              },
-              // color
-              wgpu::VertexAttribute {
-                offset: 8,
-                shader_location: 1,
-                format: wgpu::VertexFormat::Unorm8x4,
-              },
-              // mask_head
-              wgpu::VertexAttribute {
-                offset: 12,
-                shader_location: 2,
-                format: wgpu::VertexFormat::Sint32,
+    if !self.pipelines.contains_key(&blend_mode) {
+      let pipelines = blend_states(blend_mode)
+        .into_iter()
+        .map(|(blend, color_output)| {
+          // The GL backend shares one program between the pipelines of a shader
+          // module whatever their constants are, so every pipeline has its own
+          // module.
+          let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Color triangles shader"),
+            source: wgpu::ShaderSource::Wgsl(self.shader.as_str().into()),
+          });
+          let constants = HashMap::from([("color_output".to_owned(), color_output)]);
+          device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Color triangles pipeline"),
+            layout: Some(&self.layout),
+            vertex: wgpu::VertexState {
+              module: &shader,
+              entry_point: "vs_main",
+              buffers: &[wgpu::VertexBufferLayout {
+                array_stride: size_of::<Vertex<ColorAttr>>() as wgpu::BufferAddress,
+                step_mode: wgpu::VertexStepMode::Vertex,
+                attributes: &[
+                  // position
+                  wgpu::VertexAttribute {
+                    offset: 0,
+                    shader_location: 0,
+                    format: wgpu::VertexFormat::Float32x2,
+                  },
+                  // color
+                  wgpu::VertexAttribute {
+                    offset: 8,
+                    shader_location: 1,
+                    format: wgpu::VertexFormat::Unorm8x4,
+                  },
+                  // mask_head
+                  wgpu::VertexAttribute {
+                    offset: 12,
+                    shader_location: 2,
+                    format: wgpu::VertexFormat::Sint32,
+                  },
+                ],
+              }],
+              compilation_options: Default::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+              module: &shader,
+              entry_point: "fs_main",
+              targets: &[Some(wgpu::ColorTargetState {
+                format,
+                blend: Some(blend),
+                write_mask: wgpu::ColorWrites::all(),
+              })],
+              compilation_options: wgpu::PipelineCompilationOptions {
+                constants: &constants,
+                ..Default::default()
               },
-            ],
-          }],
-          compilation_options: Default::default(),
-        },
-        fragment: Some(wgpu::FragmentState {
-          module: &self.shader,
-          entry_point: "fs_main",
-          targets: &[Some(wgpu::ColorTargetState {
-            format,
-            blend: Some(wgpu::BlendState::ALPHA_BLENDING),
-            write_mask: wgpu::ColorWrites::all(),
-          })],
-          compilation_options: Default::default(),
-        }),
-        primitive: wgpu::PrimitiveState {
-          topology: wgpu::PrimitiveTopology::TriangleList,
-          strip_index_format: None,
-          front_face: wgpu::FrontFace::Ccw,
-          // Always draw rect with transform, there is no distinction between front and back,
-          // everything needs to be drawn.
-          cull_mode: None,
-          unclipped_depth: false,
-          polygon_mode: wgpu::PolygonMode::Fill,
-          conservative: false,
-        },
-        depth_stencil: None,
-        multisample: wgpu::MultisampleState {
-          count: 1,
-          mask: !0,
-          alpha_to_coverage_enabled: false,
-        },
-        multiview: None,
-      });
-      self.pipeline = Some(pipeline);
+            }),
+            primitive: wgpu::PrimitiveState {
+              topology: wgpu::PrimitiveTopology::TriangleList,
+              strip_index_format: None,
+              front_face: wgpu::FrontFace::Ccw,
+              // Always draw rect with transform, there is no distinction between front and back,
+              // everything needs to be drawn.
+              cull_mode: None,
+              unclipped_depth: false,
+              polygon_mode: wgpu::PolygonMode::Fill,
+              conservative: false,
+            },
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState {
+              count: 1,
+              mask: !0,
+              alpha_to_coverage_enabled: false,
+            },
+            multiview: None,
+          })
+        })
+        .collect();
+      self.pipelines.insert(blend_mode, pipelines);
     }
   }
 }
@@ -0,0 +1,187 @@
+use std::{collections::HashMap, mem::size_of, ops::Range};
+
+use ribir_painter::{BlendMode, Color, Vertex, VertexBuffers};
+
+use super::{
+  blend_states, shaders::conic_gradient_shader, uniform::Uniform, vertex_buffer::VerticesBuffer,
+};
+use crate::{
+  ConicGradientPrimIndex, ConicGradientPrimitive, DrawPhaseLimits, GradientStopPrimitive,
+  MaskLayer, WgpuTexture,
+};
+
+pub struct DrawConicGradientTrianglesPass {
+  vertices_buffer: VerticesBuffer<ConicGradientPrimIndex>,
+  pipelines: ahash::HashMap<BlendMode, Vec<wgpu::RenderPipeline>>,
+  shader: String,
+  format: Option<wgpu::TextureFormat>,
+  prims_uniform: Uniform<ConicGradientPrimitive>,
+  stops_uniform: Uniform<GradientStopPrimitive>,
+  layout: wgpu::PipelineLayout,
+}
+
+impl DrawConicGradientTrianglesPass {
+  pub fn new(
+    device: &wgpu::Device, mask_layout: &wgpu::BindGroupLayout,
+    texs_layout: &wgpu::BindGroupLayout, limits: &DrawPhaseLimits,
+  ) -> Self {
+    let vertices_buffer = VerticesBuffer::new(512, 1024, device);
+    let shader = conic_gradient_shader(limits);
+
+    let prims_uniform =
+      Uniform::new(device, wgpu::ShaderStages::FRAGMENT, limits.max_conic_gradient_primitives);
+    let stops_unifrom =
+      Uniform::new(device, wgpu::ShaderStages::FRAGMENT, limits.max_gradient_stop_primitives);
+    let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+      label: Some("update triangles pipeline layout"),
+      bind_group_layouts: &[
+        mask_layout,
+        texs_layout,
+        prims_uniform.layout(),
+        stops_unifrom.layout(),
+      ],
+      push_constant_ranges: &[],
+    });
+    Self {
+      vertices_buffer,
+      pipelines: <_>::default(),
+      shader,
+      format: None,
+      prims_uniform,
+      stops_uniform: stops_unifrom,
+      layout,
+    }
+  }
+
+  pub fn load_triangles_vertices(
+    &mut self, buffers: &VertexBuffers<ConicGradientPrimIndex>, device: &wgpu::Device,
+    queue: &wgpu::Queue,
+  ) {
+    self
+      .vertices_buffer
+      .write_buffer(buffers, device, queue);
+  }
+
+  pub fn load_conic_gradient_primitives(
+    &mut self, queue: &wgpu::Queue, primitives: &[ConicGradientPrimitive],
+  ) {
+    self.prims_uniform.write_buffer(queue, primitives);
+  }
+
+  pub fn load_gradient_stops(&mut self, queue: &wgpu::Queue, stops: &[GradientStopPrimitive]) {
+    self.stops_uniform.write_buffer(queue, stops);
+  }
+
+  #[allow(clippy::too_many_arguments)]
+  pub fn draw_triangles(
+    &mut self, texture: &WgpuTexture, indices: Range<u32>, clear: Option<Color>,
+    blend_mode: BlendMode, device: &wgpu::Device, encoder: &mut wgpu::CommandEncoder,
+    textures_bind: &wgpu::BindGroup, mask_layer_uniform: &Uniform<MaskLayer>,
+  ) {
+    self.update(texture.format(), blend_mode, device);
+    let pipelines = &self.pipelines[&blend_mode];
+
+    let color_attachments = texture.color_attachments(clear);
+    let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+      label: Some("Conic triangles render pass"),
+      color_attachments: &[Some(color_attachments)],
+      depth_stencil_attachment: None,
+      timestamp_writes: None,
+      occlusion_query_set: None,
+    });
+
+    rpass.set_vertex_buffer(0, self.vertices_buffer.vertices().slice(..));
+    rpass.set_index_buffer(self.vertices_buffer.indices().slice(..), wgpu::IndexFormat::Uint32);
+    rpass.set_bind_group(0, mask_layer_uniform.bind_group(), &[]);
+    rpass.set_bind_group(1, textures_bind, &[]);
+    rpass.set_bind_group(2, self.prims_uniform.bind_group(), &[]);
+    rpass.set_bind_group(3, self.stops_uniform.bind_group(), &[]);
+
+    for pipeline in pipelines {
+      rpass.set_pipeline(pipeline);
+      rpass.draw_indexed(indices.clone(), 0, 0..1);
+    }
+  }
+
+  fn update(&mut self, format: wgpu::TextureFormat, blend_mode: BlendMode, device: &wgpu::Device) {
+    if self.format != Some(format) {
+      self.pipelines.clear();
+      self.format = Some(format);
+    }
+
+    if !self.pipelines.contains_key(&blend_mode) {
+      let pipelines = blend_states(blend_mode)
+        .into_iter()
+        .map(|(blend, color_output)| {
+          // The GL backend shares one program between the pipelines of a shader
+          // module whatever their constants are, so every pipeline has its own
+          // module.
+          let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Conic gradient triangles shader"),
+            source: wgpu::ShaderSource::Wgsl(self.shader.as_str().into()),
+          });
+          let constants = HashMap::from([("color_output".to_owned(), color_output)]);
+          device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Conic triangles pipeline"),
+            layout: Some(&self.layout),
+            vertex: wgpu::VertexState {
+              module: &shader,
+              entry_point: "vs_main",
+              buffers: &[wgpu::VertexBufferLayout {
+                array_stride: size_of::<Vertex<ConicGradientPrimIndex>>() as wgpu::BufferAddress,
+                step_mode: wgpu::VertexStepMode::Vertex,
+                attributes: &[
+                  // position
+                  wgpu::VertexAttribute {
+                    offset: 0,
+                    shader_location: 0,
+                    format: wgpu::VertexFormat::Float32x2,
+                  },
+                  // prim_idx
+                  wgpu::VertexAttribute {
+                    offset: 8,
+                    shader_location: 1,
+                    format: wgpu::VertexFormat::Uint32,
+                  },
+                ],
+              }],
+              compilation_options: Default::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+              module: &shader,
+              entry_point: "fs_main",
+              targets: &[Some(wgpu::ColorTargetState {
+                format,
+                blend: Some(blend),
+                write_mask: wgpu::ColorWrites::all(),
+              })],
+              compilation_options: wgpu::PipelineCompilationOptions {
+                constants: &constants,
+                ..Default::default()
+              },
+            }),
+            primitive: wgpu::PrimitiveState {
+              topology: wgpu::PrimitiveTopology::TriangleList,
+              strip_index_format: None,
+              front_face: wgpu::FrontFace::Ccw,
+              // Always draw rect with transform, there is no distinction between front and back,
+              // everything needs to be drawn.
+              cull_mode: None,
+              unclipped_depth: false,
+              polygon_mode: wgpu::PolygonMode::Fill,
+              conservative: false,
+            },
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState {
+              count: 1,
+              mask: !0,
+              alpha_to_coverage_enabled: false,
+            },
+            multiview: None,
+          })
+        })
+        .collect();
+      self.pipelines.insert(blend_mode, pipelines);
+    }
+  }
+}
@@ -1,8 +1,10 @@
-use std::{mem::size_of, ops::Range};
+use std::{collections::HashMap, mem::size_of, ops::Range};
 
-use ribir_painter::{Color, Vertex, VertexBuffers};
+use ribir_painter::{BlendMode, Color, Vertex, VertexBuffers};
 
-use super::{shaders::linear_gradient_shader, uniform::Uniform, vertex_buffer::VerticesBuffer};
+use super::{
+  blend_states, shaders::linear_gradient_shader, uniform::Uniform, vertex_buffer::VerticesBuffer,
+};
 use crate::{
   DrawPhaseLimits, GradientStopPrimitive, LinearGradientPrimIndex, LinearGradientPrimitive,
   MaskLayer, WgpuTexture,
@@ -10,8 +12,8 @@ use crate::{
 
 pub struct DrawLinearGradientTrianglesPass {
   vertices_buffer: VerticesBuffer<LinearGradientPrimIndex>,
-  pipeline: Option<wgpu::RenderPipeline>,
-  shader: wgpu::ShaderModule,
+  pipelines: ahash::HashMap<BlendMode, Vec<wgpu::RenderPipeline>>,
+  shader: String,
   format: Option<wgpu::TextureFormat>,
   prims_uniform: Uniform<LinearGradientPrimitive>,
   stops_uniform: Uniform<GradientStopPrimitive>,
@@ -24,10 +26,7 @@ impl DrawLinearGradientTrianglesPass {
     texs_layout: &wgpu::BindGroupLayout, limits: &DrawPhaseLimits,
   ) -> Self {
     let vertices_buffer = VerticesBuffer::new(512, 1024, device);
-    let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
-      label: Some("Linear gradient triangles shader"),
-      source: wgpu::ShaderSource::Wgsl(linear_gradient_shader(limits).into()),
-    });
+    let shader = linear_gradient_shader(limits);
 
     let prims_uniform =
       Uniform::new(device, wgpu::ShaderStages::FRAGMENT, limits.max_linear_gradient_primitives);
@@ -45,7 +44,7 @@ impl DrawLinearGradientTrianglesPass {
     });
     Self {
       vertices_buffer,
-      pipeline: None,
+      pipelines: <_>::default(),
       shader,
       format: None,
       prims_uniform,
@@ -76,11 +75,11 @@ impl DrawLinearGradientTrianglesPass {
   #[allow(clippy::too_many_arguments)]
   pub fn draw_triangles(
     &mut self, texture: &WgpuTexture, indices: Range<u32>, clear: Option<Color>,
-    device: &wgpu::Device, encoder: &mut wgpu::CommandEncoder, textures_bind: &wgpu::BindGroup,
-    mask_layer_uniform: &Uniform<MaskLayer>,
+    blend_mode: BlendMode, device: &wgpu::Device, encoder: &mut wgpu::CommandEncoder,
+    textures_bind: &wgpu::BindGroup, mask_layer_uniform: &Uniform<MaskLayer>,
   ) {
-    self.update(texture.format(), device);
-    let pipeline = self.pipeline.as_ref().unwrap();
+    self.update(texture.format(), blend_mode, device);
+    let pipelines = &self.pipelines[&blend_mode];
 
     let color_attachments = texture.color_attachments(clear);
     let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
@@ -98,73 +97,91 @@ impl DrawLinearGradientTrianglesPass {
     rpass.set_bind_group(2, self.prims_uniform.bind_group(), &[]);
     rpass.set_bind_group(3, self.stops_uniform.bind_group(), &[]);
 
-    rpass.set_pipeline(pipeline);
-    rpass.draw_indexed(indices, 0, 0..1);
+    for pipeline in pipelines {
+      rpass.set_pipeline(pipeline);
+      rpass.draw_indexed(indices.clone(), 0, 0..1);
+    }
   }
 
-  fn update(&mut self, format: wgpu::TextureFormat, device: &wgpu::Device) {
+  fn update(&mut self, format: wgpu::TextureFormat, blend_mode: BlendMode, device: &wgpu::Device) {
     if self.format != Some(format) {
-      self.pipeline.take();
+      self.pipelines.clear();
       self.format = Some(format);
     }
 
-    if self.pipeline.is_none() {
-      let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-        label: Some("Linear triangles pipeline"),
-        layout: Some(&self.layout),
-        vertex: wgpu::VertexState {
-          module: &self.shader,
-          entry_point: "vs_main",
-          buffers: &[wgpu::VertexBufferLayout {
-            array_stride: size_of::<Vertex<LinearGradientPrimIndex>>() as wgpu::BufferAddress,
-            step_mode: wgpu::VertexStepMode::Vertex,
-            attributes: &[
-              // position
-              wgpu::VertexAttribute {
-                offset: 0,
-                shader_location: 0,
-                format: wgpu::VertexFormat::Float32x2,
-              },
-              // prim_idx
-              wgpu::VertexAttribute {
-                offset: 8,
-                shader_location: 1,
-                format: wgpu::VertexFormat::Uint32,
+    if !self.pipelines.contains_key(&blend_mode) {
+      let pipelines = blend_states(blend_mode)
+        .into_iter()
+        .map(|(blend, color_output)| {
+          // The GL backend shares one program between the pipelines of a shader
+          // module whatever their constants are, so every pipeline has its own
+          // module.
+          let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Linear gradient triangles shader"),
+            source: wgpu::ShaderSource::Wgsl(self.shader.as_str().into()),
+          });
+          let constants = HashMap::from([("color_output".to_owned(), color_output)]);
+          device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Linear triangles pipeline"),
+            layout: Some(&self.layout),
+            vertex: wgpu::VertexState {
+              module: &shader,
+              entry_point: "vs_main",
+              buffers: &[wgpu::VertexBufferLayout {
+                array_stride: size_of::<Vertex<LinearGradientPrimIndex>>() as wgpu::BufferAddress,
+                step_mode: wgpu::VertexStepMode::Vertex,
+                attributes: &[
+                  // position
+                  wgpu::VertexAttribute {
+                    offset: 0,
+                    shader_location: 0,
+                    format: wgpu::VertexFormat::Float32x2,
+                  },
+                  // prim_idx
+                  wgpu::VertexAttribute {
+                    offset: 8,
+                    shader_location: 1,
+                    format: wgpu::VertexFormat::Uint32,
+                  },
+                ],
+              }],
+              compilation_options: Default::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+              module: &shader,
+              entry_point: "fs_main",
+              targets: &[Some(wgpu::ColorTargetState {
+                format,
+                blend: Some(blend),
+                write_mask: wgpu::ColorWrites::all(),
+              })],
+              compilation_options: wgpu::PipelineCompilationOptions {
+                constants: &constants,
+                ..Default::default()
               },
-            ],
-          }],
-          compilation_options: Default::default(),
-        },
-        fragment: Some(wgpu::FragmentState {
-          module: &self.shader,
-          entry_point: "fs_main",
-          targets: &[Some(wgpu::ColorTargetState {
-            format,
-            blend: Some(wgpu::BlendState::ALPHA_BLENDING),
-            write_mask: wgpu::ColorWrites::all(),
-          })],
-          compilation_options: Default::default(),
-        }),
-        primitive: wgpu::PrimitiveState {
-          topology: wgpu::PrimitiveTopology::TriangleList,
-          strip_index_format: None,
-          front_face: wgpu::FrontFace::Ccw,
-          // Always draw rect with transform, there is no distinction between front and back,
-          // everything needs to be drawn.
-          cull_mode: None,
-          unclipped_depth: false,
-          polygon_mode: wgpu::PolygonMode::Fill,
-          conservative: false,
-        },
-        depth_stencil: None,
-        multisample: wgpu::MultisampleState {
-          count: 1,
-          mask: !0,
-          alpha_to_coverage_enabled: false,
-        },
-        multiview: None,
-      });
-      self.pipeline = Some(pipeline);
+            }),
+            primitive: wgpu::PrimitiveState {
+              topology: wgpu::PrimitiveTopology::TriangleList,
+              strip_index_format: None,
+              front_face: wgpu::FrontFace::Ccw,
+              // Always draw rect with transform, there is no distinction between front and back,
+              // everything needs to be drawn.
+              cull_mode: None,
+              unclipped_depth: false,
+              polygon_mode: wgpu::PolygonMode::Fill,
+              conservative: false,
+            },
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState {
+              count: 1,
+              mask: !0,
+              alpha_to_coverage_enabled: false,
+            },
+            multiview: None,
+          })
+        })
+        .collect();
+      self.pipelines.insert(blend_mode, pipelines);
     }
   }
 }
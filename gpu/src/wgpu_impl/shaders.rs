@@ -84,7 +84,7 @@ fn fs_main(input: FragInput) -> @location(0) vec4<f32> {
     let res = calc_offset(pos.x, pos.y, prim.start_center.x, prim.start_center.y, prim.start_radius, prim.end_center.x, prim.end_center.y, prim.end_radius);
 
     if res[0] < 0. || (prim.start_radius != prim.end_radius && res[1] < (prim.start_radius / (prim.start_radius - prim.end_radius))) {
-        return vec4<f32>(1., 1., 1., alpha);
+        return output_color(vec4<f32>(1., 1., 1., alpha), input.pos.xy);
     }
     var offset = res[1];
     if prim.spread == 0u {
@@ -108,7 +108,7 @@ fn fs_main(input: FragInput) -> @location(0) vec4<f32> {
     offset = max(prev.offset, min(next.offset, offset));
     let weight1 = (next.offset - offset) / (next.offset - prev.offset);
     let weight2 = 1. - weight1;
-    return (prev.color * weight1 + next.color * weight2) * vec4<f32>(1., 1., 1., alpha);
+    return output_color((prev.color * weight1 + next.color * weight2) * vec4<f32>(1., 1., 1., alpha), input.pos.xy);
 }
 // input the center and radius of the circles, return the tag of resolvable (1. mean resolvable and -1. unresolvable) and the offset if tag is resolvable.
 fn calc_offset(x: f32, y: f32, x_0: f32, y_0: f32, r_0: f32, x_1: f32, y_1: f32, r_1: f32) -> vec2<f32> {
@@ -179,12 +179,12 @@ fn get_stop(idx: u32) -> Stop {
     }
 }
 
-fn unpackUnorm4x8(packed: u32) -> vec4<f32> {
+fn unpackUnorm4x8(v: u32) -> vec4<f32> {
     return vec4<f32>(
-        f32((packed & 0xff000000) >> 24) / 255.0,
-        f32((packed & 0x00ff0000) >> 16) / 255.0,
-        f32((packed & 0x0000ff00) >> 8) / 255.0,
-        f32((packed & 0x000000ff) >> 0) / 255.0
+        f32((v & 0xff000000) >> 24) / 255.0,
+        f32((v & 0x00ff0000) >> 16) / 255.0,
+        f32((v & 0x0000ff00) >> 8) / 255.0,
+        f32((v & 0x000000ff) >> 0) / 255.0
     );
 }"#
 }
@@ -260,12 +260,12 @@ fn calc_offset(x: f32, y: f32, x_0: f32, y_0: f32, x_1: f32, y_1: f32) -> f32 {
     return (dx_0 * dx_1_0 + dy_0 * dy_1_0) / (dx_1_0 * dx_1_0 + dy_1_0 * dy_1_0);
 }
 
-fn unpackUnorm4x8(packed: u32) -> vec4<f32> {
+fn unpackUnorm4x8(v: u32) -> vec4<f32> {
     return vec4<f32>(
-        f32((packed & 0xff000000) >> 24) / 255.0,
-        f32((packed & 0x00ff0000) >> 16) / 255.0,
-        f32((packed & 0x0000ff00) >> 8) / 255.0,
-        f32((packed & 0x000000ff) >> 0) / 255.0
+        f32((v & 0xff000000) >> 24) / 255.0,
+        f32((v & 0x00ff0000) >> 16) / 255.0,
+        f32((v & 0x0000ff00) >> 8) / 255.0,
+        f32((v & 0x000000ff) >> 0) / 255.0
     );
 }
 
@@ -294,7 +294,7 @@ fn fs_main(input: FragInput) -> @location(0) vec4<f32> {
     }
 
     if prim.start_position.x == prim.end_position.x && prim.start_position.y == prim.end_position.y {
-        return vec4<f32>(1., 1., 1., alpha);
+        return output_color(vec4<f32>(1., 1., 1., alpha), input.pos.xy);
     }
     var offset = calc_offset(pos.x, pos.y, prim.start_position.x, prim.start_position.y, prim.end_position.x, prim.end_position.y);
     let spread = abs(prim.mask_head_and_spread & 0x0000ffff);
@@ -321,7 +321,138 @@ fn fs_main(input: FragInput) -> @location(0) vec4<f32> {
     offset = max(prev.offset, min(next.offset, offset));
     let weight1 = (next.offset - offset) / (next.offset - prev.offset);
     let weight2 = 1. - weight1;
-    return (prev.color * weight1 + next.color * weight2) * vec4<f32>(1., 1., 1., alpha);
+    return output_color((prev.color * weight1 + next.color * weight2) * vec4<f32>(1., 1., 1., alpha), input.pos.xy);
+}
+"#
+}
+
+pub fn conic_gradient_shader(limits: &DrawPhaseLimits) -> String {
+  basic_template(limits.max_mask_layers)
+    + &format!(
+      r#"
+@group(2) @binding(0)
+var<uniform> prims: array<Primitive, {}>;
+
+@group(3) @binding(0)
+var<uniform> stops: array<StopPair, {}>;"#,
+      limits.max_conic_gradient_primitives,
+      limits.max_gradient_stop_primitives / 2,
+    )
+    + r#"
+const TAU: f32 = 6.283185307179586;
+
+struct Vertex {
+  @location(0) pos: vec2<f32>,
+  @location(1) @interpolate(flat) prim_idx: u32,
+};
+
+struct FragInput {
+  @builtin(position) pos: vec4<f32>,
+  @location(0) @interpolate(flat) prim_idx: u32,
+}
+
+@vertex
+fn vs_main(v: Vertex) -> FragInput {
+    var input: FragInput;
+    // convert from gpu-backend coords(0..1) to wgpu corrds(-1..1)
+    let pos = v.pos * vec2(2., -2.) + vec2(-1., 1.);
+    input.pos = vec4<f32>(pos, 0.0, 1.0);
+    input.prim_idx = v.prim_idx;
+    return input;
+}
+
+// A pair of stops. This arrangement aligns the stops with 16 bytes, minimizing excessive padding.
+struct StopPair {
+    color1: u32,
+    offset1: f32,
+    color2: u32,
+    offset2: f32,
+}
+
+struct Stop {
+    color: vec4<f32>,
+    offset: f32,
+}
+
+// Since a the different alignment between WebGPU and WebGL, we not use 
+// mat3x2<f32> in the struct, but use vec2<f32> instead. Then, we compose it.
+struct Primitive {
+  t0: vec2<f32>,
+  t1: vec2<f32>,
+  t2: vec2<f32>,
+  center: vec2<f32>,
+  start_angle: f32,
+  // A value mixed stop_start(u16) and stop_cnt(u16)
+  stop: u32,
+  mask_head: i32,
+  // 0 for clockwise, 1 for counter-clockwise
+  winding: u32,
+}
+
+fn unpackUnorm4x8(v: u32) -> vec4<f32> {
+    return vec4<f32>(
+        f32((v & 0xff000000) >> 24) / 255.0,
+        f32((v & 0x00ff0000) >> 16) / 255.0,
+        f32((v & 0x0000ff00) >> 8) / 255.0,
+        f32((v & 0x000000ff) >> 0) / 255.0
+    );
+}
+
+fn get_stop(idx: u32) -> Stop {
+    let pair = stops[idx / 2];
+    if idx % 2 == 0 {
+        return Stop(unpackUnorm4x8(pair.color1), pair.offset1);
+    } else {
+        return Stop(unpackUnorm4x8(pair.color2), pair.offset2);
+    }
+}
+
+@fragment
+fn fs_main(input: FragInput) -> @location(0) vec4<f32> {
+    let prim = prims[input.prim_idx];
+    let pos = mat3x2(prim.t0, prim.t1, prim.t2) * vec3(input.pos.xy, 1.);
+
+    var alpha = 1.;
+    var mask_idx = prim.mask_head;
+    loop {
+        if mask_idx < 0 { break; }
+
+        let mask = mask_layers[u32(mask_idx)];
+        alpha *= mask_sample(mask, input.pos.xy);
+        mask_idx = mask.prev_mask_idx;
+    }
+
+    let v = pos - prim.center;
+    var angle = atan2(v.y, v.x) - prim.start_angle;
+    if prim.winding == 1u {
+        angle = -angle;
+    }
+    // The stops cover the full circle. `fract` of a tiny negative angle rounds
+    // to 1, which is the start angle too, so wrap it to the first stop.
+    var offset = fract(angle / TAU);
+    if offset >= 1. {
+        offset = 0.;
+    }
+
+    let stop_start = prim.stop >> 16;
+    let stop_cnt = prim.stop & 0x0000ffff;
+    var prev = get_stop(stop_start);
+    if stop_cnt == 1u {
+        return output_color(prev.color * vec4<f32>(1., 1., 1., alpha), input.pos.xy);
+    }
+    var next = get_stop(stop_start + 1);
+    for (var i = 2u; i < stop_cnt && next.offset < offset; i++) {
+        prev = next;
+        next = get_stop(stop_start + i);
+    }
+
+    offset = max(prev.offset, min(next.offset, offset));
+    var weight2 = 0.;
+    if next.offset > prev.offset {
+        weight2 = (offset - prev.offset) / (next.offset - prev.offset);
+    }
+    let weight1 = 1. - weight2;
+    return output_color((prev.color * weight1 + next.color * weight2) * vec4<f32>(1., 1., 1., alpha), input.pos.xy);
 }
 "#
 }
@@ -367,7 +498,7 @@ pub fn color_triangles_shader(max_mask_layers: usize) -> String {
       }
   
       color.a *= alpha;
-      return color;
+      return output_color(color, input.pos.xy);
   }
  "#
 }
@@ -440,7 +571,7 @@ pub fn img_triangles_shader(limits: &DrawPhaseLimits) -> String {
       }
   
       color.a = color.a * alpha * prim.opacity;
-      return color;
+      return output_color(color, f.pos.xy);
   }
   
   fn img_sample(prim: ImgPrimitive, pos: vec2<f32>) -> vec4<f32> {
@@ -491,6 +622,10 @@ var tex_5: texture_2d<f32>;
 var tex_6: texture_2d<f32>;
 @group(1) @binding(8)
 var tex_7: texture_2d<f32>;
+// The destination colors copied before the draw, only the blend modes that
+// composite in the shader read it.
+@group(1) @binding(9)
+var backdrop: texture_2d<f32>;
 
 // Since a the different alignment between WebGPU and WebGL, we not use 
 // mat3x2<f32> in the struct, but use vec2<f32> instead. Then, we compose it.
@@ -530,5 +665,33 @@ fn mask_tex_sampler(tex: texture_2d<f32>, mask: MaskLayer, pos: vec2<f32>) -> f3
     let tex_size = vec2(f32(size.x), f32(size.y));
     return textureSampleLevel(tex, s_sampler, mask_pos / tex_size, 0.).r;
 }
+
+// How the fragment color is output, decided by the blend mode of the pipeline.
+// 0 for the straight alpha, 1 for the premultiplied alpha, 2 for the
+// premultiplied alpha over white and 3 for the color overlaid on the backdrop.
+override color_output: u32 = 0u;
+
+fn output_color(color: vec4<f32>, pos: vec2<f32>) -> vec4<f32> {
+    switch color_output {
+      case 1u: { return vec4<f32>(color.rgb * color.a, color.a); }
+      case 2u: { return vec4<f32>(color.rgb * color.a + vec3<f32>(1. - color.a), color.a); }
+      case 3u: { return overlay(color, textureLoad(backdrop, vec2<i32>(pos), 0)); }
+      default: { return color; }
+    }
+}
+
+// Composite the `src` color over the `dst` color with the overlay blend, the
+// same math as `BlendMode::blend` of the painter.
+fn overlay(src: vec4<f32>, dst: vec4<f32>) -> vec4<f32> {
+    let alpha = src.a + dst.a * (1. - src.a);
+    if alpha == 0. {
+        return vec4<f32>(0.);
+    }
+    let multiply = 2. * src.rgb * dst.rgb;
+    let screen = 1. - 2. * (1. - src.rgb) * (1. - dst.rgb);
+    let blended = select(screen, multiply, dst.rgb <= vec3<f32>(0.5));
+    let mixed = (1. - dst.a) * src.rgb + dst.a * blended;
+    return vec4<f32>((src.a * mixed + dst.a * dst.rgb * (1. - src.a)) / alpha, alpha);
+}
 "#
 }
@@ -1,8 +1,13 @@
 @group(0) @binding(0) 
 var<uniform> view_size: vec4<u32>;
 
+struct FragInput {
+  @builtin(position) pos: vec4<f32>,
+  @location(0) @interpolate(flat) value: f32,
+}
+
 @vertex
-fn vs_main(@location(0) pos: vec2<f32>, @builtin(instance_index) instance: u32) -> @builtin(position) vec4<f32> {
+fn vs_main(@location(0) pos: vec2<f32>, @builtin(instance_index) instance: u32) -> FragInput {
   // An 8x sample provides better quality than a 4x sample in text rendering. 
   // Text rendering often prioritizes horizontal resolution due to LCD subpixel rendering. 
   // High-DPI displays without subpixel rendering already provide sufficient quality, 
@@ -51,11 +56,17 @@ fn vs_main(@location(0) pos: vec2<f32>, @builtin(instance_index) instance: u32)
     let size = vec2(f32(view_size.x), f32(view_size.y));
     var sample_pos = pos + sample_pattern[instance % 6];
     sample_pos = sample_pos * vec2(2., -2.)  / size + vec2(-1., 1.);
-    return vec4<f32>(sample_pos, 0.0, 1.0);
+
+    var input: FragInput;
+    input.pos = vec4<f32>(sample_pos, 0.0, 1.0);
+    // The 1/6 of a sample can't be stored exactly in 8 bits, six samples of it
+    // sum to 252 or overflow by rounding. So half of the samples write 43 and
+    // the others write 42, and a fully covered pixel is exactly 255.
+    input.value = select(42., 43., instance % 6 < 3) / 255.;
+    return input;
 }
 
 @fragment
-fn fs_main() -> @location(0) vec4<f32> {
-  let value: f32 = 1.0 / 6.0;
-  return vec4(value, value, value, value);
+fn fs_main(input: FragInput) -> @location(0) vec4<f32> {
+  return vec4(input.value, input.value, input.value, input.value);
 }
@@ -0,0 +1,46 @@
+struct BlurParams {
+  src_min: vec2<i32>,
+  src_max: vec2<i32>,
+  offset: vec2<i32>,
+  dir: vec2<i32>,
+  radius: f32,
+  sigma: f32,
+  invert: u32,
+  _dummy: u32,
+}
+
+@group(0) @binding(0)
+var<uniform> params: BlurParams;
+
+@group(1) @binding(0)
+var texture: texture_2d<f32>;
+
+@vertex
+fn vs_main(@builtin(vertex_index) idx: u32) -> @builtin(position) vec4<f32> {
+    // A triangle covers the whole target, the scissor limits the area to draw.
+    let pos = vec2<f32>(f32((idx << 1u) & 2u), f32(idx & 2u));
+    return vec4<f32>(pos * 2. - 1., 0., 1.);
+}
+
+@fragment
+fn fs_main(@builtin(position) pos: vec4<f32>) -> @location(0) vec4<f32> {
+    let center = vec2<i32>(floor(pos.xy)) + params.offset;
+    let max_pos = params.src_max - vec2<i32>(1, 1);
+    let r = i32(ceil(params.radius));
+    var sum = vec4<f32>(0.);
+    var weight = 0.;
+    for (var i = -r; i <= r; i++) {
+        let x = f32(i);
+        let w = exp(-x * x / (2. * params.sigma * params.sigma));
+        let p = clamp(center + params.dir * i, params.src_min, max_pos);
+        sum += textureLoad(texture, p, 0) * w;
+        weight += w;
+    }
+
+    // An alpha texture only keeps the red channel.
+    var color = sum / weight;
+    if params.invert != 0u {
+        color.r = 1. - color.r;
+    }
+    return color;
+}
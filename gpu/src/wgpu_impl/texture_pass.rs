@@ -5,8 +5,10 @@ use ribir_painter::Vertex;
 use wgpu::{include_wgsl, StoreOp};
 use zerocopy::AsBytes;
 
-use super::vertex_buffer::new_vertices;
-use crate::{command_encoder, gpu_backend::Texture, vertices_coord, WgpuImpl, WgpuTexture};
+use super::{uniform::Uniform, vertex_buffer::new_vertices};
+use crate::{
+  command_encoder, gpu_backend::Texture, vertices_coord, GPUBackendImpl, WgpuImpl, WgpuTexture,
+};
 
 pub struct CopyTexturePass {
   pipeline: Option<wgpu::RenderPipeline>,
@@ -132,7 +134,180 @@ impl ClearTexturePass {
   }
 }
 
+pub struct BlurTexturePass {
+  pipeline: wgpu::RenderPipeline,
+  tex_layout: wgpu::BindGroupLayout,
+  params: Uniform<BlurParams>,
+  /// The texture to store the result of the horizontal blur.
+  scratch: Option<WgpuTexture>,
+}
+
+#[repr(C)]
+#[derive(AsBytes, Clone, Copy)]
+struct BlurParams {
+  src_min: [i32; 2],
+  src_max: [i32; 2],
+  /// The offset from the target pixel to the source pixel.
+  offset: [i32; 2],
+  dir: [i32; 2],
+  radius: f32,
+  sigma: f32,
+  invert: u32,
+  _dummy: u32,
+}
+
+impl BlurTexturePass {
+  pub fn new(format: wgpu::TextureFormat, device: &wgpu::Device) -> Self {
+    let shader = device.create_shader_module(include_wgsl!("./shaders/blur_texture.wgsl"));
+
+    let tex_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+      entries: &[wgpu::BindGroupLayoutEntry {
+        binding: 0,
+        visibility: wgpu::ShaderStages::FRAGMENT,
+        ty: wgpu::BindingType::Texture {
+          sample_type: wgpu::TextureSampleType::Float { filterable: false },
+          view_dimension: wgpu::TextureViewDimension::D2,
+          multisampled: false,
+        },
+        count: None,
+      }],
+      label: Some("Blur texture"),
+    });
+    let params = Uniform::new(device, wgpu::ShaderStages::FRAGMENT, 1);
+
+    let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+      label: Some("Blur texture"),
+      bind_group_layouts: &[params.layout(), &tex_layout],
+      push_constant_ranges: &[],
+    });
+
+    let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+      label: Some("Blur texture"),
+      layout: Some(&layout),
+      vertex: wgpu::VertexState {
+        module: &shader,
+        entry_point: "vs_main",
+        buffers: &[],
+        compilation_options: Default::default(),
+      },
+      fragment: Some(wgpu::FragmentState {
+        module: &shader,
+        entry_point: "fs_main",
+        targets: &[Some(wgpu::ColorTargetState {
+          format,
+          blend: Some(wgpu::BlendState::REPLACE),
+          write_mask: wgpu::ColorWrites::all(),
+        })],
+        compilation_options: Default::default(),
+      }),
+      primitive: wgpu::PrimitiveState {
+        topology: wgpu::PrimitiveTopology::TriangleList,
+        strip_index_format: None,
+        front_face: wgpu::FrontFace::Ccw,
+        cull_mode: None,
+        unclipped_depth: false,
+        polygon_mode: wgpu::PolygonMode::Fill,
+        conservative: false,
+      },
+      depth_stencil: None,
+      multisample: wgpu::MultisampleState { count: 1, mask: !0, alpha_to_coverage_enabled: false },
+      multiview: None,
+    });
+
+    Self { pipeline, tex_layout, params, scratch: None }
+  }
+}
+
 impl WgpuImpl {
+  /// Blur the `rect` of an alpha or a rgba texture, first horizontally into
+  /// the scratch texture, then vertically back to the `rect`.
+  pub(crate) fn blur_tex(
+    &mut self, texture: &WgpuTexture, rect: &DeviceRect, radius: f32, invert: bool,
+  ) {
+    let format = texture.format();
+    let pass = self.blur_pass(format);
+    let size = rect.size;
+    let scratch = match pass.scratch.take() {
+      Some(t) if t.width() as i32 >= size.width && t.height() as i32 >= size.height => t,
+      t => {
+        let (w, h) = t.map_or((0, 0), |t| (t.width() as i32, t.height() as i32));
+        let size = DeviceSize::new(w.max(size.width), h.max(size.height));
+        self.new_texture(size, texture.color_format())
+      }
+    };
+
+    let scratch_rect = DeviceRect::from_size(size);
+    let params = |from: &DeviceRect, to: &DeviceRect, dir, invert: bool| BlurParams {
+      src_min: from.min().to_array(),
+      src_max: from.max().to_array(),
+      offset: (from.origin - to.origin).to_array(),
+      dir,
+      radius,
+      sigma: (radius / 2.).max(0.5),
+      invert: invert as u32,
+      _dummy: 0,
+    };
+    let horizontal = params(rect, &scratch_rect, [1, 0], false);
+    self.draw_blur_pass(texture, &scratch, &scratch_rect, horizontal);
+    let vertical = params(&scratch_rect, rect, [0, 1], invert);
+    self.draw_blur_pass(&scratch, texture, rect, vertical);
+
+    self.blur_pass(format).scratch = Some(scratch);
+  }
+
+  /// The blur pass of the texture format, every format has its own pass.
+  fn blur_pass(&mut self, format: wgpu::TextureFormat) -> &mut BlurTexturePass {
+    let Self { device, blur_tex_pass, blur_rgba_pass, .. } = self;
+    let pass = if format == wgpu::TextureFormat::R8Unorm { blur_tex_pass } else { blur_rgba_pass };
+    pass.get_or_insert_with(|| BlurTexturePass::new(format, device))
+  }
+
+  fn draw_blur_pass(
+    &mut self, from: &WgpuTexture, to: &WgpuTexture, to_rect: &DeviceRect, params: BlurParams,
+  ) {
+    let pass = if to.format() == wgpu::TextureFormat::R8Unorm {
+      &mut self.blur_tex_pass
+    } else {
+      &mut self.blur_rgba_pass
+    };
+    let pass = pass.as_mut().unwrap();
+    pass.params.write_buffer(&self.queue, &[params]);
+    let bind_group = self
+      .device
+      .create_bind_group(&wgpu::BindGroupDescriptor {
+        layout: &pass.tex_layout,
+        entries: &[wgpu::BindGroupEntry {
+          binding: 0,
+          resource: wgpu::BindingResource::TextureView(from.view()),
+        }],
+        label: Some("Blur texture bind group"),
+      });
+
+    let encoder = command_encoder!(self);
+    let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+      label: Some("Blur texture"),
+      color_attachments: &[Some(to.color_attachments(None))],
+      depth_stencil_attachment: None,
+      timestamp_writes: None,
+      occlusion_query_set: None,
+    });
+
+    rpass.set_scissor_rect(
+      to_rect.min_x() as u32,
+      to_rect.min_y() as u32,
+      to_rect.width() as u32,
+      to_rect.height() as u32,
+    );
+    rpass.set_pipeline(&pass.pipeline);
+    rpass.set_bind_group(0, pass.params.bind_group(), &[]);
+    rpass.set_bind_group(1, &bind_group, &[]);
+    rpass.draw(0..3, 0..1);
+    drop(rpass);
+
+    // Submit the pass, so the next pass can write its own parameters.
+    self.submit();
+  }
+
   pub(crate) fn draw_texture_to_texture(
     &mut self, dist_tex: &WgpuTexture, dist_at: DevicePoint, from_tex: &WgpuTexture,
     src_rect: &DeviceRect,
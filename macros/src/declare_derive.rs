@@ -233,6 +233,16 @@ pub(crate) fn declare_derive(input: &mut syn::DeriveInput) -> syn::Result<TokenS
           self
         }
 
+        #[doc="Attaches a handler to the widget that is triggered when a double tap occurs, \
+          with the config of the max interval and distance between the two taps."]
+        #vis fn on_double_tap_with(
+          mut self,
+          f: (DoubleTapConfig, impl FnMut(&mut PointerEvent) + 'static),
+        ) -> Self {
+          self.fat_obj = self.fat_obj.on_double_tap_with(f);
+          self
+        }
+
         #[doc="Attaches a handler to the widget that is triggered during the capture \
           phase of a double tap event. This is similar to `on_double_tap`, but it's \
           triggered earlier in the event flow."]
@@ -285,6 +295,40 @@ pub(crate) fn declare_derive(input: &mut syn::DeriveInput) -> syn::Result<TokenS
           self
         }
 
+        #[doc="Attaches a handler to the widget that is triggered by the pan gesture, \
+          with the default `PanConfig`."]
+        #vis fn on_pan(mut self, f: impl FnMut(&Pan, &mut PointerEvent) + 'static) -> Self {
+          self.fat_obj = self.fat_obj.on_pan(f);
+          self
+        }
+
+        #[doc="Attaches a handler to the widget that is triggered by the pan gesture, \
+          with the config of the threshold and the axis lock."]
+        #vis fn on_pan_with(
+          mut self,
+          f: (PanConfig, impl FnMut(&Pan, &mut PointerEvent) + 'static),
+        ) -> Self {
+          self.fat_obj = self.fat_obj.on_pan_with(f);
+          self
+        }
+
+        #[doc="Attaches a handler to the widget that is triggered by the long press \
+          gesture, with the default `LongPressConfig`."]
+        #vis fn on_long_press(mut self, f: impl FnMut(&mut PointerEvent) + 'static) -> Self {
+          self.fat_obj = self.fat_obj.on_long_press(f);
+          self
+        }
+
+        #[doc="Attaches a handler to the widget that is triggered by the long press \
+          gesture, with the config of the duration and the slop."]
+        #vis fn on_long_press_with(
+          mut self,
+          f: (LongPressConfig, impl FnMut(&mut PointerEvent) + 'static),
+        ) -> Self {
+          self.fat_obj = self.fat_obj.on_long_press_with(f);
+          self
+        }
+
         #[doc="Attaches a handler to the widget that is triggered when the user rotates a
           wheel button on a pointing device (typically a mouse)."]
         #vis fn on_wheel(mut self, f: impl FnMut(&mut WheelEvent) + 'static) -> Self {
@@ -543,6 +587,15 @@ pub(crate) fn declare_derive(input: &mut syn::DeriveInput) -> syn::Result<TokenS
           self
         }
 
+        #[doc="Initializes whether the hidden widget still takes its space in the layout."]
+        #vis fn maintain_space<_M, _V>(mut self, v: _V) -> Self
+        where
+          DeclareInit<bool>: DeclareFrom<_V, _M>,
+        {
+          self.fat_obj = self.fat_obj.maintain_space(v);
+          self
+        }
+
         #[doc="Initializes the opacity of the widget."]
         #vis fn opacity<_M, _V>(mut self, v: _V) -> Self
         where
@@ -552,6 +605,12 @@ pub(crate) fn declare_derive(input: &mut syn::DeriveInput) -> syn::Result<TokenS
           self
         }
 
+        #[doc="Initializes the hit test function of the widget."]
+        #vis fn hit_test(mut self, f: impl Fn(Point) -> bool + 'static) -> Self {
+          self.fat_obj = self.fat_obj.hit_test(f);
+          self
+        }
+
         #[doc="Initializes the `keep_alive` value of the `KeepAlive` widget."]
         #vis fn keep_alive<_M, _V>(mut self, v: _V) -> Self
         where
@@ -561,6 +620,52 @@ pub(crate) fn declare_derive(input: &mut syn::DeriveInput) -> syn::Result<TokenS
           self
         }
 
+        #[doc="Initializes the role of the widget in the accessibility tree."]
+        #vis fn a11y_role<_M, _V>(mut self, v: _V) -> Self
+        where
+          DeclareInit<AccessRole>: DeclareFrom<_V, _M>,
+        {
+          self.fat_obj = self.fat_obj.a11y_role(v);
+          self
+        }
+
+        #[doc="Initializes the label of the widget in the accessibility tree."]
+        #vis fn a11y_label<_M, _V>(mut self, v: _V) -> Self
+        where
+          DeclareInit<CowArc<str>>: DeclareFrom<_V, _M>,
+        {
+          self.fat_obj = self.fat_obj.a11y_label(v);
+          self
+        }
+
+        #[doc="Initializes the value of the widget in the accessibility tree."]
+        #vis fn a11y_value<_M, _V>(mut self, v: _V) -> Self
+        where
+          DeclareInit<CowArc<str>>: DeclareFrom<_V, _M>,
+        {
+          self.fat_obj = self.fat_obj.a11y_value(v);
+          self
+        }
+
+        #[doc="Initializes the hint of the widget in the accessibility tree."]
+        #vis fn a11y_hint<_M, _V>(mut self, v: _V) -> Self
+        where
+          DeclareInit<CowArc<str>>: DeclareFrom<_V, _M>,
+        {
+          self.fat_obj = self.fat_obj.a11y_hint(v);
+          self
+        }
+
+        #[doc="Initializes whether the widget and its descendants are excluded from \
+          the accessibility tree."]
+        #vis fn a11y_hidden<_M, _V>(mut self, v: _V) -> Self
+        where
+          DeclareInit<bool>: DeclareFrom<_V, _M>,
+        {
+          self.fat_obj = self.fat_obj.a11y_hidden(v);
+          self
+        }
+
       }
   };
 
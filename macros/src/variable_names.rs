@@ -53,11 +53,16 @@ pub static BUILTIN_INFOS: phf::Map<&'static str, BuiltinMember> = phf_map! {
   "on_tap" => BuiltinMember { host_ty: "MixBuiltin", mem_ty: Method, var_name: "mix_builtin" },
   "on_tap_capture" => BuiltinMember { host_ty: "MixBuiltin", mem_ty: Method, var_name: "mix_builtin" },
   "on_double_tap" => BuiltinMember { host_ty: "MixBuiltin", mem_ty: Method, var_name: "mix_builtin" },
+  "on_double_tap_with" => BuiltinMember { host_ty: "MixBuiltin", mem_ty: Method, var_name: "mix_builtin" },
   "on_double_tap_capture" => BuiltinMember { host_ty: "MixBuiltin", mem_ty: Method, var_name: "mix_builtin" },
   "on_triple_tap" => BuiltinMember { host_ty: "MixBuiltin", mem_ty: Method, var_name: "mix_builtin" },
   "on_triple_tap_capture" => BuiltinMember { host_ty: "MixBuiltin", mem_ty: Method, var_name: "mix_builtin" },
   "on_x_times_tap" => BuiltinMember { host_ty: "MixBuiltin", mem_ty: Method, var_name: "mix_builtin" },
   "on_x_times_tap_capture" => BuiltinMember { host_ty: "MixBuiltin", mem_ty: Method, var_name: "mix_builtin" },
+  "on_pan" => BuiltinMember { host_ty: "MixBuiltin", mem_ty: Method, var_name: "mix_builtin" },
+  "on_pan_with" => BuiltinMember { host_ty: "MixBuiltin", mem_ty: Method, var_name: "mix_builtin" },
+  "on_long_press" => BuiltinMember { host_ty: "MixBuiltin", mem_ty: Method, var_name: "mix_builtin" },
+  "on_long_press_with" => BuiltinMember { host_ty: "MixBuiltin", mem_ty: Method, var_name: "mix_builtin" },
   "on_ime_pre_edit" => BuiltinMember { host_ty: "MixBuiltin", mem_ty: Method, var_name: "mix_builtin" },
   "on_ime_pre_edit_capture" => BuiltinMember { host_ty: "MixBuiltin", mem_ty: Method, var_name: "mix_builtin" },
   "on_wheel" => BuiltinMember { host_ty: "MixBuiltin", mem_ty: Method, var_name: "mix_builtin" },
@@ -112,6 +117,8 @@ pub static BUILTIN_INFOS: phf::Map<&'static str, BuiltinMember> = phf_map! {
   "scroll_view_size" => BuiltinMember { host_ty: "ScrollableWidget", mem_ty: Method, var_name: "scrollable"},
   "scroll_content_size" => BuiltinMember { host_ty: "ScrollableWidget", mem_ty: Method, var_name: "scrollable"},
   "jump_to" => BuiltinMember { host_ty: "ScrollableWidget", mem_ty: Method, var_name: "scrollable"},
+  "scroll_by" => BuiltinMember { host_ty: "ScrollableWidget", mem_ty: Method, var_name: "scrollable"},
+  "ensure_visible" => BuiltinMember { host_ty: "ScrollableWidget", mem_ty: Method, var_name: "scrollable"},
   // TransformWidget
   "transform" => BuiltinMember { host_ty: "TransformWidget", mem_ty: Field, var_name: "transform" },
   // HAlignWidget
@@ -122,8 +129,17 @@ pub static BUILTIN_INFOS: phf::Map<&'static str, BuiltinMember> = phf_map! {
   "anchor" => BuiltinMember { host_ty: "RelativeAnchor", mem_ty: Field, var_name: "relative_anchor" },
   // Visibility
   "visible" => BuiltinMember { host_ty: "Visibility", mem_ty: Field, var_name: "visibility" },
+  "maintain_space" => BuiltinMember { host_ty: "Visibility", mem_ty: Field, var_name: "visibility" },
   // Opacity
   "opacity" => BuiltinMember { host_ty: "Opacity", mem_ty: Field, var_name: "opacity" },
+  // HitTestWidget
+  "hit_test" => BuiltinMember { host_ty: "HitTestWidget", mem_ty: Field, var_name: "hit_test" },
   // KeepAlive
   "keep_alive" => BuiltinMember { host_ty: "KeepAlive", mem_ty: Field, var_name: "keep_alive" },
+  // Accessibility
+  "a11y_role" => BuiltinMember { host_ty: "Accessibility", mem_ty: Field, var_name: "accessibility" },
+  "a11y_label" => BuiltinMember { host_ty: "Accessibility", mem_ty: Field, var_name: "accessibility" },
+  "a11y_value" => BuiltinMember { host_ty: "Accessibility", mem_ty: Field, var_name: "accessibility" },
+  "a11y_hint" => BuiltinMember { host_ty: "Accessibility", mem_ty: Field, var_name: "accessibility" },
+  "a11y_hidden" => BuiltinMember { host_ty: "Accessibility", mem_ty: Field, var_name: "accessibility" },
 };
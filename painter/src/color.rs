@@ -1,5 +1,7 @@
+use std::f32::consts::TAU;
+
 use material_color_utilities_rs::htc;
-use ribir_geom::Point;
+use ribir_geom::{Angle, Point};
 use serde::{Deserialize, Serialize};
 
 use crate::SpreadMethod;
@@ -41,6 +43,27 @@ pub struct LinearGradient {
   pub spread_method: SpreadMethod,
 }
 
+/// A gradient that the colors rotate around a center point, the stops cover
+/// the full circle from the `start_angle`. The stops out of order are sorted
+/// by their offset when the gradient is painted.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ConicGradient {
+  pub center: Point,
+  /// The angle of the first stop in radians, zero is the direction of the
+  /// positive x-axis.
+  pub start_angle: f32,
+  pub stops: Vec<GradientStop>,
+  pub winding: GradientWinding,
+}
+
+/// The direction that the stops of a [`ConicGradient`] rotate.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum GradientWinding {
+  #[default]
+  Clockwise,
+  CounterClockwise,
+}
+
 /// Describe the light tone of a color, should between [0, 1.0], 0.0 gives
 /// absolute black and 1.0 give the brightest white.
 #[derive(Clone, Debug, Copy)]
@@ -51,6 +74,75 @@ impl LightnessTone {
   pub fn new(tone: f32) -> Self { Self(tone.clamp(0., 1.0)) }
 }
 
+impl ConicGradient {
+  /// Create a clockwise conic gradient, the stops out of order are sorted by
+  /// their offset.
+  pub fn new(center: Point, start_angle: Angle, stops: Vec<GradientStop>) -> Self {
+    let mut conic =
+      Self { center, start_angle: start_angle.radians, stops, winding: GradientWinding::Clockwise };
+    if conic.sort_stops() {
+      log::warn!("The stops of the conic gradient are out of order, sorted by their offset.");
+    }
+    conic
+  }
+
+  /// Sort the stops by their offset, return if they were out of order.
+  pub fn sort_stops(&mut self) -> bool {
+    let unordered = self
+      .stops
+      .windows(2)
+      .any(|w| w[0].offset > w[1].offset);
+    if unordered {
+      self
+        .stops
+        .sort_by(|a, b| a.offset.total_cmp(&b.offset));
+    }
+    unordered
+  }
+
+  /// Return the color of the gradient at the `pos`.
+  pub fn color_at(&self, pos: Point) -> Color {
+    if self
+      .stops
+      .windows(2)
+      .any(|w| w[0].offset > w[1].offset)
+    {
+      let mut sorted = self.clone();
+      sorted.sort_stops();
+      return sorted.color_at(pos);
+    }
+
+    let (Some(first), Some(last)) = (self.stops.first(), self.stops.last()) else {
+      return Color::new(0, 0, 0, 0);
+    };
+
+    let offset = self.offset_at(pos);
+    if offset <= first.offset {
+      return first.color;
+    }
+    let Some(idx) = self.stops.iter().position(|s| offset < s.offset) else {
+      return last.color;
+    };
+
+    let (prev, next) = (&self.stops[idx - 1], &self.stops[idx]);
+    let weight = (offset - prev.offset) / (next.offset - prev.offset);
+    let [r1, g1, b1, a1] = prev.color.into_f32_components();
+    let [r2, g2, b2, a2] = next.color.into_f32_components();
+    let lerp = |a: f32, b: f32| a + (b - a) * weight;
+    Color::from_f32_rgba(lerp(r1, r2), lerp(g1, g2), lerp(b1, b2), lerp(a1, a2))
+  }
+
+  /// Return the offset of the `pos` in the gradient, between 0 and 1.
+  pub fn offset_at(&self, pos: Point) -> f32 {
+    let v = pos - self.center;
+    let mut angle = v.y.atan2(v.x) - self.start_angle;
+    if self.winding == GradientWinding::CounterClockwise {
+      angle = -angle;
+    }
+    angle.rem_euclid(TAU) / TAU
+  }
+}
+
 impl Color {
   #[inline]
   pub const fn new(red: u8, green: u8, blue: u8, alpha: u8) -> Self {
@@ -70,6 +162,32 @@ impl Color {
     }
   }
 
+  /// Parse a color from a hex string, like `#fff`, `#ffff`, `#ff0000` or
+  /// `#ff000080`. The leading `#` is optional, the digits are
+  /// case-insensitive and the alpha is `ff` if not provided.
+  pub fn from_hex(hex: &str) -> Result<Self, ParseColorError> {
+    let digits = hex.strip_prefix('#').unwrap_or(hex);
+    if let Some(c) = digits.chars().find(|c| !c.is_ascii_hexdigit()) {
+      return Err(ParseColorError::InvalidDigit(c));
+    }
+    let nibble = |b: &u8| (*b as char).to_digit(16).unwrap() as u8;
+    let bytes = digits.as_bytes();
+    let mut values = [0xff; 4];
+    match bytes.len() {
+      3 | 4 => values
+        .iter_mut()
+        .zip(bytes)
+        .for_each(|(v, b)| *v = nibble(b) * 0x11),
+      6 | 8 => values
+        .iter_mut()
+        .zip(bytes.chunks(2))
+        .for_each(|(v, pair)| *v = (nibble(&pair[0]) << 4) | nibble(&pair[1])),
+      len => return Err(ParseColorError::InvalidLength(len)),
+    }
+    let [red, green, blue, alpha] = values;
+    Ok(Self { red, green, blue, alpha })
+  }
+
   #[inline]
   pub fn from_u32(rgba: u32) -> Self {
     let bytes = rgba.to_be_bytes();
@@ -122,6 +240,28 @@ impl Color {
   }
 }
 
+/// The error of parsing a color from a hex string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseColorError {
+  /// The count of the hex digits is not 3, 4, 6 or 8.
+  InvalidLength(usize),
+  /// The character is not a hex digit.
+  InvalidDigit(char),
+}
+
+impl std::fmt::Display for ParseColorError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      ParseColorError::InvalidLength(len) => {
+        write!(f, "expected 3, 4, 6 or 8 hex digits of a color, but got {len}")
+      }
+      ParseColorError::InvalidDigit(c) => write!(f, "`{c}` is not a hex digit of a color"),
+    }
+  }
+}
+
+impl std::error::Error for ParseColorError {}
+
 const C23: u32 = 0x4b00_0000;
 // Algorithm from https://github.com/Ogeon/palette/pull/184/files.
 fn u8_component_to_f32(v: u8) -> f32 {
@@ -349,3 +489,26 @@ impl Color {
   pub const YELLOWGREEN: Color = Self::from_rgb(154, 205, 50);
   pub const TRANSPARENT: Color = Self::new(0, 0, 0, 0);
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn parse_hex() {
+    assert_eq!(Color::from_hex("#fff"), Ok(Color::WHITE));
+    assert_eq!(Color::from_hex("#FFF"), Ok(Color::WHITE));
+    assert_eq!(Color::from_hex("#ff0000"), Ok(Color::RED));
+    assert_eq!(Color::from_hex("#Ff000080"), Ok(Color::new(255, 0, 0, 128)));
+    assert_eq!(Color::from_hex("f008"), Ok(Color::new(255, 0, 0, 136)));
+    assert_eq!(Color::from_hex("#ff00"), Ok(Color::new(255, 255, 0, 0)));
+  }
+
+  #[test]
+  fn parse_invalid_hex() {
+    assert_eq!(Color::from_hex("#ff000"), Err(ParseColorError::InvalidLength(5)));
+    assert_eq!(Color::from_hex(""), Err(ParseColorError::InvalidLength(0)));
+    assert_eq!(Color::from_hex("#gg0000"), Err(ParseColorError::InvalidDigit('g')));
+    assert_eq!(Color::from_hex("#ff000\u{e9}"), Err(ParseColorError::InvalidDigit('\u{e9}')));
+  }
+}
@@ -6,7 +6,7 @@ pub mod path_builder;
 pub use path::*;
 
 pub use crate::{
-  color::{Color, GradientStop, LightnessTone},
+  color::{Color, GradientStop, LightnessTone, ParseColorError},
   painter::*,
 };
 pub mod image;
@@ -1,14 +1,14 @@
 use std::ops::{Deref, DerefMut};
 
 use ribir_algo::Resource;
-use ribir_geom::{Angle, DeviceRect, Point, Rect, Size, Transform, Vector};
+use ribir_geom::{Angle, DeviceRect, Point, Rect, SideOffsets, Size, Transform, Vector};
 use serde::{Deserialize, Serialize};
 
 use crate::{
-  color::{LinearGradient, RadialGradient},
+  color::{ConicGradient, LinearGradient, RadialGradient},
   path::*,
   path_builder::PathBuilder,
-  Brush, Color, PixelImage, Svg,
+  BlendMode, Brush, Color, PixelImage, ShadowStyle, Svg,
 };
 /// The painter is a two-dimensional grid. The coordinate (0, 0) is at the
 /// upper-left corner of the canvas. Along the X-axis, values increase towards
@@ -20,6 +20,16 @@ pub struct Painter {
   state_stack: Vec<PainterState>,
   commands: Vec<PaintCommand>,
   path_builder: PathBuilder,
+  /// The opacity layers pushed by `push_opacity_layer`, `None` for a layer
+  /// that is skipped because it's opaque.
+  layers: Vec<Option<OpacityLayer>>,
+}
+
+struct OpacityLayer {
+  /// The index of the first command in the layer.
+  start: usize,
+  /// The opacity to composite the layer with.
+  opacity: f32,
 }
 
 pub struct PainterResult<'a>(&'a mut Vec<PaintCommand>);
@@ -74,6 +84,12 @@ pub enum PaintPathAction {
   Image { img: Resource<PixelImage>, opacity: f32 },
   Radial(RadialGradient),
   Linear(LinearGradient),
+  Conic(ConicGradient),
+  /// Paint a blurred silhouette of the path, see [`Painter::draw_shadow`].
+  Shadow(ShadowStyle),
+  /// Blur the content painted before inside the path with the radius, see
+  /// [`Painter::blur_backdrop`].
+  Backdrop(f32),
   Clip,
 }
 
@@ -107,6 +123,9 @@ pub struct PathCommand {
   pub transform: Transform,
   // The action to apply to the path.
   pub action: PaintPathAction,
+  /// The blend mode to composite the path with what's already painted.
+  #[serde(default)]
+  pub blend_mode: BlendMode,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -122,6 +141,9 @@ pub enum PaintCommand {
     /// command
     bounds: Rect,
     cmds: Resource<Box<[PaintCommand]>>,
+    /// The blend mode to composite the bundle with what's already painted.
+    #[serde(default)]
+    blend_mode: BlendMode,
   },
 }
 
@@ -132,6 +154,7 @@ struct PainterState {
   brush: Brush,
   transform: Transform,
   opacity: f32,
+  blend_mode: BlendMode,
   clip_cnt: usize,
   /// The visible boundary of the painter in visual axis, not care about the
   /// transform.
@@ -147,6 +170,7 @@ impl PainterState {
       transform: Transform::identity(),
       clip_cnt: 0,
       opacity: 1.,
+      blend_mode: BlendMode::SrcOver,
     }
   }
 }
@@ -159,6 +183,7 @@ impl Painter {
       commands: vec![],
       path_builder: Path::builder(),
       viewport,
+      layers: vec![],
     }
   }
 
@@ -218,6 +243,7 @@ impl Painter {
   pub fn reset(&mut self) {
     self.fill_all_pop_clips();
     self.commands.clear();
+    self.layers.clear();
     self.state_stack.clear();
     self
       .state_stack
@@ -243,6 +269,94 @@ impl Painter {
 
   pub fn alpha(&self) -> f32 { self.current_state().opacity }
 
+  /// Saves the state and starts an opacity layer, the following drawing is
+  /// painted into an offscreen layer until the paired [`Painter::pop_layer`],
+  /// and the layer is composited once with the `alpha`.
+  ///
+  /// Unlike [`Painter::apply_alpha`], which applies the alpha to every
+  /// primitive, the overlapping parts of the primitives in a layer are not
+  /// blended twice. An opaque layer is skipped, its drawing is painted
+  /// directly.
+  pub fn push_opacity_layer(&mut self, alpha: f32) -> &mut Self {
+    self.save();
+    if alpha >= 1. {
+      self.layers.push(None);
+    } else {
+      let opacity = self.alpha() * alpha.max(0.);
+      let start = self.commands.len();
+      self
+        .layers
+        .push(Some(OpacityLayer { start, opacity }));
+      // The opacity applies to the whole layer, not its primitives.
+      self.current_state_mut().opacity = 1.;
+    }
+    self
+  }
+
+  /// Ends the latest opacity layer and restores the state saved by
+  /// [`Painter::push_opacity_layer`].
+  ///
+  /// # Panics
+  ///
+  /// Panics if there is no opacity layer.
+  pub fn pop_layer(&mut self) -> &mut Self {
+    let layer = self
+      .layers
+      .pop()
+      .expect("No opacity layer to pop.");
+    self.restore();
+
+    let Some(OpacityLayer { start, opacity }) = layer else {
+      return self;
+    };
+    let mut cmds = self.commands.split_off(start);
+    let bounds = cmds
+      .iter()
+      .filter_map(|cmd| match cmd {
+        PaintCommand::Path(PathCommand { action: PaintPathAction::Clip, .. })
+        | PaintCommand::PopClip => None,
+        PaintCommand::Path(path) => Some(path.paint_bounds),
+        PaintCommand::Bundle { transform, bounds, .. } => {
+          Some(transform.outer_transformed_rect(bounds))
+        }
+      })
+      .reduce(|a, b| a.union(&b));
+    let Some(bounds) = bounds.filter(|_| opacity > 0.) else {
+      return self;
+    };
+
+    // Move the commands to the axis of the layer.
+    let to_layer = Transform::translation(-bounds.min_x(), -bounds.min_y());
+    for cmd in cmds.iter_mut() {
+      match cmd {
+        PaintCommand::Path(path) => path.transform(&to_layer),
+        PaintCommand::PopClip => {}
+        PaintCommand::Bundle { transform, .. } => *transform = transform.then(&to_layer),
+      }
+    }
+    self.commands.push(PaintCommand::Bundle {
+      transform: Transform::translation(bounds.min_x(), bounds.min_y()),
+      opacity,
+      bounds: Rect::from_size(bounds.size),
+      cmds: Resource::new(cmds.into_boxed_slice()),
+      blend_mode: self.blend_mode(),
+    });
+    self
+  }
+
+  /// Return the blend mode used to composite the following fills and images.
+  #[inline]
+  pub fn blend_mode(&self) -> BlendMode { self.current_state().blend_mode }
+
+  /// Use the `mode` to composite the following fills and images, until the
+  /// returned guard is dropped.
+  #[must_use]
+  pub fn with_blend_mode(&mut self, mode: BlendMode) -> PainterGuard<'_> {
+    let mut painter = self.save_guard();
+    painter.current_state_mut().blend_mode = mode;
+    painter
+  }
+
   #[inline]
   pub fn set_strokes(&mut self, strokes: StrokeOptions) -> &mut Self {
     self.current_state_mut().stroke_options = strokes;
@@ -340,16 +454,84 @@ impl Painter {
         Brush::Image(img) => PaintPathAction::Image { img, opacity: 1. },
         Brush::RadialGradient(radial_gradient) => PaintPathAction::Radial(radial_gradient),
         Brush::LinearGradient(linear_gradient) => PaintPathAction::Linear(linear_gradient),
+        // A single stop is a solid fill.
+        Brush::ConicGradient(conic) if conic.stops.len() == 1 => {
+          PaintPathAction::Color(conic.stops[0].color)
+        }
+        Brush::ConicGradient(mut conic) => {
+          // The gradient may be built without `ConicGradient::new`.
+          conic.sort_stops();
+          PaintPathAction::Conic(conic)
+        }
       };
       action.apply_alpha(self.alpha());
       let ts = *self.get_transform();
-      let cmd = PathCommand::new(path, action, ts);
+      let mut cmd = PathCommand::new(path, action, ts);
+      cmd.blend_mode = self.blend_mode();
       self.commands.push(PaintCommand::Path(cmd));
     }
 
     self
   }
 
+  /// Paint the shadow of a path. A drop shadow is painted behind the path
+  /// (paint it before the path), and an inset shadow is painted inside the
+  /// path. The shadow ignores the current brush.
+  ///
+  /// A zero `blur` paints a crisp shadow, and a `blur` larger than
+  /// [`ShadowStyle::MAX_BLUR`] is clamped to it.
+  pub fn draw_shadow(&mut self, p: impl Into<PaintPath>, mut shadow: ShadowStyle) -> &mut Self {
+    invisible_return!(self);
+
+    let path = p.into();
+    shadow.blur = shadow.blur.clamp(0., ShadowStyle::MAX_BLUR);
+    if !locatable_bounds(&path.bounds)
+      || shadow.color.alpha == 0
+      || !self.intersect_paint_bounds(&shadow.paint_bounds(&path.bounds))
+    {
+      return self;
+    }
+
+    let mut action = match shadow.silhouette_bounds(&path.bounds) {
+      Some(_) => PaintPathAction::Shadow(shadow),
+      // The silhouette is shrunk to nothing, a drop shadow paints nothing and
+      // an inset shadow covers the whole path.
+      None if shadow.inset => PaintPathAction::Color(shadow.color),
+      None => return self,
+    };
+    action.apply_alpha(self.alpha());
+    let ts = *self.get_transform();
+    let mut cmd = PathCommand::new(path, action, ts);
+    cmd.blend_mode = self.blend_mode();
+    self.commands.push(PaintCommand::Path(cmd));
+
+    self
+  }
+
+  /// Blur the content painted before inside the path, such as the content
+  /// behind a frosted glass. The blur ignores the current brush and alpha.
+  ///
+  /// A zero `radius` blurs nothing, and a `radius` larger than
+  /// [`ShadowStyle::MAX_BLUR`] is clamped to it.
+  pub fn blur_backdrop(&mut self, p: impl Into<PaintPath>, radius: f32) -> &mut Self {
+    invisible_return!(self);
+
+    let path = p.into();
+    let radius = radius.min(ShadowStyle::MAX_BLUR);
+    if radius <= 0.
+      || !locatable_bounds(&path.bounds)
+      || !self.intersect_paint_bounds(&path.bounds)
+    {
+      return self;
+    }
+
+    let ts = *self.get_transform();
+    let cmd = PathCommand::new(path, PaintPathAction::Backdrop(radius), ts);
+    self.commands.push(PaintCommand::Path(cmd));
+
+    self
+  }
+
   /// Outlines the current path with the current brush and `StrokeOptions`.
   ///
   /// ## Note
@@ -496,6 +678,14 @@ impl Painter {
     self
   }
 
+  /// Fill the rounded rectangle with the current brush. The radii that not fit
+  /// the rectangle are scaled down, see [`RRect::fit_radius`].
+  pub fn fill_rrect(&mut self, rrect: &RRect) -> &mut Self { self.fill_path(rrect.to_path()) }
+
+  /// Clip the paint area to the rounded rectangle, the radii that not fit the
+  /// rectangle are scaled down, see [`RRect::fit_radius`].
+  pub fn clip_rrect(&mut self, rrect: &RRect) -> &mut Self { self.clip(rrect.to_path()) }
+
   /// Draws a bundle of paint commands that can be treated as a single command.
   /// This allows the backend to cache it.
   ///
@@ -510,7 +700,8 @@ impl Painter {
     invisible_return!(self);
     let transform = *self.get_transform();
     let opacity = self.alpha();
-    let cmd = PaintCommand::Bundle { transform, opacity, bounds, cmds };
+    let blend_mode = self.blend_mode();
+    let cmd = PaintCommand::Bundle { transform, opacity, bounds, cmds, blend_mode };
     self.commands.push(cmd);
     self
   }
@@ -527,21 +718,26 @@ impl Painter {
     if svg.commands.len() <= 16 {
       let transform = *self.get_transform();
       let alpha = self.alpha();
+      let blend_mode = self.blend_mode();
 
       for cmd in svg.commands.iter() {
         let cmd = match cmd.clone() {
           PaintCommand::Path(mut path) => {
             path.transform(&transform);
             path.action.apply_alpha(alpha);
+            path.blend_mode = blend_mode;
             PaintCommand::Path(path)
           }
           PaintCommand::PopClip => PaintCommand::PopClip,
-          PaintCommand::Bundle { transform: b_ts, opacity, bounds, cmds } => PaintCommand::Bundle {
-            transform: transform.then(&b_ts),
-            opacity: alpha * opacity,
-            bounds,
-            cmds,
-          },
+          PaintCommand::Bundle { transform: b_ts, opacity, bounds, cmds, .. } => {
+            PaintCommand::Bundle {
+              transform: transform.then(&b_ts),
+              opacity: alpha * opacity,
+              bounds,
+              cmds,
+              blend_mode,
+            }
+          }
         };
         self.commands.push(cmd);
       }
@@ -586,6 +782,62 @@ impl Painter {
 
     self
   }
+
+  /// Draw the image in the nine-patch mode, also known as nine-slice scaling.
+  ///
+  /// The `center_insets` in the source pixels split the image into nine
+  /// slices. The four corners keep unscaled, the top and bottom edges are
+  /// stretched horizontally, the left and right edges are stretched vertically,
+  /// and the center is stretched in both directions to fill the `dst_rect`.
+  ///
+  /// If the `dst_rect` is smaller than the corners, the corners shrink
+  /// proportionally and the center is not drawn.
+  pub fn draw_image_nine_patch(
+    &mut self, img: Resource<PixelImage>, center_insets: &SideOffsets, dst_rect: &Rect,
+  ) -> &mut Self {
+    invisible_return!(self);
+    let src_size = Size::new(img.width() as f32, img.height() as f32);
+    for (src, dst) in nine_patch_slices(src_size, center_insets, dst_rect) {
+      self.draw_img(img.clone(), &dst, &Some(src));
+    }
+    self
+  }
+}
+
+/// Split the image of `src_size` by the `insets` to nine slices, return the
+/// pairs of the source rect and the destination rect of the non-empty slices.
+fn nine_patch_slices(
+  src_size: Size, insets: &SideOffsets, dst: &Rect,
+) -> impl Iterator<Item = (Rect, Rect)> {
+  // Return the source and destination ranges of the three slices in an axis.
+  fn split(src: f32, start: f32, end: f32, dst: f32) -> [(f32, f32, f32, f32); 3] {
+    let start = start.clamp(0., src);
+    let end = end.clamp(0., src - start);
+    let corners = start + end;
+    // Shrink the corners proportionally if they can't fit in the destination.
+    let scale = if corners > dst { dst / corners } else { 1. };
+    let (dst_start, dst_end) = (start * scale, dst - end * scale);
+    [
+      (0., start, 0., dst_start),
+      (start, src - end, dst_start, dst_end),
+      (src - end, src, dst_end, dst),
+    ]
+  }
+
+  let cols = split(src_size.width, insets.left, insets.right, dst.width());
+  let rows = split(src_size.height, insets.top, insets.bottom, dst.height());
+  let origin = dst.origin;
+  rows
+    .into_iter()
+    .flat_map(move |row| cols.into_iter().map(move |col| (col, row)))
+    .filter(|((sx0, sx1, dx0, dx1), (sy0, sy1, dy0, dy1))| {
+      sx1 > sx0 && sy1 > sy0 && dx1 > dx0 && dy1 > dy0
+    })
+    .map(move |((sx0, sx1, dx0, dx1), (sy0, sy1, dy0, dy1))| {
+      let src = Rect::new(Point::new(sx0, sy0), Size::new(sx1 - sx0, sy1 - sy0));
+      let dst = Rect::new(origin + Vector::new(dx0, dy0), Size::new(dx1 - dx0, dy1 - dy0));
+      (src, dst)
+    })
 }
 
 impl Painter {
@@ -644,7 +896,8 @@ impl Painter {
       Brush::Color(c) => c.alpha > 0,
       Brush::Image(_) => true,
       Brush::RadialGradient(RadialGradient { ref stops, .. })
-      | Brush::LinearGradient(LinearGradient { ref stops, .. }) => {
+      | Brush::LinearGradient(LinearGradient { ref stops, .. })
+      | Brush::ConicGradient(ConicGradient { ref stops, .. }) => {
         stops.iter().any(|s| s.color.alpha > 0)
       }
     }
@@ -716,8 +969,8 @@ impl PathCommand {
     {
       println!("paint_bounds.width().is_nan()");
     }
-    let paint_bounds = transform.outer_transformed_rect(path.bounds());
-    Self { path, transform, paint_bounds, action }
+    let paint_bounds = transform.outer_transformed_rect(&action.bounds(path.bounds()));
+    Self { path, transform, paint_bounds, action, blend_mode: BlendMode::SrcOver }
   }
 
   pub fn scale(&mut self, scale: f32) {
@@ -729,7 +982,7 @@ impl PathCommand {
     self.transform = self.transform.then(transform);
     self.paint_bounds = self
       .transform
-      .outer_transformed_rect(self.path.bounds());
+      .outer_transformed_rect(&self.action.bounds(self.path.bounds()));
   }
 }
 
@@ -739,13 +992,24 @@ impl PaintPathAction {
       PaintPathAction::Color(color) => *color = color.apply_alpha(alpha),
       PaintPathAction::Image { opacity, .. } => *opacity *= alpha,
       PaintPathAction::Radial(RadialGradient { stops, .. })
-      | PaintPathAction::Linear(LinearGradient { stops, .. }) => stops
+      | PaintPathAction::Linear(LinearGradient { stops, .. })
+      | PaintPathAction::Conic(ConicGradient { stops, .. }) => stops
         .iter_mut()
         .for_each(|s| s.color = s.color.apply_alpha(alpha)),
-      PaintPathAction::Clip => {}
+      PaintPathAction::Shadow(ShadowStyle { color, .. }) => *color = color.apply_alpha(alpha),
+      PaintPathAction::Backdrop(_) | PaintPathAction::Clip => {}
     }
     self
   }
+
+  /// The bounds this action paints for a path with `path_bounds`, in the path
+  /// axis.
+  fn bounds(&self, path_bounds: &Rect) -> Rect {
+    match self {
+      PaintPathAction::Shadow(shadow) => shadow.paint_bounds(path_bounds),
+      _ => *path_bounds,
+    }
+  }
 }
 // bounds that has a limited location and size
 fn locatable_bounds(bounds: &Rect) -> bool {
@@ -766,6 +1030,7 @@ mod test {
   use ribir_geom::rect;
 
   use super::*;
+  use crate::GradientStop;
 
   fn painter() -> Painter { Painter::new(Rect::from_size(Size::new(512., 512.))) }
 
@@ -810,6 +1075,27 @@ mod test {
     assert_eq!(painter.current_state().clip_cnt, 0);
   }
 
+  #[test]
+  fn sort_conic_stops_when_paint() {
+    let mut painter = painter();
+    let conic = ConicGradient {
+      stops: vec![GradientStop::new(Color::BLUE, 1.), GradientStop::new(Color::RED, 0.)],
+      ..Default::default()
+    };
+    painter
+      .set_brush(Brush::ConicGradient(conic))
+      .rect(&rect(0., 0., 10., 10.))
+      .fill();
+
+    let PaintCommand::Path(PathCommand { action: PaintPathAction::Conic(conic), .. }) =
+      &painter.commands[0]
+    else {
+      unreachable!()
+    };
+    let offsets = conic.stops.iter().map(|s| s.offset).collect::<Vec<_>>();
+    assert_eq!(offsets, [0., 1.]);
+  }
+
   #[test]
   fn filter_invalid_clip() {
     let mut painter = painter();
@@ -854,4 +1140,173 @@ mod test {
       .fill();
     assert_eq!(painter.commands.len(), 2);
   }
+
+  #[test]
+  fn scoped_blend_mode() {
+    let mut painter = painter();
+    {
+      let mut painter = painter.with_blend_mode(BlendMode::Multiply);
+      painter.rect(&rect(0., 0., 10., 10.)).fill();
+    }
+    painter.rect(&rect(0., 0., 10., 10.)).fill();
+
+    let modes: Vec<_> = painter
+      .finish()
+      .iter()
+      .map(|cmd| match cmd {
+        PaintCommand::Path(path) => path.blend_mode,
+        _ => unreachable!(),
+      })
+      .collect();
+    assert_eq!(modes, [BlendMode::Multiply, BlendMode::SrcOver]);
+  }
+
+  #[test]
+  fn backdrop_blur_radius() {
+    let mut painter = painter();
+    let path = Path::rect(&rect(0., 0., 64., 64.));
+    painter
+      .blur_backdrop(path.clone(), 0.)
+      .blur_backdrop(path.clone(), 4.)
+      .blur_backdrop(path, 1e6);
+
+    let radii: Vec<_> = painter
+      .finish()
+      .iter()
+      .map(|cmd| match cmd {
+        PaintCommand::Path(PathCommand { action: PaintPathAction::Backdrop(r), .. }) => *r,
+        _ => unreachable!(),
+      })
+      .collect();
+    // nothing to blur with a zero radius, and a huge radius is clamped.
+    assert_eq!(radii, [4., ShadowStyle::MAX_BLUR]);
+  }
+
+  #[test]
+  fn shadow_bounds() {
+    let mut painter = painter();
+    let path = Path::rect(&rect(96., 96., 128., 64.));
+    let shadow = ShadowStyle {
+      color: Color::BLACK,
+      blur: 8.,
+      offset: Vector::zero(),
+      spread: 16.,
+      inset: false,
+    };
+    let crisp = ShadowStyle { blur: 0., offset: Vector::new(5., 5.), ..shadow };
+    let huge = ShadowStyle { blur: 1e6, spread: 0., ..shadow };
+    let inset = ShadowStyle { inset: true, ..crisp };
+    painter
+      .draw_shadow(path.clone(), shadow)
+      .draw_shadow(path.clone(), crisp)
+      .draw_shadow(path.clone(), huge)
+      .draw_shadow(path, inset);
+
+    let bounds: Vec<_> = painter
+      .finish()
+      .iter()
+      .map(|cmd| match cmd {
+        PaintCommand::Path(path) => path.paint_bounds,
+        _ => unreachable!(),
+      })
+      .collect();
+    let max = ShadowStyle::MAX_BLUR;
+    assert_eq!(
+      bounds,
+      [
+        // expands by `blur + spread`
+        rect(72., 72., 176., 112.),
+        // a crisp shadow only grows by the spread and moves with the offset
+        rect(85., 85., 160., 96.),
+        // a huge blur is clamped
+        rect(96. - max, 96. - max, 128. + 2. * max, 64. + 2. * max),
+        // an inset shadow is inside the path
+        rect(96., 96., 128., 64.),
+      ]
+    );
+  }
+
+  #[test]
+  fn rrect_fit_radius() {
+    let rect = rect(0., 0., 100., 40.);
+    // The pill shape keeps its radii.
+    assert_eq!(RRect::new(rect, Radius::all(20.)).fit_radius(), Radius::all(20.));
+    // Scaled down proportionally by the overlapped side.
+    assert_eq!(
+      RRect::new(rect, Radius::new(40., 20., 0., 0.)).fit_radius(),
+      Radius::new(40., 20., 0., 0.)
+    );
+    assert_eq!(
+      RRect::new(rect, Radius::new(60., 20., 20., 0.)).fit_radius(),
+      Radius::new(30., 10., 10., 0.)
+    );
+    assert_eq!(
+      RRect::new(rect, Radius::new(-10., 10., 0., 0.)).fit_radius(),
+      Radius::new(0., 10., 0., 0.)
+    );
+    // The zero radius is a sharp rect.
+    let path = RRect::new(rect, Radius::default()).to_path();
+    assert_eq!(path.bounds(), &rect);
+  }
+
+  #[test]
+  fn nine_patch_slices() {
+    let insets = SideOffsets::new(2., 3., 4., 1.);
+    let slices: Vec<_> =
+      super::nine_patch_slices(Size::new(10., 10.), &insets, &rect(5., 5., 20., 30.)).collect();
+    assert_eq!(slices.len(), 9);
+    // The corners are unscaled.
+    assert_eq!(slices[0], (rect(0., 0., 1., 2.), rect(5., 5., 1., 2.)));
+    assert_eq!(slices[2], (rect(7., 0., 3., 2.), rect(22., 5., 3., 2.)));
+    assert_eq!(slices[6], (rect(0., 6., 1., 4.), rect(5., 31., 1., 4.)));
+    assert_eq!(slices[8], (rect(7., 6., 3., 4.), rect(22., 31., 3., 4.)));
+    // The edges are stretched in one axis, the center in both.
+    assert_eq!(slices[1], (rect(1., 0., 6., 2.), rect(6., 5., 16., 2.)));
+    assert_eq!(slices[3], (rect(0., 2., 1., 4.), rect(5., 7., 1., 24.)));
+    assert_eq!(slices[4], (rect(1., 2., 6., 4.), rect(6., 7., 16., 24.)));
+
+    // The destination is smaller than the corners, the corners shrink and the
+    // center is skipped.
+    let slices: Vec<_> =
+      super::nine_patch_slices(Size::new(10., 10.), &insets, &rect(0., 0., 2., 3.)).collect();
+    assert_eq!(slices.len(), 4);
+    assert_eq!(slices[0], (rect(0., 0., 1., 2.), rect(0., 0., 0.5, 1.)));
+    assert_eq!(slices[1], (rect(7., 0., 3., 2.), rect(0.5, 0., 1.5, 1.)));
+    assert_eq!(slices[3], (rect(7., 6., 3., 4.), rect(0.5, 1., 1.5, 2.)));
+  }
+
+  #[test]
+  fn opacity_layer() {
+    let mut painter = painter();
+    painter
+      .push_opacity_layer(1.)
+      .rect(&rect(0., 0., 10., 10.))
+      .fill()
+      .pop_layer();
+    painter
+      .push_opacity_layer(0.5)
+      .rect(&rect(20., 20., 10., 10.))
+      .fill()
+      .rect(&rect(25., 25., 10., 10.))
+      .fill()
+      .pop_layer();
+    assert_eq!(painter.alpha(), 1.);
+
+    let commands = painter.finish();
+    assert_eq!(commands.len(), 2);
+    // The opaque layer is skipped.
+    assert!(matches!(&commands[0], PaintCommand::Path(_)));
+    let PaintCommand::Bundle { transform, opacity, bounds, cmds, .. } = &commands[1] else {
+      panic!("The opacity layer should be a bundle.");
+    };
+    assert_eq!(*opacity, 0.5);
+    assert_eq!(*bounds, rect(0., 0., 15., 15.));
+    assert_eq!(transform.transform_point(Point::zero()), Point::new(20., 20.));
+    assert_eq!(cmds.len(), 2);
+    // The primitives in the layer are opaque.
+    assert!(matches!(
+      &cmds[0],
+      PaintCommand::Path(PathCommand { action: PaintPathAction::Color(Color::BLACK), .. })
+    ));
+  }
 }
@@ -295,6 +295,49 @@ impl Radius {
   }
 }
 
+/// A rectangle with four rounded corners.
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+pub struct RRect {
+  pub rect: Rect,
+  pub radius: Radius,
+}
+
+impl RRect {
+  #[inline]
+  pub const fn new(rect: Rect, radius: Radius) -> Self { Self { rect, radius } }
+
+  /// Return the radius that fits the rect. If the radii of two adjacent
+  /// corners overlap, all the radii are scaled down by the same factor until
+  /// they fit the side, as the CSS does. A negative radius is treated as zero.
+  pub fn fit_radius(&self) -> Radius {
+    let Radius { top_left, top_right, bottom_left, bottom_right } = self.radius;
+    let r =
+      Radius::new(top_left.max(0.), top_right.max(0.), bottom_left.max(0.), bottom_right.max(0.));
+    let Rect { size, .. } = self.rect;
+    let factor = |side: f32, sum: f32| if sum > side { side / sum } else { 1. };
+    let f = factor(size.width, r.top_left + r.top_right)
+      .min(factor(size.width, r.bottom_left + r.bottom_right))
+      .min(factor(size.height, r.top_left + r.bottom_left))
+      .min(factor(size.height, r.top_right + r.bottom_right));
+    if f < 1. {
+      Radius::new(r.top_left * f, r.top_right * f, r.bottom_left * f, r.bottom_right * f)
+    } else {
+      r
+    }
+  }
+
+  /// Create a path of the rounded rectangle with the fitted radius, a sharp
+  /// rectangle if all the radii are zero.
+  pub fn to_path(&self) -> Path {
+    let radius = self.fit_radius();
+    if radius == Radius::default() {
+      Path::rect(&self.rect)
+    } else {
+      Path::rect_round(&self.rect, &radius)
+    }
+  }
+}
+
 impl From<LyonPath> for Path {
   fn from(lyon_path: LyonPath) -> Self {
     let bounds = lyon_algorithms::aabb::bounding_box(&lyon_path)
@@ -1,9 +1,10 @@
 use ribir_algo::Resource;
+use ribir_geom::{Angle, Point, Rect, Vector};
 use serde::{Deserialize, Serialize};
 
 use crate::{
-  color::{LinearGradient, RadialGradient},
-  Color, PixelImage,
+  color::{ConicGradient, LinearGradient, RadialGradient},
+  Color, GradientStop, PixelImage,
 };
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -13,9 +14,15 @@ pub enum Brush {
   Image(Resource<PixelImage>),
   RadialGradient(RadialGradient),
   LinearGradient(LinearGradient),
+  ConicGradient(ConicGradient),
 }
 
 impl Brush {
+  /// Create a clockwise conic gradient brush, see [`ConicGradient::new`].
+  pub fn conic_gradient(center: Point, start_angle: Angle, stops: Vec<GradientStop>) -> Self {
+    Brush::ConicGradient(ConicGradient::new(center, start_angle, stops))
+  }
+
   pub fn only_convert_color(&self, f: impl FnOnce(&Color) -> Color) -> Brush {
     match self {
       Brush::Color(color) => f(color).into(),
@@ -48,3 +55,158 @@ impl Default for Brush {
   #[inline]
   fn default() -> Self { Color::BLACK.into() }
 }
+
+/// The way to composite the painted color with the color already in the
+/// target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum BlendMode {
+  /// Paint the source over the destination.
+  #[default]
+  SrcOver,
+  /// Multiply the source with the destination, the result is always darker.
+  Multiply,
+  /// Multiply the complements of the source and the destination, the result is
+  /// always lighter.
+  Screen,
+  /// Multiply or screen the colors, depending on the destination color.
+  Overlay,
+  /// Keep the darker one of the source and the destination.
+  Darken,
+  /// Keep the lighter one of the source and the destination.
+  Lighten,
+}
+
+impl BlendMode {
+  /// Composite the `src` color over the `dst` color with this blend mode, and
+  /// return the result color.
+  ///
+  /// This is the per-pixel math of the blend mode, the backends that can't
+  /// composite by their pipeline can use it directly.
+  pub fn blend(self, src: Color, dst: Color) -> Color {
+    let [sr, sg, sb, sa] = src.into_f32_components();
+    let [dr, dg, db, da] = dst.into_f32_components();
+    let alpha = sa + da * (1. - sa);
+    if alpha == 0. {
+      return Color::new(0, 0, 0, 0);
+    }
+
+    let composite = |s: f32, d: f32| {
+      // The source color is mixed with the blended color by the destination
+      // alpha, then composited over the destination.
+      let mixed = (1. - da) * s + da * self.blend_channel(s, d);
+      (sa * mixed + da * d * (1. - sa)) / alpha
+    };
+    Color::from_f32_rgba(composite(sr, dr), composite(sg, dg), composite(sb, db), alpha)
+  }
+
+  fn blend_channel(self, s: f32, d: f32) -> f32 {
+    match self {
+      BlendMode::SrcOver => s,
+      BlendMode::Multiply => s * d,
+      BlendMode::Screen => s + d - s * d,
+      BlendMode::Overlay => {
+        if d <= 0.5 {
+          2. * s * d
+        } else {
+          1. - 2. * (1. - s) * (1. - d)
+        }
+      }
+      BlendMode::Darken => s.min(d),
+      BlendMode::Lighten => s.max(d),
+    }
+  }
+}
+
+/// The style of a shadow painted by
+/// [`Painter::draw_shadow`](crate::Painter::draw_shadow).
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ShadowStyle {
+  pub color: Color,
+  /// The blur radius of the shadow, zero paints a crisp shadow.
+  pub blur: f32,
+  /// The offset of the shadow from the path.
+  pub offset: Vector,
+  /// How far the silhouette grows on every side before it is blurred, a
+  /// negative value shrinks it. For an inset shadow, the silhouette is the hole
+  /// of the shadow, so the spread shrinks it.
+  pub spread: f32,
+  /// Paint the shadow inside the path instead of behind it.
+  pub inset: bool,
+}
+
+impl ShadowStyle {
+  /// The maximum blur radius, a larger one is clamped to it. The backends
+  /// also clamp the radius scaled to the device pixels by it.
+  pub const MAX_BLUR: f32 = 128.;
+
+  /// Return the bounds of the silhouette of the shadow in the path axis, or
+  /// `None` if the spread shrinks the silhouette to nothing.
+  ///
+  /// The silhouette is the path with its outline offset by the spread, then
+  /// moved by the offset.
+  pub fn silhouette_bounds(&self, path_bounds: &Rect) -> Option<Rect> {
+    let spread = if self.inset { -self.spread } else { self.spread };
+    let bounds = path_bounds.inflate(spread, spread);
+    if spread < 0. && (bounds.width() <= 0. || bounds.height() <= 0.) {
+      return None;
+    }
+    Some(bounds.translate(self.offset))
+  }
+
+  /// Return the bounds the shadow of a path with `path_bounds` paints in, in
+  /// the path axis.
+  pub fn paint_bounds(&self, path_bounds: &Rect) -> Rect {
+    if self.inset {
+      *path_bounds
+    } else {
+      self
+        .silhouette_bounds(path_bounds)
+        .map_or(Rect::zero(), |bounds| bounds.inflate(self.blur, self.blur))
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn blend_opaque() {
+    let gray = Color::from_rgb(128, 128, 128);
+    assert_eq!(BlendMode::SrcOver.blend(Color::RED, gray), Color::RED);
+    assert_eq!(BlendMode::Multiply.blend(Color::RED, gray), Color::from_rgb(128, 0, 0));
+    assert_eq!(BlendMode::Screen.blend(Color::RED, gray), Color::from_rgb(255, 128, 128));
+    assert_eq!(BlendMode::Overlay.blend(Color::RED, gray), Color::from_rgb(255, 1, 1));
+    assert_eq!(BlendMode::Darken.blend(Color::RED, gray), Color::from_rgb(128, 0, 0));
+    assert_eq!(BlendMode::Lighten.blend(Color::RED, gray), Color::from_rgb(255, 128, 128));
+  }
+
+  #[test]
+  fn blend_with_alpha() {
+    let gray = Color::from_rgb(128, 128, 128);
+    let half_red = Color::RED.with_alpha(0.5);
+    // Half of the multiply result and half of the gray.
+    assert_eq!(BlendMode::Multiply.blend(half_red, gray), Color::from_rgb(128, 64, 64));
+    // Nothing to blend with a transparent destination.
+    let transparent = Color::new(0, 0, 0, 0);
+    assert_eq!(BlendMode::Multiply.blend(Color::RED, transparent), Color::RED);
+  }
+
+  #[test]
+  fn conic_gradient() {
+    let stops = vec![GradientStop::new(Color::BLUE, 1.), GradientStop::new(Color::RED, 0.)];
+    let Brush::ConicGradient(mut conic) =
+      Brush::conic_gradient(Point::new(50., 50.), Angle::zero(), stops)
+    else {
+      unreachable!()
+    };
+    assert_eq!(conic.stops[0].color, Color::RED);
+
+    assert_eq!(conic.color_at(Point::new(100., 50.)), Color::RED);
+    assert_eq!(conic.color_at(Point::new(50., 100.)), Color::new(191, 0, 64, 255));
+    assert_eq!(conic.color_at(Point::new(50., 0.)), Color::new(64, 0, 191, 255));
+
+    conic.winding = crate::color::GradientWinding::CounterClockwise;
+    assert_eq!(conic.color_at(Point::new(50., 0.)), Color::new(191, 0, 64, 255));
+  }
+}
@@ -118,12 +118,13 @@ impl App {
           }
           WindowEvent::RedrawRequested => {
             if let Some(wnd) = AppCtx::get_window(wnd_id) {
+              wnd.redraw_delivered();
               // if the window is not visible, don't draw it./
               if wnd.is_visible() != Some(false) {
                 // if this frame is really draw, request another redraw. To make sure the draw
                 // always end with a empty draw and emit an extra tick cycle message.
                 if wnd.draw_frame() {
-                  request_redraw(&wnd);
+                  wnd.request_redraw();
                 }
               }
             }
@@ -131,7 +132,13 @@ impl App {
           WindowEvent::Resized(_) => {
             let size = wnd.shell_wnd().borrow().inner_size();
             wnd.shell_wnd().borrow_mut().on_resize(size);
-            request_redraw(&wnd)
+            wnd.request_redraw()
+          }
+          WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
+            let size = wnd.shell_wnd().borrow().inner_size();
+            wnd.shell_wnd().borrow_mut().on_resize(size);
+            wnd.processes_scale_factor_changed(scale_factor as f32);
+            wnd.request_redraw()
           }
           WindowEvent::Focused(focused) => {
             let mut event = AppEvent::WndFocusChanged(wnd_id, focused);
@@ -148,13 +155,13 @@ impl App {
         let run_count = AppCtx::run_until_stalled();
         if run_count > 0 {
           for wnd in AppCtx::windows().borrow().values() {
-            request_redraw(wnd);
+            wnd.request_redraw();
           }
         }
         if run_count > 0 {
           loop_handle.set_control_flow(ControlFlow::Poll);
         } else if let Some(t) = Timer::recently_timeout() {
-          let control = ControlFlow::wait_duration(t.duration_since(Instant::now()));
+          let control = ControlFlow::wait_duration(t.duration_since(Timer::now()));
           loop_handle.set_control_flow(control);
         } else {
           loop_handle.set_control_flow(ControlFlow::Wait);
@@ -433,15 +440,6 @@ impl RuntimeWaker for EventWaker {
 /// EventWaker only send `RibirEvent::FuturesWake`.
 unsafe impl Send for EventWaker {}
 
-pub(crate) fn request_redraw(wnd: &Window) {
-  let wnd = wnd.shell_wnd().borrow();
-  let shell = wnd
-    .as_any()
-    .downcast_ref::<WinitShellWnd>()
-    .unwrap();
-  shell.winit_wnd.request_redraw();
-}
-
 #[derive(Default)]
 struct PreEditHandle(Option<String>);
 
@@ -1,5 +1,8 @@
-use ribir_core::prelude::{Color, DeviceRect, DeviceSize, PaintCommand, PainterBackend, Transform};
-use ribir_gpu::Surface;
+use ribir_core::prelude::{
+  image::ColorFormat, Color, DeviceRect, DeviceSize, PaintCommand, PainterBackend, PixelImage,
+  Transform,
+};
+use ribir_gpu::{GPUBackendImpl, Surface, Texture};
 
 use crate::winit_shell_wnd::WinitBackend;
 
@@ -43,4 +46,34 @@ impl<'a> WinitBackend<'a> for WgpuBackend<'a> {
     self.backend.end_frame();
     self.surface.present();
   }
+
+  #[cfg(not(target_family = "wasm"))]
+  fn capture(
+    &mut self, viewport: DeviceRect, global_matrix: &Transform, surface_color: Color,
+    commands: &[PaintCommand],
+  ) -> Option<PixelImage> {
+    let rect = DeviceRect::from_size(viewport.size);
+    let offset = viewport.origin.to_f32().to_vector().cast_unit();
+    let matrix = global_matrix.then_translate(-offset);
+    let mut texture = self
+      .backend
+      .get_impl_mut()
+      .new_texture(rect.size, ColorFormat::Rgba8);
+
+    self.backend.begin_frame(surface_color);
+    self
+      .backend
+      .draw_commands(rect, commands, &matrix, &mut texture);
+    let img = texture.copy_as_image(&rect, self.backend.get_impl_mut());
+    self.backend.end_frame();
+    futures::executor::block_on(img).ok()
+  }
+
+  /// The pixels can't be read back synchronously in the browser.
+  #[cfg(target_family = "wasm")]
+  fn capture(
+    &mut self, _: DeviceRect, _: &Transform, _: Color, _: &[PaintCommand],
+  ) -> Option<PixelImage> {
+    None
+  }
 }
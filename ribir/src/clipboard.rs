@@ -1,23 +1,82 @@
 use std::{
   borrow::Cow,
+  collections::hash_map::DefaultHasher,
+  hash::{Hash, Hasher},
   io::{Error, ErrorKind},
 };
 
 use arboard::ImageData;
-use ribir_core::prelude::{image::ColorFormat, log::warn, PixelImage};
+use ribir_core::{
+  clipboard::{IMAGE_FORMAT, TEXT_FORMAT},
+  prelude::{image::ColorFormat, PixelImage},
+};
+
+/// The text written to mark the content of the custom formats if there is no
+/// text or image in the clipboard.
+const CUSTOM_TEXT_PREFIX: &str = "x-ribir-clipboard:";
 
+/// The clipboard of the platform.
+///
+/// The platform clipboard only accepts the text and the image, so the data of
+/// the custom formats is kept in this process beside the text or the image of
+/// the platform clipboard. It's valid until the platform clipboard is changed,
+/// and can't be read by the other processes, even the other instances of the
+/// application, they get the text or the image instead.
 pub struct Clipboard {
   pub clipboard: arboard::Clipboard,
+  custom: Option<CustomContent>,
+}
+
+struct CustomContent {
+  /// The content in the platform clipboard when the custom formats are
+  /// written.
+  content: PlatformContent,
+  formats: Vec<(String, Vec<u8>)>,
+}
+
+#[derive(PartialEq)]
+enum PlatformContent {
+  Text(String),
+  /// The hash of the image, to not keep a copy of the pixels.
+  Image(u64),
 }
 
 impl Clipboard {
   /// Creates an instance of the clipboard
   pub fn new() -> Result<Self, Error> {
     match arboard::Clipboard::new() {
-      Ok(clipboard) => Ok(Clipboard { clipboard }),
+      Ok(clipboard) => Ok(Clipboard { clipboard, custom: None }),
       Err(e) => Err(error_convert(e)),
     }
   }
+
+  /// The custom content written by this application, if the platform clipboard
+  /// has not been changed since then.
+  fn owned_custom(&mut self) -> Option<&mut CustomContent> {
+    if self.custom.is_some() {
+      let content = self.platform_content();
+      if self
+        .custom
+        .as_ref()
+        .is_some_and(|c| Some(&c.content) != content.as_ref())
+      {
+        self.custom = None;
+      }
+    }
+    self.custom.as_mut()
+  }
+
+  /// The text in the platform clipboard, or the image if there is no text.
+  fn platform_content(&mut self) -> Option<PlatformContent> {
+    if let Ok(text) = self.clipboard.get_text() {
+      return Some(PlatformContent::Text(text));
+    }
+    self.clipboard.get_image().ok().map(|img| {
+      let mut hasher = DefaultHasher::new();
+      (img.width, img.height, &img.bytes).hash(&mut hasher);
+      PlatformContent::Image(hasher.finish())
+    })
+  }
 }
 
 impl ribir_core::clipboard::Clipboard for Clipboard {
@@ -26,6 +85,7 @@ impl ribir_core::clipboard::Clipboard for Clipboard {
   }
 
   fn write_text(&mut self, text: &str) -> Result<(), Error> {
+    self.custom = None;
     self
       .clipboard
       .set_text(text)
@@ -42,36 +102,155 @@ impl ribir_core::clipboard::Clipboard for Clipboard {
   }
 
   fn write_img(&mut self, img: &PixelImage) -> Result<(), Error> {
+    // The platform clipboard only accepts RGBA8 pixels, convert the gray image
+    // as the same as we encode it to png.
+    let bytes = match img.color_format() {
+      ColorFormat::Rgba8 => img.pixel_bytes().to_vec(),
+      ColorFormat::Alpha8 => img
+        .pixel_bytes()
+        .iter()
+        .flat_map(|v| [*v, *v, *v, u8::MAX])
+        .collect(),
+    };
+    self.custom = None;
     self
       .clipboard
       .set_image(ImageData {
         width: img.width() as usize,
         height: img.height() as usize,
-        bytes: Cow::Owned(img.pixel_bytes().to_vec()),
+        bytes: Cow::Owned(bytes),
       })
       .map_err(error_convert)
   }
 
   fn read(&mut self, format: &str) -> Result<Cow<[u8]>, Error> {
-    warn!("read {format} data from clipboard");
-    Err(Error::new(std::io::ErrorKind::Unsupported, "clipboard read format {format}"))
+    if format == TEXT_FORMAT {
+      return self
+        .read_text()
+        .map(|text| Cow::Owned(text.into_bytes()));
+    }
+    self
+      .owned_custom()
+      .and_then(|c| c.formats.iter().find(|(f, _)| f == format))
+      .map(|(_, data)| Cow::Borrowed(data.as_slice()))
+      .ok_or_else(|| Error::new(ErrorKind::Other, "ContentNotAvailable"))
+  }
+
+  fn write(&mut self, format: &str, data: &[u8]) -> Result<(), Error> {
+    if format == TEXT_FORMAT {
+      let text = std::str::from_utf8(data).map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+      return self.write_text(text);
+    }
+
+    if self.owned_custom().is_none() {
+      let content = match self.platform_content() {
+        Some(content) => content,
+        None => {
+          let text = format!("{CUSTOM_TEXT_PREFIX}{format}");
+          self
+            .clipboard
+            .set_text(&text)
+            .map_err(error_convert)?;
+          PlatformContent::Text(text)
+        }
+      };
+      self.custom = Some(CustomContent { content, formats: vec![] });
+    }
+
+    let formats = &mut self.custom.as_mut().unwrap().formats;
+    match formats.iter_mut().find(|(f, _)| f == format) {
+      Some((_, old)) => *old = data.to_vec(),
+      None => formats.push((format.to_string(), data.to_vec())),
+    }
+    Ok(())
   }
 
-  fn write(&mut self, format: &str, _data: &[u8]) -> Result<(), Error> {
-    warn!("write {format} data to clipboard");
-    Err(Error::new(std::io::ErrorKind::Unsupported, "clipboard write format {format}"))
+  fn available_formats(&mut self) -> Result<Vec<String>, Error> {
+    let mut formats = vec![];
+    if self.clipboard.get_text().is_ok() {
+      formats.push(TEXT_FORMAT.to_string());
+    }
+    if self.clipboard.get_image().is_ok() {
+      formats.push(IMAGE_FORMAT.to_string());
+    }
+    if let Some(custom) = self.owned_custom() {
+      formats.extend(custom.formats.iter().map(|(f, _)| f.clone()));
+    }
+    Ok(formats)
   }
 
-  fn clear(&mut self) -> Result<(), Error> { self.clipboard.clear().map_err(error_convert) }
+  fn clear(&mut self) -> Result<(), Error> {
+    self.custom = None;
+    self.clipboard.clear().map_err(error_convert)
+  }
 }
 
 fn error_convert(err: arboard::Error) -> Error {
   match err {
     arboard::Error::ContentNotAvailable => Error::new(ErrorKind::Other, "ContentNotAvailable"),
-    arboard::Error::ClipboardNotSupported => Error::new(ErrorKind::Other, "ClipboardNotSupported"),
+    arboard::Error::ClipboardNotSupported => {
+      Error::new(ErrorKind::Unsupported, "ClipboardNotSupported")
+    }
     arboard::Error::ClipboardOccupied => Error::new(ErrorKind::Other, "ClipboardOccupied"),
     arboard::Error::ConversionFailure => Error::new(ErrorKind::Other, "ConversionFailure"),
     arboard::Error::Unknown { description } => Error::new(ErrorKind::Other, description),
     e => Error::new(ErrorKind::Other, e),
   }
 }
+
+#[cfg(all(test, feature = "clipboard-test"))]
+mod tests {
+  use ribir_core::clipboard::Clipboard as _;
+
+  use super::*;
+
+  #[test]
+  fn image_round_trip() {
+    let mut clipboard = Clipboard::new().unwrap();
+    let pixels = vec![255, 0, 0, 255, 0, 255, 0, 255, 0, 0, 255, 255, 255, 255, 255, 128];
+    let img = PixelImage::new(pixels.clone().into(), 2, 2, ColorFormat::Rgba8);
+    clipboard.write_img(&img).unwrap();
+
+    let img = clipboard.read_img().unwrap();
+    assert_eq!((img.width(), img.height()), (2, 2));
+    assert_eq!(img.pixel_bytes(), &pixels[..]);
+
+    let gray = PixelImage::new(vec![0, 255].into(), 2, 1, ColorFormat::Alpha8);
+    clipboard.write_img(&gray).unwrap();
+    let img = clipboard.read_img().unwrap();
+    assert_eq!(img.pixel_bytes(), &[0, 0, 0, 255, 255, 255, 255, 255]);
+  }
+
+  #[test]
+  fn custom_format_round_trip() {
+    const FORMAT: &str = "application/x-myapp+json";
+    let mut clipboard = Clipboard::new().unwrap();
+    clipboard.write_text("readable").unwrap();
+    clipboard.write(FORMAT, br#"{"id":1}"#).unwrap();
+
+    assert_eq!(&*clipboard.read(FORMAT).unwrap(), br#"{"id":1}"#);
+    assert_eq!(clipboard.read_text().unwrap(), "readable");
+    let formats = clipboard.available_formats().unwrap();
+    assert!(formats.iter().any(|f| f == TEXT_FORMAT));
+    assert!(formats.iter().any(|f| f == FORMAT));
+
+    // The custom content is gone with a new text.
+    clipboard.write_text("other").unwrap();
+    assert!(clipboard.read(FORMAT).is_err());
+
+    // The image is kept beside the custom content.
+    let pixels = vec![255, 0, 0, 255];
+    let img = PixelImage::new(pixels.clone().into(), 1, 1, ColorFormat::Rgba8);
+    clipboard.write_img(&img).unwrap();
+    clipboard.write(FORMAT, b"[]").unwrap();
+    assert_eq!(&*clipboard.read(FORMAT).unwrap(), b"[]");
+    assert_eq!(clipboard.read_img().unwrap().pixel_bytes(), &pixels[..]);
+
+    // Without text or image, a marker text is written for the other
+    // applications.
+    clipboard.clear().unwrap();
+    clipboard.write(FORMAT, b"{}").unwrap();
+    assert_eq!(&*clipboard.read(FORMAT).unwrap(), b"{}");
+    assert_eq!(clipboard.read_text().unwrap(), format!("{CUSTOM_TEXT_PREFIX}{FORMAT}"));
+  }
+}
@@ -9,10 +9,7 @@ use winit::{
   event_loop::EventLoopWindowTarget,
 };
 
-use crate::{
-  backends::*,
-  prelude::{request_redraw, WindowAttributes},
-};
+use crate::{backends::*, prelude::WindowAttributes};
 pub trait WinitBackend<'a>: Sized {
   fn new(window: &'a winit::window::Window) -> impl Future<Output = Self>;
 
@@ -25,6 +22,13 @@ pub trait WinitBackend<'a>: Sized {
   );
 
   fn end_frame(&mut self);
+
+  /// Render the `commands` to an offscreen texture of the `viewport` size and
+  /// read back its pixels, the `viewport` is moved to the origin of the image.
+  fn capture(
+    &mut self, viewport: DeviceRect, global_matrix: &Transform, surface_color: Color,
+    commands: &[PaintCommand],
+  ) -> Option<PixelImage>;
 }
 
 pub struct WinitShellWnd {
@@ -61,9 +65,7 @@ impl ShellWindow for WinitShellWnd {
       .map(|size| Size::new(size.width as f32, size.height as f32));
     if let Some(size) = size {
       self.on_resize(size);
-      if let Some(wnd) = AppCtx::get_window(self.id()) {
-        request_redraw(&wnd);
-      }
+      self.winit_wnd.request_redraw();
     }
   }
 
@@ -139,6 +141,8 @@ impl ShellWindow for WinitShellWnd {
   #[inline]
   fn set_ime_allowed(&mut self, allowed: bool) { self.winit_wnd.set_ime_allowed(allowed); }
 
+  fn request_redraw(&mut self) { self.winit_wnd.request_redraw(); }
+
   #[inline]
   fn as_any(&self) -> &dyn std::any::Any { self }
 
@@ -149,10 +153,9 @@ impl ShellWindow for WinitShellWnd {
   fn begin_frame(&mut self, surface: Color) { self.backend.begin_frame(surface) }
 
   #[inline]
-  fn draw_commands(&mut self, viewport: Rect, commands: &[PaintCommand]) {
-    let scale = self.winit_wnd.scale_factor() as f32;
-    let viewport: DeviceRect = viewport
-      .scale(scale, scale)
+  fn draw_commands(&mut self, viewport: Rect, transform: &Transform, commands: &[PaintCommand]) {
+    let viewport: DeviceRect = transform
+      .outer_transformed_rect(&viewport)
       .round_out()
       .to_i32()
       .cast_unit();
@@ -160,11 +163,25 @@ impl ShellWindow for WinitShellWnd {
     self.winit_wnd.pre_present_notify();
     self
       .backend
-      .draw_commands(viewport, &Transform::scale(scale, scale), commands);
+      .draw_commands(viewport, transform, commands);
   }
 
   #[inline]
   fn end_frame(&mut self) { self.backend.end_frame() }
+
+  fn capture_commands(
+    &mut self, viewport: Rect, surface_color: Color, commands: &[PaintCommand],
+  ) -> Option<PixelImage> {
+    let scale = self.winit_wnd.scale_factor() as f32;
+    let viewport: DeviceRect = viewport
+      .scale(scale, scale)
+      .round_out()
+      .to_i32()
+      .cast_unit();
+    self
+      .backend
+      .capture(viewport, &Transform::scale(scale, scale), surface_color, commands)
+  }
 }
 
 pub(crate) fn new_id(id: winit::window::WindowId) -> WindowId {
@@ -5,15 +5,19 @@ use fontdb::{Database, Query};
 pub use fontdb::{FaceInfo, Family, ID};
 use ribir_algo::{Resource, Sc};
 use ribir_geom::{rect, Point, Rect};
-use ribir_painter::{path_builder::PathBuilder, Path, PathStyle, PixelImage, Svg};
+use ribir_painter::{path_builder::PathBuilder, Color, Path, PathStyle, PixelImage, Svg};
 use rustybuzz::ttf_parser::{GlyphId, OutlineBuilder};
 
-use crate::{svg_glyph_cache::SvgGlyphCache, FontFace, FontFamily};
+use crate::{
+  svg_glyph_cache::SvgGlyphCache, FontFace, FontFamily, GlyphCacheStats, GlyphRasterCache,
+};
 /// A wrapper of fontdb and cache font data.
 pub struct FontDB {
   default_fonts: Vec<ID>,
   data_base: fontdb::Database,
   cache: HashMap<ID, Option<Face>>,
+  raster_glyphs: Sc<RefCell<GlyphRasterCache>>,
+  faces_revision: usize,
 }
 
 type FontGlyphCache<K, V> = Sc<RefCell<HashMap<K, Option<V>>>>;
@@ -24,7 +28,7 @@ pub struct Face {
   pub face_data_index: u32,
   pub rb_face: rustybuzz::Face<'static>,
   #[cfg(feature = "raster_png_font")]
-  raster_image_glyphs: FontGlyphCache<GlyphId, Resource<PixelImage>>,
+  raster_image_glyphs: Sc<RefCell<GlyphRasterCache>>,
   outline_glyphs: FontGlyphCache<(GlyphId, PathStyle), Resource<Path>>,
   svg_glyphs: Sc<RefCell<SvgGlyphCache>>,
 }
@@ -45,9 +49,19 @@ impl FontDB {
   }
 
   pub fn face_data_or_insert(&mut self, face_id: ID) -> Option<&Face> {
-    get_or_insert_face(&mut self.cache, &self.data_base, face_id).as_ref()
+    get_or_insert_face(&mut self.cache, &self.data_base, &self.raster_glyphs, face_id).as_ref()
   }
 
+  /// Sets the byte budget of the rasterized glyphs cached by all the faces,
+  /// the least recently used glyphs are evicted when it's exceeded.
+  pub fn set_glyph_cache_budget(&mut self, bytes: usize) {
+    self.raster_glyphs.borrow_mut().set_budget(bytes);
+  }
+
+  /// Return the hit and miss counts and the usage of the rasterized glyphs
+  /// cache.
+  pub fn glyph_cache_stats(&self) -> GlyphCacheStats { self.raster_glyphs.borrow().stats() }
+
   /// Selects a `FaceInfo` by `id`.
   ///
   /// Returns `None` if a face with such ID was already removed,
@@ -66,11 +80,37 @@ impl FontDB {
       face_id_iter: self.data_base.faces(),
       data_base: &self.data_base,
       cache: &mut self.cache,
+      raster_glyphs: &self.raster_glyphs,
     }
   }
 
   #[inline]
-  pub fn load_from_bytes(&mut self, data: Vec<u8>) { self.data_base.load_font_data(data); }
+  pub fn load_from_bytes(&mut self, data: Vec<u8>) { self.load_font_data(data); }
+
+  /// Loads a font from its bytes, and returns the ids of the faces in it, a
+  /// font collection may contain several faces.
+  pub fn load_font_data(&mut self, data: Vec<u8>) -> Vec<ID> {
+    let source = fontdb::Source::Binary(Arc::new(data));
+    self
+      .data_base
+      .load_font_source(source)
+      .into_iter()
+      .collect()
+  }
+
+  /// Removes the face of `id` and all the glyphs cached for it. The text
+  /// shaped by this face is shaped again by the other faces.
+  pub fn unload(&mut self, id: ID) {
+    self.data_base.remove_face(id);
+    self.cache.remove(&id);
+    self.default_fonts.retain(|f| *f != id);
+    self.raster_glyphs.borrow_mut().remove_face(id);
+    self.faces_revision += 1;
+  }
+
+  /// The revision of the faces, it changes every time a face is unloaded. The
+  /// caches of the shaped text are dropped when it changes.
+  pub fn faces_revision(&self) -> usize { self.faces_revision }
 
   /// Loads a font file into the `Database`.
   ///
@@ -254,7 +294,13 @@ impl Default for FontDB {
     let mut data_base = fontdb::Database::new();
     data_base.load_font_data(include_bytes!("../Lato-Regular.ttf").to_vec());
     let default_font = data_base.faces().next().map(|f| f.id).unwrap();
-    let mut this = FontDB { default_fonts: vec![default_font], data_base, cache: <_>::default() };
+    let mut this = FontDB {
+      default_fonts: vec![default_font],
+      data_base,
+      cache: <_>::default(),
+      raster_glyphs: <_>::default(),
+      faces_revision: 0,
+    };
     this.face_data_or_insert(default_font);
     this
   }
@@ -316,8 +362,7 @@ impl Face {
     self
       .raster_image_glyphs
       .borrow_mut()
-      .entry(glyph_id)
-      .or_insert_with(|| {
+      .get_or_insert(self.face_id, glyph_id, pixels_per_em, || {
         self
           .rb_face
           .glyph_raster_image(glyph_id, pixels_per_em)
@@ -329,17 +374,30 @@ impl Face {
             }
           })
       })
-      .clone()
   }
 
-  pub fn glyph_svg_image(&self, glyph_id: GlyphId) -> Option<Svg> {
+  /// Return the svg image of the glyph. If `override_fill` is set, it's the
+  /// `currentColor` of the glyph, and the other colors of the glyph are kept.
+  pub fn glyph_svg_image(&self, glyph_id: GlyphId, override_fill: Option<Color>) -> Option<Svg> {
     self
       .svg_glyphs
       .borrow_mut()
-      .svg_or_insert(glyph_id, &self.rb_face)
+      .svg_or_insert(glyph_id, &self.rb_face, override_fill)
       .clone()
   }
 
+  /// Use the svg `content` as the document of the glyphs in the `range`, so a
+  /// test can draw a svg glyph with any font.
+  #[cfg(test)]
+  pub(crate) fn insert_svg_document(
+    &self, range: std::ops::RangeInclusive<GlyphId>, content: &str,
+  ) {
+    self
+      .svg_glyphs
+      .borrow_mut()
+      .insert_document(range, content);
+  }
+
   #[inline]
   pub fn units_per_em(&self) -> u16 { self.rb_face.deref().units_per_em() }
 }
@@ -417,6 +475,7 @@ where
   face_id_iter: T,
   data_base: &'a Database,
   cache: &'a mut HashMap<ID, Option<Face>>,
+  raster_glyphs: &'a Sc<RefCell<GlyphRasterCache>>,
 }
 
 impl<'a, T> Iterator for FaceIter<'a, T>
@@ -427,7 +486,7 @@ where
   fn next(&mut self) -> Option<Self::Item> {
     loop {
       let info = self.face_id_iter.next()?;
-      let face = get_or_insert_face(self.cache, self.data_base, info.id)
+      let face = get_or_insert_face(self.cache, self.data_base, self.raster_glyphs, info.id)
         .as_ref()
         .cloned();
       if face.is_some() {
@@ -437,8 +496,10 @@ where
   }
 }
 
+#[cfg_attr(not(feature = "raster_png_font"), allow(unused_variables, unused_mut))]
 fn get_or_insert_face<'a>(
-  cache: &'a mut HashMap<ID, Option<Face>>, data_base: &'a Database, id: ID,
+  cache: &'a mut HashMap<ID, Option<Face>>, data_base: &'a Database,
+  raster_glyphs: &Sc<RefCell<GlyphRasterCache>>, id: ID,
 ) -> &'a Option<Face> {
   cache.entry(id).or_insert_with(|| {
     data_base
@@ -457,7 +518,13 @@ fn get_or_insert_face<'a>(
           }
           fontdb::Source::SharedFile(_, data) => Some(data),
         }?;
-        Face::from_data(id, source_data, face_index)
+        let mut face = Face::from_data(id, source_data, face_index)?;
+        // All the faces share the budget of the rasterized glyphs.
+        #[cfg(feature = "raster_png_font")]
+        {
+          face.raster_image_glyphs = raster_glyphs.clone();
+        }
+        Some(face)
       })
   })
 }
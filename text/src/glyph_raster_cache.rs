@@ -0,0 +1,214 @@
+use std::collections::BTreeMap;
+
+use ahash::HashMap;
+use fontdb::ID;
+use ribir_algo::Resource;
+use ribir_painter::PixelImage;
+use rustybuzz::ttf_parser::GlyphId;
+
+/// The default byte budget of the glyph raster cache.
+pub const DEFAULT_GLYPH_CACHE_BUDGET: usize = 16 * 1024 * 1024;
+
+type RasterKey = (ID, GlyphId, u16);
+
+/// A least-recently-used cache of the rasterized glyphs, shared by all the
+/// faces of a [`FontDB`](crate::font_db::FontDB). The pixel bytes it holds
+/// never exceed its budget, the least recently used glyphs are evicted first.
+pub struct GlyphRasterCache {
+  budget: usize,
+  bytes: usize,
+  tick: u64,
+  entries: HashMap<RasterKey, RasterEntry>,
+  /// The keys ordered by their last used tick.
+  lru: BTreeMap<u64, RasterKey>,
+  hits: usize,
+  misses: usize,
+}
+
+struct RasterEntry {
+  image: Option<Resource<PixelImage>>,
+  bytes: usize,
+  tick: u64,
+}
+
+/// The statistics of the [`GlyphRasterCache`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GlyphCacheStats {
+  pub hits: usize,
+  pub misses: usize,
+  /// The pixel bytes of the cached glyphs.
+  pub bytes: usize,
+  pub entries: usize,
+}
+
+impl GlyphRasterCache {
+  pub fn new(budget: usize) -> Self {
+    Self {
+      budget,
+      bytes: 0,
+      tick: 0,
+      entries: <_>::default(),
+      lru: BTreeMap::new(),
+      hits: 0,
+      misses: 0,
+    }
+  }
+
+  /// Return the cached raster of the glyph, or rasterize it by `raster` and
+  /// cache it. A raster larger than the whole budget is returned without
+  /// caching.
+  pub fn get_or_insert(
+    &mut self, face: ID, glyph_id: GlyphId, pixels_per_em: u16,
+    raster: impl FnOnce() -> Option<Resource<PixelImage>>,
+  ) -> Option<Resource<PixelImage>> {
+    let key = (face, glyph_id, pixels_per_em);
+    self.tick += 1;
+    if let Some(entry) = self.entries.get_mut(&key) {
+      self.hits += 1;
+      self.lru.remove(&entry.tick);
+      entry.tick = self.tick;
+      self.lru.insert(self.tick, key);
+      return entry.image.clone();
+    }
+
+    self.misses += 1;
+    let image = raster();
+    let bytes = image
+      .as_ref()
+      .map_or(0, |img| img.pixel_bytes().len());
+    if bytes <= self.budget {
+      self.bytes += bytes;
+      self.lru.insert(self.tick, key);
+      let entry = RasterEntry { image: image.clone(), bytes, tick: self.tick };
+      self.entries.insert(key, entry);
+      self.evict();
+    }
+    image
+  }
+
+  /// Set the byte budget of the cache, the glyphs over the budget are evicted
+  /// immediately.
+  pub fn set_budget(&mut self, bytes: usize) {
+    self.budget = bytes;
+    self.evict();
+  }
+
+  pub fn budget(&self) -> usize { self.budget }
+
+  pub fn stats(&self) -> GlyphCacheStats {
+    GlyphCacheStats {
+      hits: self.hits,
+      misses: self.misses,
+      bytes: self.bytes,
+      entries: self.entries.len(),
+    }
+  }
+
+  /// Remove all the cached glyphs of the `face`.
+  pub fn remove_face(&mut self, face: ID) {
+    let Self { entries, lru, bytes, .. } = self;
+    entries.retain(|key, entry| {
+      let keep = key.0 != face;
+      if !keep {
+        lru.remove(&entry.tick);
+        *bytes -= entry.bytes;
+      }
+      keep
+    });
+  }
+
+  pub fn clear(&mut self) {
+    self.entries.clear();
+    self.lru.clear();
+    self.bytes = 0;
+  }
+
+  fn evict(&mut self) {
+    while self.bytes > self.budget {
+      let Some((_, key)) = self.lru.pop_first() else { break };
+      if let Some(entry) = self.entries.remove(&key) {
+        self.bytes -= entry.bytes;
+      }
+    }
+  }
+}
+
+impl Default for GlyphRasterCache {
+  fn default() -> Self { Self::new(DEFAULT_GLYPH_CACHE_BUDGET) }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::borrow::Cow;
+
+  use ribir_painter::image::ColorFormat;
+
+  use super::*;
+
+  fn image(bytes: usize) -> Option<Resource<PixelImage>> {
+    let data = Cow::Owned(vec![0; bytes]);
+    let img = PixelImage::new(data, bytes as u32 / 4, 1, ColorFormat::Rgba8);
+    Some(Resource::new(img))
+  }
+
+  fn face_id() -> ID {
+    let mut db = fontdb::Database::new();
+    db.load_font_data(include_bytes!("../Lato-Regular.ttf").to_vec());
+    let id = db.faces().next().unwrap().id;
+    id
+  }
+
+  fn is_cached(cache: &mut GlyphRasterCache, face: ID, glyph: u16) -> bool {
+    let mut hit = true;
+    cache.get_or_insert(face, GlyphId(glyph), 16, || {
+      hit = false;
+      None
+    });
+    hit
+  }
+
+  #[test]
+  fn evict_least_recently_used() {
+    let face = face_id();
+    let mut cache = GlyphRasterCache::new(400);
+    for i in 0..4 {
+      cache.get_or_insert(face, GlyphId(i), 16, || image(100));
+    }
+    // Touch the oldest one, so it's the most recently used.
+    cache.get_or_insert(face, GlyphId(0), 16, || unreachable!());
+    cache.get_or_insert(face, GlyphId(4), 16, || image(100));
+    cache.get_or_insert(face, GlyphId(5), 16, || image(100));
+    assert_eq!(cache.stats(), GlyphCacheStats { hits: 1, misses: 6, bytes: 400, entries: 4 });
+
+    assert!(is_cached(&mut cache, face, 0));
+    assert!(is_cached(&mut cache, face, 3));
+    assert!(is_cached(&mut cache, face, 4));
+    assert!(is_cached(&mut cache, face, 5));
+    assert!(!is_cached(&mut cache, face, 1));
+    assert!(!is_cached(&mut cache, face, 2));
+  }
+
+  #[test]
+  fn bypass_glyph_over_budget() {
+    let face = face_id();
+    let mut cache = GlyphRasterCache::new(400);
+    cache.get_or_insert(face, GlyphId(0), 16, || image(100));
+
+    let img = cache.get_or_insert(face, GlyphId(1), 16, || image(1000));
+    assert!(img.is_some());
+    assert!(is_cached(&mut cache, face, 0));
+    assert_eq!(cache.stats().bytes, 100);
+  }
+
+  #[test]
+  fn shrink_budget() {
+    let face = face_id();
+    let mut cache = GlyphRasterCache::new(400);
+    for i in 0..4 {
+      cache.get_or_insert(face, GlyphId(i), 16, || image(100));
+    }
+    cache.set_budget(200);
+    assert_eq!(cache.stats().entries, 2);
+    assert!(is_cached(&mut cache, face, 3));
+  }
+}
@@ -21,12 +21,17 @@ pub mod text_reorder;
 pub mod typography;
 use ordered_float::OrderedFloat;
 pub use text_reorder::TextReorder;
-pub use typography::Overflow;
+pub use typography::{Overflow, TextOverflow};
 mod typography_store;
-pub use typography_store::{TypographyStore, VisualGlyphs};
+pub use typography_store::{TextMetrics, TypographyStore, VisualGlyphs};
 mod text_render;
-pub use text_render::{draw_glyphs, draw_glyphs_in_rect, TextStyle};
+pub use text_render::{
+  draw_faded_glyphs_in_rect, draw_glyph, draw_glyphs, draw_glyphs_in_rect, draw_text_run, GlyphPaint,
+  TextStyle,
+};
 mod svg_glyph_cache;
+mod glyph_raster_cache;
+pub use glyph_raster_cache::{GlyphCacheStats, GlyphRasterCache, DEFAULT_GLYPH_CACHE_BUDGET};
 
 mod text_writer;
 pub use text_writer::{
@@ -166,9 +171,10 @@ pub enum TextAlign {
   End,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
 pub enum TextDirection {
   /// Text is set horizontally from left to right.
+  #[default]
   LeftToRight,
   /// Text is set horizontally from right to left.
   RightToLeft,
@@ -386,16 +392,19 @@ pub trait VisualText {
   fn text_style(&self) -> &TextStyle;
   fn text_align(&self) -> TextAlign;
   fn overflow(&self) -> Overflow;
+  fn max_lines(&self) -> Option<usize> { None }
+  fn text_overflow(&self) -> TextOverflow { TextOverflow::Clip }
 
   fn text_layout(&self, typography_store: &TypographyStore, bound: Size) -> VisualGlyphs {
-    let TextStyle { font_size, letter_space, line_height, ref font_face, .. } = *self.text_style();
+    let style = self.text_style();
+    let TextStyle { font_size, letter_space, line_height, .. } = *style;
 
     let width: Em = Pixel(bound.width).into();
     let height: Em = Pixel(bound.height).into();
     typography_store.typography(
       self.text().substr(..),
       font_size,
-      font_face,
+      &style.fallback_face(),
       TypographyCfg {
         line_height,
         letter_space,
@@ -403,6 +412,8 @@ pub trait VisualText {
         bounds: (width, height).into(),
         line_dir: PlaceLineDirection::TopToBottom,
         overflow: self.overflow(),
+        max_lines: self.max_lines(),
+        text_overflow: self.text_overflow(),
       },
     )
   }
@@ -1,5 +1,5 @@
 use std::{
-  cell::RefCell,
+  cell::{Cell, RefCell},
   hash::{Hash, Hasher},
   rc::Rc,
 };
@@ -22,6 +22,8 @@ pub const NEWLINE_GLYPH_ID: GlyphId = GlyphId(u16::MAX);
 pub struct TextShaper {
   font_db: Rc<RefCell<FontDB>>,
   shape_cache: Rc<RefCell<FrameCache<ShapeKey, Rc<ShapeResult>>>>,
+  /// The faces revision of the font database that the cache shaped by.
+  faces_revision: Rc<Cell<usize>>,
 }
 
 #[derive(Debug, Clone)]
@@ -43,8 +45,10 @@ struct GlyphsWithoutFallback {
 }
 
 impl TextShaper {
-  #[inline]
-  pub fn new(font_db: Rc<RefCell<FontDB>>) -> Self { Self { font_db, shape_cache: <_>::default() } }
+  pub fn new(font_db: Rc<RefCell<FontDB>>) -> Self {
+    let faces_revision = Rc::new(Cell::new(font_db.borrow().faces_revision()));
+    Self { font_db, shape_cache: <_>::default(), faces_revision }
+  }
 
   pub fn end_frame(&mut self) {
     self
@@ -53,6 +57,9 @@ impl TextShaper {
       .end_frame("Text shape");
   }
 
+  /// Drop all the cached shape results.
+  pub fn clear_cache(&self) { self.shape_cache.borrow_mut().clear(); }
+
   /// Shape text and return the glyphs, caller should do text reorder before
   /// call this method.
   pub fn shape_text(
@@ -132,6 +139,11 @@ impl TextShaper {
   pub fn get_from_cache(
     &self, text: &str, face_ids: &[ID], direction: TextDirection,
   ) -> Option<Rc<ShapeResult>> {
+    // The cached glyphs may be shaped by an unloaded face.
+    let revision = self.font_db.borrow().faces_revision();
+    if self.faces_revision.replace(revision) != revision {
+      self.clear_cache();
+    }
     self
       .shape_cache
       .borrow_mut()
@@ -354,7 +366,7 @@ impl<'a> FallBackFaceHelper<'a> {
 #[cfg(test)]
 mod tests {
   use super::*;
-  use crate::{FontFace, FontFamily};
+  use crate::{FontFace, FontFamily, TextStyle};
 
   #[test]
   fn smoke() {
@@ -534,4 +546,88 @@ mod tests {
     let res = shaper.shape_text(&"👨‍👩‍👦‍👦".into(), &ids_all, TextDirection::LeftToRight);
     assert!(res.glyphs.len() == 7);
   }
+
+  #[test]
+  fn fallback_fonts_of_mixed_scripts() {
+    let shaper = TextShaper::new(<_>::default());
+    let path = env!("CARGO_MANIFEST_DIR").to_owned() + "/../fonts/";
+    let fonts = ["Nunito-VariableFont_wght.ttf", "NotoSerifSC-Bold.你好世界.otf", "DejaVuSans.ttf"];
+    for font in fonts {
+      let _ = shaper
+        .font_db
+        .borrow_mut()
+        .load_font_file(path.clone() + font);
+    }
+    let face_id = |name: &'static str| {
+      shaper
+        .font_db
+        .borrow_mut()
+        .select_best_match(&FontFace {
+          families: Box::new([FontFamily::Name(name.into())]),
+          ..<_>::default()
+        })
+        .unwrap()
+    };
+    let (latin, cjk, emoji) = (face_id("Nunito"), face_id("Noto Serif SC"), face_id("DejaVu Sans"));
+
+    let text: Substr = "Hi 你好 😀!".into();
+    let dir = TextDirection::LeftToRight;
+    let mut style = TextStyle {
+      font_face: FontFace {
+        families: Box::new([FontFamily::Name("Nunito".into())]),
+        ..<_>::default()
+      },
+      ..<_>::default()
+    };
+    let ids = shaper
+      .font_db
+      .borrow_mut()
+      .select_all_match(&style.fallback_face());
+    let res = shaper.shape_text(&text, &ids, dir);
+    assert!(res.glyphs.iter().any(|g| g.is_miss()));
+
+    style.fallback_fonts =
+      vec![FontFamily::Name("Noto Serif SC".into()), FontFamily::Name("DejaVu Sans".into())];
+    let ids = shaper
+      .font_db
+      .borrow_mut()
+      .select_all_match(&style.fallback_face());
+    let res = shaper.shape_text(&text, &ids, dir);
+    assert!(res.glyphs.iter().all(|g| g.is_not_miss()));
+    let faces = res
+      .glyphs
+      .iter()
+      .map(|g| g.face_id)
+      .collect::<Vec<_>>();
+    assert_eq!(faces, [latin, latin, latin, cjk, cjk, latin, emoji, latin]);
+  }
+
+  #[test]
+  fn reshape_after_unload() {
+    let shaper = TextShaper::new(<_>::default());
+    let bytes = include_bytes!("../../fonts/GaramondNo8-Reg.ttf");
+    let id = {
+      let mut font_db = shaper.font_db.borrow_mut();
+      let loaded = font_db.load_font_data(bytes.to_vec());
+      assert_eq!(loaded.len(), 1);
+      assert!(font_db.face_data_or_insert(loaded[0]).is_some());
+      loaded[0]
+    };
+
+    let text: Substr = "abc".into();
+    let dir = TextDirection::LeftToRight;
+    let res = shaper.shape_text(&text, &[id], dir);
+    assert_eq!(res.glyphs.len(), 3);
+    assert!(res.glyphs.iter().all(|g| g.face_id == id));
+
+    shaper.font_db.borrow_mut().unload(id);
+    assert!(shaper.font_db.borrow().face_info(id).is_none());
+    assert!(shaper.get_from_cache(&text, &[id], dir).is_none());
+
+    // Fall back to the default font.
+    let res = shaper.shape_text(&text, &[id], dir);
+    assert_eq!(res.glyphs.len(), 3);
+    let fallback = res.glyphs.iter().all(|g| g.face_id != id);
+    assert!(fallback && res.glyphs.iter().all(Glyph::is_not_miss));
+  }
 }
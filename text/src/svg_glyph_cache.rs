@@ -1,4 +1,6 @@
-use std::{borrow::Cow, collections::BTreeMap, io::prelude::*, ops::RangeInclusive};
+use std::{
+  borrow::Cow, cell::RefCell, collections::BTreeMap, io::prelude::*, ops::RangeInclusive, rc::Rc,
+};
 
 use ahash::{HashMap, HashSet};
 use log::warn;
@@ -7,30 +9,66 @@ use quick_xml::{
   name::QName,
   reader::Reader,
 };
-use ribir_painter::Svg;
+use ribir_painter::{Color, Svg};
 use rustybuzz::ttf_parser::GlyphId;
 
+/// The max number of the glyphs cached with an override fill, the least
+/// recently used ones are evicted first. The glyphs without it are bounded by
+/// the glyphs of the face.
+const MAX_FILLED_GLYPHS: usize = 256;
+
+type FilledKey = (GlyphId, u32);
+
 #[derive(Default)]
 pub struct SvgGlyphCache {
   svg_docs: SvgDocumentCache,
   svg_glyphs: HashMap<GlyphId, Option<Svg>>,
+  /// The glyphs filled with an override color and their last used tick.
+  filled_glyphs: HashMap<FilledKey, (Option<Svg>, u64)>,
+  tick: u64,
+  /// The keys of the filled glyphs ordered by their last used tick.
+  lru: BTreeMap<u64, FilledKey>,
 }
 
 impl SvgGlyphCache {
-  pub fn svg_or_insert(&mut self, glyph_id: GlyphId, rb_face: &rustybuzz::Face) -> &Option<Svg> {
-    let SvgGlyphCache { svg_docs, svg_glyphs } = self;
-    svg_glyphs.entry(glyph_id).or_insert_with(|| {
-      if let Some(doc) = svg_docs.get(glyph_id) {
-        doc.glyph_svg(glyph_id, rb_face)
-      } else {
-        rb_face.glyph_svg_image(glyph_id).and_then(|doc| {
-          let doc = SvgDocument::new(doc.glyphs_range(), doc.data);
-          let svg = doc.glyph_svg(glyph_id, rb_face);
-          svg_docs.insert(doc);
-          svg
-        })
+  /// Return the svg of the glyph, if `override_fill` is set, it's the
+  /// `currentColor` of the glyph.
+  pub fn svg_or_insert(
+    &mut self, glyph_id: GlyphId, rb_face: &rustybuzz::Face, override_fill: Option<Color>,
+  ) -> &Option<Svg> {
+    let SvgGlyphCache { svg_docs, svg_glyphs, filled_glyphs, tick, lru } = self;
+    // The glyph without `currentColor` is the same for every fill.
+    let override_fill = override_fill.filter(|_| svg_docs.use_current_color(glyph_id, rb_face));
+    let Some(fill) = override_fill else {
+      return svg_glyphs
+        .entry(glyph_id)
+        .or_insert_with(|| svg_docs.glyph_svg(glyph_id, rb_face, None));
+    };
+
+    let key = (glyph_id, fill.into_u32());
+    *tick += 1;
+    if let Some((_, used)) = filled_glyphs.get_mut(&key) {
+      lru.remove(used);
+      *used = *tick;
+    } else {
+      if filled_glyphs.len() >= MAX_FILLED_GLYPHS {
+        if let Some((_, key)) = lru.pop_first() {
+          filled_glyphs.remove(&key);
+        }
       }
-    })
+      let svg = svg_docs.glyph_svg(glyph_id, rb_face, override_fill);
+      filled_glyphs.insert(key, (svg, *tick));
+    }
+    lru.insert(*tick, key);
+    &filled_glyphs[&key].0
+  }
+
+  /// Use the svg `content` as the document of the glyphs in the `range`.
+  #[cfg(test)]
+  pub(crate) fn insert_document(&mut self, range: RangeInclusive<GlyphId>, content: &str) {
+    self
+      .svg_docs
+      .insert(SvgDocument::new(range, content.as_bytes()));
   }
 }
 
@@ -42,6 +80,32 @@ struct SvgDocumentCache {
 impl SvgDocumentCache {
   fn insert(&mut self, doc: SvgDocument) { self.docs.insert(*doc.range.start(), doc); }
 
+  /// Load the document of the glyph from the face if it's not loaded, and
+  /// return the svg of the glyph.
+  fn glyph_svg(
+    &mut self, glyph_id: GlyphId, rb_face: &rustybuzz::Face, override_fill: Option<Color>,
+  ) -> Option<Svg> {
+    self
+      .load(glyph_id, rb_face)?
+      .glyph_svg(glyph_id, rb_face, override_fill)
+  }
+
+  /// Whether the glyph paints with `currentColor`.
+  fn use_current_color(&mut self, glyph_id: GlyphId, rb_face: &rustybuzz::Face) -> bool {
+    self
+      .load(glyph_id, rb_face)
+      .and_then(|doc| doc.resolve_glyph(glyph_id))
+      .map_or(false, |resolved| resolved.current_color)
+  }
+
+  fn load(&mut self, glyph_id: GlyphId, rb_face: &rustybuzz::Face) -> Option<&SvgDocument> {
+    if self.get(glyph_id).is_none() {
+      let doc = rb_face.glyph_svg_image(glyph_id)?;
+      self.insert(SvgDocument::new(doc.glyphs_range(), doc.data));
+    }
+    self.get(glyph_id)
+  }
+
   fn get(&self, glyph_id: GlyphId) -> Option<&SvgDocument> {
     // use btreemap.lower_bound is better, but it's unstable now
     let its = self.docs.range(..=glyph_id);
@@ -54,29 +118,44 @@ impl SvgDocumentCache {
 struct SvgDocument {
   range: RangeInclusive<GlyphId>,
   elems: HashMap<String, String>,
+  /// The resolved glyphs, so the links of a glyph are only scanned once.
+  resolved: RefCell<HashMap<GlyphId, Rc<ResolvedGlyph>>>,
+}
+
+/// The elements a glyph depends on, resolved from its content.
+struct ResolvedGlyph {
+  key: String,
+  links: Vec<String>,
+  /// The glyph or its links paint with `currentColor`.
+  current_color: bool,
 }
 
 impl SvgDocument {
   fn new(range: RangeInclusive<GlyphId>, content: &[u8]) -> Self {
     let elems = Self::parse(content).unwrap_or_default();
 
-    Self { range, elems }
+    Self { range, elems, resolved: <_>::default() }
   }
 
-  fn glyph_svg(&self, glyph: GlyphId, face: &rustybuzz::Face) -> Option<Svg> {
-    let key = format!("glyph{}", glyph.0);
-    if !self.elems.contains_key(&key) {
-      return None;
-    }
+  fn glyph_svg(
+    &self, glyph: GlyphId, face: &rustybuzz::Face, override_fill: Option<Color>,
+  ) -> Option<Svg> {
+    let content = self.glyph_svg_content(glyph, face, override_fill)?;
+    Svg::parse_from_bytes(content.as_bytes()).ok()
+  }
 
-    let mut all_links = HashSet::default();
-    let mut elems = vec![key.clone()];
-
-    while let Some(curr) = elems.pop() {
-      if let Some(content) = self.elems.get(&curr) {
-        elems.extend(Self::collect_link(content, &mut all_links));
-      }
-    }
+  fn glyph_svg_content(
+    &self, glyph: GlyphId, face: &rustybuzz::Face, override_fill: Option<Color>,
+  ) -> Option<String> {
+    let resolved = self.resolve_glyph(glyph)?;
+    let glyph_elem = self.elems.get(&resolved.key)?;
+    let links = resolved
+      .links
+      .iter()
+      .filter_map(|link| self.elems.get(link));
+    // Only the `currentColor` is the override color, the other colors of the
+    // glyph are kept.
+    let fill = override_fill.filter(|_| resolved.current_color);
 
     let units_per_em = face.units_per_em();
     let ascender = face.ascender() as i32;
@@ -93,20 +172,55 @@ impl SvgDocument {
       )
       .ok()?;
     writer.write_all("<defs>".as_bytes()).ok()?;
-    for link in all_links {
-      if let Some(content) = self.elems.get(&link) {
-        writer.write_all(content.as_bytes()).ok()?;
-      }
+    for content in links {
+      writer.write_all(content.as_bytes()).ok()?;
     }
     writer.write_all("</defs>".as_bytes()).ok()?;
-    writer
-      .write_all(self.elems.get(&key).unwrap().as_bytes())
-      .ok()?;
+    if let Some(color) = fill {
+      let [r, g, b, a] = color.into_components();
+      writer
+        .write_all(format!("<g color=\"#{r:02x}{g:02x}{b:02x}{a:02x}\">").as_bytes())
+        .ok()?;
+      writer.write_all(glyph_elem.as_bytes()).ok()?;
+      writer.write_all("</g>".as_bytes()).ok()?;
+    } else {
+      writer.write_all(glyph_elem.as_bytes()).ok()?;
+    }
     writer.write_all("</svg>".as_bytes()).ok()?;
 
-    std::str::from_utf8(&writer.into_inner())
-      .ok()
-      .and_then(|str| Svg::parse_from_bytes(str.as_bytes()).ok())
+    String::from_utf8(writer.into_inner()).ok()
+  }
+
+  fn resolve_glyph(&self, glyph: GlyphId) -> Option<Rc<ResolvedGlyph>> {
+    if let Some(resolved) = self.resolved.borrow().get(&glyph) {
+      return Some(resolved.clone());
+    }
+
+    let key = format!("glyph{}", glyph.0);
+    let glyph_elem = self.elems.get(&key)?;
+
+    let mut all_links = HashSet::default();
+    let mut links = vec![];
+    let mut elems = vec![key.clone()];
+    while let Some(curr) = elems.pop() {
+      if let Some(content) = self.elems.get(&curr) {
+        let new_links = Self::collect_link(content, &mut all_links);
+        links.extend(new_links.iter().cloned());
+        elems.extend(new_links);
+      }
+    }
+
+    let current_color = links
+      .iter()
+      .filter_map(|link| self.elems.get(link))
+      .chain(Some(glyph_elem))
+      .any(|content| content.contains("currentColor"));
+    let resolved = Rc::new(ResolvedGlyph { key, links, current_color });
+    self
+      .resolved
+      .borrow_mut()
+      .insert(glyph, resolved.clone());
+    Some(resolved)
   }
 
   fn parse(data: &[u8]) -> Option<HashMap<String, String>> {
@@ -233,9 +347,12 @@ impl SvgDocument {
 
 #[cfg(test)]
 mod tests {
+  use std::rc::Rc;
+
+  use ribir_painter::{Color, PaintCommand, PaintPathAction, PathCommand};
   use rustybuzz::ttf_parser::GlyphId;
 
-  use super::{SvgDocument, SvgDocumentCache};
+  use super::{SvgDocument, SvgDocumentCache, SvgGlyphCache, MAX_FILLED_GLYPHS};
   use crate::font_db::FontDB;
 
   #[test]
@@ -268,12 +385,12 @@ mod tests {
     assert_eq!(doc.elems.len(), 4);
     assert!(
       doc
-        .glyph_svg(GlyphId(2428), dummy_face.as_rb_face())
+        .glyph_svg(GlyphId(2428), dummy_face.as_rb_face(), None)
         .is_some()
     );
     assert!(
       doc
-        .glyph_svg(GlyphId(0), dummy_face.as_rb_face())
+        .glyph_svg(GlyphId(0), dummy_face.as_rb_face(), None)
         .is_none()
     );
   }
@@ -306,4 +423,129 @@ mod tests {
         .map(|doc| *doc.range.start())
     );
   }
+
+  #[test]
+  fn override_fill() {
+    let content = r##"
+        <svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink" version="1.1">
+          <defs>
+            <radialGradient id="g799" cx="638" cy="380" r="508" gradientUnits="userSpaceOnUse">
+              <stop offset="0.598" stop-color="#212121" />
+              <stop offset="1" stop-color="#616161" />
+            </radialGradient>
+          </defs>
+          <path id="glyph1" d="M0,0 L100,0 L100,-100 Z" fill="currentColor"></path>
+          <g id="glyph2">
+            <path d="M0,0 L100,0 L100,-100 Z" fill="url(#g799)"></path>
+            <path d="M0,0 L50,0 L50,-50 Z" fill="#FFCC32"></path>
+          </g>
+          <path id="glyph3" d="M0,0 L100,0 L100,-100 Z" fill="#000000"></path>
+        </svg>"##;
+    let doc = SvgDocument::new(GlyphId(1)..=GlyphId(3), content.as_bytes());
+    let mut db = FontDB::default();
+    let face = db
+      .face_data_or_insert(db.default_fonts()[0])
+      .unwrap();
+    let face = face.as_rb_face();
+    let red = Some(Color::from_u32(0xFF0000FF));
+
+    let current = doc
+      .glyph_svg_content(GlyphId(1), face, red)
+      .unwrap();
+    assert!(current.contains("<g color=\"#ff0000ff\">"));
+    let svg = doc.glyph_svg(GlyphId(1), face, red).unwrap();
+    let painted_red = svg.commands.iter().any(|cmd| {
+      matches!(cmd, PaintCommand::Path(PathCommand { action: PaintPathAction::Color(c), .. })
+        if *c == Color::from_u32(0xFF0000FF))
+    });
+    assert!(painted_red);
+
+    // The glyphs without `currentColor` keep their colors, even if they are
+    // single-color.
+    for glyph in [GlyphId(2), GlyphId(3)] {
+      let filled = doc.glyph_svg_content(glyph, face, red).unwrap();
+      let origin = doc.glyph_svg_content(glyph, face, None).unwrap();
+      assert_eq!(filled, origin);
+      assert!(!filled.contains("#ff0000"));
+    }
+  }
+
+  #[test]
+  fn resolve_glyph_once() {
+    let content = r##"
+        <svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink" version="1.1">
+          <defs>
+            <path id="p1" d="M0,0 L100,0 L100,-100 Z"></path>
+          </defs>
+          <g id="glyph1"><use xlink:href="#p1" fill="#000000" /></g>
+        </svg>"##;
+    let doc = SvgDocument::new(GlyphId(1)..=GlyphId(1), content.as_bytes());
+    let mut db = FontDB::default();
+    let face = db
+      .face_data_or_insert(db.default_fonts()[0])
+      .unwrap();
+    let face = face.as_rb_face();
+
+    assert!(doc.glyph_svg(GlyphId(1), face, None).is_some());
+    let resolved = doc.resolve_glyph(GlyphId(1)).unwrap();
+    assert_eq!(resolved.links, ["p1"]);
+    assert!(!resolved.current_color);
+
+    let red = Some(Color::from_u32(0xFF0000FF));
+    assert!(doc.glyph_svg(GlyphId(1), face, red).is_some());
+    assert_eq!(doc.resolved.borrow().len(), 1);
+    assert!(Rc::ptr_eq(&resolved, &doc.resolve_glyph(GlyphId(1)).unwrap()));
+  }
+
+  #[test]
+  fn evict_filled_glyphs() {
+    let mut db = FontDB::default();
+    let face = db
+      .face_data_or_insert(db.default_fonts()[0])
+      .unwrap();
+    let face = face.as_rb_face();
+    let content = r##"
+        <svg xmlns="http://www.w3.org/2000/svg" version="1.1">
+          <path id="glyph1" d="M0,0 L100,0 L100,-100 Z" fill="currentColor"></path>
+        </svg>"##;
+    let mut cache = SvgGlyphCache::default();
+    cache
+      .svg_docs
+      .insert(SvgDocument::new(GlyphId(1)..=GlyphId(1), content.as_bytes()));
+
+    let fill = |i: usize| Some(Color::from_u32(i as u32));
+    for i in 0..MAX_FILLED_GLYPHS {
+      cache.svg_or_insert(GlyphId(1), face, fill(i));
+    }
+    // Use the first one again, so the second one is the least recently used.
+    cache.svg_or_insert(GlyphId(1), face, fill(0));
+    cache.svg_or_insert(GlyphId(1), face, fill(MAX_FILLED_GLYPHS));
+
+    assert_eq!(cache.filled_glyphs.len(), MAX_FILLED_GLYPHS);
+    assert_eq!(cache.lru.len(), MAX_FILLED_GLYPHS);
+    assert!(cache.filled_glyphs.contains_key(&(GlyphId(1), 0)));
+    assert!(!cache.filled_glyphs.contains_key(&(GlyphId(1), 1)));
+  }
+
+  #[test]
+  fn fill_without_current_color() {
+    let mut db = FontDB::default();
+    let face = db
+      .face_data_or_insert(db.default_fonts()[0])
+      .unwrap();
+    let face = face.as_rb_face();
+    let content = r##"
+        <svg xmlns="http://www.w3.org/2000/svg" version="1.1">
+          <path id="glyph1" d="M0,0 L100,0 L100,-100 Z" fill="#000000"></path>
+        </svg>"##;
+    let mut cache = SvgGlyphCache::default();
+    cache
+      .svg_docs
+      .insert(SvgDocument::new(GlyphId(1)..=GlyphId(1), content.as_bytes()));
+
+    let red = Some(Color::from_u32(0xFF0000FF));
+    assert!(cache.svg_or_insert(GlyphId(1), face, red).is_some());
+    assert!(cache.filled_glyphs.is_empty());
+    assert_eq!(cache.svg_glyphs.len(), 1);
+  }
 }
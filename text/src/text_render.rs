@@ -1,9 +1,11 @@
-use std::{cell::RefCell, rc::Rc};
+use std::{borrow::Cow, cell::RefCell, rc::Rc};
 
-use ribir_geom::{Rect, Size};
-use ribir_painter::{Brush, Painter, Path, PathStyle};
+use ribir_geom::{Point, Rect, Size, Transform};
+use ribir_painter::{
+  color::LinearGradient, Brush, GradientStop, Painter, Path, PathStyle, SpreadMethod,
+};
 
-use crate::{font_db::FontDB, Em, FontFace, FontSize, GlyphBound, Pixel, VisualGlyphs};
+use crate::{font_db::FontDB, Em, FontFace, FontFamily, FontSize, GlyphBound, Pixel, VisualGlyphs};
 
 /// Encapsulates the text style for painting.
 #[derive(Clone, Debug, PartialEq)]
@@ -18,6 +20,9 @@ pub struct TextStyle {
   /// The factor use to multiplied by the font size to specify the text line
   /// height.
   pub line_height: Option<Em>,
+  /// The font families to try in order when a glyph is missing from the
+  /// `font_face`, before the default fonts of the system.
+  pub fallback_fonts: Vec<FontFamily>,
 }
 
 impl Default for TextStyle {
@@ -27,6 +32,26 @@ impl Default for TextStyle {
       font_face: Default::default(),
       letter_space: None,
       line_height: None,
+      fallback_fonts: vec![],
+    }
+  }
+}
+
+impl TextStyle {
+  /// The font face to shape the text, its families are followed by the
+  /// `fallback_fonts`.
+  pub fn fallback_face(&self) -> Cow<FontFace> {
+    if self.fallback_fonts.is_empty() {
+      Cow::Borrowed(&self.font_face)
+    } else {
+      let families = self
+        .font_face
+        .families
+        .iter()
+        .chain(self.fallback_fonts.iter())
+        .cloned()
+        .collect();
+      Cow::Owned(FontFace { families, ..self.font_face.clone() })
     }
   }
 }
@@ -55,55 +80,366 @@ pub fn draw_glyphs_in_rect(
   );
 }
 
+/// Draw the text glyphs within the box_rect like [`draw_glyphs_in_rect`], but
+/// if the text is truncated or overflows the box_rect, the end of the last line
+/// fades out in the `fade_width`.
+///
+/// A color brush fades by a linear gradient, the other brushes and the color
+/// glyphs like emoji fade glyph by glyph.
+#[allow(clippy::too_many_arguments)]
+pub fn draw_faded_glyphs_in_rect(
+  painter: &mut Painter, visual_glyphs: VisualGlyphs, box_rect: Rect, brush: Brush, font_size: f32,
+  path_style: &PathStyle, font_db: Rc<RefCell<FontDB>>, fade_width: f32,
+) {
+  let visual_rect = visual_glyphs.visual_rect();
+  let rows = visual_glyphs.glyph_row_count();
+  let overflow = visual_glyphs.is_over_bounds() || !box_rect.contains_rect(&visual_rect);
+  let last_line = rows
+    .checked_sub(1)
+    .map(|row| {
+      visual_glyphs
+        .line_rect(row)
+        .translate(visual_rect.origin.to_vector())
+    })
+    .and_then(|line| line.intersection(&box_rect));
+  let (Some(line), true) = (last_line, overflow) else {
+    draw_glyphs_in_rect(painter, visual_glyphs, box_rect, brush, font_size, path_style, font_db);
+    return;
+  };
+  let Some(paint_rect) = painter.intersection_paint_bounds(&box_rect) else {
+    return;
+  };
+
+  // The end of a right-to-left line is at the left.
+  let is_rtl = visual_glyphs.is_rtl_row(rows - 1);
+  let fade_width = fade_width.min(line.width());
+  let fade_x = if is_rtl { line.min_x() } else { line.max_x() - fade_width };
+  let fade = Rect::new(Point::new(fade_x, line.min_y()), Size::new(fade_width, line.height()));
+  let rest_height = box_rect.max_y() - fade.min_y();
+  let above =
+    Rect::new(box_rect.origin, Size::new(box_rect.width(), fade.min_y() - box_rect.min_y()));
+  let rest = if is_rtl {
+    Rect::new(
+      Point::new(fade.max_x(), fade.min_y()),
+      Size::new(box_rect.max_x() - fade.max_x(), rest_height),
+    )
+  } else {
+    Rect::new(
+      Point::new(box_rect.min_x(), fade.min_y()),
+      Size::new(fade.min_x() - box_rect.min_x(), rest_height),
+    )
+  };
+
+  let offset = visual_rect.origin.to_vector();
+  for rect in [above, rest] {
+    let Some(rect) = rect.intersection(&paint_rect) else { continue };
+    let mut painter = painter.save_guard();
+    painter
+      .clip(Path::rect(&rect))
+      .translate(offset.x, offset.y);
+    draw_glyphs(
+      &mut painter,
+      visual_glyphs.glyph_bounds_in_rect(&rect),
+      brush.clone(),
+      font_size,
+      path_style,
+      font_db.clone(),
+    );
+  }
+
+  let Some(fade_clip) = fade.intersection(&paint_rect) else { return };
+  let mut painter = painter.save_guard();
+  painter
+    .clip(Path::rect(&fade_clip))
+    .translate(offset.x, offset.y);
+  // The fade from the opaque `from` to the transparent `to`, in the glyphs axis.
+  let fade = fade.translate(-offset);
+  let (from, to) = if is_rtl {
+    (Point::new(fade.max_x(), fade.min_y()), fade.origin)
+  } else {
+    (fade.origin, Point::new(fade.max_x(), fade.min_y()))
+  };
+  let font_db = font_db.borrow();
+  visual_glyphs
+    .glyph_bounds_in_rect(&fade_clip)
+    .for_each(|g| {
+      let Some(face) = font_db.try_get_face_data(g.face_id) else { return };
+      let mut painter = painter.save_guard();
+      let brush = match &brush {
+        Brush::Color(color) if face.outline_glyph(g.glyph_id, path_style).is_some() => {
+          // The gradient is in the axis of the glyph outline.
+          let to_glyph = outline_transform(face.units_per_em() as f32, font_size)
+            .then_translate(g.bound.origin.to_vector())
+            .inverse()
+            .unwrap_or_default();
+          Brush::LinearGradient(LinearGradient {
+            start: to_glyph.transform_point(from),
+            end: to_glyph.transform_point(to),
+            stops: vec![
+              GradientStop::new(*color, 0.),
+              GradientStop::new(color.apply_alpha(0.), 1.),
+            ],
+            spread_method: SpreadMethod::Pad,
+          })
+        }
+        _ => {
+          let center = g.bound.center();
+          let x = if is_rtl { fade.max_x() - center.x } else { center.x - fade.min_x() };
+          painter.apply_alpha(1. - (x / fade.width()).clamp(0., 1.));
+          brush.clone()
+        }
+      };
+      draw_glyph(&mut painter, &g, &Transform::identity(), &brush, font_size, path_style, &font_db);
+    });
+}
+
+/// The transform from the outline of a glyph to its bound, the outline is in
+/// font units with the y axis upward.
+fn outline_transform(units_per_em: f32, font_size: f32) -> Transform {
+  let scale = font_size / units_per_em;
+  Transform::translation(0., -units_per_em).then_scale(scale, -scale)
+}
+
 /// draw the glyphs with the given brush, font_size and path style
 pub fn draw_glyphs(
   painter: &mut Painter, glyphs: impl Iterator<Item = GlyphBound>, brush: Brush, font_size: f32,
   path_style: &PathStyle, font_db: Rc<RefCell<FontDB>>,
 ) {
+  let font_db = font_db.borrow();
   glyphs.for_each(|g| {
-    let font_db = font_db.borrow();
-    let face = font_db.try_get_face_data(g.face_id);
-
-    if let Some(face) = face {
-      let unit = face.units_per_em() as f32;
-      let scale = font_size / unit;
-      if let Some(path) = face.outline_glyph(g.glyph_id, path_style) {
-        let mut painter = painter.save_guard();
-        painter
-          .translate(g.bound.min_x(), g.bound.min_y())
-          .scale(scale, -scale)
-          .translate(0., -unit);
-
-        painter.set_brush(brush.clone()).fill_path(path);
-      } else if let Some(svg) = face.glyph_svg_image(g.glyph_id) {
-        let mut painter = painter.save_guard();
-
-        let grid_scale = face
-          .vertical_height()
-          .map(|h| h as f32 / face.units_per_em() as f32)
-          .unwrap_or(1.)
-          .max(1.);
-        let size = svg.size;
-        let bound_size = g.bound.size;
-        let scale =
-          (bound_size.width / size.width).min(bound_size.height / size.height) / grid_scale;
-        painter
-          .translate(g.bound.min_x(), g.bound.min_y())
-          .scale(scale, scale)
-          .draw_svg(&svg);
-      } else if let Some(img) = face.glyph_raster_image(g.glyph_id, (unit / font_size) as u16) {
-        let m_width = img.width() as f32;
-        let m_height = img.height() as f32;
-        let scale = (g.bound.width() / m_width).min(g.bound.height() / m_height);
-
-        let x_offset = g.bound.min_x() + (g.bound.width() - (m_width * scale)) / 2.;
-        let y_offset = g.bound.min_y() + (g.bound.height() - (m_height * scale)) / 2.;
-        let mut painter = painter.save_guard();
-        painter
-          .translate(x_offset, y_offset)
-          .scale(scale, scale)
-          .draw_img(img, &Rect::from_size(Size::new(m_width, m_height)), &None);
-      }
-    }
+    draw_glyph(painter, &g, &Transform::identity(), &brush, font_size, path_style, &font_db);
   });
 }
+
+/// How to paint a glyph of a text run, see [`draw_text_run`].
+#[derive(Debug, Clone)]
+pub struct GlyphPaint {
+  /// The transform applied to the glyph, its origin is the top-left of the
+  /// glyph bound.
+  pub transform: Transform,
+  /// The brush to fill the glyph, the color glyphs like emoji keep their own
+  /// colors.
+  pub brush: Brush,
+}
+
+/// Draw the glyphs of a text run like [`draw_glyphs`], but every glyph can be
+/// painted with its own transform and brush by the `adjust` callback, which is
+/// called with the index of the glyph in the run and its bound. It's useful to
+/// animate the text letter by letter, e.g. a wavy or bouncing text.
+pub fn draw_text_run(
+  painter: &mut Painter, glyphs: impl Iterator<Item = GlyphBound>, brush: Brush, font_size: f32,
+  path_style: &PathStyle, font_db: Rc<RefCell<FontDB>>,
+  mut adjust: impl FnMut(usize, &GlyphBound, &mut GlyphPaint),
+) {
+  let font_db = font_db.borrow();
+  glyphs.enumerate().for_each(|(idx, g)| {
+    let mut paint = GlyphPaint { transform: Transform::identity(), brush: brush.clone() };
+    adjust(idx, &g, &mut paint);
+    draw_glyph(painter, &g, &paint.transform, &paint.brush, font_size, path_style, &font_db);
+  });
+}
+
+/// Draw a glyph in its bound with the `transform` applied, the origin of the
+/// `transform` is the top-left of the bound.
+///
+/// The `brush` fills the outline glyph and the `currentColor` of the svg glyph,
+/// and the color glyphs keep their own colors.
+pub fn draw_glyph(
+  painter: &mut Painter, glyph: &GlyphBound, transform: &Transform, brush: &Brush, font_size: f32,
+  path_style: &PathStyle, font_db: &FontDB,
+) {
+  let Some(face) = font_db.try_get_face_data(glyph.face_id) else { return };
+  let mut painter = painter.save_guard();
+  painter
+    .translate(glyph.bound.min_x(), glyph.bound.min_y())
+    .apply_transform(transform);
+
+  let unit = face.units_per_em() as f32;
+  // The `currentColor` of the svg glyph is the text color.
+  let override_fill = match brush {
+    Brush::Color(color) => Some(*color),
+    _ => None,
+  };
+  let bound_size = glyph.bound.size;
+  if let Some(path) = face.outline_glyph(glyph.glyph_id, path_style) {
+    painter
+      .apply_transform(&outline_transform(unit, font_size))
+      .set_brush(brush.clone())
+      .fill_path(path);
+  } else if let Some(svg) = face.glyph_svg_image(glyph.glyph_id, override_fill) {
+    let grid_scale = face
+      .vertical_height()
+      .map(|h| h as f32 / face.units_per_em() as f32)
+      .unwrap_or(1.)
+      .max(1.);
+    let size = svg.size;
+    let scale = (bound_size.width / size.width).min(bound_size.height / size.height) / grid_scale;
+    painter.scale(scale, scale).draw_svg(&svg);
+  } else if let Some(img) = face.glyph_raster_image(glyph.glyph_id, (unit / font_size) as u16) {
+    let m_width = img.width() as f32;
+    let m_height = img.height() as f32;
+    let scale = (bound_size.width / m_width).min(bound_size.height / m_height);
+
+    let x_offset = (bound_size.width - (m_width * scale)) / 2.;
+    let y_offset = (bound_size.height - (m_height * scale)) / 2.;
+    painter
+      .translate(x_offset, y_offset)
+      .scale(scale, scale)
+      .draw_img(img, &Rect::from_size(Size::new(m_width, m_height)), &None);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use ribir_geom::Vector;
+  use ribir_painter::{Color, PaintCommand, PaintPathAction, PathCommand};
+
+  use super::*;
+  use crate::{
+    shaper::TextShaper,
+    typography::{PlaceLineDirection, TypographyCfg},
+    Overflow, TextAlign, TextOverflow, TypographyStore,
+  };
+
+  fn glyphs_of(text: &str) -> (VisualGlyphs, Rc<RefCell<FontDB>>) {
+    let font_db = Rc::new(RefCell::new(FontDB::default()));
+    let path = env!("CARGO_MANIFEST_DIR").to_owned() + "/../fonts/DejaVuSans.ttf";
+    let _ = font_db.borrow_mut().load_font_file(path);
+    let shaper = TextShaper::new(font_db.clone());
+    let store = TypographyStore::new(<_>::default(), font_db.clone(), shaper);
+    let face =
+      FontFace { families: Box::new([FontFamily::Name("DejaVu Sans".into())]), ..<_>::default() };
+    let cfg = TypographyCfg {
+      letter_space: None,
+      text_align: TextAlign::Start,
+      line_height: None,
+      bounds: (Em::MAX, Em::MAX).into(),
+      line_dir: PlaceLineDirection::TopToBottom,
+      overflow: Overflow::Clip,
+      max_lines: None,
+      text_overflow: TextOverflow::Clip,
+    };
+    let visual = store.typography(text.to_owned().into(), FontSize::Pixel(20.0.into()), &face, cfg);
+    (visual, font_db)
+  }
+
+  fn glyph_paths(painter: &mut Painter) -> Vec<(Rect, Option<Color>)> {
+    painter
+      .finish()
+      .iter()
+      .filter_map(|cmd| match cmd {
+        PaintCommand::Path(PathCommand { paint_bounds, action, .. }) => {
+          let color = match action {
+            PaintPathAction::Color(c) => Some(*c),
+            _ => None,
+          };
+          Some((*paint_bounds, color))
+        }
+        _ => None,
+      })
+      .collect()
+  }
+
+  #[test]
+  fn text_run_glyph_transform() {
+    let (visual, font_db) = glyphs_of("ab");
+    let rect = visual.visual_rect();
+    let viewport = Rect::from_size(Size::new(200., 200.));
+    let style = PathStyle::Fill;
+
+    let mut painter = Painter::new(viewport);
+    let brush = Brush::Color(Color::BLACK);
+    draw_glyphs(
+      &mut painter,
+      visual.glyph_bounds_in_rect(&rect),
+      brush.clone(),
+      20.,
+      &style,
+      font_db.clone(),
+    );
+    let expected = glyph_paths(&mut painter);
+    assert_eq!(expected.len(), 2);
+
+    let offsets = [Vector::new(0., 10.), Vector::new(5., 30.)];
+    let colors = [Color::RED, Color::BLUE];
+    draw_text_run(
+      &mut painter,
+      visual.glyph_bounds_in_rect(&rect),
+      brush,
+      20.,
+      &style,
+      font_db,
+      |idx, _, paint| {
+        paint.transform = Transform::translation(offsets[idx].x, offsets[idx].y);
+        paint.brush = colors[idx].into();
+      },
+    );
+    let painted = glyph_paths(&mut painter);
+    assert_eq!(painted.len(), 2);
+    for (idx, ((bounds, color), (base, _))) in painted.iter().zip(&expected).enumerate() {
+      let moved = bounds.origin - base.origin;
+      assert!((moved - offsets[idx]).length() < 1e-3);
+      assert_eq!(bounds.size, base.size);
+      assert_eq!(*color, Some(colors[idx]));
+    }
+  }
+
+  #[test]
+  fn text_run_color_glyph() {
+    let (visual, font_db) = glyphs_of("a b");
+    let rect = visual.visual_rect();
+    let viewport = Rect::from_size(Size::new(200., 200.));
+    let style = PathStyle::Fill;
+
+    // The space has no outline, paint it by a green svg glyph.
+    let space = visual.glyph_bounds_in_rect(&rect).nth(1).unwrap();
+    let svg = format!(
+      r##"<svg xmlns="http://www.w3.org/2000/svg" version="1.1">
+        <path id="glyph{}" d="M0,0 L1000,0 L1000,-1000 Z" fill="#00FF00"></path>
+      </svg>"##,
+      space.glyph_id.0
+    );
+    font_db
+      .borrow()
+      .try_get_face_data(space.face_id)
+      .unwrap()
+      .insert_svg_document(space.glyph_id..=space.glyph_id, &svg);
+
+    let mut painter = Painter::new(viewport);
+    let brush = Brush::Color(Color::BLACK);
+    draw_glyphs(
+      &mut painter,
+      visual.glyph_bounds_in_rect(&rect),
+      brush.clone(),
+      20.,
+      &style,
+      font_db.clone(),
+    );
+    let expected = glyph_paths(&mut painter);
+    assert_eq!(expected.len(), 3);
+
+    let offsets = [Vector::new(0., 10.), Vector::new(5., 30.), Vector::new(8., 0.)];
+    draw_text_run(
+      &mut painter,
+      visual.glyph_bounds_in_rect(&rect),
+      brush,
+      20.,
+      &style,
+      font_db,
+      |idx, _, paint| {
+        paint.transform = Transform::translation(offsets[idx].x, offsets[idx].y);
+        paint.brush = Color::RED.into();
+      },
+    );
+    let painted = glyph_paths(&mut painter);
+    assert_eq!(painted.len(), 3);
+    for (idx, ((bounds, _), (base, _))) in painted.iter().zip(&expected).enumerate() {
+      let moved = bounds.origin - base.origin;
+      assert!((moved - offsets[idx]).length() < 1e-3);
+      assert_eq!(bounds.size, base.size);
+    }
+    // The color glyph is moved with the others, but keeps its own color.
+    let colors: Vec<_> = painted.iter().map(|(_, c)| *c).collect();
+    assert_eq!(colors, [Some(Color::RED), Some(Color::from_rgb(0, 255, 0)), Some(Color::RED)]);
+  }
+}
@@ -16,6 +16,25 @@ pub struct ReorderResult {
   pub paras: Vec<Paragraph>,
 }
 
+impl ReorderResult {
+  /// Whether the base direction of the paragraph at `idx` is right-to-left,
+  /// it's decided by the first strong character of the paragraph.
+  pub fn is_rtl_paragraph(&self, idx: usize) -> bool {
+    let Some(p) = self.paras.get(idx) else { return false };
+    self
+      .original_classes
+      .get(p.range.clone())
+      .into_iter()
+      .flatten()
+      .find_map(|c| match c {
+        BidiClass::L => Some(false),
+        BidiClass::R | BidiClass::AL => Some(true),
+        _ => None,
+      })
+      .unwrap_or(false)
+  }
+}
+
 // unnecessary cache
 #[derive(Clone, Default)]
 pub struct TextReorder {
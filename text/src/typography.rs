@@ -17,6 +17,25 @@ impl Overflow {
   fn is_auto_wrap(&self) -> bool { matches!(self, Overflow::AutoWrap) }
 }
 
+/// How to show the end of a text that is truncated by the `max_lines` or
+/// overflows its line.
+///
+/// A word longer than a line is broken in the `AutoWrap` mode, so only the
+/// last line can overflow, and in the `Clip` mode the word is ellipsized or
+/// faded in the middle.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default, Debug)]
+pub enum TextOverflow {
+  /// Clip the text at the bounds.
+  #[default]
+  Clip,
+  /// Truncate the last line at a grapheme boundary and append an ellipsis
+  /// "…" within the bounds. The ellipsis is at the left of a right-to-left
+  /// line.
+  Ellipsis,
+  /// Fade out the end of the last line at the clipped edge.
+  Fade,
+}
+
 #[derive(Clone, Copy, PartialEq, Eq, Hash)]
 pub enum PlaceLineDirection {
   /// place the line from left to right
@@ -39,6 +58,9 @@ pub struct TypographyCfg {
   pub bounds: Size<Em>,
   pub line_dir: PlaceLineDirection,
   pub overflow: Overflow,
+  /// The max count of the lines, the lines after it are dropped.
+  pub max_lines: Option<usize>,
+  pub text_overflow: TextOverflow,
 }
 
 /// Trait control how to place glyph inline.
@@ -65,6 +87,8 @@ pub struct VisualLine {
   pub y: Em,
   pub height: Em,
   pub width: Em,
+  /// Whether the line is in a right-to-left paragraph.
+  pub is_rtl: bool,
   /// The glyph position is relative the line x/y
   pub glyphs: Vec<Glyph<Em>>,
 }
@@ -82,11 +106,17 @@ pub struct VisualInfos {
 /// Typography the glyphs in a bounds.
 pub struct TypographyMan<Inputs> {
   cfg: TypographyCfg,
+  /// The glyphs of the ellipsis relative to 1em, used in the `Ellipsis` mode.
+  ellipsis: Vec<Glyph<Em>>,
+  is_rtl: bool,
   /// Not directly use text as inputs, but accept glyphs after text shape
   /// because both simple text and rich text can custom compose its glyph runs
   /// by text reorder result and its style .
   inputs: Inputs,
   inline_cursor: Em,
+  /// Where the whitespace over the line bound starts, it hangs at the end of
+  /// the line and is not counted in the line extent.
+  hang_start: Option<Em>,
   visual_lines: Vec<VisualLine>,
   over_bounds: bool,
 }
@@ -98,13 +128,29 @@ where
   Runs::Item: InputRun,
 {
   pub fn new(inputs: Inputs, cfg: TypographyCfg) -> Self {
-    Self { cfg, inputs, inline_cursor: Em::ZERO, visual_lines: vec![], over_bounds: false }
+    Self {
+      cfg,
+      ellipsis: vec![],
+      is_rtl: false,
+      inputs,
+      inline_cursor: Em::ZERO,
+      hang_start: None,
+      visual_lines: vec![],
+      over_bounds: false,
+    }
+  }
+
+  /// Set the glyphs of the ellipsis, they should be shaped in 1em.
+  pub fn with_ellipsis(mut self, ellipsis: Vec<Glyph<Em>>) -> Self {
+    self.ellipsis = ellipsis;
+    self
   }
 
   pub fn typography_all(mut self) -> VisualInfos {
     while let Some(p) = self.inputs.next() {
       self.consume_paragraph(p);
     }
+    self.truncate_lines();
 
     if self.cfg.line_dir.is_reverse() {
       self.visual_lines.reverse();
@@ -177,6 +223,7 @@ where
 
   /// consume paragraph and return if early break because over boundary.
   fn consume_paragraph(&mut self, p: InputParagraph<Runs>) -> bool {
+    self.is_rtl = p.is_rtl;
     self.begin_line();
 
     if self.cfg.line_dir.is_horizontal() {
@@ -276,13 +323,21 @@ where
         let width = word
           .iter()
           .fold(Em::ZERO, |acc, glyph| acc + cursor.measure(glyph, text));
-        (width, word)
+        let is_space = word.iter().all(|g| {
+          text[g.cluster as usize..]
+            .chars()
+            .next()
+            .map_or(false, char::is_whitespace)
+        });
+        (width, word, is_space)
       })
       .collect::<Vec<_>>();
 
     (verify_line_height)(self);
-    for (width, word) in words {
+    for (width, word, is_space) in words {
+      // The whitespace never wraps, it hangs at the end of the line.
       if is_auto_wrap
+        && !is_space
         && self.inline_cursor != Em::ZERO
         && self.is_over_line_bound(width + self.inline_cursor)
       {
@@ -300,8 +355,16 @@ where
 
         if self.inline_cursor == Em::ZERO
           || !is_auto_wrap
+          || is_space
           || !self.is_over_line_bound(cursor.position())
         {
+          if is_auto_wrap
+            && is_space
+            && self.hang_start.is_none()
+            && self.is_over_line_bound(cursor.position())
+          {
+            self.hang_start = Some(self.inline_cursor);
+          }
           self.push_glyph(at);
           self.inline_cursor = cursor.position();
           word.next();
@@ -317,24 +380,114 @@ where
     line.unwrap().glyphs.push(g)
   }
 
-  fn begin_line(&mut self) { self.visual_lines.push(<_>::default()); }
+  fn begin_line(&mut self) {
+    self
+      .visual_lines
+      .push(VisualLine { is_rtl: self.is_rtl, ..<_>::default() });
+  }
+
+  /// Drop the lines over the `max_lines`, and in the `Ellipsis` mode, replace
+  /// the end of the last line with the ellipsis if the text is truncated or
+  /// the line overflows.
+  fn truncate_lines(&mut self) {
+    let mut truncated = false;
+    if let Some(max_lines) = self.cfg.max_lines {
+      if self.visual_lines.len() > max_lines {
+        self.visual_lines.truncate(max_lines);
+        self.over_bounds = true;
+        truncated = true;
+      }
+    }
+
+    // Only the ellipsis of the horizontal lines is supported.
+    if self.cfg.text_overflow != TextOverflow::Ellipsis || self.cfg.line_dir.is_horizontal() {
+      return;
+    }
+    let bound = self.cfg.bounds.width;
+    let Some(line) = self.visual_lines.last_mut() else { return };
+    let eps: Em = Em(0.00001_f32);
+    if !truncated && line.width <= bound + eps {
+      return;
+    }
+
+    let ellipsis_width = self
+      .ellipsis
+      .iter()
+      .fold(Em::ZERO, |acc, g| acc + g.x_advance);
+    let glyphs = &mut line.glyphs;
+    let place_ellipsis = |x: Em, cluster: u32| {
+      let mut x = x;
+      self.ellipsis.iter().map(move |g| {
+        let mut g = g.clone();
+        g.x_offset += x;
+        g.cluster = cluster;
+        x += g.x_advance;
+        g
+      })
+    };
+    // The glyphs of a line are placed from left to right, and the line is not
+    // cut in a grapheme.
+    if line.is_rtl {
+      // The end of a right-to-left line is at the left.
+      let right = glyphs
+        .last()
+        .map_or(Em::ZERO, |g| g.x_offset + g.x_advance);
+      let mut start = glyphs.partition_point(|g| right - g.x_offset + ellipsis_width > bound + eps);
+      while in_grapheme(glyphs, start) {
+        start += 1;
+      }
+      let cluster = glyphs[..start]
+        .last()
+        .or(glyphs.first())
+        .map_or(0, |g| g.cluster);
+      glyphs.drain(..start);
+      let left = glyphs.first().map_or(right, |g| g.x_offset);
+      glyphs
+        .iter_mut()
+        .for_each(|g| g.x_offset = g.x_offset - left + ellipsis_width);
+      let rest = std::mem::take(glyphs);
+      glyphs.extend(place_ellipsis(Em::ZERO, cluster));
+      glyphs.extend(rest);
+      line.width = ellipsis_width + right - left;
+    } else {
+      let mut end =
+        glyphs.partition_point(|g| g.x_offset + g.x_advance + ellipsis_width <= bound + eps);
+      while in_grapheme(glyphs, end) {
+        end -= 1;
+      }
+      let cluster = glyphs
+        .get(end)
+        .or(glyphs.last())
+        .map_or(0, |g| g.cluster);
+      glyphs.truncate(end);
+      let x = glyphs
+        .last()
+        .map_or(Em::ZERO, |g| g.x_offset + g.x_advance);
+      glyphs.extend(place_ellipsis(x, cluster));
+      line.width = x + ellipsis_width;
+    }
+  }
 
   fn end_line(&mut self) {
+    let extent = self
+      .hang_start
+      .take()
+      .unwrap_or(self.inline_cursor);
     let line = self.visual_lines.last_mut().unwrap();
     // we will reorder the line after consumed all inputs.
     if self.cfg.line_dir.is_horizontal() {
-      line.height = self.inline_cursor;
+      line.height = extent;
       if let Some(line_height) = self.cfg.line_height {
         line.width = line_height;
       }
     } else {
-      line.width = self.inline_cursor;
+      line.width = extent;
       if let Some(line_height) = self.cfg.line_height {
         line.height = line_height;
       }
     }
 
-    self.over_bounds |= self.is_over_line_bound(self.inline_cursor);
+    self.over_bounds |= self.is_over_line_bound(extent);
     self.over_bounds |= self.is_last_line_over();
     self.inline_cursor = Em::ZERO;
   }
@@ -371,6 +524,8 @@ where
 
 pub struct InputParagraph<Runs> {
   pub runs: Runs,
+  /// Whether the base direction of the paragraph is right-to-left.
+  pub is_rtl: bool,
 }
 
 pub trait InputRun {
@@ -496,6 +651,12 @@ pub(crate) fn text_align_offset(
   }
 }
 
+/// Whether the position `at` is in a grapheme, the glyphs of a grapheme have
+/// the same cluster.
+fn in_grapheme(glyphs: &[Glyph<Em>], at: usize) -> bool {
+  0 < at && at < glyphs.len() && glyphs[at].cluster == glyphs[at - 1].cluster
+}
+
 /// Check if a char support apply letter spacing.
 fn letter_spacing_char(c: char) -> bool {
   let script = c.script();
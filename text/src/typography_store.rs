@@ -1,5 +1,5 @@
 use std::{
-  cell::RefCell,
+  cell::{Cell, RefCell},
   ops::Range,
   rc::Rc,
   sync::{Arc, RwLock},
@@ -13,10 +13,11 @@ use crate::{
   shaper::{ShapeResult, TextShaper, NEWLINE_GLYPH_ID},
   text_reorder::ReorderResult,
   typography::{
-    text_align_offset, InputParagraph, InputRun, Overflow, PlaceLineDirection, TypographyCfg,
-    TypographyMan, VisualInfos,
+    text_align_offset, InputParagraph, InputRun, Overflow, PlaceLineDirection, TextOverflow,
+    TypographyCfg, TypographyMan, VisualInfos,
   },
   Em, FontFace, FontSize, Glyph, GlyphBound, Pixel, TextAlign, TextDirection, TextReorder,
+  TextStyle,
 };
 
 /// Typography `text` relative to 1em.
@@ -30,6 +31,8 @@ pub struct TypographyKey {
   pub text_align: TextAlign,
   pub line_dir: PlaceLineDirection,
   pub overflow: Overflow,
+  pub max_lines: Option<usize>,
+  pub text_overflow: TextOverflow,
   pub text: Substr,
 }
 
@@ -45,6 +48,8 @@ pub struct TypographyStore {
   shaper: TextShaper,
   font_db: Rc<RefCell<FontDB>>,
   cache: Arc<RwLock<FrameCache<TypographyKey, TypographyResult>>>,
+  /// The faces revision of the font database that the cache laid out by.
+  faces_revision: Rc<Cell<usize>>,
 }
 pub struct VisualGlyphs {
   scale: f32,
@@ -77,6 +82,22 @@ impl VisualGlyphs {
   }
 }
 
+/// The size and the lines of a text measured by
+/// [`TypographyStore::measure_text`], in logical pixels.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TextMetrics {
+  /// The width of the widest line, the trailing whitespace of a line is not
+  /// counted.
+  pub width: f32,
+  pub height: f32,
+  /// The baseline of every line, relative to the top of the text.
+  pub baselines: Vec<f32>,
+}
+
+impl TextMetrics {
+  pub fn line_count(&self) -> usize { self.baselines.len() }
+}
+
 struct ShapeRun {
   shape_result: Rc<ShapeResult>,
   font_size: FontSize,
@@ -86,7 +107,8 @@ struct ShapeRun {
 
 impl TypographyStore {
   pub fn new(reorder: TextReorder, font_db: Rc<RefCell<FontDB>>, shaper: TextShaper) -> Self {
-    TypographyStore { reorder, shaper, font_db, cache: <_>::default() }
+    let faces_revision = Rc::new(Cell::new(font_db.borrow().faces_revision()));
+    TypographyStore { reorder, shaper, font_db, cache: <_>::default(), faces_revision }
   }
 
   pub fn end_frame(&self) {
@@ -97,6 +119,9 @@ impl TypographyStore {
       .end_frame("Typography");
   }
 
+  /// Drop all the cached typography results.
+  pub fn clear_cache(&self) { self.cache.write().unwrap().clear(); }
+
   pub fn typography(
     &self, text: Substr, font_size: FontSize, face: &FontFace, cfg: TypographyCfg,
   ) -> VisualGlyphs {
@@ -118,7 +143,7 @@ impl TypographyStore {
 
     let info = self.reorder.reorder_text(&input.text);
     let ids = self.font_db.borrow_mut().select_all_match(face);
-    let inputs = info.paras.iter().map(|p| {
+    let inputs = info.paras.iter().enumerate().map(|(idx, p)| {
       let runs = p.runs.iter().map(|r| {
         let dir = if r.is_empty() || p.levels[r.start].is_ltr() {
           TextDirection::LeftToRight
@@ -138,7 +163,7 @@ impl TypographyStore {
         }
       });
 
-      InputParagraph { runs }
+      InputParagraph { runs, is_rtl: info.is_rtl_paragraph(idx) }
     });
 
     let t_cfg = TypographyCfg {
@@ -148,8 +173,16 @@ impl TypographyStore {
       bounds,
       line_dir: input.line_dir,
       overflow: input.overflow,
+      max_lines: input.max_lines,
+      text_overflow: input.text_overflow,
     };
-    let t_man = TypographyMan::new(inputs, t_cfg);
+    let mut t_man = TypographyMan::new(inputs, t_cfg);
+    if input.text_overflow == TextOverflow::Ellipsis {
+      let ellipsis = self
+        .shaper
+        .shape_text(&"…".into(), &ids, TextDirection::LeftToRight);
+      t_man = t_man.with_ellipsis(ellipsis.glyphs.clone());
+    }
     let visual_info = t_man.typography_all();
     let visual_info = Arc::new(visual_info);
     self
@@ -168,28 +201,89 @@ impl TypographyStore {
     )
   }
 
+  /// Measure the `text` in the `style` without a widget, the lines are wrapped
+  /// at `max_width`. A zero or infinite `max_width` measures the text without
+  /// wrapping. The typography result is cached like the `typography`.
+  pub fn measure_text(&self, text: Substr, style: &TextStyle, max_width: f32) -> TextMetrics {
+    let TextStyle { font_size, letter_space, line_height, .. } = *style;
+    let (width, overflow) = if max_width > 0. && max_width.is_finite() {
+      (Pixel(max_width).into(), Overflow::AutoWrap)
+    } else {
+      (Em::MAX, Overflow::Clip)
+    };
+    let cfg = TypographyCfg {
+      line_height,
+      letter_space,
+      text_align: TextAlign::Start,
+      bounds: (width, Em::MAX).into(),
+      line_dir: PlaceLineDirection::TopToBottom,
+      overflow,
+      max_lines: None,
+      text_overflow: TextOverflow::Clip,
+    };
+    let glyphs = self.typography(text.clone(), font_size, &style.fallback_face(), cfg);
+
+    let lines = &glyphs.visual_info.visual_lines;
+    let width = lines
+      .iter()
+      .map(|l| {
+        // The trailing whitespace hangs at the end of the line.
+        let content = l.glyphs.iter().rev().find(|g| {
+          text
+            .get(g.cluster as usize..)
+            .and_then(|t| t.chars().next())
+            .map_or(false, |c| !c.is_whitespace())
+        });
+        content.map_or(Em::ZERO, |g| g.x_offset + g.x_advance)
+      })
+      .fold(Em::ZERO, |a, b| a.max(b));
+    let baselines = lines
+      .iter()
+      .map(|l| glyphs.to_pixel_value(l.y + Em::absolute(1.)))
+      .collect();
+
+    TextMetrics {
+      width: glyphs.to_pixel_value(width),
+      height: glyphs.to_pixel_value(glyphs.visual_info.visual_height),
+      baselines,
+    }
+  }
+
   pub fn font_db(&self) -> &Rc<RefCell<FontDB>> { &self.font_db }
 
   fn get_from_cache(
     &self, text: Substr, font_size: FontSize, cfg: &TypographyCfg,
   ) -> Option<TypographyResult> {
+    // The cached glyphs may be shaped by an unloaded face.
+    let revision = self.font_db.borrow().faces_revision();
+    if self.faces_revision.replace(revision) != revision {
+      self.clear_cache();
+    }
     let input = Self::key(text, font_size, cfg);
     self.cache.write().unwrap().get(&input).cloned()
   }
 
   fn key(text: Substr, font_size: FontSize, cfg: &TypographyCfg) -> TypographyKey {
     let &TypographyCfg {
-      line_height, text_align, line_dir, overflow, letter_space, bounds, ..
+      line_height,
+      text_align,
+      line_dir,
+      overflow,
+      letter_space,
+      bounds,
+      max_lines,
+      text_overflow,
     } = cfg;
     let line_height = line_height.map(|l| l / font_size.into_em());
     let letter_space = letter_space.map(|l| l / font_size.into_pixel());
 
     let line_width = match overflow {
       // line width is not so important in clip mode, the cache can be use even with difference line
-      // width. The wider one can use for the narrower one. S
-      Overflow::Clip => Em::absolute(f32::MAX),
+      // width. The wider one can use for the narrower one. But the ellipsis is placed by the line
+      // width.
+      Overflow::Clip if text_overflow != TextOverflow::Ellipsis => Em::absolute(f32::MAX),
 
-      Overflow::AutoWrap => {
+      _ => {
         if line_dir.is_horizontal() {
           bounds.height / font_size.into_em()
         } else {
@@ -198,7 +292,17 @@ impl TypographyStore {
       }
     };
 
-    TypographyKey { line_height, line_width, letter_space, text_align, line_dir, overflow, text }
+    TypographyKey {
+      line_height,
+      line_width,
+      letter_space,
+      text_align,
+      line_dir,
+      overflow,
+      max_lines,
+      text_overflow,
+      text,
+    }
   }
 }
 
@@ -553,6 +657,32 @@ impl VisualGlyphs {
   }
 
   pub fn glyph_row_count(&self) -> usize { self.visual_info.visual_lines.len() }
+
+  /// The rect of the line at `row` relative to the visual rect, in pixel.
+  pub fn line_rect(&self, row: usize) -> Rect {
+    self
+      .visual_info
+      .visual_lines
+      .get(row)
+      .map_or(Rect::zero(), |l| {
+        Rect::new(
+          Point::new(self.to_pixel_value(l.x), self.to_pixel_value(l.y)),
+          Size::new(self.to_pixel_value(l.width), self.to_pixel_value(l.height)),
+        )
+      })
+  }
+
+  /// Whether the line at `row` is in a right-to-left paragraph.
+  pub fn is_rtl_row(&self, row: usize) -> bool {
+    self
+      .visual_info
+      .visual_lines
+      .get(row)
+      .map_or(false, |l| l.is_rtl)
+  }
+
+  /// Whether the text is truncated by the `max_lines` or over the bounds.
+  pub fn is_over_bounds(&self) -> bool { self.visual_info.over_bounds }
 }
 
 #[cfg(test)]
@@ -596,6 +726,8 @@ mod tests {
         bounds: (Em::MAX, Em::MAX).into(),
         line_dir: PlaceLineDirection::TopToBottom,
         overflow: Overflow::Clip,
+        max_lines: None,
+        text_overflow: TextOverflow::Clip,
       },
     );
 
@@ -616,6 +748,8 @@ mod tests {
         bounds: (Em::MAX, Em::MAX).into(),
         line_dir: PlaceLineDirection::TopToBottom,
         overflow: Overflow::Clip,
+        max_lines: None,
+        text_overflow: TextOverflow::Clip,
       },
     );
 
@@ -636,6 +770,8 @@ mod tests {
         bounds: (Em::MAX, Em::MAX).into(),
         line_dir: PlaceLineDirection::TopToBottom,
         overflow: Overflow::Clip,
+        max_lines: None,
+        text_overflow: TextOverflow::Clip,
       },
     );
 
@@ -670,6 +806,8 @@ mod tests {
       bounds: (Em::MAX, Em::MAX).into(),
       line_dir: PlaceLineDirection::TopToBottom,
       overflow: Overflow::Clip,
+      max_lines: None,
+      text_overflow: TextOverflow::Clip,
     };
 
     let not_bounds = glyphs(cfg.clone());
@@ -801,6 +939,8 @@ mod tests {
       bounds: (Em::MAX, Em::MAX).into(),
       line_dir: PlaceLineDirection::TopToBottom,
       overflow: Overflow::Clip,
+      max_lines: None,
+      text_overflow: TextOverflow::Clip,
     };
     let text: Substr = "hi!".into();
     let font_size = FontSize::Em(Em::absolute(1.));
@@ -840,6 +980,8 @@ mod tests {
       bounds: (Em::MAX, Em::MAX).into(),
       line_dir: PlaceLineDirection::TopToBottom,
       overflow: Overflow::Clip,
+      max_lines: None,
+      text_overflow: TextOverflow::Clip,
     };
     let text =
       "abcd \u{202e} right_to_left_1 \u{202d} embed \u{202c} right_to_left_2 \u{202c} end".into();
@@ -860,6 +1002,8 @@ mod tests {
       bounds,
       line_dir: PlaceLineDirection::TopToBottom,
       overflow: Overflow::AutoWrap,
+      max_lines: None,
+      text_overflow: TextOverflow::Clip,
     };
     let text = "WITHIN BOUND\rLINE WITH LONG WORD LIKE: ABCDEFGHIJKLMNOPQRSTUVWXYZ, WILL AUTO \
                 WRAP TO 3 LINES."
@@ -899,6 +1043,8 @@ mod tests {
       bounds: Size::new(Em::absolute(10.0), Em::absolute(2.0)),
       line_dir: PlaceLineDirection::TopToBottom,
       overflow: Overflow::Clip,
+      max_lines: None,
+      text_overflow: TextOverflow::Clip,
     };
     let text: Substr = "1234".into();
 
@@ -915,4 +1061,144 @@ mod tests {
     );
     assert_eq!(1, store.cache.read().unwrap().len());
   }
+
+  fn test_style() -> TextStyle {
+    TextStyle { font_size: FontSize::Pixel(14.0.into()), font_face: test_face(), ..<_>::default() }
+  }
+
+  #[test]
+  fn measure_single_line() {
+    let store = test_store();
+    let style = test_style();
+
+    let metrics = store.measure_text("Hello world".into(), &style, 0.);
+    assert_eq!(metrics.line_count(), 1);
+    assert_eq!(metrics.height, 14.);
+    assert_eq!(metrics.baselines, vec![14.]);
+    assert!(metrics.width > 0.);
+
+    // A wider bound not wraps the text.
+    let wider = store.measure_text("Hello world".into(), &style, metrics.width + 10.);
+    assert_eq!(wider, metrics);
+  }
+
+  #[test]
+  fn measure_multi_lines() {
+    let store = test_store();
+    let style = test_style();
+
+    let metrics = store.measure_text("Hello\nworld\n!".into(), &style, 0.);
+    assert_eq!(metrics.line_count(), 3);
+    assert_eq!(metrics.height, 42.);
+    assert_eq!(metrics.baselines, vec![14., 28., 42.]);
+  }
+
+  #[test]
+  fn measure_wrapped_lines() {
+    let store = test_store();
+    let style = test_style();
+
+    let world = store.measure_text("world".into(), &style, 0.);
+    let full = store.measure_text("Hello world".into(), &style, 0.);
+    let wrapped = store.measure_text("Hello world".into(), &style, full.width - 1.);
+    assert_eq!(wrapped.line_count(), 2);
+    assert_eq!(wrapped.height, 28.);
+    // The trailing space of the first line is not counted, so the widest line is
+    // the second one.
+    assert_eq!(wrapped.width, world.width);
+
+    let words = store.measure_text("a b c d".into(), &style, 1.);
+    assert_eq!(words.line_count(), 4);
+  }
+
+  fn truncated_cfg(width: f32, overflow: Overflow, text_overflow: TextOverflow) -> TypographyCfg {
+    TypographyCfg {
+      line_height: None,
+      letter_space: None,
+      text_align: TextAlign::Start,
+      bounds: (Pixel(width).into(), Em::MAX).into(),
+      line_dir: PlaceLineDirection::TopToBottom,
+      overflow,
+      max_lines: Some(2),
+      text_overflow,
+    }
+  }
+
+  fn ellipsis_glyph(store: &TypographyStore) -> u16 {
+    let ids = store
+      .font_db
+      .borrow_mut()
+      .select_all_match(&test_face());
+    let ellipsis = store
+      .shaper
+      .shape_text(&"…".into(), &ids, TextDirection::LeftToRight);
+    ellipsis.glyphs[0].glyph_id.0
+  }
+
+  #[test]
+  fn max_lines() {
+    let store = test_store();
+    let text: Substr = "Hello world, nice to meet you.".into();
+    let font_size = FontSize::Pixel(10.0.into());
+    let mut cfg = truncated_cfg(60., Overflow::AutoWrap, TextOverflow::Clip);
+
+    let glyphs = store.typography(text.clone(), font_size, &test_face(), cfg.clone());
+    assert_eq!(glyphs.glyph_row_count(), 2);
+    assert_eq!(glyphs.visual_rect().height(), 20.);
+    assert!(glyphs.is_over_bounds());
+
+    cfg.max_lines = None;
+    let glyphs = store.typography(text, font_size, &test_face(), cfg);
+    assert!(glyphs.glyph_row_count() > 2);
+  }
+
+  #[test]
+  fn ellipsis_in_width() {
+    let store = test_store();
+    let ellipsis = ellipsis_glyph(&store);
+    let font_size = FontSize::Pixel(10.0.into());
+
+    // The wrapped text is truncated at the last line.
+    let cfg = truncated_cfg(60., Overflow::AutoWrap, TextOverflow::Ellipsis);
+    let text = "Hello world, nice to meet you.".into();
+    let glyphs = store.typography(text, font_size, &test_face(), cfg);
+    assert_eq!(glyphs.glyph_row_count(), 2);
+    assert!(glyphs.line_rect(1).max_x() <= 60.);
+    let last = glyphs.pixel_glyphs().last().unwrap();
+    assert_eq!(last.glyph_id.0, ellipsis);
+    assert!((last.x_offset + last.x_advance).value() <= 60.);
+
+    // A word longer than the line is ellipsized in the middle.
+    let cfg = truncated_cfg(30., Overflow::Clip, TextOverflow::Ellipsis);
+    let text = "Supercalifragilistic".into();
+    let glyphs = store.typography(text, font_size, &test_face(), cfg);
+    assert_eq!(glyphs.glyph_row_count(), 1);
+    assert!(glyphs.visual_rect().width() <= 30.);
+    let last = glyphs.pixel_glyphs().last().unwrap();
+    assert_eq!(last.glyph_id.0, ellipsis);
+
+    // The text fits the width is not ellipsized.
+    let cfg = truncated_cfg(100., Overflow::Clip, TextOverflow::Ellipsis);
+    let glyphs = store.typography("Hello".into(), font_size, &test_face(), cfg);
+    assert!(
+      glyphs
+        .pixel_glyphs()
+        .all(|g| g.glyph_id.0 != ellipsis)
+    );
+  }
+
+  #[test]
+  fn rtl_ellipsis_at_left() {
+    let store = test_store();
+    let ellipsis = ellipsis_glyph(&store);
+    let cfg = truncated_cfg(30., Overflow::Clip, TextOverflow::Ellipsis);
+    let text = "שלום עולם שלום".into();
+    let glyphs = store.typography(text, FontSize::Pixel(10.0.into()), &test_face(), cfg);
+
+    assert!(glyphs.is_rtl_row(0));
+    assert!(glyphs.visual_rect().width() <= 30.);
+    let first = glyphs.pixel_glyphs().next().unwrap();
+    assert_eq!(first.glyph_id.0, ellipsis);
+    assert_eq!(first.x_offset.value(), 0.);
+  }
 }
@@ -28,6 +28,7 @@ fn new(palette: Palette) -> FullTheme {
     transitions_theme: TransitionTheme::default(),
     compose_decorators: <_>::default(),
     custom_styles: <_>::default(),
+    text_direction: TextDirection::LeftToRight,
     font_bytes: Some(vec![
       include_bytes!("./fonts/Roboto-Regular.ttf").to_vec(),
       include_bytes!("./fonts/Roboto-Medium.ttf").to_vec(),
@@ -98,29 +99,33 @@ const ICON_LARGE: Size = Size::new(48., 48.);
 const ICON_HUGE: Size = Size::new(64., 64.);
 
 fn init_custom_style(theme: &mut FullTheme) {
+  // The styles with colors are derived from the palette when they are read, so
+  // they follow the switch of the palette.
+  let typography = theme.typography_theme.clone();
   theme
     .custom_styles
-    .set_custom_style(ScrollBarStyle {
+    .set_palette_style(|palette| ScrollBarStyle {
       thumb_min_size: 12.,
       thickness: 8.,
-      track_brush: theme.palette.primary_container().into(),
+      track_brush: palette.primary_container().into(),
     });
+  let label_style = typography.body_large.text.clone();
   theme
     .custom_styles
-    .set_custom_style(CheckBoxStyle {
+    .set_palette_style(move |palette| CheckBoxStyle {
       icon_size: ICON_SMALL,
-      label_style: theme.typography_theme.body_large.text.clone(),
-      label_color: theme.palette.on_surface().into(),
+      label_style: label_style.clone(),
+      label_color: palette.on_surface().into(),
     });
-  theme
-    .custom_styles
-    .set_custom_style(InputStyle { size: Some(20.) });
   theme
     .custom_styles
     .set_custom_style(TextAreaStyle { rows: Some(2.), cols: Some(20.) });
   theme
     .custom_styles
-    .set_custom_style(SelectedHighLightStyle { brush: Color::from_rgb(181, 215, 254).into() });
+    .set_palette_style(|palette| PreEditUnderlineStyle {
+      brush: palette.on_surface().into(),
+      thickness: 1.,
+    });
   theme
     .custom_styles
     .set_custom_style(FilledButtonStyle {
@@ -163,37 +168,42 @@ fn init_custom_style(theme: &mut FullTheme) {
       radius: FAB_RADIUS,
       padding_style: EdgeInsets::horizontal(BUTTON_PADDING),
     });
-  theme.custom_styles.set_custom_style(TabsStyle {
-    extent_with_both: 64.,
-    extent_only_label: 48.,
-    extent_only_icon: 48.,
-    icon_size: theme.icon_theme.icon_size.small,
-    icon_pos: Position::Top,
-    active_color: theme.palette.primary().into(),
-    foreground: theme.palette.on_surface_variant().into(),
-    label_style: theme.typography_theme.title_small.text.clone(),
-    indicator: IndicatorStyle { extent: 3., measure: Some(INDICATOR_SIZE) },
-  });
+  let icon_size = theme.icon_theme.icon_size.small;
+  let label_style = typography.title_small.text.clone();
+  theme
+    .custom_styles
+    .set_palette_style(move |palette| TabsStyle {
+      extent_with_both: 64.,
+      extent_only_label: 48.,
+      extent_only_icon: 48.,
+      icon_size,
+      icon_pos: Position::Top,
+      active_color: palette.primary().into(),
+      foreground: palette.on_surface_variant().into(),
+      label_style: label_style.clone(),
+      indicator: IndicatorStyle { extent: 3., measure: Some(INDICATOR_SIZE) },
+    });
   theme.custom_styles.set_custom_style(AvatarStyle {
     size: Size::splat(AVATAR_SIZE),
     radius: Some(AVATAR_RADIUS),
     text_style: theme.typography_theme.body_large.text.clone(),
   });
+  let typo = typography.clone();
   theme
     .custom_styles
-    .set_custom_style(ListItemStyle {
+    .set_palette_style(move |palette| ListItemStyle {
       padding_style: Some(EdgeInsets { left: 0., right: 24., bottom: 8., top: 8. }),
       item_align: |num| {
         if num >= 2 { Align::Start } else { Align::Center }
       },
       label_gap: Some(EdgeInsets::only_left(LIST_ITEM_GAP)),
-      headline_style: theme.typography_theme.body_large.text.clone(),
-      supporting_style: theme.typography_theme.body_medium.text.clone(),
+      headline_style: typo.body_large.text.clone(),
+      supporting_style: typo.body_medium.text.clone(),
       leading_config: EdgeWidgetStyle {
         icon: EdgeItemStyle { size: ICON_SMALL, gap: Some(EdgeInsets::only_left(LIST_ITEM_GAP)) },
         text: EdgeTextItemStyle {
-          style: theme.typography_theme.label_small.text.clone(),
-          foreground: theme.palette.on_surface_variant().into(),
+          style: typo.label_small.text.clone(),
+          foreground: palette.on_surface_variant().into(),
           gap: Some(EdgeInsets::only_left(LIST_ITEM_GAP)),
         },
         avatar: EdgeItemStyle {
@@ -213,8 +223,8 @@ fn init_custom_style(theme: &mut FullTheme) {
       trailing_config: EdgeWidgetStyle {
         icon: EdgeItemStyle { size: ICON_SMALL, gap: Some(EdgeInsets::only_left(LIST_ITEM_GAP)) },
         text: EdgeTextItemStyle {
-          style: theme.typography_theme.label_small.text.clone(),
-          foreground: theme.palette.on_surface_variant().into(),
+          style: typo.label_small.text.clone(),
+          foreground: palette.on_surface_variant().into(),
           gap: Some(EdgeInsets::only_left(LIST_ITEM_GAP)),
         },
         avatar: EdgeItemStyle {
@@ -232,11 +242,23 @@ fn init_custom_style(theme: &mut FullTheme) {
         },
       },
     });
+  let text_style = typography.body_medium.text.clone();
   theme
     .custom_styles
-    .set_custom_style(PlaceholderStyle {
-      foreground: theme.palette.on_surface_variant().into(),
-      text_style: theme.typography_theme.body_medium.text.clone(),
+    .set_palette_style(move |palette| PlaceholderStyle {
+      foreground: palette.on_surface_variant().into(),
+      text_style: text_style.clone(),
+    });
+  let text_style = typography.body_small.text.clone();
+  theme
+    .custom_styles
+    .set_palette_style(move |palette| TooltipStyle {
+      text_style: text_style.clone(),
+      foreground: palette.inverse_on_surface().into(),
+      background: palette.inverse_surface().into(),
+      padding: EdgeInsets::new(4., 8., 4., 8.),
+      radius: 4.,
+      gap: 4.,
     });
 }
 
@@ -246,7 +268,10 @@ fn override_compose_decorator(theme: &mut FullTheme) {
       @$host {
         margin,
         border_radius: Radius::all(4.),
-        background: Palette::of(ctx!()).primary(),
+        background: {
+          let palette = Palette::state_of(ctx!());
+          pipe!($palette.primary())
+        },
       }
     }
   }
@@ -393,8 +418,10 @@ fn override_compose_decorator(theme: &mut FullTheme) {
     }
     .build(ctx)
   });
-  let textfield = TextFieldThemeSuit::from_theme(&theme.palette, &theme.typography_theme);
-  theme.custom_styles.set_custom_style(textfield);
+  let typography = theme.typography_theme.clone();
+  theme
+    .custom_styles
+    .set_palette_style(move |palette| TextFieldThemeSuit::from_theme(palette, &typography));
 }
 
 pub mod purple {
@@ -458,6 +485,7 @@ pub fn typography_theme(
         font_size: FontSize::Pixel(57.0.into()),
         letter_space: Some(0.0.into()),
         font_face: regular_face.clone(),
+        fallback_fonts: vec![],
       }),
       decoration: decoration.clone(),
     },
@@ -467,6 +495,7 @@ pub fn typography_theme(
         font_size: FontSize::Pixel(45.0.into()),
         letter_space: Some(0.0.into()),
         font_face: regular_face.clone(),
+        fallback_fonts: vec![],
       }),
       decoration: decoration.clone(),
     },
@@ -476,6 +505,7 @@ pub fn typography_theme(
         font_size: FontSize::Pixel(36.0.into()),
         letter_space: Some(0.0.into()),
         font_face: regular_face.clone(),
+        fallback_fonts: vec![],
       }),
       decoration: decoration.clone(),
     },
@@ -485,6 +515,7 @@ pub fn typography_theme(
         font_size: FontSize::Pixel(32.0.into()),
         letter_space: Some(0.0.into()),
         font_face: regular_face.clone(),
+        fallback_fonts: vec![],
       }),
       decoration: decoration.clone(),
     },
@@ -494,6 +525,7 @@ pub fn typography_theme(
         font_size: FontSize::Pixel(28.0.into()),
         letter_space: Some(0.0.into()),
         font_face: regular_face.clone(),
+        fallback_fonts: vec![],
       }),
       decoration: decoration.clone(),
     },
@@ -503,6 +535,7 @@ pub fn typography_theme(
         font_size: FontSize::Pixel(24.0.into()),
         letter_space: Some(0.0.into()),
         font_face: regular_face.clone(),
+        fallback_fonts: vec![],
       }),
       decoration: decoration.clone(),
     },
@@ -512,6 +545,7 @@ pub fn typography_theme(
         font_size: FontSize::Pixel(22.0.into()),
         letter_space: Some(0.0.into()),
         font_face: medium_face.clone(),
+        fallback_fonts: vec![],
       }),
       decoration: decoration.clone(),
     },
@@ -521,6 +555,7 @@ pub fn typography_theme(
         font_size: FontSize::Pixel(16.0.into()),
         letter_space: Some(0.15.into()),
         font_face: medium_face.clone(),
+        fallback_fonts: vec![],
       }),
       decoration: decoration.clone(),
     },
@@ -530,6 +565,7 @@ pub fn typography_theme(
         font_size: FontSize::Pixel(14.0.into()),
         letter_space: Some(0.1.into()),
         font_face: medium_face.clone(),
+        fallback_fonts: vec![],
       }),
       decoration: decoration.clone(),
     },
@@ -539,6 +575,7 @@ pub fn typography_theme(
         font_size: FontSize::Pixel(14.0.into()),
         letter_space: Some(0.1.into()),
         font_face: medium_face.clone(),
+        fallback_fonts: vec![],
       }),
       decoration: decoration.clone(),
     },
@@ -548,6 +585,7 @@ pub fn typography_theme(
         font_size: FontSize::Pixel(12.0.into()),
         letter_space: Some(0.5.into()),
         font_face: medium_face.clone(),
+        fallback_fonts: vec![],
       }),
       decoration: decoration.clone(),
     },
@@ -557,6 +595,7 @@ pub fn typography_theme(
         font_size: FontSize::Pixel(11.0.into()),
         letter_space: Some(0.5.into()),
         font_face: medium_face,
+        fallback_fonts: vec![],
       }),
       decoration: decoration.clone(),
     },
@@ -566,6 +605,7 @@ pub fn typography_theme(
         font_size: FontSize::Pixel(16.0.into()),
         letter_space: Some(0.5.into()),
         font_face: regular_face.clone(),
+        fallback_fonts: vec![],
       }),
       decoration: decoration.clone(),
     },
@@ -575,6 +615,7 @@ pub fn typography_theme(
         font_size: FontSize::Pixel(14.0.into()),
         letter_space: Some(0.25.into()),
         font_face: regular_face.clone(),
+        fallback_fonts: vec![],
       }),
       decoration: decoration.clone(),
     },
@@ -584,8 +625,46 @@ pub fn typography_theme(
         font_size: FontSize::Pixel(12.0.into()),
         letter_space: Some(0.4.into()),
         font_face: regular_face,
+        fallback_fonts: vec![],
       }),
       decoration,
     },
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use ribir_core::{reset_test_env, test_helper::*};
+
+  use super::*;
+
+  #[test]
+  fn styles_follow_app_palette() {
+    reset_test_env!();
+    unsafe { AppCtx::set_app_theme(purple::light()) };
+
+    let w = fn_widget! {
+      @HScrollBar {
+        @Container { size: Size::new(400., 100.) }
+      }
+    };
+    let mut wnd = TestWindow::new_with_size(w, Size::new(100., 100.));
+    let mut painted = |color: Color| {
+      wnd.draw_frame();
+      let frame = wnd.take_last_frame().unwrap();
+      frame.commands.iter().any(|cmd| {
+        matches!(cmd, PaintCommand::Path(PathCommand {
+          action: PaintPathAction::Color(c), ..
+        }) if *c == color)
+      })
+    };
+    let light = AppCtx::app_palette().read().primary_container();
+    assert!(painted(light));
+
+    // The track of the scrollbar is restyled without rebuilding.
+    AppCtx::app_palette().write().brightness = Brightness::Dark;
+    let dark = AppCtx::app_palette().read().primary_container();
+    assert_ne!(light, dark);
+    assert!(painted(dark));
+  }
+}
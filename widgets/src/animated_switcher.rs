@@ -0,0 +1,310 @@
+use std::{
+  cell::{Cell, RefCell},
+  rc::Rc,
+};
+
+use ribir_core::prelude::*;
+
+use crate::prelude::*;
+
+/// A widget that transitions between its old child and new child when the
+/// `key` changes.
+///
+/// The child is generated again every time the `key` changes, the outgoing
+/// child is kept in the tree and plays an exit animation while the incoming
+/// child plays an enter animation. If the `key` is switched again before the
+/// transition finished, the exit of the outgoing child is cancelled and its
+/// place is used by the new child.
+///
+/// # Example
+///
+/// ```no_run
+/// # use ribir_core::prelude::*;
+/// # use ribir_widgets::prelude::*;
+/// fn_widget! {
+///   let tab = Stateful::new(0);
+///   @Column {
+///     @FilledButton {
+///       on_tap: move |_| *$tab.write() += 1,
+///       @{ Label::new("Next") }
+///     }
+///     @AnimatedSwitcher {
+///       key: pipe!(*$tab),
+///       transition_builder: SwitchTransitionBuilder::slide(Vector::new(100., 0.)),
+///       @ {
+///         move |ctx: &BuildCtx| {
+///           let tab = *$tab;
+///           let text = format!("Tab {tab}");
+///           Text::declarer().text(text).finish(ctx).build(ctx)
+///         }
+///       }
+///     }
+///   }
+/// };
+/// ```
+#[derive(Declare)]
+pub struct AnimatedSwitcher {
+  /// The identity of the child, the switch is not animated if the new key is
+  /// the same as the current one.
+  pub key: Key,
+  /// The transition drives both the enter and the exit animation. It's only
+  /// used when the switcher composed, the default is `EASE_IN_OUT` of the
+  /// theme.
+  #[declare(default)]
+  pub transition: Option<Box<dyn Transition>>,
+  /// Builds the animated widget of the entering or exiting child.
+  #[declare(default)]
+  pub transition_builder: SwitchTransitionBuilder,
+}
+
+/// Wraps the child of [`AnimatedSwitcher`] to a widget that animates with the
+/// progress of the transition. The progress is `0.` when the child is out of
+/// the switcher and `1.` when it's fully shown.
+#[derive(Clone)]
+pub struct SwitchTransitionBuilder(Rc<TransitionFn>);
+
+type TransitionFn = dyn Fn(Widget, Watcher<Reader<f32>>, &BuildCtx) -> Widget;
+
+impl SwitchTransitionBuilder {
+  pub fn new(f: impl Fn(Widget, Watcher<Reader<f32>>, &BuildCtx) -> Widget + 'static) -> Self {
+    Self(Rc::new(f))
+  }
+
+  /// Fades the child in and out.
+  pub fn fade() -> Self {
+    Self::new(|w, progress, ctx| fn_widget! { @$w { opacity: pipe!(*$progress) } }.build(ctx))
+  }
+
+  /// Slides the child from `offset` in, and slides it to `offset` out.
+  pub fn slide(offset: Vector) -> Self {
+    Self::new(move |w, progress, ctx| {
+      fn_widget! {
+        @$w {
+          transform: pipe! {
+            let offset = offset * (1. - *$progress);
+            Transform::translation(offset.x, offset.y)
+          }
+        }
+      }
+      .build(ctx)
+    })
+  }
+
+  /// Scales the child from its top-left corner in and out.
+  pub fn scale() -> Self {
+    Self::new(|w, progress, ctx| {
+      fn_widget! {
+        @$w { transform: pipe!(Transform::scale(*$progress, *$progress)) }
+      }
+      .build(ctx)
+    })
+  }
+
+  pub(crate) fn build(&self, w: Widget, progress: Watcher<Reader<f32>>, ctx: &BuildCtx) -> Widget {
+    (self.0)(w, progress, ctx)
+  }
+}
+
+impl Default for SwitchTransitionBuilder {
+  fn default() -> Self { Self::fade() }
+}
+
+/// A place in the switcher that hosts the incoming or the outgoing child.
+struct SwitchSlot {
+  /// Write it to generate the child of the slot again.
+  shown: Stateful<bool>,
+  exiting: Rc<Cell<bool>>,
+  progress: Stateful<f32>,
+  animate: State<Animate<Writer<f32>>>,
+}
+
+impl SwitchSlot {
+  fn new(shown: bool, transition: &Rc<Box<dyn Transition>>, ctx: &BuildCtx) -> Self {
+    let progress = Stateful::new(if shown { 1. } else { 0. });
+    let shown = Stateful::new(shown);
+    let exiting = Rc::new(Cell::new(false));
+    let c_shown = shown.clone_writer();
+    let c_exiting = exiting.clone();
+    let animate = Animate::declarer()
+      .transition(transition.clone().box_it())
+      .state(progress.clone_writer())
+      .from(0.)
+      .on_progress(move |rate| {
+        // The exit finished, release the outgoing child.
+        if rate == 1. && c_exiting.get() {
+          c_exiting.set(false);
+          *c_shown.write() = false;
+        }
+      })
+      .finish(ctx);
+    Self { shown, exiting, progress, animate }
+  }
+
+  fn enter(&self) {
+    self.exiting.set(false);
+    self.animate.stop();
+    self.animate.write().from = 0.;
+    *self.progress.write() = 1.;
+    *self.shown.write() = true;
+    self.animate.run();
+  }
+
+  fn exit(&self) {
+    self.exiting.set(true);
+    let from = *self.progress.read();
+    self.animate.write().from = from;
+    *self.progress.write() = 0.;
+    self.animate.run();
+  }
+}
+
+impl ComposeChild for AnimatedSwitcher {
+  type Child = GenWidget;
+  fn compose_child(this: impl StateWriter<Value = Self>, child: Self::Child) -> impl WidgetBuilder {
+    fn_widget! {
+      let transition = $this
+        .silent()
+        .transition
+        .take()
+        .unwrap_or_else(|| transitions::EASE_IN_OUT.of(ctx!()));
+      let transition = Rc::new(transition);
+      let builder = $this.transition_builder.clone();
+      let gen = Rc::new(RefCell::new(child));
+      let slots = [
+        SwitchSlot::new(true, &transition, ctx!()),
+        SwitchSlot::new(false, &transition, ctx!()),
+      ];
+
+      let slot_widget = |idx: usize| {
+        let slot: &SwitchSlot = &slots[idx];
+        let shown = slot.shown.clone_watcher();
+        let progress = slot.progress.clone_watcher();
+        let exiting = slot.exiting.clone();
+        let gen = gen.clone();
+        let builder = builder.clone();
+        pipe!(*$shown).map(move |shown| {
+          if !shown {
+            return Void.build(ctx!());
+          }
+          let w = gen.borrow_mut().gen_widget(ctx!());
+          let w = builder.build(w, progress.clone_watcher(), ctx!());
+          let exiting = exiting.clone();
+          // The outgoing child not receives the pointer events anymore.
+          @$w { hit_test: move |_: Point| !exiting.get() }.build(ctx!())
+        })
+      };
+      let first = slot_widget(0);
+      let second = slot_widget(1);
+
+      // `this` and the slots have same lifetime, so we needn't unsubscribe.
+      let mut current = 0;
+      let mut key = $this.key.clone();
+      watch!($this.key.clone()).subscribe(move |new_key| {
+        if new_key == key {
+          return;
+        }
+        key = new_key;
+        let next = 1 - current;
+        slots[next].enter();
+        slots[current].exit();
+        current = next;
+      });
+
+      @Stack {
+        @ { first }
+        @ { second }
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use ribir_core::{reset_test_env, test_helper::*};
+
+  use super::*;
+
+  fn switcher(
+    tab: Watcher<Reader<usize>>, mounted: Rc<RefCell<Vec<usize>>>, duration: Duration,
+  ) -> impl WidgetBuilder {
+    fn_widget! {
+      let c_tab = tab.clone_reader();
+      @AnimatedSwitcher {
+        key: pipe!(*$tab),
+        transition: EasingTransition { easing: easing::LINEAR, duration }.box_it(),
+        @ {
+          move |ctx: &BuildCtx| {
+            let idx = *c_tab.read();
+            let (c_mounted, c_disposed) = (mounted.clone(), mounted.clone());
+            fn_widget! {
+              @MockBox {
+                size: Size::new(100., 100.),
+                on_mounted: move |_| c_mounted.borrow_mut().push(idx),
+                on_disposed: move |_| c_disposed.borrow_mut().retain(|i| *i != idx),
+              }
+            }
+            .build(ctx)
+          }
+        }
+      }
+    }
+  }
+
+  #[test]
+  fn both_mounted_during_transition() {
+    reset_test_env!();
+
+    let tab = Stateful::new(0);
+    let mounted = Rc::new(RefCell::new(vec![]));
+    let w = switcher(tab.clone_watcher(), mounted.clone(), Duration::from_millis(50));
+    let mut wnd = TestWindow::new(w);
+    wnd.draw_frame();
+    assert_eq!(&*mounted.borrow(), &[0]);
+
+    *tab.write() = 1;
+    wnd.draw_frame();
+    assert_eq!(&*mounted.borrow(), &[0, 1]);
+
+    for _ in 0..100 {
+      if mounted.borrow().len() == 1 {
+        break;
+      }
+      std::thread::sleep(Duration::from_millis(1));
+      wnd.draw_frame();
+    }
+    assert_eq!(&*mounted.borrow(), &[1]);
+  }
+
+  #[test]
+  fn rapid_switch_repurposes_exit() {
+    reset_test_env!();
+
+    let tab = Stateful::new(0);
+    let mounted = Rc::new(RefCell::new(vec![]));
+    let w = switcher(tab.clone_watcher(), mounted.clone(), Duration::from_secs(10));
+    let mut wnd = TestWindow::new(w);
+    wnd.draw_frame();
+
+    *tab.write() = 1;
+    wnd.draw_frame();
+    *tab.write() = 2;
+    wnd.draw_frame();
+    // The exiting `0` gives its place to `2`.
+    assert_eq!(&*mounted.borrow(), &[1, 2]);
+  }
+
+  #[test]
+  fn same_key_not_switch() {
+    reset_test_env!();
+
+    let tab = Stateful::new(0);
+    let mounted = Rc::new(RefCell::new(vec![]));
+    let w = switcher(tab.clone_watcher(), mounted.clone(), Duration::from_secs(10));
+    let mut wnd = TestWindow::new(w);
+    wnd.draw_frame();
+
+    *tab.write() = 0;
+    wnd.draw_frame();
+    assert_eq!(&*mounted.borrow(), &[0]);
+  }
+}
@@ -58,11 +58,7 @@ impl ComposeChild for Checkbox {
 
   fn compose_child(this: impl StateWriter<Value = Self>, child: Self::Child) -> impl WidgetBuilder {
     fn_widget! {
-      let CheckBoxStyle {
-        icon_size,
-        label_style,
-        label_color,
-      } = CheckBoxStyle::of(ctx!());
+      let CheckBoxStyle { icon_size, label_style, .. } = CheckBoxStyle::of(ctx!());
 
       let icon = @CheckBoxDecorator {
         color: pipe!($this.color),
@@ -82,7 +78,7 @@ impl ComposeChild for Checkbox {
       let checkbox = if let Some(child) = child  {
         let label = |label: State<Label>| @Text {
           text: $label.0.clone(),
-          foreground: label_color,
+          foreground: CheckBoxStyle::pipe_of(ctx!(), |s| s.label_color.clone()),
           text_style: label_style,
         };
 
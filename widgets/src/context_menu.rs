@@ -0,0 +1,429 @@
+use std::{
+  cell::{Cell, RefCell},
+  rc::Rc,
+};
+
+use ribir_core::{
+  overlay::{ClosePolicy, OverlayStyle},
+  prelude::*,
+  ticker::FrameMsg,
+};
+
+use crate::prelude::*;
+
+/// A menu shown at the pointer when its child is tapped by the secondary
+/// button, e.g. the right click of the mouse.
+///
+/// The menu is closed by a tap outside of it, the `Esc` key, or selecting an
+/// item. An item with a submenu opens the submenu beside it when it's hovered,
+/// on the right side if there is enough space, otherwise on the left side.
+///
+/// The menu can be operated by the keyboard, `ArrowUp` and `ArrowDown` move
+/// the highlighted item, `Enter` selects it, `ArrowRight` opens its submenu
+/// and `ArrowLeft` closes the current submenu.
+///
+/// # Example
+///
+/// ```no_run
+/// use ribir_core::prelude::*;
+/// use ribir_widgets::prelude::*;
+///
+/// let _w = fn_widget! {
+///   @ContextMenu {
+///     items: vec![
+///       MenuItem::new("Copy", || println!("copy")),
+///       MenuItem::submenu("Share", vec![
+///         MenuItem::new("Email", || println!("email")),
+///       ]),
+///     ],
+///     @SizedBox { size: Size::new(200., 200.) }
+///   }
+/// };
+/// ```
+#[derive(Declare)]
+pub struct ContextMenu {
+  pub items: Vec<MenuItem>,
+  #[declare(skip)]
+  pressed: MouseButtons,
+  #[declare(skip)]
+  menus: MenuStack,
+}
+
+/// An item of a [`ContextMenu`].
+#[derive(Clone)]
+pub struct MenuItem {
+  label: CowArc<str>,
+  action: MenuAction,
+}
+
+#[derive(Clone)]
+enum MenuAction {
+  Select(Rc<dyn Fn()>),
+  Submenu(Rc<[MenuItem]>),
+}
+
+impl MenuItem {
+  /// Create an item that calls `on_select` when it's selected.
+  pub fn new(label: impl Into<CowArc<str>>, on_select: impl Fn() + 'static) -> Self {
+    Self { label: label.into(), action: MenuAction::Select(Rc::new(on_select)) }
+  }
+
+  /// Create an item that opens a submenu of the `items`.
+  pub fn submenu(label: impl Into<CowArc<str>>, items: Vec<MenuItem>) -> Self {
+    Self { label: label.into(), action: MenuAction::Submenu(items.into()) }
+  }
+}
+
+impl ComposeChild for ContextMenu {
+  type Child = Widget;
+  fn compose_child(this: impl StateWriter<Value = Self>, child: Self::Child) -> impl WidgetBuilder {
+    fn_widget! {
+      @ $child {
+        on_pointer_down: move |e| $this.silent().pressed = e.mouse_buttons(),
+        on_tap: move |e| {
+          let pressed = std::mem::take(&mut $this.silent().pressed);
+          if pressed == MouseButtons::SECONDARY {
+            $this.open(e.global_pos(), e.window());
+          }
+        },
+      }
+    }
+  }
+}
+
+impl ContextMenu {
+  /// Open the menu at the global `pos` of the window, the opened one is closed
+  /// first.
+  fn open(&self, pos: Point, wnd: Rc<Window>) {
+    self.menus.close_from(0);
+    let level = MenuLevel {
+      depth: 0,
+      items: self.items.clone().into(),
+      excludes: vec![],
+      highlight: None,
+      focus_parent: None,
+    };
+    self
+      .menus
+      .open(level, 0, Rect::new(pos, Size::zero()), wnd);
+  }
+}
+
+/// The opened menus of a `ContextMenu`, the first one is the root menu and each
+/// of the others is a submenu of its previous one. Every menu is kept with the
+/// index of the item it's opened from.
+#[derive(Clone, Default)]
+struct MenuStack(Rc<RefCell<Vec<(usize, Overlay)>>>);
+
+struct MenuLevel {
+  depth: usize,
+  items: Rc<[MenuItem]>,
+  /// The widgets of the parent menus, the taps on them pass through the mask
+  /// of this menu.
+  excludes: Vec<WidgetId>,
+  highlight: Option<usize>,
+  focus_parent: Option<Rc<dyn Fn()>>,
+}
+
+impl MenuStack {
+  /// Open the menu of `level` beside the `anchor`, the menus at the same level
+  /// or deeper are closed first. Nothing happens if the menu from the same item
+  /// is showing.
+  fn open(&self, level: MenuLevel, from: usize, anchor: Rect, wnd: Rc<Window>) {
+    let depth = level.depth;
+    let opened = self
+      .0
+      .borrow()
+      .get(depth)
+      .map_or(false, |(idx, o)| *idx == from && o.is_show());
+    if opened {
+      return;
+    }
+    self.close_from(depth);
+
+    let style = OverlayStyle {
+      close_policy: ClosePolicy::ESC | ClosePolicy::TAP_OUTSIDE,
+      backdrop: None,
+      exclude_regions: level.excludes.clone(),
+    };
+    let stack = self.clone();
+    let level = Rc::new(level);
+    let overlay = Overlay::new_with_handle(move |_| menu(stack.clone(), level.clone()));
+    overlay.with_style(style);
+    overlay.show_map(
+      move |w, _| {
+        fn_widget! {
+          let wnd = ctx!().window();
+          let mut w = @$w { anchor: Anchor::from_point(anchor.origin) };
+          let u = wnd
+            .frame_tick_stream()
+            .filter(|msg| matches!(msg, FrameMsg::LayoutReady(_)))
+            .subscribe(move |_| {
+              let pos = Anchor::from_point(menu_position(anchor, $w.layout_size(), wnd.size()));
+              if $w.anchor != pos {
+                $w.write().anchor = pos;
+              }
+            });
+          @$w { on_disposed: move |_| u.unsubscribe() }
+        }
+      },
+      wnd,
+    );
+    self.0.borrow_mut().push((from, overlay));
+  }
+
+  /// Close the menus at the `depth` and deeper.
+  fn close_from(&self, depth: usize) {
+    let closed = {
+      let mut menus = self.0.borrow_mut();
+      let at = depth.min(menus.len());
+      menus.split_off(at)
+    };
+    closed
+      .into_iter()
+      .rev()
+      .for_each(|(_, o)| o.close());
+  }
+}
+
+/// Place a menu of `size` beside the `anchor`, on the right side if there is
+/// enough space, otherwise on the left side, and keep it in the window
+/// vertically.
+fn menu_position(anchor: Rect, size: Size, wnd_size: Size) -> Point {
+  let mut x = anchor.max_x();
+  if x + size.width > wnd_size.width {
+    x = (anchor.min_x() - size.width).max(0.);
+  }
+  let y = anchor
+    .min_y()
+    .min(wnd_size.height - size.height)
+    .max(0.);
+  Point::new(x, y)
+}
+
+fn menu(stack: MenuStack, level: Rc<MenuLevel>) -> impl WidgetBuilder {
+  fn_widget! {
+    let palette = Palette::of(ctx!());
+    let highlight = Stateful::new(level.highlight);
+    let ids: Rc<[Cell<Option<WidgetId>>]> = level.items.iter().map(|_| Cell::new(None)).collect();
+    // The items are stretched to the widest one, so every item is tappable in
+    // the whole width of the menu.
+    let mut menu = @Column {
+      align_items: Align::Stretch,
+      background: palette.surface_container(),
+      auto_focus: true,
+    };
+    let menu_id = menu.lazy_id();
+    let focus_menu: Rc<dyn Fn()> = Rc::new(move || $menu.request_focus());
+
+    let c_stack = stack.clone();
+    let c_level = level.clone();
+    let c_ids = ids.clone();
+    let open_submenu = Rc::new(move |idx: usize, highlight: Option<usize>, wnd: Rc<Window>| {
+      let depth = c_level.depth + 1;
+      let MenuAction::Submenu(items) = &c_level.items[idx].action else {
+        c_stack.close_from(depth);
+        return;
+      };
+      let Some(id) = c_ids[idx].get() else { return };
+      let anchor = Rect::new(
+        wnd.map_to_global(Point::zero(), id), wnd.layout_size(id).unwrap_or_default()
+      );
+      let mut excludes = c_level.excludes.clone();
+      excludes.push(menu_id.assert_id());
+      let sub = MenuLevel {
+        depth,
+        items: items.clone(),
+        excludes,
+        highlight,
+        focus_parent: Some(focus_menu.clone()),
+      };
+      c_stack.open(sub, idx, anchor, wnd);
+    });
+
+    let c_stack = stack.clone();
+    let c_level = level.clone();
+    let c_open_submenu = open_submenu.clone();
+    let select = Rc::new(move |idx: usize, wnd: Rc<Window>| {
+      match &c_level.items[idx].action {
+        MenuAction::Select(on_select) => {
+          c_stack.close_from(0);
+          on_select();
+        }
+        MenuAction::Submenu(_) => c_open_submenu(idx, Some(0), wnd),
+      }
+    });
+
+    let items = level.items.iter().enumerate().map(|(idx, item)| {
+      let label = item.label.clone();
+      let has_submenu = matches!(item.action, MenuAction::Submenu(_));
+      let ids = ids.clone();
+      let highlight = highlight.clone_writer();
+      let open_submenu = open_submenu.clone();
+      let select = select.clone();
+      let active = palette.secondary_container();
+      fn_widget! {
+        let trailing = has_submenu.then(|| @Icon {
+          size: IconSize::of(ctx!()).tiny,
+          @ { svgs::CHEVRON_RIGHT }
+        });
+        // The listeners wrap the padding, so the padding is tappable too.
+        @Stack {
+          background: pipe!(match *$highlight == Some(idx) {
+            true => Brush::from(active),
+            false => Brush::from(Color::TRANSPARENT),
+          }),
+          on_mounted: move |e| ids[idx].set(Some(e.current_target())),
+          on_pointer_enter: move |e| {
+            *$highlight.write() = Some(idx);
+            open_submenu(idx, None, e.window());
+          },
+          on_tap: move |e| select(idx, e.window()),
+          @Row {
+            align_items: Align::Center,
+            padding: EdgeInsets::new(8., 12., 8., 12.),
+            @Text { text: label.clone() }
+            @ { trailing }
+          }
+        }
+      }
+    }).collect::<Vec<_>>();
+
+    let len = level.items.len();
+    let c_stack = stack.clone();
+    let c_level = level.clone();
+    @UnconstrainedBox {
+      dir: UnconstrainedDir::X,
+      @$menu {
+        on_key_down: move |e| {
+          if len == 0 {
+            return;
+          }
+          let current = *$highlight;
+          match e.key() {
+            VirtualKey::Named(NamedKey::ArrowDown) => {
+              *$highlight.write() = Some(current.map_or(0, |i| (i + 1) % len));
+            }
+            VirtualKey::Named(NamedKey::ArrowUp) => {
+              *$highlight.write() = Some(current.map_or(len - 1, |i| (i + len - 1) % len));
+            }
+            VirtualKey::Named(NamedKey::Enter) => {
+              if let Some(idx) = current {
+                select(idx, e.window());
+              }
+            }
+            VirtualKey::Named(NamedKey::ArrowRight) => {
+              if let Some(idx) = current {
+                open_submenu(idx, Some(0), e.window());
+              }
+            }
+            VirtualKey::Named(NamedKey::ArrowLeft) if c_level.depth > 0 => {
+              c_stack.close_from(c_level.depth);
+            }
+            _ => {}
+          }
+        },
+        on_disposed: move |_| {
+          stack.close_from(level.depth + 1);
+          if let Some(focus_parent) = level.focus_parent.as_ref() {
+            focus_parent();
+          }
+        },
+        @ { items }
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::cell::RefCell;
+
+  use ribir_core::{reset_test_env, test_helper::*};
+  use winit::event::{DeviceId, ElementState, MouseButton, WindowEvent};
+
+  use super::*;
+  use crate::layout::SizedBox;
+
+  #[allow(deprecated)]
+  fn tap_at(wnd: &mut TestWindow, x: f32, y: f32, btn: MouseButton) {
+    let device_id = unsafe { DeviceId::dummy() };
+    wnd.processes_native_event(WindowEvent::CursorMoved { device_id, position: (x, y).into() });
+    wnd.process_mouse_input(device_id, ElementState::Pressed, btn);
+    wnd.run_frame_tasks();
+    wnd.process_mouse_input(device_id, ElementState::Released, btn);
+    wnd.draw_frame();
+    wnd.draw_frame();
+  }
+
+  fn press_key(wnd: &mut TestWindow, key: NamedKey, code: KeyCode) {
+    for state in [ElementState::Pressed, ElementState::Released] {
+      wnd.processes_keyboard_event(
+        PhysicalKey::Code(code),
+        VirtualKey::Named(key),
+        false,
+        KeyLocation::Standard,
+        state,
+      );
+    }
+    wnd.draw_frame();
+    wnd.draw_frame();
+  }
+
+  #[test]
+  fn select_item() {
+    reset_test_env!();
+
+    let selected = Rc::new(RefCell::new(vec![]));
+    let c_copy = selected.clone();
+    let c_email = selected.clone();
+    let items = vec![
+      MenuItem::new("Copy", move || c_copy.borrow_mut().push("copy")),
+      MenuItem::submenu(
+        "Share",
+        vec![MenuItem::new("Email", move || c_email.borrow_mut().push("email"))],
+      ),
+    ];
+    let w = fn_widget! {
+      @ContextMenu {
+        items,
+        @SizedBox { size: Size::new(200., 200.) }
+      }
+    };
+
+    let mut wnd = TestWindow::new_with_size(w, Size::new(300., 300.));
+    wnd.draw_frame();
+
+    // The primary tap not opens the menu.
+    tap_at(&mut wnd, 50., 50., MouseButton::Left);
+    assert_eq!(wnd.root_children_count(), 1);
+
+    tap_at(&mut wnd, 50., 50., MouseButton::Right);
+    assert_eq!(wnd.root_children_count(), 2);
+
+    // The whole first item is tappable, the top right corner is in its padding
+    // and out of its content width.
+    tap_at(&mut wnd, 120., 53., MouseButton::Left);
+    assert_eq!(&*selected.borrow(), &["copy"]);
+    assert_eq!(wnd.root_children_count(), 1);
+
+    // Select the item of the submenu by the keyboard, the first item is
+    // highlighted by the pointer hovering it.
+    tap_at(&mut wnd, 50., 50., MouseButton::Right);
+    press_key(&mut wnd, NamedKey::ArrowDown, KeyCode::ArrowDown);
+    press_key(&mut wnd, NamedKey::ArrowRight, KeyCode::ArrowRight);
+    assert_eq!(wnd.root_children_count(), 3);
+
+    press_key(&mut wnd, NamedKey::Enter, KeyCode::Enter);
+    assert_eq!(&*selected.borrow(), &["copy", "email"]);
+    assert_eq!(wnd.root_children_count(), 1);
+  }
+
+  #[test]
+  fn submenu_flip_near_edge() {
+    let anchor = Rect::new(Point::new(150., 20.), Size::new(40., 20.));
+    let wnd_size = Size::new(300., 100.);
+    assert_eq!(menu_position(anchor, Size::new(100., 50.), wnd_size), Point::new(190., 20.));
+    assert_eq!(menu_position(anchor, Size::new(120., 90.), wnd_size), Point::new(30., 10.));
+  }
+}
@@ -0,0 +1,169 @@
+use std::cell::RefCell;
+
+use ribir_core::prelude::*;
+
+/// A widget that paints by a closure with the painter directly in its layout
+/// slot, it's useful for the content like a chart or a game view.
+///
+/// The painter passed to the closure is clipped to the bounds of the widget,
+/// and its origin is at the top-left of the widget. The closure is called with
+/// the size of the widget every time the widget is painted.
+///
+/// The widget repaints when the `painter` is updated by a `pipe!`, so the
+/// states the closure depends on are watched by the pipe.
+///
+/// # Example
+///
+/// ```no_run
+/// use ribir_core::prelude::*;
+/// use ribir_widgets::prelude::*;
+///
+/// let progress = Stateful::new(0.5);
+/// let w = fn_widget! {
+///   @CustomPaint {
+///     size_policy: SizePolicy::Fixed(Size::new(100., 10.)),
+///     painter: pipe! {
+///       let progress = *$progress;
+///       move |painter: &mut Painter, size: Size| {
+///         painter
+///           .set_brush(Color::RED)
+///           .rect(&Rect::from_size(Size::new(size.width * progress, size.height)))
+///           .fill();
+///       }
+///     }
+///   }
+/// };
+/// ```
+#[derive(Declare)]
+pub struct CustomPaint {
+  #[declare(default)]
+  pub size_policy: SizePolicy,
+  pub painter: CustomPainter,
+}
+
+/// How the [`CustomPaint`] decides its size.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum SizePolicy {
+  /// As large as the parent allows, or as small as possible if the parent is
+  /// unbounded.
+  #[default]
+  Fill,
+  /// A fixed size that is clamped by the parent.
+  Fixed(Size),
+}
+
+/// The closure to paint the [`CustomPaint`] with the painter and the size of
+/// the widget.
+pub struct CustomPainter(RefCell<Box<PaintFn>>);
+
+type PaintFn = dyn FnMut(&mut Painter, Size);
+
+impl<F: FnMut(&mut Painter, Size) + 'static> From<F> for CustomPainter {
+  #[inline]
+  fn from(f: F) -> Self { Self(RefCell::new(Box::new(f))) }
+}
+
+impl Render for CustomPaint {
+  fn perform_layout(&self, clamp: BoxClamp, _: &mut LayoutCtx) -> Size {
+    match self.size_policy {
+      SizePolicy::Fill => {
+        let fill = |max: f32, min: f32| if max.is_finite() { max } else { min };
+        Size::new(fill(clamp.max.width, clamp.min.width), fill(clamp.max.height, clamp.min.height))
+      }
+      SizePolicy::Fixed(size) => clamp.clamp(size),
+    }
+  }
+
+  fn paint(&self, ctx: &mut PaintingCtx) {
+    let size = ctx.box_size().unwrap();
+    let mut painter = ctx.painter().save_guard();
+    painter.clip(Path::rect(&Rect::from_size(size)));
+    (self.painter.0.borrow_mut())(&mut painter, size);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use ribir_core::{reset_test_env, test_helper::*};
+
+  use super::*;
+
+  fn color_bounds(frame: &Frame, color: Color) -> Option<Rect> {
+    frame.commands.iter().find_map(|cmd| match cmd {
+      PaintCommand::Path(PathCommand {
+        action: PaintPathAction::Color(c), paint_bounds, ..
+      }) if *c == color => Some(*paint_bounds),
+      _ => None,
+    })
+  }
+
+  #[test]
+  fn paint_at_widget_offset() {
+    reset_test_env!();
+
+    let (color, w_color) = split_value(Color::RED);
+    let w = fn_widget! {
+      @CustomPaint {
+        anchor: Anchor::left_top(20., 30.),
+        size_policy: SizePolicy::Fixed(Size::new(40., 40.)),
+        painter: pipe! {
+          let color = *$color;
+          move |painter: &mut Painter, _: Size| {
+            painter.set_brush(color).rect(&Rect::from_size(Size::new(10., 10.))).fill();
+          }
+        }
+      }
+    };
+    let mut wnd = TestWindow::new_with_size(w, Size::new(100., 100.));
+    wnd.draw_frame();
+    let frame = wnd.take_last_frame().unwrap();
+    assert_eq!(
+      color_bounds(&frame, Color::RED),
+      Some(Rect::new(Point::new(20., 30.), Size::new(10., 10.)))
+    );
+
+    // Repaint when the dependency changed.
+    *w_color.write() = Color::BLUE;
+    wnd.draw_frame();
+    let frame = wnd.take_last_frame().unwrap();
+    assert_eq!(color_bounds(&frame, Color::RED), None);
+    assert!(color_bounds(&frame, Color::BLUE).is_some());
+  }
+
+  #[test]
+  fn clip_to_bounds() {
+    reset_test_env!();
+
+    let w = fn_widget! {
+      @CustomPaint {
+        anchor: Anchor::left_top(20., 30.),
+        size_policy: SizePolicy::Fixed(Size::new(40., 40.)),
+        painter: |painter: &mut Painter, _: Size| {
+          painter.rect(&Rect::new(Point::new(-10., -10.), Size::new(100., 100.))).fill();
+        }
+      }
+    };
+    let mut wnd = TestWindow::new_with_size(w, Size::new(100., 100.));
+    wnd.draw_frame();
+    let frame = wnd.take_last_frame().unwrap();
+    let clip = frame.commands.iter().find_map(|cmd| match cmd {
+      PaintCommand::Path(PathCommand { action: PaintPathAction::Clip, paint_bounds, .. }) => {
+        Some(*paint_bounds)
+      }
+      _ => None,
+    });
+    assert_eq!(clip, Some(Rect::new(Point::new(20., 30.), Size::new(40., 40.))));
+  }
+
+  #[test]
+  fn fill_the_parent() {
+    reset_test_env!();
+
+    let w = fn_widget! {
+      @CustomPaint { painter: |_: &mut Painter, _: Size| {} }
+    };
+    let mut wnd = TestWindow::new_with_size(w, Size::new(100., 80.));
+    wnd.draw_frame();
+    assert_eq!(wnd.layout_info_by_path(&[0]).unwrap().size, Some(Size::new(100., 80.)));
+  }
+}
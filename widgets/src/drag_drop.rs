@@ -0,0 +1,329 @@
+use std::{
+  any::{Any, TypeId},
+  cell::RefCell,
+  rc::{Rc, Weak},
+};
+
+use ribir_core::{
+  overlay::{ClosePolicy, OverlayStyle},
+  prelude::*,
+  window::WindowId,
+};
+
+/// A widget that starts a drag with its `payload` by the pan gesture, and drops
+/// the payload on the innermost [`DropTarget`] of the same payload type under
+/// the pointer when released.
+///
+/// # Example
+///
+/// ```no_run
+/// # use ribir_core::prelude::*;
+/// # use ribir_widgets::prelude::*;
+/// fn_widget! {
+///   @Row {
+///     @Draggable {
+///       payload: "Ribir".to_string(),
+///       @Text { text: "Drag me" }
+///     }
+///     @DropTarget {
+///       on_drop: |v: String| println!("{v} dropped"),
+///       @Text { text: "Drop here" }
+///     }
+///   }
+/// };
+/// ```
+#[derive(Declare)]
+pub struct Draggable<T: Clone + 'static> {
+  /// The data carried by the drag.
+  #[declare(custom)]
+  pub payload: T,
+  /// The widget follows the pointer during the drag.
+  #[declare(default)]
+  pub feedback: Option<GenWidget>,
+}
+
+/// A widget that receives the payload of a [`Draggable`] dropped on it, only
+/// the draggable with the payload of type `T` is accepted. It's highlighted
+/// when an accepted draggable hovers on it.
+#[derive(Declare)]
+pub struct DropTarget<T: Clone + 'static> {
+  #[declare(custom, default)]
+  on_drop: Option<Box<dyn FnMut(T)>>,
+  #[declare(skip)]
+  hovering: bool,
+}
+
+impl<T: Clone + 'static> DraggableDeclarer<T> {
+  /// Sets the data carried by the drag.
+  pub fn payload(mut self, payload: T) -> Self {
+    self.payload = Some(DeclareInit::Value(payload));
+    self
+  }
+}
+
+impl<T: Clone + 'static> DropTargetDeclarer<T> {
+  /// Sets the handler that receives the payload dropped on the target.
+  pub fn on_drop(mut self, f: impl FnMut(T) + 'static) -> Self {
+    let f: Box<dyn FnMut(T)> = Box::new(f);
+    self.on_drop = Some(DeclareInit::Value(Some(f)));
+    self
+  }
+}
+
+impl<T: Clone + 'static> DropTarget<T> {
+  /// Return if an accepted draggable hovers on the target.
+  pub fn is_hovering(&self) -> bool { self.hovering }
+}
+
+trait AnyDropTarget {
+  fn set_hovering(&self, hovering: bool);
+  fn drop_payload(&self, payload: &dyn Any);
+}
+
+struct DropTargetInfo {
+  id: WidgetId,
+  wnd_id: WindowId,
+  accept: TypeId,
+  /// The target is owned by its widget, the registry not keeps it alive after
+  /// the widget is dropped.
+  target: Weak<dyn AnyDropTarget>,
+}
+
+thread_local! {
+  static DROP_TARGETS: RefCell<Vec<DropTargetInfo>> = RefCell::default();
+}
+
+impl<T: Clone + 'static> ComposeChild for Draggable<T> {
+  type Child = Widget;
+  fn compose_child(this: impl StateWriter<Value = Self>, child: Self::Child) -> impl WidgetBuilder {
+    fn_widget! {
+      let pos = Stateful::new(Point::zero());
+      let feedback = $this.silent().feedback.take().map(|gen| {
+        let gen = Rc::new(RefCell::new(gen));
+        let pos = pos.clone_watcher();
+        let overlay = Overlay::new_with_handle(move |_| {
+          let gen = gen.clone();
+          let pos = pos.clone_watcher();
+          fn_widget! {
+            let w = gen.borrow_mut().gen_widget(ctx!());
+            @$w { anchor: pipe!(Anchor::from_point(*$pos)) }
+          }
+        });
+        overlay.with_style(OverlayStyle {
+          close_policy: ClosePolicy::NONE,
+          backdrop: None,
+          exclude_regions: vec![],
+        });
+        overlay
+      });
+      let mut hovering: Option<Rc<dyn AnyDropTarget>> = None;
+      @$child {
+        on_pan: move |pan, e| {
+          let at = e.global_pos();
+          match pan {
+            Pan::Start => {
+              *$pos.write() = at;
+              if let Some(feedback) = feedback.as_ref() {
+                feedback.show(e.window());
+              }
+            }
+            Pan::Update { .. } => {
+              *$pos.write() = at;
+              let target = target_at::<T>(at, e);
+              let changed = match (&hovering, &target) {
+                (Some(a), Some(b)) => !Rc::ptr_eq(a, b),
+                (None, None) => false,
+                _ => true,
+              };
+              if changed {
+                if let Some(old) = hovering.take() {
+                  old.set_hovering(false);
+                }
+                if let Some(new) = target.as_ref() {
+                  new.set_hovering(true);
+                }
+                hovering = target;
+              }
+            }
+            Pan::End { .. } | Pan::Cancel => {
+              if let Some(feedback) = feedback.as_ref() {
+                feedback.close();
+              }
+              if let Some(old) = hovering.take() {
+                old.set_hovering(false);
+              }
+              // A drop out of any accepted target cancels the drag.
+              if matches!(pan, Pan::End { .. }) {
+                if let Some(target) = target_at::<T>(at, e) {
+                  let payload = $this.payload.clone();
+                  target.drop_payload(&payload);
+                }
+              }
+            }
+          }
+        }
+      }
+    }
+  }
+}
+
+impl<T: Clone + 'static> ComposeChild for DropTarget<T> {
+  type Child = Widget;
+  fn compose_child(this: impl StateWriter<Value = Self>, child: Self::Child) -> impl WidgetBuilder {
+    fn_widget! {
+      let target: Rc<dyn AnyDropTarget> = Rc::new(this.clone_writer());
+      let weak_target = Rc::downgrade(&target);
+      let highlight = Palette::of(ctx!()).primary();
+      @$child {
+        border: pipe! {
+          $this.hovering.then(|| Border::all(BorderSide::new(2., highlight.into())))
+        },
+        on_mounted: move |e| {
+          let info = DropTargetInfo {
+            id: e.current_target(),
+            wnd_id: e.window().id(),
+            accept: TypeId::of::<T>(),
+            target: weak_target,
+          };
+          DROP_TARGETS.with(|targets| {
+            let mut targets = targets.borrow_mut();
+            targets.retain(|info| info.target.strong_count() > 0);
+            targets.push(info);
+          });
+        },
+        on_disposed: move |e| {
+          let id = e.current_target();
+          DROP_TARGETS.with(|targets| targets.borrow_mut().retain(|info| info.id != id));
+          // The target lives until the widget is disposed.
+          drop(target);
+        },
+      }
+    }
+  }
+}
+
+impl<T, W> AnyDropTarget for W
+where
+  T: Clone + 'static,
+  W: StateWriter<Value = DropTarget<T>>,
+{
+  fn set_hovering(&self, hovering: bool) {
+    if self.read().hovering != hovering {
+      self.write().hovering = hovering;
+    }
+  }
+
+  fn drop_payload(&self, payload: &dyn Any) {
+    let Some(payload) = payload.downcast_ref::<T>() else { return };
+    // Take the handler out to not hold the borrow of the target in the handler.
+    let on_drop = self.silent().on_drop.take();
+    if let Some(mut on_drop) = on_drop {
+      on_drop(payload.clone());
+      self.silent().on_drop.get_or_insert(on_drop);
+    }
+  }
+}
+
+/// Return the innermost drop target at the global position `pos` that accepts
+/// the payload of type `T`.
+fn target_at<T: 'static>(pos: Point, ctx: &CommonEvent) -> Option<Rc<dyn AnyDropTarget>> {
+  let wnd = ctx.window();
+  let wnd_id = wnd.id();
+  let accept = TypeId::of::<T>();
+  let depth = |mut id: WidgetId| {
+    let mut depth = 0;
+    while let Some(p) = ctx.widget_parent(id) {
+      depth += 1;
+      id = p;
+    }
+    depth
+  };
+
+  DROP_TARGETS.with(|targets| {
+    targets
+      .borrow()
+      .iter()
+      .filter(|info| info.wnd_id == wnd_id && info.accept == accept)
+      .filter(|info| {
+        wnd
+          .widget_rect(info.id)
+          .map_or(false, |rect| rect.contains(pos))
+      })
+      .max_by_key(|info| depth(info.id))
+      .and_then(|info| info.target.upgrade())
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use ribir_core::{reset_test_env, test_helper::*};
+  use winit::event::{DeviceId, ElementState, MouseButton, WindowEvent};
+
+  use super::*;
+
+  fn drag(wnd: &mut TestWindow, from: Point, to: Point) {
+    let device_id = unsafe { DeviceId::dummy() };
+    #[allow(deprecated)]
+    let move_to = |wnd: &mut TestWindow, pos: Point| {
+      wnd.processes_native_event(WindowEvent::CursorMoved {
+        device_id,
+        position: (pos.x, pos.y).into(),
+      });
+      wnd.run_frame_tasks();
+    };
+    move_to(wnd, from);
+    wnd.process_mouse_input(device_id, ElementState::Pressed, MouseButton::Left);
+    wnd.run_frame_tasks();
+    // Start the pan in the draggable, then it captures the pointer.
+    move_to(wnd, from + Vector::new(5., 5.));
+    move_to(wnd, to);
+    wnd.process_mouse_input(device_id, ElementState::Released, MouseButton::Left);
+    wnd.draw_frame();
+  }
+
+  #[test]
+  fn drop_to_accepted_target() {
+    reset_test_env!();
+
+    let drops = Rc::new(RefCell::new(vec![]));
+    let (c_outer, c_inner, c_text) = (drops.clone(), drops.clone(), drops.clone());
+    let w = fn_widget! {
+      @MockMulti {
+        @Draggable {
+          payload: 1,
+          @MockBox { size: Size::new(50., 50.) }
+        }
+        @DropTarget {
+          on_drop: move |v: i32| c_outer.borrow_mut().push(("outer", v)),
+          @MockBox {
+            size: Size::new(100., 100.),
+            @DropTarget {
+              on_drop: move |v: i32| c_inner.borrow_mut().push(("inner", v)),
+              @MockBox { size: Size::new(40., 40.) }
+            }
+          }
+        }
+        @DropTarget {
+          on_drop: move |_: String| c_text.borrow_mut().push(("text", 0)),
+          @MockBox { size: Size::new(100., 100.) }
+        }
+      }
+    };
+    let mut wnd = TestWindow::new_with_size(w, Size::new(400., 400.));
+    wnd.draw_frame();
+
+    let start = Point::new(25., 25.);
+    // The innermost target wins.
+    drag(&mut wnd, start, Point::new(70., 20.));
+    assert_eq!(&*drops.borrow(), &[("inner", 1)]);
+
+    drag(&mut wnd, start, Point::new(120., 80.));
+    assert_eq!(&*drops.borrow(), &[("inner", 1), ("outer", 1)]);
+
+    // The target of another payload type ignores the drop.
+    drag(&mut wnd, start, Point::new(200., 50.));
+    // Released outside of any target, the drop is cancelled.
+    drag(&mut wnd, start, Point::new(350., 350.));
+    assert_eq!(&*drops.borrow(), &[("inner", 1), ("outer", 1)]);
+  }
+}
@@ -3,18 +3,22 @@ mod caret;
 mod caret_state;
 mod glyphs_helper;
 mod handle;
+mod navigation;
+mod pre_edit;
 mod selected_text;
 mod text_selectable;
 use std::{ops::Range, rc::Rc};
 
 pub use caret_state::{CaretPosition, CaretState};
-pub use selected_text::SelectedHighLightStyle;
+pub use navigation::*;
+pub use pre_edit::PreEditUnderlineStyle;
 pub use text_selectable::TextSelectable;
 
 use crate::{
   input::{
     caret::Caret,
     handle::{edit_handle, edit_key_handle, TextCaretWriter},
+    pre_edit::PreEditUnderline,
     selected_text::SelectedHighLight,
     text_selectable::{bind_point_listener, select_key_handle, SelectableText},
   },
@@ -47,10 +51,23 @@ impl CustomStyle for PlaceholderStyle {
 #[derive(Clone, PartialEq)]
 pub struct InputStyle {
   pub size: Option<f32>,
+  /// The brush of the caret, `None` means draw the caret with the
+  /// `svgs::TEXT_CARET` icon of the theme.
+  pub caret_color: Option<Brush>,
+  /// The brush to highlight the selected text, it's painted under the text, so
+  /// a translucent color keeps the text readable on both the light and the
+  /// dark background.
+  pub selection_color: Brush,
 }
 
 impl CustomStyle for InputStyle {
-  fn default_style(_: &BuildCtx) -> Self { InputStyle { size: Some(20.) } }
+  fn default_style(ctx: &BuildCtx) -> Self {
+    InputStyle {
+      size: Some(20.),
+      caret_color: None,
+      selection_color: Palette::of(ctx).primary().with_alpha(0.4).into(),
+    }
+  }
 }
 
 #[derive(Clone, PartialEq)]
@@ -104,11 +121,43 @@ pub struct TextArea {
 impl Input {
   /// set the text and the caret selection will be reset to the start.
   pub fn set_text(&mut self, text: &str) { self.set_text_with_caret(text, CaretState::default()); }
+
+  /// Return the selected byte range of the text, or `None` if nothing is
+  /// selected.
+  #[inline]
+  pub fn selection(&self) -> Option<Range<usize>> { SelectableText::selection(self) }
+
+  /// Select the text from the byte offset `start` to `end`, the offsets are
+  /// snapped to the grapheme boundaries.
+  #[inline]
+  pub fn set_selection(&mut self, start: usize, end: usize) {
+    SelectableText::set_selection(self, start, end)
+  }
+
+  /// Select all the text.
+  #[inline]
+  pub fn select_all(&mut self) { SelectableText::select_all(self) }
 }
 
 impl TextArea {
   /// set the text and the caret selection will be reset to the start.
   pub fn set_text(&mut self, text: &str) { self.set_text_with_caret(text, CaretState::default()); }
+
+  /// Return the selected byte range of the text, or `None` if nothing is
+  /// selected.
+  #[inline]
+  pub fn selection(&self) -> Option<Range<usize>> { SelectableText::selection(self) }
+
+  /// Select the text from the byte offset `start` to `end`, the offsets are
+  /// snapped to the grapheme boundaries.
+  #[inline]
+  pub fn set_selection(&mut self, start: usize, end: usize) {
+    SelectableText::set_selection(self, start, end)
+  }
+
+  /// Select all the text.
+  #[inline]
+  pub fn select_all(&mut self) { SelectableText::select_all(self) }
 }
 
 impl SelectableText for Input {
@@ -174,6 +223,14 @@ where
   fn new(window: Rc<Window>, host: H, caret_id: LazyWidgetId) -> Self {
     Self { window, host, pre_edit: None, guard: None, caret_id }
   }
+  /// The byte range of the composing text in the host text.
+  fn pre_edit_range(&self) -> Option<Range<usize>> {
+    let PreEditState { position, value } = self.pre_edit.as_ref()?;
+    value
+      .as_ref()
+      .map(|v| *position..*position + v.len())
+  }
+
   fn ime_allowed(&mut self) {
     self.window.set_ime_allowed(true);
     self.track_cursor();
@@ -393,6 +450,19 @@ where
         }
       };
 
+      let pre_edit_underline = @UnconstrainedBox {
+        clamp_dim: ClampDim::MIN_SIZE,
+        @OnlySizedByParent {
+          @PreEditUnderline {
+            rects: pipe! {
+              $ime_handle
+                .pre_edit_range()
+                .map_or(vec![], |rg| $this.text_rects(&$text, $text.layout_size(), &rg))
+            }
+          }
+        }
+      };
+
       let caret = @UnconstrainedBox {
         clamp_dim: ClampDim::MIN_SIZE,
         @OnlySizedByParent {
@@ -414,6 +484,7 @@ where
         padding: EdgeInsets::horizontal(2.),
         @ { placeholder }
         @ { high_light_rect }
+        @ { pre_edit_underline }
         @ { caret }
         @ { text_widget }
       }
@@ -473,14 +544,17 @@ fn auto_scroll_pos(container: &ScrollableWidget, before: Point, after: Point, si
 
 #[cfg(test)]
 mod tests {
+  use std::{cell::RefCell, rc::Rc};
+
   use ribir_core::{
     prelude::*,
     reset_test_env,
-    test_helper::{split_value, TestWindow},
+    test_helper::{split_value, Frame, TestWindow},
   };
+  use ribir_dev_helper::*;
   use winit::event::{DeviceId, ElementState, MouseButton, WindowEvent};
 
-  use super::{EditableText, Input};
+  use super::{CaretPosition, CaretState, EditableText, Input, InputStyle};
   use crate::layout::SizedBox;
 
   #[test]
@@ -507,6 +581,64 @@ mod tests {
     assert_eq!(*input_value.read(), "hello world");
   }
 
+  #[test]
+  fn input_ime_pre_edit() {
+    reset_test_env!();
+    let (input_value, input_value_writer) = split_value(String::default());
+    let w = fn_widget! {
+      let input = @Input { auto_focus: true };
+      watch!($input.text().clone())
+        .subscribe(move |text| *input_value_writer.write() = text.to_string());
+      @ { input }
+    };
+
+    let mut wnd = TestWindow::new_with_size(w, Size::new(200., 200.));
+    wnd.draw_frame();
+
+    wnd.processes_ime_pre_edit(ImePreEdit::Begin);
+    wnd.processes_ime_pre_edit(ImePreEdit::PreEdit { value: "ni".into(), cursor: Some((2, 2)) });
+    wnd.draw_frame();
+    assert_eq!(*input_value.read(), "ni");
+
+    // The committed text replaces the composing text.
+    wnd.processes_ime_pre_edit(ImePreEdit::End);
+    wnd.processes_receive_chars("你".into());
+    wnd.draw_frame();
+    assert_eq!(*input_value.read(), "你");
+
+    // A canceled composition is cleared without commit.
+    wnd.processes_ime_pre_edit(ImePreEdit::Begin);
+    wnd.processes_ime_pre_edit(ImePreEdit::PreEdit { value: "hao".into(), cursor: Some((3, 3)) });
+    wnd.draw_frame();
+    assert_eq!(*input_value.read(), "你hao");
+
+    wnd.processes_ime_pre_edit(ImePreEdit::End);
+    wnd.draw_frame();
+    assert_eq!(*input_value.read(), "你");
+  }
+
+  #[test]
+  fn pre_edit_underline_by_wgpu() {
+    reset_test_env!();
+    let w = fn_widget! {
+      @Input { auto_focus: true }
+    };
+    let mut wnd = TestWindow::new_with_size(w, Size::new(64., 24.));
+    wnd.draw_frame();
+
+    wnd.processes_receive_chars("hi ".into());
+    wnd.processes_ime_pre_edit(ImePreEdit::Begin);
+    wnd.processes_ime_pre_edit(ImePreEdit::PreEdit { value: "ni".into(), cursor: Some((2, 2)) });
+    wnd.draw_frame();
+
+    // Only the composing "ni" is underlined.
+    let Frame { commands, viewport, surface, .. } = wnd.take_last_frame().unwrap();
+    let viewport = viewport.to_i32().cast_unit();
+    let img = wgpu_render_commands(&commands, viewport, surface);
+    let name = "pre_edit_underline_by_wgpu";
+    ImageTest::new(img, &test_case_name!(name, "png")).test();
+  }
+
   #[test]
   fn input_tap_focus() {
     reset_test_env!();
@@ -540,4 +672,85 @@ mod tests {
     wnd.draw_frame();
     assert_eq!(*input_value.read(), "hello");
   }
+
+  #[test]
+  fn custom_selection_color() {
+    reset_test_env!();
+
+    let selection = Color::from_rgb(0, 128, 255).with_alpha(0.5);
+    let mut custom_styles = CustomStyles::default();
+    custom_styles.set_custom_style(InputStyle {
+      size: Some(20.),
+      caret_color: None,
+      selection_color: selection.into(),
+    });
+    let theme = InheritTheme { custom_styles: Some(custom_styles), ..<_>::default() };
+    let w = fn_widget! {
+      @ThemeWidget {
+        theme: Theme::Inherit(theme),
+        @ {
+          Box::new(fn_widget! {
+            let input = @Input { auto_focus: true };
+            let pos = |cluster| CaretPosition { cluster, position: None };
+            $input.write().set_text_with_caret("hello", CaretState::Select(pos(0), pos(5)));
+            @ { input }
+          })
+        }
+      }
+    };
+
+    let mut wnd = TestWindow::new_with_size(w, Size::new(200., 200.));
+    wnd.draw_frame();
+    wnd.draw_frame();
+    let frame = wnd.take_last_frame().unwrap();
+    let highlighted = frame.commands.iter().any(|cmd| {
+      matches!(cmd, PaintCommand::Path(PathCommand {
+        action: PaintPathAction::Color(c), ..
+      }) if *c == selection)
+    });
+    assert!(highlighted);
+  }
+
+  #[test]
+  fn selection_follows_palette() {
+    reset_test_env!();
+
+    let light = Palette::default();
+    let dark = Palette { brightness: Brightness::Dark, ..Palette::default() };
+    let theme = InheritTheme { palette: Some(Rc::new(light.clone())), ..<_>::default() };
+    let palette = Rc::new(RefCell::new(None));
+    let c_palette = palette.clone();
+    let w = fn_widget! {
+      @ThemeWidget {
+        theme: Theme::Inherit(theme),
+        @ {
+          Box::new(fn_widget! {
+            *c_palette.borrow_mut() = Some(Palette::state_of(ctx!()));
+            let input = @Input { auto_focus: true };
+            let pos = |cluster| CaretPosition { cluster, position: None };
+            $input.write().set_text_with_caret("hello", CaretState::Select(pos(0), pos(5)));
+            @ { input }
+          })
+        }
+      }
+    };
+
+    let mut wnd = TestWindow::new_with_size(w, Size::new(200., 200.));
+    wnd.draw_frame();
+    let mut painted = |color: Color| {
+      wnd.draw_frame();
+      let frame = wnd.take_last_frame().unwrap();
+      frame.commands.iter().any(|cmd| {
+        matches!(cmd, PaintCommand::Path(PathCommand {
+          action: PaintPathAction::Color(c), ..
+        }) if *c == color)
+      })
+    };
+    assert!(painted(light.primary().with_alpha(0.4)));
+
+    let palette = palette.borrow_mut().take().unwrap();
+    palette.write().brightness = Brightness::Dark;
+    assert_ne!(light.primary(), dark.primary());
+    assert!(painted(dark.primary().with_alpha(0.4)));
+  }
 }
@@ -1,35 +1,172 @@
 use ribir_core::prelude::*;
+
+use crate::{custom_paint::*, input::InputStyle};
+
+/// The width of the caret when it's drawn with a color rather than an icon.
+const CARET_WIDTH: f32 = 2.;
+
 #[derive(Declare)]
 pub struct Caret {
   pub focused: bool,
   #[declare(default = svgs::TEXT_CARET)]
   pub icon: NamedSvg,
+  /// The brush to draw the caret, the `icon` is used if it's `None`.
+  #[declare(default = InputStyle::of(ctx!()).caret_color)]
+  pub color: Option<Brush>,
+  /// The interval of the caret switch between visible and invisible. A zero
+  /// interval means the caret is always visible when focused, no blink.
+  #[declare(default = Duration::from_millis(500))]
+  pub blink_interval: Duration,
+  /// The easing of the caret fade in and out in the blink interval, it's a
+  /// hard on/off switch by default.
+  #[declare(
+    strict,
+    default = Box::new(easing::steps(1, easing::StepsJump::JumpStart)) as Box<dyn Easing>
+  )]
+  pub blink_easing: Box<dyn Easing>,
+}
+
+/// The transition read from the `blink_interval` and the `blink_easing` of the
+/// caret.
+struct CaretTransition<R>(R);
+
+impl<R: StateReader<Value = Caret>> Transition for CaretTransition<R> {
+  fn rate_of_change(&self, run_dur: Duration) -> AnimateProgress {
+    let caret = self.0.read();
+    let interval = caret.blink_interval;
+    if interval.is_zero() || run_dur > interval {
+      return AnimateProgress::Finish;
+    }
+    let time_rate = run_dur.as_secs_f32() / interval.as_secs_f32();
+    AnimateProgress::Between(caret.blink_easing.easing(time_rate))
+  }
+
+  fn duration(&self) -> Duration { self.0.read().blink_interval }
 }
 
 impl Compose for Caret {
   fn compose(this: impl StateWriter<Value = Self>) -> impl WidgetBuilder {
-    let blink_interval = Duration::from_millis(500);
+    let transition = CaretTransition(this.clone_reader());
     fn_widget! {
-      let icon = $this.icon;
-      let mut caret = @ $icon {
-        opacity: 0.,
-        box_fit: BoxFit::CoverY,
+      let color = $this.color.clone();
+      let content = match color {
+        Some(color) => @CustomPaint {
+          size_policy: SizePolicy::Fixed(Size::new(CARET_WIDTH, f32::MAX)),
+          painter: move |painter: &mut Painter, size: Size| {
+            painter.set_brush(color.clone()).rect(&Rect::from_size(size)).fill();
+          }
+        }.build(ctx!()),
+        None => {
+          let icon = $this.icon;
+          @ $icon { box_fit: BoxFit::CoverY }.build(ctx!())
+        }
       };
+      let mut caret = @ $content { opacity: 0. };
+      caret
+        .get_opacity_widget()
+        .map_writer(|w| PartData::from_ref_mut(&mut w.opacity))
+        .transition(transition.box_it(), ctx!());
       let mut _guard = None;
-      let u = watch!($this.focused)
-        .subscribe(move |focused| {
+      let u = watch!(($this.focused, $this.blink_interval))
+        .subscribe(move |(focused, blink_interval)| {
+          // Restart the blink, the interval may be changed.
+          _guard = None;
           if focused {
             $caret.write().opacity = 1.;
-            let unsub = interval(blink_interval, AppCtx::scheduler())
-              .subscribe(move |idx| $caret.write().opacity = (idx % 2) as f32)
-              .unsubscribe_when_dropped();
-            _guard = Some(unsub);
+            if !blink_interval.is_zero() {
+              let unsub = interval(blink_interval, AppCtx::scheduler())
+                .subscribe(move |idx| $caret.write().opacity = (idx % 2) as f32)
+                .unsubscribe_when_dropped();
+              _guard = Some(unsub);
+            }
           } else {
             $caret.write().opacity = 0.;
-            _guard = None;
           }
         });
       @ $caret { on_disposed: move |_| u.unsubscribe() }
     }
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use ribir_core::{reset_test_env, test_helper::*, timer::Timer};
+
+  use super::*;
+
+  #[test]
+  fn zero_interval_no_blink() {
+    reset_test_env!();
+
+    let before = Timer::recently_timeout();
+    let (size, w_size) = split_value(Size::new(10., 10.));
+    let (focused, w_focused) = split_value(false);
+    let mut wnd = TestWindow::new(fn_widget! {
+      @MockMulti {
+        // Resized to draw a new frame.
+        @MockBox { size: pipe!(*$size) }
+        @Caret {
+          focused: pipe!(*$focused),
+          blink_interval: Duration::ZERO,
+          color: Some(Color::RED.into()),
+        }
+      }
+    });
+    wnd.draw_frame();
+    *w_focused.write() = true;
+    wnd.draw_frame();
+    AppCtx::run_until_stalled();
+
+    // no blink timer was started.
+    assert_eq!(Timer::recently_timeout(), before);
+
+    // The caret is always visible.
+    for i in 1..=4 {
+      Timer::advance(Duration::from_millis(500));
+      AppCtx::run_until_stalled();
+      *w_size.write() = Size::new(10. + i as f32, 10.);
+      wnd.draw_frame();
+      let frame = wnd.take_last_frame().unwrap();
+      assert!(!frame.commands.is_empty());
+    }
+  }
+
+  #[test]
+  fn change_blink_interval() {
+    reset_test_env!();
+
+    let (size, w_size) = split_value(Size::new(10., 10.));
+    let (blink, w_blink) = split_value(Duration::ZERO);
+    let mut wnd = TestWindow::new(fn_widget! {
+      @MockMulti {
+        // Resized to draw a new frame.
+        @MockBox { size: pipe!(*$size) }
+        @Caret {
+          focused: true,
+          blink_interval: pipe!(*$blink),
+          color: Some(Color::RED.into()),
+        }
+      }
+    });
+    wnd.draw_frame();
+    AppCtx::run_until_stalled();
+    let before = Timer::recently_timeout();
+
+    *w_blink.write() = Duration::from_millis(100);
+    wnd.draw_frame();
+    AppCtx::run_until_stalled();
+    // The blink timer is started with the new interval.
+    assert_ne!(Timer::recently_timeout(), before);
+
+    let mut visible = vec![];
+    for i in 1..=4 {
+      Timer::advance(Duration::from_millis(100));
+      AppCtx::run_until_stalled();
+      *w_size.write() = Size::new(10. + i as f32, 10.);
+      wnd.draw_frame();
+      let frame = wnd.take_last_frame().unwrap();
+      visible.push(!frame.commands.is_empty());
+    }
+    assert_eq!(visible, [false, true, false, true]);
+  }
+}
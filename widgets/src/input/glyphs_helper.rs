@@ -212,7 +212,7 @@ mod tests {
     font_db::FontDB,
     shaper::TextShaper,
     typography::{PlaceLineDirection, TypographyCfg},
-    Em, FontFace, FontFamily, FontSize, Overflow, TextAlign, TypographyStore,
+    Em, FontFace, FontFamily, FontSize, Overflow, TextAlign, TextOverflow, TypographyStore,
   };
   use ribir_geom::Size;
 
@@ -237,6 +237,8 @@ mod tests {
       bounds: Size::new(Em::absolute(5.0), Em::absolute(3.0)),
       line_dir: PlaceLineDirection::TopToBottom,
       overflow: Overflow::AutoWrap,
+      max_lines: None,
+      text_overflow: TextOverflow::Clip,
     };
 
     let face =
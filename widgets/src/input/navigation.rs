@@ -0,0 +1,112 @@
+use ribir_core::prelude::{select_next_word, select_prev_word, CharacterCursor, GraphemeCursor};
+
+/// Return the byte offset of the grapheme boundary before `offset` in the
+/// `text`, so a grapheme cluster like an emoji ZWJ sequence, a character with
+/// combining marks or a CRLF is skipped as one unit.
+pub fn move_left(text: &str, offset: usize) -> usize {
+  let mut cursor = GraphemeCursor(offset.min(text.len()));
+  cursor.prev(text);
+  cursor.byte_offset()
+}
+
+/// Return the byte offset of the grapheme boundary after `offset` in the
+/// `text`, see [`move_left`].
+pub fn move_right(text: &str, offset: usize) -> usize {
+  let mut cursor = GraphemeCursor(offset.min(text.len()));
+  cursor.next(text);
+  cursor.byte_offset()
+}
+
+/// Return the byte offset of the grapheme boundary at or after `offset`, it's
+/// `offset` itself if it's a boundary.
+pub fn grapheme_ceil(text: &str, offset: usize) -> usize {
+  if offset == 0 { 0 } else { move_right(text, move_left(text, offset)) }
+}
+
+/// Return the byte offset of the grapheme boundary at or before `offset`, it's
+/// `offset` itself if it's a boundary.
+pub fn grapheme_floor(text: &str, offset: usize) -> usize {
+  if offset >= text.len() { text.len() } else { move_left(text, move_right(text, offset)) }
+}
+
+/// Return the byte offset of the start of the word before `offset`, the
+/// whitespaces between are skipped. The words are split by the Unicode word
+/// boundaries.
+pub fn move_word_left(text: &str, offset: usize) -> usize {
+  select_prev_word(text, offset.min(text.len()), true).start
+}
+
+/// Return the byte offset of the end of the word after `offset`, the
+/// whitespaces between are skipped. The words are split by the Unicode word
+/// boundaries.
+pub fn move_word_right(text: &str, offset: usize) -> usize {
+  select_next_word(text, offset.min(text.len()), true).end
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn zwj_emoji() {
+    let family = "👨‍👩‍👧";
+    let text = format!("a{family}b");
+    let emoji_end = 1 + family.len();
+
+    assert_eq!(move_right(&text, 0), 1);
+    assert_eq!(move_right(&text, 1), emoji_end);
+    assert_eq!(move_right(&text, emoji_end), text.len());
+    assert_eq!(move_right(&text, text.len()), text.len());
+
+    assert_eq!(move_left(&text, text.len()), emoji_end);
+    assert_eq!(move_left(&text, emoji_end), 1);
+    assert_eq!(move_left(&text, 1), 0);
+    assert_eq!(move_left(&text, 0), 0);
+  }
+
+  #[test]
+  fn combining_diacritics() {
+    // "e" with an acute accent, and "o" with a diaeresis and a macron.
+    let text = "e\u{0301}o\u{0308}\u{0304}x";
+    assert_eq!(move_right(text, 0), 3);
+    assert_eq!(move_right(text, 3), 8);
+    assert_eq!(move_right(text, 8), 9);
+
+    assert_eq!(move_left(text, 9), 8);
+    assert_eq!(move_left(text, 8), 3);
+    assert_eq!(move_left(text, 3), 0);
+  }
+
+  #[test]
+  fn crlf_as_one_position() {
+    let text = "a\r\nb";
+    assert_eq!(move_right(text, 1), 3);
+    assert_eq!(move_left(text, 3), 1);
+  }
+
+  #[test]
+  fn snap_to_boundary() {
+    let text = "ae\u{0301}x";
+    assert_eq!(grapheme_ceil(text, 0), 0);
+    assert_eq!(grapheme_ceil(text, 1), 1);
+    assert_eq!(grapheme_ceil(text, 2), 4);
+    assert_eq!(grapheme_ceil(text, 5), 5);
+    assert_eq!(grapheme_floor(text, 0), 0);
+    assert_eq!(grapheme_floor(text, 2), 1);
+    assert_eq!(grapheme_floor(text, 4), 4);
+    assert_eq!(grapheme_floor(text, 5), 5);
+  }
+
+  #[test]
+  fn move_by_word() {
+    let text = "héllo,  wörld 👨‍👩‍👧!";
+    assert_eq!(move_word_right(text, 0), "héllo".len());
+    assert_eq!(move_word_right(text, "héllo".len()), "héllo,".len());
+    assert_eq!(move_word_right(text, "héllo,".len()), "héllo,  wörld".len());
+
+    assert_eq!(move_word_left(text, text.len()), text.len() - 1);
+    assert_eq!(move_word_left(text, "héllo,  wörld".len()), "héllo,  ".len());
+    assert_eq!(move_word_left(text, "héllo,  ".len()), "héllo".len());
+    assert_eq!(move_word_left(text, "héllo".len()), 0);
+  }
+}
@@ -0,0 +1,40 @@
+use ribir_core::prelude::*;
+
+use crate::layout::Stack;
+
+/// The underline of the composing text of the input method.
+#[derive(Declare)]
+pub(crate) struct PreEditUnderline {
+  pub(crate) rects: Vec<Rect>,
+}
+
+#[derive(Clone, PartialEq)]
+pub struct PreEditUnderlineStyle {
+  pub brush: Brush,
+  pub thickness: f32,
+}
+
+impl CustomStyle for PreEditUnderlineStyle {
+  fn default_style(ctx: &BuildCtx) -> Self {
+    PreEditUnderlineStyle { brush: Palette::of(ctx).on_surface().into(), thickness: 1. }
+  }
+}
+
+impl Compose for PreEditUnderline {
+  fn compose(this: impl StateWriter<Value = Self>) -> impl WidgetBuilder {
+    fn_widget! {
+      let thickness = PreEditUnderlineStyle::of(ctx!()).thickness;
+      @Stack {
+        @ { pipe!{
+          $this.rects.clone().into_iter().map(move |rc| {
+            @Container {
+              background: PreEditUnderlineStyle::pipe_of(ctx!(), |s| s.brush.clone()),
+              anchor: Anchor::left_top(rc.min_x(), rc.max_y() - thickness),
+              size: Size::new(rc.width(), thickness),
+            }
+          })
+        }}
+      }
+    }
+  }
+}
@@ -31,11 +31,54 @@ pub trait SelectableText {
 
   fn set_caret(&mut self, caret: CaretState);
 
+  /// Return the selected byte range of the text, or `None` if nothing is
+  /// selected.
+  fn selection(&self) -> Option<Range<usize>> {
+    let rg = self.select_range();
+    (!rg.is_empty()).then_some(rg)
+  }
+
+  /// Select the text from the byte offset `start` to `end`, the caret is
+  /// placed at `end`. The offsets are snapped to the grapheme boundaries, so
+  /// a grapheme cluster is always selected as a whole. If `start` equals
+  /// `end`, nothing is selected and the caret is placed at the start of the
+  /// grapheme the offset is in.
+  fn set_selection(&mut self, start: usize, end: usize) {
+    let rg = grapheme_snap(self.text(), start.min(end)..start.max(end));
+    let (begin, end) = if start == end {
+      (rg.start, rg.start)
+    } else if start < end {
+      (rg.start, rg.end)
+    } else {
+      (rg.end, rg.start)
+    };
+    let caret = if begin == end {
+      CaretPosition { cluster: end, position: None }.into()
+    } else {
+      CaretState::Select(
+        CaretPosition { cluster: begin, position: None },
+        CaretPosition { cluster: end, position: None },
+      )
+    };
+    self.set_caret(caret);
+  }
+
+  /// Select all the text.
+  fn select_all(&mut self) {
+    let len = self.text().len();
+    self.set_selection(0, len);
+  }
+
   fn select_text_rect(&self, text: &Text, text_size: Size) -> Vec<Rect> {
+    self.text_rects(text, text_size, &self.select_range())
+  }
+
+  /// Return the rects of the text in the byte range `rg`, one rect per line.
+  fn text_rects(&self, text: &Text, text_size: Size, rg: &Range<usize>) -> Vec<Rect> {
     let glyphs = text.text_layout(AppCtx::typography_store(), text_size);
     let helper = TextGlyphsHelper::new(text.text.clone(), glyphs);
     helper
-      .selection(self.text(), &self.select_range())
+      .selection(self.text(), rg)
       .unwrap_or_default()
   }
 
@@ -73,6 +116,8 @@ pub(crate) fn bind_point_listener<T: SelectableText>(
   fn_widget! {
     @$host {
       on_pointer_down: move |e| {
+        // Dragging selects the text rather than scrolls it.
+        e.prevent_default();
         let _hint_capture_reader = || $layout_box;
         let mut this = $this.write();
         let position = e.position();
@@ -210,18 +255,55 @@ fn deal_with_command<F: SelectableText>(
         let _ = clipboard.borrow_mut().write_text(&text);
       }
     }
-    PhysicalKey::Code(KeyCode::KeyA) => {
-      let len = this.read().text().len();
-      this.write().set_caret(CaretState::Select(
-        CaretPosition { cluster: 0, position: None },
-        CaretPosition { cluster: len, position: None },
-      ));
-    }
+    PhysicalKey::Code(KeyCode::KeyA) => this.write().select_all(),
     _ => return false,
   }
   true
 }
 
+/// Extend the byte range `rg` to the grapheme boundaries of the `text`.
+fn grapheme_snap(text: &str, rg: Range<usize>) -> Range<usize> {
+  let (start, end) = (rg.start.min(text.len()), rg.end.min(text.len()));
+  let mut cursor = GraphemeCursor(0);
+  let mut snap_start = 0;
+  loop {
+    if cursor.0 <= start {
+      snap_start = cursor.0;
+    }
+    if cursor.0 >= end || !cursor.next(text) {
+      break;
+    }
+  }
+  snap_start..cursor.0.max(snap_start)
+}
+
+/// Move the caret by `step` in the visual order of the glyphs until it leaves
+/// the cluster it's in, then snap it to the nearest grapheme boundary in the
+/// direction it moved. So the caret follows the visual order of a bidi text,
+/// but never stops inside a grapheme cluster.
+fn snap_to_grapheme(
+  text: &str, caret: CaretPosition, step: impl Fn(CaretPosition) -> Option<CaretPosition>,
+) -> Option<CaretPosition> {
+  let mut moved = step(caret)?;
+  while moved.cluster == caret.cluster {
+    match step(moved) {
+      Some(next) if next != moved => moved = next,
+      _ => return Some(moved),
+    }
+  }
+
+  let cluster = if moved.cluster > caret.cluster {
+    grapheme_ceil(text, moved.cluster)
+  } else {
+    grapheme_floor(text, moved.cluster)
+  };
+  if cluster == moved.cluster {
+    Some(moved)
+  } else {
+    Some(CaretPosition { cluster, position: None })
+  }
+}
+
 fn is_move_by_word(event: &KeyboardEvent) -> bool {
   #[cfg(target_os = "macos")]
   return event.with_alt_key();
@@ -243,22 +325,24 @@ fn deal_with_selection<F: SelectableText>(
   let new_caret_position = match event.key() {
     VirtualKey::Named(NamedKey::ArrowLeft) => {
       if is_move_by_word(event) {
-        let cluster = select_prev_word(&text, old_caret.cluster(), false).start;
+        let cluster = move_word_left(&text, old_caret.cluster());
         Some(CaretPosition { cluster, position: None })
       } else if event.with_command_key() {
         helper().line_begin(&text, old_caret.caret_position())
       } else {
-        helper().prev(&text, old_caret.caret_position())
+        let helper = helper();
+        snap_to_grapheme(&text, old_caret.caret_position(), |c| helper.prev(&text, c))
       }
     }
     VirtualKey::Named(NamedKey::ArrowRight) => {
       if is_move_by_word(event) {
-        let cluster = select_next_word(&text, old_caret.cluster(), true).end;
+        let cluster = move_word_right(&text, old_caret.cluster());
         Some(CaretPosition { cluster, position: None })
       } else if event.with_command_key() {
         helper().line_end(&text, old_caret.caret_position())
       } else {
-        helper().next(&text, old_caret.caret_position())
+        let helper = helper();
+        snap_to_grapheme(&text, old_caret.caret_position(), |c| helper.next(&text, c))
       }
     }
     VirtualKey::Named(NamedKey::ArrowUp) => helper().up(&text, old_caret.caret_position()),
@@ -282,3 +366,104 @@ fn deal_with_selection<F: SelectableText>(
     }
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn selectable(text: &str) -> TextSelectable {
+    TextSelectable { caret: CaretState::default(), text: text.to_string().into() }
+  }
+
+  #[test]
+  fn select_range_and_all() {
+    let mut this = selectable("hello world");
+    assert_eq!(this.selection(), None);
+
+    this.set_selection(6, 11);
+    assert_eq!(this.selection(), Some(6..11));
+    assert_eq!(&*this.selected_text(), "world");
+
+    // Backward selection keeps the caret at the end.
+    this.set_selection(5, 0);
+    assert_eq!(this.selection(), Some(0..5));
+    assert_eq!(this.caret().cluster(), 0);
+
+    this.select_all();
+    assert_eq!(this.selection(), Some(0..11));
+
+    this.set_selection(3, 3);
+    assert_eq!(this.selection(), None);
+    assert_eq!(this.caret().cluster(), 3);
+
+    this.set_selection(2, 2);
+    assert_eq!(this.selection(), None);
+    assert_eq!(this.caret().cluster(), 2);
+  }
+
+  #[test]
+  fn select_whole_grapheme() {
+    // A family emoji is a cluster of 3 emojis joined by zero width joiners.
+    let family = "\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}";
+    let text = format!("a{family}b");
+    let mut this = selectable(&text);
+    this.set_selection(0, 2);
+    assert_eq!(this.selection(), Some(0..1 + family.len()));
+
+    this.set_selection(3, 1 + family.len() + 1);
+    assert_eq!(this.selection(), Some(1..text.len()));
+
+    // A base character with a combining acute accent.
+    let mut this = selectable("e\u{301}x");
+    this.set_selection(0, 1);
+    assert_eq!(&*this.selected_text(), "e\u{301}");
+
+    // An empty selection in a grapheme is a caret at the start of it.
+    this.set_selection(2, 2);
+    assert_eq!(this.selection(), None);
+    assert_eq!(this.caret().cluster(), 0);
+    this.set_selection(3, 3);
+    assert_eq!(this.selection(), None);
+    assert_eq!(this.caret().cluster(), 3);
+
+    // Out of range offsets are clamped to the text.
+    this.set_selection(1, 100);
+    assert_eq!(this.selection(), Some(0..4));
+  }
+
+  fn caret(cluster: usize) -> CaretPosition { CaretPosition { cluster, position: None } }
+
+  #[test]
+  fn snap_visual_move_to_grapheme() {
+    // The caret moves by char in the logical order of "ae\u{301}x".
+    let text = "ae\u{301}x";
+    let chars = [0, 1, 2, 4, 5];
+    let idx = |c: CaretPosition| chars.iter().position(|i| *i == c.cluster).unwrap();
+    let forward = |c: CaretPosition| Some(caret(chars[(idx(c) + 1).min(4)]));
+    let backward = |c: CaretPosition| Some(caret(chars[idx(c).saturating_sub(1)]));
+    assert_eq!(snap_to_grapheme(text, caret(1), forward), Some(caret(4)));
+    assert_eq!(snap_to_grapheme(text, caret(4), backward), Some(caret(1)));
+    assert_eq!(snap_to_grapheme(text, caret(4), forward), Some(caret(5)));
+
+    // In a right-to-left text, the visual right arrow moves to the logical
+    // previous cluster, the move is kept and snapped backward.
+    let rtl_right = backward;
+    assert_eq!(snap_to_grapheme(text, caret(5), rtl_right), Some(caret(4)));
+    assert_eq!(snap_to_grapheme(text, caret(4), rtl_right), Some(caret(1)));
+  }
+
+  #[test]
+  fn keep_moving_in_the_same_cluster() {
+    // Two glyphs share the cluster 0, the caret moves over both of them.
+    let text = "ab";
+    let glyphs = [(0, 0), (0, 1), (1, 2), (2, 3)];
+    let pos = |(cluster, col): (usize, usize)| CaretPosition { cluster, position: Some((0, col)) };
+    let next = |c: CaretPosition| {
+      let col = c.position.map_or(0, |(_, col)| col);
+      glyphs.get(col + 1).copied().map(pos)
+    };
+    assert_eq!(snap_to_grapheme(text, pos(glyphs[0]), next), Some(pos(glyphs[2])));
+    // At the end, the caret stays.
+    assert_eq!(snap_to_grapheme(text, pos(glyphs[3]), next), None);
+  }
+}
@@ -27,10 +27,16 @@ pub use flex::*;
 pub use sized_box::SizedBox;
 pub mod expanded;
 pub use expanded::Expanded;
+mod flexible;
+pub use flexible::*;
 mod stack;
 pub use stack::*;
 pub mod constrained_box;
 pub use constrained_box::ConstrainedBox;
+mod aspect_ratio;
+pub use aspect_ratio::AspectRatio;
 pub mod only_sized_by_parent;
 pub use only_sized_by_parent::OnlySizedByParent;
+mod grid;
+pub use grid::*;
 pub use ribir_core::builtin_widgets::container::Container;
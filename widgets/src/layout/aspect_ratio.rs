@@ -0,0 +1,172 @@
+use ribir_core::prelude::*;
+
+/// A widget that sizes its child to a specific aspect ratio.
+///
+/// The box tries the largest width the clamp permits and derives the height
+/// from the `ratio`. If the width is unbounded, it tries the largest height
+/// instead. If both are unbounded, the size of the child is adjusted to the
+/// `ratio`.
+///
+/// # Example
+///
+/// ```no_run
+/// # use ribir_core::prelude::*;
+/// # use ribir_widgets::prelude::*;
+/// fn_widget! {
+///   @AspectRatio {
+///     ratio: 16. / 9.,
+///     @Container { size: Size::new(100., 100.) }
+///   }
+/// };
+/// ```
+#[derive(SingleChild, Declare, Clone)]
+pub struct AspectRatio {
+  /// The ratio of the width to the height, should be a finite number greater
+  /// than zero. Otherwise, the ratio is ignored and the child is laid out as
+  /// if there is no `AspectRatio`.
+  pub ratio: f32,
+}
+
+impl AspectRatio {
+  fn apply_ratio(&self, clamp: BoxClamp, preferred: Size) -> Size {
+    let BoxClamp { min, max } = clamp;
+    let ratio = self.ratio;
+    let mut size = if max.width.is_finite() {
+      Size::new(max.width, max.width / ratio)
+    } else if max.height.is_finite() {
+      Size::new(max.height * ratio, max.height)
+    } else if preferred.width > 0. {
+      Size::new(preferred.width, preferred.width / ratio)
+    } else {
+      Size::new(preferred.height * ratio, preferred.height)
+    };
+
+    if size.width > max.width {
+      size = Size::new(max.width, max.width / ratio);
+    }
+    if size.height > max.height {
+      size = Size::new(max.height * ratio, max.height);
+    }
+    if size.width < min.width {
+      size = Size::new(min.width, min.width / ratio);
+    }
+    if size.height < min.height {
+      size = Size::new(min.height * ratio, min.height);
+    }
+
+    // The ratio can't be satisfied in the clamp, the tighter dimension wins.
+    clamp.clamp(size)
+  }
+}
+
+impl Render for AspectRatio {
+  fn perform_layout(&self, clamp: BoxClamp, ctx: &mut LayoutCtx) -> Size {
+    if !(self.ratio.is_finite() && self.ratio > 0.) {
+      log::warn!("The ratio of `AspectRatio` should be greater than zero, but got {}.", self.ratio);
+      return ctx
+        .perform_single_child_layout(clamp)
+        .unwrap_or(clamp.min);
+    }
+
+    let preferred = if clamp.max.width.is_finite() || clamp.max.height.is_finite() {
+      Size::zero()
+    } else {
+      ctx
+        .perform_single_child_layout(clamp)
+        .unwrap_or_default()
+    };
+    let size = self.apply_ratio(clamp, preferred);
+    ctx.perform_single_child_layout(BoxClamp::fixed_size(size));
+    size
+  }
+
+  #[inline]
+  fn paint(&self, _: &mut PaintingCtx) {}
+}
+
+#[cfg(test)]
+mod tests {
+  use ribir_core::test_helper::*;
+  use ribir_dev_helper::*;
+
+  use super::*;
+  use crate::prelude::*;
+
+  fn tall_parent() -> impl WidgetBuilder {
+    fn_widget! {
+      @Container {
+        size: Size::new(160., 400.),
+        @AspectRatio { ratio: 16. / 9., @Void {} }
+      }
+    }
+  }
+  widget_layout_test!(
+    tall_parent,
+    { path = [0, 0], width == 160., height == 90.,}
+    { path = [0, 0, 0], width == 160., height == 90.,}
+  );
+
+  fn wide_parent() -> impl WidgetBuilder {
+    fn_widget! {
+      @Container {
+        size: Size::new(800., 90.),
+        @AspectRatio { ratio: 16. / 9., @Void {} }
+      }
+    }
+  }
+  widget_layout_test!(
+    wide_parent,
+    { path = [0, 0], width == 160., height == 90.,}
+  );
+
+  fn unbounded_parent() -> impl WidgetBuilder {
+    fn_widget! {
+      @UnconstrainedBox {
+        @AspectRatio {
+          ratio: 2.,
+          @Container { size: Size::new(100., 100.) }
+        }
+      }
+    }
+  }
+  widget_layout_test!(
+    unbounded_parent,
+    { path = [0, 0], width == 100., height == 50.,}
+  );
+
+  fn ratio_not_fit() -> impl WidgetBuilder {
+    fn_widget! {
+      @SizedBox {
+        size: Size::new(100., 100.),
+        @AspectRatio { ratio: 2., @Void {} }
+      }
+    }
+  }
+  widget_layout_test!(
+    ratio_not_fit,
+    { path = [0, 0], width == 100., height == 100.,}
+  );
+
+  fn invalid_ratio() -> impl WidgetBuilder {
+    fn_widget! {
+      @Container {
+        size: Size::new(160., 400.),
+        @Row {
+          @AspectRatio {
+            ratio: 0.,
+            @Container { size: Size::new(100., 100.) }
+          }
+          @AspectRatio {
+            ratio: -1.,
+            @Container { size: Size::new(50., 50.) }
+          }
+        }
+      }
+    }
+  }
+  widget_layout_test!(
+    invalid_ratio,
+    { path = [0, 0, 0], width == 100., height == 100.,}
+    { path = [0, 0, 1], width == 50., height == 50.,}
+  );
+}
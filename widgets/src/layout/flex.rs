@@ -1,6 +1,6 @@
 use ribir_core::prelude::{log::warn, *};
 
-use super::{Direction, Expanded};
+use super::{Direction, Expanded, FlexFit, Flexible};
 
 /// How the children should be placed along the main axis in a flex layout.
 #[derive(Debug, Copy, Clone, PartialEq, Default)]
@@ -54,6 +54,14 @@ pub struct Flex {
   /// Define item between gap in cross axis
   #[declare(default)]
   pub line_gap: f32,
+  /// The horizontal direction to place the children. In `RightToLeft`, the
+  /// children are mirrored horizontally, so the start of the horizontal axis
+  /// is the right edge. The default is the text direction of the theme.
+  #[declare(default = ctx!().text_direction())]
+  pub text_direction: TextDirection,
+  /// Clip the children that overflow the bounds of the flex.
+  #[declare(default)]
+  pub clip_overflow: bool,
 }
 
 /// A type help to declare flex widget as horizontal.
@@ -81,7 +89,16 @@ impl Render for Flex {
     let max_size = FlexSize::from_size(clamp.max, direction);
     let mut min_size = FlexSize::from_size(clamp.min, direction);
     if Align::Stretch == self.align_items {
-      min_size.cross = max_size.cross;
+      min_size.cross = if max_size.cross.is_finite() {
+        max_size.cross
+      } else {
+        // Stretch to the widest child if the cross axis is unbounded.
+        let cross = match direction {
+          Direction::Horizontal => ctx.children_max_intrinsic_height(f32::INFINITY),
+          Direction::Vertical => ctx.children_max_intrinsic_width(f32::INFINITY),
+        };
+        cross.max(min_size.cross)
+      };
     }
     let mut layouter = FlexLayouter {
       max: max_size,
@@ -93,14 +110,19 @@ impl Render for Flex {
       wrap: self.wrap,
       main_axis_gap: self.item_gap,
       cross_axis_gap: self.line_gap,
+      rtl: self.text_direction == TextDirection::RightToLeft,
       current_line: <_>::default(),
       lines: vec![],
     };
     layouter.layout(ctx)
   }
 
-  #[inline]
-  fn paint(&self, _: &mut PaintingCtx) {}
+  fn paint(&self, ctx: &mut PaintingCtx) {
+    if self.clip_overflow {
+      let rect = Rect::from_size(ctx.box_size().unwrap());
+      ctx.painter().clip(Path::rect(&rect));
+    }
+  }
 }
 
 #[derive(Debug, Clone, Copy, Default)]
@@ -146,6 +168,7 @@ struct FlexLayouter {
   lines: Vec<MainLineInfo>,
   main_axis_gap: f32,
   cross_axis_gap: f32,
+  rtl: bool,
 }
 
 impl FlexLayouter {
@@ -198,19 +221,18 @@ impl FlexLayouter {
       let size = FlexSize::from_size(size, dir);
 
       let mut flex = None;
-      l.query_type(|expanded: &Expanded| flex = Some(expanded.flex));
+      l.query_type(|expanded: &Expanded| flex = Some(FlexItem::expanded(expanded)));
+      l.query_type(|flexible: &Flexible| flex = Some(FlexItem::flexible(flexible)));
+      // A zero flex child is laid out as a normal child.
+      let flex = flex.filter(|f| f.flex > 0.);
 
       // flex-item need use empty space to resize after all fixed widget performed
       // layout.
       let line = &mut self.current_line;
-      if let Some(flex) = flex {
-        // expanded child size is zero, it don't need calculate
-        if size.main > 0. {
-          line.flex_sum += flex;
-        }
+      if flex.is_some() {
         line.main_width += gap;
       } else {
-        if wrap && !line.is_empty() && line.main_width + size.main > max.main {
+        if wrap && !line.is_empty() && line.main_width + gap + size.main > max.main {
           self.place_line();
         } else {
           line.main_width += gap;
@@ -227,44 +249,54 @@ impl FlexLayouter {
         .push(FlexLayoutInfo { size, flex, pos: <_>::default() });
 
       layouter = l.into_next_sibling();
-      if layouter.is_some() && !FlexLayouter::is_space_layout(self.justify_content) {
-        gap = self.main_axis_gap;
-      } else {
-        gap = 0.;
-      }
+      // The gap is the minimum space between items, the space layouts will
+      // distribute the remaining space after the gaps.
+      gap = if layouter.is_some() { self.main_axis_gap } else { 0. };
     }
     self.place_line();
   }
 
-  fn is_space_layout(justify_content: JustifyContent) -> bool {
-    matches!(
-      justify_content,
-      JustifyContent::SpaceAround | JustifyContent::SpaceBetween | JustifyContent::SpaceEvenly
-    )
-  }
-
   fn flex_children_layout(&mut self, ctx: &mut LayoutCtx) {
     let mut layouter = ctx.first_child_layouter();
     self.lines.iter_mut().for_each(|line| {
-      let flex_unit = (self.max.main - line.main_width) / line.flex_sum;
-      line.items_info.iter_mut().for_each(|info| {
-        let mut l = layouter.take().unwrap();
-        if info.size.main > 0. {
-          if let Some(flex) = info.flex {
+      let free = self.max.main - line.main_width;
+      let items = line
+        .items_info
+        .iter()
+        .map(|info| {
+          info
+            .flex
+            .filter(|f| f.takes_space(info.size.main))
+        })
+        .collect::<Vec<_>>();
+      let shares = distribute_free_space(free, &items);
+      line
+        .items_info
+        .iter_mut()
+        .zip(items.iter().zip(shares))
+        .for_each(|(info, (item, share))| {
+          let mut l = layouter.take().unwrap();
+          if let (Some(item), Some(share)) = (item, share) {
             let &mut Self { mut max, mut min, dir, .. } = self;
-            let main = flex_unit * flex;
-            max.main = main;
-            min.main = main;
+            max.main = share;
+            min.main = match item.fit {
+              FlexFit::Tight => share,
+              FlexFit::Loose => item.min,
+            };
             let clamp = BoxClamp { max: max.to_size(dir), min: min.to_size(dir) };
             let size = l.perform_widget_layout(clamp);
             info.size = FlexSize::from_size(size, dir);
             line.main_width += info.size.main;
             line.cross_line_height = line.cross_line_height.max(info.size.cross);
           }
-        }
 
-        layouter = l.into_next_sibling();
-      });
+          layouter = l.into_next_sibling();
+        });
+
+      let overflow = line.main_width - self.max.main;
+      if overflow > 0.5 {
+        warn!("The children of the flex overflow its main axis by {overflow}.");
+      }
     });
   }
 
@@ -300,11 +332,17 @@ impl FlexLayouter {
       update_position!();
     }
 
+    let width = bound.to_size(*dir).width;
+    let rtl = self.rtl;
     let mut layouter = ctx.first_child_layouter();
     self.lines.iter_mut().for_each(|line| {
       line.items_info.iter_mut().for_each(|info| {
         let mut l = layouter.take().unwrap();
-        l.update_position(info.pos.to_size(*dir).to_vector().to_point());
+        let mut pos = info.pos.to_size(*dir).to_vector().to_point();
+        if rtl {
+          pos.x = width - pos.x - info.size.to_size(*dir).width;
+        }
+        l.update_position(pos);
         layouter = l.into_next_sibling();
       })
     });
@@ -323,14 +361,116 @@ impl FlexLayouter {
 struct MainLineInfo {
   main_width: f32,
   items_info: Vec<FlexLayoutInfo>,
-  flex_sum: f32,
   cross_line_height: f32,
 }
 
 struct FlexLayoutInfo {
   pos: FlexSize,
   size: FlexSize,
-  flex: Option<f32>,
+  flex: Option<FlexItem>,
+}
+
+/// The flex arguments of an `Expanded` or a `Flexible` child.
+#[derive(Clone, Copy)]
+struct FlexItem {
+  flex: f32,
+  fit: FlexFit,
+  min: f32,
+  max: f32,
+  /// An expanded child with an empty size keeps empty.
+  keep_empty: bool,
+}
+
+impl FlexItem {
+  fn expanded(expanded: &Expanded) -> Self {
+    Self { flex: expanded.flex, fit: FlexFit::Tight, min: 0., max: f32::INFINITY, keep_empty: true }
+  }
+
+  fn flexible(flexible: &Flexible) -> Self {
+    let min = flexible.min.max(0.);
+    Self {
+      flex: flexible.flex,
+      fit: flexible.fit,
+      min,
+      max: flexible.max.max(min),
+      keep_empty: false,
+    }
+  }
+
+  fn takes_space(&self, main: f32) -> bool { !self.keep_empty || main > 0. }
+
+  fn clamp(&self, main: f32) -> f32 { main.clamp(self.min, self.max) }
+}
+
+/// Distribute the `free` space to the flex items according to their flex
+/// factors. As the flexbox does, an item whose share violates its `min` or
+/// `max` is frozen at the bound, and the rest space is distributed again among
+/// the others. So the shares are never negative even if the `free` is.
+///
+/// The last item takes the remainder of the space, so the rounding error of
+/// the shares does not leave a gap.
+fn distribute_free_space(free: f32, items: &[Option<FlexItem>]) -> Vec<Option<f32>> {
+  let mut shares: Vec<Option<f32>> = vec![None; items.len()];
+  loop {
+    let mut rest = free;
+    let mut flex_sum = 0.;
+    items
+      .iter()
+      .zip(&shares)
+      .for_each(|(item, share)| match (item, share) {
+        (Some(_), Some(share)) => rest -= share,
+        (Some(item), None) => flex_sum += item.flex,
+        _ => {}
+      });
+    if flex_sum <= 0. {
+      return shares;
+    }
+
+    let unit = rest / flex_sum;
+    let violation = |item: &FlexItem| {
+      let share = unit * item.flex;
+      let clamped = item.clamp(share);
+      if clamped == share { 0. } else { clamped - share }
+    };
+    let unfrozen = || {
+      items
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, item)| {
+          item
+            .filter(|_| shares[idx].is_none())
+            .map(|item| (idx, item))
+        })
+    };
+    if unfrozen().all(|(_, item)| violation(&item) == 0.) {
+      let unfrozen = unfrozen().collect::<Vec<_>>();
+      let last = unfrozen.len() - 1;
+      let mut allocated = 0.;
+      unfrozen
+        .into_iter()
+        .enumerate()
+        .for_each(|(i, (idx, item))| {
+          let share =
+            if i == last && rest.is_finite() { rest - allocated } else { unit * item.flex };
+          allocated += share;
+          shares[idx] = Some(share);
+        });
+      return shares;
+    }
+
+    // Freeze the items at their min if the total violation is positive, at
+    // their max if it's negative, or all the violated items if it's zero.
+    let total: f32 = unfrozen().map(|(_, item)| violation(&item)).sum();
+    let frozen = unfrozen()
+      .filter(|(_, item)| {
+        let v = violation(item);
+        v != 0. && v * total >= 0.
+      })
+      .collect::<Vec<_>>();
+    frozen
+      .into_iter()
+      .for_each(|(idx, item)| shares[idx] = Some(item.clamp(unit * item.flex)));
+  }
 }
 
 impl MainLineInfo {
@@ -348,15 +488,15 @@ impl MainLineInfo {
       JustifyContent::End => (main_max - self.main_width, gap),
       JustifyContent::SpaceAround => {
         let step = (main_max - self.main_width) / item_cnt;
-        (step / 2., step)
+        (step / 2., step + gap)
       }
       JustifyContent::SpaceBetween => {
         let step = (main_max - self.main_width) / (item_cnt - 1.);
-        (0., step)
+        (0., step + gap)
       }
       JustifyContent::SpaceEvenly => {
         let step = (main_max - self.main_width) / (item_cnt + 1.);
-        (step, step)
+        (step, step + gap)
       }
     }
   }
@@ -364,7 +504,7 @@ impl MainLineInfo {
 
 #[cfg(test)]
 mod tests {
-  use ribir_core::test_helper::*;
+  use ribir_core::{reset_test_env, test_helper::*};
   use ribir_dev_helper::*;
 
   use super::*;
@@ -493,6 +633,58 @@ mod tests {
     { path = [0, 4], rect == ribir_geom::rect(470., 0., 30., 20.),}
   );
 
+  fn column_gap() -> impl WidgetBuilder {
+    fn_widget! {
+      @Column {
+        item_gap: 10.,
+        @{ (0..4).map(|_| SizedBox { size: Size::new(20., 30.) }) }
+      }
+    }
+  }
+  widget_layout_test!(
+    column_gap,
+    wnd_size = Size::new(500., 500.),
+    { path = [0], size == Size::new(20., 150.),}
+    { path = [0, 3], y == 120.,}
+  );
+
+  fn space_between_gap() -> impl WidgetBuilder {
+    fn_widget! {
+      @Row {
+        item_gap: 10.,
+        justify_content: JustifyContent::SpaceBetween,
+        @SizedBox { size: Size::new(100., 20.) }
+        @SizedBox { size: Size::new(100., 20.) }
+        @SizedBox { size: Size::new(100., 20.) }
+      }
+    }
+  }
+  widget_layout_test!(
+    space_between_gap,
+    wnd_size = Size::new(500., 40.),
+    { path = [0, 1], x == 200.,}
+    { path = [0, 2], x == 400.,}
+  );
+
+  fn wrap_with_gap() -> impl WidgetBuilder {
+    let size = Size::new(200., 20.);
+    fn_widget! {
+      @Flex {
+        wrap: true,
+        item_gap: 10.,
+        line_gap: 5.,
+        @{ (0..3).map(|_| SizedBox { size }) }
+      }
+    }
+  }
+  widget_layout_test!(
+    wrap_with_gap,
+    wnd_size = Size::new(400., 500.),
+    { path = [0], size == Size::new(200., 70.),}
+    { path = [0, 1], rect == ribir_geom::rect(0., 25., 200., 20.),}
+    { path = [0, 2], rect == ribir_geom::rect(0., 50., 200., 20.),}
+  );
+
   fn cross_axis_gap() -> impl WidgetBuilder {
     let size = Size::new(200., 20.);
     fn_widget! {
@@ -664,4 +856,162 @@ mod tests {
     { path = [0, 0, 0], rect == ribir_geom::rect(0., 0., 100., 25.),}
     { path = [0, 0, 2], rect == ribir_geom::rect(200., 0., 300., 25.),}
   );
+
+  fn flex_proportion() -> impl WidgetBuilder {
+    fn_widget! {
+      @SizedBox {
+        size: Size::new(300., 20.),
+        @Row {
+          @Expanded {
+            flex: 2.,
+            @SizedBox { size: INFINITY_SIZE }
+          }
+          @Expanded {
+            flex: 1.,
+            @SizedBox { size: INFINITY_SIZE }
+          }
+          @Expanded {
+            flex: 0.,
+            @SizedBox { size: Size::new(30., 20.) }
+          }
+        }
+      }
+    }
+  }
+  widget_layout_test!(
+    flex_proportion,
+    wnd_size = Size::new(500., 500.),
+    { path = [0, 0, 0], rect == ribir_geom::rect(0., 0., 180., 20.),}
+    { path = [0, 0, 1], rect == ribir_geom::rect(180., 0., 90., 20.),}
+    { path = [0, 0, 2], rect == ribir_geom::rect(270., 0., 30., 20.),}
+  );
+
+  #[test]
+  fn flex_remainder_to_last() {
+    reset_test_env!();
+
+    let w = fn_widget! {
+      @SizedBox {
+        size: Size::new(100., 20.),
+        @Row {
+          @Expanded { @SizedBox { size: INFINITY_SIZE } }
+          @Expanded { @SizedBox { size: INFINITY_SIZE } }
+          @Expanded { @SizedBox { size: INFINITY_SIZE } }
+        }
+      }
+    };
+    let mut wnd = TestWindow::new(w);
+    wnd.draw_frame();
+
+    let last = wnd.layout_info_by_path(&[0, 0, 2]).unwrap();
+    assert_eq!(last.pos.x + last.size.unwrap().width, 100.);
+  }
+
+  #[test]
+  fn stretch_to_intrinsic_width() {
+    reset_test_env!();
+
+    let mut wnd = TestWindow::new(fn_widget! { @Text { text: "Hello Ribir" } });
+    wnd.draw_frame();
+    let text_width = wnd
+      .layout_info_by_path(&[0])
+      .unwrap()
+      .size
+      .unwrap()
+      .width;
+
+    let w = fn_widget! {
+      @UnconstrainedBox {
+        dir: UnconstrainedDir::X,
+        @Column {
+          align_items: Align::Stretch,
+          @Text { text: "Hello Ribir" }
+          @SizedBox { size: Size::new(10., 10.) }
+        }
+      }
+    };
+    let mut wnd = TestWindow::new(w);
+    wnd.draw_frame();
+
+    let column = wnd.layout_info_by_path(&[0, 0]).unwrap();
+    assert_eq!(column.size.unwrap().width, text_width);
+    let sized_box = wnd.layout_info_by_path(&[0, 0, 1]).unwrap();
+    assert_eq!(sized_box.size.unwrap().width, text_width);
+  }
+
+  #[test]
+  fn rtl_mirror_children() {
+    reset_test_env!();
+
+    let x_of = |text_direction: TextDirection| {
+      let w = fn_widget! {
+        @SizedBox {
+          size: Size::new(100., 20.),
+          @Row {
+            text_direction,
+            @SizedBox { size: Size::new(10., 10.) }
+            @SizedBox { size: Size::new(20., 10.) }
+          }
+        }
+      };
+      let mut wnd = TestWindow::new(w);
+      wnd.draw_frame();
+      let x = |idx| {
+        wnd
+          .layout_info_by_path(&[0, 0, idx])
+          .unwrap()
+          .pos
+          .x
+      };
+      (x(0), x(1))
+    };
+
+    assert_eq!(x_of(TextDirection::LeftToRight), (0., 10.));
+    // The start is the right edge.
+    assert_eq!(x_of(TextDirection::RightToLeft), (90., 70.));
+  }
+
+  #[test]
+  fn nested_text_direction() {
+    reset_test_env!();
+
+    let rtl = InheritTheme { text_direction: Some(TextDirection::RightToLeft), ..<_>::default() };
+    let w = fn_widget! {
+      @ThemeWidget {
+        theme: Theme::Inherit(rtl),
+        @ {
+          Box::new(fn_widget! {
+            @Column {
+              @Row {
+                @SizedBox { size: Size::new(10., 10.) }
+                @SizedBox { size: Size::new(20., 10.) }
+              }
+              @ThemeWidget {
+                theme: Theme::Inherit(InheritTheme {
+                  text_direction: Some(TextDirection::LeftToRight),
+                  ..<_>::default()
+                }),
+                @ {
+                  Box::new(fn_widget! {
+                    @Row {
+                      @SizedBox { size: Size::new(10., 10.) }
+                      @SizedBox { size: Size::new(20., 10.) }
+                    }
+                  })
+                }
+              }
+            }
+          })
+        }
+      }
+    };
+    let mut wnd = TestWindow::new(w);
+    wnd.draw_frame();
+    let x = |path: &[usize]| wnd.layout_info_by_path(path).unwrap().pos.x;
+
+    // The row of the rtl page.
+    assert_eq!((x(&[0, 0, 0, 0]), x(&[0, 0, 0, 1])), (20., 0.));
+    // The ltr island inside the rtl page.
+    assert_eq!((x(&[0, 0, 1, 0, 0]), x(&[0, 0, 1, 0, 1])), (0., 10.));
+  }
 }
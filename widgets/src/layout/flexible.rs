@@ -0,0 +1,157 @@
+use ribir_core::prelude::*;
+
+use super::ConstrainedBox;
+
+/// How a [`Flexible`] child fills the space allocated to it by the `Flex`.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum FlexFit {
+  /// The child can be smaller than the allocated space, but not smaller than
+  /// the `min` of the `Flexible`.
+  #[default]
+  Loose,
+  /// The child is forced to fill the allocated space.
+  Tight,
+}
+
+/// A widget that makes a child of `Flex` flexible. The free space of a line is
+/// divided among the flexible and the expanded children according to their
+/// flex factors, and the share of a flexible child is clamped by its `min` and
+/// `max` along the main axis.
+///
+/// If the sum of the minimums is larger than the free space, the children keep
+/// their minimums and overflow the `Flex`, the overflow is warned and can be
+/// clipped by the `clip_overflow` of the `Flex`.
+#[derive(Clone, PartialEq, Declare)]
+pub struct Flexible {
+  #[declare(default = 1.)]
+  pub flex: f32,
+  #[declare(default)]
+  pub fit: FlexFit,
+  /// The min size of the child along the main axis.
+  #[declare(default)]
+  pub min: f32,
+  /// The max size of the child along the main axis.
+  #[declare(default = f32::INFINITY)]
+  pub max: f32,
+}
+
+impl ComposeChild for Flexible {
+  type Child = Widget;
+  #[inline]
+  fn compose_child(this: impl StateWriter<Value = Self>, child: Self::Child) -> impl WidgetBuilder {
+    fn_widget! {
+      @ConstrainedBox {
+        clamp: BoxClamp {
+          min: Size::new(0., 0.),
+          max: Size::new(f32::INFINITY, f32::INFINITY)
+        },
+        @{ child }
+      }
+      .try_unwrap_state_and_attach(this, ctx!())
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use ribir_core::{reset_test_env, test_helper::*};
+  use ribir_dev_helper::*;
+
+  use super::*;
+  use crate::prelude::*;
+
+  fn loose_and_tight() -> impl WidgetBuilder {
+    let size = Size::new(100., 50.);
+    fn_widget! {
+      @Row {
+        @Flexible { @SizedBox { size } }
+        @Flexible {
+          fit: FlexFit::Tight,
+          @SizedBox { size }
+        }
+      }
+    }
+  }
+  widget_layout_test!(
+    loose_and_tight,
+    wnd_size = Size::new(500., 500.),
+    { path = [0, 0], rect == ribir_geom::rect(0., 0., 100., 50.),}
+    { path = [0, 1], rect == ribir_geom::rect(100., 0., 250., 50.),}
+  );
+
+  fn clamp_by_max() -> impl WidgetBuilder {
+    let size = Size::new(100., 50.);
+    fn_widget! {
+      @Row {
+        @Flexible {
+          fit: FlexFit::Tight,
+          max: 100.,
+          @SizedBox { size }
+        }
+        @Flexible {
+          fit: FlexFit::Tight,
+          @SizedBox { size }
+        }
+      }
+    }
+  }
+  widget_layout_test!(
+    clamp_by_max,
+    wnd_size = Size::new(500., 500.),
+    { path = [0, 0], rect == ribir_geom::rect(0., 0., 100., 50.),}
+    { path = [0, 1], rect == ribir_geom::rect(100., 0., 400., 50.),}
+  );
+
+  fn min_overflow() -> impl WidgetBuilder {
+    let size = Size::new(200., 50.);
+    fn_widget! {
+      @Row {
+        @SizedBox { size }
+        @Flexible {
+          fit: FlexFit::Tight,
+          min: 150.,
+          @SizedBox { size }
+        }
+        @Flexible {
+          fit: FlexFit::Tight,
+          min: 100.,
+          @SizedBox { size }
+        }
+      }
+    }
+  }
+  widget_layout_test!(
+    min_overflow,
+    wnd_size = Size::new(300., 500.),
+    { path = [0], width == 300.,}
+    { path = [0, 1], rect == ribir_geom::rect(200., 0., 150., 50.),}
+    { path = [0, 2], rect == ribir_geom::rect(350., 0., 100., 50.),}
+  );
+
+  #[test]
+  fn clip_overflow() {
+    reset_test_env!();
+
+    let w = fn_widget! {
+      @Row {
+        clip_overflow: true,
+        @SizedBox { size: Size::new(200., 50.) }
+        @Flexible {
+          min: 150.,
+          // The painter drops the clip if nothing is drawn in it.
+          @SizedBox { size: Size::new(200., 50.), background: Color::RED }
+        }
+      }
+    };
+    let mut wnd = TestWindow::new_with_size(w, Size::new(300., 500.));
+    wnd.draw_frame();
+    let frame = wnd.take_last_frame().unwrap();
+    let clip = frame.commands.iter().find_map(|cmd| match cmd {
+      PaintCommand::Path(PathCommand { action: PaintPathAction::Clip, paint_bounds, .. }) => {
+        Some(*paint_bounds)
+      }
+      _ => None,
+    });
+    assert_eq!(clip, Some(Rect::from_size(Size::new(300., 50.))));
+  }
+}
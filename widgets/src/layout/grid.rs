@@ -0,0 +1,382 @@
+use ribir_core::prelude::*;
+
+/// A widget lays out its children in a two-dimensional grid.
+///
+/// The columns and rows are defined by the [`GridTrack`]s, and a child is
+/// placed in the cell by wrapping it with a [`GridItem`], a child not wrapped
+/// is placed in the first cell. A child is laid out in the box of the cells it
+/// spans and aligned to the left top.
+///
+/// The tracks are resolved in the order like the CSS grid: the `Fixed` tracks
+/// first, then the `Auto` tracks are sized by the children in them, at last,
+/// the remaining space is distributed to the `Flex` tracks by their factors.
+/// The columns are resolved before the rows, so a child is measured in the
+/// width of its columns when sizing the rows.
+///
+/// # Example
+///
+/// ```
+/// # use ribir_core::prelude::*;
+/// # use ribir_widgets::prelude::*;
+///
+/// fn_widget! {
+///   @Grid {
+///     columns: vec![GridTrack::Fixed(100.), GridTrack::Flex(1.)],
+///     rows: vec![GridTrack::Auto, GridTrack::Auto],
+///     @GridItem {
+///       row: 0usize, col: 0usize, col_span: 2usize,
+///       @Text { text: "Title" }
+///     }
+///     @GridItem {
+///       row: 1usize, col: 1usize,
+///       @Text { text: "Content" }
+///     }
+///   }
+/// };
+/// ```
+#[derive(MultiChild, Declare)]
+pub struct Grid {
+  /// The tracks of the columns, from left to right.
+  #[declare(default)]
+  pub columns: Vec<GridTrack>,
+  /// The tracks of the rows, from top to bottom.
+  #[declare(default)]
+  pub rows: Vec<GridTrack>,
+}
+
+/// How to size a column or a row of the [`Grid`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GridTrack {
+  /// The track has a fixed size in pixels.
+  Fixed(f32),
+  /// The track takes the share of the remaining space by its factor, after
+  /// the `Fixed` and `Auto` tracks are sized.
+  Flex(f32),
+  /// The track is sized to fit the children in it.
+  Auto,
+}
+
+/// A widget that places its child in the cells of the parent [`Grid`].
+#[derive(Clone, PartialEq, Declare)]
+pub struct GridItem {
+  /// The index of the first row the child placed in.
+  #[declare(default)]
+  pub row: usize,
+  /// The index of the first column the child placed in.
+  #[declare(default)]
+  pub col: usize,
+  /// How many rows the child spans.
+  #[declare(default = 1usize)]
+  pub row_span: usize,
+  /// How many columns the child spans.
+  #[declare(default = 1usize)]
+  pub col_span: usize,
+}
+
+impl ComposeChild for GridItem {
+  type Child = Widget;
+  #[inline]
+  fn compose_child(this: impl StateWriter<Value = Self>, child: Self::Child) -> impl WidgetBuilder {
+    fn_widget! { child.try_unwrap_state_and_attach(this, ctx!()) }
+  }
+}
+
+/// The tracks a child spans in an axis.
+#[derive(Clone, Copy)]
+struct Span {
+  start: usize,
+  end: usize,
+}
+
+impl Render for Grid {
+  fn perform_layout(&self, clamp: BoxClamp, ctx: &mut LayoutCtx) -> Size {
+    let mut spans = vec![];
+    let mut layouter = ctx.first_child_layouter();
+    while let Some(l) = layouter {
+      let item = l
+        .query_type(|item: &GridItem| item.clone())
+        .unwrap_or_default();
+      let col = Span::new(item.col, item.col_span, self.columns.len());
+      let row = Span::new(item.row, item.row_span, self.rows.len());
+      spans.push((col, row));
+      layouter = l.skip_to_next_sibling();
+    }
+
+    // Resolve the columns with the widths of the children.
+    let mut sizes = Vec::with_capacity(spans.len());
+    let mut layouter = ctx.first_child_layouter();
+    for (col, row) in spans.iter() {
+      let mut l = layouter.unwrap();
+      let size = col
+        .is_measured(&self.columns, clamp.max.width)
+        .then(|| {
+          let max_width = col
+            .fixed_size(&self.columns)
+            .unwrap_or(f32::INFINITY);
+          let max_height = row
+            .fixed_size(&self.rows)
+            .unwrap_or(f32::INFINITY);
+          let max = Size::new(max_width, max_height);
+          l.perform_widget_layout(BoxClamp { min: ZERO_SIZE, max })
+            .width
+        });
+      sizes.push((*col, size));
+      layouter = l.skip_to_next_sibling();
+    }
+    let columns = resolve_tracks(&self.columns, &sizes, clamp.max.width);
+
+    // Resolve the rows with the heights of the children in their columns.
+    sizes.clear();
+    let mut layouter = ctx.first_child_layouter();
+    for (col, row) in spans.iter() {
+      let mut l = layouter.unwrap();
+      let size = row
+        .is_measured(&self.rows, clamp.max.height)
+        .then(|| {
+          let max_width = col.track_size(&columns);
+          let max_height = row
+            .fixed_size(&self.rows)
+            .unwrap_or(f32::INFINITY);
+          let max = Size::new(max_width, max_height);
+          l.perform_widget_layout(BoxClamp { min: ZERO_SIZE, max })
+            .height
+        });
+      sizes.push((*row, size));
+      layouter = l.skip_to_next_sibling();
+    }
+    let rows = resolve_tracks(&self.rows, &sizes, clamp.max.height);
+
+    // Place the children in their cells.
+    let col_offsets = track_offsets(&columns);
+    let row_offsets = track_offsets(&rows);
+    let mut layouter = ctx.first_child_layouter();
+    for (col, row) in spans.iter() {
+      let mut l = layouter.unwrap();
+      let max = Size::new(col.track_size(&columns), row.track_size(&rows));
+      l.perform_widget_layout(BoxClamp { min: ZERO_SIZE, max });
+      l.update_position(Point::new(col_offsets[col.start], row_offsets[row.start]));
+      layouter = l.into_next_sibling();
+    }
+
+    let size = Size::new(columns.iter().sum(), rows.iter().sum());
+    clamp.clamp(size)
+  }
+
+  fn paint(&self, _: &mut PaintingCtx) {
+    // nothing to paint.
+  }
+}
+
+impl Default for GridItem {
+  fn default() -> Self { Self { row: 0, col: 0, row_span: 1, col_span: 1 } }
+}
+
+impl Span {
+  /// Create a span in `cnt` tracks, the out of range part is clamped.
+  fn new(start: usize, span: usize, cnt: usize) -> Self {
+    let start = start.min(cnt.saturating_sub(1));
+    let end = (start + span.max(1)).min(cnt).max(start);
+    Self { start, end }
+  }
+
+  fn tracks<'a>(&self, tracks: &'a [GridTrack]) -> &'a [GridTrack] { &tracks[self.start..self.end] }
+
+  fn track_size(&self, sizes: &[f32]) -> f32 { sizes[self.start..self.end].iter().sum() }
+
+  /// The size of the tracks if all of them are `Fixed`.
+  fn fixed_size(&self, tracks: &[GridTrack]) -> Option<f32> {
+    self
+      .tracks(tracks)
+      .iter()
+      .map(|t| if let GridTrack::Fixed(px) = t { Some(*px) } else { None })
+      .sum()
+  }
+
+  /// Whether the child of this span contributes to the size of its tracks. A
+  /// child spans a `Flex` track only contributes when the space is infinite.
+  fn is_measured(&self, tracks: &[GridTrack], available: f32) -> bool {
+    let tracks = self.tracks(tracks);
+    let has_flex = tracks
+      .iter()
+      .any(|t| matches!(t, GridTrack::Flex(_)));
+    let has_auto = tracks.iter().any(|t| t == &GridTrack::Auto);
+    if has_flex { available.is_infinite() } else { has_auto }
+  }
+}
+
+/// Resolve the size of the `tracks` by the sizes of the children in them.
+fn resolve_tracks(
+  tracks: &[GridTrack], children: &[(Span, Option<f32>)], available: f32,
+) -> Vec<f32> {
+  let mut sizes: Vec<f32> = tracks
+    .iter()
+    .map(|t| if let GridTrack::Fixed(px) = t { *px } else { 0. })
+    .collect();
+
+  // The `Auto` tracks are sized by the children not spanning a `Flex` track,
+  // and the children spanning fewer tracks first.
+  let mut auto_children: Vec<_> = children
+    .iter()
+    .filter_map(|(span, size)| size.map(|size| (*span, size)))
+    .filter(|(span, _)| {
+      span
+        .tracks(tracks)
+        .iter()
+        .all(|t| !matches!(t, GridTrack::Flex(_)))
+    })
+    .collect();
+  auto_children.sort_by_key(|(span, _)| span.end - span.start);
+  for (span, size) in auto_children {
+    let autos: Vec<_> = (span.start..span.end)
+      .filter(|i| tracks[*i] == GridTrack::Auto)
+      .collect();
+    let extra = size - span.track_size(&sizes);
+    if extra > 0. && !autos.is_empty() {
+      let share = extra / autos.len() as f32;
+      autos.iter().for_each(|i| sizes[*i] += share);
+    }
+  }
+
+  let flex_sum: f32 = tracks
+    .iter()
+    .map(|t| if let GridTrack::Flex(f) = t { f.max(0.) } else { 0. })
+    .sum();
+  if flex_sum > 0. {
+    let unit = if available.is_finite() {
+      (available - sizes.iter().sum::<f32>()).max(0.) / flex_sum
+    } else {
+      // No space to distribute, the `Flex` tracks are sized to fit the children
+      // in them, and keep the ratio of their factors.
+      children
+        .iter()
+        .filter_map(|(span, size)| {
+          let factor: f32 = span
+            .tracks(tracks)
+            .iter()
+            .map(|t| if let GridTrack::Flex(f) = t { f.max(0.) } else { 0. })
+            .sum();
+          (factor > 0.).then(|| (size.unwrap_or(0.) - span.track_size(&sizes)) / factor)
+        })
+        .fold(0., f32::max)
+    };
+    tracks
+      .iter()
+      .zip(sizes.iter_mut())
+      .for_each(|(t, s)| {
+        if let GridTrack::Flex(f) = t {
+          *s = f.max(0.) * unit;
+        }
+      });
+  }
+
+  sizes
+}
+
+fn track_offsets(sizes: &[f32]) -> Vec<f32> {
+  let mut offsets = Vec::with_capacity(sizes.len() + 1);
+  let mut offset = 0.;
+  for s in sizes {
+    offsets.push(offset);
+    offset += s;
+  }
+  offsets.push(offset);
+  offsets
+}
+
+#[cfg(test)]
+mod tests {
+  use ribir_core::test_helper::*;
+  use ribir_dev_helper::*;
+
+  use super::*;
+  use crate::prelude::*;
+
+  fn fixed_and_flex() -> impl WidgetBuilder {
+    fn_widget! {
+      @Grid {
+        columns: vec![GridTrack::Fixed(100.), GridTrack::Flex(1.)],
+        rows: vec![GridTrack::Fixed(50.), GridTrack::Flex(1.)],
+        @GridItem {
+          row: 0usize, col: 0usize,
+          @SizedBox { size: Size::new(20., 20.) }
+        }
+        @GridItem {
+          row: 0usize, col: 1usize,
+          @Container { size: INFINITY_SIZE }
+        }
+        @GridItem {
+          row: 1usize, col: 0usize,
+          @Container { size: INFINITY_SIZE }
+        }
+        @GridItem {
+          row: 1usize, col: 1usize,
+          @SizedBox { size: Size::new(30., 30.) }
+        }
+      }
+    }
+  }
+  widget_layout_test!(
+    fixed_and_flex,
+    wnd_size = Size::new(400., 300.),
+    { path = [0], size == Size::new(400., 300.),}
+    { path = [0, 0], rect == ribir_geom::rect(0., 0., 20., 20.),}
+    { path = [0, 1], rect == ribir_geom::rect(100., 0., 300., 50.),}
+    { path = [0, 2], rect == ribir_geom::rect(0., 50., 100., 250.),}
+    { path = [0, 3], rect == ribir_geom::rect(100., 50., 30., 30.),}
+  );
+
+  fn auto_tracks() -> impl WidgetBuilder {
+    fn_widget! {
+      @Grid {
+        columns: vec![GridTrack::Auto, GridTrack::Flex(1.), GridTrack::Flex(3.)],
+        rows: vec![GridTrack::Auto, GridTrack::Auto],
+        @GridItem {
+          row: 0usize, col: 0usize,
+          @SizedBox { size: Size::new(60., 20.) }
+        }
+        @GridItem {
+          row: 1usize, col: 0usize,
+          @SizedBox { size: Size::new(40., 30.) }
+        }
+        @GridItem {
+          row: 0usize, col: 1usize, col_span: 2usize,
+          @Container { size: Size::new(f32::INFINITY, 10.) }
+        }
+      }
+    }
+  }
+  widget_layout_test!(
+    auto_tracks,
+    wnd_size = Size::new(460., 300.),
+    { path = [0], size == Size::new(460., 50.),}
+    { path = [0, 1], rect == ribir_geom::rect(0., 20., 40., 30.),}
+    { path = [0, 2], rect == ribir_geom::rect(60., 0., 400., 10.),}
+  );
+
+  fn spanning_auto() -> impl WidgetBuilder {
+    fn_widget! {
+      @Grid {
+        columns: vec![GridTrack::Fixed(30.), GridTrack::Auto, GridTrack::Auto],
+        rows: vec![GridTrack::Auto],
+        @GridItem {
+          col: 1usize,
+          @SizedBox { size: Size::new(20., 10.) }
+        }
+        @GridItem {
+          col: 0usize, col_span: 3usize,
+          @SizedBox { size: Size::new(130., 10.) }
+        }
+        @GridItem {
+          col: 2usize,
+          @SizedBox { size: Size::new(10., 10.) }
+        }
+      }
+    }
+  }
+  widget_layout_test!(
+    spanning_auto,
+    wnd_size = Size::new(400., 300.),
+    { path = [0], size == Size::new(130., 10.),}
+    { path = [0, 2], rect == ribir_geom::rect(85., 0., 10., 10.),}
+  );
+}
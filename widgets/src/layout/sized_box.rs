@@ -1,5 +1,7 @@
 use ribir_core::prelude::*;
 
+use super::ConstrainedBox;
+
 /// A box with a specified size.
 ///
 /// This widget forces its child to have a specific width and/or height
@@ -10,6 +12,19 @@ pub struct SizedBox {
   pub size: Size,
 }
 
+impl SizedBox {
+  /// Create a box that only constrains its child between `min` and `max`
+  /// instead of a fixed size, the box is sized by its child.
+  ///
+  /// The bounds are intersected with the clamp from the parent, so an infinite
+  /// `max` means defer to the parent max, and a `min` larger than the parent
+  /// max will be clamped to the parent max.
+  #[inline]
+  pub fn constrained(min: Size, max: Size) -> ConstrainedBox {
+    ConstrainedBox { clamp: BoxClamp { min, max } }
+  }
+}
+
 impl Render for SizedBox {
   #[inline]
   fn perform_layout(&self, _: BoxClamp, ctx: &mut LayoutCtx) -> Size {
@@ -73,4 +88,32 @@ mod tests {
 
   fn empty_box() -> impl WidgetBuilder { fn_widget!(SizedBox { size: Size::new(10., 10.) }) }
   widget_layout_test!(empty_box, width == 10., height == 10.,);
+
+  fn constrained_infinite_max() -> impl WidgetBuilder {
+    fn_widget! {
+      let b = SizedBox::constrained(Size::new(0., 100.), Size::new(f32::INFINITY, 300.));
+      @$b {
+        @Container { size: Size::new(1000., 50.) }
+      }
+    }
+  }
+  widget_layout_test!(
+    constrained_infinite_max,
+    wnd_size = Size::new(500., 500.),
+    { path = [0], size == Size::new(500., 100.),}
+  );
+
+  fn constrained_min_over_parent() -> impl WidgetBuilder {
+    fn_widget! {
+      let b = SizedBox::constrained(Size::new(0., 800.), Size::new(f32::INFINITY, 1000.));
+      @$b {
+        @Container { size: Size::new(50., 50.) }
+      }
+    }
+  }
+  widget_layout_test!(
+    constrained_min_over_parent,
+    wnd_size = Size::new(500., 500.),
+    { path = [0], size == Size::new(50., 500.),}
+  );
 }
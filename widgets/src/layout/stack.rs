@@ -1,12 +1,45 @@
 use ribir_core::prelude::*;
 
 /// A widget that overlap children align with left top.
+///
+/// The children are painted in order, the last child is on the top. A child
+/// can be positioned by wrapping it with a [`StackPosition`]. The stack is
+/// sized by the non-positioned children, the positioned children are laid out
+/// after the stack size is determined.
 #[derive(MultiChild, Declare)]
 pub struct Stack {
   #[declare(default)]
   fit: StackFit,
 }
 
+/// A widget that positions its child in the parent [`Stack`].
+///
+/// If both the `left` and `right` are set, the child is stretched to fill the
+/// space between them, the same for the `top` and `bottom`. If neither edge of
+/// an axis is set, the child is aligned by the `x_align` or `y_align` of that
+/// axis.
+#[derive(Clone, Default, PartialEq, Declare)]
+pub struct StackPosition {
+  /// The distance between the left edge of the child and the stack.
+  #[declare(default)]
+  pub left: Option<f32>,
+  /// The distance between the top edge of the child and the stack.
+  #[declare(default)]
+  pub top: Option<f32>,
+  /// The distance between the right edge of the child and the stack.
+  #[declare(default)]
+  pub right: Option<f32>,
+  /// The distance between the bottom edge of the child and the stack.
+  #[declare(default)]
+  pub bottom: Option<f32>,
+  /// How to align the child horizontally if neither `left` nor `right` is set.
+  #[declare(default)]
+  pub x_align: HAlign,
+  /// How to align the child vertically if neither `top` nor `bottom` is set.
+  #[declare(default)]
+  pub y_align: VAlign,
+}
+
 /// How to size the non-positioned children of a [Stack]. (same as flutter)
 #[derive(Default)]
 pub enum StackFit {
@@ -50,13 +83,51 @@ impl Render for Stack {
       StackFit::Passthrough => clamp,
     };
 
+    // Lay out the non-positioned children to determine the stack size.
+    let mut positions = vec![];
     let mut size = ZERO_SIZE;
+    let mut has_flow_child = false;
     let mut layouter = ctx.first_child_layouter();
     while let Some(mut l) = layouter {
-      let child_size = l.perform_widget_layout(clamp);
-      size = size.max(child_size);
+      let pos = l.query_type(|p: &StackPosition| p.clone());
+      if !pos
+        .as_ref()
+        .map_or(false, StackPosition::is_positioned)
+      {
+        let child_size = l.perform_widget_layout(clamp);
+        size = size.max(child_size);
+        has_flow_child = true;
+      }
+      positions.push(pos);
+      layouter = l.skip_to_next_sibling();
+    }
+    if !has_flow_child {
+      let max = clamp.max;
+      size = if max.width.is_finite() && max.height.is_finite() { max } else { clamp.min };
+    }
+
+    let mut layouter = ctx.first_child_layouter();
+    let mut positions = positions.into_iter();
+    while let Some(mut l) = layouter {
+      if let Some(pos) = positions.next().flatten() {
+        let child_size = if pos.is_positioned() {
+          let (min_w, max_w) =
+            axis_clamp(pos.left, pos.right, pos.x_align == HAlign::Stretch, size.width);
+          let (min_h, max_h) =
+            axis_clamp(pos.top, pos.bottom, pos.y_align == VAlign::Stretch, size.height);
+          let clamp = BoxClamp { min: Size::new(min_w, min_h), max: Size::new(max_w, max_h) };
+          l.perform_widget_layout(clamp)
+        } else {
+          l.box_size().unwrap()
+        };
+        let x = axis_offset(pos.left, pos.right, pos.x_align.into(), child_size.width, size.width);
+        let y =
+          axis_offset(pos.top, pos.bottom, pos.y_align.into(), child_size.height, size.height);
+        l.update_position(Point::new(x, y));
+      }
       layouter = l.into_next_sibling();
     }
+
     size
   }
 
@@ -65,6 +136,48 @@ impl Render for Stack {
   }
 }
 
+impl StackPosition {
+  /// Whether the child is laid out by the stack size, the positioned children
+  /// don't affect the size of the stack.
+  fn is_positioned(&self) -> bool {
+    self.left.is_some()
+      || self.top.is_some()
+      || self.right.is_some()
+      || self.bottom.is_some()
+      || self.x_align == HAlign::Stretch
+      || self.y_align == VAlign::Stretch
+  }
+}
+
+impl ComposeChild for StackPosition {
+  type Child = Widget;
+  #[inline]
+  fn compose_child(this: impl StateWriter<Value = Self>, child: Self::Child) -> impl WidgetBuilder {
+    fn_widget! { child.try_unwrap_state_and_attach(this, ctx!()) }
+  }
+}
+
+/// Return the min and max size of a positioned child in an axis, the child is
+/// stretched if both the edges are set.
+fn axis_clamp(start: Option<f32>, end: Option<f32>, stretch: bool, box_size: f32) -> (f32, f32) {
+  if stretch || (start.is_some() && end.is_some()) {
+    let size = (box_size - start.unwrap_or(0.) - end.unwrap_or(0.)).max(0.);
+    (size, size)
+  } else {
+    (0., box_size)
+  }
+}
+
+fn axis_offset(
+  start: Option<f32>, end: Option<f32>, align: Align, child_size: f32, box_size: f32,
+) -> f32 {
+  match (start, end) {
+    (Some(start), _) => start,
+    (None, Some(end)) => box_size - end - child_size,
+    (None, None) => align.align_value(child_size, box_size),
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use ribir_core::test_helper::*;
@@ -85,4 +198,44 @@ mod tests {
   }
 
   widget_layout_test!(smoke, size == FIVE,);
+
+  fn positioned() -> impl WidgetBuilder {
+    fn_widget! {
+      @Stack {
+        @SizedBox { size: Size::new(100., 80.) }
+        @StackPosition {
+          left: 10.,
+          right: 20.,
+          top: 5.,
+          @SizedBox { size: Size::new(10., 10.) }
+        }
+        @StackPosition {
+          right: 10.,
+          bottom: 10.,
+          @SizedBox { size: Size::new(20., 20.) }
+        }
+        @StackPosition {
+          x_align: HAlign::Center,
+          y_align: VAlign::Center,
+          @SizedBox { size: Size::new(40., 40.) }
+        }
+        @StackPosition {
+          top: 10.,
+          bottom: 10.,
+          left: 0.,
+          right: 0.,
+          @Container { size: INFINITY_SIZE }
+        }
+      }
+    }
+  }
+  widget_layout_test!(
+    positioned,
+    wnd_size = Size::new(500., 500.),
+    { path = [0], size == Size::new(100., 80.),}
+    { path = [0, 1], rect == ribir_geom::rect(10., 5., 70., 10.),}
+    { path = [0, 2], rect == ribir_geom::rect(70., 50., 20., 20.),}
+    { path = [0, 3], rect == ribir_geom::rect(30., 20., 40., 40.),}
+    { path = [0, 4], rect == ribir_geom::rect(0., 10., 100., 60.),}
+  );
 }
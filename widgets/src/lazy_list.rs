@@ -0,0 +1,480 @@
+use std::ops::Range;
+
+use ribir_core::prelude::*;
+
+type ItemBuilder = dyn Fn(usize, &BuildCtx) -> Widget;
+
+/// A vertical scrollable list that only builds the items in the view, so it can
+/// show a huge number of items.
+///
+/// The items are built by `item_builder` with their index. When the view
+/// scrolls, the items that leave the view are disposed and the items that come
+/// into the view are built. Besides the view, `overscan` items are built on
+/// each side to avoid blank edges while scrolling.
+///
+/// If all items have the same extent, set `fixed_extent` to skip the
+/// measurement. Otherwise, the extent of an item is `estimated_extent` until it
+/// has been built and measured. The position of the view is corrected when an
+/// item before it gets a different extent, so the visible items don't jump.
+///
+/// Before the first layout, the view is assumed to be as high as the window,
+/// so the items of the first frame are built with the list.
+///
+/// # Example
+///
+/// ```
+/// # use ribir_core::prelude::*;
+/// # use ribir_widgets::prelude::*;
+/// fn_widget! {
+///   @LazyList {
+///     count: 100_000usize,
+///     fixed_extent: 24.,
+///     item_builder: Box::new(|i: usize, ctx: &BuildCtx| {
+///       fn_widget! { @Text { text: format!("Item {i}") } }.build(ctx)
+///     }),
+///   }
+/// };
+/// ```
+#[derive(Declare)]
+pub struct LazyList {
+  /// The number of items.
+  pub count: usize,
+  /// The extent of every item in the main axis, if all items have the same
+  /// extent.
+  #[declare(default)]
+  pub fixed_extent: Option<f32>,
+  /// The extent of an item that has not been measured yet.
+  #[declare(default = 48.)]
+  pub estimated_extent: f32,
+  /// The number of items built beyond each edge of the view.
+  #[declare(default = 2usize)]
+  pub overscan: usize,
+  /// Build the widget of the item at the index.
+  #[declare(strict)]
+  pub item_builder: Box<ItemBuilder>,
+  /// The extents of the items, if they are not fixed.
+  #[declare(skip)]
+  extents: Extents,
+  /// The range of the built items.
+  #[declare(skip)]
+  range: Range<usize>,
+  /// The scrolled distance of the view.
+  #[declare(skip)]
+  offset: f32,
+  #[declare(skip)]
+  view_extent: f32,
+  /// The distance the items before the view have grown, the view should
+  /// scroll the same distance to keep the visible items still.
+  #[declare(skip)]
+  shift: f32,
+}
+
+impl Compose for LazyList {
+  fn compose(this: impl StateWriter<Value = Self>) -> impl WidgetBuilder {
+    fn_widget! {
+      let mut scrolling = @ScrollableWidget { scrollable: Scrollable::Y };
+      let layout = FatObj::new(LazyListLayout { list: this.clone_writer() });
+
+      let wnd_height = ctx!().window().size().height;
+      {
+        let mut list = $this.silent();
+        list.set_view(0., wnd_height);
+        list.range = list.visible_range();
+      }
+
+      let u = watch!((
+        $scrolling.scroll_pos.y,
+        $scrolling.scroll_view_size().height,
+        $this.count,
+      ))
+        .distinct_until_changed()
+        // The view size is unknown before the first layout, keep the range
+        // estimated by the window until then.
+        .filter(|(_, height, _)| *height > 0.)
+        .subscribe(move |(y, height, _)| {
+          $this.silent().set_view(-y, height);
+          let range = $this.visible_range();
+          if $this.range != range {
+            $this.write().range = range;
+          }
+        });
+
+      // Key the items by their index, so only the items that come into the view
+      // are built, and the others are kept.
+      let reader = this.clone_reader();
+      let items = KeyedList::new(
+        pipe!($this.range.clone()).value_chain(|s| s.distinct_until_changed().box_it()),
+        |i: &usize| *i,
+        move |i: Watcher<Reader<usize>>| {
+          let i = *i.read();
+          let items = reader.clone_reader();
+          move |ctx: &BuildCtx| (items.read().item_builder)(i, ctx)
+        },
+      );
+
+      let scroll = scrolling.get_scrollable_widget().clone_writer();
+      let scrolling = scrolling.on_disposed(move |_| u.unsubscribe());
+      @ $scrolling {
+        @ $layout {
+          on_performed_layout: move |_| {
+            let shift = std::mem::take(&mut $this.silent().shift);
+            if shift != 0. {
+              scroll.write().scroll_by(Vector::new(0., -shift));
+            }
+            // The measured extents may change the items in the view.
+            let range = $this.visible_range();
+            if $this.range != range {
+              $this.write().range = range;
+            }
+          },
+          @ { items }
+        }
+      }
+    }
+  }
+}
+
+impl LazyList {
+  fn set_view(&mut self, offset: f32, view_extent: f32) {
+    self.offset = offset;
+    self.view_extent = view_extent;
+    self.sync_extents();
+  }
+
+  /// Keep the extents in sync with the count of the items.
+  fn sync_extents(&mut self) {
+    if self.fixed_extent.is_none() {
+      self
+        .extents
+        .resize(self.count, self.estimated_extent);
+    }
+  }
+
+  /// The distance from the top of the list to the top of the item at `idx`.
+  fn item_offset(&self, idx: usize) -> f32 {
+    match self.fixed_extent {
+      Some(extent) => idx as f32 * extent,
+      None => self.extents.offset(idx),
+    }
+  }
+
+  fn content_extent(&self) -> f32 { self.item_offset(self.count) }
+
+  /// The range of the items in the view, including the overscan items.
+  fn visible_range(&self) -> Range<usize> {
+    let bottom = self.offset + self.view_extent;
+    let (start, end) = match self.fixed_extent.filter(|e| *e > 0.) {
+      Some(extent) => ((self.offset / extent).floor() as usize, (bottom / extent).ceil() as usize),
+      None if bottom <= 0. => (0, 0),
+      None => {
+        // From the first item that ends below the top of the view, to the last
+        // item that starts above the bottom of the view.
+        let start = self.extents.leading_count(self.offset, true);
+        let end = (self.extents.leading_count(bottom, false) + 1).min(self.count);
+        (start, end)
+      }
+    };
+
+    let start = start
+      .min(self.count)
+      .saturating_sub(self.overscan);
+    let end = (end + self.overscan).min(self.count);
+    start..end.max(start)
+  }
+
+  /// Record the measured extents of the items, and accumulate how much the
+  /// items before the view have grown.
+  fn record_extents(&mut self, measured: &[(usize, f32)]) {
+    self.sync_extents();
+    let first_visible = self.extents.leading_count(self.offset, true);
+    for &(idx, extent) in measured
+      .iter()
+      .filter(|(idx, _)| *idx < self.count)
+    {
+      let old = self.extents.set(idx, extent);
+      if idx < first_visible {
+        self.shift += extent - old;
+      }
+    }
+  }
+}
+
+/// The extents of the items kept in a Fenwick tree of their prefix sums, so the
+/// offset of an item and the item at an offset are found in `O(log n)`.
+#[derive(Default)]
+struct Extents {
+  /// The extent of every item, the estimated extent if it's not measured.
+  values: Vec<f32>,
+  measured: Vec<bool>,
+  estimated: f32,
+  /// The Fenwick tree of `values`, one-based.
+  tree: Vec<f32>,
+}
+
+impl Extents {
+  /// Resize to `count` items, the items not measured take the `estimated`
+  /// extent. It's `O(n)` but only if the count or the estimate changed.
+  fn resize(&mut self, count: usize, estimated: f32) {
+    if self.values.len() == count && self.estimated == estimated {
+      return;
+    }
+    self.estimated = estimated;
+    self.measured.resize(count, false);
+    self.values.resize(count, estimated);
+    self
+      .values
+      .iter_mut()
+      .zip(&self.measured)
+      .filter(|(_, measured)| !**measured)
+      .for_each(|(v, _)| *v = estimated);
+
+    self.tree = vec![0.; count + 1];
+    for i in 1..=count {
+      self.tree[i] += self.values[i - 1];
+      let parent = i + lowest_bit(i);
+      if parent <= count {
+        self.tree[parent] += self.tree[i];
+      }
+    }
+  }
+
+  /// Set the measured extent of the item at `idx`, and return its old extent.
+  fn set(&mut self, idx: usize, extent: f32) -> f32 {
+    self.measured[idx] = true;
+    let old = std::mem::replace(&mut self.values[idx], extent);
+    let delta = extent - old;
+    if delta != 0. {
+      let mut i = idx + 1;
+      while i < self.tree.len() {
+        self.tree[i] += delta;
+        i += lowest_bit(i);
+      }
+    }
+    old
+  }
+
+  /// The sum of the extents of the items before `idx`.
+  fn offset(&self, idx: usize) -> f32 {
+    let mut i = idx.min(self.values.len());
+    let mut sum = 0.;
+    while i > 0 {
+      sum += self.tree[i];
+      i -= lowest_bit(i);
+    }
+    sum
+  }
+
+  /// The max count of the leading items whose extents sum is less than
+  /// `offset`, or equal to it if `inclusive`.
+  fn leading_count(&self, offset: f32, inclusive: bool) -> usize {
+    let len = self.values.len();
+    let mut pos = 0;
+    let mut rest = offset;
+    let mut step = if len == 0 { 0 } else { 1 << len.ilog2() };
+    while step > 0 {
+      let next = pos + step;
+      if next <= len {
+        let v = self.tree[next];
+        if v < rest || (inclusive && v == rest) {
+          pos = next;
+          rest -= v;
+        }
+      }
+      step >>= 1;
+    }
+    pos
+  }
+}
+
+fn lowest_bit(i: usize) -> usize { i & i.wrapping_neg() }
+
+/// The render widget that places the built items of a `LazyList` at their
+/// offsets, and measures them.
+#[derive(MultiChild)]
+struct LazyListLayout<W> {
+  list: W,
+}
+
+impl<W: StateWriter<Value = LazyList>> Render for LazyListLayout<W> {
+  fn perform_layout(&self, clamp: BoxClamp, ctx: &mut LayoutCtx) -> Size {
+    let list = self.list.read();
+    let Range { start, end } = list.range.clone();
+    let fixed = list.fixed_extent;
+    let mut y = list.item_offset(start);
+    drop(list);
+
+    let child_clamp = BoxClamp {
+      min: Size::new(0., fixed.unwrap_or(0.)),
+      max: Size::new(clamp.max.width, fixed.unwrap_or(f32::INFINITY)),
+    };
+    let mut width: f32 = 0.;
+    let mut measured = Vec::with_capacity(end - start);
+    let mut layouter = ctx.first_child_layouter();
+    while let Some(mut l) = layouter {
+      let size = l.perform_widget_layout(child_clamp);
+      // The placeholder of an empty range is not an item.
+      let idx = start + measured.len();
+      if idx < end {
+        l.update_position(Point::new(0., y));
+        width = width.max(size.width);
+        y += fixed.unwrap_or(size.height);
+        measured.push((idx, size.height));
+      }
+      layouter = l.into_next_sibling();
+    }
+
+    let mut list = self.list.silent();
+    if fixed.is_none() {
+      list.record_extents(&measured);
+    }
+    clamp.clamp(Size::new(width, list.content_extent()))
+  }
+
+  fn paint(&self, _: &mut PaintingCtx) {}
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{cell::RefCell, rc::Rc};
+
+  use ribir_core::{reset_test_env, test_helper::*};
+  use winit::event::{DeviceId, MouseScrollDelta, TouchPhase, WindowEvent};
+
+  use super::*;
+  use crate::layout::SizedBox;
+
+  fn list(count: usize, fixed_extent: Option<f32>) -> LazyList {
+    LazyList {
+      count,
+      fixed_extent,
+      estimated_extent: 10.,
+      overscan: 0,
+      item_builder: Box::new(|_, ctx: &BuildCtx| Void.build(ctx)),
+      extents: Extents::default(),
+      range: 0..0,
+      offset: 0.,
+      view_extent: 0.,
+      shift: 0.,
+    }
+  }
+
+  fn scroll(wnd: &mut TestWindow, delta_y: f32) {
+    let device_id = unsafe { DeviceId::dummy() };
+    #[allow(deprecated)]
+    wnd.processes_native_event(WindowEvent::MouseWheel {
+      device_id,
+      delta: MouseScrollDelta::PixelDelta((0., delta_y).into()),
+      phase: TouchPhase::Started,
+    });
+    wnd.draw_frame();
+  }
+
+  #[test]
+  fn only_build_visible_items() {
+    reset_test_env!();
+
+    let built = Rc::new(RefCell::new(vec![]));
+    let c_built = built.clone();
+    let w = fn_widget! {
+      @LazyList {
+        count: 100_000usize,
+        fixed_extent: 10.,
+        overscan: 1usize,
+        item_builder: Box::new(move |i: usize, ctx: &BuildCtx| {
+          c_built.borrow_mut().push(i);
+          fn_widget! { @SizedBox { size: Size::new(100., 10.) } }.build(ctx)
+        }),
+      }
+    };
+    let mut wnd = TestWindow::new_with_size(w, Size::new(100., 100.));
+    wnd.draw_frame();
+    assert_eq!(built.borrow().last(), Some(&10));
+    assert!(built.borrow().iter().all(|i| *i <= 10));
+
+    built.borrow_mut().clear();
+    scroll(&mut wnd, -55.);
+    // The items already built are kept, only the new ones in the view are built.
+    assert_eq!(*built.borrow(), (11..17).collect::<Vec<_>>());
+  }
+
+  #[test]
+  fn only_build_visible_measured_items() {
+    reset_test_env!();
+
+    let built = Rc::new(RefCell::new(vec![]));
+    let c_built = built.clone();
+    let w = fn_widget! {
+      @LazyList {
+        count: 100_000usize,
+        estimated_extent: 10.,
+        overscan: 0usize,
+        item_builder: Box::new(move |i: usize, ctx: &BuildCtx| {
+          c_built.borrow_mut().push(i);
+          fn_widget! { @SizedBox { size: Size::new(100., 20.) } }.build(ctx)
+        }),
+      }
+    };
+    let mut wnd = TestWindow::new_with_size(w, Size::new(100., 100.));
+    wnd.draw_frame();
+    assert_eq!(*built.borrow(), (0..10).collect::<Vec<_>>());
+
+    // Built by the estimated extent, after they are measured, the visible ones
+    // are kept and the others are dropped.
+    wnd.draw_frame();
+    assert_eq!(*built.borrow(), (0..10).collect::<Vec<_>>());
+
+    built.borrow_mut().clear();
+    scroll(&mut wnd, -30.);
+    assert_eq!(*built.borrow(), [5, 6]);
+  }
+
+  #[test]
+  fn fixed_extent_range() {
+    let mut list = list(100, Some(10.));
+    list.set_view(0., 100.);
+    assert_eq!(list.visible_range(), 0..10);
+    list.set_view(55., 100.);
+    assert_eq!(list.visible_range(), 5..16);
+    list.overscan = 2;
+    assert_eq!(list.visible_range(), 3..18);
+    list.set_view(980., 100.);
+    assert_eq!(list.visible_range(), 96..100);
+    assert_eq!(list.content_extent(), 1000.);
+  }
+
+  #[test]
+  fn extents_prefix_sum() {
+    let mut extents = Extents::default();
+    extents.resize(100_000, 10.);
+    assert_eq!(extents.offset(100_000), 1_000_000.);
+    assert_eq!(extents.set(3, 30.), 10.);
+    assert_eq!(extents.offset(3), 30.);
+    assert_eq!(extents.offset(4), 60.);
+    assert_eq!(extents.offset(100_000), 1_000_020.);
+    // The item 3 covers the offsets from 30 to 60.
+    assert_eq!(extents.leading_count(45., true), 3);
+    assert_eq!(extents.leading_count(60., true), 4);
+    assert_eq!(extents.leading_count(60., false), 3);
+
+    // The measured extents are kept when the count grows.
+    extents.resize(100_001, 20.);
+    assert_eq!(extents.offset(4), 30. + 3. * 20.);
+    assert_eq!(extents.offset(100_001), 30. + 100_000. * 20.);
+  }
+
+  #[test]
+  fn measured_extent_correction() {
+    let mut list = list(100, None);
+    list.set_view(100., 50.);
+    assert_eq!(list.visible_range(), 10..15);
+
+    // The items before the view grow, the view should follow them.
+    list.record_extents(&[(8, 20.), (9, 30.), (10, 20.)]);
+    assert_eq!(list.shift, 30.);
+    assert_eq!(list.content_extent(), 1040.);
+
+    // After the correction, the first visible item is still the same.
+    list.set_view(100. + list.shift, 50.);
+    assert_eq!(list.visible_range(), 10..14);
+  }
+}
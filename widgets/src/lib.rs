@@ -1,25 +1,33 @@
+pub mod animated_switcher;
 pub mod avatar;
 pub mod buttons;
 pub mod checkbox;
 pub mod common_widget;
+pub mod context_menu;
+pub mod custom_paint;
 pub mod divider;
+pub mod drag_drop;
 pub mod grid_view;
 pub mod icon;
 pub mod input;
 pub mod label;
 pub mod layout;
+pub mod lazy_list;
 pub mod link;
 pub mod lists;
+pub mod navigator;
 pub mod path;
 pub mod scrollbar;
 pub mod tabs;
 pub mod text;
 pub mod text_field;
+pub mod tooltip;
 pub mod transform_box;
 pub mod prelude {
   pub use super::{
-    avatar::*, buttons::*, checkbox::*, common_widget::*, divider::*, grid_view::*, icon::*,
-    input::*, label::*, layout::*, link::*, lists::*, path::*, scrollbar::*, tabs::*, text::*,
-    text_field::*, transform_box::*,
+    animated_switcher::*, avatar::*, buttons::*, checkbox::*, common_widget::*, context_menu::*,
+    custom_paint::*, divider::*, drag_drop::*, grid_view::*, icon::*, input::*, label::*,
+    layout::*, lazy_list::*, link::*, lists::*, navigator::*, path::*, scrollbar::*, tabs::*,
+    text::*, text_field::*, tooltip::*, transform_box::*,
   };
 }
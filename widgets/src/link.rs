@@ -1,7 +1,15 @@
+use std::{cell::Cell, rc::Rc};
+
 use log::warn;
-use ribir_core::prelude::*;
+use ribir_core::{prelude::*, timer::Timer};
 use webbrowser::{open_browser as open, Browser};
 
+/// How long to press a link to copy its url.
+const LONG_PRESS_DURATION: Duration = Duration::from_millis(500);
+
+type NavigateFn = dyn FnMut(&CowArc<str>, LinkTarget) -> bool;
+type OpenFailFn = dyn FnMut(&CowArc<str>, std::io::Error);
+
 #[derive(Declare)]
 pub struct Link {
   /// Want to open url
@@ -9,20 +17,380 @@ pub struct Link {
   /// Select the browser software you expect to open
   #[declare(default=Browser::Default)]
   browser: Browser,
+  /// When to copy the url to the clipboard, the copy not depends on the
+  /// browser, so it works even if the navigation is intercepted.
+  #[declare(default)]
+  copy_on: CopyLink,
+  /// The handler to intercept the navigation, return `true` means the
+  /// navigation is handled, and the browser will not be opened.
+  #[declare(strict, default)]
+  on_navigate: Option<Box<NavigateFn>>,
+  /// The handler called when failed to open the browser. If not set, only a
+  /// warning will be logged.
+  #[declare(strict, default)]
+  on_open_fail: Option<Box<OpenFailFn>>,
+  #[declare(skip)]
+  pressed: MouseButtons,
+  #[declare(skip)]
+  long_pressed: bool,
+  /// If the pending long press is still valid, shared with its timer task.
+  #[declare(skip)]
+  long_press: Option<Rc<Cell<bool>>>,
+}
+
+/// The gestures to copy the url of a [`Link`] to the clipboard.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CopyLink {
+  /// Never copy the url.
+  Never,
+  /// Copy by the secondary click, e.g. the right click of the mouse.
+  SecondaryClick,
+  /// Copy by pressing the link with the primary button or touch for a while.
+  LongPress,
+  /// Copy by the secondary click or the long press.
+  #[default]
+  Both,
+}
+
+/// Where the link is asked to open.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkTarget {
+  /// Open in the current context, by the primary click.
+  Current,
+  /// Open in a new context without switching to it, by the middle click.
+  Background,
 }
 
 impl ComposeChild for Link {
   type Child = Widget;
   fn compose_child(this: impl StateWriter<Value = Self>, child: Self::Child) -> impl WidgetBuilder {
     fn_widget! {
+      let c_this = this.clone_writer();
+      let tap_this = this.clone_writer();
       @ $child {
+        a11y_role: AccessRole::Link,
+        a11y_value: $this.url.clone(),
+        on_pointer_down: move |e| {
+          let mut link = $this.silent();
+          link.cancel_long_press();
+          link.pressed = e.mouse_buttons();
+          link.long_pressed = false;
+          if link.pressed == MouseButtons::PRIMARY && link.copy_on.by_long_press() {
+            let writer = c_this.clone_writer();
+            let pending = Rc::new(Cell::new(true));
+            let c_pending = pending.clone();
+            let timer = Timer::new(Timer::now() + LONG_PRESS_DURATION);
+            let _ = AppCtx::spawn_local(async move {
+              timer.await;
+              if c_pending.get() {
+                let mut this = writer.silent();
+                this.long_press = None;
+                this.long_pressed = true;
+                copy_url(&this.url);
+              }
+            });
+            link.long_press = Some(pending);
+          }
+        },
+        on_pointer_up: move |_| $this.silent().cancel_long_press(),
+        on_pointer_leave: move |_| $this.silent().cancel_long_press(),
+        on_pointer_cancel: move |_| $this.silent().cancel_long_press(),
         on_tap: move |_| {
-          let this = $this;
-          if open(this.browser, &this.url).is_err() {
-            warn!("Open link fail");
+          let mut this = $this.silent();
+          let pressed = std::mem::take(&mut this.pressed);
+          let target = if std::mem::take(&mut this.long_pressed) {
+            // The url is copied by the long press, not navigate.
+            None
+          } else if pressed == MouseButtons::SECONDARY {
+            if this.copy_on.by_secondary_click() {
+              copy_url(&this.url);
+            }
+            None
+          } else if pressed == MouseButtons::AUXILIARY {
+            Some(LinkTarget::Background)
+          } else {
+            Some(LinkTarget::Current)
+          };
+          // Release the link before calling the handlers, they may read it.
+          drop(this);
+          if let Some(target) = target {
+            Link::navigate(&tap_this, target);
           }
         },
       }
     }
   }
 }
+
+impl Link {
+  /// Navigate to the url of the link, the handlers are taken out of the link
+  /// while they are called, so they can access the link.
+  fn navigate(this: &impl StateWriter<Value = Self>, target: LinkTarget) {
+    let (url, browser, on_navigate) = {
+      let mut link = this.silent();
+      (link.url.clone(), link.browser, link.on_navigate.take())
+    };
+    if let Some(mut on_navigate) = on_navigate {
+      let handled = on_navigate(&url, target);
+      this.silent().on_navigate.get_or_insert(on_navigate);
+      if handled {
+        return;
+      }
+    }
+    if let Err(err) = open(browser, &url) {
+      let on_open_fail = this.silent().on_open_fail.take();
+      match on_open_fail {
+        Some(mut on_open_fail) => {
+          on_open_fail(&url, err);
+          this.silent().on_open_fail.get_or_insert(on_open_fail);
+        }
+        None => warn!("Open link fail: {err}"),
+      }
+    }
+  }
+
+  fn cancel_long_press(&mut self) {
+    if let Some(pending) = self.long_press.take() {
+      pending.set(false);
+    }
+  }
+}
+
+impl CopyLink {
+  fn by_secondary_click(self) -> bool { matches!(self, CopyLink::SecondaryClick | CopyLink::Both) }
+
+  fn by_long_press(self) -> bool { matches!(self, CopyLink::LongPress | CopyLink::Both) }
+}
+
+fn copy_url(url: &str) {
+  if let Err(err) = AppCtx::clipboard().borrow_mut().write_text(url) {
+    warn!("Copy link fail: {err}");
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{
+    borrow::Cow,
+    cell::RefCell,
+    io::{Error, ErrorKind},
+    rc::Rc,
+  };
+
+  use ribir_core::{clipboard::Clipboard, reset_test_env, test_helper::*, timer::Timer};
+  use winit::event::{DeviceId, ElementState, MouseButton, WindowEvent};
+
+  use super::*;
+  use crate::{layout::SizedBox, text::Text};
+
+  #[test]
+  fn intercept_navigate() {
+    reset_test_env!();
+
+    let navigated = Rc::new(RefCell::new(vec![]));
+    let c_navigated = navigated.clone();
+    let w = fn_widget! {
+      @Link {
+        url: "https://ribir.org",
+        on_navigate: Some(Box::new(move |url: &CowArc<str>, target| {
+          c_navigated.borrow_mut().push((url.to_string(), target));
+          true
+        })),
+        on_open_fail: Some(Box::new(|_: &CowArc<str>, _: std::io::Error| unreachable!())),
+        @SizedBox { size: Size::new(100., 100.) }
+      }
+    };
+
+    let mut wnd = TestWindow::new_with_size(w, Size::new(200., 200.));
+    wnd.draw_frame();
+
+    let device_id = unsafe { DeviceId::dummy() };
+    #[allow(deprecated)]
+    wnd.processes_native_event(WindowEvent::CursorMoved { device_id, position: (50., 50.).into() });
+    wnd.process_mouse_input(device_id, ElementState::Pressed, MouseButton::Left);
+    wnd.run_frame_tasks();
+    wnd.process_mouse_input(device_id, ElementState::Released, MouseButton::Left);
+    wnd.draw_frame();
+
+    assert_eq!(&*navigated.borrow(), &[("https://ribir.org".to_string(), LinkTarget::Current)]);
+
+    wnd.process_mouse_input(device_id, ElementState::Pressed, MouseButton::Middle);
+    wnd.run_frame_tasks();
+    wnd.process_mouse_input(device_id, ElementState::Released, MouseButton::Middle);
+    wnd.draw_frame();
+    assert_eq!(navigated.borrow()[1], ("https://ribir.org".to_string(), LinkTarget::Background));
+  }
+
+  #[test]
+  fn open_fail() {
+    reset_test_env!();
+
+    let link = Rc::new(RefCell::new(None));
+    let failed = Rc::new(RefCell::new(vec![]));
+    let (c_link, c_failed) = (link.clone(), failed.clone());
+    let w = fn_widget! {
+      let c_link = c_link.clone();
+      let c_failed = c_failed.clone();
+      let on_navigate = move |_: &CowArc<str>, _| {
+        // The link can be read by the handlers.
+        let link = c_link.borrow();
+        let link: &Reader<Link> = link.as_ref().unwrap();
+        assert_eq!(&*link.read().url, "https://ribir.org");
+        false
+      };
+      let w = @Link {
+        url: "https://ribir.org",
+        // Internet Explorer is not supported to open the link.
+        browser: Browser::InternetExplorer,
+        on_navigate: Some(Box::new(on_navigate)),
+        on_open_fail: Some(Box::new(move |url: &CowArc<str>, _: std::io::Error| {
+          c_failed.borrow_mut().push(url.to_string());
+        })),
+      };
+      *link.borrow_mut() = Some(w.clone_reader());
+      @ $w { @SizedBox { size: Size::new(100., 100.) } }
+    };
+
+    let mut wnd = TestWindow::new_with_size(w, Size::new(200., 200.));
+    wnd.draw_frame();
+
+    let device_id = unsafe { DeviceId::dummy() };
+    #[allow(deprecated)]
+    wnd.processes_native_event(WindowEvent::CursorMoved { device_id, position: (50., 50.).into() });
+    wnd.process_mouse_input(device_id, ElementState::Pressed, MouseButton::Left);
+    wnd.run_frame_tasks();
+    wnd.process_mouse_input(device_id, ElementState::Released, MouseButton::Left);
+    wnd.draw_frame();
+
+    assert_eq!(&*failed.borrow(), &["https://ribir.org".to_string()]);
+  }
+
+  struct RecordClipboard(Rc<RefCell<Vec<String>>>);
+
+  impl Clipboard for RecordClipboard {
+    fn read_text(&mut self) -> Result<String, Error> { Err(ErrorKind::Unsupported.into()) }
+
+    fn write_text(&mut self, text: &str) -> Result<(), Error> {
+      self.0.borrow_mut().push(text.to_string());
+      Ok(())
+    }
+
+    fn read_img(&mut self) -> Result<PixelImage, Error> { Err(ErrorKind::Unsupported.into()) }
+
+    fn write_img(&mut self, _: &PixelImage) -> Result<(), Error> {
+      Err(ErrorKind::Unsupported.into())
+    }
+
+    fn read(&mut self, _: &str) -> Result<Cow<[u8]>, Error> { Err(ErrorKind::Unsupported.into()) }
+
+    fn write(&mut self, _: &str, _: &[u8]) -> Result<(), Error> {
+      Err(ErrorKind::Unsupported.into())
+    }
+
+    fn clear(&mut self) -> Result<(), Error> { Err(ErrorKind::Unsupported.into()) }
+  }
+
+  #[test]
+  fn copy_by_secondary_click() {
+    reset_test_env!();
+
+    let copied = Rc::new(RefCell::new(vec![]));
+    unsafe { AppCtx::set_clipboard(Box::new(RecordClipboard(copied.clone()))) };
+    let w = fn_widget! {
+      @Link {
+        url: "https://ribir.org",
+        // Disable the navigation, the copy still works.
+        on_navigate: Some(Box::new(|_: &CowArc<str>, _| true)),
+        @SizedBox { size: Size::new(100., 100.) }
+      }
+    };
+
+    let mut wnd = TestWindow::new_with_size(w, Size::new(200., 200.));
+    wnd.draw_frame();
+
+    let device_id = unsafe { DeviceId::dummy() };
+    #[allow(deprecated)]
+    wnd.processes_native_event(WindowEvent::CursorMoved { device_id, position: (50., 50.).into() });
+    wnd.process_mouse_input(device_id, ElementState::Pressed, MouseButton::Left);
+    wnd.run_frame_tasks();
+    wnd.process_mouse_input(device_id, ElementState::Released, MouseButton::Left);
+    wnd.draw_frame();
+    assert!(copied.borrow().is_empty());
+
+    wnd.process_mouse_input(device_id, ElementState::Pressed, MouseButton::Right);
+    wnd.run_frame_tasks();
+    wnd.process_mouse_input(device_id, ElementState::Released, MouseButton::Right);
+    wnd.draw_frame();
+    assert_eq!(&*copied.borrow(), &["https://ribir.org".to_string()]);
+  }
+
+  #[test]
+  fn copy_by_long_press() {
+    reset_test_env!();
+
+    let copied = Rc::new(RefCell::new(vec![]));
+    unsafe { AppCtx::set_clipboard(Box::new(RecordClipboard(copied.clone()))) };
+    let navigated = Rc::new(RefCell::new(vec![]));
+    let c_navigated = navigated.clone();
+    let w = fn_widget! {
+      @Link {
+        url: "https://ribir.org",
+        on_navigate: Some(Box::new(move |_: &CowArc<str>, target| {
+          c_navigated.borrow_mut().push(target);
+          true
+        })),
+        @SizedBox { size: Size::new(100., 100.) }
+      }
+    };
+
+    let mut wnd = TestWindow::new_with_size(w, Size::new(200., 200.));
+    wnd.draw_frame();
+
+    let device_id = unsafe { DeviceId::dummy() };
+    #[allow(deprecated)]
+    wnd.processes_native_event(WindowEvent::CursorMoved { device_id, position: (50., 50.).into() });
+
+    // A short press navigates, and the timer of the released press not copy.
+    wnd.process_mouse_input(device_id, ElementState::Pressed, MouseButton::Left);
+    wnd.run_frame_tasks();
+    Timer::advance(Duration::from_millis(300));
+    wnd.run_frame_tasks();
+    wnd.process_mouse_input(device_id, ElementState::Released, MouseButton::Left);
+    Timer::advance(Duration::from_millis(300));
+    wnd.draw_frame();
+    assert!(copied.borrow().is_empty());
+    assert_eq!(&*navigated.borrow(), &[LinkTarget::Current]);
+
+    // A long press copies, and not navigate.
+    wnd.process_mouse_input(device_id, ElementState::Pressed, MouseButton::Left);
+    wnd.run_frame_tasks();
+    Timer::advance(LONG_PRESS_DURATION);
+    wnd.run_frame_tasks();
+    assert_eq!(&*copied.borrow(), &["https://ribir.org".to_string()]);
+    wnd.process_mouse_input(device_id, ElementState::Released, MouseButton::Left);
+    wnd.draw_frame();
+    assert_eq!(navigated.borrow().len(), 1);
+  }
+
+  #[test]
+  fn link_accessibility() {
+    reset_test_env!();
+
+    let w = fn_widget! {
+      @Link {
+        url: "https://ribir.org",
+        @Text { text: "Ribir" }
+      }
+    };
+
+    let mut wnd = TestWindow::new(w);
+    wnd.draw_frame();
+
+    let tree = wnd.accessibility_tree();
+    let link = &tree.children[0];
+    assert_eq!(link.role, AccessRole::Link);
+    assert_eq!(link.label.as_deref(), Some("Ribir"));
+    assert_eq!(link.value.as_deref(), Some("https://ribir.org"));
+    assert!(link.children.is_empty());
+  }
+}
@@ -0,0 +1,553 @@
+use std::{
+  cell::{Cell, RefCell},
+  collections::HashMap,
+  rc::{Rc, Weak},
+};
+
+use ribir_core::prelude::*;
+
+use crate::prelude::*;
+
+/// A navigation stack of routes, only the top route is shown.
+///
+/// The child of the navigator is its first route. Call `push` to show a new
+/// route over the current one, `pop` to go back to the previous route and
+/// `replace` to replace the top route. The routes enter and exit with the
+/// animation of the `transition_builder`, the exiting route is kept in the
+/// tree until its exit animation finishes. A covered route is hidden but keeps
+/// its state, so it's shown as before when the routes over it are popped.
+///
+/// The last route is never popped, the `on_pop_last` is called instead, for
+/// example to exit the app. It's called after the navigator is released, so
+/// it can access the navigator.
+///
+/// # Example
+///
+/// ```no_run
+/// # use ribir_core::prelude::*;
+/// # use ribir_widgets::prelude::*;
+/// fn_widget! {
+///   let nav = @Navigator {
+///     transition_builder: SwitchTransitionBuilder::slide(Vector::new(100., 0.)),
+///   };
+///   @$nav {
+///     @FilledButton {
+///       on_tap: move |_| $nav.write().push(fn_widget! {
+///         @Text { text: "Press the escape key to go back" }
+///       }),
+///       @{ Label::new("Next") }
+///     }
+///   }
+/// };
+/// ```
+#[derive(Declare)]
+pub struct Navigator {
+  /// The transition drives the enter and the exit animation of the routes.
+  /// It's only used when the navigator composed, the default is `EASE_IN_OUT`
+  /// of the theme.
+  #[declare(default)]
+  pub transition: Option<Box<dyn Transition>>,
+  /// Builds the animated widget of the entering or exiting route.
+  #[declare(default)]
+  pub transition_builder: SwitchTransitionBuilder,
+  /// Whether to pop the top route when the escape key is pressed in the
+  /// navigator.
+  #[declare(default = true)]
+  pub pop_on_escape: bool,
+  /// Called when popping the last route, the last route is not popped.
+  #[declare(default)]
+  pub on_pop_last: Option<Box<dyn FnMut()>>,
+  #[declare(skip, default = 1usize)]
+  depth: usize,
+  #[declare(skip)]
+  requests: Vec<NavRequest>,
+}
+
+enum NavRequest {
+  Push(BoxedWidget),
+  Pop,
+  PopLast,
+  Replace(BoxedWidget),
+}
+
+impl Navigator {
+  /// Push a route to the top of the stack.
+  pub fn push(&mut self, route: impl WidgetBuilder + 'static) {
+    self.depth += 1;
+    self
+      .requests
+      .push(NavRequest::Push(route.box_it()));
+  }
+
+  /// Pop the top route and go back to the previous route. If the top route is
+  /// the last one, it's not popped and the `on_pop_last` is called.
+  pub fn pop(&mut self) {
+    if self.depth > 1 {
+      self.depth -= 1;
+      self.requests.push(NavRequest::Pop);
+    } else {
+      self.requests.push(NavRequest::PopLast);
+    }
+  }
+
+  /// Replace the top route with `route`.
+  pub fn replace(&mut self, route: impl WidgetBuilder + 'static) {
+    self
+      .requests
+      .push(NavRequest::Replace(route.box_it()));
+  }
+
+  /// The count of the routes in the stack.
+  pub fn depth(&self) -> usize { self.depth }
+}
+
+struct Route {
+  /// The widget of the route, it's taken when the route is built.
+  widget: Option<BoxedWidget>,
+  progress: Stateful<f32>,
+  /// A covered route is hidden but keeps its state.
+  visible: Stateful<bool>,
+  exiting: Rc<Cell<bool>>,
+  animate: Option<State<Animate<Writer<f32>>>>,
+}
+
+struct RouteStack {
+  routes: HashMap<usize, Route>,
+  /// The routes in the stack, the last one is the top.
+  stack: Vec<usize>,
+  /// The popped or replaced routes that are playing the exit animation.
+  exiting: Vec<usize>,
+  /// The routes in the tree.
+  mounted: Stateful<Vec<usize>>,
+  next_id: usize,
+}
+
+impl Route {
+  fn new(widget: BoxedWidget, shown: bool) -> Self {
+    Self {
+      widget: Some(widget),
+      progress: Stateful::new(if shown { 1. } else { 0. }),
+      visible: Stateful::new(true),
+      exiting: Rc::new(Cell::new(false)),
+      animate: None,
+    }
+  }
+
+  fn enter(&self) {
+    if let Some(animate) = self.animate.as_ref() {
+      animate.write().from = 0.;
+      *self.progress.write() = 1.;
+      animate.run();
+    }
+  }
+
+  fn exit(&self) {
+    self.exiting.set(true);
+    if let Some(animate) = self.animate.as_ref() {
+      let from = *self.progress.read();
+      animate.write().from = from;
+      *self.progress.write() = 0.;
+      animate.run();
+    }
+  }
+}
+
+impl RouteStack {
+  fn new(first: BoxedWidget) -> Self {
+    let mut this = Self {
+      routes: HashMap::default(),
+      stack: vec![],
+      exiting: vec![],
+      mounted: Stateful::new(vec![]),
+      next_id: 0,
+    };
+    this.add_route(first, true);
+    this.update_mounted();
+    this
+  }
+
+  fn add_route(&mut self, widget: BoxedWidget, shown: bool) {
+    let id = self.next_id;
+    self.next_id += 1;
+    self.routes.insert(id, Route::new(widget, shown));
+    self.stack.push(id);
+  }
+
+  fn handle(&mut self, request: NavRequest) {
+    match request {
+      NavRequest::Push(widget) => self.add_route(widget, false),
+      NavRequest::Pop => {
+        self.exit_top();
+        if let Some(top) = self.stack.last() {
+          *self.routes[top].visible.write() = true;
+        }
+      }
+      NavRequest::Replace(widget) => {
+        self.exit_top();
+        self.add_route(widget, false);
+      }
+      // The last route is kept, the navigator calls its `on_pop_last`.
+      NavRequest::PopLast => {}
+    }
+  }
+
+  fn exit_top(&mut self) {
+    let Some(top) = self.stack.pop() else { return };
+    let route = &self.routes[&top];
+    // A route not built yet needn't to exit.
+    if route.animate.is_some() {
+      route.exit();
+      self.exiting.push(top);
+    } else {
+      self.routes.remove(&top);
+    }
+  }
+
+  fn transition_end(&mut self, id: usize) {
+    let Some(route) = self.routes.get(&id) else { return };
+    if route.exiting.get() {
+      self.routes.remove(&id);
+      self.exiting.retain(|e| *e != id);
+      self.update_mounted();
+    } else if self.stack.last() == Some(&id) {
+      // The top route is fully shown, hide the covered routes.
+      for covered in &self.stack[..self.stack.len() - 1] {
+        *self.routes[covered].visible.write() = false;
+      }
+    }
+  }
+
+  fn update_mounted(&self) {
+    let mounted = self
+      .stack
+      .iter()
+      .chain(self.exiting.iter())
+      .copied()
+      .collect();
+    *self.mounted.write() = mounted;
+  }
+}
+
+impl ComposeChild for Navigator {
+  type Child = Widget;
+  fn compose_child(this: impl StateWriter<Value = Self>, child: Self::Child) -> impl WidgetBuilder {
+    fn_widget! {
+      let transition = $this
+        .silent()
+        .transition
+        .take()
+        .unwrap_or_else(|| transitions::EASE_IN_OUT.of(ctx!()));
+      let transition = Rc::new(transition);
+      let builder = $this.transition_builder.clone();
+      let routes = Rc::new(RefCell::new(RouteStack::new((move |_: &BuildCtx| child).box_it())));
+      let mounted = routes.borrow().mounted.clone_watcher();
+
+      let c_routes = routes.clone();
+      let c_this = this.clone_writer();
+      let u = this.modifies().subscribe(move |_| {
+        // Taking the requests modifies the navigator too, check it by a read
+        // to not notify again.
+        if c_this.read().requests.is_empty() {
+          return;
+        }
+        let requests = std::mem::take(&mut c_this.silent().requests);
+        let mut pop_last = 0;
+        let mut routes = c_routes.borrow_mut();
+        for r in requests {
+          if matches!(r, NavRequest::PopLast) {
+            pop_last += 1;
+          } else {
+            routes.handle(r);
+          }
+        }
+        routes.update_mounted();
+        drop(routes);
+
+        // Call the `on_pop_last` without borrowing the navigator.
+        if pop_last == 0 {
+          return;
+        }
+        let Some(mut on_pop_last) = c_this.silent().on_pop_last.take() else { return };
+        (0..pop_last).for_each(|_| on_pop_last());
+        c_this
+          .silent()
+          .on_pop_last
+          .get_or_insert(on_pop_last);
+      });
+
+      let weak_routes = Rc::downgrade(&routes);
+      let route_widget = move |id: Watcher<Reader<usize>>| {
+        let id = *id.read();
+        let routes = weak_routes.clone();
+        let transition = transition.clone();
+        let builder = builder.clone();
+        move |ctx: &BuildCtx| build_route(id, routes, transition, builder, ctx)
+      };
+
+      @Stack {
+        on_disposed: move |_| u.unsubscribe(),
+        on_key_down: move |e| {
+          if $this.pop_on_escape
+            && *e.key() == VirtualKey::Named(NamedKey::Escape) {
+            $this.write().pop();
+          }
+        },
+        @ { KeyedList::new(pipe!($mounted.clone()), |id: &usize| *id, route_widget) }
+      }
+    }
+  }
+}
+
+fn build_route(
+  id: usize, routes: Weak<RefCell<RouteStack>>, transition: Rc<Box<dyn Transition>>,
+  builder: SwitchTransitionBuilder, ctx: &BuildCtx,
+) -> Widget {
+  let Some(stack) = routes.upgrade() else { return Void.build(ctx) };
+  let (widget, progress, visible, exiting) = {
+    let mut stack = stack.borrow_mut();
+    let Some(route) = stack.routes.get_mut(&id) else { return Void.build(ctx) };
+    let shown = *route.progress.read() == 1.;
+    let animate = Animate::declarer()
+      .transition(transition.box_it())
+      .state(route.progress.clone_writer())
+      .from(0.)
+      .on_progress(move |rate| {
+        if rate == 1. {
+          if let Some(routes) = routes.upgrade() {
+            routes.borrow_mut().transition_end(id);
+          }
+        }
+      })
+      .finish(ctx);
+    route.animate = Some(animate);
+    if !shown {
+      route.enter();
+    }
+    let widget = route
+      .widget
+      .take()
+      .expect("A route is built only once.");
+    (widget, route.progress.clone_watcher(), route.visible.clone_watcher(), route.exiting.clone())
+  };
+
+  let w = builder.build(widget(ctx), progress, ctx);
+  fn_widget! {
+    // The exiting route not receives the pointer events anymore.
+    @$w {
+      visible: pipe!(*$visible),
+      hit_test: move |_: Point| !exiting.get(),
+    }
+  }
+  .build(ctx)
+}
+
+#[cfg(test)]
+mod tests {
+  use ribir_core::{reset_test_env, test_helper::*, timer::Timer};
+  use ribir_dev_helper::*;
+  use winit::{
+    event::ElementState,
+    keyboard::{KeyCode, KeyLocation, PhysicalKey},
+  };
+
+  use super::*;
+
+  fn route(name: &'static str, mounted: Rc<RefCell<Vec<&'static str>>>) -> impl WidgetBuilder {
+    let c_mounted = mounted.clone();
+    fn_widget! {
+      @MockBox {
+        size: Size::new(100., 100.),
+        on_mounted: move |_| c_mounted.borrow_mut().push(name),
+        on_disposed: move |_| mounted.borrow_mut().retain(|n| *n != name),
+      }
+    }
+  }
+
+  fn wait_transition(wnd: &mut TestWindow) {
+    Timer::advance(Duration::from_millis(10));
+    // The transition finishes in the first frame, and the finished routes are
+    // updated in the next frame.
+    wnd.draw_frame();
+    wnd.draw_frame();
+  }
+
+  #[test]
+  fn push_and_pop() {
+    reset_test_env!();
+
+    let mounted = Rc::new(RefCell::new(vec![]));
+    let nav = Stateful::new(None);
+    let c_nav = nav.clone_writer();
+    let c_mounted = mounted.clone();
+    let w = fn_widget! {
+      let navigator = @Navigator {
+        transition: EasingTransition {
+          easing: easing::LINEAR,
+          duration: Duration::from_millis(10),
+        }.box_it(),
+      };
+      *c_nav.write() = Some(navigator.clone_writer());
+      @$navigator { @ { route("home", c_mounted.clone()) } }
+    };
+    let mut wnd = TestWindow::new(w);
+    wnd.draw_frame();
+    let nav = nav.read().as_ref().unwrap().clone_writer();
+
+    nav.write().push(route("first", mounted.clone()));
+    nav.write().push(route("second", mounted.clone()));
+    wnd.draw_frame();
+    wait_transition(&mut wnd);
+    assert_eq!(nav.read().depth(), 3);
+    assert_eq!(&*mounted.borrow(), &["home", "first", "second"]);
+    // Only the top route is visible.
+    assert_layout_result_by_path!(
+      wnd,
+      { path = [0, 0], width == 0., }
+      { path = [0, 1], width == 0., }
+      { path = [0, 2], width == 100., }
+    );
+
+    nav.write().pop();
+    wnd.draw_frame();
+    // The popped route is kept until its exit animation finished.
+    assert_eq!(&*mounted.borrow(), &["home", "first", "second"]);
+    wait_transition(&mut wnd);
+    assert_eq!(&*mounted.borrow(), &["home", "first"]);
+    assert_layout_result_by_path!(
+      wnd,
+      { path = [0, 0], width == 0., }
+      { path = [0, 1], width == 100., }
+    );
+
+    // The last route is never popped.
+    nav.write().pop();
+    nav.write().pop();
+    wnd.draw_frame();
+    wait_transition(&mut wnd);
+    assert_eq!(nav.read().depth(), 1);
+    assert_eq!(&*mounted.borrow(), &["home"]);
+  }
+
+  #[test]
+  fn rapid_push_pop() {
+    reset_test_env!();
+
+    let mounted = Rc::new(RefCell::new(vec![]));
+    let nav = Stateful::new(None);
+    let c_nav = nav.clone_writer();
+    let c_mounted = mounted.clone();
+    let w = fn_widget! {
+      let navigator = @Navigator {};
+      *c_nav.write() = Some(navigator.clone_writer());
+      @$navigator { @ { route("home", c_mounted.clone()) } }
+    };
+    let mut wnd = TestWindow::new(w);
+    wnd.draw_frame();
+    let nav = nav.read().as_ref().unwrap().clone_writer();
+
+    // A route pushed and popped before built is never mounted.
+    nav.write().push(route("first", mounted.clone()));
+    nav.write().pop();
+    wnd.draw_frame();
+    assert_eq!(&*mounted.borrow(), &["home"]);
+    assert_layout_result_by_path!(wnd, { path = [0, 0], width == 100., });
+  }
+
+  #[test]
+  fn replace_top_route() {
+    reset_test_env!();
+
+    let mounted = Rc::new(RefCell::new(vec![]));
+    let nav = Stateful::new(None);
+    let c_nav = nav.clone_writer();
+    let c_mounted = mounted.clone();
+    let w = fn_widget! {
+      let navigator = @Navigator {
+        transition: EasingTransition {
+          easing: easing::LINEAR,
+          duration: Duration::from_millis(10),
+        }.box_it(),
+      };
+      *c_nav.write() = Some(navigator.clone_writer());
+      @$navigator { @ { route("home", c_mounted.clone()) } }
+    };
+    let mut wnd = TestWindow::new(w);
+    wnd.draw_frame();
+    let nav = nav.read().as_ref().unwrap().clone_writer();
+
+    nav.write().push(route("first", mounted.clone()));
+    wnd.draw_frame();
+    wait_transition(&mut wnd);
+
+    nav.write().replace(route("second", mounted.clone()));
+    wnd.draw_frame();
+    // The replaced route is kept until its exit animation finished.
+    assert_eq!(&*mounted.borrow(), &["home", "first", "second"]);
+    wait_transition(&mut wnd);
+    assert_eq!(nav.read().depth(), 2);
+    assert_eq!(&*mounted.borrow(), &["home", "second"]);
+    assert_layout_result_by_path!(
+      wnd,
+      { path = [0, 0], width == 0., }
+      { path = [0, 1], width == 100., }
+    );
+  }
+
+  #[test]
+  fn escape_to_pop() {
+    reset_test_env!();
+
+    let nav = Stateful::new(None);
+    let c_nav = nav.clone_writer();
+    let reader = nav.clone_reader();
+    let pop_last_at = Rc::new(RefCell::new(vec![]));
+    let c_pop_last_at = pop_last_at.clone();
+    let on_pop_last = move || {
+      // The navigator is not borrowed when popping the last route.
+      let depth = reader.read().as_ref().map(|n: &Writer<Navigator>| n.read().depth());
+      c_pop_last_at.borrow_mut().push(depth);
+    };
+    let w = fn_widget! {
+      let navigator = @Navigator {
+        transition: EasingTransition {
+          easing: easing::LINEAR,
+          duration: Duration::from_millis(10),
+        }.box_it(),
+        on_pop_last: Some(Box::new(on_pop_last) as Box<dyn FnMut()>),
+      };
+      *c_nav.write() = Some(navigator.clone_writer());
+      @$navigator {
+        @MockBox { size: Size::new(100., 100.), auto_focus: true }
+      }
+    };
+    let mut wnd = TestWindow::new(w);
+    wnd.draw_frame();
+    let nav = nav.read().as_ref().unwrap().clone_writer();
+
+    let escape = |wnd: &mut TestWindow| {
+      wnd.processes_keyboard_event(
+        PhysicalKey::Code(KeyCode::Escape),
+        VirtualKey::Named(NamedKey::Escape),
+        false,
+        KeyLocation::Standard,
+        ElementState::Pressed,
+      );
+      wnd.draw_frame();
+    };
+
+    escape(&mut wnd);
+    assert_eq!(nav.read().depth(), 1);
+    assert_eq!(&*pop_last_at.borrow(), &[Some(1)]);
+
+    nav.write().push(fn_widget! {
+      @MockBox { size: Size::new(100., 100.), auto_focus: true }
+    });
+    wnd.draw_frame();
+    wait_transition(&mut wnd);
+    assert_eq!(nav.read().depth(), 2);
+
+    escape(&mut wnd);
+    assert_eq!(nav.read().depth(), 1);
+    assert_eq!(pop_last_at.borrow().len(), 1);
+  }
+}
@@ -182,15 +182,11 @@ impl Compose for HRawScrollbar {
     fn_widget! {
       @ {
         let scrolling = $this.scrolling.clone_watcher();
-        let ScrollBarStyle {
-          thickness,
-          thumb_min_size,
-          track_brush,
-        } = ScrollBarStyle::of(ctx!());
+        let ScrollBarStyle { thickness, thumb_min_size, .. } = ScrollBarStyle::of(ctx!());
 
         let mut track_box = @Container {
           size: Size::new(f32::MAX, 0.),
-          background: track_brush
+          background: ScrollBarStyle::pipe_of(ctx!(), |s| s.track_brush.clone()),
         };
 
         let thumb_outline = @HScrollBarThumbDecorator {
@@ -243,15 +239,11 @@ impl Compose for VRawScrollbar {
     fn_widget! {
       @ {
         let scrolling = $this.scrolling.clone_watcher();
-        let ScrollBarStyle {
-          thickness,
-          thumb_min_size,
-          ref track_brush
-        } = ScrollBarStyle::of(ctx!());
+        let ScrollBarStyle { thickness, thumb_min_size, .. } = ScrollBarStyle::of(ctx!());
 
         let mut track_box = @Container {
           size: Size::new(0., f32::MAX),
-          background: track_brush.clone()
+          background: ScrollBarStyle::pipe_of(ctx!(), |s| s.track_brush.clone()),
         };
 
         let thumb_outline = @VScrollBarThumbDecorator {
@@ -288,6 +280,207 @@ impl Compose for VRawScrollbar {
   }
 }
 
+/// The direction of a `ScrollBar`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ScrollBarDir {
+  #[default]
+  Vertical,
+  Horizontal,
+}
+
+/// A scrollbar binds to the `ScrollableWidget` it controls, it displays a
+/// track and a thumb sized by the fraction of the content in the view, and
+/// the thumb can be dragged to scroll the content.
+///
+/// The bar is hidden if the content isn't larger than the view in its
+/// direction.
+///
+/// # Example
+///
+/// ```no_run
+/// use ribir_core::prelude::*;
+/// use ribir_widgets::prelude::*;
+///
+/// let w = fn_widget! {
+///   let mut view = @ScrollableWidget { scrollable: Scrollable::Y };
+///   let scrolling = view.get_scrollable_widget().clone_writer();
+///   @Stack {
+///     @ $view { @Container { size: Size::new(100., 1000.) } }
+///     @ScrollBar { scrolling, h_align: HAlign::Right }
+///   }
+/// };
+/// ```
+#[derive(Declare)]
+pub struct ScrollBar {
+  scrolling: Writer<ScrollableWidget>,
+  #[declare(default)]
+  pub dir: ScrollBarDir,
+  /// Hide the bar after the content isn't scrolled for the duration, `None`
+  /// means the bar is always shown.
+  #[declare(default)]
+  pub auto_hide: Option<Duration>,
+  #[declare(skip)]
+  idle: bool,
+  /// The pointer position and the thumb position when the drag started.
+  #[declare(skip)]
+  drag_from: Option<(f32, f32)>,
+}
+
+impl Compose for ScrollBar {
+  fn compose(this: impl StateWriter<Value = Self>) -> impl WidgetBuilder {
+    fn_widget! {
+      let scrolling = $this.scrolling.clone_writer();
+      let dir = $this.dir;
+      let ScrollBarStyle { thickness, thumb_min_size, .. } = ScrollBarStyle::of(ctx!());
+
+      let mut track = @Container {
+        size: dir.to_size(f32::MAX, thickness),
+        background: ScrollBarStyle::pipe_of(ctx!(), |s| s.track_brush.clone()),
+      };
+      let thumb = @Container {
+        size: pipe! {
+          let track = dir.main_of(&$track.layout_size());
+          let (_, extent) = thumb_of(&$scrolling, dir, track, thumb_min_size);
+          dir.to_size(extent, thickness)
+        },
+        on_pointer_down: move |e| {
+          if e.mouse_buttons() == MouseButtons::PRIMARY {
+            let track = dir.main_of(&$track.layout_size());
+            let (pos, _) = thumb_of(&$scrolling, dir, track, thumb_min_size);
+            $this.silent().drag_from = Some((dir.main_of(&e.global_pos()), pos));
+            // Keep dragging when the pointer moves off the bar.
+            e.capture_pointer();
+          }
+        },
+        on_pointer_move: move |e| {
+          let Some((from, thumb_from)) = $this.drag_from else { return };
+          if e.mouse_buttons().contains(MouseButtons::PRIMARY) {
+            let track = dir.main_of(&$track.layout_size());
+            let thumb = thumb_from + dir.main_of(&e.global_pos()) - from;
+            let mut scrolling = $scrolling.write();
+            let pos = scroll_pos_of(&scrolling, dir, track, thumb_min_size, thumb);
+            let mut new = scrolling.scroll_pos;
+            *dir.main_of_mut(&mut new) = pos;
+            scrolling.jump_to(new);
+          } else {
+            $this.silent().drag_from = None;
+          }
+        },
+        on_pointer_up: move |e| {
+          $this.silent().drag_from = None;
+          e.release_pointer();
+        },
+        on_pointer_cancel: move |_| $this.silent().drag_from = None,
+      };
+      let offset = pipe! {
+        let track = dir.main_of(&$track.layout_size());
+        thumb_of(&$scrolling, dir, track, thumb_min_size).0
+      };
+      let thumb = match dir {
+        ScrollBarDir::Vertical => @VScrollBarThumbDecorator { offset, @ { thumb } }.build(ctx!()),
+        ScrollBarDir::Horizontal => @HScrollBarThumbDecorator { offset, @ { thumb } }.build(ctx!()),
+      };
+
+      let handles = $this.auto_hide.map(|dur| {
+        let active = scrolling
+          .modifies()
+          .subscribe(move |_| if $this.idle { $this.write().idle = false; });
+        let idle = scrolling
+          .modifies_debounced(dur)
+          .subscribe(move |_| $this.write().idle = true);
+        (active, idle)
+      });
+
+      @Stack {
+        visible: pipe!(can_scroll(&$scrolling, dir) && !$this.idle),
+        on_disposed: move |_| {
+          if let Some((active, idle)) = handles {
+            active.unsubscribe();
+            idle.unsubscribe();
+          }
+        },
+        @ { track }
+        @ { thumb }
+      }
+    }
+  }
+}
+
+impl ScrollBarDir {
+  fn main_of<T: MainAxis>(self, v: &T) -> f32 {
+    match self {
+      ScrollBarDir::Vertical => v.y_part(),
+      ScrollBarDir::Horizontal => v.x_part(),
+    }
+  }
+
+  fn main_of_mut(self, pos: &mut Point) -> &mut f32 {
+    match self {
+      ScrollBarDir::Vertical => &mut pos.y,
+      ScrollBarDir::Horizontal => &mut pos.x,
+    }
+  }
+
+  fn to_size(self, main: f32, cross: f32) -> Size {
+    match self {
+      ScrollBarDir::Vertical => Size::new(cross, main),
+      ScrollBarDir::Horizontal => Size::new(main, cross),
+    }
+  }
+}
+
+trait MainAxis {
+  fn x_part(&self) -> f32;
+  fn y_part(&self) -> f32;
+}
+
+impl MainAxis for Size {
+  fn x_part(&self) -> f32 { self.width }
+  fn y_part(&self) -> f32 { self.height }
+}
+
+impl MainAxis for Point {
+  fn x_part(&self) -> f32 { self.x }
+  fn y_part(&self) -> f32 { self.y }
+}
+
+fn can_scroll(scrolling: &ScrollableWidget, dir: ScrollBarDir) -> bool {
+  dir.main_of(&scrolling.scroll_content_size()) > dir.main_of(&scrolling.scroll_view_size())
+}
+
+/// Return the position and the extent of the thumb in the `track`.
+fn thumb_of(scrolling: &ScrollableWidget, dir: ScrollBarDir, track: f32, min: f32) -> (f32, f32) {
+  let view = dir.main_of(&scrolling.scroll_view_size());
+  let content = dir.main_of(&scrolling.scroll_content_size());
+  let extent = (view * safe_recip(content) * track)
+    .max(min)
+    .min(track);
+  let travel = track - extent;
+  let max = content - view;
+  let pos = if max > 0. && travel > 0. {
+    (-dir.main_of(&scrolling.scroll_pos) / max * travel).clamp(0., travel)
+  } else {
+    0.
+  };
+  (pos, extent)
+}
+
+/// Return the scroll position of the main axis when the thumb is at `thumb`,
+/// the thumb at the start and the end of the track map to exactly zero and the
+/// max offset.
+fn scroll_pos_of(
+  scrolling: &ScrollableWidget, dir: ScrollBarDir, track: f32, min: f32, thumb: f32,
+) -> f32 {
+  let (_, extent) = thumb_of(scrolling, dir, track, min);
+  let travel = track - extent;
+  let max =
+    dir.main_of(&scrolling.scroll_content_size()) - dir.main_of(&scrolling.scroll_view_size());
+  if max <= 0. || travel <= 0. {
+    return 0.;
+  }
+  -(thumb / travel).clamp(0., 1.) * max
+}
+
 fn safe_recip(v: f32) -> f32 {
   let v = v.recip();
   if v.is_infinite() || v.is_nan() { 0. } else { v }
@@ -305,8 +498,9 @@ impl CustomStyle for ScrollBarStyle {
 
 #[cfg(test)]
 mod test {
-  use ribir_core::{reset_test_env, test_helper::*};
+  use ribir_core::{reset_test_env, test_helper::*, timer::Timer};
   use ribir_dev_helper::*;
+  use winit::event::{DeviceId, ElementState, MouseButton, WindowEvent};
 
   use super::*;
   use crate::layout::{Column, ConstrainedBox};
@@ -387,4 +581,151 @@ mod test {
     assert_eq!(*c_v_offset.read(), c_offset.read().y);
     assert_eq!(*c_h_offset.read(), c_offset.read().x);
   }
+
+  fn scrolling_of(scrollable: Scrollable, page: Size, content: Size) -> ScrollableWidget {
+    let mut w = ScrollableWidget::default();
+    w.scrollable = scrollable;
+    w.set_page(page);
+    w.set_content_size(content);
+    w
+  }
+
+  #[test]
+  fn thumb_size_by_visible_fraction() {
+    let page = Size::new(100., 100.);
+    let mut w = scrolling_of(Scrollable::Both, page, Size::new(400., 200.));
+    assert_eq!(thumb_of(&w, ScrollBarDir::Vertical, 100., 0.), (0., 50.));
+    assert_eq!(thumb_of(&w, ScrollBarDir::Horizontal, 100., 0.), (0., 25.));
+
+    w.jump_to(Point::new(-300., -100.));
+    assert_eq!(thumb_of(&w, ScrollBarDir::Vertical, 100., 0.), (50., 50.));
+    assert_eq!(thumb_of(&w, ScrollBarDir::Horizontal, 100., 0.), (75., 25.));
+
+    // The thumb is not smaller than the min size and not larger than the track.
+    assert_eq!(thumb_of(&w, ScrollBarDir::Horizontal, 100., 40.), (60., 40.));
+    let w = scrolling_of(Scrollable::Y, page, Size::new(100., 50.));
+    assert!(!can_scroll(&w, ScrollBarDir::Vertical));
+    assert_eq!(thumb_of(&w, ScrollBarDir::Vertical, 100., 0.), (0., 100.));
+  }
+
+  #[test]
+  fn drag_thumb_to_scroll() {
+    reset_test_env!();
+
+    let scrolling = Stateful::new(ScrollableWidget::default());
+    scrolling.write().scrollable = Scrollable::Y;
+    let c_scrolling = scrolling.clone_writer();
+    let bar_scrolling = scrolling.clone_writer();
+    let w = fn_widget! {
+      @Stack {
+        @ $c_scrolling { @Container { size: Size::new(100., 400.) } }
+        @ScrollBar { scrolling: bar_scrolling, h_align: HAlign::Right }
+      }
+    };
+    let mut wnd = TestWindow::new_with_size(w, Size::new(100., 100.));
+    wnd.draw_frame();
+
+    let device_id = unsafe { DeviceId::dummy() };
+    #[allow(deprecated)]
+    let move_to = |wnd: &TestWindow, x: f32, y: f32| {
+      wnd.processes_native_event(WindowEvent::CursorMoved { device_id, position: (x, y).into() });
+      wnd.run_frame_tasks();
+    };
+    let press = |wnd: &TestWindow, state| {
+      wnd.process_mouse_input(device_id, state, MouseButton::Left);
+      wnd.run_frame_tasks();
+    };
+
+    // The thumb is 25 pixels, so it can travel 75 pixels in the track.
+    move_to(&wnd, 96., 10.);
+    press(&wnd, ElementState::Pressed);
+    move_to(&wnd, 96., 40.);
+    assert_eq!(scrolling.read().scroll_pos, Point::new(0., -120.));
+
+    // Drag beyond the end of the track, pinned to the max offset.
+    move_to(&wnd, 96., 99.);
+    assert_eq!(scrolling.read().scroll_pos, Point::new(0., -300.));
+
+    // Back to the start.
+    move_to(&wnd, 96., 0.);
+    assert_eq!(scrolling.read().scroll_pos, Point::zero());
+    press(&wnd, ElementState::Released);
+
+    // Not dragging after the release.
+    move_to(&wnd, 96., 50.);
+    assert_eq!(scrolling.read().scroll_pos, Point::zero());
+  }
+
+  #[test]
+  fn drag_thumb_off_the_bar() {
+    reset_test_env!();
+
+    let scrolling = Stateful::new(ScrollableWidget::default());
+    scrolling.write().scrollable = Scrollable::Y;
+    let c_scrolling = scrolling.clone_writer();
+    let bar_scrolling = scrolling.clone_writer();
+    let w = fn_widget! {
+      @Stack {
+        @ $c_scrolling { @Container { size: Size::new(100., 400.) } }
+        @ScrollBar { scrolling: bar_scrolling, h_align: HAlign::Right }
+      }
+    };
+    let mut wnd = TestWindow::new_with_size(w, Size::new(100., 100.));
+    wnd.draw_frame();
+
+    let device_id = unsafe { DeviceId::dummy() };
+    #[allow(deprecated)]
+    let move_to = |wnd: &TestWindow, x: f32, y: f32| {
+      wnd.processes_native_event(WindowEvent::CursorMoved { device_id, position: (x, y).into() });
+      wnd.run_frame_tasks();
+    };
+    let press = |wnd: &TestWindow, state| {
+      wnd.process_mouse_input(device_id, state, MouseButton::Left);
+      wnd.run_frame_tasks();
+    };
+
+    move_to(&wnd, 96., 10.);
+    press(&wnd, ElementState::Pressed);
+    // The pointer leaves the bar, but the thumb still follows it.
+    move_to(&wnd, 20., 40.);
+    assert_eq!(scrolling.read().scroll_pos, Point::new(0., -120.));
+
+    // Released off the bar, the drag ends.
+    press(&wnd, ElementState::Released);
+    move_to(&wnd, 96., 60.);
+    assert_eq!(scrolling.read().scroll_pos, Point::new(0., -120.));
+  }
+
+  #[test]
+  fn auto_hide_after_idle() {
+    reset_test_env!();
+
+    let scrolling = Stateful::new(ScrollableWidget::default());
+    scrolling.write().scrollable = Scrollable::Y;
+    let c_scrolling = scrolling.clone_writer();
+    let bar_scrolling = scrolling.clone_writer();
+    let (visible, w_visible) = split_value(true);
+    let w = fn_widget! {
+      let bar = @ScrollBar {
+        scrolling: bar_scrolling,
+        auto_hide: Some(Duration::from_secs(1)),
+      };
+      watch!(!$bar.idle).subscribe(move |v| *$w_visible.write() = v);
+      @Stack {
+        @ $c_scrolling { @Container { size: Size::new(100., 400.) } }
+        @ { bar }
+      }
+    };
+    let mut wnd = TestWindow::new_with_size(w, Size::new(100., 100.));
+    wnd.draw_frame();
+    assert!(*visible.read());
+
+    Timer::advance(Duration::from_secs(1));
+    AppCtx::run_until_stalled();
+    assert!(!*visible.read());
+
+    scrolling.write().scroll_by(Vector::new(0., -10.));
+    wnd.draw_frame();
+    assert!(*visible.read());
+  }
 }
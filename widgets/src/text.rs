@@ -1,5 +1,8 @@
 use ribir_core::prelude::*;
 
+/// The width to fade out the end of a text, relative to its font size.
+const FADE_EM: f32 = 2.;
+
 /// The text widget display text with a single style.
 #[derive(Debug, Declare, Clone, PartialEq)]
 pub struct Text {
@@ -14,6 +17,13 @@ pub struct Text {
   pub overflow: Overflow,
   #[declare(default = TextAlign::Start)]
   pub text_align: TextAlign,
+  /// The max count of the lines to show, the following lines are dropped.
+  #[declare(default)]
+  pub max_lines: Option<usize>,
+  /// How to show the end of the text if it's truncated by the `max_lines` or
+  /// overflows.
+  #[declare(default)]
+  pub text_overflow: TextOverflow,
 }
 
 impl VisualText for Text {
@@ -21,6 +31,8 @@ impl VisualText for Text {
   fn text_style(&self) -> &TextStyle { &self.text_style }
   fn text_align(&self) -> TextAlign { self.text_align }
   fn overflow(&self) -> Overflow { self.overflow }
+  fn max_lines(&self) -> Option<usize> { self.max_lines }
+  fn text_overflow(&self) -> TextOverflow { self.text_overflow }
 }
 
 impl Render for Text {
@@ -50,15 +62,36 @@ impl Render for Text {
     let visual_glyphs = self.text_layout(AppCtx::typography_store(), bounds);
     let font_db = AppCtx::font_db().clone();
     let font_size = self.text_style.font_size.into_pixel().value();
-    draw_glyphs_in_rect(
-      ctx.painter(),
-      visual_glyphs,
-      box_rect,
-      self.foreground.clone(),
-      font_size,
-      &self.path_style,
-      font_db,
-    );
+    if self.text_overflow == TextOverflow::Fade {
+      draw_faded_glyphs_in_rect(
+        ctx.painter(),
+        visual_glyphs,
+        box_rect,
+        self.foreground.clone(),
+        font_size,
+        &self.path_style,
+        font_db,
+        font_size * FADE_EM,
+      );
+    } else {
+      draw_glyphs_in_rect(
+        ctx.painter(),
+        visual_glyphs,
+        box_rect,
+        self.foreground.clone(),
+        font_size,
+        &self.path_style,
+        font_db,
+      );
+    }
+  }
+
+  fn accessibility(&self) -> Option<Accessibility> {
+    Some(Accessibility {
+      a11y_role: Some(AccessRole::StaticText),
+      a11y_label: Some(self.text.clone()),
+      ..Default::default()
+    })
   }
 }
 
@@ -98,6 +131,7 @@ define_text_with_theme_style!(H6, title_small);
 #[cfg(test)]
 mod tests {
   use ribir_core::test_helper::*;
+  use ribir_dev_helper::*;
 
   use super::*;
   use crate::layout::SizedBox;
@@ -117,4 +151,20 @@ mod tests {
     let wnd = TestWindow::new_with_size(w, Size::new(120., 80.));
     wnd.layout();
   }
+
+  fn text_fade_overflow() -> impl WidgetBuilder {
+    fn_widget! {
+      @SizedBox {
+        size: Size::new(120., 40.),
+        @Text {
+          text: "hello world, nice to meet you, have a nice day.",
+          foreground: Color::RED,
+          overflow: Overflow::AutoWrap,
+          max_lines: Some(2),
+          text_overflow: TextOverflow::Fade,
+        }
+      }
+    }
+  }
+  widget_image_test!(text_fade_overflow, wnd_size = Size::new(120., 40.));
 }
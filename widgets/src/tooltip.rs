@@ -0,0 +1,228 @@
+use std::{cell::RefCell, rc::Rc};
+
+use ribir_core::{
+  overlay::{ClosePolicy, OverlayStyle},
+  prelude::*,
+};
+
+use crate::prelude::*;
+
+/// The style of the message of the [`Tooltip`].
+#[derive(Clone)]
+pub struct TooltipStyle {
+  pub text_style: CowArc<TextStyle>,
+  pub foreground: Brush,
+  pub background: Brush,
+  pub padding: EdgeInsets,
+  pub radius: f32,
+  /// The gap between the tooltip and its host.
+  pub gap: f32,
+}
+
+impl CustomStyle for TooltipStyle {
+  fn default_style(ctx: &BuildCtx) -> Self {
+    let palette = Palette::of(ctx);
+    Self {
+      text_style: TypographyTheme::of(ctx).body_small.text.clone(),
+      foreground: palette.inverse_on_surface().into(),
+      background: palette.inverse_surface().into(),
+      padding: EdgeInsets::new(4., 8., 4., 8.),
+      radius: 4.,
+      gap: 4.,
+    }
+  }
+}
+
+/// A short message shown above its child when the pointer hovers the child for
+/// the `show_delay`, it's placed below the child if there is not enough space
+/// above it.
+///
+/// The message is hidden after the pointer leaves the child for the
+/// `hide_delay`, or the child is disposed. The delay restarts every time the
+/// pointer enters, so moving quickly across the tooltipped widgets shows no
+/// message until the pointer rests on one of them.
+///
+/// # Example
+///
+/// ```no_run
+/// use ribir_core::prelude::*;
+/// use ribir_widgets::prelude::*;
+///
+/// let _w = fn_widget! {
+///   @Tooltip {
+///     message: "Delete the file",
+///     @Icon { @ { svgs::DELETE } }
+///   }
+/// };
+/// ```
+#[derive(Declare)]
+pub struct Tooltip {
+  pub message: CowArc<str>,
+  #[declare(default = Duration::from_millis(500))]
+  pub show_delay: Duration,
+  #[declare(default)]
+  pub hide_delay: Duration,
+}
+
+impl ComposeChild for Tooltip {
+  type Child = Widget;
+  fn compose_child(this: impl StateWriter<Value = Self>, child: Self::Child) -> impl WidgetBuilder {
+    fn_widget! {
+      let style = TooltipStyle::of(ctx!());
+      let gap = style.gap;
+      let handle = ctx!().handle();
+      let overlay = Overlay::new_with_handle(move |_| {
+        let message = $this.message.clone();
+        // Read the style when shown, so it follows the switch of the palette.
+        let TooltipStyle { text_style, foreground, background, padding, radius, .. } = handle
+          .with_ctx(TooltipStyle::of)
+          .unwrap_or_else(|| style.clone());
+        fn_widget! {
+          @Text {
+            text: message.clone(),
+            text_style: text_style.clone(),
+            foreground: foreground.clone(),
+            background: background.clone(),
+            padding,
+            border_radius: Radius::all(radius),
+          }
+        }
+      });
+      let ctrl = Rc::new(TooltipCtrl { overlay, pending: RefCell::new(None) });
+      let (c_enter, c_leave) = (ctrl.clone(), ctrl.clone());
+      @ $child {
+        on_pointer_enter: move |e| {
+          let delay = $this.show_delay;
+          c_enter.show_after(e.current_target(), delay, gap, &e.window());
+        },
+        on_pointer_leave: move |e| c_leave.hide_after($this.hide_delay, &e.window()),
+        on_disposed: move |_| {
+          ctrl.cancel_pending();
+          ctrl.overlay.close();
+        },
+      }
+    }
+  }
+}
+
+/// The overlay of a `Tooltip` with the task waiting to show or hide it, a new
+/// task always cancels the pending one.
+struct TooltipCtrl {
+  overlay: Overlay,
+  pending: RefCell<Option<DelayedTask>>,
+}
+
+impl TooltipCtrl {
+  fn show_after(&self, anchor: WidgetId, delay: Duration, gap: f32, wnd: &Window) {
+    self.cancel_pending();
+    if self.overlay.is_show() {
+      return;
+    }
+    let overlay = self.overlay.clone();
+    let task = wnd.run_after(delay, move |wnd| {
+      // The taps on the host pass through the mask of the overlay.
+      overlay.with_style(OverlayStyle {
+        close_policy: ClosePolicy::ESC | ClosePolicy::TAP_OUTSIDE,
+        backdrop: None,
+        exclude_regions: vec![anchor],
+      });
+      let wnd = AppCtx::get_window_assert(wnd.id());
+      overlay.show_anchored(anchor, Placement::TopCenter, Vector::new(0., gap), wnd);
+    });
+    *self.pending.borrow_mut() = Some(task);
+  }
+
+  fn hide_after(&self, delay: Duration, wnd: &Window) {
+    self.cancel_pending();
+    if delay.is_zero() {
+      self.overlay.close();
+    } else {
+      let overlay = self.overlay.clone();
+      let task = wnd.run_after(delay, move |_| overlay.close());
+      *self.pending.borrow_mut() = Some(task);
+    }
+  }
+
+  fn cancel_pending(&self) {
+    if let Some(task) = self.pending.borrow_mut().take() {
+      task.cancel();
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use ribir_core::{reset_test_env, test_helper::*, timer::Timer};
+  use winit::event::{DeviceId, WindowEvent};
+
+  use super::*;
+  use crate::layout::SizedBox;
+
+  #[allow(deprecated)]
+  fn move_to(wnd: &mut TestWindow, x: f32, y: f32) {
+    let device_id = unsafe { DeviceId::dummy() };
+    wnd.processes_native_event(WindowEvent::CursorMoved { device_id, position: (x, y).into() });
+    wnd.draw_frame();
+  }
+
+  fn wait(wnd: &mut TestWindow, dur: Duration) {
+    Timer::advance(dur);
+    AppCtx::run_until_stalled();
+    wnd.draw_frame();
+  }
+
+  fn tooltip_host(message: &'static str) -> impl WidgetBuilder {
+    fn_widget! {
+      @Tooltip {
+        message,
+        @SizedBox { size: Size::new(50., 50.) }
+      }
+    }
+  }
+
+  #[test]
+  fn show_after_delay() {
+    reset_test_env!();
+
+    let w = fn_widget! {
+      @Row { @ { tooltip_host("a") } }
+    };
+    let mut wnd = TestWindow::new_with_size(w, Size::new(200., 200.));
+    wnd.draw_frame();
+
+    move_to(&mut wnd, 10., 10.);
+    wait(&mut wnd, Duration::from_millis(400));
+    assert_eq!(wnd.root_children_count(), 1);
+
+    wait(&mut wnd, Duration::from_millis(100));
+    assert_eq!(wnd.root_children_count(), 2);
+
+    move_to(&mut wnd, 150., 150.);
+    wait(&mut wnd, Duration::ZERO);
+    assert_eq!(wnd.root_children_count(), 1);
+  }
+
+  #[test]
+  fn delay_restarts_across_hosts() {
+    reset_test_env!();
+
+    let w = fn_widget! {
+      @Row {
+        @ { tooltip_host("a") }
+        @ { tooltip_host("b") }
+      }
+    };
+    let mut wnd = TestWindow::new_with_size(w, Size::new(200., 200.));
+    wnd.draw_frame();
+
+    move_to(&mut wnd, 10., 10.);
+    wait(&mut wnd, Duration::from_millis(300));
+    move_to(&mut wnd, 60., 10.);
+    wait(&mut wnd, Duration::from_millis(300));
+    assert_eq!(wnd.root_children_count(), 1);
+
+    wait(&mut wnd, Duration::from_millis(200));
+    // Only the tooltip of the second host is shown.
+    assert_eq!(wnd.root_children_count(), 2);
+  }
+}